@@ -0,0 +1,17 @@
+//! Runs the standard workloads from `dfdx::bench` on the cpu, and on cuda when it is enabled.
+//!
+//! `cargo bench --features bench`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dfdx::tensor::Cpu;
+
+fn benches(c: &mut Criterion) {
+    #[cfg(not(feature = "cuda"))]
+    dfdx::bench::bench_device(c, "cpu", &Cpu::default());
+
+    #[cfg(feature = "cuda")]
+    dfdx::bench::bench_against_cpu(c, "cuda", &dfdx::tensor::Cuda::default());
+}
+
+criterion_group!(ops, benches);
+criterion_main!(ops);
@@ -13,6 +13,12 @@ fn main() {
 
     #[cfg(feature = "intel-mkl")]
     intel_mkl::link().unwrap();
+
+    #[cfg(feature = "openblas")]
+    println!("cargo:rustc-link-lib=openblas");
+
+    #[cfg(feature = "accelerate")]
+    println!("cargo:rustc-link-lib=framework=Accelerate");
 }
 
 #[cfg(feature = "cuda")]
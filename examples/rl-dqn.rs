@@ -4,6 +4,7 @@ use dfdx::{
     losses::mse_loss,
     optim::{Momentum, Sgd, SgdConfig},
     prelude::*,
+    rl::polyak_update,
 };
 use std::time::Instant;
 
@@ -33,7 +34,7 @@ fn main() {
 
     // initiliaze model
     let mut q_net: QNetwork = dev.build_module();
-    let target_q_net: QNetwork = q_net.clone();
+    let mut target_q_net: QNetwork = q_net.clone();
 
     let mut sgd = Sgd::new(SgdConfig {
         lr: 1e-1,
@@ -66,6 +67,9 @@ fn main() {
         // update weights with optimizer
         sgd.update(&mut q_net, gradients).expect("Unused params");
 
+        // slowly move the target network towards the q network
+        polyak_update(&mut target_q_net, &q_net, 0.1);
+
         println!("q loss={:#.3} in {:?}", loss_v, start.elapsed());
     }
 }
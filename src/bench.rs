@@ -0,0 +1,173 @@
+//! Standardized [criterion](https://docs.rs/criterion) workloads for comparing devices and
+//! kernel changes, enabled by the "bench" feature.
+//!
+//! Each workload is registered in a criterion group named after the workload, with one
+//! benchmark per pass (`forward`/`backward`) and device. Benchmarking a device next to
+//! the [Cpu] puts both in the same group, so the cpu kernels act as the baseline:
+//!
+//! ```ignore
+//! // benches/my_device.rs, with `harness = false`
+//! use criterion::{criterion_group, criterion_main, Criterion};
+//!
+//! fn benches(c: &mut Criterion) {
+//!     let dev: MyDevice = Default::default();
+//!     dfdx::bench::bench_against_cpu(c, "my_device", &dev);
+//! }
+//!
+//! criterion_group!(my_device, benches);
+//! criterion_main!(my_device);
+//! ```
+//!
+//! The workloads are:
+//! - [bench_matmul()] for square matrices of size 64, 256 and 512
+//! - [bench_conv2d()] for a 3x3 [Conv2D] over a batch of 32x32 images
+//! - [bench_transformer_block()] for a [TransformerEncoderBlock] over a batch of sequences
+//!
+//! The conv and transformer workloads need the "nightly" or "stable-fallback" feature.
+//! The outputs of every iteration are copied to the host, so devices that run kernels
+//! asynchronously are measured until the work actually finishes. Run the suite for the
+//! cpu with `cargo bench --features bench`, and use criterion's `--save-baseline` and
+//! `--baseline` flags to compare kernel changes.
+
+use crate::{
+    shapes::*,
+    tensor::{AsVec, Cpu, Tensor},
+    tensor_ops::*,
+};
+use criterion::{black_box, BenchmarkId, Criterion, Throughput};
+use std::format;
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+use crate::{
+    gradients::OwnedTape,
+    nn::{Conv2D, Module, ModuleBuilder, TransformerEncoderBlock},
+};
+
+/// A device that can run every workload: the [Cpu], and `Cuda` with the "cuda" feature.
+pub trait BenchDevice: Device<f32> + private::ConvWorkload {}
+impl BenchDevice for Cpu {}
+#[cfg(feature = "cuda")]
+impl BenchDevice for crate::tensor::Cuda {}
+
+mod private {
+    use super::*;
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    use crate::tensor::SampleTensor;
+
+    /// Conv kernels aren't part of [Device], so the conv workload is implemented per device.
+    pub trait ConvWorkload {
+        #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+        fn bench_conv2d(c: &mut Criterion, device_name: &str, dev: &Self);
+    }
+
+    macro_rules! impl_conv_workload {
+        ($Dev:ty) => {
+            impl ConvWorkload for $Dev {
+                #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+                fn bench_conv2d(c: &mut Criterion, device_name: &str, dev: &Self) {
+                    let model: Conv2D<3, 16, 3, 1, 1, $Dev> = dev.build_module();
+                    let x: Tensor<Rank4<8, 3, 32, 32>, f32, $Dev> = dev.sample_normal();
+
+                    let mut group = c.benchmark_group("conv2d_3x16_k3");
+                    group.bench_function(BenchmarkId::new("forward", device_name), |bench| {
+                        bench.iter(|| black_box(model.forward(x.clone()).as_vec()))
+                    });
+                    group.bench_function(BenchmarkId::new("backward", device_name), |bench| {
+                        bench.iter(|| {
+                            let y = model.forward(x.trace());
+                            let grads = y.mean::<Rank0, _>().backward();
+                            black_box(grads.get(&model.weight).as_vec())
+                        })
+                    });
+                    group.finish();
+                }
+            }
+        };
+    }
+
+    impl_conv_workload!(Cpu);
+    #[cfg(feature = "cuda")]
+    impl_conv_workload!(crate::tensor::Cuda);
+}
+
+/// Runs every workload on `dev`, naming the benchmarks after `device_name`.
+pub fn bench_device<D: BenchDevice>(c: &mut Criterion, device_name: &str, dev: &D) {
+    bench_matmul::<64, D>(c, device_name, dev);
+    bench_matmul::<256, D>(c, device_name, dev);
+    bench_matmul::<512, D>(c, device_name, dev);
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    {
+        bench_conv2d(c, device_name, dev);
+        bench_transformer_block(c, device_name, dev);
+    }
+}
+
+/// Runs every workload on the [Cpu] and then on `dev`, so the cpu results show up as the
+/// baseline in each group.
+pub fn bench_against_cpu<D: BenchDevice>(c: &mut Criterion, device_name: &str, dev: &D) {
+    bench_device(c, "cpu", &Cpu::default());
+    bench_device(c, device_name, dev);
+}
+
+/// Multiplies two `N`x`N` matrices, with throughput measured in floating point operations.
+pub fn bench_matmul<const N: usize, D: Device<f32>>(c: &mut Criterion, device_name: &str, dev: &D) {
+    let a: Tensor<Rank2<N, N>, f32, D> = dev.sample_normal();
+    let b: Tensor<Rank2<N, N>, f32, D> = dev.sample_normal();
+
+    let mut group = c.benchmark_group(format!("matmul_{N}x{N}"));
+    group.throughput(Throughput::Elements((2 * N * N * N) as u64));
+    group.bench_function(BenchmarkId::new("forward", device_name), |bench| {
+        bench.iter(|| black_box(a.clone().matmul(b.clone()).as_vec()))
+    });
+    group.bench_function(BenchmarkId::new("backward", device_name), |bench| {
+        bench.iter(|| {
+            let grads = a.trace().matmul(b.clone()).sum::<Rank0, _>().backward();
+            black_box(grads.get(&a).as_vec())
+        })
+    });
+    group.finish();
+}
+
+/// A `Conv2D<3, 16, 3, 1, 1>` over a batch of 8 `3x32x32` images.
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+pub fn bench_conv2d<D: BenchDevice>(c: &mut Criterion, device_name: &str, dev: &D) {
+    D::bench_conv2d(c, device_name, dev)
+}
+
+/// A `TransformerEncoderBlock<64, 4, 128>` over a batch of 4 sequences of length 32.
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+pub fn bench_transformer_block<D: Device<f32>>(c: &mut Criterion, device_name: &str, dev: &D) {
+    let model: TransformerEncoderBlock<64, 4, 128, D> = dev.build_module();
+    let x: Tensor<Rank3<4, 32, 64>, f32, D> = dev.sample_normal();
+
+    let mut group = c.benchmark_group("transformer_encoder_block_64");
+    group.bench_function(BenchmarkId::new("forward", device_name), |bench| {
+        bench.iter(|| black_box(model.forward(x.clone()).as_vec()))
+    });
+    group.bench_function(BenchmarkId::new("backward", device_name), |bench| {
+        bench.iter(|| {
+            let y: Tensor<_, _, _, OwnedTape<D>> = model.forward(x.trace());
+            let grads = y.mean::<Rank0, _>().backward();
+            black_box(grads.get(&model.self_attn.w_q.weight).as_vec())
+        })
+    });
+    group.finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestDevice;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bench_matmul() {
+        let mut c = Criterion::default()
+            .sample_size(10)
+            .warm_up_time(Duration::from_millis(1))
+            .measurement_time(Duration::from_millis(10))
+            .without_plots();
+        let dev: TestDevice = Default::default();
+        bench_matmul::<4, _>(&mut c, "test", &dev);
+    }
+}
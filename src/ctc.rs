@@ -0,0 +1,267 @@
+//! Decoders for models trained with connectionist temporal classification (CTC), which turn
+//! per-frame class scores into label sequences: [greedy_decode()] and [beam_search_decode()].
+//!
+//! Both take a `(batch, time, classes)` tensor of log probabilities (e.g. the output of
+//! [crate::tensor_ops::log_softmax()]) and the index of the blank class. A label sequence
+//! is read from a path of per-frame classes by merging repeated classes and then removing
+//! blanks, so `[a, a, blank, a, b, b]` becomes `[a, a, b]`.
+//!
+//! Decoding happens on the host, so the log probabilities are copied off the device.
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use std::{collections::BTreeMap, vec::Vec};
+
+use crate::{
+    shapes::{Dim, HasShape},
+    tensor::{AsVec, DeviceStorage, Tensor},
+};
+
+/// `ln(exp(a) + exp(b))`, where either can be `-inf`.
+fn log_add(a: f32, b: f32) -> f32 {
+    let max = a.max(b);
+    if max == f32::NEG_INFINITY {
+        max
+    } else {
+        max + ((a - max).exp() + (b - max).exp()).ln()
+    }
+}
+
+/// The label sequence of the most likely class of every frame, for each item of the batch.
+///
+/// This is fast, but it ignores that many paths map to the same label sequence; see
+/// [beam_search_decode()] for a more accurate decoder.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, ctc::greedy_decode};
+/// # let dev: Cpu = Default::default();
+/// // 5 frames over the classes [blank, a, b]
+/// let log_probs: Tensor<Rank3<1, 5, 3>> = dev
+///     .tensor([[
+///         [0.1, 0.8, 0.1],
+///         [0.1, 0.8, 0.1],
+///         [0.8, 0.1, 0.1],
+///         [0.1, 0.8, 0.1],
+///         [0.1, 0.1, 0.8],
+///     ]])
+///     .ln();
+/// assert_eq!(greedy_decode(&log_probs, 0), [[1, 1, 2]]);
+/// ```
+pub fn greedy_decode<B: Dim, T: Dim, C: Dim, D: DeviceStorage>(
+    log_probs: &Tensor<(B, T, C), f32, D>,
+    blank: usize,
+) -> Vec<Vec<usize>>
+where
+    D::Storage<(B, T, C), f32>: AsVec<Unit = f32>,
+{
+    let (batch, time, classes) = *log_probs.shape();
+    let data = log_probs.as_vec();
+    let seq_len = time.size() * classes.size();
+    (0..batch.size())
+        .map(|b| {
+            let mut labels = Vec::new();
+            let mut prev = blank;
+            for frame in data[b * seq_len..(b + 1) * seq_len].chunks(classes.size()) {
+                let mut best = 0;
+                for (i, v) in frame.iter().enumerate() {
+                    if *v > frame[best] {
+                        best = i;
+                    }
+                }
+                if best != blank && best != prev {
+                    labels.push(best);
+                }
+                prev = best;
+            }
+            labels
+        })
+        .collect()
+}
+
+/// A label sequence found by [beam_search_decode()].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hypothesis {
+    pub labels: Vec<usize>,
+
+    /// The log probability of the label sequence, summed over all of the paths in the beam
+    /// that produce it.
+    pub log_prob: f32,
+}
+
+/// Prefix beam search: keeps the `beam_width` most likely label sequences (prefixes) after
+/// every frame, where the probability of a prefix sums over all the paths that produce it.
+///
+/// Returns the final beam for each item of the batch, most likely first.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, ctc::beam_search_decode};
+/// # let dev: Cpu = Default::default();
+/// // classes [blank, a]. the most likely path is [blank, blank] with probability 0.36,
+/// // but the paths for [a] add up to 0.64
+/// let log_probs: Tensor<Rank3<1, 2, 2>> = dev.tensor([[[0.6, 0.4], [0.6, 0.4]]]).ln();
+/// let beams = beam_search_decode(&log_probs, 0, 4);
+/// assert_eq!(beams[0][0].labels, [1]);
+/// assert!((beams[0][0].log_prob.exp() - 0.64).abs() < 1e-6);
+/// assert_eq!(beams[0][1].labels, Vec::<usize>::new());
+/// ```
+pub fn beam_search_decode<B: Dim, T: Dim, C: Dim, D: DeviceStorage>(
+    log_probs: &Tensor<(B, T, C), f32, D>,
+    blank: usize,
+    beam_width: usize,
+) -> Vec<Vec<Hypothesis>>
+where
+    D::Storage<(B, T, C), f32>: AsVec<Unit = f32>,
+{
+    assert!(beam_width > 0);
+    let (batch, time, classes) = *log_probs.shape();
+    let data = log_probs.as_vec();
+    let seq_len = time.size() * classes.size();
+    (0..batch.size())
+        .map(|b| {
+            let seq = &data[b * seq_len..(b + 1) * seq_len];
+            beam_search(seq, classes.size(), blank, beam_width)
+        })
+        .collect()
+}
+
+/// Prefix beam search over a `(time, classes)` matrix. Each prefix keeps the log
+/// probability of the paths ending in a blank and of the paths ending in its last label,
+/// since a repeated label only extends the prefix after a blank.
+fn beam_search(
+    log_probs: &[f32],
+    num_classes: usize,
+    blank: usize,
+    beam_width: usize,
+) -> Vec<Hypothesis> {
+    let mut beam: Vec<(Vec<usize>, f32, f32)> = alloc::vec![(Vec::new(), 0.0, f32::NEG_INFINITY)];
+    for frame in log_probs.chunks(num_classes) {
+        let mut next: BTreeMap<Vec<usize>, (f32, f32)> = BTreeMap::new();
+        for (prefix, p_blank, p_label) in beam.iter() {
+            let total = log_add(*p_blank, *p_label);
+            for (c, &p) in frame.iter().enumerate() {
+                if c == blank {
+                    let e = next
+                        .entry(prefix.clone())
+                        .or_insert((f32::NEG_INFINITY, f32::NEG_INFINITY));
+                    e.0 = log_add(e.0, total + p);
+                    continue;
+                }
+                let mut extended = prefix.clone();
+                extended.push(c);
+                let e = next
+                    .entry(extended)
+                    .or_insert((f32::NEG_INFINITY, f32::NEG_INFINITY));
+                if prefix.last() == Some(&c) {
+                    // repeats merge unless separated by a blank
+                    e.1 = log_add(e.1, p_blank + p);
+                    let e = next
+                        .entry(prefix.clone())
+                        .or_insert((f32::NEG_INFINITY, f32::NEG_INFINITY));
+                    e.1 = log_add(e.1, p_label + p);
+                } else {
+                    e.1 = log_add(e.1, total + p);
+                }
+            }
+        }
+        beam = next.into_iter().map(|(k, (pb, pl))| (k, pb, pl)).collect();
+        beam.sort_by(|a, b| {
+            let a = log_add(a.1, a.2);
+            let b = log_add(b.1, b.2);
+            b.total_cmp(&a)
+        });
+        beam.truncate(beam_width);
+    }
+    beam.into_iter()
+        .map(|(labels, pb, pl)| Hypothesis {
+            labels,
+            log_prob: log_add(pb, pl),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+
+    #[test]
+    fn test_greedy_decode() {
+        let dev: TestDevice = Default::default();
+        // the argmax paths are [2, 2, 0, 2, 1] and [0, 0, 1, 1, 0]
+        let log_probs: Tensor<Rank3<2, 5, 3>, f32, _> = dev.tensor([
+            [
+                [0.0, 1.0, 2.0],
+                [0.0, 1.0, 2.0],
+                [2.0, 1.0, 0.0],
+                [0.0, 1.0, 2.0],
+                [0.0, 2.0, 1.0],
+            ],
+            [
+                [2.0, 1.0, 0.0],
+                [2.0, 1.0, 0.0],
+                [0.0, 2.0, 1.0],
+                [0.0, 2.0, 1.0],
+                [2.0, 1.0, 0.0],
+            ],
+        ]);
+        assert_eq!(
+            greedy_decode(&log_probs, 0),
+            [alloc::vec![2, 2, 1], alloc::vec![1]]
+        );
+        // with class 2 as the blank
+        assert_eq!(
+            greedy_decode(&log_probs, 2),
+            [alloc::vec![0, 1], alloc::vec![0, 1, 0]]
+        );
+    }
+
+    #[test]
+    fn test_beam_search_sums_paths() {
+        let dev: TestDevice = Default::default();
+        // classes [blank, a, b]
+        let probs: Tensor<Rank3<1, 3, 3>, f32, _> =
+            dev.tensor([[[0.5, 0.4, 0.1], [0.5, 0.4, 0.1], [0.5, 0.4, 0.1]]]);
+        let beams = beam_search_decode(&probs.clone().ln(), 0, 100);
+        assert_eq!(beams.len(), 1);
+
+        // the probabilities of all label sequences add up to 1
+        let total: f32 = beams[0].iter().map(|h| h.log_prob.exp()).sum();
+        assert!((total - 1.0).abs() < 1e-5, "{total}");
+
+        // [a] comes from the paths with at least one a and no b, where the a's are
+        // contiguous: 3 paths with one a, 2 with two, and 1 with three
+        let p_a = 3.0 * 0.4 * 0.25 + 2.0 * 0.16 * 0.5 + 0.064;
+        assert_eq!(beams[0][0].labels, [1]);
+        assert!((beams[0][0].log_prob.exp() - p_a).abs() < 1e-6);
+        assert_eq!(beams[0][1].labels, Vec::<usize>::new());
+        assert!((beams[0][1].log_prob.exp() - 0.125).abs() < 1e-6);
+
+        // greedy decoding picks the blank path
+        assert_eq!(
+            greedy_decode(&probs.ln(), 0),
+            alloc::vec![Vec::<usize>::new()]
+        );
+    }
+
+    #[test]
+    fn test_beam_search_width() {
+        let dev: TestDevice = Default::default();
+        let log_probs: Tensor<Rank3<2, 6, 4>, f32, _> =
+            dev.sample_normal().log_softmax::<Axis<2>>();
+        let beams = beam_search_decode(&log_probs, 3, 5);
+        for b in beams.iter() {
+            assert_eq!(b.len(), 5);
+            for w in b.windows(2) {
+                assert!(w[0].log_prob >= w[1].log_prob);
+            }
+            assert!(b.iter().all(|h| !h.labels.contains(&3)));
+        }
+
+        // a beam of 1 only matches greedy decoding when the best path is the best prefix,
+        // so just check that it returns a single hypothesis
+        let beams = beam_search_decode(&log_probs, 3, 1);
+        assert!(beams.iter().all(|b| b.len() == 1));
+    }
+}
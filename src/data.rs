@@ -1,11 +1,12 @@
-//! A collection of data utility classes such as [Arange], [OneHotEncode], and [SubsetIterator].
+//! A collection of data utility classes such as [Arange], [OneHotEncode], [SubsetIterator],
+//! and [BpeTokenizer].
 
 use rand::prelude::SliceRandom;
-use std::vec::Vec;
+use std::{collections::BTreeMap, string::String, vec::Vec};
 
 use crate::{
     shapes::{Const, Rank1},
-    tensor::{CopySlice, DeviceStorage, Tensor, ZerosTensor},
+    tensor::{CopySlice, DeviceStorage, Tensor, TensorFromVec, ZerosTensor},
 };
 
 /// Generates a tensor with ordered data from 0 to `N`.
@@ -33,6 +34,8 @@ impl<D: DeviceStorage + ZerosTensor<f32> + CopySlice<f32>> Arange for D {}
 /// One hot encodes an array of class labels into a 2d tensor of probability
 /// vectors. This can be used in tandem with [crate::losses::cross_entropy_with_logits_loss()].
 ///
+/// To encode labels that are already on the device, see [Tensor::one_hot()].
+///
 /// Const Generic Arguments:
 /// - `N` - the number of classes
 ///
@@ -130,9 +133,239 @@ impl<const B: usize> Iterator for SubsetIterator<B> {
     }
 }
 
+/// Turns text into token ids and back.
+///
+/// [BpeTokenizer] is a small built-in implementation. To use another tokenizer (like the
+/// ones of the `tokenizers` crate), implement this trait for a wrapper around it, and
+/// [PadSequences::tokenize_batch()] will work with it.
+pub trait Tokenizer {
+    fn encode(&self, text: &str) -> Vec<usize>;
+    fn decode(&self, ids: &[usize]) -> String;
+}
+
+/// A byte level [byte pair encoding](https://en.wikipedia.org/wiki/Byte_pair_encoding)
+/// tokenizer, like the one of GPT-2.
+///
+/// Ids `0..256` are the bytes of the text, and every merge adds a token that stands for
+/// a pair of existing tokens. Text is split after each run of whitespace before merging,
+/// so tokens never span multiple words. Since every byte has a token, any text can be
+/// encoded, and decoding always gives back the original text. Ids that aren't in the
+/// vocabulary (like a padding id) are skipped when decoding.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::data::*;
+/// let tok = BpeTokenizer::train("low lower lowest", 259);
+/// assert_eq!(tok.vocab_size(), 259);
+/// let ids = tok.encode("lowest");
+/// // the merges are "l" + "o", "lo" + "w", and "low" + "e"
+/// assert_eq!(ids, [258, 115, 116]);
+/// assert_eq!(tok.decode(&ids), "lowest");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BpeTokenizer {
+    merges: Vec<(usize, usize)>,
+    ranks: BTreeMap<(usize, usize), usize>,
+    vocab: Vec<Vec<u8>>,
+}
+
+impl Default for BpeTokenizer {
+    fn default() -> Self {
+        Self::from_merges(Vec::new())
+    }
+}
+
+impl BpeTokenizer {
+    /// Creates a tokenizer from a list of merges, where merge `i` makes token `256 + i`.
+    ///
+    /// **Panics** if a merge uses a token that doesn't exist yet, or if the same pair is
+    /// merged twice.
+    pub fn from_merges(merges: Vec<(usize, usize)>) -> Self {
+        let mut vocab: Vec<Vec<u8>> = (0..=255u8).map(|b| alloc::vec![b]).collect();
+        let mut ranks = BTreeMap::new();
+        for (i, &(a, b)) in merges.iter().enumerate() {
+            assert!(
+                a < vocab.len() && b < vocab.len(),
+                "Merge {i} uses a token that doesn't exist yet"
+            );
+            let mut bytes = vocab[a].clone();
+            bytes.extend_from_slice(&vocab[b]);
+            vocab.push(bytes);
+            if let Some(j) = ranks.insert((a, b), i) {
+                panic!("Merge {i} merges the same pair as merge {j}");
+            }
+        }
+        Self {
+            merges,
+            ranks,
+            vocab,
+        }
+    }
+
+    /// Learns merges from `text` until there are `vocab_size` tokens, by repeatedly merging
+    /// the most frequent pair of adjacent tokens. Stops early if no pair appears twice.
+    pub fn train(text: &str, vocab_size: usize) -> Self {
+        let mut words: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+        for word in text.split_inclusive(char::is_whitespace) {
+            let ids = word.bytes().map(|b| b as usize).collect();
+            *words.entry(ids).or_insert(0) += 1;
+        }
+
+        let mut merges = Vec::new();
+        while 256 + merges.len() < vocab_size {
+            let mut counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+            for (ids, &n) in words.iter() {
+                for pair in ids.windows(2) {
+                    *counts.entry((pair[0], pair[1])).or_insert(0) += n;
+                }
+            }
+            // the first of the most frequent pairs, so training is deterministic
+            let best = counts.into_iter().fold(
+                None,
+                |best: Option<((usize, usize), usize)>, (pair, n)| match best {
+                    Some((_, m)) if m >= n => best,
+                    _ => Some((pair, n)),
+                },
+            );
+            let pair = match best {
+                Some((pair, n)) if n >= 2 => pair,
+                _ => break,
+            };
+            let new_id = 256 + merges.len();
+            merges.push(pair);
+            words = words
+                .into_iter()
+                .map(|(ids, n)| (merge_pair(&ids, pair, new_id), n))
+                .collect();
+        }
+        Self::from_merges(merges)
+    }
+
+    /// The number of tokens, which is 256 plus the number of merges.
+    pub fn vocab_size(&self) -> usize {
+        self.vocab.len()
+    }
+
+    /// The merges, in the order they are applied.
+    pub fn merges(&self) -> &[(usize, usize)] {
+        &self.merges
+    }
+}
+
+/// Replaces every occurrence of `pair` in `ids` with `new_id`.
+fn merge_pair(ids: &[usize], pair: (usize, usize), new_id: usize) -> Vec<usize> {
+    let mut merged = Vec::with_capacity(ids.len());
+    let mut i = 0;
+    while i < ids.len() {
+        if i + 1 < ids.len() && (ids[i], ids[i + 1]) == pair {
+            merged.push(new_id);
+            i += 2;
+        } else {
+            merged.push(ids[i]);
+            i += 1;
+        }
+    }
+    merged
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn encode(&self, text: &str) -> Vec<usize> {
+        let mut encoded = Vec::with_capacity(text.len());
+        for word in text.split_inclusive(char::is_whitespace) {
+            let mut ids: Vec<usize> = word.bytes().map(|b| b as usize).collect();
+            // apply the earliest learned merge that is present, like during training
+            while let Some((rank, pair)) = ids
+                .windows(2)
+                .filter_map(|p| self.ranks.get(&(p[0], p[1])).map(|&r| (r, (p[0], p[1]))))
+                .min()
+            {
+                ids = merge_pair(&ids, pair, 256 + rank);
+            }
+            encoded.extend(ids);
+        }
+        encoded
+    }
+
+    fn decode(&self, ids: &[usize]) -> String {
+        let bytes: Vec<u8> = ids
+            .iter()
+            .filter_map(|&i| self.vocab.get(i))
+            .flatten()
+            .cloned()
+            .collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// A batch of token sequences that are padded to the same length. See [PadSequences].
+#[derive(Debug, Clone)]
+pub struct TokenBatch<D: DeviceStorage> {
+    /// The `(batch, seq)` token ids, with padding after the end of each sequence.
+    pub ids: Tensor<(usize, usize), usize, D>,
+
+    /// `1.0` for real tokens and `0.0` for padding, with the same shape as `ids`.
+    pub mask: Tensor<(usize, usize), f32, D>,
+}
+
+/// Pads token sequences into a [TokenBatch], so raw text can be fed to a model.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::{prelude::*, data::*};
+/// # let dev: Cpu = Default::default();
+/// let batch = dev.pad_sequences(&[vec![5, 6, 7], vec![8]], 0, None);
+/// assert_eq!(batch.ids.shape(), &(2, 3));
+/// assert_eq!(batch.ids.as_vec(), [5, 6, 7, 8, 0, 0]);
+/// assert_eq!(batch.mask.as_vec(), [1.0, 1.0, 1.0, 1.0, 0.0, 0.0]);
+///
+/// let tok = BpeTokenizer::default();
+/// let batch = dev.tokenize_batch(&tok, &["hi", "hello"], 0, Some(4));
+/// assert_eq!(batch.ids.as_vec(), [104, 105, 0, 0, 104, 101, 108, 108]);
+/// ```
+pub trait PadSequences: DeviceStorage + TensorFromVec<usize> + TensorFromVec<f32> {
+    /// Pads each of `seqs` with `pad_id` to the length of the longest one. Sequences longer
+    /// than `max_len` are truncated.
+    fn pad_sequences(
+        &self,
+        seqs: &[Vec<usize>],
+        pad_id: usize,
+        max_len: Option<usize>,
+    ) -> TokenBatch<Self> {
+        let longest = seqs.iter().map(|s| s.len()).max().unwrap_or(0);
+        let len = max_len.map_or(longest, |m| m.min(longest));
+        let mut ids = Vec::with_capacity(seqs.len() * len);
+        let mut mask = Vec::with_capacity(seqs.len() * len);
+        for seq in seqs {
+            for i in 0..len {
+                ids.push(seq.get(i).cloned().unwrap_or(pad_id));
+                mask.push(if i < seq.len() { 1.0 } else { 0.0 });
+            }
+        }
+        TokenBatch {
+            ids: self.tensor_from_vec(ids, (seqs.len(), len)),
+            mask: self.tensor_from_vec(mask, (seqs.len(), len)),
+        }
+    }
+
+    /// Encodes each of `texts` with `tokenizer`, and pads the ids with
+    /// [PadSequences::pad_sequences()].
+    fn tokenize_batch<T: Tokenizer, S: AsRef<str>>(
+        &self,
+        tokenizer: &T,
+        texts: &[S],
+        pad_id: usize,
+        max_len: Option<usize>,
+    ) -> TokenBatch<Self> {
+        let seqs: Vec<Vec<usize>> = texts.iter().map(|t| tokenizer.encode(t.as_ref())).collect();
+        self.pad_sequences(&seqs, pad_id, max_len)
+    }
+}
+impl<D: DeviceStorage + TensorFromVec<usize> + TensorFromVec<f32>> PadSequences for D {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{shapes::HasShape, tensor::AsVec, tests::TestDevice};
 
     #[test]
     fn sampler_uses_all() {
@@ -155,4 +388,54 @@ mod tests {
             assert!(seen.contains(&i));
         }
     }
+
+    #[test]
+    fn test_bpe_train() {
+        let tok = BpeTokenizer::train("aaab aaab aab", 1000);
+        // "aa" is the most frequent pair
+        assert_eq!(tok.merges()[0], (97, 97));
+        assert_eq!(tok.decode(&[256]), "aa");
+        // training stops once no pair appears twice, so "aab" is never merged
+        assert_eq!(tok.vocab_size(), 260);
+        assert_eq!(tok.encode("aaab "), [259]);
+        assert_eq!(tok.encode("aab"), [256, 98]);
+
+        // text outside of the training data still round trips
+        let text = "ab ba\tünïcödé";
+        assert_eq!(tok.decode(&tok.encode(text)), text);
+    }
+
+    #[test]
+    fn test_bpe_from_merges() {
+        // "h" + "e", "l" + "l", "he" + "ll"
+        let tok = BpeTokenizer::from_merges(alloc::vec![(104, 101), (108, 108), (256, 257)]);
+        assert_eq!(tok.vocab_size(), 259);
+        assert_eq!(tok.encode("hello hell"), [258, 111, 32, 258]);
+        assert_eq!(tok.decode(&[258, 111]), "hello");
+        // ids outside of the vocabulary are skipped
+        assert_eq!(tok.decode(&[258, 259, 111, usize::MAX]), "hello");
+    }
+
+    #[test]
+    #[should_panic = "Merge 1 merges the same pair as merge 0"]
+    fn test_bpe_from_merges_duplicate() {
+        BpeTokenizer::from_merges(alloc::vec![(104, 101), (104, 101)]);
+    }
+
+    #[test]
+    fn test_pad_sequences() {
+        let dev: TestDevice = Default::default();
+        let batch = dev.pad_sequences(
+            &[alloc::vec![1, 2], alloc::vec![], alloc::vec![3, 4, 5]],
+            9,
+            Some(2),
+        );
+        assert_eq!(batch.ids.shape(), &(3, 2));
+        assert_eq!(batch.ids.as_vec(), [1, 2, 9, 9, 3, 4]);
+        assert_eq!(batch.mask.as_vec(), [1.0, 1.0, 0.0, 0.0, 1.0, 1.0]);
+
+        let batch = dev.tokenize_batch(&BpeTokenizer::default(), &["ab", "c"], 0, None);
+        assert_eq!(batch.ids.as_vec(), [97, 98, 99, 0]);
+        assert_eq!(batch.mask.as_vec(), [1.0, 1.0, 1.0, 0.0]);
+    }
 }
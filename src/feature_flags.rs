@@ -18,9 +18,16 @@
 //!
 //! Note that allocations are necessary, so the no_std_compat dependency looks like:
 //! ```toml
-//! no-std-compat = { version = "0.4.1", features = [ "alloc", "compat_hash" ] }
+//! no-std-compat = { version = "0.4.1", features = [ "alloc", "compat_hash", "compat_sync" ] }
 //! ```
 //!
+//! Without "std", the [crate::tensor::Cpu] device, tensor ops, and nn modules all work with just
+//! `alloc`, so trained models can run inference on embedded targets or in WASM. The
+//! target needs a global allocator. Differences from the default build:
+//! - The device's rng is behind a spin lock instead of a [std::sync::Mutex]
+//! - Float math in random distributions uses [libm](https://crates.io/crates/libm)
+//! - Features that need files or threads ("numpy", "mmap", "python") are unavailable
+//!
 //! # "intel-mkl"
 //!
 //! Enables using the `Intel MKL` libraries (assuming you installed it already) for matrix multiplication.
@@ -45,6 +52,21 @@
 //!
 //! `build.rs` will fail helpfully if you don't have the correct path/environment variables.
 //!
+//! # "openblas", "accelerate", & "cblas"
+//!
+//! Enables using a system BLAS library for matrix multiplication. "openblas" links to
+//! OpenBLAS, and "accelerate" links to Apple's Accelerate framework. "cblas" on its own
+//! does not link anything, so you can link to any other cblas implementation yourself.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["openblas"] }
+//! ```
+//!
+//! When any BLAS feature is enabled, [crate::tensor::Cpu] uses it by default. The backend
+//! can also be selected at runtime with [crate::tensor::Cpu::with_matmul_backend], which
+//! additionally supports a multi-threaded pure rust kernel ([crate::tensor::CpuMatMulBackend::Blocked]).
+//!
 //! # "numpy"
 //!
 //! **Enabled by default**
@@ -56,6 +78,48 @@
 //! dfdx = { version = "...", features = ["numpy"] }
 //! ```
 //!
+//! # "mmap"
+//!
+//! Enables creating tensors that are memory mapped from `.npy` and `.npz` files with
+//! `Cpu::mmap_npy()` and `MmapNpz`, which load instantly and share memory between processes.
+//! Only supported on unix.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["mmap"] }
+//! ```
+//!
+//! # "ndarray"
+//!
+//! Enables converting tensors on the `Cpu` to and from `ndarray` arrays, with
+//! `Tensor::as_ndarray()`, `Cpu::from_ndarray()` and the matching `From`/`TryFrom` impls.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["ndarray"] }
+//! ```
+//!
+//! # "python"
+//!
+//! Enables the `python` module, which exposes tensors and models to python with
+//! [PyO3](https://pyo3.rs), including zero copy numpy views. Enables "ndarray" as well.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["python"] }
+//! ```
+//!
+//! # "bench"
+//!
+//! Enables the [crate::bench] module, standardized [criterion](https://docs.rs/criterion)
+//! workloads (matmuls, conv layers and transformer blocks) for comparing devices and kernel
+//! changes. The cpu suite runs with `cargo bench --features bench`.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["bench"] }
+//! ```
+//!
 //! # "nightly"
 //!
 //! Enables using all features that currently require the nightly rust compiler.
@@ -64,11 +128,33 @@
 //! ```toml
 //! dfdx = { version = "...", features = ["nightly"] }
 //! ```
+//!
+//! # "stable-fallback"
+//!
+//! Enables the conv, pool, flatten and transformer modules on the stable compiler. Output
+//! dimensions that "nightly" computes at compile time become `usize` dimensions that are
+//! checked at runtime:
+//! - `conv2d()` and the pooling ops output `usize` heights and widths
+//! - [crate::nn::Flatten2D] outputs a `usize` flattened dimension
+//! - [crate::nn::MultiHeadAttention] masks heads instead of reshaping into them, and
+//!   panics if the key/value dims aren't divisible by the number of heads
+//!
+//! If "nightly" is enabled as well, the nightly implementations are used.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["stable-fallback"] }
+//! ```
 
-#[cfg(not(feature = "intel-mkl"))]
-/// The library used for BLAS. Configure with crate features.
-pub const BLAS_LIB: &str = "matrix-multiply";
-
-#[cfg(feature = "intel-mkl")]
 /// The library used for BLAS. Configure with crate features.
-pub const BLAS_LIB: &str = "intel-mkl";
+pub const BLAS_LIB: &str = if cfg!(feature = "intel-mkl") {
+    "intel-mkl"
+} else if cfg!(feature = "accelerate") {
+    "accelerate"
+} else if cfg!(feature = "openblas") {
+    "openblas"
+} else if cfg!(feature = "cblas") {
+    "cblas"
+} else {
+    "matrix-multiply"
+};
@@ -2,11 +2,11 @@
 #![allow(clippy::type_complexity)]
 
 use core::marker::PhantomData;
-use std::collections::HashMap;
-use std::{boxed::Box, vec::Vec};
+use std::collections::{BTreeMap, HashMap};
+use std::{boxed::Box, string::String, vec::Vec};
 
-use crate::shapes::{HasDtype, HasShape};
-use crate::tensor::storage_traits::{AllocGrad, DeviceStorage};
+use crate::shapes::{Dtype, HasDtype, HasShape, Shape};
+use crate::tensor::storage_traits::{AllocGrad, AsVec, DeviceStorage};
 use crate::unique_id::{HasUniqueId, UniqueId};
 
 /// A generic container for keeping variable sized arrays associated with a [UniqueId].
@@ -54,6 +54,15 @@ impl<D: DeviceStorage> Gradients<D> {
         Ok(())
     }
 
+    /// Inserts `grad` as the gradient of `t`, replacing any previous gradient.
+    pub(crate) fn insert<T: HasUniqueId + HasShape + HasDtype>(
+        &mut self,
+        t: &T,
+        grad: D::Storage<T::Shape, T::Dtype>,
+    ) {
+        self.gradient_by_id.insert(*t.id(), Box::new(grad));
+    }
+
     /// Removes and returns the data associated with `t.id()`.
     ///
     /// **Panics** if data associated with `t` is not found. This indicates an unrecoverable bug.
@@ -98,6 +107,16 @@ impl<D: DeviceStorage> Gradients<D> {
             .unwrap()
     }
 
+    /// Returns a reference to the gradient associated with `t`, or `None` if there isn't one.
+    pub(crate) fn get_checked<T: HasUniqueId + HasDtype + HasShape>(
+        &self,
+        t: &T,
+    ) -> Option<&D::Storage<T::Shape, T::Dtype>> {
+        self.gradient_by_id
+            .get(t.id())
+            .map(|g| g.as_ref().downcast_ref().unwrap())
+    }
+
     /// Borrows a pair of a gradients `(&mut L, &R)`.
     /// `l` is the gradient to update, and `r` is the gradient to backprop.
     ///
@@ -185,6 +204,8 @@ impl<D: DeviceStorage> Gradients<D> {
 pub struct GradientTape<D: DeviceStorage> {
     operations: Vec<Box<dyn FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>>,
     gradients: Gradients<D>,
+    recording: Option<Recording<D>>,
+    detect_anomaly: bool,
 }
 
 impl<D: DeviceStorage> Default for GradientTape<D> {
@@ -192,10 +213,53 @@ impl<D: DeviceStorage> Default for GradientTape<D> {
         Self {
             operations: Vec::new(),
             gradients: Default::default(),
+            recording: None,
+            detect_anomaly: false,
+        }
+    }
+}
+
+/// The metadata of the operations on a [GradientTape]. This is only recorded if asked for
+/// with [OwnedTape::with_summary()], so that normal training doesn't pay for it.
+struct Recording<D: DeviceStorage> {
+    /// The index into [GradientTape::operations] of each recorded operation, its record,
+    /// and the checks for the gradients of its tensors.
+    ops: Vec<(usize, OpRecord, Vec<NonFiniteCheck<D>>)>,
+    /// The tensors used by the next operation.
+    pending: Vec<(TensorRecord, NonFiniteCheck<D>)>,
+}
+
+impl<D: DeviceStorage> Default for Recording<D> {
+    fn default() -> Self {
+        Self {
+            ops: Vec::new(),
+            pending: Vec::new(),
         }
     }
 }
 
+/// Returns whether the gradient with `id` contains a NaN or infinite value.
+type NonFiniteCheck<D> = fn(&Gradients<D>, &UniqueId) -> bool;
+
+fn has_non_finite<D: DeviceStorage, S: Shape, E: Dtype>(
+    grads: &Gradients<D>,
+    id: &UniqueId,
+) -> bool {
+    grads
+        .gradient_by_id
+        .get(id)
+        .and_then(|g| g.downcast_ref::<D::Storage<S, E>>())
+        .map(|g| {
+            #[allow(clippy::eq_op)]
+            g.as_vec().into_iter().any(|x| {
+                // `x - x` is NaN for both NaN and +/- infinity, and NaN is the only value not equal to itself.
+                let d = x - x;
+                d != d
+            })
+        })
+        .unwrap_or(false)
+}
+
 impl<D: DeviceStorage> std::fmt::Debug for GradientTape<D> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("GradientTape")
@@ -214,30 +278,447 @@ impl<D: DeviceStorage> GradientTape<D> {
     /// See src/tensor_ops for implementation examples.
     pub(crate) fn add_backward_op<F: 'static + FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>(
         &mut self,
+        name: &'static str,
         operation: F,
     ) {
+        if let Some(recording) = self.recording.as_mut() {
+            let (tensors, checks) = recording.pending.drain(..).unzip();
+            let record = OpRecord {
+                type_name: name,
+                tensors,
+            };
+            recording.ops.push((self.operations.len(), record, checks));
+        }
         self.operations.push(Box::new(operation));
     }
 
+    /// Allocates the gradient for `t`, and if the tape is recording, records `t` as being
+    /// used by the next operation, as its output if `is_output`.
+    pub(crate) fn try_alloc_grad<T: HasUniqueId + AllocGrad<D>>(
+        &mut self,
+        t: &T,
+        is_output: bool,
+    ) -> Result<(), D::Err> {
+        if let Some(recording) = self.recording.as_mut() {
+            let check = has_non_finite::<D, T::Shape, T::Dtype>;
+            recording
+                .pending
+                .push((TensorRecord::new(t, is_output), check));
+        }
+        self.gradients.try_alloc_for(t)
+    }
+
+    /// Returns a [TapeSummary] of all the operations currently recorded. This is empty
+    /// unless the tape was created with [OwnedTape::with_summary()].
+    pub fn summary(&self) -> TapeSummary {
+        let ops = match self.recording.as_ref() {
+            Some(recording) => recording.ops.iter().map(|op| op.1.clone()).collect(),
+            None => Vec::new(),
+        };
+        TapeSummary { ops }
+    }
+
     /// Compute the [Gradients]! This just runs all the operations on a new [Gradients] struct.
     ///
     /// Note that this method takes ownership of self, so it can't be called twice!
+    ///
+    /// If the tape was created with [OwnedTape::with_anomaly_detection()], the gradients
+    /// each operation writes to are checked after it runs.
     pub(crate) fn execute(mut self) -> Result<Gradients<D>, D::Err> {
-        for operation in self.operations.drain(..).rev() {
+        let num_ops = self.operations.len();
+        let mut records = match self.recording.take() {
+            Some(recording) if self.detect_anomaly => recording.ops,
+            _ => Vec::new(),
+        };
+        for (i, operation) in self.operations.drain(..).enumerate().rev() {
             (operation)(&mut self.gradients)?;
+            if records.last().map(|r| r.0) == Some(i) {
+                let (_, record, checks) = records.pop().unwrap();
+                Self::check_anomaly(&self.gradients, &record, &checks, i, num_ops);
+            }
         }
         Ok(self.gradients)
     }
 
+    /// Panics if any of the gradients written to by `record`'s backward op are non finite.
+    /// The output's gradient is only read by the backward op, so only the inputs are checked.
+    fn check_anomaly(
+        grads: &Gradients<D>,
+        record: &OpRecord,
+        checks: &[NonFiniteCheck<D>],
+        index: usize,
+        num_ops: usize,
+    ) {
+        for (t, check) in record.tensors.iter().zip(checks.iter()) {
+            if !t.is_output && check(grads, &t.id) {
+                panic!(
+                    "Anomaly detected: backward of `{}` (op {} of {}) produced NaN/inf gradient for tensor {} with shape {:?}",
+                    record.name(),
+                    index,
+                    num_ops,
+                    t.id,
+                    t.shape,
+                );
+            }
+        }
+    }
+
     /// Moves all the operations from `other` into self. Leaves `other` empty.
     pub(crate) fn append(&mut self, other: &mut Self) {
+        self.detect_anomaly |= other.detect_anomaly;
         self.gradients
             .gradient_by_id
             .extend(other.gradients.gradient_by_id.drain());
+        if let Some(mut recording) = other.recording.take() {
+            let offset = self.operations.len();
+            for op in recording.ops.iter_mut() {
+                op.0 += offset;
+            }
+            match self.recording.as_mut() {
+                Some(ours) => {
+                    ours.ops.append(&mut recording.ops);
+                    ours.pending.append(&mut recording.pending);
+                }
+                None => self.recording = Some(recording),
+            }
+        }
         self.operations.append(&mut other.operations);
     }
 }
 
+/// Debugging information about a tensor used by an operation on a [GradientTape].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorRecord {
+    /// The id of the tensor.
+    pub id: UniqueId,
+    /// The concrete dimensions of the tensor.
+    pub shape: Vec<usize>,
+    /// The number of bytes the tensor's gradient takes up.
+    pub num_bytes: usize,
+    /// Whether the tensor is the output of the operation. The backward op reads the
+    /// gradient of the output, and writes the gradients of the other tensors.
+    pub is_output: bool,
+}
+
+impl TensorRecord {
+    fn new<T: HasUniqueId + HasShape + HasDtype>(t: &T, is_output: bool) -> Self {
+        let shape = *t.shape();
+        Self {
+            id: *t.id(),
+            shape: shape.concrete().into(),
+            num_bytes: shape.num_elements() * std::mem::size_of::<T::Dtype>(),
+            is_output,
+        }
+    }
+}
+
+/// Debugging information about an operation recorded on a [GradientTape].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpRecord {
+    type_name: &'static str,
+    /// The tensors that this operation allocated gradients for, in the order they
+    /// were registered. See [TensorRecord::is_output].
+    pub tensors: Vec<TensorRecord>,
+}
+
+impl OpRecord {
+    /// The tensors whose gradients the backward op writes to.
+    pub fn inputs(&self) -> impl Iterator<Item = &TensorRecord> {
+        self.tensors.iter().filter(|t| !t.is_output)
+    }
+
+    /// The output of the operation, if it recorded one.
+    pub fn output(&self) -> Option<&TensorRecord> {
+        self.tensors.iter().find(|t| t.is_output)
+    }
+
+    /// A short human readable name of the operation, e.g. `ReLUKernelOp` or `try_sum`.
+    pub fn name(&self) -> String {
+        // strip generic parameters, which may contain `::` themselves
+        let mut stripped = String::new();
+        let mut depth = 0;
+        for c in self.type_name.chars() {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                c if depth == 0 => stripped.push(c),
+                _ => {}
+            }
+        }
+        let segments = stripped
+            .split("::")
+            .filter(|s| !s.is_empty() && *s != "{{closure}}")
+            .collect::<Vec<_>>();
+        let mut segments = segments.into_iter().rev();
+        match (segments.next(), segments.next()) {
+            (Some(last), Some(parent)) if last.starts_with(char::is_lowercase) => {
+                alloc::format!("{parent}::{last}")
+            }
+            (Some(last), _) => last.into(),
+            (None, _) => self.type_name.into(),
+        }
+    }
+}
+
+/// A snapshot of the operations recorded on a [GradientTape], in the order they were
+/// recorded during the forward pass.
+///
+/// Get one from a tensor traced with [crate::tensor::Tensor::trace_with_summary()] like so:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<3>> = dev.zeros();
+/// let y = x.trace_with_summary().relu().sum();
+/// let (y, tape) = y.split_tape();
+/// let summary = tape.summary();
+/// assert_eq!(summary.ops.len(), 2);
+/// assert_eq!(summary.ops[0].name(), "ReLUKernelOp");
+/// std::println!("{}", summary.to_dot());
+/// let y = y.put_tape(tape);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TapeSummary {
+    pub ops: Vec<OpRecord>,
+}
+
+impl TapeSummary {
+    /// All the unique tensors referenced by the tape.
+    pub fn tensors(&self) -> BTreeMap<UniqueId, &TensorRecord> {
+        let mut tensors = BTreeMap::new();
+        for op in self.ops.iter() {
+            for t in op.tensors.iter() {
+                tensors.insert(t.id, t);
+            }
+        }
+        tensors
+    }
+
+    /// The total number of bytes of gradients the tape will allocate.
+    pub fn num_bytes(&self) -> usize {
+        self.tensors().values().map(|t| t.num_bytes).sum()
+    }
+
+    /// Renders the tape as a [Graphviz](https://graphviz.org/) DOT graph. Operations
+    /// are boxes, and tensors are ellipses labeled with their shape and gradient size.
+    pub fn to_dot(&self) -> String {
+        use std::fmt::Write;
+        let mut dot = String::new();
+        writeln!(dot, "digraph tape {{").unwrap();
+        for (id, t) in self.tensors() {
+            writeln!(
+                dot,
+                "    t{} [shape=ellipse, label=\"{:?}\\n{} bytes\"];",
+                id, t.shape, t.num_bytes
+            )
+            .unwrap();
+        }
+        for (i, op) in self.ops.iter().enumerate() {
+            writeln!(dot, "    op{i} [shape=box, label=\"{}\"];", op.name()).unwrap();
+            for inp in op.inputs() {
+                writeln!(dot, "    t{} -> op{i};", inp.id).unwrap();
+            }
+            if let Some(out) = op.output() {
+                writeln!(dot, "    op{i} -> t{};", out.id).unwrap();
+            }
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    /// Estimates how much memory the tensors on the tape take up over the course of the
+    /// forward and backward pass. Nothing is measured, the estimate is computed from the
+    /// recorded shapes only. See [MemoryEstimate] for details.
+    pub fn estimate_memory(&self) -> MemoryEstimate {
+        let num_ops = self.ops.len();
+        let outputs: BTreeMap<UniqueId, usize> = self
+            .ops
+            .iter()
+            .enumerate()
+            .filter_map(|(i, op)| op.output().map(|t| (t.id, i)))
+            .collect();
+
+        // (first op, last op, bytes) for every tensor. ops are recorded in forward order,
+        // so the first op is where the tensor appears and the last op is the latest user.
+        let mut spans: BTreeMap<UniqueId, (usize, usize, usize)> = BTreeMap::new();
+        for (i, op) in self.ops.iter().enumerate() {
+            for t in op.tensors.iter() {
+                spans
+                    .entry(t.id)
+                    .and_modify(|s| s.1 = i)
+                    .or_insert((i, i, t.num_bytes));
+            }
+        }
+        // backward runs in reverse, so the first op to use a tensor is the last
+        // backward op that holds onto its value.
+        let mut value_freed = alloc::vec![0; num_ops];
+        for (id, &(first, _, num_bytes)) in spans.iter() {
+            if outputs.contains_key(id) {
+                value_freed[first] += num_bytes;
+            }
+        }
+
+        let mut timeline = Vec::with_capacity(2 * num_ops);
+        let mut live_bytes = 0;
+        let mut allocated = alloc::vec![0; num_ops];
+        for &(first, _, num_bytes) in spans.values() {
+            // the value and the gradient
+            allocated[first] += 2 * num_bytes;
+        }
+        for (op, &num_bytes) in allocated.iter().enumerate() {
+            live_bytes += num_bytes;
+            timeline.push(MemoryStep {
+                op,
+                backward: false,
+                live_bytes,
+            });
+        }
+        for op in (0..num_ops).rev() {
+            live_bytes -= value_freed[op];
+            timeline.push(MemoryStep {
+                op,
+                backward: true,
+                live_bytes,
+            });
+        }
+
+        let peak = timeline
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, s)| (s.live_bytes, core::cmp::Reverse(*i)))
+            .map(|(i, _)| i);
+        let mut contributions = alloc::vec![0; num_ops];
+        if let Some(peak) = peak {
+            for (id, &(first, _, num_bytes)) in spans.iter() {
+                if peak < first {
+                    continue;
+                }
+                let value_alive = match outputs.contains_key(id) {
+                    true => peak < 2 * num_ops - 1 - first,
+                    false => true,
+                };
+                contributions[first] += num_bytes + if value_alive { num_bytes } else { 0 };
+            }
+        }
+        let mut top_ops: Vec<OpMemory> = contributions
+            .into_iter()
+            .enumerate()
+            .filter(|(_, num_bytes)| *num_bytes > 0)
+            .map(|(op, num_bytes)| OpMemory {
+                op,
+                name: self.ops[op].name(),
+                num_bytes,
+            })
+            .collect();
+        top_ops.sort_by(|a, b| b.num_bytes.cmp(&a.num_bytes).then(a.op.cmp(&b.op)));
+
+        MemoryEstimate {
+            peak: peak.map(|i| timeline[i]),
+            timeline,
+            top_ops,
+        }
+    }
+}
+
+/// The estimated memory in use after a single step of the forward or backward pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStep {
+    /// The index of the operation in [TapeSummary::ops].
+    pub op: usize,
+    /// Whether this is after the backward of the op, or after the op in the forward pass.
+    pub backward: bool,
+    /// The number of bytes of tensor values and gradients alive after this step.
+    pub live_bytes: usize,
+}
+
+/// How many bytes of an operation's tensors are alive at the peak of a [MemoryEstimate].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpMemory {
+    /// The index of the operation in [TapeSummary::ops].
+    pub op: usize,
+    /// See [OpRecord::name()].
+    pub name: String,
+    /// The estimated bytes of values and gradients, of the tensors attributed to this op,
+    /// that are alive at the peak.
+    pub num_bytes: usize,
+}
+
+/// Estimated memory usage of a [GradientTape] over time, created with [TapeSummary::estimate_memory()].
+///
+/// This does not measure any allocations, it is computed from the shapes recorded on the tape.
+/// Temporary buffers used inside kernels, and values that are freed earlier or later than
+/// assumed here, are not accounted for. It assumes that:
+/// - Gradients are allocated during the forward pass when a tensor is first used, and live
+///   until the end of backward, since they are returned in [Gradients].
+/// - Values of op outputs are kept alive by the backward ops that use them, and are freed once
+///   the backward of their first user has run.
+/// - Values of tensors not created on the tape (e.g. parameters) stay alive the whole time.
+///
+/// Each tensor is attributed to the op that first used it, which for intermediate values is the
+/// op that produced it. Ops with large contributions to [MemoryEstimate::top_ops] are good
+/// candidates for checkpointing or fusing.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank2<8, 8>> = dev.zeros();
+/// let y = x.trace_with_summary().relu().sum::<Rank0, _>();
+/// let (_, tape) = y.split_tape();
+/// let estimate = tape.summary().estimate_memory();
+/// assert_eq!(estimate.peak_bytes(), 2 * 4 * (64 + 64 + 1));
+/// assert_eq!(estimate.top_ops[0].name, "ReLUKernelOp");
+/// assert!(estimate.to_string().starts_with("estimated peak memory: 1032 bytes"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// The step with the most live bytes, or `None` if the tape is empty.
+    pub peak: Option<MemoryStep>,
+    /// One step for each op in the forward pass, followed by one for each op in the backward pass.
+    pub timeline: Vec<MemoryStep>,
+    /// The ops whose tensors are alive at the peak, sorted by most bytes first.
+    pub top_ops: Vec<OpMemory>,
+}
+
+impl MemoryEstimate {
+    /// The maximum number of bytes alive at once.
+    pub fn peak_bytes(&self) -> usize {
+        self.peak.map_or(0, |s| s.live_bytes)
+    }
+}
+
+impl std::fmt::Display for MemoryEstimate {
+    /// Writes the peak and the ten biggest contributors to it.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.peak {
+            Some(peak) => writeln!(
+                f,
+                "estimated peak memory: {} bytes after {} of op #{}",
+                peak.live_bytes,
+                if peak.backward { "backward" } else { "forward" },
+                peak.op
+            )?,
+            None => writeln!(f, "estimated peak memory: 0 bytes")?,
+        }
+        for op in self.top_ops.iter().take(10) {
+            writeln!(f, "#{} {} {}B", op.op, op.name, op.num_bytes)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TapeSummary {
+    /// Writes one line per operation with its tensor shapes and gradient sizes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, op) in self.ops.iter().enumerate() {
+            write!(f, "#{i} {}", op.name())?;
+            for t in op.tensors.iter() {
+                write!(f, " | t{} {:?} {}B", t.id, t.shape, t.num_bytes)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "total gradient bytes: {}", self.num_bytes())
+    }
+}
+
 /// Contains a boxed [GradientTape]. When [Tape::add_backward_op] is called,
 /// this function passes the operation directly to [GradientTape].
 #[derive(Debug, Default)]
@@ -254,33 +735,103 @@ pub trait Tape<D: DeviceStorage>: Default + Merge<Self> + Merge<NoneTape> {
     fn add_backward_op<F: 'static + FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>(
         &mut self,
         operation: F,
+    ) {
+        self.add_named_backward_op(std::any::type_name::<F>(), operation)
+    }
+    /// Same as [Tape::add_backward_op], but records `name` as the name of the operation
+    /// instead of the type name of `operation`. See [OpRecord::name()].
+    fn add_named_backward_op<F: 'static + FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>(
+        &mut self,
+        name: &'static str,
+        operation: F,
     );
+    /// Allocates the gradient of `t`, which is an input of the next operation.
     fn try_alloc_grad<T: HasUniqueId + AllocGrad<D>>(&mut self, t: &T) -> Result<(), D::Err>;
+    /// Allocates the gradient of `t`, which is the output of the next operation.
+    fn try_alloc_output_grad<T: HasUniqueId + AllocGrad<D>>(&mut self, t: &T)
+        -> Result<(), D::Err>;
+}
+
+impl<D: DeviceStorage> OwnedTape<D> {
+    /// A tape that also records the name and tensors of every operation, so that
+    /// [OwnedTape::summary()] can describe them. Normal tapes don't record anything.
+    ///
+    /// When two tapes are merged and either of them records, the merged tape records too.
+    pub fn with_summary() -> Self {
+        Self(Box::new(GradientTape {
+            recording: Some(Default::default()),
+            ..Default::default()
+        }))
+    }
+
+    /// A tape that checks the gradients each backward operation writes to for NaN/infinite
+    /// values. If any are found, the backward pass **panics** with the name of the forward
+    /// operation that produced them along with the shape of the offending tensor. This
+    /// also records the operations like [OwnedTape::with_summary()].
+    ///
+    /// This copies every gradient to the host after each operation, so it is very slow
+    /// and should only be used for debugging. Only the ops on this tape (or tapes merged
+    /// into it) are checked.
+    ///
+    /// ```rust,should_panic
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<2>> = dev.zeros();
+    /// // gradient of sqrt at 0 is infinite
+    /// let _ = x.trace_with_anomaly_detection().sqrt().sum().backward();
+    /// ```
+    pub fn with_anomaly_detection() -> Self {
+        Self(Box::new(GradientTape {
+            recording: Some(Default::default()),
+            detect_anomaly: true,
+            ..Default::default()
+        }))
+    }
+
+    /// Returns a [TapeSummary] of all the operations recorded so far, or an empty one
+    /// if the tape wasn't created with [OwnedTape::with_summary()].
+    pub fn summary(&self) -> TapeSummary {
+        self.0.summary()
+    }
 }
 
 impl<D: DeviceStorage> Tape<D> for OwnedTape<D> {
     const OWNS_TAPE: bool = true;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>(
+    fn add_named_backward_op<F: 'static + FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>(
         &mut self,
+        name: &'static str,
         operation: F,
     ) {
-        self.0.add_backward_op(operation)
+        self.0.add_backward_op(name, operation)
     }
     fn try_alloc_grad<T: HasUniqueId + AllocGrad<D>>(&mut self, t: &T) -> Result<(), D::Err> {
-        self.0.gradients.try_alloc_for(t)
+        self.0.try_alloc_grad(t, false)
+    }
+    fn try_alloc_output_grad<T: HasUniqueId + AllocGrad<D>>(
+        &mut self,
+        t: &T,
+    ) -> Result<(), D::Err> {
+        self.0.try_alloc_grad(t, true)
     }
 }
 
 impl<D: DeviceStorage> Tape<D> for NoneTape {
     const OWNS_TAPE: bool = false;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>(
+    fn add_named_backward_op<F: 'static + FnOnce(&mut Gradients<D>) -> Result<(), D::Err>>(
         &mut self,
+        _: &'static str,
         _: F,
     ) {
     }
     fn try_alloc_grad<T: HasUniqueId + AllocGrad<D>>(&mut self, _: &T) -> Result<(), D::Err> {
         Ok(())
     }
+    fn try_alloc_output_grad<T: HasUniqueId + AllocGrad<D>>(
+        &mut self,
+        _: &T,
+    ) -> Result<(), D::Err> {
+        Ok(())
+    }
 }
 
 /// Combine two things
@@ -307,3 +858,146 @@ impl<D: DeviceStorage> Merge<OwnedTape<D>> for OwnedTape<D> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_tape_summary() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let b: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let c = (a.trace_with_summary() * b.clone())
+            .relu()
+            .sum::<Rank0, _>();
+        let (_, tape) = c.split_tape();
+        let summary = tape.summary();
+        let names: Vec<String> = summary.ops.iter().map(|op| op.name()).collect();
+        assert_eq!(names, ["BinaryMulKernelOp", "ReLUKernelOp", "try_sum"]);
+        assert_eq!(summary.ops[0].tensors.len(), 3);
+        assert_eq!(summary.ops[0].tensors[0].shape, [2, 3]);
+        assert_eq!(
+            summary.ops[2].tensors[1].shape,
+            std::vec::Vec::<usize>::new()
+        );
+        // a, b, a * b, relu(a * b), and the sum
+        assert_eq!(summary.tensors().len(), 5);
+        assert_eq!(summary.num_bytes(), 4 * (6 * 4 + 1));
+
+        let dot = summary.to_dot();
+        assert!(dot.starts_with("digraph tape {"));
+        assert!(dot.contains("[shape=box, label=\"ReLUKernelOp\"]"));
+        assert!(alloc::format!("{summary}").ends_with("total gradient bytes: 100"));
+    }
+
+    #[test]
+    fn test_summary_is_opt_in() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        let (_, tape) = a.trace().relu().sum::<Rank0, _>().split_tape();
+        assert!(tape.summary().ops.is_empty());
+        assert!(tape.0.recording.is_none());
+
+        // merging in a recording tape records the ops after it
+        let b = a.trace_with_summary().exp();
+        let (_, tape) = (a.trace().relu() + b).sum::<Rank0, _>().split_tape();
+        let names: Vec<String> = tape.summary().ops.iter().map(|op| op.name()).collect();
+        assert_eq!(names, ["ExpKernelOp", "BinaryAddKernelOp", "try_sum"]);
+    }
+
+    #[test]
+    fn test_estimate_memory() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let b: Tensor<Rank2<4, 3>, f32, _> = dev.zeros();
+        let c = a
+            .trace_with_summary()
+            .matmul(b.permute())
+            .exp()
+            .sum::<Rank0, _>();
+        let (_, tape) = c.split_tape();
+        let estimate = tape.summary().estimate_memory();
+
+        // forward allocates the value & gradient of every tensor
+        let live: Vec<usize> = estimate.timeline.iter().map(|s| s.live_bytes).collect();
+        let mm = 2 * 4 * (6 + 12 + 8);
+        let exp = mm + 2 * 4 * 8;
+        let sum = exp + 2 * 4;
+        // backward frees the sum, the exp output and the matmul output
+        assert_eq!(
+            live,
+            [mm, exp, sum, sum - 4, sum - 4 - 32, sum - 4 - 32 - 32]
+        );
+        assert_eq!(
+            estimate.peak,
+            Some(MemoryStep {
+                op: 2,
+                backward: false,
+                live_bytes: sum
+            })
+        );
+        assert_eq!(estimate.peak_bytes(), sum);
+
+        let top: Vec<(usize, usize)> = estimate
+            .top_ops
+            .iter()
+            .map(|op| (op.op, op.num_bytes))
+            .collect();
+        assert_eq!(top, [(0, mm), (1, 64), (2, 8)]);
+        assert!(alloc::format!("{estimate}").starts_with("estimated peak memory: 280 bytes"));
+    }
+
+    #[test]
+    fn test_empty_estimate_memory() {
+        let estimate = TapeSummary { ops: Vec::new() }.estimate_memory();
+        assert_eq!(estimate.peak, None);
+        assert_eq!(estimate.peak_bytes(), 0);
+        assert!(estimate.top_ops.is_empty());
+    }
+
+    #[test]
+    fn test_no_anomaly() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let y = x.trace_with_anomaly_detection().sqrt().sum::<Rank0, _>();
+        let grads = y.backward();
+        assert!(!has_non_finite::<_, Rank1<3>, f32>(&grads, x.id()));
+    }
+
+    #[test]
+    #[should_panic = "backward of `SqrtKernelOp` (op 0 of 3) produced NaN/inf gradient"]
+    fn test_detect_anomaly() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 0.0, 3.0]);
+        let _ = x
+            .trace_with_anomaly_detection()
+            .sqrt()
+            .sum::<Rank0, _>()
+            .backward();
+    }
+
+    #[test]
+    fn test_anomaly_detection_is_per_tape() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 0.0, 3.0]);
+        let _ = x.trace_with_anomaly_detection().exp().sum::<Rank0, _>();
+        let grads = x.trace().sqrt().sum::<Rank0, _>().backward();
+        assert!(has_non_finite::<_, Rank1<3>, f32>(&grads, x.id()));
+    }
+
+    #[test]
+    fn test_output_is_recorded_explicitly() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        let y = x.trace_with_summary().backward_hook(|g| g).exp();
+        let (_, tape) = y.split_tape();
+        let summary = tape.summary();
+        // the hook only has an input, whose gradient it writes
+        assert_eq!(summary.ops[0].inputs().count(), 1);
+        assert_eq!(summary.ops[0].output(), None);
+        assert_eq!(summary.ops[1].inputs().next().unwrap().id, *x.id());
+        assert!(summary.ops[1].output().unwrap().is_output);
+    }
+}
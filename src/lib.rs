@@ -6,7 +6,8 @@
 //! 4. Ergonomic neural network building blocks (like `Linear`, `Conv2D`, and `Transformer`).
 //! 5. Standard deep learning optimizers such as `Sgd`, `Adam`, `AdamW`, `RMSprop`, and more.
 //! 6. Reverse mode auto differentiation implementation.
-//! 7. Serialization to/from `.npy` and `.npz` for transferring models to/from python.
+//! 7. Serialization to/from `.npy` and `.npz` for transferring models to/from python, and a native
+//!    binary format with optional zstd compression.
 //!
 //! # A quick tutorial
 //!
@@ -103,12 +104,19 @@
 extern crate alloc;
 extern crate no_std_compat as std;
 
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod ctc;
 pub mod data;
 pub mod feature_flags;
 pub mod gradients;
 pub mod losses;
+pub mod metrics;
 pub mod nn;
 pub mod optim;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod rl;
 pub mod shapes;
 pub mod tensor;
 pub mod tensor_ops;
@@ -206,6 +214,18 @@ pub(crate) mod tests {
         }
     }
 
+    impl<T: AssertClose> AssertClose for std::vec::Vec<T> {
+        fn get_far_pair(&self, rhs: &Self, tolerance: f32) -> Option<(f32, f32)> {
+            assert_eq!(self.len(), rhs.len());
+            for (l, r) in self.iter().zip(rhs.iter()) {
+                if let Some(pair) = l.get_far_pair(r, tolerance) {
+                    return Some(pair);
+                }
+            }
+            None
+        }
+    }
+
     pub fn assert_close<T: AssertClose + std::fmt::Debug>(a: &T, b: &T) {
         a.assert_close(b, TOLERANCE);
     }
@@ -1,6 +1,144 @@
 //! Standard loss functions such as [mse_loss()], [cross_entropy_with_logits_loss()], and more.
+//!
+//! Losses return the mean over all elements. The `*_with` versions of losses take a [Reduction]
+//! instead, which is one of [MeanReduction], [SumReduction], or [NoReduction] to get the
+//! loss of each element:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let pred = dev.tensor([1.0, 2.0, 3.0]);
+//! let targ = dev.tensor([1.0, 0.0, 3.5]);
+//! let loss: Tensor<Rank1<3>> = huber_loss_with(pred.clone(), targ.clone(), 1.0, NoReduction);
+//! assert_eq!(loss.array(), [0.0, 1.5, 0.125]);
+//! let loss: Tensor<Rank0> = huber_loss_with(pred, targ, 1.0, SumReduction);
+//! assert_eq!(loss.array(), 1.625);
+//! ```
+//!
+//! Losses over probability vectors, like [cross_entropy_with_logits_loss_with()], compute one loss
+//! per vector, so [NoReduction] gives a loss per sample.
+//!
+//! Per element weights can be applied with [Weighted], for example to scale samples by
+//! importance or to change the weighting over a curriculum. [WeightedMean] divides by the sum
+//! of the weights instead of the number of elements, which masks out padded tokens of a sequence:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let logits: Tensor<Rank3<2, 4, 10>> = dev.sample_normal();
+//! let targets: Tensor<Rank3<2, 4, 10>> = dev.sample_normal::<Rank3<2, 4, 10>>().softmax::<Axis<2>>();
+//! // the second sequence only has 2 tokens
+//! let mask = dev.tensor([[1.0; 4], [1.0, 1.0, 0.0, 0.0]]);
+//! let loss = cross_entropy_with_logits_loss_with(logits.traced(), targets, WeightedMean(mask));
+//! ```
+//!
+//! For output layers with a very large number of classes, [sampled_softmax_loss()] and [nce_loss()]
+//! only use the target classes and a sample of the other classes, see [crate::nn::SampledClasses].
 
-use crate::{gradients::Tape, shapes::*, tensor::Tensor, tensor_ops::*};
+use crate::{
+    gradients::{Merge, Tape},
+    nn::SampledClasses,
+    shapes::*,
+    tensor::Tensor,
+    tensor_ops::*,
+};
+
+/// How the loss of each element is reduced into the output of a loss function.
+/// See [MeanReduction], [SumReduction], and [NoReduction].
+pub trait Reduction<S: Shape, D: Device<f32>, T: Tape<D>> {
+    type Output;
+    fn reduce(self, losses: Tensor<S, f32, D, T>) -> Self::Output;
+}
+
+/// Averages the loss of all elements into a scalar. This is what the losses without a
+/// [Reduction] parameter do.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MeanReduction;
+
+/// Sums the loss of all elements into a scalar.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SumReduction;
+
+/// Keeps the loss of each element, with the same shape as the inputs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoReduction;
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Reduction<S, D, T> for MeanReduction {
+    type Output = Tensor<Rank0, f32, D, T>;
+    fn reduce(self, losses: Tensor<S, f32, D, T>) -> Self::Output {
+        losses.mean()
+    }
+}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Reduction<S, D, T> for SumReduction {
+    type Output = Tensor<Rank0, f32, D, T>;
+    fn reduce(self, losses: Tensor<S, f32, D, T>) -> Self::Output {
+        losses.sum()
+    }
+}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Reduction<S, D, T> for NoReduction {
+    type Output = Tensor<S, f32, D, T>;
+    fn reduce(self, losses: Tensor<S, f32, D, T>) -> Self::Output {
+        losses
+    }
+}
+
+/// Multiplies the loss of each element by `weights`, then reduces with `reduction`.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let pred = dev.tensor([1.0, 2.0, 3.0]);
+/// let targ = dev.zeros();
+/// let weights = dev.tensor([1.0, 0.5, 0.0]);
+/// let loss = mse_loss_with(pred, targ, Weighted::new(weights, SumReduction));
+/// assert_eq!(loss.array(), 3.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Weighted<S: Shape, D: Device<f32>, R> {
+    pub weights: Tensor<S, f32, D>,
+    pub reduction: R,
+}
+
+impl<S: Shape, D: Device<f32>, R> Weighted<S, D, R> {
+    pub fn new(weights: Tensor<S, f32, D>, reduction: R) -> Self {
+        Self { weights, reduction }
+    }
+}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D, T>> Reduction<S, D, T>
+    for Weighted<S, D, R>
+{
+    type Output = R::Output;
+    fn reduce(self, losses: Tensor<S, f32, D, T>) -> Self::Output {
+        self.reduction.reduce(losses * self.weights)
+    }
+}
+
+/// The weighted average of the loss of all elements: `(losses * weights).sum() / weights.sum()`.
+/// With a mask of `0`s and `1`s as weights, this is the mean over the elements where the mask is `1`.
+///
+/// The weights should be non-negative. If they are all `0` the loss is `0` instead of `NaN`.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let pred = dev.tensor([1.0, 2.0, 3.0]);
+/// let targ = dev.zeros();
+/// let mask = dev.tensor([1.0, 1.0, 0.0]);
+/// let loss = mse_loss_with(pred, targ, WeightedMean(mask));
+/// assert_eq!(loss.array(), 2.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedMean<S: Shape, D: Device<f32>>(pub Tensor<S, f32, D>);
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Reduction<S, D, T> for WeightedMean<S, D> {
+    type Output = Tensor<Rank0, f32, D, T>;
+    fn reduce(self, losses: Tensor<S, f32, D, T>) -> Self::Output {
+        // clamping keeps an all zero mask from dividing `0` by `0`
+        let total = self.0.clone().sum().clamp(f32::MIN_POSITIVE, f32::INFINITY);
+        (losses * self.0).sum() / total
+    }
+}
 
 /// [Mean Squared Error](https://en.wikipedia.org/wiki/Mean_squared_error).
 /// This computes `(pred - targ).square().mean()`.
@@ -10,13 +148,23 @@ pub fn mse_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
 ) -> Tensor<Rank0, f32, D, T> {
-    (pred - targ).square().mean()
+    mse_loss_with(pred, targ, MeanReduction)
+}
+
+/// [mse_loss()] with a [Reduction].
+pub fn mse_loss_with<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D, T>>(
+    pred: Tensor<S, f32, D, T>,
+    targ: Tensor<S, f32, D>,
+    reduction: R,
+) -> R::Output {
+    reduction.reduce((pred - targ).square())
 }
 
 /// [Root Mean square error](https://en.wikipedia.org/wiki/Root-mean-square_deviation).
 /// This computes `(pred - targ).square().mean().sqrt()`
 ///
-/// See [mse_loss()] and [sqrt()]
+/// See [mse_loss()] and [sqrt()]. Since the root is taken after the mean, there is no
+/// version of this with a [Reduction].
 pub fn rmse_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
@@ -32,7 +180,16 @@ pub fn mae_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
 ) -> Tensor<Rank0, f32, D, T> {
-    (pred - targ).abs().mean()
+    mae_loss_with(pred, targ, MeanReduction)
+}
+
+/// [mae_loss()] with a [Reduction].
+pub fn mae_loss_with<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D, T>>(
+    pred: Tensor<S, f32, D, T>,
+    targ: Tensor<S, f32, D>,
+    reduction: R,
+) -> R::Output {
+    reduction.reduce((pred - targ).abs())
 }
 
 /// [Huber Loss](https://en.wikipedia.org/wiki/Huber_loss)
@@ -54,7 +211,17 @@ pub fn huber_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
     targ: Tensor<S, f32, D>,
     delta: f32,
 ) -> Tensor<Rank0, f32, D, T> {
-    pred.huber_error(targ, delta).mean()
+    huber_loss_with(pred, targ, delta, MeanReduction)
+}
+
+/// [huber_loss()] with a [Reduction].
+pub fn huber_loss_with<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D, T>>(
+    pred: Tensor<S, f32, D, T>,
+    targ: Tensor<S, f32, D>,
+    delta: f32,
+    reduction: R,
+) -> R::Output {
+    reduction.reduce(pred.huber_error(targ, delta))
 }
 
 /// Smooth l1 loss (closely related to [Huber Loss](https://en.wikipedia.org/wiki/Huber_loss))
@@ -78,7 +245,55 @@ pub fn smooth_l1_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
     targ: Tensor<S, f32, D>,
     delta: f32,
 ) -> Tensor<Rank0, f32, D, T> {
-    huber_loss(pred, targ, delta) / delta
+    smooth_l1_loss_with(pred, targ, delta, MeanReduction)
+}
+
+/// [smooth_l1_loss()] with a [Reduction].
+pub fn smooth_l1_loss_with<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D, T>>(
+    pred: Tensor<S, f32, D, T>,
+    targ: Tensor<S, f32, D>,
+    delta: f32,
+    reduction: R,
+) -> R::Output {
+    reduction.reduce(pred.huber_error(targ, delta) / delta)
+}
+
+/// [Quantile loss](https://en.wikipedia.org/wiki/Quantile_regression) (also known as pinball loss),
+/// which is minimized when `pred` is the `quantile` of the distribution of `targ`.
+///
+/// It computes, with `e = targ - pred`:
+/// 1. if `e >= 0`: `quantile * e`
+/// 2. otherwise: `(quantile - 1) * e`
+///
+/// `quantile = 0.5` is half of [mae_loss()].
+///
+/// # Example
+/// ```rust
+/// # use dfdx::{prelude::*};
+/// # let dev: Cpu = Default::default();
+/// let pred = dev.tensor([1.0, 1.0]);
+/// let targ = dev.tensor([2.0, 0.0]);
+/// let loss = quantile_loss(pred.traced(), targ, 0.9);
+/// assert_eq!(loss.array(), 0.5);
+/// ```
+pub fn quantile_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+    pred: Tensor<S, f32, D, T>,
+    targ: Tensor<S, f32, D>,
+    quantile: f32,
+) -> Tensor<Rank0, f32, D, T> {
+    quantile_loss_with(pred, targ, quantile, MeanReduction)
+}
+
+/// [quantile_loss()] with a [Reduction].
+pub fn quantile_loss_with<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D, T>>(
+    pred: Tensor<S, f32, D, T>,
+    targ: Tensor<S, f32, D>,
+    quantile: f32,
+    reduction: R,
+) -> R::Output {
+    let err = pred.negate() + targ;
+    let under = err.retaped::<T>() * (quantile - 1.0);
+    reduction.reduce((err * quantile).maximum(under))
 }
 
 /// [Cross entropy loss](https://en.wikipedia.org/wiki/Cross_entropy#Cross-entropy_loss_function_and_logistic_regression).
@@ -111,6 +326,21 @@ where
     (logits.log_softmax::<Ax>() * target_probs).mean().negate() * last_axis_numel
 }
 
+/// [cross_entropy_with_logits_loss()] with a [Reduction] over the loss of each probability vector,
+/// so the losses have the shape of `logits` without the last axis.
+pub fn cross_entropy_with_logits_loss_with<Ax: Axes, S, D: Device<f32>, T: Tape<D>, R>(
+    logits: Tensor<S, f32, D, T>,
+    target_probs: Tensor<S, f32, D>,
+    reduction: R,
+) -> R::Output
+where
+    S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+    R: Reduction<S::Reduced, D, T>,
+{
+    let losses = (logits.log_softmax::<Ax>() * target_probs).sum::<_, Ax>();
+    reduction.reduce(losses.negate())
+}
+
 /// [KL Divergence loss](https://en.wikipedia.org/wiki/Kullback%E2%80%93Leibler_divergence).
 /// This computes `(target_probs * (target_probs.log() - logits.log_softmax())).sum(-1).mean()`
 ///
@@ -145,6 +375,22 @@ where
         * last_axis_numel
 }
 
+/// [kl_div_with_logits_loss()] with a [Reduction] over the loss of each probability vector,
+/// so the losses have the shape of `logits` without the last axis.
+pub fn kl_div_with_logits_loss_with<Ax: Axes, S, D: Device<f32>, T: Tape<D>, R>(
+    logits: Tensor<S, f32, D, T>,
+    target_probs: Tensor<S, f32, D>,
+    reduction: R,
+) -> R::Output
+where
+    S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+    R: Reduction<S::Reduced, D, T>,
+{
+    let probs = logits.log_softmax::<Ax>();
+    let losses = ((probs - target_probs.clone().ln()) * target_probs).sum::<_, Ax>();
+    reduction.reduce(losses.negate())
+}
+
 /// [Binary Cross Entropy](https://en.wikipedia.org/wiki/Cross_entropy#Cross-entropy_loss_function_and_logistic_regression)
 /// With Logits in numerically stable way.
 ///
@@ -166,7 +412,215 @@ pub fn binary_cross_entropy_with_logits_loss<S: Shape, D: Device<f32>, T: Tape<D
     logits: Tensor<S, f32, D, T>,
     target_probs: Tensor<S, f32, D>,
 ) -> Tensor<Rank0, f32, D, T> {
-    logits.bce_with_logits(target_probs).mean()
+    binary_cross_entropy_with_logits_loss_with(logits, target_probs, MeanReduction)
+}
+
+/// [binary_cross_entropy_with_logits_loss()] with a [Reduction].
+pub fn binary_cross_entropy_with_logits_loss_with<
+    S: Shape,
+    D: Device<f32>,
+    T: Tape<D>,
+    R: Reduction<S, D, T>,
+>(
+    logits: Tensor<S, f32, D, T>,
+    target_probs: Tensor<S, f32, D>,
+    reduction: R,
+) -> R::Output {
+    reduction.reduce(logits.bce_with_logits(target_probs))
+}
+
+/// [binary_cross_entropy_with_logits_loss()] where the loss of positive targets is scaled by
+/// `pos_weight`, to balance classes with few positive examples. A `pos_weight` of
+/// `num_negatives / num_positives` weighs both equally.
+///
+/// It computes `-(pos_weight * target * ln(sigmoid(logit)) + (1 - target) * ln(1 - sigmoid(logit)))`,
+/// using [softplus()] for the logs so it is stable for large logits.
+///
+/// # Example
+/// ```rust
+/// # use dfdx::{prelude::*};
+/// # let dev: Cpu = Default::default();
+/// let logits: Tensor<Rank2<4, 2>> = dev.sample_normal();
+/// let targets = dev.tensor([[1.0, 0.0], [0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+/// // the second class has 3x fewer positive examples
+/// let pos_weight = dev.tensor([1.0, 3.0]).broadcast();
+/// let loss = binary_cross_entropy_with_logits_pos_weight_loss(logits.traced(), targets, pos_weight);
+/// ```
+pub fn binary_cross_entropy_with_logits_pos_weight_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+    logits: Tensor<S, f32, D, T>,
+    target_probs: Tensor<S, f32, D>,
+    pos_weight: Tensor<S, f32, D>,
+) -> Tensor<Rank0, f32, D, T> {
+    binary_cross_entropy_with_logits_pos_weight_loss_with(
+        logits,
+        target_probs,
+        pos_weight,
+        MeanReduction,
+    )
+}
+
+/// [binary_cross_entropy_with_logits_pos_weight_loss()] with a [Reduction].
+pub fn binary_cross_entropy_with_logits_pos_weight_loss_with<
+    S: Shape,
+    D: Device<f32>,
+    T: Tape<D>,
+    R: Reduction<S, D, T>,
+>(
+    logits: Tensor<S, f32, D, T>,
+    target_probs: Tensor<S, f32, D>,
+    pos_weight: Tensor<S, f32, D>,
+    reduction: R,
+) -> R::Output {
+    // -ln(sigmoid(x)) = softplus(-x), and -ln(1 - sigmoid(x)) = x + softplus(-x)
+    let log_weight = (pos_weight - 1.0) * target_probs.clone() + 1.0;
+    let neg_log_sigmoid = logits.retaped::<T>().negate().softplus(1.0, 20.0);
+    let losses = logits * (target_probs.negate() + 1.0) + neg_log_sigmoid * log_weight;
+    reduction.reduce(losses)
+}
+
+/// Multi label soft margin loss, which is the [binary_cross_entropy_with_logits_loss()] of
+/// each label, averaged over the labels (the last axis) of each sample.
+/// Each sample can belong to any number of classes.
+///
+/// This is the same as [binary_cross_entropy_with_logits_loss()] when the mean is taken, and
+/// differs in the shape of the losses for other [Reduction]s.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.multilabel_soft_margin_loss(logits, targets)`
+///
+/// # Example
+/// ```rust
+/// # use dfdx::{prelude::*};
+/// # let dev: Cpu = Default::default();
+/// let logits: Tensor<Rank2<2, 3>> = dev.sample_normal();
+/// let labels = dev.tensor([[1.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+/// let loss = multilabel_soft_margin_loss(logits.traced(), labels);
+/// ```
+pub fn multilabel_soft_margin_loss<Ax: Axes, S, D: Device<f32>, T: Tape<D>>(
+    logits: Tensor<S, f32, D, T>,
+    targets: Tensor<S, f32, D>,
+) -> Tensor<Rank0, f32, D, T>
+where
+    S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+{
+    multilabel_soft_margin_loss_with(logits, targets, MeanReduction)
+}
+
+/// [multilabel_soft_margin_loss()] with a [Reduction] over the loss of each sample,
+/// so the losses have the shape of `logits` without the last axis.
+pub fn multilabel_soft_margin_loss_with<Ax: Axes, S, D: Device<f32>, T: Tape<D>, R>(
+    logits: Tensor<S, f32, D, T>,
+    targets: Tensor<S, f32, D>,
+    reduction: R,
+) -> R::Output
+where
+    S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+    R: Reduction<S::Reduced, D, T>,
+{
+    reduction.reduce(logits.bce_with_logits(targets).mean::<_, Ax>())
+}
+
+/// [Sampled softmax](https://arxiv.org/abs/1412.2007) loss of a [crate::nn::Linear] output layer
+/// with a large number of classes. This approximates
+/// `cross_entropy_with_logits_loss(layer.forward(hidden), one_hot(targets))` with the logits of the
+/// target class and of the negative classes sampled by [crate::nn::Linear::sample_classes()]
+/// only, so the cost doesn't depend on the number of classes.
+///
+/// The logits are corrected by the log of the expected number of times each class is sampled,
+/// and sampled classes that are the target class of a sample are ignored for that sample.
+///
+/// # Arguments
+///
+/// - `hidden`: The input of the output layer, with shape `(B, DIM)`.
+/// - `classes`: The rows of the output layer for the targets and the sampled classes.
+///
+/// See [crate::nn::SampledClasses] for an example.
+pub fn sampled_softmax_loss<B: Dim, const DIM: usize, D: Device<f32>, T>(
+    hidden: Tensor<(B, Const<DIM>), f32, D, T>,
+    classes: &SampledClasses<B, DIM, D>,
+) -> Tensor<Rank0, f32, D, T>
+where
+    T: Tape<D> + Merge<T>,
+{
+    sampled_softmax_loss_with(hidden, classes, MeanReduction)
+}
+
+/// [sampled_softmax_loss()] with a [Reduction] over the loss of each sample.
+pub fn sampled_softmax_loss_with<B: Dim, const DIM: usize, D: Device<f32>, T, R>(
+    hidden: Tensor<(B, Const<DIM>), f32, D, T>,
+    classes: &SampledClasses<B, DIM, D>,
+    reduction: R,
+) -> R::Output
+where
+    T: Tape<D> + Merge<T>,
+    R: Reduction<(B,), D, T>,
+{
+    let (target, sampled) = sampled_logits(hidden, classes);
+    // -log(softmax) of the target = ln(exp(target) + sum(exp(sampled))) - target
+    let losses = sampled.logsumexp::<(B,), Axis<1>>() - target;
+    reduction.reduce(losses.softplus(1.0, 20.0))
+}
+
+/// [Noise contrastive estimation](https://www.jmlr.org/papers/v13/gutmann12a.html) loss of a
+/// [crate::nn::Linear] output layer with a large number of classes: binary cross entropy that
+/// classifies the target class of each sample as positive, and the negative classes sampled by
+/// [crate::nn::Linear::sample_classes()] as negative. The logits are corrected like in
+/// [sampled_softmax_loss()].
+///
+/// The loss of each sample is the sum over its target and the sampled classes.
+pub fn nce_loss<B: Dim, const DIM: usize, D: Device<f32>, T>(
+    hidden: Tensor<(B, Const<DIM>), f32, D, T>,
+    classes: &SampledClasses<B, DIM, D>,
+) -> Tensor<Rank0, f32, D, T>
+where
+    T: Tape<D> + Merge<T>,
+{
+    nce_loss_with(hidden, classes, MeanReduction)
+}
+
+/// [nce_loss()] with a [Reduction] over the loss of each sample.
+pub fn nce_loss_with<B: Dim, const DIM: usize, D: Device<f32>, T, R>(
+    hidden: Tensor<(B, Const<DIM>), f32, D, T>,
+    classes: &SampledClasses<B, DIM, D>,
+    reduction: R,
+) -> R::Output
+where
+    T: Tape<D> + Merge<T>,
+    R: Reduction<(B,), D, T>,
+{
+    let (target, sampled) = sampled_logits(hidden, classes);
+    // -ln(sigmoid(x)) = softplus(-x) and -ln(1 - sigmoid(x)) = softplus(x)
+    let negatives = sampled.softplus(1.0, 20.0).sum::<(B,), Axis<1>>();
+    reduction.reduce(target.negate().softplus(1.0, 20.0) + negatives)
+}
+
+/// The corrected logits of the target class `(B,)` and of the sampled classes `(B, num_sampled)`.
+#[allow(clippy::type_complexity)]
+fn sampled_logits<B: Dim, const DIM: usize, D: Device<f32>, T>(
+    hidden: Tensor<(B, Const<DIM>), f32, D, T>,
+    classes: &SampledClasses<B, DIM, D>,
+) -> (Tensor<(B,), f32, D, T>, Tensor<(B, usize), f32, D, T>)
+where
+    T: Tape<D> + Merge<T>,
+{
+    let shape = (hidden.shape().0, classes.num_sampled());
+    let target = (hidden.retaped::<T>() * classes.target_weight.clone()).sum::<(B,), Axis<1>>()
+        + classes.target_bias.clone()
+        - classes.target_log_q.clone();
+
+    let sampled_weight = classes
+        .sampled_weight
+        .retaped::<T>()
+        .permute::<_, Axes2<1, 0>>();
+    let sampled_bias = classes.sampled_bias.retaped::<T>();
+    let sampled = hidden.matmul(sampled_weight)
+        + sampled_bias.broadcast_like::<_, Axis<0>>(&shape)
+        - classes
+            .sampled_log_q
+            .clone()
+            .broadcast_like::<_, Axis<0>>(&shape)
+        // removes accidental hits
+        - classes.hits.clone() * 1e9;
+    (target, sampled)
 }
 
 #[cfg(test)]
@@ -342,6 +796,88 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bce_pos_weight() {
+        let dev: TestDevice = Default::default();
+        let logit: Tensor<Rank2<3, 2>, f32, _> = dev.sample_normal();
+        let targ = dev.tensor([[1.0, 0.0], [0.25, 1.0], [0.0, 0.5]]);
+
+        // pos_weight of 1 is plain bce
+        let ones: Tensor<Rank2<3, 2>, f32, _> = dev.ones();
+        let loss =
+            binary_cross_entropy_with_logits_pos_weight_loss(logit.trace(), targ.clone(), ones);
+        let expected = binary_cross_entropy_with_logits_loss(logit.trace(), targ.clone());
+        assert_close(&loss.array(), &expected.array());
+        let g = loss.backward();
+        let g_expected = expected.backward();
+        assert_close(&g.get(&logit).array(), &g_expected.get(&logit).array());
+
+        // against the naive formula
+        let pw = dev.tensor([2.0, 0.5]).broadcast();
+        let loss = binary_cross_entropy_with_logits_pos_weight_loss_with(
+            logit.trace(),
+            targ.clone(),
+            pw.clone(),
+            NoReduction,
+        );
+        let p = logit.clone().sigmoid();
+        let naive = ((pw * targ.clone() * p.clone().ln())
+            + (targ.clone().negate() + 1.0) * (p.negate() + 1.0).ln())
+        .negate();
+        assert_close(&loss.array(), &naive.array());
+
+        // the derivative is `(1 + (pw - 1) * y) * sigmoid(x) - pw * y`
+        let g = loss.sum().backward();
+        let s = logit.clone().sigmoid().array();
+        let y = targ.array();
+        let pw = [2.0, 0.5];
+        let mut expected = [[0.0; 2]; 3];
+        for i in 0..3 {
+            for j in 0..2 {
+                expected[i][j] = (1.0 + (pw[j] - 1.0) * y[i][j]) * s[i][j] - pw[j] * y[i][j];
+            }
+        }
+        assert_close(&g.get(&logit).array(), &expected);
+    }
+
+    #[test]
+    fn test_bce_pos_weight_large_logits() {
+        let dev: TestDevice = Default::default();
+        let logit = dev.tensor([100.0, -100.0, 100.0, -100.0]);
+        let targ = dev.tensor([1.0, 0.0, 0.0, 1.0]);
+        let pw = dev.tensor([3.0; 4]);
+        let loss = binary_cross_entropy_with_logits_pos_weight_loss_with(
+            logit.trace(),
+            targ,
+            pw,
+            NoReduction,
+        );
+        assert_close(&loss.array(), &[0.0, 0.0, 100.0, 300.0]);
+        let g = loss.sum().backward();
+        assert_close(&g.get(&logit).array(), &[0.0, 0.0, 1.0, -3.0]);
+    }
+
+    #[test]
+    fn test_multilabel_soft_margin() {
+        let dev: TestDevice = Default::default();
+        let logit: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let targ = dev.tensor([[1.0, 0.0, 1.0], [0.0, 0.0, 1.0]]);
+        let loss = multilabel_soft_margin_loss(logit.trace(), targ.clone());
+        let expected = binary_cross_entropy_with_logits_loss(logit.trace(), targ.clone());
+        assert_close(&loss.array(), &expected.array());
+
+        let per_sample: Tensor<Rank1<2>, f32, _> =
+            multilabel_soft_margin_loss_with(logit.clone(), targ.clone(), NoReduction);
+        let bce = logit.bce_with_logits(targ).array();
+        assert_close(
+            &per_sample.array(),
+            &[
+                bce[0].iter().sum::<f32>() / 3.0,
+                bce[1].iter().sum::<f32>() / 3.0,
+            ],
+        );
+    }
+
     #[test]
     fn test_huber_loss() {
         let dev: TestDevice = Default::default();
@@ -378,6 +914,124 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reductions_match_mean() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let y: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let p = y.clone().softmax::<Axis<1>>();
+
+        let mse = mse_loss_with(x.trace(), y.clone(), NoReduction);
+        assert_eq!(mse.array(), (x.clone() - y.clone()).square().array());
+        assert_close(
+            &mse.mean::<Rank0, _>().array(),
+            &mse_loss(x.clone(), y.clone()).array(),
+        );
+
+        let mae = mae_loss_with(x.clone(), y.clone(), SumReduction);
+        assert_close(
+            &mae.array(),
+            &(mae_loss(x.clone(), y.clone()).array() * 12.0),
+        );
+
+        let ce: Tensor<Rank1<4>, f32, _> =
+            cross_entropy_with_logits_loss_with(x.clone(), p.clone(), NoReduction);
+        assert_close(
+            &ce.mean::<Rank0, _>().array(),
+            &cross_entropy_with_logits_loss(x.clone(), p.clone()).array(),
+        );
+
+        let kl: Tensor<Rank1<4>, f32, _> =
+            kl_div_with_logits_loss_with(x.clone(), p.clone(), NoReduction);
+        assert_close(
+            &kl.mean::<Rank0, _>().array(),
+            &kl_div_with_logits_loss(x.clone(), p.clone()).array(),
+        );
+
+        let bce = binary_cross_entropy_with_logits_loss_with(x.clone(), p.clone(), SumReduction);
+        assert_close(
+            &bce.array(),
+            &(binary_cross_entropy_with_logits_loss(x, p).array() * 12.0),
+        );
+    }
+
+    #[test]
+    fn test_weighted_reductions() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[1.0, -1.0, 2.0], [0.5, 0.0, -0.5]]);
+        let y = dev.tensor([[0.5, 0.3, 0.2], [0.1, 0.1, 0.8]]);
+        let w = dev.tensor([2.0, 0.0]);
+
+        let per_sample: Tensor<Rank1<2>, f32, _> =
+            cross_entropy_with_logits_loss_with(x.clone(), y.clone(), NoReduction);
+        let per_sample = per_sample.array();
+
+        let loss = cross_entropy_with_logits_loss_with(
+            x.trace(),
+            y.clone(),
+            Weighted::new(w.clone(), MeanReduction),
+        );
+        assert_close(&loss.array(), &per_sample[0]);
+        let g = loss.backward();
+        assert_eq!(g.get(&x).array()[1], [0.0; 3]);
+
+        let loss = cross_entropy_with_logits_loss_with(x.trace(), y.clone(), WeightedMean(w));
+        assert_close(&loss.array(), &per_sample[0]);
+        let g = loss.backward();
+        let g_unweighted = cross_entropy_with_logits_loss_with(x.trace(), y, NoReduction)
+            .select(dev.tensor(0))
+            .backward();
+        assert_close(&g.get(&x).array(), &g_unweighted.get(&x).array());
+    }
+
+    #[test]
+    fn test_weighted_mean_all_zero_weights() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let loss = mse_loss_with(x.trace(), dev.zeros(), WeightedMean(dev.zeros()));
+        assert_eq!(loss.array(), 0.0);
+        let g = loss.backward();
+        assert_eq!(g.get(&x).array(), [0.0; 3]);
+    }
+
+    #[test]
+    fn test_huber_loss_reductions() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let y: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let none = huber_loss_with(x.trace(), y.clone(), 0.5, NoReduction);
+        assert_eq!(none.array(), x.clone().huber_error(y.clone(), 0.5).array());
+        let sum = huber_loss_with(x.trace(), y.clone(), 0.5, SumReduction);
+        assert_close(
+            &sum.array(),
+            &(huber_loss(x.trace(), y.clone(), 0.5).array() * 6.0),
+        );
+
+        let g = none.sum().backward();
+        let g_sum = sum.backward();
+        assert_close(&g.get(&x).array(), &g_sum.get(&x).array());
+
+        let smooth = smooth_l1_loss_with(x.trace(), y.clone(), 0.5, NoReduction);
+        assert_close(&smooth.array(), &(x.huber_error(y, 0.5) / 0.5).array());
+    }
+
+    #[test]
+    fn test_quantile_loss() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[0.0, 1.0, 2.0], [-1.0, 3.0, 0.5]]);
+        let y = dev.tensor([[1.0, 1.5, 0.0], [1.0, 2.0, 0.0]]);
+        let loss = quantile_loss_with(x.trace(), y.clone(), 0.25, NoReduction);
+        assert_close(&loss.array(), &[[0.25, 0.125, 1.5], [0.5, 0.75, 0.375]]);
+        let g = loss.mean().backward();
+        let q = 0.25 / 6.0;
+        let p = 0.75 / 6.0;
+        assert_close(&g.get(&x).array(), &[[-q, -q, p], [-q, p, p]]);
+
+        // median is half of the mean absolute error
+        let loss = quantile_loss(x.trace(), y.clone(), 0.5);
+        assert_close(&loss.array(), &(mae_loss(x, y).array() / 2.0));
+    }
+
     #[test]
     fn test_smooth_l1_loss() {
         let dev: TestDevice = Default::default();
@@ -413,4 +1067,83 @@ mod tests {
             ]
         );
     }
+
+    /// The logits of the target class and of the sampled classes of each sample of
+    /// [sampled_softmax_loss()], computed on the host, with the rows used for each.
+    fn host_sampled_logits(
+        h: [[f32; 3]; 2],
+        layer: &crate::nn::Linear<3, 20, TestDevice>,
+        classes: &SampledClasses<Const<2>, 3, TestDevice>,
+    ) -> std::vec::Vec<std::vec::Vec<(f32, usize)>> {
+        let (w, bias) = (layer.weight.array(), layer.bias.array());
+        let log_q = |c: usize| {
+            let p = ((c as f32 + 2.0) / (c as f32 + 1.0)).ln() / 21f32.ln();
+            (classes.num_sampled() as f32 * p).ln()
+        };
+        let logit = |i: usize, c: usize| {
+            let dot: f32 = h[i].iter().zip(w[c].iter()).map(|(a, b)| a * b).sum();
+            dot + bias[c] - log_q(c)
+        };
+        let targets = classes.targets.array();
+        (0..2)
+            .map(|i| {
+                let mut logits = alloc::vec![(logit(i, targets[i]), targets[i])];
+                for c in classes.sampled.as_vec() {
+                    if c != targets[i] {
+                        logits.push((logit(i, c), c));
+                    }
+                }
+                logits
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sampled_softmax_loss() {
+        let dev: TestDevice = Default::default();
+        let layer: crate::nn::Linear<3, 20, _> = crate::nn::ModuleBuilder::build_module(&dev);
+        let h = [[0.5, -1.0, 0.3], [-0.2, 0.8, 1.1]];
+        let hidden: Tensor<Rank2<2, 3>, f32, _> = dev.tensor(h);
+        let classes = layer.sample_classes(dev.tensor([0, 2]), 8);
+
+        let loss = sampled_softmax_loss(hidden.trace(), &classes);
+        let loss_value = loss.array();
+        let g = loss.backward();
+
+        let w = layer.weight.array();
+        let mut expected_loss = 0.0;
+        let mut expected_grad = [[0.0; 3]; 2];
+        for (i, logits) in host_sampled_logits(h, &layer, &classes).iter().enumerate() {
+            let total: f32 = logits.iter().map(|(l, _)| l.exp()).sum();
+            expected_loss += (total.ln() - logits[0].0) / 2.0;
+            for (j, &(l, c)) in logits.iter().enumerate() {
+                let p = l.exp() / total - if j == 0 { 1.0 } else { 0.0 };
+                for k in 0..3 {
+                    expected_grad[i][k] += p * w[c][k] / 2.0;
+                }
+            }
+        }
+        assert_close(&loss_value, &expected_loss);
+        assert_close(&g.get(&hidden).array(), &expected_grad);
+        assert_eq!(g.get(&classes.sampled_weight).shape(), &(8, Const));
+    }
+
+    #[test]
+    fn test_nce_loss() {
+        let dev: TestDevice = Default::default();
+        let layer: crate::nn::Linear<3, 20, _> = crate::nn::ModuleBuilder::build_module(&dev);
+        let h = [[0.1, 0.4, -0.7], [1.2, -0.3, 0.6]];
+        let hidden: Tensor<Rank2<2, 3>, f32, _> = dev.tensor(h);
+        let classes = layer.sample_classes(dev.tensor([5, 1]), 6);
+
+        let loss = nce_loss_with(hidden.trace(), &classes, NoReduction);
+        let softplus = |x: f32| x.exp().ln_1p();
+        let expected: std::vec::Vec<f32> = host_sampled_logits(h, &layer, &classes)
+            .iter()
+            .map(|logits| {
+                softplus(-logits[0].0) + logits[1..].iter().map(|&(l, _)| softplus(l)).sum::<f32>()
+            })
+            .collect();
+        assert_close_with_tolerance(&loss.array().to_vec(), &expected, 1e-5);
+    }
 }
@@ -0,0 +1,448 @@
+//! Evaluation metrics such as [accuracy()], [ConfusionMatrix], [roc_auc()], and [perplexity()].
+//!
+//! Metrics are computed from tensors without a tape, and return plain `f32`s. Each metric also
+//! has an accumulating version ([Accuracy], [ConfusionMatrix], [RocAuc], [Perplexity]) that can be
+//! updated with every batch of an epoch:
+//! ```rust
+//! # use dfdx::{prelude::*, metrics::Accuracy};
+//! # let dev: Cpu = Default::default();
+//! let mut acc = Accuracy::default();
+//! for _ in 0..3 {
+//!     let logits: Tensor<Rank2<4, 10>> = dev.sample_normal();
+//!     let labels = dev.tensor([0, 1, 2, 3]);
+//!     acc.update(&logits, &labels);
+//! }
+//! assert_eq!(acc.total, 12);
+//! println!("accuracy: {}", acc.compute());
+//! ```
+//!
+//! Binary metrics treat targets greater than `0.5` as positive, and scores greater than
+//! a threshold as predicted positive.
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use std::vec::Vec;
+
+use crate::{
+    shapes::{Dim, HasShape, Shape},
+    tensor::{AsVec, DeviceStorage, Tensor},
+};
+
+/// The index of the largest value of each row of a `(batch, classes)` matrix.
+fn argmax_rows(data: &[f32], num_classes: usize) -> impl Iterator<Item = usize> + '_ {
+    data.chunks(num_classes.max(1)).map(|row| {
+        let mut best = 0;
+        for (i, v) in row.iter().enumerate() {
+            if *v > row[best] {
+                best = i;
+            }
+        }
+        best
+    })
+}
+
+/// The fraction of samples where the largest logit is the one of the label.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::accuracy};
+/// # let dev: Cpu = Default::default();
+/// let logits = dev.tensor([[0.1, 0.9], [0.8, 0.2], [0.3, 0.7], [0.6, 0.4]]);
+/// let labels = dev.tensor([1, 0, 0, 0]);
+/// assert_eq!(accuracy(&logits, &labels), 0.75);
+/// ```
+pub fn accuracy<B: Dim, C: Dim, D: DeviceStorage>(
+    logits: &Tensor<(B, C), f32, D>,
+    labels: &Tensor<(B,), usize, D>,
+) -> f32
+where
+    D::Storage<(B, C), f32>: AsVec<Unit = f32>,
+    D::Storage<(B,), usize>: AsVec<Unit = usize>,
+{
+    let mut acc = Accuracy::default();
+    acc.update(logits, labels);
+    acc.compute()
+}
+
+/// Accumulates the number of correct predictions over multiple batches. See [accuracy()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Accuracy {
+    pub correct: usize,
+    pub total: usize,
+}
+
+impl Accuracy {
+    /// Adds the predictions of a batch.
+    pub fn update<B: Dim, C: Dim, D: DeviceStorage>(
+        &mut self,
+        logits: &Tensor<(B, C), f32, D>,
+        labels: &Tensor<(B,), usize, D>,
+    ) where
+        D::Storage<(B, C), f32>: AsVec<Unit = f32>,
+        D::Storage<(B,), usize>: AsVec<Unit = usize>,
+    {
+        let (batch, classes) = *logits.shape();
+        assert_eq!(batch.size(), labels.shape().0.size());
+        let logits = logits.as_vec();
+        let labels = labels.as_vec();
+        self.correct += argmax_rows(&logits, classes.size())
+            .zip(labels)
+            .filter(|(pred, label)| pred == label)
+            .count();
+        self.total += batch.size();
+    }
+
+    /// The fraction of correct predictions so far, or `NaN` if there were none.
+    pub fn compute(&self) -> f32 {
+        self.correct as f32 / self.total as f32
+    }
+
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+/// Counts of binary predictions, from which precision, recall, and F1 are computed.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::ConfusionMatrix};
+/// # let dev: Cpu = Default::default();
+/// let probs = dev.tensor([0.9, 0.8, 0.2, 0.6, 0.1]);
+/// let targets = dev.tensor([1.0, 0.0, 1.0, 1.0, 0.0]);
+/// let cm = ConfusionMatrix::from_scores(&probs, &targets, 0.5);
+/// assert_eq!(cm.true_positives, 2);
+/// assert_eq!(cm.precision(), 2.0 / 3.0);
+/// assert_eq!(cm.recall(), 2.0 / 3.0);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConfusionMatrix {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub true_negatives: usize,
+    pub false_negatives: usize,
+}
+
+impl ConfusionMatrix {
+    /// Counts the predictions of `scores` against `targets`, where a score is a positive
+    /// prediction if it is greater than `threshold`.
+    pub fn from_scores<S: Shape, D: DeviceStorage>(
+        scores: &Tensor<S, f32, D>,
+        targets: &Tensor<S, f32, D>,
+        threshold: f32,
+    ) -> Self
+    where
+        D::Storage<S, f32>: AsVec<Unit = f32>,
+    {
+        let mut cm = Self::default();
+        cm.update(scores, targets, threshold);
+        cm
+    }
+
+    /// Adds the predictions of a batch. See [ConfusionMatrix::from_scores()].
+    pub fn update<S: Shape, D: DeviceStorage>(
+        &mut self,
+        scores: &Tensor<S, f32, D>,
+        targets: &Tensor<S, f32, D>,
+        threshold: f32,
+    ) where
+        D::Storage<S, f32>: AsVec<Unit = f32>,
+    {
+        for (s, t) in scores.as_vec().into_iter().zip(targets.as_vec()) {
+            match (s > threshold, t > 0.5) {
+                (true, true) => self.true_positives += 1,
+                (true, false) => self.false_positives += 1,
+                (false, false) => self.true_negatives += 1,
+                (false, true) => self.false_negatives += 1,
+            }
+        }
+    }
+
+    /// `tp / (tp + fp)`, or `0` if nothing was predicted positive.
+    pub fn precision(&self) -> f32 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    /// `tp / (tp + fn)`, or `0` if there are no positive targets.
+    pub fn recall(&self) -> f32 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+        )
+    }
+
+    /// The harmonic mean of [ConfusionMatrix::precision()] and [ConfusionMatrix::recall()].
+    pub fn f1(&self) -> f32 {
+        ratio(
+            2 * self.true_positives,
+            2 * self.true_positives + self.false_positives + self.false_negatives,
+        )
+    }
+
+    /// `(tp + tn) / total`
+    pub fn accuracy(&self) -> f32 {
+        ratio(
+            self.true_positives + self.true_negatives,
+            self.true_positives + self.true_negatives + self.false_positives + self.false_negatives,
+        )
+    }
+
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+fn ratio(num: usize, den: usize) -> f32 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f32 / den as f32
+    }
+}
+
+/// The area under the [ROC curve](https://en.wikipedia.org/wiki/Receiver_operating_characteristic),
+/// which is the probability that a random positive is scored higher than a random negative.
+/// Ties count as half. Returns `NaN` if there are no positive or no negative targets.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::roc_auc};
+/// # let dev: Cpu = Default::default();
+/// let scores = dev.tensor([0.1, 0.4, 0.35, 0.8]);
+/// let targets = dev.tensor([0.0, 0.0, 1.0, 1.0]);
+/// assert_eq!(roc_auc(&scores, &targets), 0.75);
+/// ```
+pub fn roc_auc<S: Shape, D: DeviceStorage>(
+    scores: &Tensor<S, f32, D>,
+    targets: &Tensor<S, f32, D>,
+) -> f32
+where
+    D::Storage<S, f32>: AsVec<Unit = f32>,
+{
+    let mut auc = RocAuc::default();
+    auc.update(scores, targets);
+    auc.compute()
+}
+
+/// Collects scores & targets over multiple batches. See [roc_auc()].
+#[derive(Debug, Default, Clone)]
+pub struct RocAuc {
+    scores: Vec<(f32, bool)>,
+}
+
+impl RocAuc {
+    /// Adds the scores of a batch.
+    pub fn update<S: Shape, D: DeviceStorage>(
+        &mut self,
+        scores: &Tensor<S, f32, D>,
+        targets: &Tensor<S, f32, D>,
+    ) where
+        D::Storage<S, f32>: AsVec<Unit = f32>,
+    {
+        let targets = targets.as_vec();
+        self.scores.extend(
+            scores
+                .as_vec()
+                .into_iter()
+                .zip(targets)
+                .map(|(s, t)| (s, t > 0.5)),
+        );
+    }
+
+    /// Computes the AUC with the rank statistic of the positives, giving tied scores
+    /// their average rank.
+    pub fn compute(&self) -> f32 {
+        let mut scores = self.scores.clone();
+        scores.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let num_pos = scores.iter().filter(|(_, t)| *t).count();
+        let num_neg = scores.len() - num_pos;
+        if num_pos == 0 || num_neg == 0 {
+            return f32::NAN;
+        }
+
+        let mut pos_rank_sum = 0.0f64;
+        let mut i = 0;
+        while i < scores.len() {
+            let mut j = i;
+            while j < scores.len() && scores[j].0 == scores[i].0 {
+                j += 1;
+            }
+            // ranks are 1 based, so the tied entries i..j share rank (i + 1 + j) / 2
+            let rank = (i + 1 + j) as f64 / 2.0;
+            let tied_pos = scores[i..j].iter().filter(|(_, t)| *t).count();
+            pos_rank_sum += rank * tied_pos as f64;
+            i = j;
+        }
+
+        let (p, n) = (num_pos as f64, num_neg as f64);
+        ((pos_rank_sum - p * (p + 1.0) / 2.0) / (p * n)) as f32
+    }
+
+    pub fn reset(&mut self) {
+        self.scores.clear();
+    }
+}
+
+/// The [perplexity](https://en.wikipedia.org/wiki/Perplexity) of `labels` under the
+/// softmax of `logits`, which is the exponential of the mean cross entropy.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::perplexity};
+/// # let dev: Cpu = Default::default();
+/// // uniform predictions over 4 classes
+/// let logits: Tensor<Rank2<3, 4>> = dev.zeros();
+/// let labels = dev.tensor([0, 1, 3]);
+/// assert!((perplexity(&logits, &labels) - 4.0).abs() < 1e-5);
+/// ```
+pub fn perplexity<B: Dim, C: Dim, D: DeviceStorage>(
+    logits: &Tensor<(B, C), f32, D>,
+    labels: &Tensor<(B,), usize, D>,
+) -> f32
+where
+    D::Storage<(B, C), f32>: AsVec<Unit = f32>,
+    D::Storage<(B,), usize>: AsVec<Unit = usize>,
+{
+    let mut ppl = Perplexity::default();
+    ppl.update(logits, labels);
+    ppl.compute()
+}
+
+/// Accumulates the negative log likelihood of the labels over multiple batches.
+/// See [perplexity()].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Perplexity {
+    pub total_nll: f64,
+    pub count: usize,
+}
+
+impl Perplexity {
+    /// Adds the predictions of a batch.
+    pub fn update<B: Dim, C: Dim, D: DeviceStorage>(
+        &mut self,
+        logits: &Tensor<(B, C), f32, D>,
+        labels: &Tensor<(B,), usize, D>,
+    ) where
+        D::Storage<(B, C), f32>: AsVec<Unit = f32>,
+        D::Storage<(B,), usize>: AsVec<Unit = usize>,
+    {
+        let (batch, classes) = *logits.shape();
+        assert_eq!(batch.size(), labels.shape().0.size());
+        let logits = logits.as_vec();
+        for (row, label) in logits.chunks(classes.size().max(1)).zip(labels.as_vec()) {
+            let max = row.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+            let lse = max + row.iter().map(|l| (l - max).exp()).sum::<f32>().ln();
+            self.total_nll += (lse - row[label]) as f64;
+        }
+        self.count += batch.size();
+    }
+
+    /// The perplexity so far, or `NaN` if nothing was added.
+    pub fn compute(&self) -> f32 {
+        (self.total_nll / self.count as f64).exp() as f32
+    }
+
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+
+    #[test]
+    fn test_accuracy_accumulates() {
+        let dev: TestDevice = Default::default();
+        let mut acc = Accuracy::default();
+        acc.update(
+            &dev.tensor([[1.0, 2.0, 0.0], [3.0, 2.0, 1.0]]),
+            &dev.tensor([1, 2]),
+        );
+        assert_eq!(acc.compute(), 0.5);
+        acc.update(&dev.tensor([[0.0, 0.0, 5.0]]), &dev.tensor([2]));
+        assert_eq!(
+            acc,
+            Accuracy {
+                correct: 2,
+                total: 3
+            }
+        );
+        acc.reset();
+        assert!(acc.compute().is_nan());
+    }
+
+    #[test]
+    fn test_confusion_matrix() {
+        let dev: TestDevice = Default::default();
+        let scores = dev.tensor([[0.9, 0.1], [0.6, 0.7], [0.2, 0.3]]);
+        let targets = dev.tensor([[1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+        let cm = ConfusionMatrix::from_scores(&scores, &targets, 0.5);
+        assert_eq!(
+            cm,
+            ConfusionMatrix {
+                true_positives: 2,
+                false_positives: 1,
+                true_negatives: 1,
+                false_negatives: 2,
+            }
+        );
+        assert_eq!(cm.precision(), 2.0 / 3.0);
+        assert_eq!(cm.recall(), 0.5);
+        assert_eq!(cm.f1(), 4.0 / 7.0);
+        assert_eq!(cm.accuracy(), 0.5);
+
+        // thresholding logits at 0
+        let logits = dev.tensor([2.0, -1.0]);
+        let cm = ConfusionMatrix::from_scores(&logits, &dev.tensor([1.0, 1.0]), 0.0);
+        assert_eq!(cm.recall(), 0.5);
+        assert_eq!(ConfusionMatrix::default().f1(), 0.0);
+    }
+
+    #[test]
+    fn test_roc_auc() {
+        let dev: TestDevice = Default::default();
+        let scores = dev.tensor([0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(roc_auc(&scores, &dev.tensor([0.0, 0.0, 1.0, 1.0])), 1.0);
+        assert_eq!(roc_auc(&scores, &dev.tensor([1.0, 1.0, 0.0, 0.0])), 0.0);
+        assert!(roc_auc(&scores, &dev.tensor([1.0; 4])).is_nan());
+
+        // ties count as half
+        let scores = dev.tensor([0.5, 0.5, 0.5, 0.5]);
+        assert_eq!(roc_auc(&scores, &dev.tensor([0.0, 1.0, 0.0, 1.0])), 0.5);
+
+        // accumulating matches a single batch
+        let a: Tensor<Rank1<50>, f32, _> = dev.sample_uniform();
+        let b: Tensor<Rank1<50>, f32, _> = dev.sample_uniform();
+        let ta = a.clone().scalar_gt(0.3).to_dtype::<f32>();
+        let tb = b.clone().scalar_lt(0.6).to_dtype::<f32>();
+        let mut auc = RocAuc::default();
+        auc.update(&a, &ta);
+        auc.update(&b, &tb);
+        let all = dev.tensor([a.array(), b.array()]);
+        let all_t = dev.tensor([ta.array(), tb.array()]);
+        assert_eq!(auc.compute(), roc_auc(&all, &all_t));
+    }
+
+    #[test]
+    fn test_perplexity() {
+        let dev: TestDevice = Default::default();
+        let logits: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+        let labels = dev.tensor([0, 4, 2, 2]);
+        let probs = logits.clone().softmax::<Axis<1>>().array();
+        let expected =
+            -(probs[0][0].ln() + probs[1][4].ln() + probs[2][2].ln() + probs[3][2].ln()) / 4.0;
+        let ppl = perplexity(&logits, &labels);
+        assert!((ppl - expected.exp()).abs() < 1e-4, "{ppl}");
+
+        let mut acc = Perplexity::default();
+        acc.update(&logits, &labels);
+        acc.update(&logits, &labels);
+        assert!((acc.compute() - ppl).abs() < 1e-4);
+    }
+}
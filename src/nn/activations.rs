@@ -1,6 +1,6 @@
-use crate::{gradients::Tape, shapes::*, tensor::*, tensor_ops::*};
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
 
-use super::module::{Module, NonMutableModule, ZeroSizedModule};
+use super::module::{Module, NonMutableModule, ResetParams, ZeroSizedModule};
 
 macro_rules! activation_impls {
     ($struct_name:ident, $func_name:ident, #[$docstring:meta]) => {
@@ -32,6 +32,243 @@ activation_impls!(Tanh, tanh, #[doc="Unit struct that impls [Module] as calling
 activation_impls!(Square, square, #[doc="Unit struct that impls [Module] as calling [square()] on `input`."]);
 activation_impls!(Sqrt, sqrt, #[doc="Unit struct that impls [Module] as calling [sqrt()] on `input`."]);
 activation_impls!(Abs, abs, #[doc="Unit struct that impls [Module] as calling [abs()] on `input`."]);
+activation_impls!(GeLU, gelu, #[doc="Unit struct that impls [Module] as calling [gelu()] on `input`."]);
+activation_impls!(FastGeLU, fast_gelu, #[doc="Unit struct that impls [Module] as calling [fast_gelu()] on `input`."]);
+activation_impls!(SiLU, silu, #[doc="Unit struct that impls [Module] as calling [silu()] on `input`."]);
+activation_impls!(Mish, mish, #[doc="Unit struct that impls [Module] as calling [mish()] on `input`."]);
+
+/// Impls [Module] as calling [leaky_relu()] on `input` with `self.slope`.
+#[derive(Debug, Clone, Copy)]
+pub struct LeakyReLU {
+    pub slope: f32,
+}
+
+impl Default for LeakyReLU {
+    /// Sets `self.slope` to `0.01`
+    fn default() -> Self {
+        Self { slope: 0.01 }
+    }
+}
+
+impl ZeroSizedModule for LeakyReLU {}
+impl NonMutableModule for LeakyReLU {}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Module<Tensor<S, f32, D, T>> for LeakyReLU {
+    type Output = Tensor<S, f32, D, T>;
+    fn forward(&self, input: Tensor<S, f32, D, T>) -> Self::Output {
+        input.leaky_relu(self.slope)
+    }
+}
+
+/// Impls [Module] as calling [elu()] on `input` with `self.alpha`.
+#[derive(Debug, Clone, Copy)]
+pub struct ELU {
+    pub alpha: f32,
+}
+
+impl Default for ELU {
+    /// Sets `self.alpha` to `1.0`
+    fn default() -> Self {
+        Self { alpha: 1.0 }
+    }
+}
+
+impl ZeroSizedModule for ELU {}
+impl NonMutableModule for ELU {}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Module<Tensor<S, f32, D, T>> for ELU {
+    type Output = Tensor<S, f32, D, T>;
+    fn forward(&self, input: Tensor<S, f32, D, T>) -> Self::Output {
+        input.elu(self.alpha)
+    }
+}
+
+/// Impls [Module] as calling [softplus()] on `input` with `self.beta` and `self.threshold`.
+#[derive(Debug, Clone, Copy)]
+pub struct Softplus {
+    pub beta: f32,
+    pub threshold: f32,
+}
+
+impl Default for Softplus {
+    /// Sets `self.beta` to `1.0` and `self.threshold` to `20.0`
+    fn default() -> Self {
+        Self {
+            beta: 1.0,
+            threshold: 20.0,
+        }
+    }
+}
+
+impl ZeroSizedModule for Softplus {}
+impl NonMutableModule for Softplus {}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Module<Tensor<S, f32, D, T>> for Softplus {
+    type Output = Tensor<S, f32, D, T>;
+    fn forward(&self, input: Tensor<S, f32, D, T>) -> Self::Output {
+        input.softplus(self.beta, self.threshold)
+    }
+}
+
+/// Parametric ReLU with a learnable slope per channel: `max(0, x) + a * min(0, x)`.
+///
+/// The channel axis is the 0th axis for 1d & 3d inputs, and the 1st axis for
+/// 2d & 4d (batched) inputs, same as pytorch.
+///
+/// Initializes [Self::a] to `0.25`.
+///
+/// # Generics
+/// - `C` The number of channels.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model: PReLU<3> = dev.build_module();
+/// let _: Tensor<Rank1<3>> = model.forward(dev.zeros::<Rank1<3>>());
+/// let _: Tensor<Rank2<10, 3>> = model.forward(dev.zeros::<Rank2<10, 3>>());
+/// let _: Tensor<Rank4<10, 3, 4, 4>> = model.forward(dev.zeros::<Rank4<10, 3, 4, 4>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PReLU<const C: usize, D: Device<f32> = Cpu> {
+    /// Slope of the negative part, shape (C, )
+    pub a: Tensor<Rank1<C>, f32, D>,
+}
+
+impl<const C: usize, D: Device<f32>> PReLU<C, D> {
+    /// `a` must already be broadcast to the shape of `x`
+    fn prelu<S: Shape, T: Tape<D>>(
+        x: Tensor<S, f32, D, T>,
+        a: Tensor<S, f32, D, T>,
+    ) -> Tensor<S, f32, D, T> {
+        let neg = x.retaped::<T>().negate().relu();
+        x.relu() - a * neg
+    }
+}
+
+impl<const C: usize, D: Device<f32>> ResetParams<D, f32> for PReLU<C, D> {
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        let a = device.try_ones()?.try_mul(0.25)?;
+        Ok(Self { a })
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        // replacing only the storage keeps the id of `a`
+        self.a.try_fill_with_ones()?;
+        self.a.storage = self.a.clone().try_mul(0.25)?.storage;
+        Ok(())
+    }
+}
+
+impl<const C: usize, D: Device<f32>> GradientUpdate<D, f32> for PReLU<C, D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.a.update(updater, unused)
+    }
+}
+
+impl<const C: usize, D: Device<f32>> NonMutableModule for PReLU<C, D> {}
+
+impl<const C: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<Rank1<C>, f32, D, T>>
+    for PReLU<C, D>
+{
+    type Output = Tensor<Rank1<C>, f32, D, T>;
+    fn forward(&self, x: Tensor<Rank1<C>, f32, D, T>) -> Self::Output {
+        Self::prelu(x, self.a.retaped())
+    }
+}
+
+impl<B: Dim, const C: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<(B, Const<C>), f32, D, T>>
+    for PReLU<C, D>
+{
+    type Output = Tensor<(B, Const<C>), f32, D, T>;
+    fn forward(&self, x: Tensor<(B, Const<C>), f32, D, T>) -> Self::Output {
+        let a = self
+            .a
+            .retaped::<T>()
+            .broadcast_like::<_, Axis<0>>(x.shape());
+        Self::prelu(x, a)
+    }
+}
+
+impl<const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(Const<C>, H, W), f32, D, T>> for PReLU<C, D>
+{
+    type Output = Tensor<(Const<C>, H, W), f32, D, T>;
+    fn forward(&self, x: Tensor<(Const<C>, H, W), f32, D, T>) -> Self::Output {
+        let a = self
+            .a
+            .retaped::<T>()
+            .broadcast_like::<_, Axes2<1, 2>>(x.shape());
+        Self::prelu(x, a)
+    }
+}
+
+impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(B, Const<C>, H, W), f32, D, T>> for PReLU<C, D>
+{
+    type Output = Tensor<(B, Const<C>, H, W), f32, D, T>;
+    fn forward(&self, x: Tensor<(B, Const<C>, H, W), f32, D, T>) -> Self::Output {
+        let a = self
+            .a
+            .retaped::<T>()
+            .broadcast_like::<_, Axes3<0, 2, 3>>(x.shape());
+        Self::prelu(x, a)
+    }
+}
+
+/// Swish with a learnable `beta`: `x * sigmoid(beta * x)`.
+///
+/// With `beta` fixed to `1.0` this is the same as [SiLU]. Initializes [Self::beta] to `1.0`.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model: Swish = dev.build_module();
+/// let _: Tensor<Rank2<2, 3>> = model.forward(dev.zeros::<Rank2<2, 3>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Swish<D: Device<f32> = Cpu> {
+    pub beta: Tensor<Rank0, f32, D>,
+}
+
+impl<D: Device<f32>> ResetParams<D, f32> for Swish<D> {
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self {
+            beta: device.try_ones()?,
+        })
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.beta.try_fill_with_ones()
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for Swish<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.beta.update(updater, unused)
+    }
+}
+
+impl<D: Device<f32>> NonMutableModule for Swish<D> {}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Module<Tensor<S, f32, D, T>> for Swish<D> {
+    type Output = Tensor<S, f32, D, T>;
+    fn forward(&self, x: Tensor<S, f32, D, T>) -> Self::Output {
+        let beta = self
+            .beta
+            .retaped::<T>()
+            .broadcast_like::<_, S::AllAxes>(x.shape());
+        let gate = (beta * x.retaped::<T>()).sigmoid();
+        x * gate
+    }
+}
 
 /// Unit struct that impls [Module] as calling [softmax()] on `input`."
 #[derive(Default, Debug, Clone, Copy)]
@@ -51,7 +288,11 @@ impl<Ax: Axes, S: Shape<LastAxis = Ax> + ReduceShape<Ax>, E: Dtype, D: Device<E>
 
 #[cfg(test)]
 mod tests {
-    use crate::{nn::ModuleMut, tests::TestDevice};
+    use crate::{
+        nn::{tests::SimpleUpdater, ModuleBuilder, ModuleMut},
+        tests::{assert_close, TestDevice},
+        unique_id::HasUniqueId,
+    };
 
     use super::*;
 
@@ -155,4 +396,134 @@ mod tests {
         let r2 = t.softmax::<crate::shapes::Axis<1>>();
         assert_eq!(r1.array(), r2.array());
     }
+
+    #[test]
+    fn test_nn_activations_gelus() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([-2.0, -1.0, 0.0, 1.0, 2.0]);
+        assert_eq!(GeLU.forward_mut(t.clone()).array(), gelu(t.clone()).array());
+        assert_eq!(
+            FastGeLU.forward_mut(t.clone()).array(),
+            fast_gelu(t).array()
+        );
+    }
+
+    #[test]
+    fn test_nn_activations_silu_mish() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([-2.0, -1.0, 0.0, 1.0, 2.0]);
+        assert_eq!(SiLU.forward_mut(t.clone()).array(), silu(t.clone()).array());
+        assert_eq!(Mish.forward_mut(t.clone()).array(), mish(t).array());
+    }
+
+    #[test]
+    fn test_nn_activations_with_params() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([-2.0, -1.0, 0.0, 1.0, 2.0]);
+        let r1 = LeakyReLU::default().forward_mut(t.clone());
+        assert_eq!(r1.array(), leaky_relu(t.clone(), 0.01).array());
+        let r1 = ELU { alpha: 0.5 }.forward_mut(t.clone());
+        assert_eq!(r1.array(), elu(t.clone(), 0.5).array());
+        let r1 = Softplus::default().forward_mut(t.clone());
+        assert_eq!(r1.array(), softplus(t, 1.0, 20.0).array());
+    }
+
+    #[test]
+    fn test_activations_in_tuple() {
+        let dev: TestDevice = Default::default();
+        let model: (GeLU, SiLU, Mish, LeakyReLU, ELU, Softplus) = dev.build_module();
+        let t = dev.tensor([-2.0, -1.0, 0.0, 1.0, 2.0]);
+        let r = model.forward(t.clone());
+        let expected = t
+            .gelu()
+            .silu()
+            .mish()
+            .leaky_relu(0.01)
+            .elu(1.0)
+            .softplus(1.0, 20.0);
+        assert_eq!(r.array(), expected.array());
+    }
+
+    #[test]
+    fn test_prelu() {
+        let dev: TestDevice = Default::default();
+        let mut model: PReLU<3, _> = dev.build_module();
+        assert_eq!(model.a.array(), [0.25; 3]);
+
+        model.a = dev.tensor([0.1, 0.2, 0.3]);
+        let x = dev.tensor([[-1.0, 2.0, -3.0], [4.0, -5.0, 6.0]]);
+        let r = model.forward_mut(x.trace());
+        assert_close(&r.array(), &[[-0.1, 2.0, -0.9], [4.0, -1.0, 6.0]]);
+        let g = r.mean().backward();
+        assert_close(&g.get(&model.a).array(), &[-0.16666667, -0.8333333, -0.5]);
+        assert_close(
+            &g.get(&x).array(),
+            &[
+                [0.016666668, 0.16666667, 0.05],
+                [0.16666667, 0.033333335, 0.16666667],
+            ],
+        );
+
+        model.reset_params();
+        assert_eq!(model.a.array(), [0.25; 3]);
+    }
+
+    #[test]
+    fn test_prelu_reset_params() {
+        let dev: TestDevice = Default::default();
+        let mut model: PReLU<3, _> = dev.build_module();
+        let id = *model.a.id();
+        model.a.fill_with_zeros();
+        model.reset_params();
+        assert_eq!(model.a.id(), &id);
+        assert_eq!(model.a.array(), [0.25; 3]);
+    }
+
+    #[test]
+    fn test_prelu_channels_first() {
+        let dev: TestDevice = Default::default();
+        let mut model: PReLU<2, _> = dev.build_module();
+        model.a = dev.tensor([0.5, 2.0]);
+        let x: Tensor<Rank4<1, 2, 1, 2>, f32, _> = dev.tensor([[[[-1.0, 1.0]], [[-1.0, 1.0]]]]);
+        let r = model.forward(x.clone());
+        assert_eq!(r.array(), [[[[-0.5, 1.0]], [[-2.0, 1.0]]]]);
+        let r = model.forward(dev.tensor([[[-1.0, 1.0]], [[-1.0, 1.0]]]));
+        assert_eq!(r.array(), [[[-0.5, 1.0]], [[-2.0, 1.0]]]);
+    }
+
+    #[test]
+    fn test_prelu_missing_gradients() {
+        let dev: TestDevice = Default::default();
+        let mut model: PReLU<3, _> = dev.build_module();
+        let mut g: SimpleUpdater<_> = Default::default();
+
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert_eq!(&unused.ids, &[*model.a.id()]);
+
+        g.0.try_alloc_for(&model.a).unwrap();
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    fn test_swish() {
+        let dev: TestDevice = Default::default();
+        let mut model: Swish<_> = dev.build_module();
+        assert_eq!(model.beta.array(), 1.0);
+
+        let x = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+        assert_close(&model.forward(x.clone()).array(), &silu(x.clone()).array());
+
+        model.beta = dev.tensor(2.0);
+        let r = model.forward_mut(x.trace());
+        assert_close(&r.array(), &[-0.11920292, 0.0, 0.8807971, 1.9640276]);
+        let g = r.mean().backward();
+        assert_close(&g.get(&model.beta).array(), &0.0701595);
+        assert_close(
+            &g.get(&x).array(),
+            &[-0.022696063, 0.125, 0.27269608, 0.26316616],
+        );
+    }
 }
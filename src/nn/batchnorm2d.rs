@@ -185,7 +185,11 @@ impl<const C: usize, D: Device<f32>> GradientUpdate<D, f32> for BatchNorm2D<C, D
     {
         self.scale.update(updater, unused)?;
         self.bias.update(updater, unused)?;
-        Ok(())
+        updater.update_running_stats(
+            &mut self.running_mean,
+            &mut self.running_var,
+            &mut self.momentum,
+        )
     }
 }
 
@@ -0,0 +1,278 @@
+use crate::{
+    optim::{GradientUpdate, ParamUpdater, UnusedTensors},
+    shapes::{Dtype, Rank0, Shape},
+    tensor::{
+        binary::{BinaryDtype, BinaryError, BinaryReader, BinaryWriter, Compression},
+        AsArray, Cpu, DeviceStorage, Tensor, TensorFromArray, ZerosTensor,
+    },
+    tensor_ops::Device,
+};
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// Something that can be saved in the binary format of [crate::tensor::binary].
+///
+/// This is implemented for everything that implements [GradientUpdate], which includes all
+/// [super::Module]s in nn. The tensors are written in the order that optimizers visit them:
+/// every parameter, the running statistics of [super::BatchNorm2D], the statistics of
+/// [super::RunningNorm1D] followed by their sample count, and the mask of each parameter of
+/// [super::Pruned] after the parameter.
+///
+/// Tensors are written one at a time, so saving doesn't need a second copy of the model.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, tensor::binary::Compression};
+/// # let dev: Cpu = Default::default();
+/// # let dir = tempfile::tempdir().unwrap();
+/// # let path = dir.path().join("model.dfdx");
+/// type Model = (Linear<5, 10>, ReLU, Linear<10, 5>);
+/// let model: Model = dev.build_module();
+/// model.save_binary(&path, Compression::None).unwrap();
+///
+/// let mut loaded: Model = dev.build_module();
+/// loaded.load_binary(&path).unwrap();
+/// assert_eq!(model.0.weight.array(), loaded.0.weight.array());
+/// ```
+pub trait SaveToBinary<D: DeviceStorage, E: Dtype> {
+    /// Saves this object to a new file at `path`.
+    fn save_binary<P: AsRef<Path>>(&self, path: P, compression: Compression) -> io::Result<()> {
+        let f = BufWriter::new(File::create(path)?);
+        let mut w = BinaryWriter::new(f, compression)?;
+        self.write_binary(&mut w)?;
+        w.finish()?;
+        Ok(())
+    }
+
+    /// Appends the tensors of this object to `w`.
+    fn write_binary<W: Write>(&self, w: &mut BinaryWriter<W>) -> io::Result<()>;
+}
+
+/// Something that can be loaded from the binary format of [crate::tensor::binary]. See
+/// [SaveToBinary].
+pub trait LoadFromBinary<D: DeviceStorage, E: Dtype> {
+    /// Loads this object from the file at `path`, which must contain exactly its tensors.
+    fn load_binary<P: AsRef<Path>>(&mut self, path: P) -> Result<(), BinaryError> {
+        let f = BufReader::new(File::open(path)?);
+        let mut r = BinaryReader::new(f)?;
+        self.read_binary(&mut r)?;
+        r.finish()
+    }
+
+    /// Reads the tensors of this object from `r`.
+    fn read_binary<R: Read>(&mut self, r: &mut BinaryReader<R>) -> Result<(), BinaryError>;
+}
+
+impl<M, D, E> SaveToBinary<D, E> for M
+where
+    M: GradientUpdate<D, E> + Clone,
+    D: Device<E>,
+    E: Dtype + BinaryDtype,
+{
+    fn write_binary<W: Write>(&self, w: &mut BinaryWriter<W>) -> io::Result<()> {
+        let mut visitor = WriteTensors { w, result: Ok(()) };
+        // clones of tensors share their data, so this doesn't copy the model
+        self.clone()
+            .update(&mut visitor, &mut Default::default())
+            .unwrap_or_else(|_| unreachable!());
+        visitor.result
+    }
+}
+
+impl<M, D, E> LoadFromBinary<D, E> for M
+where
+    M: GradientUpdate<D, E>,
+    D: Device<E>,
+    E: Dtype + BinaryDtype,
+{
+    fn read_binary<R: Read>(&mut self, r: &mut BinaryReader<R>) -> Result<(), BinaryError> {
+        let mut visitor = ReadTensors { r, result: Ok(()) };
+        self.update(&mut visitor, &mut Default::default())
+            .unwrap_or_else(|_| unreachable!());
+        visitor.result
+    }
+}
+
+/// Writes every tensor it visits, and keeps the first error, since [ParamUpdater] can only
+/// return device errors.
+struct WriteTensors<'a, W: Write> {
+    w: &'a mut BinaryWriter<W>,
+    result: io::Result<()>,
+}
+
+impl<'a, W: Write, D: Device<E>, E: Dtype + BinaryDtype> ParamUpdater<D, E>
+    for WriteTensors<'a, W>
+{
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, E, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        if self.result.is_ok() {
+            self.result = self.w.write_tensor(p);
+        }
+        Ok(())
+    }
+
+    fn update_running_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, E, D>,
+        var: &mut Tensor<S, E, D>,
+        _: &mut E,
+    ) -> Result<(), D::Err> {
+        self.update_param(mean, &mut Default::default())?;
+        self.update_param(var, &mut Default::default())
+    }
+
+    fn update_accumulated_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, E, D>,
+        var: &mut Tensor<S, E, D>,
+        count: &mut u64,
+    ) -> Result<(), D::Err> {
+        self.update_param(mean, &mut Default::default())?;
+        self.update_param(var, &mut Default::default())?;
+        if self.result.is_ok() {
+            let count: Tensor<Rank0, usize, Cpu> = Cpu::default().tensor(*count as usize);
+            self.result = self.w.write_tensor(&count);
+        }
+        Ok(())
+    }
+
+    fn update_buffer<S: Shape>(&mut self, buffer: &mut Tensor<S, E, D>) -> Result<(), D::Err> {
+        self.update_param(buffer, &mut Default::default())
+    }
+}
+
+/// Reads every tensor it visits, and keeps the first error like [WriteTensors].
+struct ReadTensors<'a, R: Read> {
+    r: &'a mut BinaryReader<R>,
+    result: Result<(), BinaryError>,
+}
+
+impl<'a, R: Read, D: Device<E>, E: Dtype + BinaryDtype> ParamUpdater<D, E> for ReadTensors<'a, R> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, E, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        if self.result.is_ok() {
+            self.result = self.r.read_tensor(p);
+        }
+        Ok(())
+    }
+
+    fn update_running_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, E, D>,
+        var: &mut Tensor<S, E, D>,
+        _: &mut E,
+    ) -> Result<(), D::Err> {
+        self.update_param(mean, &mut Default::default())?;
+        self.update_param(var, &mut Default::default())
+    }
+
+    fn update_accumulated_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, E, D>,
+        var: &mut Tensor<S, E, D>,
+        count: &mut u64,
+    ) -> Result<(), D::Err> {
+        self.update_param(mean, &mut Default::default())?;
+        self.update_param(var, &mut Default::default())?;
+        if self.result.is_ok() {
+            let mut c: Tensor<Rank0, usize, Cpu> = Cpu::default().zeros();
+            self.result = self.r.read_tensor(&mut c);
+            if self.result.is_ok() {
+                *count = c.array() as u64;
+            }
+        }
+        Ok(())
+    }
+
+    fn update_buffer<S: Shape>(&mut self, buffer: &mut Tensor<S, E, D>) -> Result<(), D::Err> {
+        self.update_param(buffer, &mut Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, shapes::*, tensor::*, tests::TestDevice};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_save_load_model_binary() {
+        let dev: TestDevice = Default::default();
+        type Model = (Linear<3, 4>, ReLU, BatchNorm2D<4>, Linear<4, 2>);
+        let mut saved: Model = dev.build_module();
+        let x: Tensor<Rank4<2, 4, 3, 3>, f32, _> = dev.sample_normal();
+        let _ = saved.2.forward_mut(x.trace());
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        saved.save_binary(file.path(), Compression::None).unwrap();
+
+        let mut loaded: Model = dev.build_module();
+        loaded.load_binary(file.path()).unwrap();
+        assert_eq!(saved.0.weight.array(), loaded.0.weight.array());
+        assert_eq!(saved.3.bias.array(), loaded.3.bias.array());
+        assert_eq!(saved.2.running_mean.array(), loaded.2.running_mean.array());
+        assert_eq!(saved.2.running_var.array(), loaded.2.running_var.array());
+
+        // a different architecture
+        let mut other: (Linear<3, 4>, Linear<4, 2>) = dev.build_module();
+        assert!(matches!(
+            other.load_binary(file.path()),
+            Err(BinaryError::ShapeMismatch { .. })
+        ));
+        let mut other: Linear<3, 4> = dev.build_module();
+        assert!(matches!(
+            other.load_binary(file.path()),
+            Err(BinaryError::UnreadTensors(2))
+        ));
+    }
+
+    #[test]
+    fn test_save_load_pruned_binary() {
+        let dev: TestDevice = Default::default();
+        let mut saved: Pruned<Linear<4, 4, TestDevice>, TestDevice> = dev.build_module();
+        saved.prune_magnitude(0.25);
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        saved.save_binary(file.path(), Compression::None).unwrap();
+
+        let mut loaded: Pruned<Linear<4, 4, TestDevice>, TestDevice> = dev.build_module();
+        loaded.load_binary(file.path()).unwrap();
+        assert_eq!(loaded.sparsity(), 0.25);
+        assert_eq!(saved.module.weight.array(), loaded.module.weight.array());
+        assert_eq!(saved.module.bias.array(), loaded.module.bias.array());
+
+        // the masks are saved after each parameter
+        let mut plain: Linear<4, 4, TestDevice> = dev.build_module();
+        assert!(matches!(
+            plain.load_binary(file.path()),
+            Err(BinaryError::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_save_load_running_norm_binary() {
+        let dev: TestDevice = Default::default();
+        let mut saved: RunningNorm1D<3, _> = dev.build_module();
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let _ = saved.forward_mut(x * 2.0 + 1.0);
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        saved.save_binary(file.path(), Compression::None).unwrap();
+
+        let mut loaded: RunningNorm1D<3, _> = dev.build_module();
+        loaded.load_binary(file.path()).unwrap();
+        assert_eq!(loaded.count, 4);
+        assert_eq!(saved.running_mean.array(), loaded.running_mean.array());
+        assert_eq!(saved.running_var.array(), loaded.running_var.array());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{
+    shapes::{Dtype, HasShape, Shape},
+    tensor::{
+        replay::{Recorder, ReplayBuffers, ReplayFn},
+        DeviceStorage, HasErr, Tensor,
+    },
+    unique_id::{unique_id, UniqueId},
+};
+
+use super::Module;
+
+use std::{collections::BTreeSet, sync::Arc, vec::Vec};
+
+/// Traces one [Module::forward()] of `module` on `x`, and returns a [Compiled] module that
+/// replays the recorded kernels on new inputs of the same shape, without running the code of
+/// `module` again.
+///
+/// This cuts the per call overhead of models with many small ops, like in inference servers.
+/// The weights are captured by reference, so the [Compiled] module doesn't copy them, but it
+/// also doesn't see changes made to `module` afterwards.
+///
+/// Only the forward pass of tensors without a tape is compiled, and each kernel still
+/// allocates its output on every call. The kernels that can be replayed are elementwise ops,
+/// [crate::tensor_ops::matmul()], broadcasts, reshapes, permutes, sums (and the reductions
+/// built on them, like [crate::tensor_ops::MeanTo::mean()]), and
+/// [crate::tensor_ops::FusedElementwise].
+///
+/// **Panics** if the forward pass used a tensor created by any other op during the trace,
+/// e.g. the output of [crate::tensor_ops::softmax()]. Ops run on the same device from other
+/// threads while tracing are recorded as well, so don't share the device during the trace.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<5, 10>, ReLU, Residual<(Linear<10, 10>, Tanh)>, Linear<10, 2>);
+/// let model = dev.build_module::<Model>();
+/// let compiled = compile(&model, dev.zeros::<Rank2<3, 5>>());
+///
+/// let x: Tensor<Rank2<3, 5>> = dev.sample_normal();
+/// assert_eq!(compiled.forward(x.clone()).array(), model.forward(x).array());
+/// ```
+pub fn compile<M, Src, Dst, E, D>(module: &M, x: Tensor<Src, E, D>) -> Compiled<Src, Dst, E, D>
+where
+    Src: Shape,
+    Dst: Shape,
+    E: Dtype,
+    D: DeviceStorage,
+    M: Module<Tensor<Src, E, D>, Output = Tensor<Dst, E, D>>,
+{
+    /// Stops recording even if the forward pass panics.
+    struct Trace<'a, D: HasErr>(&'a Recorder<D>);
+    impl<'a, D: HasErr> Drop for Trace<'a, D> {
+        fn drop(&mut self) {
+            self.0.stop();
+        }
+    }
+
+    let device = x.device.clone();
+    let (input, shape) = (x.id, *x.shape());
+    let start = unique_id();
+    let trace = Trace(device.recorder());
+    trace.0.start();
+    let y = module.forward(x);
+    let steps = trace.0.stop();
+    drop(trace);
+
+    // every tensor used by a step has to be the input, a tensor from before the trace
+    // (like a parameter), or the output of an earlier step.
+    let mut computed = BTreeSet::from([input]);
+    for step in steps.iter() {
+        for id in step.inputs.iter() {
+            assert!(
+                *id < start || computed.contains(id),
+                "Can't compile, `{}` uses a tensor created by an op that can't be replayed",
+                step.name
+            );
+        }
+        computed.insert(step.output);
+    }
+    assert!(
+        computed.contains(&y.id),
+        "Can't compile, the output is created by an op that can't be replayed"
+    );
+
+    Compiled {
+        shape,
+        input,
+        output: y.id,
+        steps: Arc::new(steps.into_iter().map(|step| step.run).collect()),
+        marker: Default::default(),
+    }
+}
+
+/// A module traced by [compile()]. Cloning it only increments a reference count.
+pub struct Compiled<Src: Shape, Dst: Shape, E: Dtype, D: DeviceStorage> {
+    shape: Src,
+    input: UniqueId,
+    output: UniqueId,
+    steps: Arc<Vec<ReplayFn<D>>>,
+    marker: core::marker::PhantomData<(Dst, E)>,
+}
+
+impl<Src: Shape, Dst: Shape, E: Dtype, D: DeviceStorage> Compiled<Src, Dst, E, D> {
+    /// The number of kernels run by each [Module::forward()].
+    pub fn num_kernels(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Fallible version of [Module::forward()]. **Panics** if `x` doesn't have the shape
+    /// that the module was compiled for.
+    pub fn try_forward(&self, x: Tensor<Src, E, D>) -> Result<Tensor<Dst, E, D>, D::Err> {
+        assert_eq!(
+            x.shape().concrete(),
+            self.shape.concrete(),
+            "Compiled for an input of shape {:?}, got {:?}",
+            self.shape.concrete(),
+            x.shape().concrete(),
+        );
+        let mut bufs = ReplayBuffers::default();
+        bufs.insert::<Src, E, D>(self.input, x.storage);
+        for step in self.steps.iter() {
+            step(&mut bufs)?;
+        }
+        let storage = bufs.remove::<Dst, E, D>(&self.output).unwrap();
+        Ok(x.device.upgrade(storage))
+    }
+}
+
+impl<Src: Shape, Dst: Shape, E: Dtype, D: DeviceStorage> Module<Tensor<Src, E, D>>
+    for Compiled<Src, Dst, E, D>
+{
+    type Output = Tensor<Dst, E, D>;
+    fn forward(&self, x: Tensor<Src, E, D>) -> Self::Output {
+        self.try_forward(x).unwrap()
+    }
+}
+
+impl<Src: Shape, Dst: Shape, E: Dtype, D: DeviceStorage> Clone for Compiled<Src, Dst, E, D> {
+    fn clone(&self) -> Self {
+        Self {
+            shape: self.shape,
+            input: self.input,
+            output: self.output,
+            steps: self.steps.clone(),
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<Src: Shape, Dst: Shape, E: Dtype, D: DeviceStorage> std::fmt::Debug
+    for Compiled<Src, Dst, E, D>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Compiled")
+            .field("shape", &self.shape)
+            .field("num_kernels", &self.steps.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_compiled_matches_module() {
+        let dev: TestDevice = Default::default();
+        type Model = (
+            Linear<3, 4, TestDevice>,
+            ReLU,
+            Residual<(Linear<4, 4, TestDevice>, Tanh)>,
+            Linear<4, 2, TestDevice>,
+            Sigmoid,
+        );
+        let model: Model = dev.build_module();
+        let compiled = compile(&model, dev.sample_normal::<Rank2<5, 3>>());
+        // permute, matmul, broadcast & add for each linear, the activations and residual add
+        assert_eq!(compiled.num_kernels(), 16);
+
+        for _ in 0..3 {
+            let x: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+            let y = compiled.forward(x.clone());
+            assert_close(&y.array(), &model.forward(x).array());
+        }
+    }
+
+    #[test]
+    fn test_compiled_reductions_and_fused() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let f = |x: Tensor<Rank2<2, 3>, f32, TestDevice>| {
+            let mean = x
+                .clone()
+                .mean::<Rank1<2>, _>()
+                .broadcast::<Rank2<2, 3>, _>();
+            (x - mean)
+                .fused(|x| x.square() * 0.5)
+                .permute::<Rank2<3, 2>, _>()
+        };
+        let compiled = compile(&FnModule(f), x.clone());
+        let x2: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        assert_close(&compiled.forward(x2.clone()).array(), &f(x2).array());
+    }
+
+    #[test]
+    #[should_panic = "can't be replayed"]
+    fn test_compile_unsupported_op() {
+        let dev: TestDevice = Default::default();
+        let model: (Linear<3, 3, TestDevice>, Softmax, ReLU) = dev.build_module();
+        let _ = compile(&model, dev.zeros::<Rank1<3>>());
+    }
+
+    #[test]
+    #[should_panic = "Compiled for an input of shape [2, 3], got [4, 3]"]
+    fn test_compiled_wrong_shape() {
+        let dev: TestDevice = Default::default();
+        let model: Linear<3, 2, TestDevice> = dev.build_module();
+        let compiled = compile(&model, dev.zeros_like(&(2, Const::<3>)));
+        let _ = compiled.forward(dev.zeros_like(&(4, Const::<3>)));
+    }
+
+    /// Wraps a function of a tensor into a [Module].
+    struct FnModule<F>(F);
+
+    impl<T, O, F: Fn(T) -> O> Module<T> for FnModule<F> {
+        type Output = O;
+        fn forward(&self, x: T) -> O {
+            (self.0)(x)
+        }
+    }
+}
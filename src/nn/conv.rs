@@ -3,12 +3,12 @@ use crate::{
     optim::*,
     shapes::*,
     tensor::{Cpu, Tensor},
-    tensor_ops::{BroadcastTo, Device, TryConv2DTo},
+    tensor_ops::{BroadcastTo, Device, TryAdd, TryConv2DTo, TryMul},
 };
 
-use super::{Module, ModuleMut, ResetParams};
+use super::{BatchNorm2D, IntoInference, Module, ModuleMut, ResetParams};
 
-/// **Requires Nightly** Performs 2d convolutions on 3d and 4d images.
+/// **Requires Nightly or `stable-fallback`** Performs 2d convolutions on 3d and 4d images.
 ///
 /// **Pytorch Equivalent**: `torch.nn.Conv2d`
 ///
@@ -95,9 +95,41 @@ where
     }
 }
 
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    Conv2D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    /// Folds a [BatchNorm2D] that comes right after this convolution into [Self::weight] and
+    /// [Self::bias], so that the result computes `bn.forward(conv.forward(x))` for inference.
+    pub fn fold_batchnorm(self, bn: &BatchNorm2D<O, D>) -> Self {
+        self.try_fold_batchnorm(bn).unwrap()
+    }
+
+    /// Fallible version of [Conv2D::fold_batchnorm()]
+    pub fn try_fold_batchnorm(self, bn: &BatchNorm2D<O, D>) -> Result<Self, D::Err> {
+        let bn = bn.clone().try_into_inference()?;
+        self.try_fold_affine(&bn.scale, &bn.bias)
+    }
+
+    /// Multiplies the output channels by `scale` and adds `bias` to them.
+    pub(super) fn try_fold_affine(
+        self,
+        scale: &Tensor<Rank1<O>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Self, D::Err> {
+        Ok(Self {
+            weight: self
+                .weight
+                .try_mul(scale.clone().try_broadcast::<_, Axes3<1, 2, 3>>()?)?,
+            bias: self.bias.try_mul(scale.clone())?.try_add(bias.clone())?,
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
-struct Bias2D<'a, const C: usize, D: Device<f32> = Cpu> {
-    beta: &'a Tensor<Rank1<C>, f32, D>,
+pub(super) struct Bias2D<'a, const C: usize, D: Device<f32> = Cpu> {
+    pub(super) beta: &'a Tensor<Rank1<C>, f32, D>,
 }
 
 impl<'a, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
@@ -125,11 +157,26 @@ mod tests {
         nn::ModuleBuilder,
         tensor::{AsArray, SampleTensor, ZerosTensor},
         tensor_ops::*,
-        tests::TestDevice,
+        tests::{assert_close, TestDevice},
     };
 
     use super::*;
 
+    #[test]
+    fn test_fold_batchnorm() {
+        let dev: TestDevice = Default::default();
+        let conv: Conv2D<2, 3, 3, 1, 1, _> = dev.build_module();
+        let mut bn: BatchNorm2D<3, _> = dev.build_module();
+        bn.scale = dev.sample_normal();
+        bn.running_mean = dev.sample_normal();
+        bn.running_var = dev.sample_uniform::<Rank1<3>>() + 0.5;
+
+        let x = dev.sample_normal::<Rank4<2, 2, 5, 5>>();
+        let y = bn.forward(conv.forward(x.clone()));
+        let folded = conv.fold_batchnorm(&bn);
+        assert_close(&folded.forward(x).array(), &y.array());
+    }
+
     #[rustfmt::skip]
     #[test]
     fn test_forward_3d_sizes() {
@@ -0,0 +1,190 @@
+use crate::{gradients::*, optim::*, shapes::*, tensor::*, tensor_ops::*};
+use rand::Rng;
+use rand_distr::Distribution;
+
+use super::{Module, ModuleMut, ResetParams};
+
+/// Stochastic depth, as introduced in [Deep Networks with Stochastic Depth](https://arxiv.org/abs/1603.09382).
+///
+/// Wraps a residual branch `M`, and is meant to be used inside of a residual connection,
+/// e.g. `Residual<DropPath<M>>`.
+///
+/// 1. As [ModuleMut] with an [OwnedTape], the output of `M` for each item of the batch is kept
+///    with probability [Self::survival_prob], and multiplied by `0` otherwise. This means the
+///    residual connection only passes that item through. The output of `M` is still computed
+///    when the branch is dropped, so that all parameters receive (zero) gradients.
+///
+///    Like in other implementations, the first dimension of outputs with at least 2 dimensions
+///    is the batch dimension, so e.g. a single 3d image should be passed as a batch of 1.
+///    Outputs with fewer dimensions are a single item.
+/// 2. As [Module] with a [NoneTape], the output of `M` is scaled by [Self::survival_prob],
+///    which is its expected value during training.
+///
+/// Like [super::Dropout], [Module] is only implemented for [NoneTape] and [ModuleMut] only for
+/// [OwnedTape], so that the training and evaluation behavior can't be mixed up.
+///
+/// [Self::survival_prob] defaults to `0.9`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut model: Residual<DropPath<Linear<5, 5>>> = dev.build_module();
+/// model.0.survival_prob = 0.8;
+/// let _ = model.forward_mut(dev.zeros::<Rank1<5>>().trace());
+/// let _ = model.forward(dev.zeros::<Rank1<5>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DropPath<M> {
+    pub module: M,
+    pub survival_prob: f32,
+}
+
+impl<D: Device<E>, E: Dtype, M: GradientUpdate<D, E>> GradientUpdate<D, E> for DropPath<M> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        self.module.update(updater, unused)
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: ResetParams<D, E>> ResetParams<D, E> for DropPath<M> {
+    /// Builds [Self::module] and sets [Self::survival_prob] to `0.9`.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self {
+            module: ResetParams::try_build(device)?,
+            survival_prob: 0.9,
+        })
+    }
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.module.try_reset_params()
+    }
+}
+
+impl<S: Shape, D: Device<f32>, M> Module<Tensor<S, f32, D, NoneTape>> for DropPath<M>
+where
+    M: Module<Tensor<S, f32, D, NoneTape>>,
+    M::Output: std::ops::Mul<f32, Output = M::Output>,
+{
+    type Output = M::Output;
+    /// Scales the output of [DropPath::module] by [DropPath::survival_prob].
+    fn forward(&self, input: Tensor<S, f32, D, NoneTape>) -> Self::Output {
+        self.module.forward(input) * self.survival_prob
+    }
+}
+
+impl<S: Shape, S2: Shape, D: Device<f32>, M> ModuleMut<Tensor<S, f32, D, OwnedTape<D>>>
+    for DropPath<M>
+where
+    M: ModuleMut<Tensor<S, f32, D, OwnedTape<D>>, Output = Tensor<S2, f32, D, OwnedTape<D>>>,
+{
+    type Output = M::Output;
+    /// Keeps the output of [DropPath::module] for each item of the batch with probability
+    /// [DropPath::survival_prob].
+    fn forward_mut(&mut self, input: Tensor<S, f32, D, OwnedTape<D>>) -> Self::Output {
+        let out = self.module.forward_mut(input);
+        let shape = *out.shape();
+        let dims = shape.concrete();
+        let (batch, rest) = if S2::NUM_DIMS >= 2 {
+            (dims[0], dims.into_iter().skip(1).product())
+        } else {
+            (1, shape.num_elements())
+        };
+        let keep = KeepMask(self.survival_prob);
+        let mask = out.device.sample_like(&(batch,), keep);
+        let out =
+            out.reshape_like(&(batch, rest)) * mask.broadcast_like::<_, Axis<1>>(&(batch, rest));
+        out.reshape_like(&shape)
+    }
+}
+
+/// Samples `1.0` with probability `.0`, and `0.0` otherwise.
+#[derive(Debug, Clone, Copy)]
+struct KeepMask(f32);
+
+impl Distribution<f32> for KeepMask {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        if rng.gen::<f32>() < self.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{tests::SimpleUpdater, Linear, ModuleBuilder, Residual},
+        tests::TestDevice,
+    };
+
+    #[test]
+    fn test_drop_path_eval_scales() {
+        let dev: TestDevice = Default::default();
+        let mut model: DropPath<Linear<3, 3, _>> = dev.build_module();
+        model.survival_prob = 0.5;
+        let x = dev.sample_normal::<Rank2<2, 3>>();
+        let y = model.forward(x.clone());
+        assert_eq!(y.array(), (model.module.forward(x) * 0.5).array());
+    }
+
+    #[test]
+    fn test_drop_path_train_keeps_or_drops() {
+        let dev: TestDevice = Default::default();
+        let mut model: Residual<DropPath<Linear<3, 3, _>>> = dev.build_module();
+        model.0.survival_prob = 0.5;
+        let x = dev.sample_normal::<Rank1<3>>();
+        let kept = model.0.module.forward(x.clone()) + x.clone();
+
+        let mut num_kept = 0;
+        for _ in 0..100 {
+            let y = model.forward_mut(x.trace()).array();
+            if y == x.array() {
+                continue;
+            }
+            assert_eq!(y, kept.array());
+            num_kept += 1;
+        }
+        assert!((30..=70).contains(&num_kept), "{num_kept}");
+    }
+
+    #[test]
+    fn test_drop_path_per_sample() {
+        let dev: TestDevice = Default::default();
+        let mut model: Residual<DropPath<Linear<3, 3, _>>> = dev.build_module();
+        model.0.survival_prob = 0.5;
+        let x = dev.sample_normal::<Rank2<100, 3>>();
+        let kept = (model.0.module.forward(x.clone()) + x.clone()).array();
+        let y = model.forward_mut(x.trace()).array();
+        let x = x.array();
+
+        let mut num_kept = 0;
+        for i in 0..100 {
+            if y[i] == x[i] {
+                continue;
+            }
+            assert_eq!(y[i], kept[i]);
+            num_kept += 1;
+        }
+        assert!((30..=70).contains(&num_kept), "{num_kept}");
+    }
+
+    #[test]
+    fn test_drop_path_dropped_branch_has_grads() {
+        let dev: TestDevice = Default::default();
+        let mut model: DropPath<Linear<3, 3, _>> = dev.build_module();
+        model.survival_prob = 0.0;
+        let y = model.forward_mut(dev.sample_normal::<Rank1<3>>().trace());
+        assert_eq!(y.array(), [0.0; 3]);
+        let g = y.mean().backward();
+        assert_eq!(g.get(&model.module.weight).array(), [[0.0; 3]; 3]);
+
+        let mut updater = SimpleUpdater(g);
+        let mut unused = Default::default();
+        model.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{Module, ModuleMut, ResetParams};
+
+/// A [super::LayerNorm1D] whose size is only known at runtime, so it can be used with [super::Sequential]
+/// and [super::ModelConfig].
+///
+/// Acts on tensors of shape `(usize,)` or `(usize, usize)` (batched).
+///
+/// **Note**: [ResetParams::build()] creates a layer of size 0, use [DynLayerNorm1D::new()] instead.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model = DynLayerNorm1D::new(&dev, 5);
+/// let y = model.forward(dev.zeros_like(&(10, 5)));
+/// assert_eq!(y.shape(), &(10, 5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynLayerNorm1D<D: Device<f32> = Cpu> {
+    pub gamma: Tensor<(usize,), f32, D>,
+    pub beta: Tensor<(usize,), f32, D>,
+    pub epsilon: f32,
+}
+
+impl<D: Device<f32>> DynLayerNorm1D<D> {
+    /// Creates a layer norm of size `m`, see [ResetParams::try_reset_params()].
+    pub fn new(device: &D, m: usize) -> Self {
+        Self::try_new(device, m).unwrap()
+    }
+
+    /// Fallible version of [DynLayerNorm1D::new()]
+    pub fn try_new(device: &D, m: usize) -> Result<Self, D::Err> {
+        Ok(Self {
+            gamma: device.try_ones_like(&(m,))?,
+            beta: device.try_zeros_like(&(m,))?,
+            epsilon: 1e-5,
+        })
+    }
+}
+
+impl<D: Device<f32>> ResetParams<D, f32> for DynLayerNorm1D<D> {
+    /// Builds a layer norm of size 0.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Self::try_new(device, 0)
+    }
+
+    /// Fills [Self::gamma] with 1s and [Self::beta] with 0s.
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.gamma.try_fill_with_ones()?;
+        self.beta.try_fill_with_zeros()?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for DynLayerNorm1D<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.gamma.update(updater, unused)?;
+        self.beta.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize,), f32, D, T>> for DynLayerNorm1D<D> {
+    type Output = Tensor<(usize,), f32, D, T>;
+    fn forward(&self, x: Tensor<(usize,), f32, D, T>) -> Self::Output {
+        let m = x.shape().0;
+        let x = x.broadcast_like::<_, Axis<0>>(&(1, m));
+        x.layer_norm::<_, Axis<0>>(&self.gamma, &self.beta, self.epsilon)
+            .sum::<_, Axis<0>>()
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>> for DynLayerNorm1D<D> {
+    type Output = Tensor<(usize, usize), f32, D, T>;
+    fn forward(&self, x: Tensor<(usize, usize), f32, D, T>) -> Self::Output {
+        x.layer_norm::<_, Axis<0>>(&self.gamma, &self.beta, self.epsilon)
+    }
+}
+
+impl<T, D: Device<f32>> ModuleMut<T> for DynLayerNorm1D<D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{LayerNorm1D, ModuleBuilder};
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_dyn_layer_norm_matches_layer_norm() {
+        let dev: TestDevice = Default::default();
+        let m: LayerNorm1D<5, _> = dev.build_module();
+        let mut m_dyn = DynLayerNorm1D::new(&dev, 5);
+        m_dyn.gamma.copy_from(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        m_dyn.reset_params();
+
+        let x = dev.sample_normal::<Rank2<3, 5>>();
+        let mut x_dyn = dev.zeros_like(&(3, 5));
+        x_dyn.copy_from(&x.as_vec());
+
+        let r = m.forward(x.trace());
+        let r_dyn = m_dyn.forward_mut(x_dyn.trace());
+        assert_close(&r_dyn.as_vec(), &r.as_vec());
+
+        let mut x0 = dev.zeros_like(&(5,));
+        x0.copy_from(&x.as_vec()[..5]);
+        assert_close(&m_dyn.forward(x0).as_vec(), &r.as_vec()[..5].to_vec());
+
+        let g = r.exp().mean().backward();
+        let g_dyn = r_dyn.exp().mean().backward();
+        assert_close(&g_dyn.get(&m_dyn.gamma).as_vec(), &g.get(&m.gamma).as_vec());
+        assert_close(&g_dyn.get(&m_dyn.beta).as_vec(), &g.get(&m.beta).as_vec());
+    }
+}
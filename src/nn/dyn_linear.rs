@@ -0,0 +1,184 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use super::module::{Module, ModuleMut, ResetParams};
+
+/// A [super::Linear] whose sizes are only known at runtime, so it can be used with [super::Sequential]
+/// and [super::ModelConfig].
+///
+/// Initializes [Self::weight] and [Self::bias] from a Uniform distribution
+/// between [-1 / sqrt(I), 1 / sqrt(I)].
+///
+/// Acts on tensors of shape `(usize,)` or `(usize, usize)` (batched).
+///
+/// **Note**: [ResetParams::build()] creates a layer with 0 inputs and 0 outputs,
+/// use [DynLinear::new()] instead.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model = DynLinear::new(&dev, 5, 2);
+/// // single item forward
+/// let y = model.forward(dev.zeros_like(&(5,)));
+/// assert_eq!(y.shape(), &(2,));
+/// // batched forward
+/// let y = model.forward(dev.zeros_like(&(10, 5)));
+/// assert_eq!(y.shape(), &(10, 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynLinear<D: Device<f32> = Cpu> {
+    /// Weight matrix, shape (O, I)
+    pub weight: Tensor<(usize, usize), f32, D>,
+
+    /// Bias vector, shape (O, )
+    pub bias: Tensor<(usize,), f32, D>,
+}
+
+impl<D: Device<f32>> DynLinear<D> {
+    /// Creates a layer with `inp` inputs and `out` outputs.
+    pub fn new(device: &D, inp: usize, out: usize) -> Self {
+        Self::try_new(device, inp, out).unwrap()
+    }
+
+    /// Fallible version of [DynLinear::new()]
+    pub fn try_new(device: &D, inp: usize, out: usize) -> Result<Self, D::Err> {
+        let mut layer = Self {
+            weight: device.try_zeros_like(&(out, inp))?,
+            bias: device.try_zeros_like(&(out,))?,
+        };
+        layer.try_reset_params()?;
+        Ok(layer)
+    }
+
+    /// The number of inputs.
+    pub fn inp(&self) -> usize {
+        self.weight.shape().1
+    }
+
+    /// The number of outputs.
+    pub fn out(&self) -> usize {
+        self.weight.shape().0
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for DynLinear<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)?;
+        self.bias.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> ResetParams<D, f32> for DynLinear<D> {
+    /// Builds a layer with 0 inputs and 0 outputs.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Self::try_new(device, 0, 0)
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        let bound: f32 = 1.0 / (self.inp().max(1) as f32).sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.weight.try_fill_with_distr(distr)?;
+        self.bias.try_fill_with_distr(distr)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize,), f32, D, T>> for DynLinear<D> {
+    type Output = Tensor<(usize,), f32, D, T>;
+
+    /// Computes `sum(x * weight, axis=1) + bias`, since [matmul()] requires a compile time inner dimension.
+    fn forward(&self, x: Tensor<(usize,), f32, D, T>) -> Self::Output {
+        let shape = *self.weight.shape();
+        let w = self.weight.retaped::<T>();
+        let o = (x.broadcast_like::<_, Axis<0>>(&shape) * w).sum::<_, Axis<1>>();
+        o + self.bias.retaped::<T>()
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>> for DynLinear<D> {
+    type Output = Tensor<(usize, usize), f32, D, T>;
+
+    /// Computes `sum(x * weight, axis=2) + bias`, since [matmul()] requires a compile time inner dimension.
+    fn forward(&self, x: Tensor<(usize, usize), f32, D, T>) -> Self::Output {
+        let batch = x.shape().0;
+        let shape = (batch, self.out(), self.inp());
+        let w = self
+            .weight
+            .retaped::<T>()
+            .broadcast_like::<_, Axis<0>>(&shape);
+        let o = (x.broadcast_like::<_, Axis<1>>(&shape) * w).sum::<_, Axis<2>>();
+        o + self
+            .bias
+            .retaped::<T>()
+            .broadcast_like::<_, Axis<0>>(&(batch, self.out()))
+    }
+}
+
+impl<T, D: Device<f32>> ModuleMut<T> for DynLinear<D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gradients::OwnedTape,
+        nn::{Linear, ModuleBuilder},
+        tests::{assert_close, TestDevice},
+    };
+
+    #[test]
+    fn test_dyn_linear_matches_linear() {
+        let dev: TestDevice = Default::default();
+        let linear: Linear<3, 2, _> = dev.build_module();
+        let mut dyn_linear = DynLinear::new(&dev, 3, 2);
+        assert_eq!(dyn_linear.inp(), 3);
+        assert_eq!(dyn_linear.out(), 2);
+        dyn_linear.weight.copy_from(&linear.weight.as_vec());
+        dyn_linear.bias.copy_from(&linear.bias.as_vec());
+
+        let x = dev.sample_normal::<Rank2<4, 3>>();
+        let mut x_dyn = dev.zeros_like(&(4, 3));
+        x_dyn.copy_from(&x.as_vec());
+
+        let y = linear.forward(x.trace());
+        let y_dyn = dyn_linear.forward(x_dyn.trace());
+        assert_close(&y_dyn.as_vec(), &y.as_vec());
+
+        let mut x0 = dev.zeros_like(&(3,));
+        x0.copy_from(&x.as_vec()[..3]);
+        assert_close(&dyn_linear.forward(x0).as_vec(), &y.as_vec()[..2].to_vec());
+
+        let g = y.exp().mean().backward();
+        let g_dyn = y_dyn.exp().mean().backward();
+        assert_close(
+            &g_dyn.get(&dyn_linear.weight).as_vec(),
+            &g.get(&linear.weight).as_vec(),
+        );
+        assert_close(
+            &g_dyn.get(&dyn_linear.bias).as_vec(),
+            &g.get(&linear.bias).as_vec(),
+        );
+        let _: Tensor<(usize, usize), f32, _, OwnedTape<_>> = dyn_linear.forward_mut(x_dyn.trace());
+    }
+
+    #[test]
+    fn test_dyn_linear_build_is_empty() {
+        let dev: TestDevice = Default::default();
+        let mut m: DynLinear<_> = dev.build_module();
+        assert_eq!(m.weight.shape(), &(0, 0));
+        m.reset_params();
+    }
+}
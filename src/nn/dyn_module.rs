@@ -0,0 +1,105 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{AnyTensor, AnyTensorError, DeviceStorage, HasErr, Tensor},
+    tensor_ops::ReshapeKernel,
+};
+
+use super::Module;
+
+/// An object safe module that maps an [AnyTensor] to an [AnyTensor], so modules with
+/// different input and output types can be stored together, e.g. in a
+/// `BTreeMap<String, Box<dyn DynModule>>` of models that are selected at runtime.
+///
+/// Implement it for a [Module] by wrapping it in [Erased], which converts the input into the
+/// tensor type the module expects, and returns an error if the dtype or shape don't match.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use std::collections::BTreeMap;
+/// # let dev: Cpu = Default::default();
+/// let mut registry: BTreeMap<&str, Box<dyn DynModule>> = BTreeMap::new();
+/// let mlp: (Linear<3, 5>, ReLU, Linear<5, 2>) = dev.build_module();
+/// registry.insert("mlp", Box::new(Erased::<_, Tensor<(usize, Const<3>)>>::new(mlp)));
+/// let linear = DynLinear::new(&dev, 6, 4);
+/// registry.insert("linear", Box::new(Erased::<_, Tensor<(usize, usize)>>::new(linear)));
+///
+/// let x: Tensor<Rank2<8, 3>> = dev.sample_normal();
+/// let y = registry["mlp"].forward_any(x.into()).unwrap();
+/// assert_eq!(y.dims(), &[8, 2]);
+///
+/// let x: Tensor<Rank2<2, 6>> = dev.sample_normal();
+/// let y = registry["linear"].forward_any(x.into()).unwrap();
+/// assert_eq!(y.dims(), &[2, 4]);
+/// assert!(registry["mlp"].forward_any(y).is_err());
+/// ```
+pub trait DynModule<D: DeviceStorage = crate::tensor::Cpu>: Send + Sync {
+    /// Calls the module on `x`, see [Erased].
+    fn forward_any(&self, x: AnyTensor<D>) -> Result<AnyTensor<D>, AnyTensorError<D::Err>>;
+}
+
+/// Wraps a [Module] that acts on `Input` so it implements [DynModule]. The [AnyTensor]s passed
+/// to [DynModule::forward_any()] are converted with [AnyTensor::try_into_tensor()], so
+/// `Input` can have `usize` dimensions to accept inputs of any size, like a batch dimension.
+#[derive(Debug, Clone)]
+pub struct Erased<M, Input> {
+    pub module: M,
+    marker: std::marker::PhantomData<fn(Input)>,
+}
+
+impl<M, Input> Erased<M, Input> {
+    pub fn new(module: M) -> Self {
+        Self {
+            module,
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<M, S, E, D, S2, E2> DynModule<D> for Erased<M, Tensor<S, E, D>>
+where
+    M: Send + Sync + Module<Tensor<S, E, D>, Output = Tensor<S2, E2, D>>,
+    S: Shape,
+    E: Dtype,
+    D: ReshapeKernel<E> + ReshapeKernel<E2>,
+    S2: Shape,
+    E2: Dtype,
+{
+    fn forward_any(
+        &self,
+        x: AnyTensor<D>,
+    ) -> Result<AnyTensor<D>, AnyTensorError<<D as HasErr>::Err>> {
+        let y = self.module.forward(x.try_into_tensor()?);
+        AnyTensor::try_from_tensor(y).map_err(AnyTensorError::Device)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, shapes::*, tensor::*, tests::TestDevice};
+
+    #[test]
+    fn test_erased_matches_module() {
+        let dev: TestDevice = Default::default();
+        let model: (Linear<3, 4, _>, Tanh) = dev.build_module();
+        let erased = Erased::<_, Tensor<(usize, Const<3>), f32, _>>::new(model.clone());
+        let boxed: std::boxed::Box<dyn DynModule<_>> = std::boxed::Box::new(erased);
+
+        let x: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+        let y = boxed.forward_any(x.clone().into()).unwrap();
+        let y: Tensor<Rank2<5, 4>, f32, _> = y.into_tensor().unwrap();
+        assert_eq!(y.array(), model.forward(x).array());
+
+        let x: Tensor<Rank2<5, 2>, f32, _> = dev.sample_normal();
+        assert!(matches!(
+            boxed.forward_any(x.into()),
+            Err(AnyTensorError::ShapeMismatch { .. })
+        ));
+        let x: Tensor<Rank2<5, 3>, f64, _> = dev.zeros();
+        assert!(matches!(
+            boxed.forward_any(x.into()),
+            Err(AnyTensorError::DtypeMismatch { .. })
+        ));
+    }
+}
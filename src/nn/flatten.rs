@@ -2,13 +2,16 @@ use super::{Module, NonMutableModule, ZeroSizedModule};
 
 use crate::{gradients::Tape, shapes::*, tensor::Tensor, tensor_ops::*};
 
-/// **Requires Nightly** Flattens 3d tensors to 1d, and 4d tensors to 2d.
+/// **Requires Nightly or `stable-fallback`** Flattens 3d tensors to 1d, and 4d tensors to 2d.
+///
+/// With `stable-fallback` (and without `nightly`) the flattened dimension is a `usize`.
 #[derive(Default, Clone, Copy)]
 pub struct Flatten2D;
 
 impl ZeroSizedModule for Flatten2D {}
 impl NonMutableModule for Flatten2D {}
 
+#[cfg(feature = "nightly")]
 impl<const C: usize, const H: usize, const W: usize, D: Device<E>, E: Dtype, T: Tape<D>>
     Module<Tensor<Rank3<C, H, W>, E, D, T>> for Flatten2D
 where
@@ -20,6 +23,7 @@ where
     }
 }
 
+#[cfg(feature = "nightly")]
 impl<const B: usize, const C: usize, const H: usize, const W: usize, D, E: Dtype, T: Tape<D>>
     Module<Tensor<Rank4<B, C, H, W>, E, D, T>> for Flatten2D
 where
@@ -32,6 +36,28 @@ where
     }
 }
 
+#[cfg(not(feature = "nightly"))]
+impl<C: Dim, H: Dim, W: Dim, D: Device<E>, E: Dtype, T: Tape<D>> Module<Tensor<(C, H, W), E, D, T>>
+    for Flatten2D
+{
+    type Output = Tensor<(usize,), E, D, T>;
+    fn forward(&self, input: Tensor<(C, H, W), E, D, T>) -> Self::Output {
+        let numel = input.shape().num_elements();
+        input.reshape_like(&(numel,))
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<B: Dim, C: Dim, H: Dim, W: Dim, D: Device<E>, E: Dtype, T: Tape<D>>
+    Module<Tensor<(B, C, H, W), E, D, T>> for Flatten2D
+{
+    type Output = Tensor<(B, usize), E, D, T>;
+    fn forward(&self, input: Tensor<(B, C, H, W), E, D, T>) -> Self::Output {
+        let &(b, c, h, w) = input.shape();
+        input.reshape_like(&(b, c.size() * h.size() * w.size()))
+    }
+}
+
 #[cfg(feature = "nightly")]
 #[cfg(test)]
 mod tests {
@@ -45,3 +71,19 @@ mod tests {
         let _: Tensor<Rank2<5, 24>, _, _> = Flatten2D.forward_mut(dev.zeros::<Rank4<5, 4, 3, 2>>());
     }
 }
+
+#[cfg(not(feature = "nightly"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::ModuleMut, tensor::ZerosTensor, tests::TestDevice};
+
+    #[test]
+    fn test_flattens() {
+        let dev: TestDevice = Default::default();
+        let x = Flatten2D.forward_mut(dev.zeros::<Rank3<10, 5, 2>>());
+        assert_eq!(x.shape(), &(100,));
+        let x = Flatten2D.forward_mut(dev.zeros::<Rank4<5, 4, 3, 2>>());
+        assert_eq!(x.shape(), &(Const::<5>, 24));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{
+    module::{Module, ModuleMut, ResetParams},
+    GeLU, Linear, ReLU,
+};
+
+/// An activation function that can be fused into the same operation as a [Linear]
+/// layer. See [FusedLinear].
+#[allow(clippy::type_complexity)]
+pub trait FusedActivation<D: Device<f32>>: Default + Clone {
+    /// Computes `activation(x * weight^T + bias)`.
+    fn try_linear_forward<B: Dim, const I: usize, const O: usize, T: Tape<D>>(
+        x: Tensor<(B, Const<I>), f32, D, T>,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Tensor<(B, Const<O>), f32, D, T>, D::Err>;
+}
+
+#[allow(clippy::type_complexity)]
+impl<D: Device<f32>> FusedActivation<D> for ReLU {
+    fn try_linear_forward<B: Dim, const I: usize, const O: usize, T: Tape<D>>(
+        x: Tensor<(B, Const<I>), f32, D, T>,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Tensor<(B, Const<O>), f32, D, T>, D::Err> {
+        x.try_linear_relu(weight, bias)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl<D: Device<f32>> FusedActivation<D> for GeLU {
+    fn try_linear_forward<B: Dim, const I: usize, const O: usize, T: Tape<D>>(
+        x: Tensor<(B, Const<I>), f32, D, T>,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Tensor<(B, Const<O>), f32, D, T>, D::Err> {
+        x.try_linear_gelu(weight, bias)
+    }
+}
+
+/// A [Linear] layer followed by the activation `A`, computed as a single fused
+/// operation (e.g. [Tensor::linear_relu]). This has the same parameters and
+/// produces the same results as `(Linear<I, O>, A)`, but uses less memory and time
+/// during training.
+///
+/// This is never swapped in automatically: `(Linear<I, O>, A)` in a tuple is still computed
+/// as two ops, so use this type in place of the pair to get the fused kernel.
+///
+/// Convert an existing `(Linear, A)` pair with [From]:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model: (Linear<5, 3>, ReLU) = dev.build_module();
+/// let fused: LinearReLU<5, 3> = model.into();
+/// let _: Tensor<Rank2<10, 3>> = fused.forward(dev.zeros::<Rank2<10, 5>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FusedLinear<const I: usize, const O: usize, A, D: Device<f32> = Cpu> {
+    /// Transposed weight matrix, shape (I, O)
+    pub weight: Tensor<Rank2<O, I>, f32, D>,
+
+    /// Bias vector, shape (O, )
+    pub bias: Tensor<Rank1<O>, f32, D>,
+
+    /// The activation applied after the linear transformation
+    pub activation: A,
+}
+
+/// A [Linear] layer fused with [ReLU]. See [FusedLinear].
+pub type LinearReLU<const I: usize, const O: usize, D = Cpu> = FusedLinear<I, O, ReLU, D>;
+
+/// A [Linear] layer fused with [GeLU]. See [FusedLinear].
+pub type LinearGeLU<const I: usize, const O: usize, D = Cpu> = FusedLinear<I, O, GeLU, D>;
+
+impl<const I: usize, const O: usize, A, D: Device<f32>> From<(Linear<I, O, D>, A)>
+    for FusedLinear<I, O, A, D>
+{
+    fn from((linear, activation): (Linear<I, O, D>, A)) -> Self {
+        Self {
+            weight: linear.weight,
+            bias: linear.bias,
+            activation,
+        }
+    }
+}
+
+impl<const I: usize, const O: usize, A, D: Device<f32>> From<FusedLinear<I, O, A, D>>
+    for (Linear<I, O, D>, A)
+{
+    fn from(fused: FusedLinear<I, O, A, D>) -> Self {
+        let linear = Linear {
+            weight: fused.weight,
+            bias: fused.bias,
+        };
+        (linear, fused.activation)
+    }
+}
+
+impl<const I: usize, const O: usize, A, D: Device<f32>> GradientUpdate<D, f32>
+    for FusedLinear<I, O, A, D>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)?;
+        self.bias.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, A: Default, D: Device<f32>> ResetParams<D, f32>
+    for FusedLinear<I, O, A, D>
+{
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        let linear: Linear<I, O, D> = ResetParams::try_build(device)?;
+        Ok((linear, A::default()).into())
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        let mut linear = Linear {
+            weight: self.weight.clone(),
+            bias: self.bias.clone(),
+        };
+        linear.try_reset_params()?;
+        self.weight = linear.weight;
+        self.bias = linear.bias;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, A, D, T> Module<Tensor<Rank1<I>, f32, D, T>>
+    for FusedLinear<I, O, A, D>
+where
+    A: FusedActivation<D>,
+    D: Device<f32>,
+    T: Tape<D>,
+{
+    type Output = Tensor<Rank1<O>, f32, D, T>;
+    fn forward(&self, x: Tensor<Rank1<I>, f32, D, T>) -> Self::Output {
+        let x = x.broadcast::<Rank2<1, I>, _>();
+        A::try_linear_forward(x, &self.weight, &self.bias)
+            .unwrap()
+            .sum()
+    }
+}
+
+impl<B: Dim, const I: usize, const O: usize, A, D, T> Module<Tensor<(B, Const<I>), f32, D, T>>
+    for FusedLinear<I, O, A, D>
+where
+    A: FusedActivation<D>,
+    D: Device<f32>,
+    T: Tape<D>,
+{
+    type Output = Tensor<(B, Const<O>), f32, D, T>;
+    fn forward(&self, x: Tensor<(B, Const<I>), f32, D, T>) -> Self::Output {
+        A::try_linear_forward(x, &self.weight, &self.bias).unwrap()
+    }
+}
+
+impl<T, const I: usize, const O: usize, A, D: Device<f32>> ModuleMut<T> for FusedLinear<I, O, A, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gradients::OwnedTape,
+        nn::{tests::SimpleUpdater, ModuleBuilder},
+        tests::{assert_close, TestDevice},
+        unique_id::HasUniqueId,
+    };
+
+    #[test]
+    fn test_fused_linear_matches_unfused() {
+        let dev: TestDevice = Default::default();
+        let unfused: (Linear<5, 3, _>, ReLU) = dev.build_module();
+        let fused: LinearReLU<5, 3, _> = unfused.clone().into();
+
+        let x: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+        let y1 = unfused.forward(x.trace());
+        let y2 = fused.forward(x.trace());
+        assert_close(&y1.array(), &y2.array());
+
+        let g1 = y1.square().mean().backward();
+        let g2 = y2.square().mean().backward();
+        assert_close(&g1.get(&x).array(), &g2.get(&x).array());
+        assert_close(
+            &g1.get(&unfused.0.weight).array(),
+            &g2.get(&fused.weight).array(),
+        );
+        assert_close(
+            &g1.get(&unfused.0.bias).array(),
+            &g2.get(&fused.bias).array(),
+        );
+
+        let x1: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let y1 = unfused.forward(x1.clone());
+        let y2 = fused.forward(x1);
+        assert_close(&y1.array(), &y2.array());
+    }
+
+    #[test]
+    fn test_fused_linear_gelu_matches_unfused() {
+        let dev: TestDevice = Default::default();
+        let unfused: (Linear<5, 3, _>, GeLU) = dev.build_module();
+        let fused: LinearGeLU<5, 3, _> = unfused.clone().into();
+
+        let x: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+        let y1 = unfused.forward(x.trace());
+        let y2 = fused.forward(x.trace());
+        assert_close(&y1.array(), &y2.array());
+
+        let g1 = y1.square().mean().backward();
+        let g2 = y2.square().mean().backward();
+        assert_close(&g1.get(&x).array(), &g2.get(&x).array());
+        assert_close(
+            &g1.get(&unfused.0.weight).array(),
+            &g2.get(&fused.weight).array(),
+        );
+    }
+
+    #[test]
+    fn test_fused_linear_reset_params() {
+        let dev: TestDevice = Default::default();
+        let mut model: LinearReLU<4, 3, _> = dev.build_module();
+        let (weight_id, bias_id) = (*model.weight.id(), *model.bias.id());
+        model.weight.fill_with_zeros();
+        model.reset_params();
+        assert_eq!(model.weight.id(), &weight_id);
+        assert_eq!(model.bias.id(), &bias_id);
+        assert!(model
+            .weight
+            .as_vec()
+            .iter()
+            .all(|&w| w != 0.0 && w.abs() <= 0.5));
+        assert!(model.bias.as_vec().iter().all(|&b| b.abs() <= 0.5));
+    }
+
+    #[test]
+    fn test_fused_linear_missing_gradients() {
+        let dev: TestDevice = Default::default();
+        let mut model: LinearReLU<5, 3, _> = dev.build_module();
+        let mut g: SimpleUpdater<_> = Default::default();
+
+        // no gradients present
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert_eq!(&unused.ids, &[*model.weight.id(), *model.bias.id()]);
+
+        g.0.try_alloc_for(&model.weight).unwrap();
+        g.0.try_alloc_for(&model.bias).unwrap();
+
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert!(unused.is_empty());
+
+        let _: Tensor<Rank1<3>, f32, _, OwnedTape<_>> =
+            model.forward_mut(dev.zeros::<Rank1<5>>().traced());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{Module, ModuleMut, ResetParams};
+
+/// A residual connection around `F` where the output of `F` is multiplied by a gate `G`: `x + G(x) * F(x)`.
+///
+/// `G` usually ends in an activation like [super::Sigmoid], so the gate is between 0 and 1, and the
+/// block can learn how much of `F` to add to the input.
+///
+/// # Generics
+/// - `F`: The underlying module to do a skip connection around.
+/// - `G`: The gate module, which must produce the same output as `F`.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let module: GatedResidual<ReLU, Sigmoid> = dev.build_module();
+/// let x = dev.tensor([-1.0, 0.0, 1.0]);
+/// let y = module.forward(x);
+/// assert_eq!(y.array(), [-1.0, 0.0, 1.7310586]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GatedResidual<F, G> {
+    pub f: F,
+    pub g: G,
+}
+
+impl<D: Device<E>, E: Dtype, F: GradientUpdate<D, E>, G: GradientUpdate<D, E>> GradientUpdate<D, E>
+    for GatedResidual<F, G>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        self.f.update(updater, unused)?;
+        self.g.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<E>, E: Dtype, F: ResetParams<D, E>, G: ResetParams<D, E>> ResetParams<D, E>
+    for GatedResidual<F, G>
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Ok(Self {
+            f: ResetParams::try_build(device)?,
+            g: ResetParams::try_build(device)?,
+        })
+    }
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        self.f.try_reset_params()?;
+        self.g.try_reset_params()?;
+        Ok(())
+    }
+}
+
+impl<T, F: Module<T, Output = T>, G: Module<T, Output = T>> Module<T> for GatedResidual<F, G>
+where
+    T: SplitTape + std::ops::Add<T, Output = T> + std::ops::Mul<T, Output = T>,
+{
+    type Output = T;
+    fn forward(&self, x: T) -> Self::Output {
+        let gate = self.g.forward(x.with_empty_tape());
+        gate * self.f.forward(x.with_empty_tape()) + x
+    }
+}
+
+impl<T, F: ModuleMut<T, Output = T>, G: ModuleMut<T, Output = T>> ModuleMut<T>
+    for GatedResidual<F, G>
+where
+    T: SplitTape + std::ops::Add<T, Output = T> + std::ops::Mul<T, Output = T>,
+{
+    type Output = T;
+    fn forward_mut(&mut self, x: T) -> Self::Output {
+        let gate = self.g.forward_mut(x.with_empty_tape());
+        gate * self.f.forward_mut(x.with_empty_tape()) + x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, ModuleBuilder, Sigmoid};
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_gated_residual_gradients() {
+        let dev: TestDevice = Default::default();
+
+        let model: GatedResidual<Linear<2, 2, _>, (Linear<2, 2, _>, Sigmoid)> = dev.build_module();
+
+        let x = dev.sample_normal::<Rank2<4, 2>>();
+        let y = model.forward(x.trace());
+
+        let f = model.f.forward(x.clone());
+        let gate = model.g.forward(x.clone());
+        assert_close(&y.array(), &(gate.clone() * f.clone() + x.clone()).array());
+
+        let g = y.sum().backward();
+        // d/db_f = gate
+        assert_close(
+            &g.get(&model.f.bias).array(),
+            &gate.clone().sum::<Rank1<2>, _>().array(),
+        );
+        // d/db_g = f * gate * (1 - gate)
+        let dgate = f * gate.clone() * (gate.negate() + 1.0);
+        assert_close(
+            &g.get(&model.g.0.bias).array(),
+            &dgate.sum::<Rank1<2>, _>().array(),
+        );
+    }
+}
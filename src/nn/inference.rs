@@ -0,0 +1,572 @@
+use crate::{gradients::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::linear::Bias1D;
+use super::*;
+
+use core::any::Any;
+
+/// Converts a model into an equivalent model that is faster for repeated [Module::forward()]
+/// calls without gradients, e.g. in serving scenarios. The result can't be trained anymore.
+///
+/// The conversions are:
+/// - [Linear] (and [SpectralNorm]/[WeightNorm] of [Linear]) become [InferenceLinear], which stores
+///   the pre-transposed (and normalized) weight. [LoRALinear] becomes an [InferenceLinear] with the
+///   adapter merged into the weight. [SpectralNorm]/[WeightNorm] of [Conv2D] become a
+///   [Conv2D] with the normalized weight.
+/// - [BatchNorm2D] becomes [InferenceBatchNorm2D], which folds the running statistics into a
+///   per channel scale and bias. In a tuple, a [BatchNorm2D] right after a [Conv2D] is folded
+///   into the convolution (see [Conv2D::fold_batchnorm()]), and becomes an identity.
+/// - [Dropout] and [DropoutOneIn] become [Identity].
+/// - [DropPath] becomes [Scaled] by [DropPath::survival_prob].
+/// - Containers like tuples, [Repeated] and [Residual] convert their modules.
+/// - Everything else stays the same.
+///
+/// Use [IntoInference::inference_mode()] to also wrap the result in [Inference], which
+/// only accepts tensors without a tape.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<5, 10>, ReLU, Dropout, DropPath<Linear<10, 10>>, Linear<10, 2>);
+/// let model: Model = dev.build_module();
+/// let x: Tensor<Rank2<3, 5>> = dev.sample_normal();
+/// let y = model.forward(x.clone());
+///
+/// let model = model.inference_mode();
+/// let _: &(InferenceLinear<5, 10>, ReLU, Identity, Scaled<InferenceLinear<10, 10>>, InferenceLinear<10, 2>) = &model.0;
+/// let _ = model.forward(x);
+/// ```
+pub trait IntoInference<D: Device<E>, E: Dtype>: Sized {
+    type Inference;
+
+    /// Converts `self` into its inference version.
+    fn into_inference(self) -> Self::Inference {
+        self.try_into_inference().unwrap()
+    }
+
+    /// Fallible version of [IntoInference::into_inference()]
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err>;
+
+    /// Called by tuples with the converted module `next` that comes right after this one, so
+    /// it can be folded into `this`, like a [BatchNorm2D] after a [Conv2D]. Folding must turn
+    /// `next` into an identity. Does nothing by default.
+    fn try_fold_next(_this: &mut Self::Inference, _next: &mut dyn Any) -> Result<(), D::Err> {
+        Ok(())
+    }
+
+    /// Converts `self` and wraps it in [Inference].
+    fn inference_mode(self) -> Inference<Self::Inference> {
+        Inference(self.into_inference())
+    }
+}
+
+/// A model that can only be called with [Module::forward()] on tensors without a tape
+/// ([NoneTape]). It implements neither [ModuleMut] nor [crate::optim::GradientUpdate], so
+/// it can't accidentally be used for training.
+///
+/// See [IntoInference::inference_mode()].
+#[derive(Debug, Clone)]
+pub struct Inference<M>(pub M);
+
+impl<S: Shape, E: Dtype, D: DeviceStorage, M> Module<Tensor<S, E, D, NoneTape>> for Inference<M>
+where
+    M: Module<Tensor<S, E, D, NoneTape>>,
+{
+    type Output = M::Output;
+    fn forward(&self, x: Tensor<S, E, D, NoneTape>) -> Self::Output {
+        self.0.forward(x)
+    }
+}
+
+/// Returns its input unchanged. This is what dropout becomes with [IntoInference].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Identity;
+
+impl ZeroSizedModule for Identity {}
+impl NonMutableModule for Identity {}
+
+impl<T> Module<T> for Identity {
+    type Output = T;
+    fn forward(&self, x: T) -> Self::Output {
+        x
+    }
+}
+
+/// A [Linear] for inference, where [Self::weight] is stored transposed so that [Module::forward()]
+/// does a plain [matmul()]. Created with [IntoInference].
+#[derive(Debug, Clone)]
+pub struct InferenceLinear<const I: usize, const O: usize, D: Device<f32> = Cpu> {
+    /// Weight matrix, shape (I, O)
+    pub weight: Tensor<Rank2<I, O>, f32, D>,
+
+    /// Bias vector, shape (O, )
+    pub bias: Tensor<Rank1<O>, f32, D>,
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> NonMutableModule for InferenceLinear<I, O, D> {}
+
+impl<const I: usize, const O: usize, D: Device<f32>, T> Module<T> for InferenceLinear<I, O, D>
+where
+    T: SplitTape + TryMatMul<Tensor<Rank2<I, O>, f32, D, T::Tape>>,
+    T::Tape: Tape<D>,
+    for<'a> Bias1D<'a, O, D>: Module<T::Output, Output = T::Output>,
+{
+    type Output = T::Output;
+    fn forward(&self, x: T) -> Self::Output {
+        let o = x.matmul(self.weight.retaped::<T::Tape>());
+        Bias1D { beta: &self.bias }.forward(o)
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> InferenceLinear<I, O, D> {
+    /// Transposes `weight` of shape `(O, I)`.
+    fn try_new(
+        weight: Tensor<Rank2<O, I>, f32, D>,
+        bias: Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Self, D::Err> {
+        // reshaping the permuted weight copies it into a contiguous layout on the device
+        let weight = weight
+            .try_permute::<Rank2<I, O>, Axes2<1, 0>>()?
+            .try_reshape_like(&(Const, Const))?;
+        Ok(Self { weight, bias })
+    }
+}
+
+/// A [BatchNorm2D] for inference, where the running statistics are folded into
+/// [Self::scale] and [Self::bias]. Created with [IntoInference].
+///
+/// After it is folded into the previous [Conv2D], [Self::scale] is 1, [Self::bias] is 0,
+/// and [Module::forward()] returns its input unchanged.
+#[derive(Debug, Clone)]
+pub struct InferenceBatchNorm2D<const C: usize, D: Device<f32> = Cpu> {
+    /// `scale / sqrt(running_var + epsilon)`
+    pub scale: Tensor<Rank1<C>, f32, D>,
+    /// `bias - running_mean * scale`
+    pub bias: Tensor<Rank1<C>, f32, D>,
+    folded: bool,
+}
+
+impl<const C: usize, D: Device<f32>> NonMutableModule for InferenceBatchNorm2D<C, D> {}
+
+impl<const C: usize, D: Device<f32>> InferenceBatchNorm2D<C, D> {
+    fn affine<S: Shape, Ax: Axes, T: Tape<D>>(
+        &self,
+        x: Tensor<S, f32, D, T>,
+    ) -> Tensor<S, f32, D, T>
+    where
+        Rank1<C>: BroadcastShapeTo<S, Ax>,
+    {
+        if self.folded {
+            return x;
+        }
+        let shape = *x.shape();
+        x * self.scale.clone().broadcast_like(&shape) + self.bias.clone().broadcast_like(&shape)
+    }
+}
+
+impl<const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(Const<C>, H, W), f32, D, T>> for InferenceBatchNorm2D<C, D>
+{
+    type Output = Tensor<(Const<C>, H, W), f32, D, T>;
+    fn forward(&self, x: Tensor<(Const<C>, H, W), f32, D, T>) -> Self::Output {
+        self.affine(x)
+    }
+}
+
+impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(B, Const<C>, H, W), f32, D, T>> for InferenceBatchNorm2D<C, D>
+{
+    type Output = Tensor<(B, Const<C>, H, W), f32, D, T>;
+    fn forward(&self, x: Tensor<(B, Const<C>, H, W), f32, D, T>) -> Self::Output {
+        self.affine(x)
+    }
+}
+
+impl<M: NonMutableModule, D: Device<E>, E: Dtype> IntoInference<D, E> for M {
+    type Inference = Self;
+    fn try_into_inference(self) -> Result<Self, D::Err> {
+        Ok(self)
+    }
+}
+
+macro_rules! inference_is_self {
+    ($Ty:ty, [$($generics:tt)*]) => {
+        impl<$($generics)*> IntoInference<D, f32> for $Ty {
+            type Inference = Self;
+            fn try_into_inference(self) -> Result<Self, D::Err> {
+                Ok(self)
+            }
+        }
+    };
+}
+
+inference_is_self!(LayerNorm1D<M, D>, [const M: usize, D: Device<f32>]);
+inference_is_self!(DynLinear<D>, [D: Device<f32>]);
+inference_is_self!(DynLayerNorm1D<D>, [D: Device<f32>]);
+inference_is_self!(RunningNorm1D<M, D>, [const M: usize, D: Device<f32>]);
+inference_is_self!(FusedLinear<I, O, A, D>, [const I: usize, const O: usize, A, D: Device<f32>]);
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    IntoInference<D, f32> for Conv2D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    type Inference = Self;
+    fn try_into_inference(self) -> Result<Self, D::Err> {
+        Ok(self)
+    }
+
+    /// Folds an [InferenceBatchNorm2D] into the weight and bias.
+    fn try_fold_next(this: &mut Self, next: &mut dyn Any) -> Result<(), D::Err> {
+        if let Some(bn) = next.downcast_mut::<InferenceBatchNorm2D<O, D>>() {
+            if !bn.folded {
+                *this = this.clone().try_fold_affine(&bn.scale, &bn.bias)?;
+                bn.scale.try_fill_with_ones()?;
+                bn.bias.try_fill_with_zeros()?;
+                bn.folded = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> IntoInference<D, f32>
+    for LoRALinear<I, O, R, D>
+{
+    type Inference = InferenceLinear<I, O, D>;
+    /// Merges the adapter into the weight.
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let Linear { weight, bias } = self.try_merge()?;
+        InferenceLinear::try_new(weight, bias)
+    }
+}
+
+impl<D: Device<E>, E: Dtype> IntoInference<D, E> for Dropout {
+    type Inference = Identity;
+    fn try_into_inference(self) -> Result<Identity, D::Err> {
+        Ok(Identity)
+    }
+}
+
+impl<const N: usize, D: Device<E>, E: Dtype> IntoInference<D, E> for DropoutOneIn<N> {
+    type Inference = Identity;
+    fn try_into_inference(self) -> Result<Identity, D::Err> {
+        Ok(Identity)
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> IntoInference<D, f32> for Linear<I, O, D> {
+    type Inference = InferenceLinear<I, O, D>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        InferenceLinear::try_new(self.weight, self.bias)
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> IntoInference<D, f32>
+    for SpectralNorm<Linear<I, O, D>, D>
+{
+    type Inference = InferenceLinear<I, O, D>;
+    /// Divides the weight by its current spectral norm estimate.
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let w = self.try_normalized_weight::<NoneTape>()?;
+        InferenceLinear::try_new(w, self.module.bias)
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> IntoInference<D, f32>
+    for WeightNorm<Linear<I, O, D>, D>
+{
+    type Inference = InferenceLinear<I, O, D>;
+    /// Computes the reparameterized weight once.
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let w = self.try_weight(self.module.weight.clone())?;
+        InferenceLinear::try_new(w, self.module.bias)
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    IntoInference<D, f32> for SpectralNorm<Conv2D<I, O, K, S, P, D>, D>
+where
+    D: Device<f32>,
+{
+    type Inference = Conv2D<I, O, K, S, P, D>;
+    /// Divides the weight by its current spectral norm estimate.
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let weight = self
+            .try_normalized_weight::<NoneTape>()?
+            .try_reshape_like(&Default::default())?;
+        Ok(Conv2D {
+            weight,
+            bias: self.module.bias,
+        })
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    IntoInference<D, f32> for WeightNorm<Conv2D<I, O, K, S, P, D>, D>
+where
+    D: Device<f32>,
+{
+    type Inference = Conv2D<I, O, K, S, P, D>;
+    /// Computes the reparameterized weight once.
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let weight = self
+            .try_weight(self.module.try_weight_matrix::<NoneTape>()?)?
+            .try_reshape_like(&Default::default())?;
+        Ok(Conv2D {
+            weight,
+            bias: self.module.bias,
+        })
+    }
+}
+
+impl<const C: usize, D: Device<f32>> IntoInference<D, f32> for BatchNorm2D<C, D> {
+    type Inference = InferenceBatchNorm2D<C, D>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let std = self.running_var.try_add(self.epsilon)?.try_sqrt()?;
+        let scale = self.scale.try_div(std)?;
+        let bias = self
+            .bias
+            .try_sub(self.running_mean.try_mul(scale.clone())?)?;
+        Ok(InferenceBatchNorm2D {
+            scale,
+            bias,
+            folded: false,
+        })
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: IntoInference<D, E>> IntoInference<D, E> for DropPath<M> {
+    type Inference = Scaled<M::Inference>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        Ok(Scaled {
+            module: self.module.try_into_inference()?,
+            scale: self.survival_prob,
+        })
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: IntoInference<D, E>> IntoInference<D, E> for Scaled<M> {
+    type Inference = Scaled<M::Inference>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        Ok(Scaled {
+            module: self.module.try_into_inference()?,
+            scale: self.scale,
+        })
+    }
+}
+
+impl<D: Device<E>, E: Dtype, F: IntoInference<D, E>> IntoInference<D, E> for Residual<F> {
+    type Inference = Residual<F::Inference>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        Ok(Residual(self.0.try_into_inference()?))
+    }
+}
+
+impl<D: Device<E>, E: Dtype, T: IntoInference<D, E>> IntoInference<D, E> for AddInto<T> {
+    type Inference = AddInto<T::Inference>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        Ok(AddInto(self.0.try_into_inference()?))
+    }
+}
+
+impl<D: Device<E>, E: Dtype, T: IntoInference<D, E>> IntoInference<D, E> for SplitInto<T> {
+    type Inference = SplitInto<T::Inference>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        Ok(SplitInto(self.0.try_into_inference()?))
+    }
+}
+
+impl<D: Device<E>, E: Dtype, F, R> IntoInference<D, E> for GeneralizedResidual<F, R>
+where
+    F: IntoInference<D, E>,
+    R: IntoInference<D, E>,
+{
+    type Inference = GeneralizedResidual<F::Inference, R::Inference>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        Ok(GeneralizedResidual {
+            f: self.f.try_into_inference()?,
+            r: self.r.try_into_inference()?,
+        })
+    }
+}
+
+impl<D: Device<E>, E: Dtype, F, G> IntoInference<D, E> for GatedResidual<F, G>
+where
+    F: IntoInference<D, E>,
+    G: IntoInference<D, E>,
+{
+    type Inference = GatedResidual<F::Inference, G::Inference>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        Ok(GatedResidual {
+            f: self.f.try_into_inference()?,
+            g: self.g.try_into_inference()?,
+        })
+    }
+}
+
+impl<D: Device<E>, E: Dtype, T: IntoInference<D, E>, const N: usize> IntoInference<D, E>
+    for Repeated<T, N>
+{
+    type Inference = Repeated<T::Inference, N>;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let modules = self
+            .modules
+            .into_iter()
+            .map(IntoInference::try_into_inference)
+            .collect::<Result<_, _>>()?;
+        Ok(Repeated { modules })
+    }
+}
+
+macro_rules! tuple_inference_impl {
+    ([$($name:ident),+], [$($idx:tt),+], [$(($prev:ident, $i:tt, $j:tt)),+]) => {
+impl<D: Device<E>, E: Dtype, $($name: IntoInference<D, E>),+> IntoInference<D, E> for ($($name,)+)
+where
+    $($name::Inference: 'static,)+
+{
+    type Inference = ($($name::Inference,)+);
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        let mut inference = ($(self.$idx.try_into_inference()?,)+);
+        $($prev::try_fold_next(&mut inference.$i, &mut inference.$j)?;)+
+        Ok(inference)
+    }
+}
+    };
+}
+
+tuple_inference_impl!([A, B], [0, 1], [(A, 0, 1)]);
+tuple_inference_impl!([A, B, C], [0, 1, 2], [(A, 0, 1), (B, 1, 2)]);
+tuple_inference_impl!(
+    [A, B, C, D1],
+    [0, 1, 2, 3],
+    [(A, 0, 1), (B, 1, 2), (C, 2, 3)]
+);
+tuple_inference_impl!(
+    [A, B, C, D1, E1],
+    [0, 1, 2, 3, 4],
+    [(A, 0, 1), (B, 1, 2), (C, 2, 3), (D1, 3, 4)]
+);
+tuple_inference_impl!(
+    [A, B, C, D1, E1, F],
+    [0, 1, 2, 3, 4, 5],
+    [(A, 0, 1), (B, 1, 2), (C, 2, 3), (D1, 3, 4), (E1, 4, 5)]
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_linear_into_inference() {
+        let dev: TestDevice = Default::default();
+        let model: Linear<3, 2, _> = dev.build_module();
+        let x = dev.sample_normal::<Rank2<4, 3>>();
+        let y = model.forward(x.clone());
+
+        let weight = model.weight.clone();
+        let model = model.into_inference();
+        assert_eq!(model.weight.array(), weight.permute().array());
+        assert_eq!(model.weight.storage.strides, [2, 1]);
+        assert_close(&model.forward(x.clone()).array(), &y.array());
+        assert_close(&model.forward(x.trace()).array(), &y.array());
+    }
+
+    #[test]
+    fn test_tuple_into_inference() {
+        type Model = (
+            Linear<3, 4, TestDevice>,
+            Dropout,
+            (ReLU, DropoutOneIn<2>),
+            Residual<DropPath<Linear<4, 4, TestDevice>>>,
+            SpectralNorm<Linear<4, 4, TestDevice>, TestDevice>,
+            WeightNorm<Linear<4, 2, TestDevice>, TestDevice>,
+        );
+        let dev: TestDevice = Default::default();
+        let mut model: Model = dev.build_module();
+        model.3 .0.survival_prob = 0.5;
+        model.5.g.fill_with_distr(rand_distr::StandardNormal);
+
+        let x = dev.sample_normal::<Rank2<4, 3>>();
+        let y = model.forward(x.clone());
+
+        let model = model.inference_mode();
+        let _: &(ReLU, Identity) = &model.0 .2;
+        let _: &Residual<Scaled<InferenceLinear<4, 4, TestDevice>>> = &model.0 .3;
+        assert_close(&model.forward(x).array(), &y.array());
+    }
+
+    #[test]
+    fn test_batchnorm_into_inference() {
+        let dev: TestDevice = Default::default();
+        let mut bn: BatchNorm2D<3, _> = dev.build_module();
+        bn.scale.fill_with_distr(rand_distr::StandardNormal);
+        bn.bias.fill_with_distr(rand_distr::StandardNormal);
+        bn.running_mean.fill_with_distr(rand_distr::StandardNormal);
+        bn.running_var
+            .fill_with_distr(rand_distr::Uniform::new(0.5, 2.0));
+
+        let x = dev.sample_normal::<Rank4<2, 3, 4, 5>>();
+        let y = bn.forward(x.clone());
+
+        let bn = bn.into_inference();
+        assert_close(&bn.forward(x.clone()).array(), &y.array());
+        let x = dev.sample_normal::<Rank3<3, 4, 5>>();
+        let y = bn.forward(x.clone());
+        assert_close(&bn.forward(x.trace()).array(), &y.array());
+    }
+
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    #[test]
+    fn test_conv_batchnorm_folded_in_tuple() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Conv2D<2, 3, 3, 1, 1, _>, BatchNorm2D<3, _>, ReLU) = dev.build_module();
+        model.1.scale.fill_with_distr(rand_distr::StandardNormal);
+        model
+            .1
+            .running_mean
+            .fill_with_distr(rand_distr::StandardNormal);
+        model
+            .1
+            .running_var
+            .fill_with_distr(rand_distr::Uniform::new(0.5, 2.0));
+
+        let x = dev.sample_normal::<Rank4<2, 2, 5, 5>>();
+        let y = model.forward(x.clone());
+
+        let model = model.into_inference();
+        assert!(model.1.folded);
+        assert_eq!(model.1.scale.array(), [1.0; 3]);
+        assert_eq!(model.1.bias.array(), [0.0; 3]);
+        assert_close(&model.forward(x).as_vec(), &y.as_vec());
+
+        let z = dev.sample_normal::<Rank4<2, 3, 5, 5>>();
+        assert_eq!(model.1.forward(z.clone()).as_vec(), z.as_vec());
+    }
+
+    #[test]
+    fn test_lora_into_inference() {
+        let dev: TestDevice = Default::default();
+        let mut model: (LoRALinear<3, 4, 2, _>, ReLU) = dev.build_module();
+        model.0.b.fill_with_distr(rand_distr::StandardNormal);
+        let x = dev.sample_normal::<Rank2<5, 3>>();
+        let y = model.forward(x.clone());
+        let model: (InferenceLinear<3, 4, _>, ReLU) = model.into_inference();
+        assert_close(&model.forward(x).array(), &y.array());
+    }
+
+    #[test]
+    fn test_repeated_into_inference() {
+        let dev: TestDevice = Default::default();
+        let model: Repeated<(Linear<3, 3, _>, ReLU), 3> = dev.build_module();
+        let x = dev.sample_normal::<Rank1<3>>();
+        let y = model.forward(x.clone());
+        let model: Repeated<(InferenceLinear<3, 3, _>, ReLU), 3> = model.into_inference();
+        assert_close(&model.forward(x).array(), &y.array());
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{optim::*, shapes::*, tensor::*, tensor_ops::*};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use rand::Rng;
+use rand_distr::{Distribution, Normal, StandardNormal, Uniform};
+
+/// Which fan to use for [Init::KaimingUniform] and [Init::KaimingNormal].
+///
+/// [FanMode::FanIn] preserves the magnitude of activations in the forward pass,
+/// [FanMode::FanOut] preserves the magnitude of gradients in the backward pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FanMode {
+    FanIn,
+    FanOut,
+}
+
+/// Weight initialization strategies. Use [Init::init()] to initialize a single tensor,
+/// or [InitParams::init_params()] to initialize every weight of a model.
+///
+/// Fans are computed like pytorch: for a tensor with shape `(O, I, ...)`, `fan_in` is
+/// `I * prod(...)` and `fan_out` is `O * prod(...)`. For a 1d tensor both fans are
+/// the number of elements.
+///
+/// `gain` is the scaling factor recommended for the activation that follows the layer,
+/// e.g. `1.0` for linear/sigmoid, `5.0 / 3.0` for tanh and `SQRT_2` for relu.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut model: (Linear<5, 3>, ReLU, Linear<3, 2>) = dev.build_module();
+/// // per layer
+/// Init::KaimingNormal {
+///     gain: core::f32::consts::SQRT_2,
+///     mode: FanMode::FanIn,
+/// }
+/// .init(&mut model.0.weight);
+/// Init::Orthogonal { gain: 1.0 }.init(&mut model.2.weight);
+/// // globally
+/// model.init_params(Init::XavierUniform { gain: 1.0 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Init {
+    /// Uniform between `low` and `high`.
+    Uniform { low: f32, high: f32 },
+
+    /// Normal distribution with `mean` and standard deviation `std`.
+    Normal { mean: f32, std: f32 },
+
+    /// Normal distribution with `mean` and `std`, where values outside of `[low, high]` are redrawn.
+    /// If a value is still outside after 100 draws, which only happens when the range holds
+    /// almost none of the distribution, it is drawn uniformly from `[low, high]` instead.
+    TruncatedNormal {
+        mean: f32,
+        std: f32,
+        low: f32,
+        high: f32,
+    },
+
+    /// Uniform between `±gain * sqrt(6 / (fan_in + fan_out))`.
+    /// See [Understanding the difficulty of training deep feedforward neural networks](http://proceedings.mlr.press/v9/glorot10a/glorot10a.pdf)
+    XavierUniform { gain: f32 },
+
+    /// Normal with std `gain * sqrt(2 / (fan_in + fan_out))`.
+    XavierNormal { gain: f32 },
+
+    /// Uniform between `±gain * sqrt(3 / fan)`.
+    /// See [Delving Deep into Rectifiers](https://arxiv.org/abs/1502.01852)
+    KaimingUniform { gain: f32, mode: FanMode },
+
+    /// Normal with std `gain / sqrt(fan)`.
+    KaimingNormal { gain: f32, mode: FanMode },
+
+    /// A (semi) orthogonal matrix scaled by `gain`, where the tensor is viewed
+    /// as a matrix of shape `(O, I * prod(...))`.
+    /// See [Exact solutions to the nonlinear dynamics of learning in deep linear neural networks](https://arxiv.org/abs/1312.6120)
+    Orthogonal { gain: f32 },
+}
+
+impl Init {
+    /// Fills `t` according to this strategy.
+    pub fn init<S: Shape, D: Device<f32>, T>(&self, t: &mut Tensor<S, f32, D, T>) {
+        self.try_init(t).unwrap()
+    }
+
+    /// Fallible version of [Init::init]
+    pub fn try_init<S: Shape, D: Device<f32>, T>(
+        &self,
+        t: &mut Tensor<S, f32, D, T>,
+    ) -> Result<(), D::Err> {
+        if t.shape().num_elements() == 0 {
+            // the fans are 0, and there is nothing to fill
+            return Ok(());
+        }
+        let (fan_in, fan_out) = fans(t.shape());
+        match *self {
+            Self::Uniform { low, high } => t.try_fill_with_distr(Uniform::new(low, high)),
+            Self::Normal { mean, std } => t.try_fill_with_distr(Normal::new(mean, std).unwrap()),
+            Self::TruncatedNormal {
+                mean,
+                std,
+                low,
+                high,
+            } => {
+                assert!(low < high, "TruncatedNormal requires low < high");
+                t.try_fill_with_distr(TruncatedNormal {
+                    normal: Normal::new(mean, std).unwrap(),
+                    low,
+                    high,
+                })
+            }
+            Self::XavierUniform { gain } => {
+                let bound = gain * (6.0 / (fan_in + fan_out) as f32).sqrt();
+                t.try_fill_with_distr(Uniform::new_inclusive(-bound, bound))
+            }
+            Self::XavierNormal { gain } => {
+                let std = gain * (2.0 / (fan_in + fan_out) as f32).sqrt();
+                t.try_fill_with_distr(Normal::new(0.0, std).unwrap())
+            }
+            Self::KaimingUniform { gain, mode } => {
+                let fan = if mode == FanMode::FanIn {
+                    fan_in
+                } else {
+                    fan_out
+                };
+                let bound = gain * (3.0 / fan as f32).sqrt();
+                t.try_fill_with_distr(Uniform::new_inclusive(-bound, bound))
+            }
+            Self::KaimingNormal { gain, mode } => {
+                let fan = if mode == FanMode::FanIn {
+                    fan_in
+                } else {
+                    fan_out
+                };
+                let std = gain / (fan as f32).sqrt();
+                t.try_fill_with_distr(Normal::new(0.0, std).unwrap())
+            }
+            Self::Orthogonal { gain } => {
+                let numel = t.shape().num_elements();
+                let rows = if S::NUM_DIMS == 0 {
+                    1
+                } else {
+                    t.shape().concrete()[0]
+                };
+                t.try_fill_with_distr(StandardNormal)?;
+                let mut buf = alloc::vec![0.0; numel];
+                t.copy_into(&mut buf);
+                orthonormalize(&mut buf, rows, numel / rows.max(1));
+                for x in buf.iter_mut() {
+                    *x *= gain;
+                }
+                t.copy_from(&buf);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns `(fan_in, fan_out)` for a parameter of shape `(O, I, ...)`.
+fn fans<S: Shape>(shape: &S) -> (usize, usize) {
+    let dims = shape.concrete();
+    if S::NUM_DIMS < 2 {
+        let numel = shape.num_elements();
+        return (numel, numel);
+    }
+    let receptive: usize = dims.into_iter().skip(2).product();
+    (dims[1] * receptive, dims[0] * receptive)
+}
+
+/// Modified gram-schmidt on the rows of `buf` (or the columns, if there are more rows than columns).
+fn orthonormalize(buf: &mut [f32], rows: usize, cols: usize) {
+    let transposed = rows > cols;
+    let (n, len) = if transposed {
+        (cols, rows)
+    } else {
+        (rows, cols)
+    };
+    let at = |i: usize, j: usize| {
+        if transposed {
+            j * cols + i
+        } else {
+            i * cols + j
+        }
+    };
+    for i in 0..n {
+        for k in 0..i {
+            let dot: f32 = (0..len).map(|j| buf[at(i, j)] * buf[at(k, j)]).sum();
+            for j in 0..len {
+                buf[at(i, j)] -= dot * buf[at(k, j)];
+            }
+        }
+        let norm: f32 = (0..len).map(|j| buf[at(i, j)].powi(2)).sum::<f32>().sqrt();
+        for j in 0..len {
+            buf[at(i, j)] /= norm.max(f32::EPSILON);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TruncatedNormal {
+    normal: Normal<f32>,
+    low: f32,
+    high: f32,
+}
+
+impl TruncatedNormal {
+    const MAX_TRIES: usize = 100;
+}
+
+impl Distribution<f32> for TruncatedNormal {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        for _ in 0..Self::MAX_TRIES {
+            let x = self.normal.sample(rng);
+            if self.low <= x && x <= self.high {
+                return x;
+            }
+        }
+        // the range holds almost none of the distribution, so rejection sampling would
+        // take too long
+        rng.gen_range(self.low..=self.high)
+    }
+}
+
+/// Initializes every parameter of a model with at least 2 dimensions using an [Init] strategy.
+/// 1d parameters like biases and normalization scales are left untouched.
+///
+/// See [Init] for an example.
+pub trait InitParams<D: Device<f32>>: GradientUpdate<D, f32> {
+    /// Initializes all weights of `self` with `init`.
+    fn init_params(&mut self, init: Init) {
+        self.try_init_params(init).unwrap()
+    }
+
+    /// Fallible version of [InitParams::init_params]
+    fn try_init_params(&mut self, init: Init) -> Result<(), D::Err> {
+        let mut unused = Default::default();
+        self.update(&mut InitUpdater(init), &mut unused)
+    }
+}
+
+impl<D: Device<f32>, M: GradientUpdate<D, f32>> InitParams<D> for M {}
+
+struct InitUpdater(Init);
+
+impl<D: Device<f32>> ParamUpdater<D, f32> for InitUpdater {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        if S::NUM_DIMS >= 2 {
+            self.0.try_init(p)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::*,
+        tests::{assert_close, TestDevice},
+    };
+
+    #[test]
+    fn test_fans() {
+        assert_eq!(fans(&Rank1::<5>::default()), (5, 5));
+        assert_eq!(fans(&Rank2::<3, 5>::default()), (5, 3));
+        assert_eq!(fans(&Rank4::<8, 4, 3, 3>::default()), (36, 72));
+    }
+
+    #[test]
+    fn test_uniform_inits_within_bounds() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank2<8, 24>, f32, _> = dev.zeros();
+
+        Init::XavierUniform { gain: 2.0 }.init(&mut t);
+        let bound = 2.0 * (6.0f32 / 32.0).sqrt();
+        assert!(t.as_vec().iter().all(|x| x.abs() <= bound));
+
+        Init::KaimingUniform {
+            gain: 1.0,
+            mode: FanMode::FanOut,
+        }
+        .init(&mut t);
+        let bound = (3.0f32 / 8.0).sqrt();
+        assert!(t.as_vec().iter().all(|x| x.abs() <= bound));
+
+        Init::TruncatedNormal {
+            mean: 0.0,
+            std: 1.0,
+            low: -0.5,
+            high: 0.25,
+        }
+        .init(&mut t);
+        assert!(t.as_vec().iter().all(|&x| (-0.5..=0.25).contains(&x)));
+    }
+
+    #[test]
+    fn test_truncated_normal_far_tail() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<100>, f32, _> = dev.zeros();
+        Init::TruncatedNormal {
+            mean: 0.0,
+            std: 1.0,
+            low: 50.0,
+            high: 51.0,
+        }
+        .init(&mut t);
+        assert!(t.as_vec().iter().all(|&x| (50.0..=51.0).contains(&x)));
+    }
+
+    #[test]
+    fn test_init_empty_tensor() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<(usize, Const<3>), f32, _> = dev.zeros_like(&(0, Const));
+        Init::XavierNormal { gain: 1.0 }.init(&mut t);
+        Init::KaimingNormal {
+            gain: 1.0,
+            mode: FanMode::FanIn,
+        }
+        .init(&mut t);
+        assert!(t.as_vec().is_empty());
+    }
+
+    #[test]
+    fn test_normal_init_std() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank2<100, 400>, f32, _> = dev.zeros();
+        Init::KaimingNormal {
+            gain: 2.0,
+            mode: FanMode::FanIn,
+        }
+        .init(&mut t);
+        let std = t.stddev::<Rank0, _>(0.0).array();
+        assert!((std - 0.1).abs() < 0.005, "{std}");
+    }
+
+    #[test]
+    fn test_orthogonal_init() {
+        let dev: TestDevice = Default::default();
+
+        let mut wide: Tensor<Rank2<3, 5>, f32, _> = dev.zeros();
+        Init::Orthogonal { gain: 1.0 }.init(&mut wide);
+        let gram = wide.clone().matmul(wide.permute());
+        assert_close(
+            &gram.array(),
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        );
+
+        let mut tall: Tensor<Rank2<5, 3>, f32, _> = dev.zeros();
+        Init::Orthogonal { gain: 2.0 }.init(&mut tall);
+        let gram = tall.clone().permute().matmul(tall);
+        assert_close(
+            &gram.array(),
+            &[[4.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 4.0]],
+        );
+    }
+
+    #[test]
+    fn test_init_params_skips_1d() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Linear<3, 4>, LayerNorm1D<4>) = dev.build_module();
+        model.init_params(Init::Uniform {
+            low: 10.0,
+            high: 11.0,
+        });
+        assert!(model.0.weight.as_vec().iter().all(|&x| x >= 10.0));
+        assert!(model.0.bias.as_vec().iter().all(|&x| x < 10.0));
+        assert_eq!(model.1.gamma.array(), [1.0; 4]);
+    }
+}
@@ -4,10 +4,10 @@ use super::{Module, ModuleMut, ResetParams};
 
 /// Implements layer normalization as described in [Layer Normalization](https://arxiv.org/abs/1607.06450).
 ///
-/// This calls [normalize()] on the last axis of the input to normalize to 0 mean and unit std dev, and then does an element-wise
+/// This calls [layer_norm()] on the last axis of the input to normalize to 0 mean and unit std dev, and then does an element-wise
 /// affine transform using learnable parameters [Self::gamma] and [Self::beta].
 ///
-/// [Self::epsilon] is passed to [normalize()] and added to the variance to ensure big enough numbers. It defaults to `1e-5`.
+/// [Self::epsilon] is passed to [layer_norm()] and added to the variance to ensure big enough numbers. It defaults to `1e-5`.
 ///
 /// # Generics
 /// - `M` The size of the affine transform tensors.
@@ -59,7 +59,9 @@ impl<const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<Rank1<M>, f32, D,
 {
     type Output = Tensor<Rank1<M>, f32, D, T>;
     fn forward(&self, x: Tensor<Rank1<M>, f32, D, T>) -> Self::Output {
-        x.normalize(self.epsilon) * self.gamma.clone() + self.beta.clone()
+        let x = x.broadcast::<Rank2<1, M>, _>();
+        x.layer_norm::<_, Axis<0>>(&self.gamma, &self.beta, self.epsilon)
+            .sum()
     }
 }
 
@@ -68,9 +70,7 @@ impl<B: Dim, const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<(B, Const
 {
     type Output = Tensor<(B, Const<M>), f32, D, T>;
     fn forward(&self, x: Tensor<(B, Const<M>), f32, D, T>) -> Self::Output {
-        let shape = *x.shape();
-        x.normalize::<Axis<1>>(self.epsilon) * self.gamma.retaped::<T>().broadcast_like(&shape)
-            + self.beta.retaped::<T>().broadcast_like(&shape)
+        x.layer_norm::<_, Axis<0>>(&self.gamma, &self.beta, self.epsilon)
     }
 }
 
@@ -79,9 +79,7 @@ impl<B: Dim, S: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
 {
     type Output = Tensor<(B, S, Const<M>), f32, D, T>;
     fn forward(&self, x: Tensor<(B, S, Const<M>), f32, D, T>) -> Self::Output {
-        let shape = *x.shape();
-        x.normalize::<Axis<2>>(self.epsilon) * self.gamma.retaped::<T>().broadcast_like(&shape)
-            + self.beta.retaped::<T>().broadcast_like(&shape)
+        x.layer_norm::<_, Axes2<0, 1>>(&self.gamma, &self.beta, self.epsilon)
     }
 }
 
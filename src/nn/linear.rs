@@ -1,4 +1,6 @@
 use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use super::module::{Module, ModuleMut, ResetParams};
 
@@ -87,8 +89,8 @@ where
 }
 
 #[derive(Clone, Debug)]
-struct Bias1D<'a, const M: usize, D: Device<f32> = Cpu> {
-    beta: &'a Tensor<Rank1<M>, f32, D>,
+pub(super) struct Bias1D<'a, const M: usize, D: Device<f32> = Cpu> {
+    pub(super) beta: &'a Tensor<Rank1<M>, f32, D>,
 }
 
 impl<'a, const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<Rank1<M>, f32, D, T>>
@@ -0,0 +1,219 @@
+use crate::{
+    gradients::{Merge, Tape},
+    optim::*,
+    shapes::*,
+    tensor::*,
+    tensor_ops::*,
+};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use super::{
+    linear::Bias1D,
+    module::{Module, ModuleMut, ResetParams},
+    Linear,
+};
+
+/// A frozen [Linear] layer with a trainable low rank adapter (LoRA), computing
+/// `linear(x) + (alpha / R) * x * a^T * b^T`.
+///
+/// Only [Self::a] and [Self::b] are visited by [GradientUpdate], so optimizers don't change
+/// [Self::linear], and the binary format (see [super::SaveToBinary]) only saves the adapter.
+/// [super::SaveToNpz] saves everything.
+///
+/// [Self::b] starts at zero, so a new adapter doesn't change the output of the layer. Use
+/// [LoRALinear::merge()] or [super::IntoInference] to merge the adapter into the weight.
+///
+/// # Generics
+/// - `I` The "input" size of vectors & matrices.
+/// - `O` The "output" size of vectors & matrices.
+/// - `R` The rank of the adapter.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let pretrained: Linear<5, 3> = dev.build_module();
+/// let model = LoRALinear::<5, 3, 2>::from_linear(pretrained, 4.0);
+/// let _: Tensor<Rank2<10, 3>> = model.forward(dev.zeros::<Rank2<10, 5>>());
+/// let merged: Linear<5, 3> = model.merge();
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoRALinear<const I: usize, const O: usize, const R: usize, D: Device<f32> = Cpu> {
+    /// The frozen layer.
+    pub linear: Linear<I, O, D>,
+
+    /// Down projection, shape (R, I)
+    pub a: Tensor<Rank2<R, I>, f32, D>,
+
+    /// Up projection, shape (O, R)
+    pub b: Tensor<Rank2<O, R>, f32, D>,
+
+    /// The adapter is scaled by `alpha / R`.
+    pub alpha: f32,
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> LoRALinear<I, O, R, D> {
+    /// Adds a new adapter to `linear`, with [Self::a] initialized like [Linear::weight] and
+    /// [Self::b] set to zero.
+    pub fn from_linear(linear: Linear<I, O, D>, alpha: f32) -> Self {
+        Self::try_from_linear(linear, alpha).unwrap()
+    }
+
+    /// Fallible version of [LoRALinear::from_linear()]
+    pub fn try_from_linear(linear: Linear<I, O, D>, alpha: f32) -> Result<Self, D::Err> {
+        let bound: f32 = 1.0 / (I as f32).sqrt();
+        let dev = linear.weight.device.clone();
+        Ok(Self {
+            a: dev.try_sample(rand_distr::Uniform::new(-bound, bound))?,
+            b: dev.try_zeros()?,
+            linear,
+            alpha,
+        })
+    }
+
+    /// The [Linear] layer that computes the same as this one, with the adapter merged into
+    /// its weight.
+    pub fn merge(self) -> Linear<I, O, D> {
+        self.try_merge().unwrap()
+    }
+
+    /// Fallible version of [LoRALinear::merge()]
+    pub fn try_merge(self) -> Result<Linear<I, O, D>, D::Err> {
+        let weight = self.try_weight(self.b.clone(), self.a.clone())?;
+        Ok(Linear {
+            weight,
+            bias: self.linear.bias,
+        })
+    }
+
+    /// `linear.weight + (alpha / R) * b * a`
+    fn try_weight<T: Tape<D> + Merge<T>>(
+        &self,
+        b: Tensor<Rank2<O, R>, f32, D, T>,
+        a: Tensor<Rank2<R, I>, f32, D, T>,
+    ) -> Result<Tensor<Rank2<O, I>, f32, D, T>, D::Err> {
+        b.try_matmul(a)?
+            .try_mul(self.alpha / R as f32)?
+            .try_add(self.linear.weight.clone())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> GradientUpdate<D, f32>
+    for LoRALinear<I, O, R, D>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.a.update(updater, unused)?;
+        self.b.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> ResetParams<D, f32>
+    for LoRALinear<I, O, R, D>
+{
+    /// Builds a random [Linear] with a new adapter, and `alpha = R`.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Self::try_from_linear(ResetParams::try_build(device)?, R as f32)
+    }
+
+    /// Resets the adapter, [Self::linear] is unchanged.
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        let bound: f32 = 1.0 / (I as f32).sqrt();
+        self.a
+            .try_fill_with_distr(rand_distr::Uniform::new(-bound, bound))?;
+        self.b.try_fill_with_zeros()
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>, T> Module<T>
+    for LoRALinear<I, O, R, D>
+where
+    T: SplitTape + TryMatMul<Tensor<Rank2<I, O>, f32, D, T::Tape>>,
+    T::Tape: Tape<D> + Merge<T::Tape>,
+    for<'a> Bias1D<'a, O, D>: Module<T::Output, Output = T::Output>,
+{
+    type Output = T::Output;
+
+    /// Computes the merged weight on every call, see [LoRALinear::merge()] for inference.
+    fn forward(&self, x: T) -> Self::Output {
+        let w = self
+            .try_weight(self.b.retaped::<T::Tape>(), self.a.retaped::<T::Tape>())
+            .unwrap();
+        let o = x.matmul(w.permute());
+        Bias1D {
+            beta: &self.linear.bias,
+        }
+        .forward(o)
+    }
+}
+
+impl<T, const I: usize, const O: usize, const R: usize, D: Device<f32>> ModuleMut<T>
+    for LoRALinear<I, O, R, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::ModuleBuilder,
+        optim::{Optimizer, Sgd},
+        tests::{assert_close, TestDevice},
+    };
+
+    #[test]
+    fn test_new_lora_matches_linear() {
+        let dev: TestDevice = Default::default();
+        let linear: Linear<4, 3, _> = dev.build_module();
+        let model = LoRALinear::<4, 3, 2, _>::from_linear(linear.clone(), 2.0);
+        assert_eq!(model.b.array(), [[0.0; 2]; 3]);
+
+        let x = dev.sample_normal::<Rank2<5, 4>>();
+        assert_close(
+            &model.forward(x.clone()).array(),
+            &linear.forward(x).array(),
+        );
+    }
+
+    #[test]
+    fn test_lora_trains_only_adapter() {
+        let dev: TestDevice = Default::default();
+        let mut model: LoRALinear<4, 3, 2, _> = dev.build_module();
+        model.b.fill_with_distr(rand_distr::StandardNormal);
+        let (a, b) = (model.a.array(), model.b.array());
+        let linear = model.linear.clone();
+
+        let x = dev.sample_normal::<Rank2<5, 4>>();
+        let g = model.forward(x.trace()).square().mean().backward();
+        let mut opt = Sgd::new(Default::default());
+        opt.update(&mut model, g).expect("");
+
+        assert_ne!(model.a.array(), a);
+        assert_ne!(model.b.array(), b);
+        assert_eq!(model.linear.weight.array(), linear.weight.array());
+        assert_eq!(model.linear.bias.array(), linear.bias.array());
+    }
+
+    #[test]
+    fn test_lora_merge() {
+        let dev: TestDevice = Default::default();
+        let mut model: LoRALinear<4, 3, 2, _> = dev.build_module();
+        model.b.fill_with_distr(rand_distr::StandardNormal);
+        model.alpha = 3.0;
+
+        let x = dev.sample_normal::<Rank1<4>>();
+        let y = model.forward(x.clone());
+        let merged = model.merge();
+        assert_close(&merged.forward(x).array(), &y.array());
+    }
+}
@@ -22,6 +22,11 @@
 //! - [BatchNorm2D]
 //! - [DropoutOneIn]
 //! - [Dropout]
+//! - [DropPath]
+//! - [SpectralNorm]
+//!
+//! [Module::forward()] never mutates the module (none of the modules here use interior mutability),
+//! so a single model can be used from multiple threads at once. See [SharedModel].
 //!
 //! # Initializing
 //!
@@ -34,6 +39,16 @@
 //! let model: Linear<5, 2> = dev.build_module(); // will allocate & randomize params
 //! ```
 //!
+//! To pick a different initialization scheme, use [Init] on individual tensors,
+//! or [InitParams::init_params()] on a whole model:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let mut model: (Linear<5, 3>, ReLU, Linear<3, 2>) = dev.build_module();
+//! model.init_params(Init::XavierNormal { gain: 1.0 });
+//! ```
+//!
 //! # Sequential models
 //!
 //! Tuple's implement [Module], so you can string multiple module's together.
@@ -60,6 +75,23 @@
 //! );
 //! ```
 //!
+//! # Fused layers
+//!
+//! Fusing layers is opt-in. A [Linear] followed by a [ReLU] or [GeLU] in a tuple runs as two
+//! separate ops, because the tuple impls are generic over their members and can't single out
+//! this pair. Use [LinearReLU] or [LinearGeLU] in place of the pair to compute both with a
+//! single kernel, or convert an existing pair with [From]:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! type Mlp = (LinearReLU<5, 3>, Linear<3, 2>);
+//! let model: Mlp = dev.build_module();
+//!
+//! let (l1, act, l2): (Linear<5, 3>, ReLU, Linear<3, 2>) = dev.build_module();
+//! let model: Mlp = ((l1, act).into(), l2);
+//! ```
+//!
 //! # Saving and Loading
 //!
 //! Call [SaveToNpz::save()] and [LoadFromNpz::load()] traits. All modules provided here implement it,
@@ -78,47 +110,93 @@
 mod activations;
 mod add_into;
 mod batchnorm2d;
+mod compile;
+mod drop_path;
 mod dropout;
+mod dyn_layer_norm;
+mod dyn_linear;
+mod dyn_module;
+mod fused_linear;
+mod gated_residual;
 mod generalized_residual;
 mod impl_module_for_tuples;
+mod inference;
+mod init;
 mod layer_norm;
 mod linear;
+mod lora;
+mod model_config;
 mod module;
+mod per_sample;
 mod pool_global;
+mod pruning;
 mod repeated;
 mod residual;
+mod running_norm;
+mod sampled_softmax;
+mod scaled;
+mod sequential;
+mod shared;
 mod split_into;
+mod tied;
 mod transformer;
+mod weight_norm;
 
 pub use activations::*;
 pub use add_into::*;
 pub use batchnorm2d::*;
+pub use compile::*;
+pub use drop_path::*;
 pub use dropout::*;
+pub use dyn_layer_norm::*;
+pub use dyn_linear::*;
+pub use dyn_module::*;
+pub use fused_linear::*;
+pub use gated_residual::*;
 pub use generalized_residual::*;
 pub use impl_module_for_tuples::*;
+pub use inference::*;
+pub use init::*;
 pub use layer_norm::*;
 pub use linear::*;
+pub use lora::*;
+pub use model_config::*;
 pub use module::*;
+pub use per_sample::*;
 pub use pool_global::*;
+pub use pruning::*;
 pub use repeated::*;
 pub use residual::*;
+pub use running_norm::*;
+pub use sampled_softmax::*;
+pub use scaled::*;
+pub use sequential::*;
+pub use shared::*;
 pub use split_into::*;
+pub use tied::*;
+pub use weight_norm::*;
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 mod conv;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 pub use conv::*;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 mod flatten;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 pub use flatten::*;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 mod pool2d;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 pub use pool2d::*;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 pub use transformer::*;
 
+#[cfg(feature = "std")]
+mod binary;
+
+#[cfg(feature = "std")]
+pub use binary::*;
+
 #[cfg(feature = "numpy")]
 mod npz;
 
@@ -0,0 +1,372 @@
+use crate::tensor_ops::Device;
+
+use super::*;
+
+use std::vec::Vec;
+
+/// Describes a single layer of a [ModelConfig]. Sizes are the number of features.
+///
+/// With the `serde` feature, this is (de)serialized with a `type` tag, e.g.
+/// `{"type": "Linear", "inp": 5, "out": 10}` or `{"type": "ReLU"}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum LayerConfig {
+    /// A [DynLinear] with `inp` inputs and `out` outputs.
+    Linear { inp: usize, out: usize },
+
+    /// A [DynLayerNorm1D] of size `dim`.
+    LayerNorm { dim: usize },
+
+    /// [ReLU]
+    ReLU,
+
+    /// [GeLU]
+    GeLU,
+
+    /// [SiLU]
+    SiLU,
+
+    /// [Sigmoid]
+    Sigmoid,
+
+    /// [Tanh]
+    Tanh,
+
+    /// A [Residual] around `layers`, which must not change the size.
+    Residual { layers: Vec<LayerConfig> },
+
+    /// `layers` repeated `count` times, each repetition with its own parameters.
+    Repeated {
+        count: usize,
+        layers: Vec<LayerConfig>,
+    },
+}
+
+/// A description of a model that can be built at runtime, so different architectures
+/// (e.g. in a hyperparameter sweep) don't require recompiling. Enable the `serde`
+/// feature to read it from JSON, YAML or any other format supported by serde.
+///
+/// [ModelConfig::build()] creates a [Sequential] that acts on batches of shape `(usize, usize)`,
+/// which can be trained, saved and loaded like any other module. Nested layers ([LayerConfig::Residual]
+/// and [LayerConfig::Repeated]) are nested [Sequential]s, so saved files have the same layout as the
+/// equivalent tuples.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let config = ModelConfig {
+///     layers: vec![
+///         LayerConfig::Linear { inp: 5, out: 16 },
+///         LayerConfig::ReLU,
+///         LayerConfig::Repeated {
+///             count: 2,
+///             layers: vec![LayerConfig::Residual {
+///                 layers: vec![LayerConfig::Linear { inp: 16, out: 16 }, LayerConfig::Tanh],
+///             }],
+///         },
+///         LayerConfig::Linear { inp: 16, out: 2 },
+///     ],
+/// };
+/// assert_eq!(config.validate(), Ok(Some(2)));
+/// let model = config.build(&dev);
+/// let y = model.forward(dev.zeros_like(&(10, 5)));
+/// assert_eq!(y.shape(), &(10, 2));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelConfig {
+    pub layers: Vec<LayerConfig>,
+}
+
+/// An error from [ModelConfig::validate()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelConfigError {
+    /// A layer expected `expected` features, but the previous layer outputs `found`.
+    SizeMismatch { expected: usize, found: usize },
+
+    /// The layers of a [LayerConfig::Residual] change the size from `inp` to `out`.
+    ResidualSizeChange { inp: usize, out: usize },
+}
+
+impl std::fmt::Display for ModelConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SizeMismatch { expected, found } => {
+                write!(f, "layer expected {expected} features, but found {found}")
+            }
+            Self::ResidualSizeChange { inp, out } => {
+                write!(f, "residual layers change size from {inp} to {out}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ModelConfigError {}
+
+/// An error from [ModelConfig::try_build()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelBuildError<Err> {
+    /// The config is invalid, see [ModelConfig::validate()].
+    Config(ModelConfigError),
+    /// An error from the device.
+    Device(Err),
+}
+
+impl<Err: std::fmt::Display> std::fmt::Display for ModelBuildError<Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Config(err) => write!(f, "Invalid ModelConfig: {err}"),
+            Self::Device(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Err: std::fmt::Debug + std::fmt::Display> std::error::Error for ModelBuildError<Err> {}
+
+/// A model built from a [ModelConfig], acting on batches of shape `(usize, usize)`.
+pub type ConfigModel<D> = Sequential<(usize, usize), D>;
+
+impl ModelConfig {
+    /// Checks that the sizes of consecutive layers match, and returns the output size
+    /// of the model (`None` if the model has no sized layers).
+    pub fn validate(&self) -> Result<Option<usize>, ModelConfigError> {
+        validate_layers(&self.layers, None)
+    }
+
+    /// Builds a randomly initialized model, which can be called with an
+    /// [OwnedTape](crate::gradients::OwnedTape) for training, and without a tape for inference.
+    ///
+    /// **Panics** if [ModelConfig::validate()] fails.
+    pub fn build<D: Device<f32>>(&self, device: &D) -> ConfigModel<D> {
+        self.try_build(device).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Fallible version of [ModelConfig::build()], which returns [ModelBuildError::Config] if
+    /// [ModelConfig::validate()] fails.
+    pub fn try_build<D: Device<f32>>(
+        &self,
+        device: &D,
+    ) -> Result<ConfigModel<D>, ModelBuildError<D::Err>> {
+        self.validate().map_err(ModelBuildError::Config)?;
+        build_layers(&self.layers, device).map_err(ModelBuildError::Device)
+    }
+}
+
+fn validate_layers(
+    layers: &[LayerConfig],
+    mut size: Option<usize>,
+) -> Result<Option<usize>, ModelConfigError> {
+    let check = |size: Option<usize>, expected: usize| match size {
+        Some(found) if found != expected => Err(ModelConfigError::SizeMismatch { expected, found }),
+        _ => Ok(()),
+    };
+    for layer in layers {
+        match layer {
+            LayerConfig::Linear { inp, out } => {
+                check(size, *inp)?;
+                size = Some(*out);
+            }
+            LayerConfig::LayerNorm { dim } => {
+                check(size, *dim)?;
+                size = Some(*dim);
+            }
+            LayerConfig::ReLU
+            | LayerConfig::GeLU
+            | LayerConfig::SiLU
+            | LayerConfig::Sigmoid
+            | LayerConfig::Tanh => {}
+            LayerConfig::Residual { layers } => {
+                let out = validate_layers(layers, size)?;
+                match (size, out) {
+                    (Some(inp), Some(out)) if inp != out => {
+                        return Err(ModelConfigError::ResidualSizeChange { inp, out })
+                    }
+                    _ => size = size.or(out),
+                }
+            }
+            LayerConfig::Repeated { count, layers } => {
+                for _ in 0..*count {
+                    size = validate_layers(layers, size)?;
+                }
+            }
+        }
+    }
+    Ok(size)
+}
+
+fn build_layers<D: Device<f32>>(
+    layers: &[LayerConfig],
+    device: &D,
+) -> Result<ConfigModel<D>, D::Err> {
+    let mut model: ConfigModel<D> = Default::default();
+    for layer in layers {
+        match layer {
+            LayerConfig::Linear { inp, out } => model.push(DynLinear::try_new(device, *inp, *out)?),
+            LayerConfig::LayerNorm { dim } => model.push(DynLayerNorm1D::try_new(device, *dim)?),
+            LayerConfig::ReLU => model.push(ReLU),
+            LayerConfig::GeLU => model.push(GeLU),
+            LayerConfig::SiLU => model.push(SiLU),
+            LayerConfig::Sigmoid => model.push(Sigmoid),
+            LayerConfig::Tanh => model.push(Tanh),
+            LayerConfig::Residual { layers } => model.push(Residual(build_layers(layers, device)?)),
+            LayerConfig::Repeated { count, layers } => {
+                let mut repeated: ConfigModel<D> = Default::default();
+                for _ in 0..*count {
+                    repeated.push(build_layers(layers, device)?);
+                }
+                model.push(repeated);
+            }
+        }
+    }
+    Ok(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{optim::*, shapes::*, tensor::*, tensor_ops::*, tests::TestDevice};
+    use alloc::vec;
+
+    fn mlp(hidden: usize, depth: usize) -> ModelConfig {
+        ModelConfig {
+            layers: vec![
+                LayerConfig::Linear {
+                    inp: 3,
+                    out: hidden,
+                },
+                LayerConfig::Repeated {
+                    count: depth,
+                    layers: vec![LayerConfig::Residual {
+                        layers: vec![
+                            LayerConfig::LayerNorm { dim: hidden },
+                            LayerConfig::Linear {
+                                inp: hidden,
+                                out: hidden,
+                            },
+                            LayerConfig::GeLU,
+                        ],
+                    }],
+                },
+                LayerConfig::Linear {
+                    inp: hidden,
+                    out: 2,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(ModelConfig::default().validate(), Ok(None));
+        assert_eq!(mlp(8, 3).validate(), Ok(Some(2)));
+
+        let mut config = mlp(8, 3);
+        config.layers.push(LayerConfig::Linear { inp: 3, out: 1 });
+        assert_eq!(
+            config.validate(),
+            Err(ModelConfigError::SizeMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+
+        let config = ModelConfig {
+            layers: vec![
+                LayerConfig::Linear { inp: 3, out: 4 },
+                LayerConfig::Residual {
+                    layers: vec![LayerConfig::Linear { inp: 4, out: 5 }],
+                },
+            ],
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ModelConfigError::ResidualSizeChange { inp: 4, out: 5 })
+        );
+    }
+
+    #[test]
+    #[should_panic = "Invalid ModelConfig: layer expected 4 features, but found 3"]
+    fn test_build_invalid_panics() {
+        let dev: TestDevice = Default::default();
+        let config = ModelConfig {
+            layers: vec![
+                LayerConfig::Linear { inp: 2, out: 3 },
+                LayerConfig::Linear { inp: 4, out: 1 },
+            ],
+        };
+        assert!(matches!(
+            config.try_build(&dev),
+            Err(ModelBuildError::Config(ModelConfigError::SizeMismatch {
+                expected: 4,
+                found: 3
+            }))
+        ));
+        let _ = config.build(&dev);
+    }
+
+    #[test]
+    fn test_build_sizes() {
+        let dev: TestDevice = Default::default();
+        for (hidden, depth) in [(4, 0), (8, 1), (16, 3)] {
+            let model = mlp(hidden, depth).build(&dev);
+            assert_eq!(model.len(), 3);
+            let y = model.forward(dev.zeros_like(&(5, 3)));
+            assert_eq!(y.shape(), &(5, 2));
+        }
+    }
+
+    #[test]
+    fn test_train_built_model() {
+        let dev: TestDevice = Default::default();
+        let mut model = mlp(8, 2).build(&dev);
+        let mut opt = Sgd::new(Default::default());
+
+        let x = dev.sample_like(&(4, 3), rand_distr::StandardNormal);
+        let y = dev.sample_like(&(4, 2), rand_distr::StandardNormal);
+
+        let loss = |model: &mut ConfigModel<_>| {
+            let pred = model.forward_mut(x.trace());
+            (pred - y.clone()).square().mean()
+        };
+
+        let initial = loss(&mut model).array();
+        for _ in 0..5 {
+            let g = loss(&mut model).backward();
+            opt.update(&mut model, g).expect("");
+        }
+        assert!(loss(&mut model).array() < initial);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_from_json() {
+        let json = r#"{
+            "layers": [
+                {"type": "Linear", "inp": 3, "out": 8},
+                {"type": "ReLU"},
+                {"type": "Repeated", "count": 2, "layers": [
+                    {"type": "Residual", "layers": [{"type": "Linear", "inp": 8, "out": 8}, {"type": "Tanh"}]}
+                ]},
+                {"type": "LayerNorm", "dim": 8},
+                {"type": "Linear", "inp": 8, "out": 2}
+            ]
+        }"#;
+        let config: ModelConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.validate(), Ok(Some(2)));
+        assert_eq!(config.layers[1], LayerConfig::ReLU);
+        assert_eq!(
+            serde_json::from_str::<ModelConfig>(&serde_json::to_string(&config).unwrap()).unwrap(),
+            config
+        );
+
+        let dev: TestDevice = Default::default();
+        let model = config.build(&dev);
+        assert_eq!(model.len(), 5);
+        let y = model.forward(dev.zeros_like(&(1, 3)));
+        assert_eq!(y.shape(), &(1, 2));
+    }
+}
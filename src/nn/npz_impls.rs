@@ -2,9 +2,15 @@ use super::{
     npz::{LoadFromNpz, SaveToNpz},
     *,
 };
-use crate::{tensor::numpy::NpzError, tensor_ops::Device};
+use crate::{
+    optim::GradientUpdate,
+    shapes::{Dtype, Rank0, Shape},
+    tensor::{numpy::NpzError, AsArray, Cpu, Tensor, TensorFromArray, ZerosTensor},
+    tensor_ops::Device,
+};
 use std::format;
-use std::io::{Read, Seek, Write};
+use std::io::{Cursor, Read, Seek, Write};
+use std::vec::Vec;
 use zip::{result::ZipResult, ZipArchive, ZipWriter};
 
 impl<T: ZeroSizedModule> SaveToNpz for T {}
@@ -34,7 +40,7 @@ impl<const C: usize, D: Device<f32>> LoadFromNpz for BatchNorm2D<C, D> {
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<
         const I: usize,
         const O: usize,
@@ -51,7 +57,7 @@ impl<
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<
         const I: usize,
         const O: usize,
@@ -98,6 +104,32 @@ impl<const M: usize, D: Device<f32>> LoadFromNpz for LayerNorm1D<M, D> {
     }
 }
 
+impl<const M: usize, D: Device<f32>> SaveToNpz for RunningNorm1D<M, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.running_mean
+            .write_to_npz(w, format!("{p}running_mean.npy"))?;
+        self.running_var
+            .write_to_npz(w, format!("{p}running_var.npy"))?;
+        // f64 holds every count up to 2^53 exactly
+        let count: Tensor<Rank0, f64, Cpu> = Cpu::default().tensor(self.count as f64);
+        count.write_to_npz(w, format!("{p}count.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, D: Device<f32>> LoadFromNpz for RunningNorm1D<M, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.running_mean
+            .read_from_npz(r, format!("{p}running_mean.npy"))?;
+        self.running_var
+            .read_from_npz(r, format!("{p}running_var.npy"))?;
+        let mut count: Tensor<Rank0, f64, Cpu> = Cpu::default().zeros();
+        count.read_from_npz(r, format!("{p}count.npy"))?;
+        self.count = count.array() as u64;
+        Ok(())
+    }
+}
+
 impl<const I: usize, const O: usize, D: Device<f32>> SaveToNpz for Linear<I, O, D> {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
@@ -114,6 +146,208 @@ impl<const I: usize, const O: usize, D: Device<f32>> LoadFromNpz for Linear<I, O
     }
 }
 
+impl<D: Device<f32>> SaveToNpz for DynLinear<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
+        self.bias.write_to_npz(w, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for DynLinear<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))?;
+        self.bias.read_from_npz(r, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> SaveToNpz for DynLayerNorm1D<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.gamma.write_to_npz(w, format!("{p}gamma.npy"))?;
+        self.beta.write_to_npz(w, format!("{p}beta.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for DynLayerNorm1D<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.gamma.read_from_npz(r, format!("{p}gamma.npy"))?;
+        self.beta.read_from_npz(r, format!("{p}beta.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> SaveToNpz
+    for LoRALinear<I, O, R, D>
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.linear.write(&format!("{p}linear."), w)?;
+        self.a.write_to_npz(w, format!("{p}a.npy"))?;
+        self.b.write_to_npz(w, format!("{p}b.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> LoadFromNpz
+    for LoRALinear<I, O, R, D>
+{
+    fn read<R2: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R2>) -> Result<(), NpzError> {
+        self.linear.read(&format!("{p}linear."), r)?;
+        self.a.read_from_npz(r, format!("{p}a.npy"))?;
+        self.b.read_from_npz(r, format!("{p}b.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, A, D: Device<f32>> SaveToNpz for FusedLinear<I, O, A, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
+        self.bias.write_to_npz(w, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, A, D: Device<f32>> LoadFromNpz for FusedLinear<I, O, A, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))?;
+        self.bias.read_from_npz(r, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const C: usize, D: Device<f32>> SaveToNpz for PReLU<C, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.a.write_to_npz(w, format!("{p}a.npy"))
+    }
+}
+
+impl<const C: usize, D: Device<f32>> LoadFromNpz for PReLU<C, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.a.read_from_npz(r, format!("{p}a.npy"))
+    }
+}
+
+impl<D: Device<f32>> SaveToNpz for Swish<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.beta.write_to_npz(w, format!("{p}beta.npy"))
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for Swish<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.beta.read_from_npz(r, format!("{p}beta.npy"))
+    }
+}
+
+impl<F: SaveToNpz, G: SaveToNpz> SaveToNpz for GatedResidual<F, G> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.f.write(&format!("{p}.f"), w)?;
+        self.g.write(&format!("{p}.g"), w)
+    }
+}
+
+impl<F: LoadFromNpz, G: LoadFromNpz> LoadFromNpz for GatedResidual<F, G> {
+    fn read<Z: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<Z>) -> Result<(), NpzError> {
+        self.f.read(&format!("{p}.f"), r)?;
+        self.g.read(&format!("{p}.g"), r)
+    }
+}
+
+impl<M: SaveToNpz> SaveToNpz for Scaled<M> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(&format!("{p}module."), w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for Scaled<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(&format!("{p}module."), r)
+    }
+}
+
+impl<M: SaveToNpz> SaveToNpz for DropPath<M> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(&format!("{p}module."), w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for DropPath<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(&format!("{p}module."), r)
+    }
+}
+
+impl<M: SaveToNpz> SaveToNpz for Tied<M> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.0.write(p, w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for Tied<M> {
+    /// Every copy of a tied parameter reads the same data, so loading keeps the ties.
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.0.read(p, r)
+    }
+}
+
+impl<M: SaveToNpz + GradientUpdate<D, f32> + Clone, D: Device<f32>> SaveToNpz for Pruned<M, D> {
+    /// Writes the module, and the flattened mask of each of its parameters.
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(&format!("{p}module."), w)?;
+        for (i, mask) in self.all_masks().iter().enumerate() {
+            mask.write_to_npz(w, format!("{p}mask.{i}.npy"))?;
+        }
+        Ok(())
+    }
+}
+
+impl<M: LoadFromNpz + GradientUpdate<D, f32> + Clone, D: Device<f32>> LoadFromNpz for Pruned<M, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(&format!("{p}module."), r)?;
+        let mut masks = self.all_masks();
+        for (i, mask) in masks.iter_mut().enumerate() {
+            mask.read_from_npz(r, format!("{p}mask.{i}.npy"))?;
+        }
+        self.set_masks(masks);
+        Ok(())
+    }
+}
+
+impl<M: HasWeightMatrix<D> + SaveToNpz, D: Device<f32>> SaveToNpz for SpectralNorm<M, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(&format!("{p}module."), w)?;
+        self.u.write_to_npz(w, format!("{p}u.npy"))?;
+        self.v.write_to_npz(w, format!("{p}v.npy"))?;
+        Ok(())
+    }
+}
+
+impl<M: HasWeightMatrix<D> + LoadFromNpz, D: Device<f32>> LoadFromNpz for SpectralNorm<M, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(&format!("{p}module."), r)?;
+        self.u.read_from_npz(r, format!("{p}u.npy"))?;
+        self.v.read_from_npz(r, format!("{p}v.npy"))?;
+        Ok(())
+    }
+}
+
+impl<M: HasWeightMatrix<D> + SaveToNpz, D: Device<f32>> SaveToNpz for WeightNorm<M, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(&format!("{p}module."), w)?;
+        self.g.write_to_npz(w, format!("{p}g.npy"))?;
+        Ok(())
+    }
+}
+
+impl<M: HasWeightMatrix<D> + LoadFromNpz, D: Device<f32>> LoadFromNpz for WeightNorm<M, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(&format!("{p}module."), r)?;
+        self.g.read_from_npz(r, format!("{p}g.npy"))?;
+        Ok(())
+    }
+}
+
 macro_rules! tuple_npz_impl {
     ([$($name:ident),+], [$($idx:tt),+]) => {
 impl<$($name: SaveToNpz),+> SaveToNpz for ($($name,)+) {
@@ -156,6 +390,37 @@ impl<T: LoadFromNpz, const N: usize> LoadFromNpz for Repeated<T, N> {
     }
 }
 
+impl<S: Shape, D: Device<E>, E: Dtype> SaveToNpz for Sequential<S, D, E> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        for (i, layer) in self.layers.iter().enumerate() {
+            let mut buf = ZipWriter::new(Cursor::new(Vec::new()));
+            layer.write_dyn(&format!("{p}{i}."), &mut buf)?;
+            let mut buf = ZipArchive::new(buf.finish()?)?;
+            for j in 0..buf.len() {
+                w.raw_copy_file(buf.by_index_raw(j)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype> LoadFromNpz for Sequential<S, D, E> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            let prefix = format!("{p}{i}.");
+            let mut buf = ZipWriter::new(Cursor::new(Vec::new()));
+            for j in 0..r.len() {
+                let file = r.by_index_raw(j)?;
+                if file.name().starts_with(&prefix) {
+                    buf.raw_copy_file(file)?;
+                }
+            }
+            layer.read_dyn(&prefix, &mut ZipArchive::new(buf.finish()?)?)?;
+        }
+        Ok(())
+    }
+}
+
 impl<F: SaveToNpz> SaveToNpz for Residual<F> {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         self.0.write(&format!("{p}.0"), w)
@@ -192,7 +457,7 @@ impl<T: LoadFromNpz> LoadFromNpz for AddInto<T> {
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const F: usize, const L: usize, D: Device<f32>> SaveToNpz
     for TransformerDecoder<M, H, F, L, D>
 {
@@ -201,7 +466,7 @@ impl<const M: usize, const H: usize, const F: usize, const L: usize, D: Device<f
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> SaveToNpz
     for TransformerDecoderBlock<M, H, F, D>
 {
@@ -217,7 +482,7 @@ impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> SaveToNpz
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> LoadFromNpz
     for TransformerDecoderBlock<M, H, F, D>
 {
@@ -233,7 +498,7 @@ impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> LoadFromNpz
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const F: usize, const L: usize, D: Device<f32>> LoadFromNpz
     for TransformerDecoder<M, H, F, L, D>
 {
@@ -242,7 +507,7 @@ impl<const M: usize, const H: usize, const F: usize, const L: usize, D: Device<f
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> SaveToNpz
     for TransformerEncoderBlock<M, H, F, D>
 {
@@ -256,7 +521,7 @@ impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> SaveToNpz
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> LoadFromNpz
     for TransformerEncoderBlock<M, H, F, D>
 {
@@ -270,7 +535,7 @@ impl<const M: usize, const H: usize, const F: usize, D: Device<f32>> LoadFromNpz
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const K: usize, const V: usize, D: Device<f32>> SaveToNpz
     for MultiHeadAttention<M, H, K, V, D>
 {
@@ -283,7 +548,7 @@ impl<const M: usize, const H: usize, const K: usize, const V: usize, D: Device<f
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<const M: usize, const H: usize, const K: usize, const V: usize, D: Device<f32>> LoadFromNpz
     for MultiHeadAttention<M, H, K, V, D>
 {
@@ -296,7 +561,7 @@ impl<const M: usize, const H: usize, const K: usize, const V: usize, D: Device<f
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<
         const M: usize,
         const H: usize,
@@ -313,7 +578,7 @@ impl<
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 impl<
         const M: usize,
         const H: usize,
@@ -334,8 +599,8 @@ impl<
 mod tests {
     use crate::{
         shapes::*,
-        tensor::{AsArray, SampleTensor, Tensor},
-        tensor_ops::Device,
+        tensor::{AsArray, AsVec, SampleTensor, Tensor},
+        tensor_ops::{Device, ReshapeTo},
         tests::TestDevice,
     };
 
@@ -419,6 +684,62 @@ mod tests {
         test_save_load::<Rank1<5>, f32, TestDevice, (T, T)>(&dev);
     }
 
+    #[test]
+    fn test_save_load_gated_residual_scaled() {
+        let dev: TestDevice = Default::default();
+        type T =
+            GatedResidual<Scaled<Linear<5, 5, TestDevice>>, (Linear<5, 5, TestDevice>, Sigmoid)>;
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_drop_path() {
+        let dev: TestDevice = Default::default();
+        type T = Residual<DropPath<Linear<5, 5, TestDevice>>>;
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_weight_norms() {
+        let dev: TestDevice = Default::default();
+        type T = SpectralNorm<Linear<5, 5, TestDevice>, TestDevice>;
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+        type U = WeightNorm<Linear<5, 5, TestDevice>, TestDevice>;
+        test_save_load::<Rank1<5>, f32, TestDevice, U>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_lora() {
+        let dev: TestDevice = Default::default();
+        let mut saved: LoRALinear<5, 3, 2, TestDevice> = dev.build_module();
+        saved.b.fill_with_distr(StandardNormal);
+        let mut loaded: LoRALinear<5, 3, 2, TestDevice> = dev.build_module();
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+        assert_eq!(loaded.linear.weight.array(), saved.linear.weight.array());
+        assert_eq!(loaded.a.array(), saved.a.array());
+        assert_eq!(loaded.b.array(), saved.b.array());
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_save_load_conv_weight_norms() {
+        let dev: TestDevice = Default::default();
+        type T = SpectralNorm<Conv2D<2, 4, 3, 1, 0, TestDevice>, TestDevice>;
+        test_save_load::<Rank3<2, 8, 8>, f32, TestDevice, T>(&dev);
+        type U = WeightNorm<Conv2D<2, 4, 3, 1, 0, TestDevice>, TestDevice>;
+        test_save_load::<Rank3<2, 8, 8>, f32, TestDevice, U>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_fused_linear() {
+        let dev: TestDevice = Default::default();
+        type T = LinearReLU<5, 5, TestDevice>;
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+        test_save_load::<Rank2<3, 5>, f32, TestDevice, (T, T)>(&dev);
+    }
+
     #[test]
     fn test_save_load_tuple() {
         let dev: TestDevice = Default::default();
@@ -454,6 +775,52 @@ mod tests {
         assert_eq!(loaded.forward(x).array(), y.array());
     }
 
+    #[test]
+    fn test_save_load_running_norm() {
+        type M = RunningNorm1D<3, TestDevice>;
+        let dev: TestDevice = Default::default();
+        let x = dev.sample_normal::<Rank2<4, 3>>();
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut saved: M = dev.build_module();
+        let mut loaded: M = dev.build_module();
+
+        let _ = saved.forward_mut(x.clone() * 2.0 + 1.0);
+        let y = saved.forward(x.clone());
+
+        assert_ne!(loaded.forward(x.clone()).array(), y.array());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+
+        assert_eq!(loaded.count, 4);
+        assert_eq!(loaded.forward(x).array(), y.array());
+    }
+
+    #[test]
+    fn test_save_load_prelu_swish() {
+        type M = (PReLU<3, TestDevice>, Swish<TestDevice>);
+        let dev: TestDevice = Default::default();
+        let x = dev.sample_normal::<Rank1<3>>();
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut saved: M = dev.build_module();
+        let mut loaded: M = dev.build_module();
+
+        saved.0.a.fill_with_distr(Standard);
+        saved.1.beta.fill_with_distr(Standard);
+        let y = saved.forward(x.clone());
+
+        assert_ne!(loaded.forward(x.clone()).array(), y.array());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+
+        assert_eq!(loaded.forward(x).array(), y.array());
+    }
+
     #[test]
     fn test_save_load_repeated() {
         type T = Repeated<Linear<3, 3, TestDevice>, 4>;
@@ -462,6 +829,98 @@ mod tests {
         test_save_load::<Rank1<3>, f32, TestDevice, (T, T)>(&dev);
     }
 
+    #[test]
+    fn test_save_load_sequential() {
+        type T = (Linear<3, 3, TestDevice>, ReLU);
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(usize,), f32, TestDevice> = dev.sample_like(&(3,), StandardNormal);
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut saved: Sequential<(usize,), TestDevice> = dev.build_module();
+        let mut loaded: Sequential<(usize,), TestDevice> = dev.build_module();
+        for _ in 0..3 {
+            saved.push((DynLinear::new(&dev, 3, 3), ReLU));
+            loaded.push((DynLinear::new(&dev, 3, 3), ReLU));
+        }
+
+        let y = saved.forward(x.clone());
+        assert_ne!(loaded.forward(x.clone()).as_vec(), y.as_vec());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+        assert_eq!(loaded.forward(x.clone()).as_vec(), y.as_vec());
+
+        // same layout as tuples
+        let mut tuple: (T, T, T) = dev.build_module();
+        tuple.load(file.path()).expect("");
+        let x: Tensor<Rank1<3>, f32, TestDevice> = x.reshape_like(&(Const,));
+        assert_eq!(tuple.forward(x).as_vec(), y.as_vec());
+    }
+
+    #[test]
+    fn test_save_load_model_config() {
+        let dev: TestDevice = Default::default();
+        let config = ModelConfig {
+            layers: std::vec![
+                LayerConfig::Linear { inp: 3, out: 4 },
+                LayerConfig::Repeated {
+                    count: 2,
+                    layers: std::vec![LayerConfig::Residual {
+                        layers: std::vec![
+                            LayerConfig::LayerNorm { dim: 4 },
+                            LayerConfig::Linear { inp: 4, out: 4 },
+                        ],
+                    }],
+                },
+            ],
+        };
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let saved = config.build(&dev);
+        let mut loaded = config.build(&dev);
+
+        let x = dev.sample_like(&(2, 3), StandardNormal);
+        let y = saved.forward(x.clone());
+        assert_ne!(loaded.forward(x.clone()).as_vec(), y.as_vec());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+        assert_eq!(loaded.forward(x).as_vec(), y.as_vec());
+    }
+
+    #[test]
+    fn test_save_load_pruned() {
+        type Model = (Pruned<Linear<4, 4, TestDevice>, TestDevice>, ReLU);
+        let dev: TestDevice = Default::default();
+        let mut saved: Model = dev.build_module();
+        saved.0.prune_magnitude(0.5);
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        saved.save(file.path()).expect("");
+
+        let mut loaded: Model = dev.build_module();
+        loaded.load(file.path()).expect("");
+        assert_eq!(loaded.0.sparsity(), 0.5);
+        assert_eq!(
+            loaded.0.module.weight.array(),
+            saved.0.module.weight.array()
+        );
+
+        let x = dev.sample_normal::<Rank1<4>>();
+        assert_eq!(
+            loaded.forward(x.clone()).array(),
+            saved.forward(x.clone()).array()
+        );
+
+        // the loaded masks are applied in forward
+        loaded.0.module.weight = loaded.0.module.weight.clone() + 1.0;
+        let _ = loaded.forward_mut(x);
+        let zeros = |w: Vec<f32>| w.iter().map(|w| *w == 0.0).collect::<Vec<_>>();
+        assert_eq!(
+            zeros(loaded.0.module.weight.as_vec()),
+            zeros(saved.0.module.weight.as_vec())
+        );
+    }
+
     #[test]
     fn test_save_load_residual() {
         type T = Residual<Linear<5, 5, TestDevice>>;
@@ -0,0 +1,532 @@
+#![allow(clippy::type_complexity)]
+
+use crate::{
+    gradients::{Gradients, NoneTape, OwnedTape, Tape},
+    shapes::*,
+    tensor::*,
+    tensor_ops::*,
+    unique_id::UniqueId,
+};
+
+use super::*;
+
+use std::{collections::BTreeMap, vec::Vec};
+
+/// Per-sample (per-example) gradients of the parameters of a model, computed with a single
+/// backward pass over a batch, instead of one backward pass per sample. These are needed for
+/// differentially private training, influence functions, and [crate::optim::diagonal_fisher()].
+///
+/// Run the forward pass with [PerSampleModule::forward_per_sample()], which records every
+/// parameter it uses here. The backward pass of the loss then also computes the gradient of
+/// each parameter for each sample of the batch. Use [PerSampleGradients::get()] to get them
+/// from the resulting [Gradients], as a `(batch, num_elements)` tensor with one flattened
+/// gradient per row. The usual (summed) gradients of the parameters are computed as well.
+///
+/// The loss must be a sum of independent per-sample losses for the per-sample gradients to
+/// be meaningful, so use a sum instead of a mean over the batch (or scale them afterwards),
+/// and avoid layers that mix samples like [BatchNorm2D].
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut model: (Linear<3, 4>, ReLU, Linear<4, 2>) = dev.build_module();
+/// let x: Tensor<Rank2<5, 3>> = dev.sample_normal();
+/// let mut per_sample = PerSampleGradients::default();
+/// let y = model.forward_per_sample(x.trace(), &mut per_sample);
+/// let grads = y.square().sum().backward();
+///
+/// let w = per_sample.get(&grads, &model.0.weight).unwrap();
+/// assert_eq!(w.shape(), &(5, 12));
+/// let norms = per_sample.norms(&grads);
+/// assert_eq!(norms.shape(), &(5,));
+/// ```
+#[derive(Debug)]
+pub struct PerSampleGradients<D: DeviceStorage> {
+    /// The per-sample gradients of each parameter are stored in [Gradients] under the id of
+    /// a placeholder tensor.
+    placeholders: BTreeMap<UniqueId, Tensor<(usize, usize), f32, D>>,
+}
+
+impl<D: DeviceStorage> Default for PerSampleGradients<D> {
+    fn default() -> Self {
+        Self {
+            placeholders: Default::default(),
+        }
+    }
+}
+
+impl<D: Device<f32>> PerSampleGradients<D> {
+    /// The per-sample gradients of `param` as a `(batch, num_elements)` tensor, or `None` if
+    /// `param` wasn't used by [PerSampleModule::forward_per_sample()], or `grads` doesn't
+    /// contain its per-sample gradients (e.g. they are from a different backward pass).
+    pub fn get<S: Shape>(
+        &self,
+        grads: &Gradients<D>,
+        param: &Tensor<S, f32, D>,
+    ) -> Option<Tensor<(usize, usize), f32, D>> {
+        let placeholder = self.placeholders.get(&param.id)?;
+        let g = grads.get_checked(placeholder)?;
+        Some(placeholder.device.upgrade(g.clone()))
+    }
+
+    /// The L2 norm of the gradient of all recorded parameters together, for each sample.
+    /// Parameters without per-sample gradients in `grads` are skipped.
+    ///
+    /// **Panics** if `grads` contains no per-sample gradients.
+    pub fn norms(&self, grads: &Gradients<D>) -> Tensor<(usize,), f32, D> {
+        self.try_norms(grads).unwrap()
+    }
+
+    /// Fallible version of [PerSampleGradients::norms()]
+    pub fn try_norms(&self, grads: &Gradients<D>) -> Result<Tensor<(usize,), f32, D>, D::Err> {
+        let mut total: Option<Tensor<(usize,), f32, D>> = None;
+        for placeholder in self.placeholders.values() {
+            let g = match grads.get_checked(placeholder) {
+                Some(g) => placeholder.device.upgrade(g.clone()),
+                None => continue,
+            };
+            let sq = g.try_square()?.try_sum::<_, Axis<1>>()?;
+            total = Some(match total {
+                Some(total) => total.try_add(sq)?,
+                None => sq,
+            });
+        }
+        total
+            .expect("No per-sample gradients were recorded")
+            .try_sqrt()
+    }
+
+    /// The parameters that per-sample gradients were recorded for, by id.
+    pub fn params(&self) -> impl Iterator<Item = &UniqueId> {
+        self.placeholders.keys()
+    }
+
+    /// Adds a backward op after `y` that calls `f` with the gradient of `y`, and stores the
+    /// resulting `(batch, num_elements)` per-sample gradients for `params`. If a parameter is
+    /// used more than once, its per-sample gradients are added up.
+    fn try_record<S: Shape, F>(
+        &mut self,
+        y: Tensor<S, f32, D, OwnedTape<D>>,
+        params: &[UniqueId],
+        f: F,
+    ) -> Result<Tensor<S, f32, D, OwnedTape<D>>, D::Err>
+    where
+        F: 'static
+            + FnOnce(Tensor<S, f32, D>) -> Result<Vec<Tensor<(usize, usize), f32, D>>, D::Err>,
+    {
+        let (y, mut tape) = y.split_tape();
+        let mut placeholders = Vec::with_capacity(params.len());
+        for id in params {
+            if !self.placeholders.contains_key(id) {
+                let placeholder = y.device.try_zeros_like(&(0, 0))?;
+                self.placeholders.insert(*id, placeholder);
+            }
+            placeholders.push(self.placeholders[id].clone());
+        }
+        let phantom = y.clone();
+        tape.try_alloc_output_grad(&y)?;
+        tape.add_backward_op(move |grads| {
+            let g = phantom.device.upgrade(grads.get(&phantom).clone());
+            for (placeholder, g) in placeholders.iter().zip(f(g)?) {
+                let g = match grads.remove(placeholder) {
+                    Some(prev) => placeholder.device.upgrade(prev).try_add(g)?,
+                    None => g,
+                };
+                grads.insert(placeholder, g.storage);
+            }
+            Ok(())
+        });
+        Ok(y.put_tape(tape))
+    }
+}
+
+/// Modules that can compute per-sample gradients of their parameters with
+/// [PerSampleGradients]. This is implemented for [Linear], [Conv2D], modules without
+/// parameters, and tuples of these.
+///
+/// Inputs must be batched, and have an [OwnedTape].
+pub trait PerSampleModule<Input, D: DeviceStorage> {
+    type Output;
+
+    /// Like [ModuleMut::forward_mut()], but records the parameters in `grads`, so that the
+    /// backward pass also computes their per-sample gradients.
+    fn forward_per_sample(
+        &mut self,
+        input: Input,
+        grads: &mut PerSampleGradients<D>,
+    ) -> Self::Output {
+        self.try_forward_per_sample(input, grads).unwrap()
+    }
+
+    /// Fallible version of [PerSampleModule::forward_per_sample()]
+    fn try_forward_per_sample(
+        &mut self,
+        input: Input,
+        grads: &mut PerSampleGradients<D>,
+    ) -> Result<Self::Output, D::Err>;
+}
+
+impl<B: Dim, const I: usize, const O: usize, D: Device<f32>>
+    PerSampleModule<Tensor<(B, Const<I>), f32, D, OwnedTape<D>>, D> for Linear<I, O, D>
+{
+    type Output = Tensor<(B, Const<O>), f32, D, OwnedTape<D>>;
+
+    /// The per-sample gradients are the outer products of the gradient of the output and
+    /// the input of each sample.
+    fn try_forward_per_sample(
+        &mut self,
+        x: Tensor<(B, Const<I>), f32, D, OwnedTape<D>>,
+        grads: &mut PerSampleGradients<D>,
+    ) -> Result<Self::Output, D::Err> {
+        let b = x.shape().0.size();
+        let x_data = x.retaped::<NoneTape>().try_reshape_like(&(b, Const::<I>))?;
+        let y = self.forward(x);
+        grads.try_record(y, &[self.weight.id, self.bias.id], move |g| {
+            let g = g.try_reshape_like(&(b, Const::<O>))?;
+            let shape = (b, Const::<O>, Const::<I>);
+            let w = g.clone().try_broadcast_like::<_, Axis<2>>(&shape)?;
+            let w = w.try_mul(x_data.try_broadcast_like::<_, Axis<1>>(&shape)?)?;
+            Ok(alloc::vec![
+                w.try_reshape_like(&(b, O * I))?,
+                g.try_reshape_like(&(b, O))?,
+            ])
+        })
+    }
+}
+
+impl<B: Dim, S: Dim, const I: usize, const O: usize, D: Device<f32>>
+    PerSampleModule<Tensor<(B, S, Const<I>), f32, D, OwnedTape<D>>, D> for Linear<I, O, D>
+{
+    type Output = Tensor<(B, S, Const<O>), f32, D, OwnedTape<D>>;
+
+    /// The per-sample gradients are summed over the sequence of each sample.
+    fn try_forward_per_sample(
+        &mut self,
+        x: Tensor<(B, S, Const<I>), f32, D, OwnedTape<D>>,
+        grads: &mut PerSampleGradients<D>,
+    ) -> Result<Self::Output, D::Err> {
+        let &(b, s, _) = x.shape();
+        let (b, s) = (b.size(), s.size());
+        let x_data = x
+            .retaped::<NoneTape>()
+            .try_reshape_like(&(b, s, Const::<I>))?;
+        let y = self.forward(x);
+        grads.try_record(y, &[self.weight.id, self.bias.id], move |g| {
+            let g = g.try_reshape_like(&(b, s, Const::<O>))?;
+            let shape = (b, s, Const::<O>, Const::<I>);
+            let w = g.clone().try_broadcast_like::<_, Axis<3>>(&shape)?;
+            let w = w.try_mul(x_data.try_broadcast_like::<_, Axis<2>>(&shape)?)?;
+            let w = w.try_sum::<_, Axis<1>>()?;
+            Ok(alloc::vec![
+                w.try_reshape_like(&(b, O * I))?,
+                g.try_sum::<_, Axis<1>>()?.try_reshape_like(&(b, O))?,
+            ])
+        })
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<
+        B,
+        const C: usize,
+        H,
+        W,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        D,
+        H2,
+        W2,
+    > PerSampleModule<Tensor<(B, Const<C>, H, W), f32, D, OwnedTape<D>>, D>
+    for Conv2D<C, O, K, S, P, D>
+where
+    B: Dim,
+    H: Dim,
+    W: Dim,
+    H2: Dim,
+    W2: Dim,
+    D: Device<f32>,
+    Self: Module<
+        Tensor<(B, Const<C>, H, W), f32, D, OwnedTape<D>>,
+        Output = Tensor<(B, Const<O>, H2, W2), f32, D, OwnedTape<D>>,
+    >,
+    Tensor<(B, Const<C>, H, W), f32, D>:
+        TryUnfold2D<K, S, P, Output = Tensor<(usize, usize, usize), f32, D>, Err = D::Err>,
+{
+    type Output = Tensor<(B, Const<O>, H2, W2), f32, D, OwnedTape<D>>;
+
+    /// The per-sample gradients of the filters are computed for the whole batch at once,
+    /// from the gradient of the output and the unfolded patches of the input (im2col).
+    fn try_forward_per_sample(
+        &mut self,
+        x: Tensor<(B, Const<C>, H, W), f32, D, OwnedTape<D>>,
+        grads: &mut PerSampleGradients<D>,
+    ) -> Result<Self::Output, D::Err> {
+        let b = x.shape().0.size();
+        let x_data = x.retaped::<NoneTape>();
+        let y = self.forward(x);
+        grads.try_record(y, &[self.weight.id, self.bias.id], move |g| {
+            let patches = x_data.try_unfold2d()?;
+            let &(_, n, l) = patches.shape();
+            let g = g.try_reshape_like(&(b, Const::<O>, l))?;
+            let shape = (b, Const::<O>, n, l);
+            let w = g.clone().try_broadcast_like::<_, Axis<2>>(&shape)?;
+            let w = w.try_mul(patches.try_broadcast_like::<_, Axis<1>>(&shape)?)?;
+            let w = w.try_sum::<_, Axis<3>>()?;
+            Ok(alloc::vec![
+                w.try_reshape_like(&(b, O * n))?,
+                g.try_sum::<_, Axis<2>>()?.try_reshape_like(&(b, O))?,
+            ])
+        })
+    }
+}
+
+macro_rules! per_sample_without_params {
+    ($Ty:ty, [$($Gens:tt)*]) => {
+        impl<Input, D: DeviceStorage, $($Gens)*> PerSampleModule<Input, D> for $Ty
+        where
+            Self: ModuleMut<Input>,
+        {
+            type Output = <Self as ModuleMut<Input>>::Output;
+            fn try_forward_per_sample(
+                &mut self,
+                input: Input,
+                _: &mut PerSampleGradients<D>,
+            ) -> Result<Self::Output, D::Err> {
+                Ok(self.forward_mut(input))
+            }
+        }
+    };
+}
+
+per_sample_without_params!(ReLU, []);
+per_sample_without_params!(Sin, []);
+per_sample_without_params!(Cos, []);
+per_sample_without_params!(Ln, []);
+per_sample_without_params!(Exp, []);
+per_sample_without_params!(Sigmoid, []);
+per_sample_without_params!(Tanh, []);
+per_sample_without_params!(Square, []);
+per_sample_without_params!(Sqrt, []);
+per_sample_without_params!(Abs, []);
+per_sample_without_params!(GeLU, []);
+per_sample_without_params!(FastGeLU, []);
+per_sample_without_params!(SiLU, []);
+per_sample_without_params!(Mish, []);
+per_sample_without_params!(LeakyReLU, []);
+per_sample_without_params!(ELU, []);
+per_sample_without_params!(Softplus, []);
+per_sample_without_params!(Softmax, []);
+per_sample_without_params!(Dropout, []);
+per_sample_without_params!(DropoutOneIn<N>, [const N: usize]);
+per_sample_without_params!(AvgPoolGlobal, []);
+per_sample_without_params!(MaxPoolGlobal, []);
+per_sample_without_params!(MinPoolGlobal, []);
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+per_sample_without_params!(Flatten2D, []);
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+per_sample_without_params!(AvgPool2D<K, S, P>, [const K: usize, const S: usize, const P: usize]);
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+per_sample_without_params!(MaxPool2D<K, S, P>, [const K: usize, const S: usize, const P: usize]);
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+per_sample_without_params!(MinPool2D<K, S, P>, [const K: usize, const S: usize, const P: usize]);
+
+macro_rules! tuple_impls {
+    ([$($name:ident),+] [$($idx:tt),+], $last:ident, [$($rev_tail:ident),+]) => {
+        impl<
+            Input,
+            D: DeviceStorage,
+            $last:
+            $(PerSampleModule::<$rev_tail ::Output, D>, $rev_tail: )+
+            PerSampleModule<Input, D>
+        > PerSampleModule<Input, D> for ($($name,)+) {
+            type Output = $last ::Output;
+
+            /// Calls forward_per_sample sequentially on each module in the tuple.
+            fn try_forward_per_sample(
+                &mut self,
+                x: Input,
+                grads: &mut PerSampleGradients<D>,
+            ) -> Result<Self::Output, D::Err> {
+                $(let x = self.$idx.try_forward_per_sample(x, grads)?;)+
+                Ok(x)
+            }
+        }
+    };
+}
+
+tuple_impls!([M1, M2] [0, 1], M2, [M1]);
+tuple_impls!([M1, M2, M3] [0, 1, 2], M3, [M2, M1]);
+tuple_impls!([M1, M2, M3, M4] [0, 1, 2, 3], M4, [M3, M2, M1]);
+tuple_impls!([M1, M2, M3, M4, M5] [0, 1, 2, 3, 4], M5, [M4, M3, M2, M1]);
+tuple_impls!([M1, M2, M3, M4, M5, M6] [0, 1, 2, 3, 4, 5], M6, [M5, M4, M3, M2, M1]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_close, TestDevice};
+
+    /// Row `i` of the per-sample gradients of `param`.
+    fn row<S: Shape>(
+        per_sample: &PerSampleGradients<TestDevice>,
+        grads: &Gradients<TestDevice>,
+        param: &Tensor<S, f32, TestDevice>,
+        i: usize,
+    ) -> std::vec::Vec<f32> {
+        let g = per_sample.get(grads, param).unwrap();
+        let n = g.shape().1;
+        g.as_vec()[i * n..(i + 1) * n].to_vec()
+    }
+
+    #[test]
+    fn test_per_sample_linear_matches_loop() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Linear<3, 4, _>, Tanh, Linear<4, 2, _>) = dev.build_module();
+        let x: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+
+        let mut per_sample = PerSampleGradients::default();
+        let y = model.forward_per_sample(x.trace(), &mut per_sample);
+        let grads = y.square().sum().backward();
+        assert_eq!(per_sample.params().count(), 4);
+
+        let mut norms = [0.0; 5];
+        for (i, norm) in norms.iter_mut().enumerate() {
+            let x_i: Tensor<Rank1<3>, f32, _> = dev.tensor(x.array()[i]);
+            let g = model.forward(x_i.trace()).square().sum().backward();
+            let w0 = g.get(&model.0.weight).as_vec();
+            let b0 = g.get(&model.0.bias).as_vec();
+            let w2 = g.get(&model.2.weight).as_vec();
+            let b2 = g.get(&model.2.bias).as_vec();
+            assert_close(&row(&per_sample, &grads, &model.0.weight, i), &w0);
+            assert_close(&row(&per_sample, &grads, &model.0.bias, i), &b0);
+            assert_close(&row(&per_sample, &grads, &model.2.weight, i), &w2);
+            assert_close(&row(&per_sample, &grads, &model.2.bias, i), &b2);
+            *norm = [w0, b0, w2, b2]
+                .iter()
+                .flat_map(|g| g.iter())
+                .map(|x| x * x)
+                .sum::<f32>()
+                .sqrt();
+        }
+        assert_close(&per_sample.norms(&grads).as_vec(), &norms.to_vec());
+
+        // the summed gradients are still computed
+        let g = model.forward(x.trace()).square().sum().backward();
+        assert_close(
+            &grads.get(&model.0.weight).array(),
+            &g.get(&model.0.weight).array(),
+        );
+    }
+
+    #[test]
+    fn test_per_sample_linear_sequences() {
+        let dev: TestDevice = Default::default();
+        let mut model: Linear<3, 2, _> = dev.build_module();
+        let x: Tensor<Rank3<2, 4, 3>, f32, _> = dev.sample_normal();
+
+        let mut per_sample = PerSampleGradients::default();
+        let y = model.forward_per_sample(x.trace(), &mut per_sample);
+        let grads = y.exp().sum().backward();
+
+        for i in 0..2 {
+            let x_i: Tensor<Rank2<4, 3>, f32, _> = dev.tensor(x.array()[i]);
+            let g = model.forward(x_i.trace()).exp().sum().backward();
+            assert_close(
+                &row(&per_sample, &grads, &model.weight, i),
+                &g.get(&model.weight).as_vec(),
+            );
+            assert_close(
+                &row(&per_sample, &grads, &model.bias, i),
+                &g.get(&model.bias).as_vec(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_per_sample_shared_param_accumulates() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Linear<2, 2, _>, ReLU, Linear<2, 2, _>) = dev.build_module();
+        model.2 = model.0.clone();
+        let x: Tensor<Rank2<3, 2>, f32, _> = dev.sample_normal();
+
+        let mut per_sample = PerSampleGradients::default();
+        let y = model.forward_per_sample(x.trace(), &mut per_sample);
+        let grads = y.sum().backward();
+        assert_eq!(per_sample.params().count(), 2);
+
+        for i in 0..3 {
+            let x_i: Tensor<Rank1<2>, f32, _> = dev.tensor(x.array()[i]);
+            let g = model.forward(x_i.trace()).sum().backward();
+            assert_close(
+                &row(&per_sample, &grads, &model.0.weight, i),
+                &g.get(&model.0.weight).as_vec(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_per_sample_missing_grads() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Linear<2, 3, _>, Linear<3, 1, _>) = dev.build_module();
+        let x: Tensor<Rank2<4, 2>, f32, _> = dev.sample_normal();
+        let h: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+
+        let mut per_sample = PerSampleGradients::default();
+        let _ = model.0.forward_per_sample(x.trace(), &mut per_sample);
+        let y = model.1.forward_per_sample(h.trace(), &mut per_sample);
+        let grads = y.sum().backward();
+        assert!(per_sample.get(&grads, &model.0.weight).is_none());
+        assert!(per_sample.get(&grads, &model.1.weight).is_some());
+        let w = per_sample.get(&grads, &model.1.weight).unwrap();
+        let b = per_sample.get(&grads, &model.1.bias).unwrap();
+        let norms = (w.square().sum::<_, Axis<1>>() + b.square().sum::<_, Axis<1>>()).sqrt();
+        assert_close(&per_sample.norms(&grads).as_vec(), &norms.as_vec());
+    }
+
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    #[test]
+    fn test_per_sample_conv_matches_loop() {
+        let dev: TestDevice = Default::default();
+        let mut model: Conv2D<2, 3, 2, 1, 1, _> = dev.build_module();
+        let x: Tensor<Rank4<2, 2, 3, 3>, f32, _> = dev.sample_normal();
+
+        let mut per_sample = PerSampleGradients::default();
+        let y = model.forward_per_sample(x.trace(), &mut per_sample);
+        let grads = y.square().sum().backward();
+
+        for i in 0..2 {
+            let x_i: Tensor<Rank3<2, 3, 3>, f32, _> = dev.tensor(x.array()[i]);
+            let g = model.forward(x_i.trace()).square().sum().backward();
+            assert_close(
+                &row(&per_sample, &grads, &model.weight, i),
+                &g.get(&model.weight).as_vec(),
+            );
+            assert_close(
+                &row(&per_sample, &grads, &model.bias, i),
+                &g.get(&model.bias).as_vec(),
+            );
+        }
+    }
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    #[test]
+    fn test_per_sample_conv_stride_2() {
+        let dev: TestDevice = Default::default();
+        let mut model: Conv2D<1, 2, 3, 2, 1, _> = dev.build_module();
+        let x: Tensor<Rank4<3, 1, 5, 5>, f32, _> = dev.sample_normal();
+
+        let mut per_sample = PerSampleGradients::default();
+        let y = model.forward_per_sample(x.trace(), &mut per_sample);
+        let grads = y.exp().sum().backward();
+
+        for i in 0..3 {
+            let x_i: Tensor<Rank3<1, 5, 5>, f32, _> = dev.tensor(x.array()[i]);
+            let g = model.forward(x_i.trace()).exp().sum().backward();
+            assert_close(
+                &row(&per_sample, &grads, &model.weight, i),
+                &g.get(&model.weight).as_vec(),
+            );
+        }
+    }
+}
@@ -0,0 +1,870 @@
+use crate::{optim::*, shapes::*, tensor::*, tensor_ops::*, unique_id::UniqueId};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use super::{IntoInference, Linear, Module, ModuleMut, ResetParams};
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+use super::Conv2D;
+
+use std::vec::Vec;
+
+/// Prunes the parameters of `M` with binary masks, for model compression.
+///
+/// Every parameter has a mask on the device with one entry per element, which is `0.0` for
+/// the pruned elements. The masks are multiplied into the parameters in every forward pass,
+/// and after every [GradientUpdate::update()] and [ResetParams::reset_params()], so the
+/// pruned elements stay zero during training. Masks only ever grow.
+///
+/// There are two ways of pruning:
+/// - [PruneMagnitude::prune_magnitude()]: unstructured pruning of the elements with the smallest
+///   magnitude in each weight of `M`.
+/// - [PruneChannels::prune_channels()]: structured pruning of whole output channels of a [Linear]
+///   or [Conv2D], with the smallest L2 norm. These can then be removed with
+///   [Pruned::sparsify()], which results in a smaller layer.
+///
+/// [PruningSchedule] increases the sparsity gradually over training.
+///
+/// The masks are saved along with the parameters by both [super::SaveToNpz] and
+/// [super::SaveToBinary].
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<4, 8>, ReLU, Linear<8, 2>);
+/// let mut model: Pruned<Model> = dev.build_module();
+/// let schedule = PruningSchedule {
+///     initial_sparsity: 0.0,
+///     final_sparsity: 0.75,
+///     begin_step: 0,
+///     end_step: 20,
+///     frequency: 5,
+/// };
+/// let mut sgd: Sgd<_> = Default::default();
+/// for step in 0..=20 {
+///     if schedule.should_prune(step) {
+///         model.prune_magnitude(schedule.sparsity(step));
+///     }
+///     let y = model.forward_mut(dev.sample_normal::<Rank2<3, 4>>().trace());
+///     sgd.update(&mut model, y.square().mean().backward()).expect("");
+/// }
+/// assert_eq!(model.sparsity(), 0.75);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pruned<M, D: Device<f32> = Cpu> {
+    pub module: M,
+    /// The flattened mask of each parameter, in the order they are visited. Parameters
+    /// without a mask yet aren't pruned.
+    masks: Vec<Tensor<(usize,), f32, D>>,
+    channels: Option<Vec<bool>>,
+}
+
+impl<M: Default, D: Device<f32>> Default for Pruned<M, D> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+/// Sparsity increasing from `initial_sparsity` at `begin_step` to `final_sparsity` at
+/// `end_step`, quickly at first and slower towards the end, from
+/// [To prune, or not to prune](https://arxiv.org/abs/1710.01878):
+/// `final + (initial - final) * (1 - (step - begin) / (end - begin))^3`.
+#[derive(Debug, Clone, Copy)]
+pub struct PruningSchedule {
+    pub initial_sparsity: f32,
+    pub final_sparsity: f32,
+    pub begin_step: usize,
+    pub end_step: usize,
+    /// The number of steps between pruning.
+    pub frequency: usize,
+}
+
+impl PruningSchedule {
+    /// The target sparsity at `step`.
+    pub fn sparsity(&self, step: usize) -> f32 {
+        if step <= self.begin_step {
+            return self.initial_sparsity;
+        }
+        if step >= self.end_step {
+            return self.final_sparsity;
+        }
+        let t = (step - self.begin_step) as f32 / (self.end_step - self.begin_step) as f32;
+        self.final_sparsity + (self.initial_sparsity - self.final_sparsity) * (1.0 - t).powi(3)
+    }
+
+    /// Whether to prune at `step`: every `frequency` steps between `begin_step` and `end_step`.
+    pub fn should_prune(&self, step: usize) -> bool {
+        step >= self.begin_step
+            && step <= self.end_step
+            && (step - self.begin_step).is_multiple_of(self.frequency.max(1))
+    }
+}
+
+/// Copies `t` to the host.
+fn host<S: Shape, D: Device<f32>>(t: &Tensor<S, f32, D>) -> Vec<f32> {
+    let mut data = alloc::vec![0.0; t.shape().num_elements()];
+    t.copy_into(&mut data);
+    data
+}
+
+/// The mask of the `i`th parameter `p`, which is created without any pruned elements if
+/// `p` doesn't have one yet.
+fn mask_for<'a, S: Shape, D: Device<f32>>(
+    masks: &'a mut Vec<Tensor<(usize,), f32, D>>,
+    i: usize,
+    p: &Tensor<S, f32, D>,
+) -> Result<&'a mut Tensor<(usize,), f32, D>, D::Err> {
+    debug_assert!(i <= masks.len());
+    if i == masks.len() {
+        masks.push(p.device.try_ones_like(&(p.shape().num_elements(),))?);
+    }
+    Ok(&mut masks[i])
+}
+
+/// Multiplies `mask` into `p` on the device. Only the storage is replaced, so `p` keeps its
+/// id and any optimizer state for it.
+fn try_apply_mask<S: Shape, D: Device<f32>>(
+    p: &mut Tensor<S, f32, D>,
+    mask: &Tensor<(usize,), f32, D>,
+) -> Result<(), D::Err> {
+    let masked = p
+        .clone()
+        .try_mul(mask.clone().try_reshape_like(p.shape())?)?;
+    p.storage = masked.storage;
+    Ok(())
+}
+
+/// Prunes `amount` of the elements with the smallest `scores`, on top of the already pruned
+/// ones in `mask`.
+fn prune_smallest(scores: &[f32], mask: &mut [bool], amount: f32) {
+    let num = (amount.clamp(0.0, 1.0) * scores.len() as f32).round() as usize;
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| match (mask[a], mask[b]) {
+        (false, true) => core::cmp::Ordering::Less,
+        (true, false) => core::cmp::Ordering::Greater,
+        _ => scores[a].total_cmp(&scores[b]),
+    });
+    for &i in order.iter().take(num) {
+        mask[i] = false;
+    }
+}
+
+/// Calls `U`, then applies the masks, and visits each mask with
+/// [ParamUpdater::update_buffer()] so they are saved with the parameters.
+struct ApplyMasks<'a, U, D: Device<f32>> {
+    updater: &'a mut U,
+    masks: &'a mut Vec<Tensor<(usize,), f32, D>>,
+    i: usize,
+}
+
+impl<'a, U: ParamUpdater<D, f32>, D: Device<f32>> ParamUpdater<D, f32> for ApplyMasks<'a, U, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        self.updater.update_param(p, unused)?;
+        let mask = mask_for(self.masks, self.i, p)?;
+        self.updater.update_buffer(mask)?;
+        try_apply_mask(p, mask)?;
+        self.i += 1;
+        Ok(())
+    }
+
+    fn update_running_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, f32, D>,
+        var: &mut Tensor<S, f32, D>,
+        momentum: &mut f32,
+    ) -> Result<(), D::Err> {
+        self.updater.update_running_stats(mean, var, momentum)
+    }
+
+    fn update_accumulated_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, f32, D>,
+        var: &mut Tensor<S, f32, D>,
+        count: &mut u64,
+    ) -> Result<(), D::Err> {
+        self.updater.update_accumulated_stats(mean, var, count)
+    }
+
+    fn update_buffer<S: Shape>(&mut self, buffer: &mut Tensor<S, f32, D>) -> Result<(), D::Err> {
+        self.updater.update_buffer(buffer)
+    }
+}
+
+/// Leaves parameters as they are, so [ApplyMasks] only applies the masks.
+struct NoUpdate;
+
+impl<D: Device<f32>> ParamUpdater<D, f32> for NoUpdate {
+    fn update_param<S: Shape>(
+        &mut self,
+        _: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        Ok(())
+    }
+}
+
+/// Multiplies the masks into the parameters without creating missing ones, for
+/// [Module::forward()].
+struct MaskParams<'a, D: Device<f32>> {
+    masks: &'a [Tensor<(usize,), f32, D>],
+    i: usize,
+}
+
+impl<'a, D: Device<f32>> ParamUpdater<D, f32> for MaskParams<'a, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        if let Some(mask) = self.masks.get(self.i) {
+            try_apply_mask(p, mask)?;
+        }
+        self.i += 1;
+        Ok(())
+    }
+}
+
+/// Prunes the parameters with at least 2 dimensions by magnitude.
+struct MagnitudePruning<'a, D: Device<f32>> {
+    amount: f32,
+    masks: &'a mut Vec<Tensor<(usize,), f32, D>>,
+    i: usize,
+}
+
+impl<'a, D: Device<f32>> ParamUpdater<D, f32> for MagnitudePruning<'a, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let mask = mask_for(self.masks, self.i, p)?;
+        self.i += 1;
+        if S::NUM_DIMS < 2 {
+            return Ok(());
+        }
+        let scores: Vec<f32> = host(p).iter().map(|x| x.abs()).collect();
+        let mut keep: Vec<bool> = host(mask).iter().map(|m| *m != 0.0).collect();
+        prune_smallest(&scores, &mut keep, self.amount);
+        mask.copy_from(&keep.iter().map(|k| *k as u8 as f32).collect::<Vec<_>>());
+        Ok(())
+    }
+}
+
+/// Counts the pruned elements of the parameters with at least 2 dimensions.
+struct CountPruned<'a, D: Device<f32>> {
+    masks: &'a [Tensor<(usize,), f32, D>],
+    i: usize,
+    total: usize,
+    pruned: usize,
+}
+
+impl<'a, D: Device<f32>> ParamUpdater<D, f32> for CountPruned<'a, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        if S::NUM_DIMS >= 2 {
+            self.total += p.shape().num_elements();
+            if let Some(mask) = self.masks.get(self.i) {
+                self.pruned += host(mask).iter().filter(|m| **m == 0.0).count();
+            }
+        }
+        self.i += 1;
+        Ok(())
+    }
+}
+
+/// Collects the ids of the parameters, in the order they are visited.
+struct CollectIds(Vec<UniqueId>);
+
+impl<D: Device<f32>> ParamUpdater<D, f32> for CollectIds {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        self.0.push(p.id);
+        Ok(())
+    }
+}
+
+impl<M, D: Device<f32>> Pruned<M, D> {
+    /// Wraps `module` without pruning anything yet.
+    pub fn new(module: M) -> Self {
+        Self {
+            module,
+            masks: Default::default(),
+            channels: None,
+        }
+    }
+
+    /// Which output channels are kept after [PruneChannels::prune_channels()], e.g. to remove the
+    /// matching inputs of the next layer with [Linear::select_inputs()] or
+    /// [Conv2D::select_inputs()].
+    pub fn kept_channels(&self) -> Option<&[bool]> {
+        self.channels.as_deref()
+    }
+}
+
+impl<M: GradientUpdate<D, f32> + Clone, D: Device<f32>> Pruned<M, D> {
+    /// The fraction of the weights (the parameters with at least 2 dimensions) that is pruned.
+    pub fn sparsity(&self) -> f32 {
+        let mut count = CountPruned {
+            masks: &self.masks,
+            i: 0,
+            total: 0,
+            pruned: 0,
+        };
+        // clones of tensors share their data, so this doesn't copy the model
+        let _ = self
+            .module
+            .clone()
+            .update(&mut count, &mut Default::default());
+        if count.total == 0 {
+            0.0
+        } else {
+            count.pruned as f32 / count.total as f32
+        }
+    }
+
+    /// Multiplies the masks into the parameters, creating any missing masks.
+    fn try_apply_masks(&mut self) -> Result<(), D::Err> {
+        self.module.update(
+            &mut ApplyMasks {
+                updater: &mut NoUpdate,
+                masks: &mut self.masks,
+                i: 0,
+            },
+            &mut Default::default(),
+        )
+    }
+
+    /// The masks of all the parameters, including the ones that aren't pruned yet.
+    #[cfg(feature = "numpy")]
+    pub(super) fn all_masks(&self) -> Vec<Tensor<(usize,), f32, D>> {
+        let mut pruned = self.clone();
+        pruned.try_apply_masks().unwrap();
+        pruned.masks
+    }
+
+    /// Replaces the masks, and applies them.
+    #[cfg(feature = "numpy")]
+    pub(super) fn set_masks(&mut self, masks: Vec<Tensor<(usize,), f32, D>>) {
+        self.masks = masks;
+        self.try_apply_masks().unwrap();
+    }
+
+    /// A copy of the module with the masks multiplied into its parameters.
+    fn try_masked(&self) -> Result<M, D::Err> {
+        let mut module = self.module.clone();
+        module.update(
+            &mut MaskParams {
+                masks: &self.masks,
+                i: 0,
+            },
+            &mut Default::default(),
+        )?;
+        Ok(module)
+    }
+}
+
+/// Unstructured pruning of a [Pruned] model. See [Pruned] for an example.
+pub trait PruneMagnitude<D: Device<f32>> {
+    /// Prunes the elements with the smallest magnitude, so that `sparsity` (between 0 and 1)
+    /// of each weight is pruned. Only parameters with at least 2 dimensions are pruned, so
+    /// biases and normalization parameters stay dense.
+    fn prune_magnitude(&mut self, sparsity: f32) {
+        self.try_prune_magnitude(sparsity).unwrap()
+    }
+
+    /// Fallible version of [PruneMagnitude::prune_magnitude()]
+    fn try_prune_magnitude(&mut self, sparsity: f32) -> Result<(), D::Err>;
+}
+
+impl<D: Device<f32>, M: GradientUpdate<D, f32> + Clone> PruneMagnitude<D> for Pruned<M, D> {
+    fn try_prune_magnitude(&mut self, sparsity: f32) -> Result<(), D::Err> {
+        let mut pruning = MagnitudePruning {
+            amount: sparsity,
+            masks: &mut self.masks,
+            i: 0,
+        };
+        self.module.update(&mut pruning, &mut Default::default())?;
+        self.try_apply_masks()
+    }
+}
+
+/// Layers with output channels that can be pruned as a whole with
+/// [PruneChannels::prune_channels()]: the rows of the weight of [Linear], and the filters of
+/// [Conv2D].
+pub trait HasChannels {
+    /// The L2 norm of the weights of each output channel.
+    fn channel_norms(&self) -> Vec<f32>;
+
+    /// The masks of the parameters that remove the output channels where `keep` is false.
+    fn channel_masks(&self, keep: &[bool]) -> Vec<(UniqueId, Vec<bool>)>;
+}
+
+/// The L2 norm of each of the rows of length `len`.
+fn row_norms(data: &[f32], len: usize) -> Vec<f32> {
+    data.chunks(len.max(1))
+        .map(|row| row.iter().map(|x| x * x).sum::<f32>().sqrt())
+        .collect()
+}
+
+/// Repeats each entry of `keep` `len` times.
+fn repeat_rows(keep: &[bool], len: usize) -> Vec<bool> {
+    keep.iter()
+        .flat_map(|k| core::iter::repeat_n(*k, len))
+        .collect()
+}
+
+/// Keeps the entries of the middle axis of `(outer, keep.len(), inner)` data where `keep` is true.
+fn select_axis(data: &[f32], keep: &[bool], inner: usize) -> Vec<f32> {
+    data.chunks(inner)
+        .zip(keep.iter().cycle())
+        .filter(|(_, k)| **k)
+        .flat_map(|(x, _)| x.iter().copied())
+        .collect()
+}
+
+/// Checks that `keep` has `len` entries, `n` of which are true.
+fn check_keep(keep: &[bool], len: usize, n: usize) {
+    assert_eq!(keep.len(), len, "Expected one entry per channel");
+    assert_eq!(
+        keep.iter().filter(|k| **k).count(),
+        n,
+        "The number of kept channels doesn't match the new size"
+    );
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> HasChannels for Linear<I, O, D> {
+    fn channel_norms(&self) -> Vec<f32> {
+        row_norms(&host(&self.weight), I)
+    }
+
+    fn channel_masks(&self, keep: &[bool]) -> Vec<(UniqueId, Vec<bool>)> {
+        alloc::vec![
+            (self.weight.id, repeat_rows(keep, I)),
+            (self.bias.id, keep.to_vec()),
+        ]
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D> HasChannels
+    for Conv2D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    fn channel_norms(&self) -> Vec<f32> {
+        row_norms(&host(&self.weight), I * K * K)
+    }
+
+    fn channel_masks(&self, keep: &[bool]) -> Vec<(UniqueId, Vec<bool>)> {
+        alloc::vec![
+            (self.weight.id, repeat_rows(keep, I * K * K)),
+            (self.bias.id, keep.to_vec()),
+        ]
+    }
+}
+
+/// Structured pruning of a [Pruned] [Linear] or [Conv2D]. See [Pruned::sparsify()] for an
+/// example.
+pub trait PruneChannels<D: Device<f32>> {
+    /// Prunes the output channels with the smallest L2 norm, so that `sparsity` (between 0
+    /// and 1) of the channels is pruned. This sets both the weights and the bias of the
+    /// channels to zero.
+    fn prune_channels(&mut self, sparsity: f32) {
+        self.try_prune_channels(sparsity).unwrap()
+    }
+
+    /// Fallible version of [PruneChannels::prune_channels()]
+    fn try_prune_channels(&mut self, sparsity: f32) -> Result<(), D::Err>;
+}
+
+impl<D: Device<f32>, M: HasChannels + GradientUpdate<D, f32> + Clone> PruneChannels<D>
+    for Pruned<M, D>
+{
+    fn try_prune_channels(&mut self, sparsity: f32) -> Result<(), D::Err> {
+        let norms = self.module.channel_norms();
+        let mut keep = self
+            .channels
+            .take()
+            .unwrap_or_else(|| alloc::vec![true; norms.len()]);
+        prune_smallest(&norms, &mut keep, sparsity);
+
+        // the masks are in the order the parameters are visited in
+        let mut ids = CollectIds(Vec::new());
+        self.module
+            .clone()
+            .update(&mut ids, &mut Default::default())?;
+        self.try_apply_masks()?;
+        for (id, channel_mask) in self.module.channel_masks(&keep) {
+            let i = ids
+                .0
+                .iter()
+                .position(|p| *p == id)
+                .expect("Channel masks must be for parameters of the module");
+            let mut data = host(&self.masks[i]);
+            for (m, keep) in data.iter_mut().zip(channel_mask.iter()) {
+                if !keep {
+                    *m = 0.0;
+                }
+            }
+            self.masks[i].copy_from(&data);
+        }
+        self.channels = Some(keep);
+        self.try_apply_masks()
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> Linear<I, O, D> {
+    /// A layer with only the outputs where `keep` is true. **Panics** if `N` is not the
+    /// number of kept outputs.
+    pub fn select_outputs<const N: usize>(&self, keep: &[bool]) -> Linear<I, N, D> {
+        self.try_select_outputs(keep).unwrap()
+    }
+
+    /// Fallible version of [Linear::select_outputs()]
+    pub fn try_select_outputs<const N: usize>(
+        &self,
+        keep: &[bool],
+    ) -> Result<Linear<I, N, D>, D::Err> {
+        check_keep(keep, O, N);
+        let dev = &self.weight.device;
+        let mut weight = dev.try_zeros()?;
+        weight.copy_from(&select_axis(&host(&self.weight), keep, I));
+        let mut bias = dev.try_zeros()?;
+        bias.copy_from(&select_axis(&host(&self.bias), keep, 1));
+        Ok(Linear { weight, bias })
+    }
+
+    /// A layer with only the inputs where `keep` is true, e.g. the
+    /// [Pruned::kept_channels()] of the previous layer. **Panics** if `N` is not the number
+    /// of kept inputs.
+    pub fn select_inputs<const N: usize>(&self, keep: &[bool]) -> Linear<N, O, D> {
+        self.try_select_inputs(keep).unwrap()
+    }
+
+    /// Fallible version of [Linear::select_inputs()]
+    pub fn try_select_inputs<const N: usize>(
+        &self,
+        keep: &[bool],
+    ) -> Result<Linear<N, O, D>, D::Err> {
+        check_keep(keep, I, N);
+        let mut weight = self.weight.device.try_zeros()?;
+        weight.copy_from(&select_axis(&host(&self.weight), keep, 1));
+        Ok(Linear {
+            weight,
+            bias: self.bias.clone(),
+        })
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    Conv2D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    /// A layer with only the output channels where `keep` is true. **Panics** if `N` is not
+    /// the number of kept channels.
+    pub fn select_outputs<const N: usize>(&self, keep: &[bool]) -> Conv2D<I, N, K, S, P, D> {
+        self.try_select_outputs(keep).unwrap()
+    }
+
+    /// Fallible version of [Conv2D::select_outputs()]
+    pub fn try_select_outputs<const N: usize>(
+        &self,
+        keep: &[bool],
+    ) -> Result<Conv2D<I, N, K, S, P, D>, D::Err> {
+        check_keep(keep, O, N);
+        let dev = &self.weight.device;
+        let mut weight = dev.try_zeros()?;
+        weight.copy_from(&select_axis(&host(&self.weight), keep, I * K * K));
+        let mut bias = dev.try_zeros()?;
+        bias.copy_from(&select_axis(&host(&self.bias), keep, 1));
+        Ok(Conv2D { weight, bias })
+    }
+
+    /// A layer with only the input channels where `keep` is true, e.g. the
+    /// [Pruned::kept_channels()] of the previous layer. **Panics** if `N` is not the number
+    /// of kept channels.
+    pub fn select_inputs<const N: usize>(&self, keep: &[bool]) -> Conv2D<N, O, K, S, P, D> {
+        self.try_select_inputs(keep).unwrap()
+    }
+
+    /// Fallible version of [Conv2D::select_inputs()]
+    pub fn try_select_inputs<const N: usize>(
+        &self,
+        keep: &[bool],
+    ) -> Result<Conv2D<N, O, K, S, P, D>, D::Err> {
+        check_keep(keep, I, N);
+        let mut weight = self.weight.device.try_zeros()?;
+        weight.copy_from(&select_axis(&host(&self.weight), keep, K * K));
+        Ok(Conv2D {
+            weight,
+            bias: self.bias.clone(),
+        })
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> Pruned<Linear<I, O, D>, D> {
+    /// Removes the pruned output channels, resulting in a layer with `N` outputs.
+    /// **Panics** if `N` is not the number of kept channels.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let mut model: (Pruned<Linear<4, 8>>, ReLU, Linear<8, 2>) = dev.build_module();
+    /// model.0.prune_channels(0.5);
+    /// let keep = model.0.kept_channels().unwrap();
+    /// let small: (Linear<4, 4>, ReLU, Linear<4, 2>) =
+    ///     (model.0.sparsify(), ReLU, model.2.select_inputs(keep));
+    /// ```
+    pub fn sparsify<const N: usize>(&self) -> Linear<I, N, D> {
+        self.try_sparsify().unwrap()
+    }
+
+    /// Fallible version of [Pruned::sparsify()]
+    pub fn try_sparsify<const N: usize>(&self) -> Result<Linear<I, N, D>, D::Err> {
+        let keep = self
+            .channels
+            .clone()
+            .unwrap_or_else(|| alloc::vec![true; O]);
+        self.module.try_select_outputs(&keep)
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    Pruned<Conv2D<I, O, K, S, P, D>, D>
+where
+    D: Device<f32>,
+{
+    /// Removes the pruned output channels, resulting in a layer with `N` output channels.
+    /// **Panics** if `N` is not the number of kept channels.
+    pub fn sparsify<const N: usize>(&self) -> Conv2D<I, N, K, S, P, D> {
+        self.try_sparsify().unwrap()
+    }
+
+    /// Fallible version of [Pruned::sparsify()]
+    pub fn try_sparsify<const N: usize>(&self) -> Result<Conv2D<I, N, K, S, P, D>, D::Err> {
+        let keep = self
+            .channels
+            .clone()
+            .unwrap_or_else(|| alloc::vec![true; O]);
+        self.module.try_select_outputs(&keep)
+    }
+}
+
+impl<D: Device<f32>, M: GradientUpdate<D, f32>> GradientUpdate<D, f32> for Pruned<M, D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        let mut masked = ApplyMasks {
+            updater,
+            masks: &mut self.masks,
+            i: 0,
+        };
+        self.module.update(&mut masked, unused)
+    }
+}
+
+impl<D: Device<f32>, M: ResetParams<D, f32> + GradientUpdate<D, f32> + Clone> ResetParams<D, f32>
+    for Pruned<M, D>
+{
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self::new(ResetParams::try_build(device)?))
+    }
+
+    /// Resets the parameters of `M` and applies the masks again, so the pruned structure is
+    /// kept (e.g. to retrain it from scratch).
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.module.try_reset_params()?;
+        self.try_apply_masks()
+    }
+}
+
+impl<T, D: Device<f32>, M: Module<T> + GradientUpdate<D, f32> + Clone> Module<T> for Pruned<M, D> {
+    type Output = M::Output;
+    /// Runs a copy of the module with the masks multiplied into its parameters.
+    fn forward(&self, input: T) -> Self::Output {
+        self.try_masked().unwrap().forward(input)
+    }
+}
+
+impl<T, D: Device<f32>, M: ModuleMut<T> + GradientUpdate<D, f32> + Clone> ModuleMut<T>
+    for Pruned<M, D>
+{
+    type Output = M::Output;
+    /// Multiplies the masks into the parameters, and runs the module.
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.try_apply_masks().unwrap();
+        self.module.forward_mut(input)
+    }
+}
+
+impl<D: Device<f32>, M: IntoInference<D, f32> + GradientUpdate<D, f32> + Clone>
+    IntoInference<D, f32> for Pruned<M, D>
+{
+    /// The masks are multiplied into the parameters, so they aren't needed anymore.
+    type Inference = M::Inference;
+    fn try_into_inference(mut self) -> Result<Self::Inference, D::Err> {
+        self.try_apply_masks()?;
+        self.module.try_into_inference()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{ModuleBuilder, ReLU},
+        optim::{Optimizer, Sgd},
+        tests::{assert_close, TestDevice},
+    };
+
+    #[test]
+    fn test_prune_magnitude() {
+        let dev: TestDevice = Default::default();
+        let mut model: Pruned<Linear<4, 4, _>, _> = dev.build_module();
+        let w = model.module.weight.as_vec();
+        let b = model.module.bias.array();
+        model.prune_magnitude(0.5);
+        assert_eq!(model.sparsity(), 0.5);
+        assert_eq!(model.module.bias.array(), b);
+
+        // the 8 elements with the smallest magnitude are pruned
+        let mut sorted: std::vec::Vec<f32> = w.iter().map(|x| x.abs()).collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let pruned = model.module.weight.as_vec();
+        for (old, new) in w.iter().zip(pruned.iter()) {
+            if old.abs() < sorted[8] {
+                assert_eq!(*new, 0.0);
+            } else {
+                assert_eq!(new, old);
+            }
+        }
+
+        // pruned elements stay zero after updates
+        let mut sgd: Sgd<_> = Default::default();
+        for _ in 0..3 {
+            let x: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+            let g = model.forward_mut(x.trace()).square().mean().backward();
+            sgd.update(&mut model, g).expect("");
+        }
+        let updated = model.module.weight.as_vec();
+        for (p, u) in pruned.iter().zip(updated.iter()) {
+            assert_eq!(*p == 0.0, *u == 0.0);
+        }
+
+        // masks only grow
+        model.prune_magnitude(0.25);
+        assert_eq!(model.sparsity(), 0.5);
+        model.reset_params();
+        assert_eq!(
+            model
+                .module
+                .weight
+                .as_vec()
+                .iter()
+                .filter(|x| **x == 0.0)
+                .count(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_forward_applies_masks() {
+        let dev: TestDevice = Default::default();
+        let mut model: Pruned<Linear<2, 2, _>, _> = dev.build_module();
+        model.module.weight = dev.tensor([[1.0, -3.0], [0.5, 2.0]]);
+        model.module.bias = dev.zeros();
+        model.prune_magnitude(0.5);
+        assert_eq!(model.module.weight.array(), [[0.0, -3.0], [0.0, 2.0]]);
+
+        // pruned elements that are changed directly are masked again in forward
+        model.module.weight = model.module.weight.clone() + 1.0;
+        let x = dev.tensor([1.0, 1.0]);
+        assert_eq!(model.forward(x.clone()).array(), [-2.0, 3.0]);
+        assert_eq!(model.module.weight.array(), [[1.0, -2.0], [1.0, 3.0]]);
+        assert_eq!(model.forward_mut(x).array(), [-2.0, 3.0]);
+        assert_eq!(model.module.weight.array(), [[0.0, -2.0], [0.0, 3.0]]);
+    }
+
+    #[test]
+    fn test_prune_channels_and_sparsify() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Pruned<Linear<3, 4, _>, _>, ReLU, Linear<4, 2, _>) = dev.build_module();
+        model.0.module.weight = dev.tensor([
+            [1.0, 1.0, 1.0],
+            [0.1, -0.1, 0.1],
+            [-2.0, 0.5, 0.0],
+            [0.0, 0.2, 0.0],
+        ]);
+        model.0.prune_channels(0.5);
+        assert_eq!(
+            model.0.kept_channels(),
+            Some([true, false, true, false].as_slice())
+        );
+        assert_eq!(model.0.module.bias.array()[1], 0.0);
+        assert_eq!(model.0.module.weight.array()[3], [0.0; 3]);
+
+        let keep = model.0.kept_channels().unwrap();
+        let small: (Linear<3, 2, _>, ReLU, Linear<2, 2, _>) =
+            (model.0.sparsify(), ReLU, model.2.select_inputs(keep));
+        assert_eq!(small.0.weight.array(), [[1.0, 1.0, 1.0], [-2.0, 0.5, 0.0]]);
+
+        let x: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+        assert_close(&small.forward(x.clone()).array(), &model.forward(x).array());
+    }
+
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    #[test]
+    fn test_conv_sparsify() {
+        let dev: TestDevice = Default::default();
+        let mut model: (
+            Pruned<Conv2D<2, 4, 3, 1, 1, _>, _>,
+            ReLU,
+            Conv2D<4, 3, 3, 1, 1, _>,
+        ) = dev.build_module();
+        model.0.prune_channels(0.25);
+        let keep = model.0.kept_channels().unwrap();
+        assert_eq!(keep.iter().filter(|k| !**k).count(), 1);
+        let small: (Conv2D<2, 3, 3, 1, 1, _>, ReLU, Conv2D<3, 3, 3, 1, 1, _>) =
+            (model.0.sparsify(), ReLU, model.2.select_inputs(keep));
+        let x: Tensor<Rank3<2, 5, 5>, f32, _> = dev.sample_normal();
+        let (a, b) = (small.forward(x.clone()).as_vec(), model.forward(x).as_vec());
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_pruning_schedule() {
+        let schedule = PruningSchedule {
+            initial_sparsity: 0.0,
+            final_sparsity: 0.8,
+            begin_step: 10,
+            end_step: 20,
+            frequency: 2,
+        };
+        assert_eq!(schedule.sparsity(0), 0.0);
+        assert_eq!(schedule.sparsity(15), 0.8 - 0.8 * 0.125);
+        assert_eq!(schedule.sparsity(30), 0.8);
+        assert!(!schedule.should_prune(8));
+        assert!(schedule.should_prune(10));
+        assert!(!schedule.should_prune(11));
+        assert!(schedule.should_prune(20));
+        assert!(!schedule.should_prune(22));
+    }
+}
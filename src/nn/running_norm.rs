@@ -0,0 +1,243 @@
+use crate::{gradients::*, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{Module, ModuleMut, ResetParams};
+
+/// Normalizes each of `M` input features with a running mean and variance that are
+/// accumulated over everything the layer has seen, like the observation normalization
+/// commonly used in reinforcement learning or for tabular data:
+/// `(x - running_mean) / sqrt(running_var + epsilon)`.
+///
+/// Unlike [super::BatchNorm2D], the statistics are exact averages over all seen samples
+/// instead of an exponential moving average, and there is no affine transform.
+///
+/// # Updating vs Frozen
+///
+/// - [ModuleMut::forward_mut()] first adds the input to the statistics (with the parallel
+///   form of [Welford's algorithm](https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Parallel_algorithm)),
+///   and then normalizes it with them. This works with or without a tape, so observations
+///   can be added while collecting them.
+/// - [Module::forward()] normalizes with the statistics as they are.
+///
+/// The statistics are never trained, and gradients only flow to the input.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut norm: RunningNorm1D<2> = dev.build_module();
+/// let x = dev.tensor([[1.0, 10.0], [3.0, 30.0]]);
+/// let _ = norm.forward_mut(x);
+/// assert_eq!(norm.count, 2);
+/// assert_eq!(norm.running_mean.array(), [2.0, 20.0]);
+/// assert_eq!(norm.running_var.array(), [1.0, 100.0]);
+///
+/// // frozen
+/// let _ = norm.forward(dev.tensor([2.0, 40.0]));
+/// assert_eq!(norm.count, 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RunningNorm1D<const M: usize, D: Device<f32> = Cpu> {
+    /// Mean of every seen sample. Defaults to 0.0
+    pub running_mean: Tensor<Rank1<M>, f32, D>,
+    /// Population variance of every seen sample. Defaults to 1.0
+    pub running_var: Tensor<Rank1<M>, f32, D>,
+    /// The number of seen samples. Defaults to 0
+    pub count: u64,
+    /// Added to variance before taking sqrt for numerical stability. Defaults to 1e-5
+    pub epsilon: f32,
+}
+
+impl<const M: usize, D: Device<f32>> RunningNorm1D<M, D> {
+    /// Normalizes `x` with the current statistics
+    fn normalize<S: Shape, T: Tape<D>, Ax: Axes>(
+        &self,
+        x: Tensor<S, f32, D, T>,
+    ) -> Tensor<S, f32, D, T>
+    where
+        Rank1<M>: BroadcastShapeTo<S, Ax>,
+    {
+        let shape = *x.shape();
+        let std = (self.running_var.clone() + self.epsilon).sqrt();
+        let x = x - self.running_mean.clone().broadcast_like(&shape);
+        x / std.broadcast_like(&shape)
+    }
+
+    /// Merges the statistics of a batch into the running statistics - off tape
+    fn update_stats<S, T: Tape<D>, Ax: Axes>(&mut self, x: &Tensor<S, f32, D, T>)
+    where
+        S: Shape + HasAxes<Ax> + ReduceShapeTo<Rank1<M>, Ax>,
+    {
+        let n = <S as HasAxes<Ax>>::size(x.shape());
+        if n == 0 {
+            return;
+        }
+        let x = x.retaped::<NoneTape>();
+        let mean = x.clone().mean::<Rank1<M>, Ax>();
+        let var = x.var::<Rank1<M>, Ax>();
+
+        // the weights are computed in f64, so they stay accurate for large counts
+        let (na, nb) = (self.count as f64, n as f64);
+        let total = na + nb;
+        let (wa, wb, wd) = (na / total, nb / total, (na / total) * (nb / total));
+        let delta = mean - self.running_mean.clone();
+        self.running_mean = self.running_mean.clone() + delta.clone() * wb as f32;
+        self.running_var =
+            self.running_var.clone() * wa as f32 + var * wb as f32 + delta.square() * wd as f32;
+        self.count += n as u64;
+    }
+}
+
+impl<const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<Rank1<M>, f32, D, T>>
+    for RunningNorm1D<M, D>
+{
+    type Output = Tensor<Rank1<M>, f32, D, T>;
+    fn forward(&self, x: Tensor<Rank1<M>, f32, D, T>) -> Self::Output {
+        self.normalize(x.broadcast::<Rank2<1, M>, _>()).sum()
+    }
+}
+
+impl<B: Dim, const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<(B, Const<M>), f32, D, T>>
+    for RunningNorm1D<M, D>
+{
+    type Output = Tensor<(B, Const<M>), f32, D, T>;
+    fn forward(&self, x: Tensor<(B, Const<M>), f32, D, T>) -> Self::Output {
+        self.normalize(x)
+    }
+}
+
+impl<B: Dim, S: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(B, S, Const<M>), f32, D, T>> for RunningNorm1D<M, D>
+{
+    type Output = Tensor<(B, S, Const<M>), f32, D, T>;
+    fn forward(&self, x: Tensor<(B, S, Const<M>), f32, D, T>) -> Self::Output {
+        self.normalize(x)
+    }
+}
+
+impl<const M: usize, D: Device<f32>, T: Tape<D>> ModuleMut<Tensor<Rank1<M>, f32, D, T>>
+    for RunningNorm1D<M, D>
+{
+    type Output = Tensor<Rank1<M>, f32, D, T>;
+
+    /// Adds the sample to the statistics, and normalizes it with them
+    fn forward_mut(&mut self, x: Tensor<Rank1<M>, f32, D, T>) -> Self::Output {
+        let x = x.broadcast::<Rank2<1, M>, _>();
+        self.update_stats::<_, _, Axis<0>>(&x);
+        self.normalize(x).sum()
+    }
+}
+
+impl<B: Dim, const M: usize, D: Device<f32>, T: Tape<D>> ModuleMut<Tensor<(B, Const<M>), f32, D, T>>
+    for RunningNorm1D<M, D>
+{
+    type Output = Tensor<(B, Const<M>), f32, D, T>;
+
+    /// Adds the batch to the statistics, and normalizes it with them
+    fn forward_mut(&mut self, x: Tensor<(B, Const<M>), f32, D, T>) -> Self::Output {
+        self.update_stats::<_, _, Axis<0>>(&x);
+        self.normalize(x)
+    }
+}
+
+impl<B: Dim, S: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
+    ModuleMut<Tensor<(B, S, Const<M>), f32, D, T>> for RunningNorm1D<M, D>
+{
+    type Output = Tensor<(B, S, Const<M>), f32, D, T>;
+
+    /// Adds the batch to the statistics, and normalizes it with them
+    fn forward_mut(&mut self, x: Tensor<(B, S, Const<M>), f32, D, T>) -> Self::Output {
+        self.update_stats::<_, _, Axes2<0, 1>>(&x);
+        self.normalize(x)
+    }
+}
+
+impl<const M: usize, D: Device<f32>> ResetParams<D, f32> for RunningNorm1D<M, D> {
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self {
+            running_mean: device.try_zeros()?,
+            running_var: device.try_ones()?,
+            count: 0,
+            epsilon: 1e-5,
+        })
+    }
+
+    /// Forgets all seen samples
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.running_mean.try_fill_with_zeros()?;
+        self.running_var.try_fill_with_ones()?;
+        self.count = 0;
+        Ok(())
+    }
+}
+
+impl<const M: usize, D: Device<f32>> GradientUpdate<D, f32> for RunningNorm1D<M, D> {
+    /// There are no parameters, so this only visits the statistics with
+    /// [ParamUpdater::update_accumulated_stats()]
+    fn update<U>(&mut self, updater: &mut U, _: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        updater.update_accumulated_stats(
+            &mut self.running_mean,
+            &mut self.running_var,
+            &mut self.count,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::ModuleBuilder,
+        tests::{assert_close, TestDevice},
+    };
+
+    #[test]
+    fn test_running_norm_matches_full_statistics() {
+        let dev: TestDevice = Default::default();
+        let mut norm: RunningNorm1D<3, _> = dev.build_module();
+        let x1: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let x2: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal() * 2.0 + 1.0;
+        let x3: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let _ = norm.forward_mut(x1.clone());
+        let _ = norm.forward_mut(x2.trace());
+        let _ = norm.forward_mut(x3.clone());
+        assert_eq!(norm.count, 15);
+
+        let mut all = std::vec::Vec::new();
+        all.extend(x1.as_vec());
+        all.extend(x2.as_vec());
+        all.extend(x3.as_vec());
+        let all: Tensor<(usize, Const<3>), f32, _> = dev.tensor_from_vec(all, (15, Const));
+        assert_close(
+            &norm.running_mean.array(),
+            &all.clone().mean::<_, Axis<0>>().array(),
+        );
+        assert_close(&norm.running_var.array(), &all.var::<_, Axis<0>>().array());
+    }
+
+    #[test]
+    fn test_running_norm_frozen_forward() {
+        let dev: TestDevice = Default::default();
+        let mut norm: RunningNorm1D<2, _> = dev.build_module();
+        let _ = norm.forward_mut(dev.tensor([[1.0, -2.0], [3.0, 2.0], [5.0, 0.0]]));
+        // mean [3, 0], var [8 / 3, 8 / 3]
+        let x = dev.tensor([[3.0, 1.0], [7.0, -4.0]]);
+        let y = norm.forward(x.trace());
+        assert_eq!(norm.count, 3);
+        let s = (8.0f32 / 3.0 + 1e-5).sqrt();
+        assert_close(&y.array(), &[[0.0, 1.0 / s], [4.0 / s, -4.0 / s]]);
+
+        let g = y.sum().backward();
+        assert_close(&g.get(&x).array(), &[[1.0 / s; 2]; 2]);
+
+        norm.reset_params();
+        assert_eq!(norm.count, 0);
+        let s = (1.0f32 + 1e-5).sqrt();
+        assert_close(
+            &norm.forward(x).array(),
+            &[[3.0 / s, 1.0 / s], [7.0 / s, -4.0 / s]],
+        );
+    }
+}
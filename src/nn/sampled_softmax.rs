@@ -0,0 +1,297 @@
+use crate::{gradients::Gradients, shapes::*, tensor::*, tensor_ops::*};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use rand::Rng;
+use rand_distr::Distribution;
+
+use super::Linear;
+
+/// The rows of a [Linear] output layer that a sampled loss needs: the rows of the target
+/// classes, and the rows of negative classes that are sampled on the device. Create it with
+/// [Linear::sample_classes()], and pass it to [crate::losses::sampled_softmax_loss()] or
+/// [crate::losses::nce_loss()] together with the input of the layer.
+///
+/// The rows are copies, so the backward pass only computes gradients for them, instead of
+/// for the whole `(VOCAB, DIM)` weight of the layer. Apply these gradients with
+/// [SampledClasses::sgd_update()].
+///
+/// Negative classes are drawn with replacement from a log-uniform (Zipfian) distribution,
+/// `P(c) = ln((c + 2) / (c + 1)) / ln(VOCAB + 1)`, so class ids should be sorted by decreasing
+/// frequency, as is usual for vocabularies. The losses correct for this distribution.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut output: Linear<8, 1000> = dev.build_module();
+/// let hidden: Tensor<Rank2<4, 8>> = dev.sample_normal();
+/// let targets = dev.tensor([3, 14, 159, 265]);
+///
+/// let classes = output.sample_classes(targets, 16);
+/// let loss = sampled_softmax_loss(hidden.trace(), &classes);
+/// let grads = loss.backward();
+/// classes.sgd_update(&mut output, &grads, 0.1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SampledClasses<B: Dim, const DIM: usize, D: Device<f32> = Cpu> {
+    /// The target class of each sample.
+    pub targets: Tensor<(B,), usize, D>,
+    /// The sampled negative classes, shared by all samples.
+    pub sampled: Tensor<(usize,), usize, D>,
+    pub(crate) target_weight: Tensor<(B, Const<DIM>), f32, D>,
+    pub(crate) target_bias: Tensor<(B,), f32, D>,
+    pub(crate) sampled_weight: Tensor<(usize, Const<DIM>), f32, D>,
+    pub(crate) sampled_bias: Tensor<(usize,), f32, D>,
+    /// The log of the expected number of times each class is sampled.
+    pub(crate) target_log_q: Tensor<(B,), f32, D>,
+    pub(crate) sampled_log_q: Tensor<(usize,), f32, D>,
+    /// `1` where a sampled class is the target class of a sample, with shape `(B, num_sampled)`.
+    pub(crate) hits: Tensor<(B, usize), f32, D>,
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> Linear<I, O, D> {
+    /// Samples `num_sampled` negative classes and gathers the rows of the layer for them and
+    /// for `targets`, see [SampledClasses].
+    pub fn sample_classes<B: Dim>(
+        &self,
+        targets: Tensor<(B,), usize, D>,
+        num_sampled: usize,
+    ) -> SampledClasses<B, I, D>
+    where
+        D: SampleTensor<usize> + TensorFromVec<usize> + TensorFromVec<f32>,
+    {
+        self.try_sample_classes(targets, num_sampled).unwrap()
+    }
+
+    /// Fallible version of [Linear::sample_classes()]
+    pub fn try_sample_classes<B: Dim>(
+        &self,
+        targets: Tensor<(B,), usize, D>,
+        num_sampled: usize,
+    ) -> Result<SampledClasses<B, I, D>, D::Err>
+    where
+        D: SampleTensor<usize> + TensorFromVec<usize> + TensorFromVec<f32>,
+    {
+        let dev = &self.weight.device;
+        let b = targets.shape().0;
+        let distr = LogUniform { range: O };
+        let sampled = dev.try_sample_like(&(num_sampled,), distr)?;
+
+        let target_ids = targets.as_vec();
+        let sampled_ids = sampled.as_vec();
+        let log_q = |ids: &[usize]| -> std::vec::Vec<f32> {
+            ids.iter()
+                .map(|&c| (num_sampled as f64 * distr.prob(c)).ln() as f32)
+                .collect()
+        };
+        let target_log_q = dev.try_tensor_from_vec(log_q(&target_ids), (b,))?;
+        let sampled_log_q = dev.try_tensor_from_vec(log_q(&sampled_ids), (num_sampled,))?;
+        let hits = target_ids
+            .iter()
+            .flat_map(|t| sampled_ids.iter().map(move |c| (t == c) as u8 as f32))
+            .collect();
+        let hits = dev.try_tensor_from_vec(hits, (b, num_sampled))?;
+
+        Ok(SampledClasses {
+            target_weight: self
+                .weight
+                .clone()
+                .try_index_select::<Axis<0>, _>(targets.clone())?,
+            target_bias: self
+                .bias
+                .clone()
+                .try_index_select::<Axis<0>, _>(targets.clone())?,
+            sampled_weight: self
+                .weight
+                .clone()
+                .try_index_select::<Axis<0>, _>(sampled.clone())?,
+            sampled_bias: self
+                .bias
+                .clone()
+                .try_index_select::<Axis<0>, _>(sampled.clone())?,
+            targets,
+            sampled,
+            target_log_q,
+            sampled_log_q,
+            hits,
+        })
+    }
+}
+
+/// The log-uniform distribution over the classes `0..range`, see [SampledClasses].
+#[derive(Debug, Clone, Copy)]
+struct LogUniform {
+    range: usize,
+}
+
+impl LogUniform {
+    /// `P(c) = ln((c + 2) / (c + 1)) / ln(range + 1)`
+    fn prob(&self, c: usize) -> f64 {
+        (1.0 / (c as f64 + 1.0)).ln_1p() / (self.range as f64 + 1.0).ln()
+    }
+}
+
+impl Distribution<usize> for LogUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        // inverse of the cumulative distribution `ln(c + 1) / ln(range + 1)`
+        let u: f64 = rng.gen();
+        let c = ((self.range as f64 + 1.0).ln() * u).exp().floor() as usize;
+        c.clamp(1, self.range) - 1
+    }
+}
+
+impl<B: Dim, const I: usize, D: Device<f32>> SampledClasses<B, I, D> {
+    /// The number of sampled negative classes.
+    pub fn num_sampled(&self) -> usize {
+        self.sampled.shape().0
+    }
+
+    /// Applies a step of stochastic gradient descent with learning rate `lr` to the rows of
+    /// `layer` that were used, using the gradients of the sampled rows in `grads`. Only these
+    /// rows are changed, so other optimizers, which keep state for the whole layer, can't be
+    /// used for it.
+    ///
+    /// **Panics** if `grads` doesn't contain gradients for the rows, i.e. they weren't used by
+    /// the loss.
+    pub fn sgd_update<const O: usize>(
+        &self,
+        layer: &mut Linear<I, O, D>,
+        grads: &Gradients<D>,
+        lr: f32,
+    ) {
+        self.try_sgd_update(layer, grads, lr).unwrap()
+    }
+
+    /// Fallible version of [SampledClasses::sgd_update()]
+    pub fn try_sgd_update<const O: usize>(
+        &self,
+        layer: &mut Linear<I, O, D>,
+        grads: &Gradients<D>,
+        lr: f32,
+    ) -> Result<(), D::Err> {
+        scatter_step(
+            &mut layer.weight,
+            &self.targets,
+            &self.target_weight,
+            grads,
+            lr,
+        )?;
+        scatter_step(&mut layer.bias, &self.targets, &self.target_bias, grads, lr)?;
+        scatter_step(
+            &mut layer.weight,
+            &self.sampled,
+            &self.sampled_weight,
+            grads,
+            lr,
+        )?;
+        scatter_step(
+            &mut layer.bias,
+            &self.sampled,
+            &self.sampled_bias,
+            grads,
+            lr,
+        )
+    }
+}
+
+/// Adds `-lr` times the gradient of `rows` to the rows `idx` of `param`. Repeated indices
+/// add up, the same as their gradients would.
+fn scatter_step<S: Shape, Z: Dim, R: Shape<Concrete = S::Concrete>, D: Device<f32>>(
+    param: &mut Tensor<S, f32, D>,
+    idx: &Tensor<(Z,), usize, D>,
+    rows: &Tensor<R, f32, D>,
+    grads: &Gradients<D>,
+    lr: f32,
+) -> Result<(), D::Err> {
+    let step = rows.device.upgrade(grads.get(rows).clone()).try_mul(-lr)?;
+    let dev = param.device.clone();
+    IndexSelectKernel::backward(&dev, &mut param.storage, &idx.storage, &step.storage, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{losses::*, nn::ModuleBuilder, tests::*};
+
+    #[test]
+    fn test_sample_classes() {
+        let dev: TestDevice = Default::default();
+        let layer: Linear<3, 50, _> = dev.build_module();
+        let classes = layer.sample_classes(dev.tensor([0, 7]), 1000);
+        assert_eq!(classes.num_sampled(), 1000);
+
+        let sampled = classes.sampled.as_vec();
+        assert!(sampled.iter().all(|&c| c < 50));
+        // P(0) = ln(2) / ln(51) ~= 0.176, P(c >= 25) = 1 - ln(26) / ln(51) ~= 0.171
+        let zeros = sampled.iter().filter(|&&c| c == 0).count();
+        let high = sampled.iter().filter(|&&c| c >= 25).count();
+        assert!((120..230).contains(&zeros), "{zeros}");
+        assert!((120..230).contains(&high), "{high}");
+
+        let w = layer.weight.array();
+        for (row, &c) in classes
+            .sampled_weight
+            .as_vec()
+            .chunks(3)
+            .zip(sampled.iter())
+        {
+            assert_eq!(row, w[c]);
+        }
+        assert_eq!(
+            classes.target_bias.as_vec(),
+            [layer.bias.array()[0], layer.bias.array()[7]]
+        );
+
+        let log_q = classes.target_log_q.as_vec();
+        let expected = |c: f32| (1000.0 * ((c + 2.0) / (c + 1.0)).ln() / 51f32.ln()).ln();
+        assert_close(&log_q, &alloc::vec![expected(0.0), expected(7.0)]);
+
+        // a hit where the sampled class is the target
+        let hits = classes.hits.as_vec();
+        for (i, &c) in sampled.iter().enumerate() {
+            assert_eq!(hits[i], if c == 0 { 1.0 } else { 0.0 });
+            assert_eq!(hits[1000 + i], if c == 7 { 1.0 } else { 0.0 });
+        }
+    }
+
+    #[test]
+    fn test_log_uniform_large_vocab() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let distr = LogUniform {
+            range: (1 << 30) + 3,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let samples: std::vec::Vec<usize> = (0..10000).map(|_| distr.sample(&mut rng)).collect();
+        assert!(samples.iter().all(|&c| c < distr.range));
+        // ids above 2^24 are not rounded to multiples of 2 like they would be in f32
+        let high: std::vec::Vec<usize> = samples.into_iter().filter(|&c| c > 1 << 25).collect();
+        assert!(!high.is_empty());
+        assert!(high.iter().any(|c| c % 2 == 1));
+        let total: f64 = (0..1000).map(|c| distr.prob(c)).sum();
+        assert!((total - 1001f64.ln() / ((1u64 << 30) as f64 + 4.0).ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sgd_update_only_changes_sampled_rows() {
+        let dev: TestDevice = Default::default();
+        let mut layer: Linear<4, 100, _> = dev.build_module();
+        let before = layer.clone();
+        let hidden: Tensor<Rank2<2, 4>, f32, _> = dev.sample_normal();
+        let classes = layer.sample_classes(dev.tensor([1, 2]), 5);
+        let grads = nce_loss(hidden.trace(), &classes).backward();
+        classes.sgd_update(&mut layer, &grads, 1.0);
+
+        let mut used = classes.sampled.as_vec();
+        used.extend([1, 2]);
+        let (w0, w1) = (before.weight.array(), layer.weight.array());
+        let (b0, b1) = (before.bias.array(), layer.bias.array());
+        for c in 0..100 {
+            if !used.contains(&c) {
+                assert_eq!(w0[c], w1[c]);
+                assert_eq!(b0[c], b1[c]);
+            }
+        }
+        assert_ne!(w0[1], w1[1]);
+        assert_ne!(b0[2], b1[2]);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{optim::*, shapes::*, tensor_ops::*};
+
+use super::{Module, ModuleMut, ResetParams};
+
+/// Multiplies the output of `M` by the constant [Self::scale]: `scale * M(x)`.
+///
+/// Useful for things like scaling residual branches at initialization. [Self::scale] is
+/// not a trainable parameter, and defaults to `1.0`.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut module: Residual<Scaled<ReLU>> = dev.build_module();
+/// module.0.scale = 0.5;
+/// let x = dev.tensor([-2.0, 0.0, 2.0]);
+/// assert_eq!(module.forward(x).array(), [-2.0, 0.0, 3.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scaled<M> {
+    pub module: M,
+    pub scale: f32,
+}
+
+impl<D: Device<E>, E: Dtype, M: GradientUpdate<D, E>> GradientUpdate<D, E> for Scaled<M> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        self.module.update(updater, unused)
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: ResetParams<D, E>> ResetParams<D, E> for Scaled<M> {
+    /// Builds [Self::module] and sets [Self::scale] to `1.0`.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self {
+            module: ResetParams::try_build(device)?,
+            scale: 1.0,
+        })
+    }
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.module.try_reset_params()
+    }
+}
+
+impl<T, M: Module<T>> Module<T> for Scaled<M>
+where
+    M::Output: std::ops::Mul<f32, Output = M::Output>,
+{
+    type Output = M::Output;
+    fn forward(&self, x: T) -> Self::Output {
+        self.module.forward(x) * self.scale
+    }
+}
+
+impl<T, M: ModuleMut<T>> ModuleMut<T> for Scaled<M>
+where
+    M::Output: std::ops::Mul<f32, Output = M::Output>,
+{
+    type Output = M::Output;
+    fn forward_mut(&mut self, x: T) -> Self::Output {
+        self.module.forward_mut(x) * self.scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, ModuleBuilder};
+    use crate::tensor::*;
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_scaled_gradients() {
+        let dev: TestDevice = Default::default();
+        let mut model: Scaled<Linear<2, 3, _>> = dev.build_module();
+        model.scale = -2.0;
+
+        let x = dev.sample_normal::<Rank2<4, 2>>();
+        let y = model.forward_mut(x.trace());
+        assert_close(&y.array(), &(model.module.forward(x) * -2.0).array());
+
+        let g = y.sum().backward();
+        assert_close(&g.get(&model.module.bias).array(), &[-8.0; 3]);
+    }
+}
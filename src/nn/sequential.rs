@@ -0,0 +1,453 @@
+use crate::{
+    gradients::{OwnedTape, Tape},
+    optim::*,
+    shapes::*,
+    tensor::*,
+    tensor_ops::Device,
+};
+
+use super::{Module, ModuleMut, ResetParams};
+
+use std::{any::Any, boxed::Box, vec::Vec};
+
+#[cfg(feature = "numpy")]
+use {
+    super::npz::{LoadFromNpz, SaveToNpz},
+    crate::tensor::numpy::NpzError,
+    std::io::Cursor,
+    zip::{result::ZipResult, ZipArchive, ZipWriter},
+};
+
+/// A parameter with a runtime shape, which is what [Sequential] can pass to optimizers.
+#[derive(Debug)]
+pub enum DynParam<'a, E: Dtype, D: DeviceStorage> {
+    Rank0(&'a mut Tensor<Rank0, E, D>),
+    Rank1(&'a mut Tensor<(usize,), E, D>),
+    Rank2(&'a mut Tensor<(usize, usize), E, D>),
+    Rank3(&'a mut Tensor<(usize, usize, usize), E, D>),
+    Rank4(&'a mut Tensor<(usize, usize, usize, usize), E, D>),
+}
+
+/// An object safe version of [ParamUpdater] for parameters with runtime shapes.
+/// Implemented for all [ParamUpdater]s.
+pub trait DynParamUpdater<D: DeviceStorage, E: Dtype> {
+    fn update_dyn_param(
+        &mut self,
+        p: DynParam<'_, E, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err>;
+}
+
+impl<D: DeviceStorage, E: Dtype, U: ParamUpdater<D, E>> DynParamUpdater<D, E> for U {
+    fn update_dyn_param(
+        &mut self,
+        p: DynParam<'_, E, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        match p {
+            DynParam::Rank0(p) => self.update_param(p, unused),
+            DynParam::Rank1(p) => self.update_param(p, unused),
+            DynParam::Rank2(p) => self.update_param(p, unused),
+            DynParam::Rank3(p) => self.update_param(p, unused),
+            DynParam::Rank4(p) => self.update_param(p, unused),
+        }
+    }
+}
+
+/// Whether all dimensions of `S` are `usize`, so a parameter of shape `S` can be a [DynParam].
+fn is_runtime_shape<S: Shape>() -> bool {
+    use core::any::TypeId;
+    [
+        TypeId::of::<Rank0>(),
+        TypeId::of::<(usize,)>(),
+        TypeId::of::<(usize, usize)>(),
+        TypeId::of::<(usize, usize, usize)>(),
+        TypeId::of::<(usize, usize, usize, usize)>(),
+    ]
+    .contains(&TypeId::of::<S>())
+}
+
+/// Passes parameters with runtime shapes on to a [DynParamUpdater].
+struct ErasedUpdater<'a, D: DeviceStorage, E: Dtype>(&'a mut dyn DynParamUpdater<D, E>);
+
+impl<'a, D: DeviceStorage, E: Dtype> ParamUpdater<D, E> for ErasedUpdater<'a, D, E> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, E, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let p: &mut dyn Any = p;
+        let p = if let Some(p) = p.downcast_mut() {
+            DynParam::Rank0(p)
+        } else if let Some(p) = p.downcast_mut() {
+            DynParam::Rank1(p)
+        } else if let Some(p) = p.downcast_mut() {
+            DynParam::Rank2(p)
+        } else if let Some(p) = p.downcast_mut() {
+            DynParam::Rank3(p)
+        } else if let Some(p) = p.downcast_mut() {
+            DynParam::Rank4(p)
+        } else {
+            // rejected by Sequential::push()
+            panic!(
+                "Sequential can only update parameters with runtime shapes (`usize` dimensions), found {}",
+                std::any::type_name::<S>()
+            );
+        };
+        self.0.update_dyn_param(p, unused)
+    }
+}
+
+/// Finds the first parameter with a compile time shape.
+struct ConstShapeFinder(Option<&'static str>);
+
+impl<D: DeviceStorage, E: Dtype> ParamUpdater<D, E> for ConstShapeFinder {
+    fn update_param<S: Shape>(
+        &mut self,
+        _: &mut Tensor<S, E, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        if !is_runtime_shape::<S>() {
+            self.0.get_or_insert(std::any::type_name::<S>());
+        }
+        Ok(())
+    }
+}
+
+/// Saving and loading for [SequentialLayer]s. Without the `numpy` feature this is
+/// implemented for everything.
+#[cfg(feature = "numpy")]
+pub trait MaybeNpz: SaveToNpz + LoadFromNpz {}
+#[cfg(feature = "numpy")]
+impl<T: SaveToNpz + LoadFromNpz> MaybeNpz for T {}
+
+/// Saving and loading for [SequentialLayer]s. Without the `numpy` feature this is
+/// implemented for everything.
+#[cfg(not(feature = "numpy"))]
+pub trait MaybeNpz {}
+#[cfg(not(feature = "numpy"))]
+impl<T> MaybeNpz for T {}
+
+/// An object safe module that maps tensors of shape `S` to tensors of the same shape, with
+/// or without an [OwnedTape], which can be stored in a [Sequential].
+///
+/// This is implemented for all modules that implement [Module] and [ModuleMut] for both tapes,
+/// [ResetParams], and [GradientUpdate] (and [SaveToNpz]/[LoadFromNpz] with the `numpy` feature).
+/// Layers must be [Send] and [Sync] so a [Sequential] can be shared between threads, see
+/// [super::SharedModel].
+pub trait SequentialLayer<S: Shape, D: Device<E>, E: Dtype>:
+    Send
+    + Sync
+    + Module<Tensor<S, E, D>, Output = Tensor<S, E, D>>
+    + ModuleMut<Tensor<S, E, D>, Output = Tensor<S, E, D>>
+    + Module<Tensor<S, E, D, OwnedTape<D>>, Output = Tensor<S, E, D, OwnedTape<D>>>
+    + ModuleMut<Tensor<S, E, D, OwnedTape<D>>, Output = Tensor<S, E, D, OwnedTape<D>>>
+{
+    /// Object safe version of [ResetParams::try_reset_params()].
+    fn try_reset_params_dyn(&mut self) -> Result<(), D::Err>;
+
+    /// Object safe version of [GradientUpdate::update()].
+    ///
+    /// **Panics** if the module has parameters with compile time shapes.
+    fn update_dyn(
+        &mut self,
+        updater: &mut dyn DynParamUpdater<D, E>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err>;
+
+    /// The shape of the first parameter that has a compile time shape, which can't be
+    /// passed to [SequentialLayer::update_dyn()].
+    fn find_const_shape_param(&mut self) -> Option<&'static str>;
+
+    /// Object safe version of [SaveToNpz::write()].
+    #[cfg(feature = "numpy")]
+    fn write_dyn(&self, p: &str, w: &mut ZipWriter<Cursor<Vec<u8>>>) -> ZipResult<()>;
+
+    /// Object safe version of [LoadFromNpz::read()].
+    #[cfg(feature = "numpy")]
+    fn read_dyn(&mut self, p: &str, r: &mut ZipArchive<Cursor<Vec<u8>>>) -> Result<(), NpzError>;
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype, M> SequentialLayer<S, D, E> for M
+where
+    M: Send
+        + Sync
+        + Module<Tensor<S, E, D>, Output = Tensor<S, E, D>>
+        + ModuleMut<Tensor<S, E, D>, Output = Tensor<S, E, D>>
+        + Module<Tensor<S, E, D, OwnedTape<D>>, Output = Tensor<S, E, D, OwnedTape<D>>>
+        + ModuleMut<Tensor<S, E, D, OwnedTape<D>>, Output = Tensor<S, E, D, OwnedTape<D>>>
+        + ResetParams<D, E>
+        + GradientUpdate<D, E>
+        + MaybeNpz,
+{
+    fn try_reset_params_dyn(&mut self) -> Result<(), D::Err> {
+        self.try_reset_params()
+    }
+
+    fn update_dyn(
+        &mut self,
+        updater: &mut dyn DynParamUpdater<D, E>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        self.update(&mut ErasedUpdater(updater), unused)
+    }
+
+    fn find_const_shape_param(&mut self) -> Option<&'static str> {
+        let mut finder = ConstShapeFinder(None);
+        let mut unused = Default::default();
+        self.update(&mut finder, &mut unused).ok()?;
+        finder.0
+    }
+
+    #[cfg(feature = "numpy")]
+    fn write_dyn(&self, p: &str, w: &mut ZipWriter<Cursor<Vec<u8>>>) -> ZipResult<()> {
+        self.write(p, w)
+    }
+
+    #[cfg(feature = "numpy")]
+    fn read_dyn(&mut self, p: &str, r: &mut ZipArchive<Cursor<Vec<u8>>>) -> Result<(), NpzError> {
+        self.read(p, r)
+    }
+}
+
+/// A sequence of boxed modules that is built at runtime, as opposed to tuples
+/// which must be known at compile time. Every layer maps tensors of shape `S` to tensors
+/// of shape `S`, so `S` usually has runtime dimensions like `(usize, usize)`.
+///
+/// [Sequential] can be called with and without an [OwnedTape], and trained like any other
+/// module. Optimizers can only be passed parameters with runtime shapes (see [DynParam]),
+/// so layers with compile time shapes, like [super::Linear], are rejected by
+/// [Sequential::push()]. Use runtime sized layers like [super::DynLinear] instead.
+///
+/// Saving and loading stores each layer under its index, the same as tuples.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let depth = 3; // e.g. from a config file
+/// let mut model: Sequential<(usize, usize)> = Default::default();
+/// for _ in 0..depth {
+///     model.push((DynLinear::new(&dev, 5, 5), ReLU));
+/// }
+/// model.push(Tanh);
+/// assert_eq!(model.len(), 4);
+/// let x: Tensor<(usize, usize)> = dev.zeros_like(&(2, 5));
+/// let _ = model.forward(x.clone());
+/// let _ = model.forward_mut(x.trace());
+/// ```
+pub struct Sequential<S: Shape, D: Device<E> = Cpu, E: Dtype = f32> {
+    pub layers: Vec<Box<dyn SequentialLayer<S, D, E>>>,
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype> Default for Sequential<S, D, E> {
+    fn default() -> Self {
+        Self {
+            layers: Default::default(),
+        }
+    }
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype> std::fmt::Debug for Sequential<S, D, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sequential")
+            .field("num_layers", &self.layers.len())
+            .finish()
+    }
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype> Sequential<S, D, E> {
+    /// Appends `layer` to the end of the sequence.
+    ///
+    /// **Panics** if `layer` has parameters with compile time shapes.
+    pub fn push<M: 'static + SequentialLayer<S, D, E>>(&mut self, mut layer: M) {
+        if let Some(shape) = layer.find_const_shape_param() {
+            panic!(
+                "Sequential can only contain parameters with runtime shapes (`usize` dimensions), found {shape}"
+            );
+        }
+        self.layers.push(Box::new(layer));
+    }
+
+    /// The number of layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if there are no layers, in which case [Module::forward()] returns its input.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype> ResetParams<D, E> for Sequential<S, D, E> {
+    /// Builds an empty [Sequential].
+    fn try_build(_: &D) -> Result<Self, D::Err> {
+        Ok(Default::default())
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        for layer in self.layers.iter_mut() {
+            layer.try_reset_params_dyn()?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype> GradientUpdate<D, E> for Sequential<S, D, E> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        for layer in self.layers.iter_mut() {
+            layer.update_dyn(updater, unused)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype, T: Tape<D>> Module<Tensor<S, E, D, T>>
+    for Sequential<S, D, E>
+where
+    dyn SequentialLayer<S, D, E>: Module<Tensor<S, E, D, T>, Output = Tensor<S, E, D, T>>,
+{
+    type Output = Tensor<S, E, D, T>;
+    fn forward(&self, mut x: Tensor<S, E, D, T>) -> Self::Output {
+        for layer in self.layers.iter() {
+            x = layer.forward(x);
+        }
+        x
+    }
+}
+
+impl<S: Shape, D: Device<E>, E: Dtype, T: Tape<D>> ModuleMut<Tensor<S, E, D, T>>
+    for Sequential<S, D, E>
+where
+    dyn SequentialLayer<S, D, E>: ModuleMut<Tensor<S, E, D, T>, Output = Tensor<S, E, D, T>>,
+{
+    type Output = Tensor<S, E, D, T>;
+    fn forward_mut(&mut self, mut x: Tensor<S, E, D, T>) -> Self::Output {
+        for layer in self.layers.iter_mut() {
+            x = layer.forward_mut(x);
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gradients::Tape,
+        nn::{tests::SimpleUpdater, DynLinear, Linear, ModuleBuilder, ReLU},
+        tensor_ops::*,
+        tests::TestDevice,
+    };
+
+    /// Multiplies by a parameter with a runtime shape.
+    #[derive(Debug, Clone)]
+    struct DynScale {
+        scale: Tensor<(usize,), f32, TestDevice>,
+    }
+
+    impl ResetParams<TestDevice, f32> for DynScale {
+        fn try_build(device: &TestDevice) -> Result<Self, <TestDevice as HasErr>::Err> {
+            Ok(Self {
+                scale: device.try_ones_like(&(3,))?,
+            })
+        }
+        fn try_reset_params(&mut self) -> Result<(), <TestDevice as HasErr>::Err> {
+            self.scale.try_fill_with_ones()
+        }
+    }
+
+    impl GradientUpdate<TestDevice, f32> for DynScale {
+        fn update<U>(
+            &mut self,
+            updater: &mut U,
+            unused: &mut UnusedTensors,
+        ) -> Result<(), <TestDevice as HasErr>::Err>
+        where
+            U: ParamUpdater<TestDevice, f32>,
+        {
+            self.scale.update(updater, unused)
+        }
+    }
+
+    impl<T: Tape<TestDevice>> Module<Tensor<(usize,), f32, TestDevice, T>> for DynScale {
+        type Output = Tensor<(usize,), f32, TestDevice, T>;
+        fn forward(&self, x: Tensor<(usize,), f32, TestDevice, T>) -> Self::Output {
+            x * self.scale.clone()
+        }
+    }
+
+    impl<T: Tape<TestDevice>> ModuleMut<Tensor<(usize,), f32, TestDevice, T>> for DynScale {
+        type Output = Tensor<(usize,), f32, TestDevice, T>;
+        fn forward_mut(&mut self, x: Tensor<(usize,), f32, TestDevice, T>) -> Self::Output {
+            self.forward(x)
+        }
+    }
+
+    #[cfg(feature = "numpy")]
+    impl SaveToNpz for DynScale {}
+    #[cfg(feature = "numpy")]
+    impl LoadFromNpz for DynScale {}
+
+    #[test]
+    fn test_sequential_matches_tuple() {
+        let dev: TestDevice = Default::default();
+        let a = (DynLinear::new(&dev, 3, 3), ReLU);
+        let b = DynLinear::new(&dev, 3, 3);
+
+        let mut model: Sequential<(usize, usize), _> = dev.build_module();
+        assert!(model.is_empty());
+        model.push(a.clone());
+        model.push(b.clone());
+        assert_eq!(model.len(), 2);
+
+        let x: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(2, 3), rand_distr::StandardNormal);
+        let expected = (a, b).forward(x.clone()).as_vec();
+        assert_eq!(model.forward(x.clone()).as_vec(), expected);
+        assert_eq!(model.forward_mut(x.clone()).as_vec(), expected);
+        assert_eq!(model.forward(x.trace()).as_vec(), expected);
+    }
+
+    #[test]
+    fn test_sequential_reset_params() {
+        let dev: TestDevice = Default::default();
+        let mut model: Sequential<(usize,), _> = Default::default();
+        model.push(DynScale::build(&dev));
+        let mut x = dev.zeros_like(&(3,));
+        x.copy_from(&[1.0, 2.0, 3.0]);
+        assert_eq!(model.forward(x.clone()).as_vec(), [1.0, 2.0, 3.0]);
+        model.reset_params();
+        assert_eq!(model.forward(x).as_vec(), [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_sequential_update() {
+        let dev: TestDevice = Default::default();
+        let mut model: Sequential<(usize,), _> = Default::default();
+        model.push(DynScale::build(&dev));
+        model.push(DynScale::build(&dev));
+
+        let mut x = dev.zeros_like(&(3,));
+        x.copy_from(&[1.0, 2.0, 3.0]);
+        let g = model.forward_mut(x.trace()).sum().backward();
+
+        let mut updater = SimpleUpdater(g);
+        let mut unused = Default::default();
+        model.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    #[should_panic = "Sequential can only contain parameters with runtime shapes"]
+    fn test_sequential_push_const_shapes_panics() {
+        let dev: TestDevice = Default::default();
+        let mut model: Sequential<(usize, Const<3>), _> = Default::default();
+        model.push(ReLU);
+        model.push(dev.build_module::<Linear<3, 3, _>>());
+    }
+}
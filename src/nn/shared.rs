@@ -0,0 +1,150 @@
+use std::{ops::Deref, sync::Arc};
+
+use super::Module;
+
+/// A model that can be cheaply cloned and shared between threads to run [Module::forward()]
+/// concurrently, e.g. to serve requests in parallel without copying the weights.
+///
+/// Cloning a [SharedModel] only increments a reference count, all clones refer to the same parameters.
+///
+/// # Guarantees
+///
+/// - [Module::forward()] only takes `&self`. None of the modules in [crate::nn] use interior
+///   mutability, so calling it concurrently on the same model is safe and gives the same results
+///   as calling it sequentially.
+/// - Forward passes don't use the device's random number generator: [Dropout], [DropoutOneIn] and
+///   [DropPath] are only random in [super::ModuleMut::forward_mut()], which [SharedModel] does not
+///   implement. Creating tensors with random values does lock the rng of [crate::tensor::Cpu], so
+///   it is still safe to do from multiple threads.
+/// - The parameters can't be changed while the model is shared. Use [SharedModel::try_unwrap()]
+///   to get the model back once all other clones have been dropped.
+///
+/// For the fastest forward, convert the model with [super::IntoInference::into_inference()] first,
+/// and only pass tensors without a tape.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<5, 10>, ReLU, Linear<10, 2>);
+/// let model = SharedModel::new(dev.build_module::<Model>().into_inference());
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         let model = model.clone();
+///         let dev = dev.clone();
+///         s.spawn(move || {
+///             let y = model.forward(dev.sample_normal::<Rank2<3, 5>>());
+///             assert_eq!(y.shape(), &(Const::<3>, Const::<2>));
+///         });
+///     }
+/// });
+/// ```
+#[derive(Debug)]
+pub struct SharedModel<M>(Arc<M>);
+
+impl<M: Send + Sync> SharedModel<M> {
+    /// Moves `model` into shared storage.
+    pub fn new(model: M) -> Self {
+        Self(Arc::new(model))
+    }
+
+    /// Returns the model if this is the only reference to it, otherwise returns `self` unchanged.
+    pub fn try_unwrap(self) -> Result<M, Self> {
+        Arc::try_unwrap(self.0).map_err(Self)
+    }
+
+    /// The number of [SharedModel]s referring to the same model.
+    pub fn num_refs(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+}
+
+impl<M> Clone for SharedModel<M> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<M> Deref for SharedModel<M> {
+    type Target = M;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, M: Module<T>> Module<T> for SharedModel<M> {
+    type Output = M::Output;
+    fn forward(&self, input: T) -> Self::Output {
+        self.0.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{tests::SimpleUpdater, *},
+        optim::GradientUpdate,
+        shapes::*,
+        tensor::*,
+        tensor_ops::*,
+        tests::{assert_close, TestDevice},
+    };
+    use std::vec::Vec;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_modules_are_send_sync() {
+        assert_send_sync::<Tensor<Rank2<3, 5>, f32, TestDevice>>();
+        assert_send_sync::<(Linear<5, 3, TestDevice>, BatchNorm2D<3, TestDevice>)>();
+        assert_send_sync::<(Dropout, DropPath<Residual<LayerNorm1D<3, TestDevice>>>)>();
+        assert_send_sync::<Repeated<FusedLinear<3, 3, TestDevice>, 2>>();
+        assert_send_sync::<ConfigModel<TestDevice>>();
+        assert_send_sync::<SharedModel<Linear<5, 3, TestDevice>>>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_shared_model_threads() {
+        let dev: TestDevice = Default::default();
+        type Model = (Linear<5, 8>, DropoutOneIn<2>, ReLU, Linear<8, 2>);
+        let model = SharedModel::new(dev.build_module::<Model>());
+        let xs: Vec<Tensor<Rank2<4, 5>, f32, _>> = (0..8).map(|_| dev.sample_normal()).collect();
+        let expected: Vec<_> = xs
+            .iter()
+            .map(|x| model.forward(x.clone()).array())
+            .collect();
+
+        let outputs: Vec<_> = std::thread::scope(|s| {
+            let handles: Vec<_> = xs
+                .iter()
+                .map(|x| {
+                    let model = model.clone();
+                    s.spawn(move || model.forward(x.clone()).array())
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        for (y, e) in outputs.iter().zip(expected.iter()) {
+            assert_close(y, e);
+        }
+        assert_eq!(model.num_refs(), 1);
+    }
+
+    #[test]
+    fn test_shared_model_try_unwrap() {
+        let dev: TestDevice = Default::default();
+        let model = SharedModel::new(dev.build_module::<Linear<2, 2>>());
+        let other = model.clone();
+        assert_eq!(model.num_refs(), 2);
+        let model = model.try_unwrap().unwrap_err();
+        drop(other);
+        let mut model = model.try_unwrap().unwrap();
+
+        let y = model.forward_mut(dev.sample_normal::<Rank1<2>>().trace());
+        let g = y.square().mean().backward();
+        let mut updater = SimpleUpdater(g);
+        model.update(&mut updater, &mut Default::default()).unwrap();
+    }
+}
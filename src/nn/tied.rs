@@ -0,0 +1,212 @@
+use crate::{optim::*, shapes::*, tensor::*, tensor_ops::*, unique_id::UniqueId};
+
+use super::{IntoInference, Module, ModuleMut, ResetParams};
+
+use std::{any::Any, boxed::Box, collections::BTreeMap};
+
+/// Ties parameters inside of `M` together, e.g. the input embedding and the output layer of a
+/// language model.
+///
+/// Parameters are tied by assigning a clone of one tensor to another, since cloning a tensor
+/// keeps its id. The forward passes then record gradients for
+/// the same id, which [crate::gradients::Gradients] adds up, so a tied parameter gets the sum of
+/// the gradients of all of its uses.
+///
+/// Without [Tied], an optimizer would visit each copy of a tied parameter separately: the first
+/// visit applies the update, and the other copies are left behind. [Tied] makes
+/// [GradientUpdate::update()] visit the first copy of each parameter (in the order `M` visits
+/// them) only, and then overwrites the other copies with it, so the update is applied exactly
+/// once and the copies stay the same. This also applies to optimizer wrappers like
+/// [crate::optim::Lookahead], and to [ResetParams::reset_params()].
+///
+/// Ties are set after building the model, and tied parameters must have the same shape.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<4, 4>, ReLU, Linear<4, 4>);
+/// let mut model: Tied<Model> = dev.build_module();
+/// model.0 .2.weight = model.0 .0.weight.clone();
+///
+/// let mut sgd: Sgd<_> = Default::default();
+/// let y = model.forward_mut(dev.sample_normal::<Rank1<4>>().trace());
+/// let g = y.square().mean().backward();
+/// sgd.update(&mut model, g).expect("");
+/// assert_eq!(model.0 .0.weight.array(), model.0 .2.weight.array());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Tied<M>(pub M);
+
+/// Passes the first copy of each parameter to `U`, and overwrites the other copies with it.
+struct Dedup<'a, U> {
+    updater: &'a mut U,
+    visited: BTreeMap<UniqueId, Box<dyn Any>>,
+}
+
+impl<'a, U: ParamUpdater<D, E>, D: Device<E>, E: Dtype> ParamUpdater<D, E> for Dedup<'a, U> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, E, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        match self.visited.get(&p.id) {
+            Some(first) => {
+                let first: &Tensor<S, E, D> = first
+                    .downcast_ref()
+                    .expect("Tied parameters must have the same shape");
+                *p = first.clone();
+            }
+            None => {
+                self.updater.update_param(p, unused)?;
+                self.visited.insert(p.id, Box::new(p.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn update_running_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, E, D>,
+        var: &mut Tensor<S, E, D>,
+        momentum: &mut E,
+    ) -> Result<(), D::Err> {
+        self.updater.update_running_stats(mean, var, momentum)
+    }
+
+    fn update_accumulated_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, E, D>,
+        var: &mut Tensor<S, E, D>,
+        count: &mut u64,
+    ) -> Result<(), D::Err> {
+        self.updater.update_accumulated_stats(mean, var, count)
+    }
+
+    fn update_buffer<S: Shape>(&mut self, buffer: &mut Tensor<S, E, D>) -> Result<(), D::Err> {
+        self.updater.update_buffer(buffer)
+    }
+}
+
+/// Leaves parameters as they are, so [Dedup] only copies them.
+struct CopyTied;
+
+impl<D: Device<E>, E: Dtype> ParamUpdater<D, E> for CopyTied {
+    fn update_param<S: Shape>(
+        &mut self,
+        _: &mut Tensor<S, E, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        Ok(())
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: GradientUpdate<D, E>> GradientUpdate<D, E> for Tied<M> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        let mut dedup = Dedup {
+            updater,
+            visited: Default::default(),
+        };
+        self.0.update(&mut dedup, unused)
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: ResetParams<D, E> + GradientUpdate<D, E>> ResetParams<D, E>
+    for Tied<M>
+{
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self(ResetParams::try_build(device)?))
+    }
+
+    /// Resets the parameters of `M`, and then sets every copy of a tied parameter to the first
+    /// one again.
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.0.try_reset_params()?;
+        self.update(&mut CopyTied, &mut Default::default())
+    }
+}
+
+impl<T, M: Module<T>> Module<T> for Tied<M> {
+    type Output = M::Output;
+    fn forward(&self, input: T) -> Self::Output {
+        self.0.forward(input)
+    }
+}
+
+impl<T, M: ModuleMut<T>> ModuleMut<T> for Tied<M> {
+    type Output = M::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.0.forward_mut(input)
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: IntoInference<D, E>> IntoInference<D, E> for Tied<M> {
+    /// The inference version of `M` isn't trained anymore, so it doesn't need the ties.
+    type Inference = M::Inference;
+    fn try_into_inference(self) -> Result<Self::Inference, D::Err> {
+        self.0.try_into_inference()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{tests::SimpleUpdater, *},
+        optim::{Adam, Optimizer},
+        tests::TestDevice,
+    };
+
+    #[test]
+    fn test_tied_gradients_accumulate() {
+        let dev: TestDevice = Default::default();
+        let mut model: Tied<(Linear<3, 3, _>, Linear<3, 3, _>)> = dev.build_module();
+        model.0 .1.weight = model.0 .0.weight.clone();
+
+        let x: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let y = model.forward_mut(x.trace());
+        let g = y.sum().backward();
+
+        // d/dW of sum(W (W x + b1) + b2) = 1 (Wx + b1)^T + W^T 1 x^T
+        let w = model.0 .0.weight.clone();
+        let h = model.0 .0.forward(x.clone());
+        let ones: Tensor<Rank1<3>, f32, _> = dev.ones();
+        let expected = ones.clone().matmul(h) + ones.matmul(w).matmul(x);
+        crate::tests::assert_close(&g.get(&model.0 .0.weight).array(), &expected.array());
+
+        // the parameter is only visited once, so it isn't reported as unused
+        let mut updater = SimpleUpdater(g);
+        let mut unused = Default::default();
+        model.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    fn test_tied_update_applied_once() {
+        let dev: TestDevice = Default::default();
+        let mut model: Tied<(Linear<3, 3, _>, ReLU, Linear<3, 3, _>)> = dev.build_module();
+        model.0 .2.weight = model.0 .0.weight.clone();
+        let mut untied = model.0.clone();
+        assert_eq!(untied.0.weight.id, untied.2.weight.id);
+
+        let mut opt: Adam<_> = Default::default();
+        for _ in 0..3 {
+            let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+            let g = model.forward_mut(x.trace()).square().mean().backward();
+            opt.update(&mut model, g).expect("");
+        }
+        assert_eq!(model.0 .0.weight.array(), model.0 .2.weight.array());
+
+        // without Tied, the second copy has no gradient left
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let g = untied.forward_mut(x.trace()).square().mean().backward();
+        let mut opt: Adam<_> = Default::default();
+        assert!(opt.update(&mut untied, g).is_err());
+
+        model.reset_params();
+        assert_eq!(model.0 .0.weight.array(), model.0 .2.weight.array());
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
 
 use super::mha::MultiHeadAttention;
 
-/// **Requires Nightly** A transformer decoder.
+/// **Requires Nightly or `stable-fallback`** A transformer decoder.
 ///
 /// Generics
 /// - `MODEL_DIM`: The size of query/key/value tensors. Given to [MultiHeadAttention].
@@ -74,7 +74,7 @@ where
     }
 }
 
-/// **Requires Nightly** A transformer decoder block. Different than the normal transformer block
+/// **Requires Nightly or `stable-fallback`** A transformer decoder block. Different than the normal transformer block
 /// as this self attention accepts an additional sequence from the encoder.
 ///
 /// Generics
@@ -175,7 +175,7 @@ where
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,7 +7,7 @@ use crate::{
 
 use super::mha::MultiHeadAttention;
 
-/// **Requires Nightly** A transformer encoder.
+/// **Requires Nightly or `stable-fallback`** A transformer encoder.
 ///
 /// Generics
 /// - `MODEL_DIM`: The size of query/key/value tensors. Given to [MultiHeadAttention].
@@ -24,7 +24,7 @@ pub type TransformerEncoder<
     D = Cpu,
 > = Repeated<TransformerEncoderBlock<MODEL_DIM, NUM_HEADS, FF_DIM, D>, NUM_LAYERS>;
 
-/// **Requires Nightly** A single transformer encoder block
+/// **Requires Nightly or `stable-fallback`** A single transformer encoder block
 ///
 /// Generics
 /// - `MODEL_DIM`: The size of query/key/value tensors. Given to [MultiHeadAttention].
@@ -120,7 +120,7 @@ where
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 #[cfg(test)]
 mod tests {
     use super::*;
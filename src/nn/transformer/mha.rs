@@ -1,9 +1,12 @@
 use crate::{nn::*, optim::*, tensor::*, tensor_ops::*};
 
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+use crate::{gradients::Tape, shapes::*};
+
 #[cfg(feature = "nightly")]
-use crate::{gradients::Tape, shapes::*, Assert, ConstTrue};
+use crate::{Assert, ConstTrue};
 
-/// **Requires Nightly** A multi-head attention layer.
+/// **Requires Nightly or `stable-fallback`** A multi-head attention layer.
 ///
 /// Generics:
 /// - `EMBED_DIM`: The size of query vectors.
@@ -186,6 +189,136 @@ where
     }
 }
 
+/// A `(H, N)` mask where row `h` is 1 for the columns belonging to head `h`, and 0 elsewhere.
+///
+/// Without `generic_const_exprs` the `{N / H}` per-head shapes can't be named, so the
+/// `stable-fallback` impls below keep every head at full width and zero out the other
+/// heads' columns instead.
+#[cfg(all(feature = "stable-fallback", not(feature = "nightly")))]
+fn head_mask<const H: usize, const N: usize, D: Device<f32>>(
+    dev: &D,
+) -> Tensor<Rank2<H, N>, f32, D> {
+    assert_eq!(N % H, 0, "{N} is not divisible by the number of heads {H}");
+    let mut data = alloc::vec![0.0; H * N];
+    for (i, x) in data.iter_mut().enumerate() {
+        if (i % N) / (N / H) == i / N {
+            *x = 1.0;
+        }
+    }
+    let mut mask = dev.zeros();
+    mask.copy_from(&data);
+    mask
+}
+
+#[cfg(all(feature = "stable-fallback", not(feature = "nightly")))]
+impl<
+        const M: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank2<S1, M>, f32, D, T>,
+        Tensor<Rank2<S2, M>, f32, D>,
+        Tensor<Rank2<S2, M>, f32, D>,
+    )> for MultiHeadAttention<M, H, K, V, D>
+{
+    type Output = Tensor<Rank2<S1, M>, f32, D, T>;
+
+    /// Encoder-Decoder style self attention where one set of tensors is used for values and keys, and another is used for queries
+    fn forward(
+        &self,
+        (q, k, v): (
+            Tensor<Rank2<S1, M>, f32, D, T>,
+            Tensor<Rank2<S2, M>, f32, D>,
+            Tensor<Rank2<S2, M>, f32, D>,
+        ),
+    ) -> Self::Output {
+        let k_mask = head_mask::<H, K, D>(&q.device);
+        let v_mask = head_mask::<H, V, D>(&q.device);
+
+        let v: Tensor<Rank2<S2, V>, _, _, _> = self.w_v.forward(v.retaped::<T>());
+        let v = v.broadcast::<Rank3<H, S2, V>, _>() * v_mask.broadcast();
+
+        let k: Tensor<Rank2<S2, K>, _, _, _> = self.w_k.forward(k.retaped::<T>());
+        let k = k.broadcast::<Rank3<H, S2, K>, _>() * k_mask.clone().broadcast();
+        let k = k.permute::<Rank3<H, K, S2>, _>();
+
+        let q: Tensor<Rank2<S1, K>, _, _, _> = self.w_q.forward(q);
+        let q = q.broadcast::<Rank3<H, S1, K>, _>() * k_mask.broadcast();
+
+        // Get weights
+        let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
+        let weights: Tensor<Rank3<H, S1, S2>, _, _, _> = q.matmul(k) * scalar;
+        let weights = weights.softmax::<Axis<2>>();
+
+        // Get new tokens, each head only fills its own columns
+        let tokens: Tensor<Rank3<H, S1, V>, _, _, _> = weights.matmul(v);
+        let tokens = tokens.sum::<Rank2<S1, V>, _>();
+
+        self.w_o.forward(tokens)
+    }
+}
+
+#[cfg(all(feature = "stable-fallback", not(feature = "nightly")))]
+impl<
+        const M: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const B: usize,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank3<B, S1, M>, f32, D, T>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+    )> for MultiHeadAttention<M, H, K, V, D>
+{
+    type Output = Tensor<Rank3<B, S1, M>, f32, D, T>;
+
+    /// Batched Encoder-Decoder style self attention where one set of tensors is used for values and keys, and another is used for queries
+    fn forward(
+        &self,
+        (q, k, v): (
+            Tensor<Rank3<B, S1, M>, f32, D, T>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+        ),
+    ) -> Self::Output {
+        let k_mask = head_mask::<H, K, D>(&q.device);
+        let v_mask = head_mask::<H, V, D>(&q.device);
+
+        let v: Tensor<Rank3<B, S2, V>, _, _, _> = self.w_v.forward(v.retaped::<T>());
+        let v = v.broadcast::<Rank4<B, H, S2, V>, _>() * v_mask.broadcast();
+
+        let k: Tensor<Rank3<B, S2, K>, _, _, _> = self.w_k.forward(k.retaped::<T>());
+        let k = k.broadcast::<Rank4<B, H, S2, K>, _>() * k_mask.clone().broadcast();
+        let k = k.permute::<Rank4<B, H, K, S2>, _>();
+
+        let q: Tensor<Rank3<B, S1, K>, _, _, _> = self.w_q.forward(q);
+        let q = q.broadcast::<Rank4<B, H, S1, K>, _>() * k_mask.broadcast();
+
+        // Get weights
+        let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
+        let weights: Tensor<Rank4<B, H, S1, S2>, _, _, _> = q.matmul(k) * scalar;
+        let weights = weights.softmax::<Axis<3>>();
+
+        // Get new tokens, each head only fills its own columns
+        let tokens: Tensor<Rank4<B, H, S1, V>, _, _, _> = weights.matmul(v);
+        let tokens = tokens.sum::<Rank3<B, S1, V>, _>();
+
+        self.w_o.forward(tokens)
+    }
+}
+
 impl<const M: usize, const H: usize, const K: usize, const V: usize, D, Src> Module<Src>
     for MultiHeadAttention<M, H, K, V, D>
 where
@@ -212,7 +345,7 @@ where
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -14,7 +14,7 @@ use crate::{
 
 use super::{Module, ModuleMut, ResetParams};
 
-/// **Requires Nightly** Transformer architecture as described in
+/// **Requires Nightly or `stable-fallback`** Transformer architecture as described in
 /// [Attention is all you need](https://arxiv.org/abs/1706.03762).
 ///
 /// This is comprised of a [TransformerEncoder] and a [TransformerDecoder].
@@ -128,7 +128,7 @@ where
     }
 }
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 #[cfg(test)]
 mod tests {
     use super::*;
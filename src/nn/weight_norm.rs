@@ -0,0 +1,553 @@
+use crate::{
+    gradients::{NoneTape, Tape},
+    optim::*,
+    shapes::*,
+    tensor::*,
+    tensor_ops::*,
+};
+
+use super::{linear::Bias1D, Linear, Module, ModuleMut, ResetParams};
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+use super::{conv::Bias2D, Conv2D};
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+use crate::gradients::Merge;
+
+/// A layer with a weight that can be reparameterized by [SpectralNorm] and [WeightNorm].
+/// The weight is viewed as a matrix of shape `(Rows, Cols)`, where each row corresponds to
+/// one output.
+pub trait HasWeightMatrix<D: Device<f32>> {
+    type Rows: ConstDim;
+    type Cols: Dim;
+    /// The weight with tape `T`, reshaped into a matrix.
+    fn try_weight_matrix<T: Tape<D>>(&self) -> Result<WeightOf<Self, D, T>, D::Err>;
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> HasWeightMatrix<D> for Linear<I, O, D> {
+    type Rows = Const<O>;
+    type Cols = Const<I>;
+    fn try_weight_matrix<T: Tape<D>>(&self) -> Result<Tensor<Rank2<O, I>, f32, D, T>, D::Err> {
+        Ok(self.weight.retaped())
+    }
+}
+
+/// The weight of a [Conv2D] with shape `(O, I, K, K)` is viewed as a matrix
+/// of shape `(O, I * K * K)`.
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    HasWeightMatrix<D> for Conv2D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    type Rows = Const<O>;
+    type Cols = usize;
+    fn try_weight_matrix<T: Tape<D>>(
+        &self,
+    ) -> Result<Tensor<(Const<O>, usize), f32, D, T>, D::Err> {
+        self.weight
+            .retaped::<T>()
+            .try_reshape_like(&(Const, I * K * K))
+    }
+}
+
+/// The weight matrix of `M` with tape `T`.
+pub type WeightOf<M, D, T> = Tensor<
+    (
+        <M as HasWeightMatrix<D>>::Rows,
+        <M as HasWeightMatrix<D>>::Cols,
+    ),
+    f32,
+    D,
+    T,
+>;
+
+/// Divides `t` by its l2 norm.
+fn try_l2_normalize<S: Dim, D: Device<f32>>(
+    t: Tensor<(S,), f32, D>,
+    epsilon: f32,
+) -> Result<Tensor<(S,), f32, D>, D::Err> {
+    let shape = *t.shape();
+    let norm = t
+        .clone()
+        .try_square()?
+        .try_sum()?
+        .try_sqrt()?
+        .try_add(epsilon)?;
+    t.try_div(norm.try_broadcast_like(&shape)?)
+}
+
+/// Forward of `conv` with the weight matrix `w` instead of [Conv2D::weight], keeping
+/// the tape of `w`.
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+fn conv2d_with_weight<
+    const I: usize,
+    const O: usize,
+    const K: usize,
+    const S: usize,
+    const P: usize,
+    D,
+    Img,
+>(
+    conv: &Conv2D<I, O, K, S, P, D>,
+    x: Img,
+    w: Tensor<(Const<O>, usize), f32, D, Img::Tape>,
+) -> Img::Output
+where
+    D: Device<f32>,
+    Img: SplitTape + TryConv2DTo<Tensor<Rank4<O, I, K, K>, f32, D>, S, P>,
+    Img::Tape: Tape<D>,
+    for<'a> Bias2D<'a, O, D>: Module<Img::Output, Output = Img::Output>,
+{
+    let (w, w_tape) = w.reshape_like(&Default::default()).split_tape();
+    let (x, x_tape) = x.split_tape();
+    let y = x.put_tape(x_tape.merge(w_tape)).conv2d_to(w);
+    Bias2D { beta: &conv.bias }.forward(y)
+}
+
+/// Spectral normalization as described in [Spectral Normalization for Generative Adversarial Networks](https://arxiv.org/abs/1802.05957).
+///
+/// Divides the weight of [Self::module] (a [Linear], or a [Conv2D] with **Nightly or
+/// `stable-fallback`**) by its largest singular value `sigma` before every forward, see [HasWeightMatrix].
+/// `sigma` is estimated with power iteration using the vectors [Self::u] and [Self::v]. These are
+/// only updated by [ModuleMut::forward_mut()] (once per call for each of [Self::n_power_iterations]),
+/// so [Module::forward()] can be used for evaluation without changing the module.
+///
+/// Gradients flow through `sigma` into the original weight, while [Self::u] and [Self::v]
+/// are treated as constants and are not updated by optimizers.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut model: SpectralNorm<Linear<5, 3>> = dev.build_module();
+/// let _: Tensor<Rank1<3>, f32, Cpu, OwnedTape<Cpu>> = model.forward_mut(dev.zeros::<Rank1<5>>().traced());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpectralNorm<M: HasWeightMatrix<D>, D: Device<f32> = Cpu> {
+    pub module: M,
+    /// Estimate of the left singular vector
+    pub u: Tensor<(M::Rows,), f32, D>,
+    /// Estimate of the right singular vector
+    pub v: Tensor<(M::Cols,), f32, D>,
+    /// Defaults to `1`.
+    pub n_power_iterations: usize,
+    /// Added to norms to avoid division by zero. Defaults to `1e-12`.
+    pub epsilon: f32,
+}
+
+impl<M: HasWeightMatrix<D>, D: Device<f32>> SpectralNorm<M, D> {
+    /// Runs `n` steps of power iteration, updating [Self::u] and [Self::v].
+    pub fn try_power_iteration(&mut self, n: usize) -> Result<(), D::Err> {
+        let w = self.module.try_weight_matrix::<NoneTape>()?;
+        let shape = *w.shape();
+        for _ in 0..n {
+            // v = normalize(W^T u)
+            let v = w
+                .clone()
+                .try_mul(self.u.clone().try_broadcast_like::<_, Axis<1>>(&shape)?)?
+                .try_sum::<_, Axis<0>>()?;
+            self.v = try_l2_normalize(v, self.epsilon)?;
+            // u = normalize(W v)
+            let u = w
+                .clone()
+                .try_mul(self.v.clone().try_broadcast_like::<_, Axis<0>>(&shape)?)?
+                .try_sum::<_, Axis<1>>()?;
+            self.u = try_l2_normalize(u, self.epsilon)?;
+        }
+        Ok(())
+    }
+
+    /// The estimate of the largest singular value of the weight, `u^T W v`, with the tape of `w`.
+    pub(super) fn try_sigma<T: Tape<D>>(
+        &self,
+        w: WeightOf<M, D, T>,
+    ) -> Result<Tensor<Rank0, f32, D, T>, D::Err> {
+        let shape = *w.shape();
+        let uv = self
+            .u
+            .clone()
+            .try_broadcast_like::<_, Axis<1>>(&shape)?
+            .try_mul(self.v.clone().try_broadcast_like::<_, Axis<0>>(&shape)?)?;
+        w.try_mul(uv)?.try_sum()
+    }
+
+    /// The weight matrix divided by its spectral norm, with tape `T`.
+    pub(super) fn try_normalized_weight<T: Tape<D>>(&self) -> Result<WeightOf<M, D, T>, D::Err> {
+        let w = self.module.try_weight_matrix::<T>()?;
+        let sigma = self.try_sigma(w.with_empty_tape())?;
+        let shape = *w.shape();
+        w.try_div(sigma.try_broadcast_like(&shape)?)
+    }
+}
+
+impl<M, D: Device<f32>> GradientUpdate<D, f32> for SpectralNorm<M, D>
+where
+    M: HasWeightMatrix<D> + GradientUpdate<D, f32>,
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.module.update(updater, unused)
+    }
+}
+
+impl<M, D: Device<f32>> ResetParams<D, f32> for SpectralNorm<M, D>
+where
+    M: HasWeightMatrix<D> + ResetParams<D, f32>,
+{
+    /// Builds [Self::module], and initializes [Self::u] and [Self::v] with
+    /// 15 power iterations starting from random vectors.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        let epsilon = 1e-12;
+        let module = M::try_build(device)?;
+        let (rows, cols) = *module.try_weight_matrix::<NoneTape>()?.shape();
+        let u = device.try_sample_like(&(rows,), rand_distr::StandardNormal)?;
+        let v = device.try_sample_like(&(cols,), rand_distr::StandardNormal)?;
+        let mut m = Self {
+            module,
+            u: try_l2_normalize(u, epsilon)?,
+            v: try_l2_normalize(v, epsilon)?,
+            n_power_iterations: 1,
+            epsilon,
+        };
+        m.try_power_iteration(15)?;
+        Ok(m)
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.module.try_reset_params()?;
+        self.u.try_fill_with_distr(rand_distr::StandardNormal)?;
+        self.u = try_l2_normalize(self.u.clone(), self.epsilon)?;
+        self.try_power_iteration(15)
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>, T> Module<T>
+    for SpectralNorm<Linear<I, O, D>, D>
+where
+    T: SplitTape + TryMatMul<Tensor<Rank2<I, O>, f32, D, T::Tape>>,
+    T::Tape: Tape<D>,
+    for<'a> Bias1D<'a, O, D>: Module<T::Output, Output = T::Output>,
+{
+    type Output = T::Output;
+
+    /// Forward of [Linear] with the weight divided by its spectral norm.
+    fn forward(&self, x: T) -> Self::Output {
+        let w = self.try_normalized_weight::<T::Tape>().unwrap();
+        let o = x.matmul(w.permute());
+        Bias1D {
+            beta: &self.module.bias,
+        }
+        .forward(o)
+    }
+}
+
+impl<T, const I: usize, const O: usize, D: Device<f32>> ModuleMut<T>
+    for SpectralNorm<Linear<I, O, D>, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+
+    /// Updates [SpectralNorm::u] and [SpectralNorm::v] with power iteration, and then calls [Module::forward()].
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.try_power_iteration(self.n_power_iterations).unwrap();
+        self.forward(input)
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Img>
+    Module<Img> for SpectralNorm<Conv2D<I, O, K, S, P, D>, D>
+where
+    D: Device<f32>,
+    Img: SplitTape + TryConv2DTo<Tensor<Rank4<O, I, K, K>, f32, D>, S, P>,
+    Img::Tape: Tape<D>,
+    for<'a> Bias2D<'a, O, D>: Module<Img::Output, Output = Img::Output>,
+{
+    type Output = Img::Output;
+
+    /// Forward of [Conv2D] with the weight divided by its spectral norm.
+    fn forward(&self, x: Img) -> Self::Output {
+        let w = self.try_normalized_weight::<Img::Tape>().unwrap();
+        conv2d_with_weight(&self.module, x, w)
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Img>
+    ModuleMut<Img> for SpectralNorm<Conv2D<I, O, K, S, P, D>, D>
+where
+    D: Device<f32>,
+    Self: Module<Img>,
+{
+    type Output = <Self as Module<Img>>::Output;
+
+    /// Updates [SpectralNorm::u] and [SpectralNorm::v] with power iteration, and then calls [Module::forward()].
+    fn forward_mut(&mut self, input: Img) -> Self::Output {
+        self.try_power_iteration(self.n_power_iterations).unwrap();
+        self.forward(input)
+    }
+}
+
+/// Weight normalization as described in [Weight Normalization](https://arxiv.org/abs/1602.07868).
+///
+/// Reparameterizes each row of the weight matrix (see [HasWeightMatrix]) of [Self::module] as `g * v / ||v||`, where
+/// `v` is the original weight (the direction) and [Self::g] is a learnable magnitude per output.
+/// Both `v` and [Self::g] are updated by optimizers.
+///
+/// [Self::g] is initialized to the norms of the rows of the weight, so the effective weight
+/// starts out equal to the weight of the wrapped layer.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model: WeightNorm<Linear<5, 3>> = dev.build_module();
+/// let _: Tensor<Rank2<10, 3>> = model.forward(dev.zeros::<Rank2<10, 5>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightNorm<M: HasWeightMatrix<D>, D: Device<f32> = Cpu> {
+    pub module: M,
+    /// Magnitude of each row of the weight.
+    pub g: Tensor<(M::Rows,), f32, D>,
+}
+
+impl<M: HasWeightMatrix<D>, D: Device<f32>> WeightNorm<M, D> {
+    /// Computes `g * v / ||v||` with the tape of `v`.
+    pub(super) fn try_weight<T: Tape<D>>(
+        &self,
+        v: WeightOf<M, D, T>,
+    ) -> Result<WeightOf<M, D, T>, D::Err> {
+        let shape = *v.shape();
+        let norm = v
+            .with_empty_tape()
+            .try_square()?
+            .try_sum::<_, Axis<1>>()?
+            .try_sqrt()?;
+        let scale = norm.try_powi(-1)?.try_mul(self.g.clone())?;
+        v.try_mul(scale.try_broadcast_like::<_, Axis<1>>(&shape)?)
+    }
+}
+
+impl<M, D: Device<f32>> GradientUpdate<D, f32> for WeightNorm<M, D>
+where
+    M: HasWeightMatrix<D> + GradientUpdate<D, f32>,
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.module.update(updater, unused)?;
+        self.g.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<M, D: Device<f32>> ResetParams<D, f32> for WeightNorm<M, D>
+where
+    M: HasWeightMatrix<D> + ResetParams<D, f32>,
+{
+    /// Builds [Self::module] and sets [Self::g] to the norm of each row of its weight.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        let module = M::try_build(device)?;
+        let g = module
+            .try_weight_matrix::<NoneTape>()?
+            .try_square()?
+            .try_sum::<_, Axis<1>>()?
+            .try_sqrt()?;
+        Ok(Self { module, g })
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.module.try_reset_params()?;
+        self.g = self
+            .module
+            .try_weight_matrix::<NoneTape>()?
+            .try_square()?
+            .try_sum::<_, Axis<1>>()?
+            .try_sqrt()?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>, T> Module<T> for WeightNorm<Linear<I, O, D>, D>
+where
+    T: SplitTape + TryMatMul<Tensor<Rank2<I, O>, f32, D, T::Tape>>,
+    T::Tape: Tape<D>,
+    for<'a> Bias1D<'a, O, D>: Module<T::Output, Output = T::Output>,
+{
+    type Output = T::Output;
+
+    /// Forward of [Linear] with the reparameterized weight.
+    fn forward(&self, x: T) -> Self::Output {
+        let v = self.module.try_weight_matrix::<T::Tape>().unwrap();
+        let w = self.try_weight(v).unwrap();
+        let o = x.matmul(w.permute());
+        Bias1D {
+            beta: &self.module.bias,
+        }
+        .forward(o)
+    }
+}
+
+impl<T, const I: usize, const O: usize, D: Device<f32>> ModuleMut<T>
+    for WeightNorm<Linear<I, O, D>, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    fn forward_mut(&mut self, input: T) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Img>
+    Module<Img> for WeightNorm<Conv2D<I, O, K, S, P, D>, D>
+where
+    D: Device<f32>,
+    Img: SplitTape + TryConv2DTo<Tensor<Rank4<O, I, K, K>, f32, D>, S, P>,
+    Img::Tape: Tape<D>,
+    for<'a> Bias2D<'a, O, D>: Module<Img::Output, Output = Img::Output>,
+{
+    type Output = Img::Output;
+
+    /// Forward of [Conv2D] with the reparameterized weight.
+    fn forward(&self, x: Img) -> Self::Output {
+        let v = self.module.try_weight_matrix::<Img::Tape>().unwrap();
+        let w = self.try_weight(v).unwrap();
+        conv2d_with_weight(&self.module, x, w)
+    }
+}
+
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Img>
+    ModuleMut<Img> for WeightNorm<Conv2D<I, O, K, S, P, D>, D>
+where
+    D: Device<f32>,
+    Self: Module<Img>,
+{
+    type Output = <Self as Module<Img>>::Output;
+    fn forward_mut(&mut self, input: Img) -> Self::Output {
+        self.forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{tests::SimpleUpdater, ModuleBuilder};
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_spectral_norm_sigma() {
+        let dev: TestDevice = Default::default();
+        let mut m: SpectralNorm<Linear<2, 2, _>, _> = dev.build_module();
+        m.module.weight = dev.tensor([[3.0, 0.0], [0.0, -1.0]]);
+        m.try_power_iteration(20).unwrap();
+        let sigma = m.try_sigma(m.module.weight.clone()).unwrap();
+        assert_close(&sigma.array(), &3.0);
+
+        m.module.bias = dev.zeros();
+        let y = m.forward(dev.tensor([1.0, 1.0]));
+        assert_close(&y.array(), &[1.0, -1.0 / 3.0]);
+    }
+
+    #[test]
+    fn test_spectral_norm_forward_mut_updates_state() {
+        let dev: TestDevice = Default::default();
+        let mut m: SpectralNorm<Linear<5, 3, _>, _> = dev.build_module();
+        m.module.weight = dev.sample_normal();
+        let x = dev.sample_normal::<Rank2<4, 5>>();
+
+        let u = m.u.clone();
+        let _ = m.forward(x.clone());
+        assert_eq!(m.u.array(), u.array());
+
+        let y = m.forward_mut(x.trace());
+        assert_ne!(m.u.array(), u.array());
+
+        let g = y.square().mean().backward();
+        let mut updater = SimpleUpdater(g);
+        let mut unused = Default::default();
+        m.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    fn test_weight_norm_starts_equal() {
+        let dev: TestDevice = Default::default();
+        let m: WeightNorm<Linear<5, 3, _>, _> = dev.build_module();
+        let x = dev.sample_normal::<Rank2<4, 5>>();
+        assert_close(&m.forward(x.clone()).array(), &m.module.forward(x).array());
+    }
+
+    #[test]
+    fn test_weight_norm_grads() {
+        let dev: TestDevice = Default::default();
+        let mut m: WeightNorm<Linear<2, 2, _>, _> = dev.build_module();
+        m.module.weight = dev.tensor([[3.0, 4.0], [0.0, 2.0]]);
+        m.module.bias = dev.zeros();
+        m.g = dev.tensor([1.0, 2.0]);
+
+        let y = m.forward_mut(dev.tensor([1.0, 1.0]).trace());
+        assert_close(&y.array(), &[1.4, 2.0]);
+
+        let g = y.sum().backward();
+        assert_close(&g.get(&m.g).array(), &[1.4, 1.0]);
+        // d/dv of g * (v . x) / ||v||
+        assert_close(
+            &g.get(&m.module.weight).array(),
+            &[[0.032, -0.024], [1.0, 0.0]],
+        );
+    }
+
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    #[test]
+    fn test_spectral_norm_conv() {
+        let dev: TestDevice = Default::default();
+        let mut m: SpectralNorm<Conv2D<1, 2, 1, 1, 0, _>, _> = dev.build_module();
+        m.module.weight = dev.tensor([[[[3.0]]], [[[4.0]]]]);
+        m.module.bias = dev.zeros();
+        m.try_power_iteration(20).unwrap();
+        let sigma = m
+            .try_sigma(m.module.try_weight_matrix::<NoneTape>().unwrap())
+            .unwrap();
+        assert_close(&sigma.array(), &5.0);
+
+        let y = m.forward(dev.ones::<Rank3<1, 2, 2>>());
+        assert_close(
+            &y.as_vec(),
+            &std::vec![0.6, 0.6, 0.6, 0.6, 0.8, 0.8, 0.8, 0.8],
+        );
+    }
+
+    #[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+    #[test]
+    fn test_conv_grads_and_inference() {
+        use crate::nn::IntoInference;
+        let dev: TestDevice = Default::default();
+        let mut sn: SpectralNorm<Conv2D<2, 3, 3, 1, 1, _>, _> = dev.build_module();
+        let mut wn: WeightNorm<Conv2D<2, 3, 3, 1, 1, _>, _> = dev.build_module();
+        wn.g = dev.sample_normal();
+        let x = dev.sample_normal::<Rank4<2, 2, 4, 4>>();
+
+        let y = sn.forward_mut(x.trace());
+        let expected = sn.clone().into_inference().forward(x.clone());
+        assert_close(&y.as_vec(), &expected.as_vec());
+        let mut updater = SimpleUpdater(y.square().mean().backward());
+        let mut unused = Default::default();
+        sn.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+
+        let y = wn.forward_mut(x.trace());
+        let expected = wn.clone().into_inference().forward(x);
+        assert_close(&y.as_vec(), &expected.as_vec());
+        let mut updater = SimpleUpdater(y.square().mean().backward());
+        let mut unused = Default::default();
+        wn.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+}
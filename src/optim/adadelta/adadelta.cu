@@ -0,0 +1,50 @@
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct AdadeltaConfig {
+    float lr;
+    float rho;
+    float eps;
+    WeightDecayType weight_decay_type;
+    float weight_decay;
+};
+
+extern "C" __global__ void adadelta_update(
+    const AdadeltaConfig cfg,
+    const size_t numel,
+    float* param,
+    float* square_avg,
+    float* acc_delta,
+    const float* grad
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+
+    if (i >= numel) {
+        return;
+    }
+
+    float p = param[i];
+    float g = grad[i];
+    float s_avg = square_avg[i];
+    float a_delta = acc_delta[i];
+
+    if (cfg.weight_decay_type == L2) {
+        g += cfg.weight_decay * p;
+    }
+
+    s_avg += (1.0 - cfg.rho) * (g * g - s_avg);
+    g *= sqrtf(a_delta + cfg.eps) / sqrtf(s_avg + cfg.eps);
+    a_delta += (1.0 - cfg.rho) * (g * g - a_delta);
+    g *= cfg.lr;
+
+    if (cfg.weight_decay_type == Decoupled) {
+        g += cfg.weight_decay * cfg.lr * p;
+    }
+
+    square_avg[i] = s_avg;
+    acc_delta[i] = a_delta;
+    param[i] -= g;
+}
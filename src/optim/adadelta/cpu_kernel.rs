@@ -0,0 +1,47 @@
+use super::{AdadeltaConfig, AdadeltaKernel};
+use crate::{
+    optim::WeightDecay,
+    shapes::Shape,
+    tensor::cpu::{Cpu, StridedArray},
+};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl AdadeltaKernel<f32> for Cpu {
+    fn update<S: Shape>(
+        &self,
+        cfg: &AdadeltaConfig<f32>,
+        param: &mut StridedArray<S, f32>,
+        square_avg: &mut StridedArray<S, f32>,
+        acc_delta: &mut StridedArray<S, f32>,
+        grad: StridedArray<S, f32>,
+    ) -> Result<(), Self::Err> {
+        debug_assert_eq!(param.data.len(), grad.data.len());
+        debug_assert_eq!(param.shape, grad.shape);
+        debug_assert_eq!(param.strides, grad.strides);
+
+        for ((p, mut g), (s_avg, a_delta)) in param
+            .buf_iter_mut()
+            .zip(grad.buf_iter().cloned())
+            .zip(square_avg.buf_iter_mut().zip(acc_delta.buf_iter_mut()))
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += wd * *p;
+            }
+
+            // sa = rho * sa + (1 - rho) * g^2
+            *s_avg += (1.0 - cfg.rho) * (g * g - *s_avg);
+            g *= (*a_delta + cfg.eps).sqrt() / (*s_avg + cfg.eps).sqrt();
+            // ad = rho * ad + (1 - rho) * delta^2
+            *a_delta += (1.0 - cfg.rho) * (g * g - *a_delta);
+            g *= cfg.lr;
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += wd * cfg.lr * *p;
+            }
+
+            *p -= g;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use super::AdadeltaConfig;
+use crate::optim::optimizer::*;
+use crate::{shapes::Shape, tensor::Cuda};
+use cudarc::driver::{AsKernelParam, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+#[repr(C)]
+struct CudaAdadeltaConfig<E> {
+    lr: E,
+    rho: E,
+    eps: E,
+    weight_decay_type: WeightDecayType,
+    weight_decay: E,
+}
+
+unsafe impl<E> AsKernelParam for CudaAdadeltaConfig<E> {}
+
+fn adadelta_config_to_cuda<E: Default + Copy>(config: &AdadeltaConfig<E>) -> CudaAdadeltaConfig<E> {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaAdadeltaConfig {
+        lr: config.lr,
+        rho: config.rho,
+        eps: config.eps,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const MODULE_NAME: &str = "adadelta";
+const FN_NAME: &str = "adadelta_update";
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/adadelta.ptx"));
+
+impl super::AdadeltaKernel<f32> for Cuda {
+    fn update<S: Shape>(
+        &self,
+        cfg: &AdadeltaConfig<f32>,
+        param: &mut Self::Storage<S, f32>,
+        square_avg: &mut Self::Storage<S, f32>,
+        acc_delta: &mut Self::Storage<S, f32>,
+        grad: Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        debug_assert_eq!(param.data.len(), grad.data.len());
+        debug_assert_eq!(param.shape, grad.shape);
+        debug_assert_eq!(param.strides, grad.strides);
+
+        if !self.dev.has_func(MODULE_NAME, FN_NAME) {
+            self.dev.load_ptx(PTX_SRC.into(), MODULE_NAME, &[FN_NAME])?;
+        }
+
+        let adadelta_cfg = adadelta_config_to_cuda(cfg);
+        let numel = param.shape.num_elements();
+
+        let func = self.dev.get_func(MODULE_NAME, FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            adadelta_cfg,                        // const AdadeltaConfig cfg,
+            numel,                               // const size_t numel,
+            Arc::make_mut(&mut param.data),      // float* param,
+            Arc::make_mut(&mut square_avg.data), // float* square_avg,
+            Arc::make_mut(&mut acc_delta.data),  // float* acc_delta,
+            grad.data.as_ref(),                  // const float* grad
+        );
+        unsafe { func.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
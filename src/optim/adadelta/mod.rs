@@ -0,0 +1,245 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use std::marker::PhantomData;
+
+use crate::{
+    gradients::Gradients,
+    shapes::{Dtype, Shape},
+    tensor::{Cpu, DeviceStorage, Tensor},
+};
+
+use super::{
+    GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors, WeightDecay,
+};
+
+/// Configuration of hyperparameters for [Adadelta].
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// AdadeltaConfig {
+///     lr: 0.5,
+///     rho: 0.95,
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::L2(1e-1)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AdadeltaConfig<E> {
+    /// Scales the final update. Defaults to `1.0`.
+    pub lr: E,
+
+    /// Value for the exponential moving averages of squared gradients and squared
+    /// updates. Defaults to `0.9`.
+    pub rho: E,
+
+    /// Epsilon for numerical stability. Defaults to `1e-6`.
+    pub eps: E,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay<E>>,
+}
+
+impl Default for AdadeltaConfig<f32> {
+    fn default() -> Self {
+        Self {
+            lr: 1.0,
+            rho: 0.9,
+            eps: 1e-6,
+            weight_decay: None,
+        }
+    }
+}
+
+/// An implementation of the Adadelta optimizer from
+/// [ADADELTA: An Adaptive Learning Rate Method](https://arxiv.org/abs/1212.5701).
+///
+/// Each update is the gradient scaled by the ratio of the RMS of previous updates to the RMS
+/// of gradients, so the step size adapts per parameter without a global learning rate. This
+/// matches pytorch's Adadelta, where [AdadeltaConfig::lr] scales the update.
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Tensor<Rank0>;
+/// let mut opt: Adadelta<Model> = Default::default();
+/// ```
+///
+/// Constructing using new:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Tensor<Rank0>;
+/// let mut opt: Adadelta<Model> = Adadelta::new(AdadeltaConfig {
+///     rho: 0.95,
+///     ..Default::default()
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Adadelta<M, D: DeviceStorage = Cpu, E: Dtype = f32> {
+    /// Hyperparameter configuration
+    pub cfg: AdadeltaConfig<E>,
+
+    square_avg: Gradients<D>,
+    acc_delta: Gradients<D>,
+    gradients: Gradients<D>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, D: DeviceStorage, E: Dtype> Default for Adadelta<M, D, E>
+where
+    AdadeltaConfig<E>: Default,
+{
+    /// See [AdadeltaConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M, D: DeviceStorage, E: Dtype> Adadelta<M, D, E> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: AdadeltaConfig<E>) -> Self {
+        Self {
+            cfg,
+            square_avg: Default::default(),
+            acc_delta: Default::default(),
+            gradients: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+pub(super) trait AdadeltaKernel<E: Dtype>: DeviceStorage {
+    fn update<S: Shape>(
+        &self,
+        cfg: &AdadeltaConfig<E>,
+        param: &mut Self::Storage<S, E>,
+        square_avg: &mut Self::Storage<S, E>,
+        acc_delta: &mut Self::Storage<S, E>,
+        grad: Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+impl<M, D: AdadeltaKernel<f32>> ParamUpdater<D, f32> for Adadelta<M, D, f32> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let g = self.gradients.remove(p);
+        match g {
+            None => unused.add(p),
+            Some(g) => {
+                let sa = self.square_avg.get_or_alloc_mut(p)?;
+                let ad = self.acc_delta.get_or_alloc_mut(p)?;
+                p.device.update(&self.cfg, &mut p.storage, sa, ad, g)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<E: Dtype, D: DeviceStorage, M: GradientUpdate<D, E>> Optimizer<M, D, E> for Adadelta<M, D, E>
+where
+    Self: ParamUpdater<D, E>,
+{
+    fn update(
+        &mut self,
+        module: &mut M,
+        gradients: Gradients<D>,
+    ) -> Result<(), OptimizerUpdateError<D>> {
+        self.gradients = gradients;
+        let mut unused = Default::default();
+        match module.update(self, &mut unused) {
+            Ok(_) => unused.into(),
+            Err(e) => Err(OptimizerUpdateError::DeviceError(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_close, TestDevice};
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    fn test_matches_expected(cfg: AdadeltaConfig<f32>, expected: [[f32; 5]; 5]) {
+        let dev: TestDevice = Default::default();
+        let rate = dev.tensor([0.1, 1.0, 2.0, 10.0, 100.0]);
+        let mut t: Tensor<Rank1<5>, f32, _> = dev.ones();
+        let mut opt = Adadelta::new(cfg);
+        for e in expected.iter() {
+            let gradients = ((t.trace() * rate.clone()) - 1.0).square().sum().backward();
+            opt.update(&mut t, gradients).expect("");
+            assert_close(&t.array(), e);
+        }
+    }
+
+    #[test]
+    fn test_adadelta_default() {
+        const EXPECTED: [[f32; 5]; 5] = [
+            [1.0031618, 1.0, 0.99683774, 0.99683774, 0.99683774],
+            [1.0064051, 1.0, 0.99360305, 0.9935987, 0.9935982],
+            [1.0097041, 1.0, 0.9903256, 0.99031085, 0.9903092],
+            [1.013046, 1.0, 0.9870203, 0.986988, 0.9869844],
+            [1.0164232, 1.0, 0.98369586, 0.9836384, 0.9836319],
+        ];
+        test_matches_expected(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_diff_rho() {
+        let cfg = AdadeltaConfig {
+            lr: 0.5,
+            rho: 0.5,
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 5] = [
+            [1.000707, 1.0, 0.9992929, 0.9992929, 0.9992929],
+            [1.0015235, 1.0, 0.9984768, 0.9984766, 0.9984766],
+            [1.0024232, 1.0, 0.9975781, 0.9975774, 0.9975774],
+            [1.003394, 1.0, 0.9966089, 0.9966074, 0.99660736],
+            [1.0044291, 1.0, 0.9955763, 0.99557364, 0.99557346],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_l2_weight_decay() {
+        let cfg = AdadeltaConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.99683785, 0.9968378, 0.99683774, 0.99683774, 0.99683774],
+            [0.9936015, 0.993618, 0.9936025, 0.9935987, 0.9935982],
+            [0.99031985, 0.9903763, 0.99032384, 0.99031085, 0.9903092],
+            [0.98700726, 0.98713064, 0.9870163, 0.986988, 0.9869844],
+            [0.9836724, 0.9838919, 0.9836887, 0.9836384, 0.9836319],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_decoupled_weight_decay() {
+        let cfg = AdadeltaConfig {
+            weight_decay: Some(WeightDecay::Decoupled(0.05)),
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.9531618, 0.95, 0.9468377, 0.9468377, 0.9468377],
+            [0.90875554, 0.9056607, 0.89642936, 0.89634657, 0.8963375],
+            [0.8666363, 0.8643717, 0.84873104, 0.84845126, 0.8484211],
+            [0.82667774, 0.8259079, 0.8036526, 0.8030486, 0.8029843],
+            [0.7887647, 0.7900762, 0.7610867, 0.7600274, 0.75991565],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+}
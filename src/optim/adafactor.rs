@@ -0,0 +1,446 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{
+    gradients::Gradients,
+    shapes::*,
+    tensor::{Cpu, DeviceStorage, Tensor},
+    tensor_ops::*,
+    unique_id::UniqueId,
+};
+
+use super::{
+    GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors, WeightDecay,
+};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Configuration of hyperparameters for [Adafactor].
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// AdafactorConfig {
+///     lr: Some(1e-3),
+///     scale_parameter: false,
+///     warmup_init: false,
+///     eps: [1e-30, 1e-3],
+///     clip_threshold: 1.0,
+///     decay_rate: -0.8,
+///     beta1: Some(0.9),
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AdafactorConfig<E> {
+    /// Fixed learning rate. If `None`, the relative step size `min(1e-2, 1 / sqrt(t))` is used
+    /// instead. Defaults to `None`.
+    pub lr: Option<E>,
+
+    /// Whether the learning rate is multiplied by the root mean square of the parameter, so
+    /// that the step size is relative to the scale of the parameter. Defaults to `true`.
+    pub scale_parameter: bool,
+
+    /// Whether the relative step size starts at `1e-6` and grows linearly with the step
+    /// instead of starting at `1e-2`. Only used if [AdafactorConfig::lr] is `None`. Defaults
+    /// to `false`.
+    pub warmup_init: bool,
+
+    /// Epsilons for numerical stability: the first is added to the squared gradients, the
+    /// second is the smallest parameter scale. Defaults to `[1e-30, 1e-3]`.
+    pub eps: [E; 2],
+
+    /// Updates with a root mean square above this are scaled down to it. Defaults to `1.0`.
+    pub clip_threshold: E,
+
+    /// The decay of the second moment at step `t` is `1 - t^decay_rate`. Defaults to `-0.8`.
+    pub decay_rate: E,
+
+    /// Decay of the optional first moment. Defaults to `None`, which saves its memory.
+    pub beta1: Option<E>,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay<E>>,
+}
+
+impl Default for AdafactorConfig<f32> {
+    fn default() -> Self {
+        Self {
+            lr: None,
+            scale_parameter: true,
+            warmup_init: false,
+            eps: [1e-30, 1e-3],
+            clip_threshold: 1.0,
+            decay_rate: -0.8,
+            beta1: None,
+            weight_decay: None,
+        }
+    }
+}
+
+/// An implementation of the Adafactor optimizer from
+/// [Adafactor: Adaptive Learning Rates with Sublinear Memory Cost](https://arxiv.org/abs/1804.04235).
+///
+/// Like [super::Adam], each step is scaled by the inverse square root of a moving average of
+/// the squared gradients. For parameters with at least two dimensions, this average is
+/// factored over the last two dimensions: only the averages of each row and of each column
+/// are kept, so a `(rows, cols)` matrix needs `rows + cols` values instead of `rows * cols`.
+/// Together with not keeping a first moment by default, this uses much less memory than
+/// [super::Adam]. This matches the `Adafactor` of HuggingFace's transformers.
+///
+/// By default there is no learning rate to tune: the step size is relative to the scale of
+/// each parameter, see [AdafactorConfig::lr] and [AdafactorConfig::scale_parameter].
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Tensor<Rank0>;
+/// let mut opt: Adafactor<Model> = Default::default();
+/// ```
+///
+/// Constructing using new:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Tensor<Rank0>;
+/// let mut opt: Adafactor<Model> = Adafactor::new(AdafactorConfig {
+///     lr: Some(1e-3),
+///     scale_parameter: false,
+///     ..Default::default()
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Adafactor<M, D: DeviceStorage = Cpu, E: Dtype = f32> {
+    /// Hyperparameter configuration
+    pub cfg: AdafactorConfig<E>,
+
+    t: i32,
+    gradients: Gradients<D>,
+    state: BTreeMap<UniqueId, AdafactorState<D>>,
+
+    marker: PhantomData<*const M>,
+}
+
+/// A parameter viewed as `(batch, rows, cols)`
+type Batched<D> = Tensor<(usize, usize, usize), f32, D>;
+
+/// The moments of one parameter, which is viewed as a `(batch, rows, cols)` tensor.
+#[derive(Debug)]
+struct AdafactorState<D: DeviceStorage> {
+    second_moment: SecondMoment<D>,
+    first_moment: Option<Batched<D>>,
+}
+
+#[derive(Debug)]
+enum SecondMoment<D: DeviceStorage> {
+    /// The averages over the columns and over the rows
+    Factored {
+        rows: Tensor<(usize, usize), f32, D>,
+        cols: Tensor<(usize, usize), f32, D>,
+    },
+    Full(Batched<D>),
+}
+
+impl<M, D: DeviceStorage, E: Dtype> Default for Adafactor<M, D, E>
+where
+    AdafactorConfig<E>: Default,
+{
+    /// See [AdafactorConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M, D: DeviceStorage, E: Dtype> Adafactor<M, D, E> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: AdafactorConfig<E>) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            gradients: Default::default(),
+            state: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, D: Device<f32>> Adafactor<M, D, f32> {
+    /// The step size before it is scaled by the parameter
+    fn relative_step(&self) -> f32 {
+        let t = self.t as f32;
+        match self.cfg.lr {
+            Some(lr) => lr,
+            None => {
+                let min_step = if self.cfg.warmup_init { 1e-6 * t } else { 1e-2 };
+                min_step.min(1.0 / t.sqrt())
+            }
+        }
+    }
+}
+
+/// `sqrt(mean(t^2))` broadcast back to the shape of `t`
+fn rms<D: Device<f32>>(t: Batched<D>) -> Result<Batched<D>, D::Err> {
+    let shape = *t.shape();
+    t.try_square()?
+        .try_mean::<Rank0, _>()?
+        .try_sqrt()?
+        .try_broadcast_like(&shape)
+}
+
+impl<M, D: Device<f32>> ParamUpdater<D, f32> for Adafactor<M, D, f32> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let g = match self.gradients.remove(p) {
+            None => {
+                unused.add(p);
+                return Ok(());
+            }
+            Some(g) => g,
+        };
+        let numel = p.shape().num_elements();
+        if numel == 0 {
+            return Ok(());
+        }
+
+        let factored = S::NUM_DIMS >= 2;
+        let shape = if factored {
+            let dims = p.shape().concrete();
+            let (rows, cols) = (dims[S::NUM_DIMS - 2], dims[S::NUM_DIMS - 1]);
+            (numel / (rows * cols), rows, cols)
+        } else {
+            (1, 1, numel)
+        };
+        let (batch, rows, cols) = shape;
+        let step_size = self.relative_step();
+        let param = p.clone().try_reshape_like(&shape)?;
+        let mut g = p.device.upgrade(g).try_reshape_like(&shape)?;
+        if let Some(WeightDecay::L2(wd)) = self.cfg.weight_decay {
+            g = g.try_add(param.clone().try_mul(wd)?)?;
+        }
+
+        let state = match self.state.get_mut(&p.id) {
+            Some(state) => state,
+            None => {
+                let second_moment = if factored {
+                    SecondMoment::Factored {
+                        rows: p.device.try_zeros_like(&(batch, rows))?,
+                        cols: p.device.try_zeros_like(&(batch, cols))?,
+                    }
+                } else {
+                    SecondMoment::Full(p.device.try_zeros_like(&shape)?)
+                };
+                let first_moment = match self.cfg.beta1 {
+                    Some(_) => Some(p.device.try_zeros_like(&shape)?),
+                    None => None,
+                };
+                self.state.insert(
+                    p.id,
+                    AdafactorState {
+                        second_moment,
+                        first_moment,
+                    },
+                );
+                self.state.get_mut(&p.id).unwrap()
+            }
+        };
+
+        // update the second moment, and scale the gradient by its inverse square root
+        let beta2 = 1.0 - (self.t as f32).powf(self.cfg.decay_rate);
+        let g2 = g.clone().try_square()?.try_add(self.cfg.eps[0])?;
+        let mut update = match &mut state.second_moment {
+            SecondMoment::Factored { rows: r, cols: c } => {
+                let row_mean = g2.clone().try_mean::<(usize, usize), Axis<2>>()?;
+                let col_mean = g2.try_mean::<(usize, usize), Axis<1>>()?;
+                *r = r
+                    .clone()
+                    .try_mul(beta2)?
+                    .try_add(row_mean.try_mul(1.0 - beta2)?)?;
+                *c = c
+                    .clone()
+                    .try_mul(beta2)?
+                    .try_add(col_mean.try_mul(1.0 - beta2)?)?;
+                let r_mean = r
+                    .clone()
+                    .try_mean::<(usize,), Axis<1>>()?
+                    .try_broadcast_like::<_, Axis<1>>(&(batch, rows))?;
+                let r_factor = r
+                    .clone()
+                    .try_div(r_mean)?
+                    .try_rsqrt()?
+                    .try_broadcast_like::<_, Axis<2>>(&shape)?;
+                let c_factor = c
+                    .clone()
+                    .try_rsqrt()?
+                    .try_broadcast_like::<_, Axis<1>>(&shape)?;
+                g.try_mul(r_factor)?.try_mul(c_factor)?
+            }
+            SecondMoment::Full(v) => {
+                *v = v
+                    .clone()
+                    .try_mul(beta2)?
+                    .try_add(g2.try_mul(1.0 - beta2)?)?;
+                g.try_mul(v.clone().try_rsqrt()?)?
+            }
+        };
+        let clip = rms(update.clone())?
+            .try_div(self.cfg.clip_threshold)?
+            .try_clamp(1.0, f32::INFINITY)?;
+        update = update.try_div(clip)?;
+
+        let mut lr = step_size;
+        if self.cfg.scale_parameter {
+            let mut scale = [0.0];
+            let rms = param
+                .clone()
+                .try_square()?
+                .try_mean::<Rank0, _>()?
+                .try_sqrt()?;
+            rms.copy_into(&mut scale);
+            lr *= scale[0].max(self.cfg.eps[1]);
+        }
+        update = update.try_mul(lr)?;
+
+        if let (Some(beta1), Some(m)) = (self.cfg.beta1, &mut state.first_moment) {
+            *m = m
+                .clone()
+                .try_mul(beta1)?
+                .try_add(update.try_mul(1.0 - beta1)?)?;
+            update = m.clone();
+        }
+
+        let mut param = param;
+        if let Some(WeightDecay::Decoupled(wd)) = self.cfg.weight_decay {
+            param = param.clone().try_sub(param.try_mul(lr * wd)?)?;
+        }
+        p.storage = param.try_sub(update)?.try_reshape_like(p.shape())?.storage;
+        Ok(())
+    }
+}
+
+impl<E: Dtype, D: DeviceStorage, M: GradientUpdate<D, E>> Optimizer<M, D, E> for Adafactor<M, D, E>
+where
+    Self: ParamUpdater<D, E>,
+{
+    fn update(
+        &mut self,
+        module: &mut M,
+        gradients: Gradients<D>,
+    ) -> Result<(), OptimizerUpdateError<D>> {
+        self.t = self.t.checked_add(1).unwrap();
+        self.gradients = gradients;
+        let mut unused = Default::default();
+        match module.update(self, &mut unused) {
+            Ok(_) => unused.into(),
+            Err(e) => Err(OptimizerUpdateError::DeviceError(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::*;
+    use crate::tests::{assert_close, TestDevice};
+
+    fn test_unfactored(cfg: AdafactorConfig<f32>, expected: [[f32; 5]; 3]) {
+        let dev: TestDevice = Default::default();
+        let rate = dev.tensor([0.1, 1.0, 2.0, 10.0, 100.0]);
+        let mut t: Tensor<Rank1<5>, f32, _> = dev.ones();
+        let mut opt = Adafactor::new(cfg);
+        for e in expected.iter() {
+            let gradients = ((t.trace() * rate.clone()) - 1.0).square().sum().backward();
+            opt.update(&mut t, gradients).expect("");
+            assert_close(&t.array(), e);
+        }
+    }
+
+    fn test_factored(cfg: AdafactorConfig<f32>, expected: [[[f32; 3]; 2]; 3]) {
+        let dev: TestDevice = Default::default();
+        let rate = dev.tensor([[0.1, 1.0, 2.0], [10.0, -3.0, 0.5]]);
+        let mut t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, -0.5, 2.0], [0.25, 1.5, -1.0]]);
+        let mut opt = Adafactor::new(cfg);
+        for e in expected.iter() {
+            let gradients = ((t.trace() * rate.clone()) - 1.0).square().sum().backward();
+            opt.update(&mut t, gradients).expect("");
+            assert_close(&t.array(), e);
+        }
+        // rows and columns of the second moment, instead of every element
+        let state = opt.state.values().next().unwrap();
+        assert!(matches!(state.second_moment, SecondMoment::Factored { .. }));
+    }
+
+    #[test]
+    fn test_adafactor_default() {
+        const EXPECTED: [[f32; 5]; 3] = [
+            [1.01, 1.0, 0.99, 0.99, 0.99],
+            [1.0199556, 1.0, 0.98012595, 0.9800872, 0.98008288],
+            [1.0298682, 1.0, 0.97037331, 0.9702584, 0.9702455],
+        ];
+        test_unfactored(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_adafactor_warmup_init() {
+        let cfg = AdafactorConfig {
+            warmup_init: true,
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 3] = [
+            [1.000001, 1.0, 0.999999, 0.999999, 0.999999],
+            [1.000003, 1.0, 0.999997, 0.999997, 0.999997],
+            [1.000006, 1.0, 0.999994, 0.999994, 0.999994],
+        ];
+        test_unfactored(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_adafactor_factored() {
+        const EXPECTED: [[[f32; 3]; 2]; 3] = [
+            [
+                [1.0001639, -0.49752646, 1.9728898],
+                [0.2424261, 1.4924571, -0.9990606],
+            ],
+            [
+                [1.0003326, -0.4950518, 1.9460023],
+                [0.23499117, 1.4848958, -0.99810776],
+            ],
+            [
+                [1.0005065, -0.49257602, 1.919334],
+                [0.22769864, 1.4773174, -0.9971413],
+            ],
+        ];
+        test_factored(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_adafactor_lr_beta1_decoupled_weight_decay() {
+        let cfg = AdafactorConfig {
+            lr: Some(0.1),
+            scale_parameter: false,
+            beta1: Some(0.9),
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        const EXPECTED: [[[f32; 3]; 2]; 3] = [
+            [
+                [0.9501372, -0.47292941, 1.8773062],
+                [0.23115991, 1.4186858, -0.94921359],
+            ],
+            [
+                [0.902908, -0.4452523, 1.740331],
+                [0.20769331, 1.335629, -0.9002024],
+            ],
+            [
+                [0.8581914, -0.4170622, 1.591824],
+                [0.18070086, 1.2512867, -0.85287418],
+            ],
+        ];
+        test_factored(cfg, EXPECTED);
+    }
+}
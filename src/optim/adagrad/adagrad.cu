@@ -0,0 +1,45 @@
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct AdagradConfig {
+    float lr;
+    float initial_accumulator_value;
+    float eps;
+    WeightDecayType weight_decay_type;
+    float weight_decay;
+};
+
+extern "C" __global__ void adagrad_update(
+    const AdagradConfig cfg,
+    const size_t numel,
+    float* param,
+    float* sum_sq,
+    const float* grad
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+
+    if (i >= numel) {
+        return;
+    }
+
+    float p = param[i];
+    float g = grad[i];
+    float s = sum_sq[i];
+
+    if (cfg.weight_decay_type == L2) {
+        g += cfg.weight_decay * p;
+    }
+
+    s += g * g;
+    g *= cfg.lr / (sqrtf(s + cfg.initial_accumulator_value) + cfg.eps);
+
+    if (cfg.weight_decay_type == Decoupled) {
+        g += cfg.weight_decay * cfg.lr * p;
+    }
+
+    sum_sq[i] = s;
+    param[i] -= g;
+}
@@ -0,0 +1,44 @@
+use super::{AdagradConfig, AdagradKernel};
+use crate::{
+    optim::WeightDecay,
+    shapes::Shape,
+    tensor::cpu::{Cpu, StridedArray},
+};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl AdagradKernel<f32> for Cpu {
+    fn update<S: Shape>(
+        &self,
+        t: i32,
+        cfg: &AdagradConfig<f32>,
+        param: &mut StridedArray<S, f32>,
+        sum_sq: &mut StridedArray<S, f32>,
+        grad: StridedArray<S, f32>,
+    ) -> Result<(), Self::Err> {
+        debug_assert_eq!(param.data.len(), grad.data.len());
+        debug_assert_eq!(param.shape, grad.shape);
+        debug_assert_eq!(param.strides, grad.strides);
+
+        let lr = cfg.lr / (1.0 + (t - 1) as f32 * cfg.lr_decay);
+        for ((p, mut g), s) in param
+            .buf_iter_mut()
+            .zip(grad.buf_iter().cloned())
+            .zip(sum_sq.buf_iter_mut())
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += wd * *p;
+            }
+
+            *s += g * g;
+            g *= lr / ((*s + cfg.initial_accumulator_value).sqrt() + cfg.eps);
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += wd * lr * *p;
+            }
+
+            *p -= g;
+        }
+        Ok(())
+    }
+}
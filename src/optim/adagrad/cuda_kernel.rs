@@ -0,0 +1,68 @@
+use super::AdagradConfig;
+use crate::optim::optimizer::*;
+use crate::{shapes::Shape, tensor::Cuda};
+use cudarc::driver::{AsKernelParam, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+#[repr(C)]
+struct CudaAdagradConfig<E> {
+    lr: E,
+    initial_accumulator_value: E,
+    eps: E,
+    weight_decay_type: WeightDecayType,
+    weight_decay: E,
+}
+
+unsafe impl<E> AsKernelParam for CudaAdagradConfig<E> {}
+
+/// `lr` is the decayed learning rate of the current step.
+fn adagrad_config_to_cuda(config: &AdagradConfig<f32>, lr: f32) -> CudaAdagradConfig<f32> {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaAdagradConfig {
+        lr,
+        initial_accumulator_value: config.initial_accumulator_value,
+        eps: config.eps,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const MODULE_NAME: &str = "adagrad";
+const FN_NAME: &str = "adagrad_update";
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/adagrad.ptx"));
+
+impl super::AdagradKernel<f32> for Cuda {
+    fn update<S: Shape>(
+        &self,
+        t: i32,
+        cfg: &AdagradConfig<f32>,
+        param: &mut Self::Storage<S, f32>,
+        sum_sq: &mut Self::Storage<S, f32>,
+        grad: Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        debug_assert_eq!(param.data.len(), grad.data.len());
+        debug_assert_eq!(param.shape, grad.shape);
+        debug_assert_eq!(param.strides, grad.strides);
+
+        if !self.dev.has_func(MODULE_NAME, FN_NAME) {
+            self.dev.load_ptx(PTX_SRC.into(), MODULE_NAME, &[FN_NAME])?;
+        }
+
+        let lr = cfg.lr / (1.0 + (t - 1) as f32 * cfg.lr_decay);
+        let adagrad_cfg = adagrad_config_to_cuda(cfg, lr);
+        let numel = param.shape.num_elements();
+
+        let func = self.dev.get_func(MODULE_NAME, FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            adagrad_cfg,                     // const AdagradConfig cfg,
+            numel,                           // const size_t numel,
+            Arc::make_mut(&mut param.data),  // float* param,
+            Arc::make_mut(&mut sum_sq.data), // float* sum_sq,
+            grad.data.as_ref(),              // const float* grad
+        );
+        unsafe { func.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use std::marker::PhantomData;
+
+use crate::{
+    gradients::Gradients,
+    shapes::{Dtype, Shape},
+    tensor::{Cpu, DeviceStorage, Tensor},
+};
+
+use super::{
+    GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors, WeightDecay,
+};
+
+/// Configuration of hyperparameters for [Adagrad].
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// AdagradConfig {
+///     lr: 1e-1,
+///     lr_decay: 1e-3,
+///     initial_accumulator_value: 0.1,
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::L2(1e-1)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AdagradConfig<E> {
+    /// Learning rate. Defaults to `1e-2`.
+    pub lr: E,
+
+    /// The learning rate at step `t` is `lr / (1 + (t - 1) * lr_decay)`. Defaults to `0.0`.
+    pub lr_decay: E,
+
+    /// Starting value of the sum of squared gradients. Defaults to `0.0`.
+    pub initial_accumulator_value: E,
+
+    /// Epsilon for numerical stability. Defaults to `1e-10`.
+    pub eps: E,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay<E>>,
+}
+
+impl Default for AdagradConfig<f32> {
+    fn default() -> Self {
+        Self {
+            lr: 1e-2,
+            lr_decay: 0.0,
+            initial_accumulator_value: 0.0,
+            eps: 1e-10,
+            weight_decay: None,
+        }
+    }
+}
+
+/// An implementation of the Adagrad optimizer from
+/// [Adaptive Subgradient Methods for Online Learning and Stochastic Optimization](https://jmlr.org/papers/v12/duchi11a.html).
+///
+/// Each parameter's step is scaled by the inverse square root of the sum of all its squared
+/// gradients so far, so rarely updated parameters take larger steps. This matches pytorch's
+/// Adagrad.
+///
+/// # Example Usage
+///
+/// Constructing using default:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Tensor<Rank0>;
+/// let mut opt: Adagrad<Model> = Default::default();
+/// ```
+///
+/// Constructing using new:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Tensor<Rank0>;
+/// let mut opt: Adagrad<Model> = Adagrad::new(AdagradConfig {
+///     lr: 1e-1,
+///     lr_decay: 1e-4,
+///     ..Default::default()
+/// });
+/// ```
+///
+/// See module level documentation at [crate::optim] for examples of how to actually use an optimizer.
+#[derive(Debug)]
+pub struct Adagrad<M, D: DeviceStorage = Cpu, E: Dtype = f32> {
+    /// Hyperparameter configuration
+    pub cfg: AdagradConfig<E>,
+
+    t: i32,
+    sum_sq: Gradients<D>,
+    gradients: Gradients<D>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, D: DeviceStorage, E: Dtype> Default for Adagrad<M, D, E>
+where
+    AdagradConfig<E>: Default,
+{
+    /// See [AdagradConfig]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<M, D: DeviceStorage, E: Dtype> Adagrad<M, D, E> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(cfg: AdagradConfig<E>) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            sum_sq: Default::default(),
+            gradients: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+pub(super) trait AdagradKernel<E: Dtype>: DeviceStorage {
+    fn update<S: Shape>(
+        &self,
+        t: i32,
+        cfg: &AdagradConfig<E>,
+        param: &mut Self::Storage<S, E>,
+        sum_sq: &mut Self::Storage<S, E>,
+        grad: Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+impl<M, D: AdagradKernel<f32>> ParamUpdater<D, f32> for Adagrad<M, D, f32> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let g = self.gradients.remove(p);
+        match g {
+            None => unused.add(p),
+            Some(g) => {
+                // NOTE: the sum starts at 0, and the kernel adds `initial_accumulator_value`
+                let s = self.sum_sq.get_or_alloc_mut(p)?;
+                p.device.update(self.t, &self.cfg, &mut p.storage, s, g)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<E: Dtype, D: DeviceStorage, M: GradientUpdate<D, E>> Optimizer<M, D, E> for Adagrad<M, D, E>
+where
+    Self: ParamUpdater<D, E>,
+{
+    fn update(
+        &mut self,
+        module: &mut M,
+        gradients: Gradients<D>,
+    ) -> Result<(), OptimizerUpdateError<D>> {
+        self.t = self.t.checked_add(1).unwrap();
+        self.gradients = gradients;
+        let mut unused = Default::default();
+        match module.update(self, &mut unused) {
+            Ok(_) => unused.into(),
+            Err(e) => Err(OptimizerUpdateError::DeviceError(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_close, TestDevice};
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    fn test_matches_expected(cfg: AdagradConfig<f32>, expected: [[f32; 5]; 5]) {
+        let dev: TestDevice = Default::default();
+        let rate = dev.tensor([0.1, 1.0, 2.0, 10.0, 100.0]);
+        let mut t: Tensor<Rank1<5>, f32, _> = dev.ones();
+        let mut opt = Adagrad::new(cfg);
+        for e in expected.iter() {
+            let gradients = ((t.trace() * rate.clone()) - 1.0).square().sum().backward();
+            opt.update(&mut t, gradients).expect("");
+            assert_close(&t.array(), e);
+        }
+    }
+
+    #[test]
+    fn test_adagrad_default() {
+        const EXPECTED: [[f32; 5]; 5] = [
+            [1.01, 1.0, 0.99, 0.99, 0.99],
+            [1.0170671, 1.0, 0.9830007, 0.98296857, 0.98296493],
+            [1.0228354, 1.0, 0.9773218, 0.97724724, 0.9772388],
+            [1.0278296, 1.0, 0.9724277, 0.97230554, 0.9722917],
+            [1.0322957, 1.0, 0.96806836, 0.9678953, 0.96787584],
+        ];
+        test_matches_expected(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_lr_decay() {
+        let cfg = AdagradConfig {
+            lr: 0.1,
+            lr_decay: 0.5,
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 5] = [
+            [1.1, 1.0, 0.9, 0.9, 0.9],
+            [1.1468763, 1.0, 0.8583536, 0.8557091, 0.85543],
+            [1.1755341, 1.0, 0.8339349, 0.82913053, 0.82862514],
+            [1.1953763, 1.0, 0.8173662, 0.8108763, 0.81019616],
+            [1.2101623, 1.0, 0.80515605, 0.797326, 0.79650843],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_initial_accumulator_value() {
+        let cfg = AdagradConfig {
+            initial_accumulator_value: 0.5,
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 5] = [
+            [1.0024669, 1.0, 0.99015266, 0.99000007, 0.99],
+            [1.0048614, 1.0, 0.9832074, 0.9829686, 0.98296493],
+            [1.0071894, 1.0, 0.9775579, 0.9772473, 0.9772388],
+            [1.0094563, 1.0, 0.97268283, 0.9723056, 0.9722917],
+            [1.0116665, 1.0, 0.96833706, 0.9678954, 0.96787584],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_l2_weight_decay() {
+        let cfg = AdagradConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 5] = [
+            [0.99, 0.99, 0.99, 0.99, 0.99],
+            [0.9829871, 0.9831125, 0.9829967, 0.98296857, 0.98296493],
+            [0.9772902, 0.97758216, 0.9773125, 0.9772472, 0.9772388],
+            [0.9723759, 0.9728557, 0.97241247, 0.9723055, 0.9722917],
+            [0.967995, 0.96867615, 0.96804667, 0.96789527, 0.96787584],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_adagrad_decoupled_weight_decay() {
+        let cfg = AdagradConfig {
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        const EXPECTED: [[f32; 5]; 5] = [
+            [1.005, 0.995, 0.985, 0.985, 0.985],
+            [1.0070441, 1.000025, 0.97311246, 0.9730636, 0.9730581],
+            [1.0077804, 0.99497485, 0.96262836, 0.96250963, 0.96249634],
+            [1.0077399, 0.99708873, 0.95300007, 0.9527979, 0.9527753],
+            [1.0071722, 0.9959022, 0.9439704, 0.9436744, 0.9436414],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+}
@@ -1,5 +1,46 @@
 use super::{AdamConfig, AdamKernel};
 use crate::{optim::WeightDecay, shapes::Shape, tensor::Cpu};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use std::sync::Arc;
+
+/// Minimum number of elements each thread updates. Parameters smaller than
+/// twice this are updated on the calling thread.
+#[cfg(feature = "std")]
+const MIN_ELEMS_PER_THREAD: usize = 1 << 15;
+
+/// Updates the moments & parameters in a single pass over the buffers.
+fn adam_step(
+    cfg: &AdamConfig<f32>,
+    bias_corrections: [f32; 2],
+    param: &mut [f32],
+    moment1: &mut [f32],
+    moment2: &mut [f32],
+    grad: &[f32],
+) {
+    let [b1, b2] = cfg.betas;
+    for ((p, mut g), (m, v)) in param
+        .iter_mut()
+        .zip(grad.iter().cloned())
+        .zip(moment1.iter_mut().zip(moment2.iter_mut()))
+    {
+        if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+            g += wd * *p;
+        }
+
+        *m = *m * b1 + g * (1.0 - b1);
+        *v = *v * b2 + g.powi(2) * (1.0 - b2);
+        let m_hat = *m * bias_corrections[0];
+        let v_hat = *v * bias_corrections[1];
+        g = cfg.lr * m_hat / (v_hat.sqrt() + cfg.eps);
+
+        if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+            g += wd * cfg.lr * *p;
+        }
+
+        *p -= g;
+    }
+}
 
 impl AdamKernel<f32> for Cpu {
     fn update<S: Shape>(
@@ -12,30 +53,45 @@ impl AdamKernel<f32> for Cpu {
         grad: Self::Storage<S, f32>,
     ) -> Result<(), Self::Err> {
         debug_assert_eq!(param.data.len(), grad.data.len());
+        debug_assert_eq!(param.data.len(), moment1.data.len());
+        debug_assert_eq!(param.data.len(), moment2.data.len());
         debug_assert_eq!(param.shape, grad.shape);
         debug_assert_eq!(param.strides, grad.strides);
 
-        for ((p, mut g), (m, v)) in param
-            .buf_iter_mut()
-            .zip(grad.buf_iter().cloned())
-            .zip(moment1.buf_iter_mut().zip(moment2.buf_iter_mut()))
-        {
-            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
-                g += wd * *p;
-            }
-
-            *m = *m * cfg.betas[0] + g * (1.0 - cfg.betas[0]);
-            *v = *v * cfg.betas[1] + g.powi(2) * (1.0 - cfg.betas[1]);
-            let m_hat = *m * (1.0 - cfg.betas[0].powi(t)).recip();
-            let v_hat = *v * (1.0 - cfg.betas[1].powi(t)).recip();
-            g = cfg.lr * m_hat / (v_hat.sqrt() + cfg.eps);
+        let bias_corrections = [
+            (1.0 - cfg.betas[0].powi(t)).recip(),
+            (1.0 - cfg.betas[1].powi(t)).recip(),
+        ];
+        let param = Arc::make_mut(&mut param.data);
+        let moment1 = Arc::make_mut(&mut moment1.data);
+        let moment2 = Arc::make_mut(&mut moment2.data);
+        let grad = grad.data.as_ref();
 
-            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
-                g += wd * cfg.lr * *p;
+        #[cfg(feature = "std")]
+        {
+            let num_threads = std::thread::available_parallelism()
+                .map_or(1, |n| n.get())
+                .min(param.len() / MIN_ELEMS_PER_THREAD);
+            if num_threads > 1 {
+                let chunk_size = param.len().div_ceil(num_threads);
+                std::thread::scope(|s| {
+                    for ((p, g), (m, v)) in param
+                        .chunks_mut(chunk_size)
+                        .zip(grad.chunks(chunk_size))
+                        .zip(
+                            moment1
+                                .chunks_mut(chunk_size)
+                                .zip(moment2.chunks_mut(chunk_size)),
+                        )
+                    {
+                        s.spawn(move || adam_step(cfg, bias_corrections, p, m, v, g));
+                    }
+                });
+                return Ok(());
             }
-
-            *p -= g;
         }
+
+        adam_step(cfg, bias_corrections, param, moment1, moment2, grad);
         Ok(())
     }
 }
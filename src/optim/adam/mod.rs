@@ -54,6 +54,9 @@ impl Default for AdamConfig<f32> {
 /// An implementation of the Adam optimizer from
 /// [Adam: A Method for Stochastic Optimization](https://arxiv.org/abs/1412.6980)
 ///
+/// Use [WeightDecay::Decoupled] for AdamW. Each parameter is updated in a single pass over its
+/// buffers, and on [Cpu] large parameters are split across multiple threads.
+///
 /// # Example Usage
 ///
 /// Constructing using default:
@@ -287,6 +290,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_adam_large_param_matches_small() {
+        let dev: TestDevice = Default::default();
+        let cfg = AdamConfig {
+            weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+            ..Default::default()
+        };
+        let mut small_opt = Adam::new(cfg);
+        let mut large_opt = Adam::new(cfg);
+
+        let rate = dev.tensor([1e-4, 1e-3, 1e-2, 1e-1, 1e-0]);
+        let mut small: Tensor<Rank1<5>, f32, _> = dev.ones();
+        // big enough to be split across threads
+        let mut large: Tensor<Rank2<20000, 5>, f32, _> = dev.ones();
+
+        for _ in 0..3 {
+            let g = (small.trace() * rate.clone()).square().sum().backward();
+            small_opt.update(&mut small, g).expect("");
+            let g = (large.trace() * rate.clone().broadcast()).square().sum();
+            large_opt.update(&mut large, g.backward()).expect("");
+        }
+        let small = small.array();
+        for row in large.array().iter() {
+            assert_close(row, &small);
+        }
+    }
+
     // #[test]
     // fn test_adam_changes_all_params() {
     //     let dev: TestDevice = Default::default();
@@ -0,0 +1,363 @@
+use crate::{gradients::Gradients, nn::PerSampleGradients, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors};
+
+use rand_distr::StandardNormal;
+use std::{marker::PhantomData, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// The Rényi divergence orders that [DpSgd] tracks the privacy loss at.
+const ORDERS: [f64; 67] = {
+    let mut orders = [0.0; 67];
+    let mut i = 0;
+    while i < 63 {
+        orders[i] = (i + 2) as f64;
+        i += 1;
+    }
+    orders[63] = 80.0;
+    orders[64] = 96.0;
+    orders[65] = 128.0;
+    orders[66] = 256.0;
+    orders
+};
+
+/// Configuration of hyperparameters for [DpSgd].
+#[derive(Debug, Clone, Copy)]
+pub struct DpSgdConfig {
+    /// The gradient of each sample is scaled down to at most this L2 norm.
+    pub max_grad_norm: f32,
+
+    /// The standard deviation of the noise, relative to [DpSgdConfig::max_grad_norm].
+    pub noise_multiplier: f32,
+
+    /// The number of samples in the training set, used to compute the sampling rate of
+    /// each batch for the privacy accounting.
+    pub dataset_size: usize,
+}
+
+/// Differentially private SGD from
+/// [Deep Learning with Differential Privacy](https://arxiv.org/abs/1607.00133).
+///
+/// Wraps any other optimizer, and changes the gradients it gets: the gradient of each sample
+/// is clipped to an L2 norm of at most [DpSgdConfig::max_grad_norm], the clipped gradients are
+/// summed up, Gaussian noise with a standard deviation of
+/// `noise_multiplier * max_grad_norm` is added, and the result is divided by the batch size.
+///
+/// This needs the gradient of each sample, so the forward pass has to be run with
+/// [crate::nn::PerSampleModule::forward_per_sample()], and the loss has to be a sum over the
+/// batch. Parameters without per-sample gradients get no gradient, so the inner optimizer
+/// reports them as unused.
+///
+/// The privacy budget that has been spent is tracked with a Rényi differential privacy
+/// accountant for the sampled Gaussian mechanism, see [DpSgd::epsilon()]. This assumes that
+/// each batch is sampled uniformly at random from the training set.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<3, 8>, ReLU, Linear<8, 1>);
+/// let mut model: Model = dev.build_module();
+/// let mut opt: DpSgd<Model, Sgd<Model>> = DpSgd::new(
+///     Default::default(),
+///     DpSgdConfig {
+///         max_grad_norm: 1.0,
+///         noise_multiplier: 1.1,
+///         dataset_size: 60_000,
+///     },
+/// );
+///
+/// let x: Tensor<Rank2<16, 3>> = dev.sample_normal();
+/// let mut per_sample = PerSampleGradients::default();
+/// let loss = model.forward_per_sample(x.trace(), &mut per_sample).square().sum();
+/// opt.update(&mut model, &per_sample, loss.backward()).expect("");
+/// assert!(opt.epsilon(1e-5) > 0.0);
+/// ```
+#[derive(Debug)]
+pub struct DpSgd<M, O> {
+    /// The inner optimizer
+    pub opt: O,
+
+    /// Hyperparameter configuration
+    pub cfg: DpSgdConfig,
+
+    /// The privacy loss so far at each of [ORDERS]
+    rdp: Vec<f64>,
+    steps: usize,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, O> DpSgd<M, O> {
+    /// Wraps `opt` with hyperparameters from `cfg`
+    pub fn new(opt: O, cfg: DpSgdConfig) -> Self {
+        Self {
+            opt,
+            cfg,
+            rdp: alloc::vec![0.0; ORDERS.len()],
+            steps: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// The number of updates so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The privacy budget spent so far: training has been `(epsilon, delta)` differentially
+    /// private.
+    pub fn epsilon(&self, delta: f64) -> f64 {
+        ORDERS
+            .iter()
+            .zip(self.rdp.iter())
+            .map(|(order, rdp)| rdp + (1.0 / delta).ln() / (order - 1.0))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Privatizes the gradients of `module` with the per-sample gradients in `gradients`,
+    /// and passes them to the inner optimizer.
+    pub fn update<D: Device<f32>>(
+        &mut self,
+        module: &mut M,
+        per_sample: &PerSampleGradients<D>,
+        gradients: Gradients<D>,
+    ) -> Result<(), OptimizerUpdateError<D>>
+    where
+        M: GradientUpdate<D, f32>,
+        O: Optimizer<M, D, f32>,
+    {
+        let norms = per_sample
+            .try_norms(&gradients)
+            .map_err(OptimizerUpdateError::DeviceError)?;
+        let batch_size = norms.shape().0;
+        let mut privatize = Privatize {
+            per_sample,
+            scales: clip_scales(norms, self.cfg.max_grad_norm)
+                .map_err(OptimizerUpdateError::DeviceError)?,
+            noise_std: self.cfg.noise_multiplier * self.cfg.max_grad_norm,
+            gradients,
+        };
+        module
+            .update(&mut privatize, &mut Default::default())
+            .map_err(OptimizerUpdateError::DeviceError)?;
+        self.opt.update(module, privatize.gradients)?;
+
+        let q = batch_size as f64 / self.cfg.dataset_size as f64;
+        let sigma = self.cfg.noise_multiplier as f64;
+        for (rdp, order) in self.rdp.iter_mut().zip(ORDERS) {
+            *rdp += sampled_gaussian_rdp(q, sigma, order);
+        }
+        self.steps += 1;
+        Ok(())
+    }
+}
+
+/// `min(1, max_norm / norm)` for each sample
+fn clip_scales<D: Device<f32>>(
+    norms: Tensor<(usize,), f32, D>,
+    max_norm: f32,
+) -> Result<Tensor<(usize,), f32, D>, D::Err> {
+    norms
+        .try_clamp(max_norm, f32::INFINITY)?
+        .try_div(max_norm)?
+        .try_powi(-1)
+}
+
+/// Replaces the gradient of each parameter with the noisy mean of its clipped per-sample
+/// gradients.
+struct Privatize<'a, D: Device<f32>> {
+    per_sample: &'a PerSampleGradients<D>,
+    scales: Tensor<(usize,), f32, D>,
+    noise_std: f32,
+    gradients: Gradients<D>,
+}
+
+impl<'a, D: Device<f32>> ParamUpdater<D, f32> for Privatize<'a, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        self.gradients.remove(p);
+        if let Some(g) = self.per_sample.get(&self.gradients, p) {
+            let (batch_size, numel) = *g.shape();
+            let scales = self
+                .scales
+                .clone()
+                .try_broadcast_like::<_, Axis<1>>(g.shape())?;
+            let sum = g.try_mul(scales)?.try_sum::<(usize,), Axis<0>>()?;
+            let noise = p.device.try_sample_like(&(numel,), StandardNormal)?;
+            let g = sum
+                .try_add(noise.try_mul(self.noise_std)?)?
+                .try_div(batch_size as f32)?
+                .try_reshape_like(p.shape())?;
+            self.gradients.insert(p, g.storage);
+        }
+        Ok(())
+    }
+}
+
+/// The Rényi differential privacy of the Gaussian mechanism with noise multiplier `sigma`,
+/// when each sample is in the batch with probability `q`, at integer `order`. See
+/// [Rényi Differential Privacy of the Sampled Gaussian Mechanism](https://arxiv.org/abs/1908.10530).
+fn sampled_gaussian_rdp(q: f64, sigma: f64, order: f64) -> f64 {
+    if q <= 0.0 {
+        return 0.0;
+    }
+    if sigma <= 0.0 {
+        return f64::INFINITY;
+    }
+    if q >= 1.0 {
+        return order / (2.0 * sigma * sigma);
+    }
+
+    // log(sum_k binom(order, k) (1 - q)^(order - k) q^k exp((k^2 - k) / (2 sigma^2)))
+    let n = order as usize;
+    let mut log_binom = 0.0;
+    let mut terms = Vec::with_capacity(n + 1);
+    for k in 0..=n {
+        if k > 0 {
+            log_binom += ((n - k + 1) as f64 / k as f64).ln();
+        }
+        let k = k as f64;
+        terms.push(
+            log_binom
+                + (order - k) * (1.0 - q).ln()
+                + k * q.ln()
+                + (k * k - k) / (2.0 * sigma * sigma),
+        );
+    }
+    let max = terms.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let log_sum = max + terms.iter().map(|t| (t - max).exp()).sum::<f64>().ln();
+    log_sum / (order - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::*,
+        optim::{Sgd, SgdConfig},
+        tests::*,
+    };
+
+    fn sgd<M>() -> Sgd<M, TestDevice> {
+        Sgd::new(SgdConfig {
+            lr: 1.0,
+            momentum: None,
+            weight_decay: None,
+        })
+    }
+
+    #[test]
+    fn test_dp_sgd_clips_per_sample() {
+        let dev: TestDevice = Default::default();
+        let mut model: Linear<1, 1, _> = dev.build_module();
+        model.weight = dev.tensor([[0.5]]);
+        model.bias = dev.tensor([0.0]);
+        let mut opt = DpSgd::new(
+            sgd(),
+            DpSgdConfig {
+                max_grad_norm: 1.0,
+                noise_multiplier: 0.0,
+                dataset_size: 10,
+            },
+        );
+
+        // the gradients of each sample are x for the weight and 1 for the bias, with norms
+        // sqrt(10) and 1, so only the first one is clipped
+        let x = dev.tensor([[3.0], [0.0]]);
+        let mut per_sample = PerSampleGradients::default();
+        let y = model.forward_per_sample(x.trace(), &mut per_sample);
+        opt.update(&mut model, &per_sample, y.sum().backward())
+            .expect("");
+        let s = 1.0 / 10.0f32.sqrt();
+        assert_close(&model.weight.array(), &[[0.5 - 1.5 * s]]);
+        assert_close(&model.bias.array(), &[-(s + 1.0) / 2.0]);
+        assert_eq!(opt.steps(), 1);
+    }
+
+    #[test]
+    fn test_dp_sgd_without_clipping_or_noise_is_inner_optimizer() {
+        let dev: TestDevice = Default::default();
+        let mut a: (Linear<3, 4, _>, ReLU, Linear<4, 2, _>) = dev.build_module();
+        let mut b = a.clone();
+        let mut opt_a = sgd();
+        let mut opt_b = DpSgd::new(
+            sgd(),
+            DpSgdConfig {
+                max_grad_norm: 1e6,
+                noise_multiplier: 0.0,
+                dataset_size: 100,
+            },
+        );
+        for _ in 0..3 {
+            let x: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+            let g = a.forward(x.trace()).square().mean().backward();
+            opt_a.update(&mut a, g).expect("");
+
+            let mut per_sample = PerSampleGradients::default();
+            let y = b.forward_per_sample(x.trace(), &mut per_sample);
+            let g = (y.square().sum() / 2.0).backward();
+            opt_b.update(&mut b, &per_sample, g).expect("");
+        }
+        assert_close(&a.0.weight.array(), &b.0.weight.array());
+        assert_close(&a.2.bias.array(), &b.2.bias.array());
+    }
+
+    #[test]
+    fn test_dp_sgd_epsilon() {
+        // without subsampling, one step is eps = min(order / 2 + ln(1 / delta) / (order - 1)),
+        // which is at order 6
+        let mut rdp: Vec<f64> = ORDERS
+            .iter()
+            .map(|&o| sampled_gaussian_rdp(1.0, 1.0, o))
+            .collect();
+        let mut opt: DpSgd<(), ()> = DpSgd::new(
+            (),
+            DpSgdConfig {
+                max_grad_norm: 1.0,
+                noise_multiplier: 1.0,
+                dataset_size: 1,
+            },
+        );
+        opt.rdp = rdp.clone();
+        let expected = 3.0 + 1e5f64.ln() / 5.0;
+        assert!((opt.epsilon(1e-5) - expected).abs() < 1e-9);
+
+        // the binomial expansion agrees with the closed form for a full batch
+        let almost_full: Vec<f64> = ORDERS
+            .iter()
+            .map(|&o| sampled_gaussian_rdp(1.0 - 1e-12, 1.0, o))
+            .collect();
+        assert!((almost_full[4] - rdp[4]).abs() < 1e-6);
+
+        // 60 epochs of MNIST with batches of 256, as in the TensorFlow Privacy tutorial
+        let q = 256.0 / 60000.0;
+        let steps = (60 * 60000 / 256) as f64;
+        opt.rdp = ORDERS
+            .iter()
+            .map(|&o| steps * sampled_gaussian_rdp(q, 1.1, o))
+            .collect();
+        assert!((opt.epsilon(1e-5) - 3.01).abs() < 5e-3);
+
+        // subsampling, fewer steps, and more noise all spend less of the budget
+        rdp = ORDERS
+            .iter()
+            .map(|&o| sampled_gaussian_rdp(0.01, 1.0, o))
+            .collect();
+        opt.rdp = rdp.iter().map(|r| r * 100.0).collect();
+        let subsampled = opt.epsilon(1e-5);
+        assert!(subsampled < expected);
+        opt.rdp = rdp.iter().map(|r| r * 1000.0).collect();
+        assert!(opt.epsilon(1e-5) > subsampled);
+        opt.rdp = ORDERS
+            .iter()
+            .map(|&o| 100.0 * sampled_gaussian_rdp(0.01, 2.0, o))
+            .collect();
+        assert!(opt.epsilon(1e-5) < subsampled);
+    }
+}
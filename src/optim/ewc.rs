@@ -0,0 +1,361 @@
+use crate::{
+    gradients::{Gradients, OwnedTape},
+    nn::PerSampleGradients,
+    optim::{GradientUpdate, ParamUpdater, UnusedTensors},
+    rl::CollectParams,
+    shapes::{Axis, HasShape, Rank0, Shape},
+    tensor::Tensor,
+    tensor_ops::{Device, MeanTo, ReshapeTo, SumTo, TryAdd, TryMul, TrySub},
+};
+use std::{any::Any, boxed::Box, collections::VecDeque};
+
+/// Estimates the diagonal of the Fisher information of the parameters of `model`, as the
+/// mean of the squared per-sample gradients in `gradients`: `F = mean(g * g)`.
+///
+/// Each item of `gradients` should be the gradients of the negative log likelihood of a
+/// single sample (e.g. its loss), so the result is the empirical Fisher. Parameters without
+/// a gradient count as zero.
+///
+/// The result has the same structure as `model`, with the Fisher information in place of
+/// each parameter. See [Ewc] for an example.
+pub fn diagonal_fisher<M, D, I>(model: &M, gradients: I) -> M
+where
+    M: GradientUpdate<D, f32> + Clone,
+    D: Device<f32>,
+    I: IntoIterator<Item = Gradients<D>>,
+{
+    try_diagonal_fisher(model, gradients).unwrap()
+}
+
+/// Fallible version of [diagonal_fisher()]
+pub fn try_diagonal_fisher<M, D, I>(model: &M, gradients: I) -> Result<M, D::Err>
+where
+    M: GradientUpdate<D, f32> + Clone,
+    D: Device<f32>,
+    I: IntoIterator<Item = Gradients<D>>,
+{
+    let mut unused = Default::default();
+    let mut fisher = model.clone();
+    fisher.update(&mut FisherStep::Zero, &mut unused)?;
+    let mut num_samples = 0;
+    for mut grads in gradients {
+        fisher.update(&mut FisherStep::AddSquare(&mut grads), &mut unused)?;
+        num_samples += 1;
+    }
+    if num_samples > 0 {
+        let scale = 1.0 / num_samples as f32;
+        fisher.update(&mut FisherStep::Scale(scale), &mut unused)?;
+    }
+    Ok(fisher)
+}
+
+/// Like [diagonal_fisher()], but with the per-sample gradients of a whole batch from a
+/// single backward pass, see [PerSampleGradients]: `F = mean(g * g)` over the rows of the
+/// per-sample gradients of each parameter.
+///
+/// The loss should be the sum of the negative log likelihoods of the samples. Parameters
+/// without per-sample gradients count as zero.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<2, 4>, ReLU, Linear<4, 1>);
+/// let mut model: Model = dev.build_module();
+/// let task_a: Tensor<Rank2<8, 2>> = dev.sample_normal();
+/// let mut per_sample = Default::default();
+/// let y = model.forward_per_sample(task_a.trace(), &mut per_sample);
+/// let grads = y.square().sum().backward();
+/// let fisher = per_sample_diagonal_fisher(&model, &per_sample, &grads);
+/// let ewc = Ewc::new(&model, fisher, 100.0);
+/// ```
+pub fn per_sample_diagonal_fisher<M, D>(
+    model: &M,
+    per_sample: &PerSampleGradients<D>,
+    grads: &Gradients<D>,
+) -> M
+where
+    M: GradientUpdate<D, f32> + Clone,
+    D: Device<f32>,
+{
+    try_per_sample_diagonal_fisher(model, per_sample, grads).unwrap()
+}
+
+/// Fallible version of [per_sample_diagonal_fisher()]
+pub fn try_per_sample_diagonal_fisher<M, D>(
+    model: &M,
+    per_sample: &PerSampleGradients<D>,
+    grads: &Gradients<D>,
+) -> Result<M, D::Err>
+where
+    M: GradientUpdate<D, f32> + Clone,
+    D: Device<f32>,
+{
+    let mut fisher = model.clone();
+    fisher.update(
+        &mut PerSampleFisher { per_sample, grads },
+        &mut Default::default(),
+    )?;
+    Ok(fisher)
+}
+
+/// Replaces each parameter with the mean of its squared per-sample gradients, for
+/// [try_per_sample_diagonal_fisher()]. Like [FisherStep], the parameters keep their ids.
+struct PerSampleFisher<'a, D: Device<f32>> {
+    per_sample: &'a PerSampleGradients<D>,
+    grads: &'a Gradients<D>,
+}
+
+impl<'a, D: Device<f32>> ParamUpdater<D, f32> for PerSampleFisher<'a, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        match self.per_sample.get(self.grads, p) {
+            Some(g) => {
+                let f = g.try_square()?.try_mean::<(usize,), Axis<0>>()?;
+                p.storage = f.try_reshape_like(p.shape())?.storage;
+                Ok(())
+            }
+            None => p.try_fill_with_zeros(),
+        }
+    }
+}
+
+/// Visits the parameters of the Fisher information for [try_diagonal_fisher()]. Only the
+/// storage of each parameter is replaced, so they keep the ids of the model's parameters.
+enum FisherStep<'a, D: Device<f32>> {
+    Zero,
+    AddSquare(&'a mut Gradients<D>),
+    Scale(f32),
+}
+
+impl<'a, D: Device<f32>> ParamUpdater<D, f32> for FisherStep<'a, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        match self {
+            FisherStep::Zero => p.try_fill_with_zeros(),
+            FisherStep::AddSquare(grads) => {
+                if let Some(g) = grads.remove(p) {
+                    let g = p.device.upgrade(g);
+                    p.storage = p.clone().try_add(g.try_square()?)?.storage;
+                }
+                Ok(())
+            }
+            FisherStep::Scale(scale) => {
+                p.storage = p.clone().try_mul(*scale)?.storage;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Elastic weight consolidation from
+/// [Overcoming catastrophic forgetting in neural networks](https://arxiv.org/abs/1612.00796).
+///
+/// When training on a new task, penalizes moving the parameters that were important for a
+/// previous task away from their values after that task:
+/// `lambda / 2 * sum(fisher * (params - anchor)^2)`, where the importance of each parameter
+/// is its diagonal Fisher information from [diagonal_fisher()].
+///
+/// To remember several previous tasks, add the penalties of one [Ewc] per task.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<2, 4>, ReLU, Linear<4, 1>);
+/// let mut model: Model = dev.build_module();
+/// // -- snip training on task A --
+///
+/// let task_a: [Tensor<Rank1<2>>; 4] = [(); 4].map(|_| dev.sample_normal());
+/// let fisher = diagonal_fisher(
+///     &model,
+///     task_a.iter().map(|x| model.forward(x.trace()).square().sum().backward()),
+/// );
+/// let ewc = Ewc::new(&model, fisher, 100.0);
+///
+/// // training on task B
+/// let mut sgd: Sgd<Model> = Default::default();
+/// let x: Tensor<Rank2<8, 2>> = dev.sample_normal();
+/// let loss = model.forward(x.trace()).square().mean() + ewc.penalty(&model);
+/// sgd.update(&mut model, loss.backward()).expect("");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ewc<M> {
+    /// The parameters after the previous task.
+    pub anchor: M,
+
+    /// The diagonal Fisher information of each parameter for the previous task.
+    pub fisher: M,
+
+    /// How strongly parameters are pulled back to [Ewc::anchor].
+    pub lambda: f32,
+}
+
+impl<M: Clone> Ewc<M> {
+    /// Anchors the parameters to their current values in `model`.
+    pub fn new(model: &M, fisher: M, lambda: f32) -> Self {
+        Self {
+            anchor: model.clone(),
+            fisher,
+            lambda,
+        }
+    }
+
+    /// The penalty for the parameters of `model`, with a tape that records the gradients of
+    /// the parameters of `model`. Add it to the loss of the new task.
+    ///
+    /// **Panics** if `model` has no parameters.
+    pub fn penalty<D: Device<f32>>(&self, model: &M) -> Tensor<Rank0, f32, D, OwnedTape<D>>
+    where
+        M: GradientUpdate<D, f32>,
+    {
+        self.try_penalty(model).unwrap()
+    }
+
+    /// Fallible version of [Ewc::penalty()]
+    pub fn try_penalty<D: Device<f32>>(
+        &self,
+        model: &M,
+    ) -> Result<Tensor<Rank0, f32, D, OwnedTape<D>>, D::Err>
+    where
+        M: GradientUpdate<D, f32>,
+    {
+        let mut unused = Default::default();
+        let mut anchor = CollectParams(Default::default());
+        self.anchor.clone().update(&mut anchor, &mut unused)?;
+        let mut fisher = CollectParams(Default::default());
+        self.fisher.clone().update(&mut fisher, &mut unused)?;
+        let mut penalty = Penalty {
+            anchor: anchor.0,
+            fisher: fisher.0,
+            total: None,
+        };
+        model.clone().update(&mut penalty, &mut unused)?;
+        penalty
+            .total
+            .expect("EWC requires a model with parameters")
+            .try_mul(self.lambda / 2.0)
+    }
+}
+
+/// Sums `fisher * (p - anchor)^2` over every parameter it visits, with the anchor and Fisher
+/// information collected by [CollectParams].
+struct Penalty<D: Device<f32>> {
+    anchor: VecDeque<Box<dyn Any>>,
+    fisher: VecDeque<Box<dyn Any>>,
+    total: Option<Tensor<Rank0, f32, D, OwnedTape<D>>>,
+}
+
+impl<D: Device<f32>> ParamUpdater<D, f32> for Penalty<D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let next = |params: &mut VecDeque<Box<dyn Any>>| {
+            params
+                .pop_front()
+                .and_then(|p| p.downcast::<Tensor<S, f32, D>>().ok())
+                .expect("EWC requires the anchor and Fisher information to match the model")
+        };
+        // the anchor and the Fisher information are clones of the model's parameters, so
+        // they get new ids to keep them apart from `p` on the tape
+        let anchor = p.device.upgrade(next(&mut self.anchor).storage);
+        let fisher = p.device.upgrade(next(&mut self.fisher).storage);
+        let diff = p.trace().try_sub(anchor)?;
+        let term = diff.try_square()?.try_mul(fisher)?.try_sum()?;
+        self.total = Some(match self.total.take() {
+            Some(total) => total.try_add(term)?,
+            None => term,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_diagonal_fisher() {
+        let dev: TestDevice = Default::default();
+        let model: Linear<2, 1, _> = dev.build_module();
+        let xs: [Tensor<Rank1<2>, f32, _>; 3] = [
+            dev.tensor([1.0, 2.0]),
+            dev.tensor([-1.0, 0.5]),
+            dev.tensor([0.0, 3.0]),
+        ];
+        let grads = || xs.iter().map(|x| model.forward(x.trace()).sum().backward());
+
+        // the gradient of w x + b is x for the weight, and 1 for the bias
+        let fisher = diagonal_fisher(&model, grads());
+        assert_close(
+            &fisher.weight.array(),
+            &[[2.0 / 3.0, (4.0 + 0.25 + 9.0) / 3.0]],
+        );
+        assert_eq!(fisher.bias.array(), [1.0]);
+        assert_eq!(fisher.weight.id, model.weight.id);
+
+        let fisher = diagonal_fisher(&model, grads().take(0));
+        assert_eq!(fisher.weight.array(), [[0.0; 2]]);
+    }
+
+    #[test]
+    fn test_per_sample_diagonal_fisher() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Linear<3, 2, _>, Tanh, Linear<2, 1, _>) = dev.build_module();
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+
+        let mut per_sample = Default::default();
+        let y = model.forward_per_sample(x.trace(), &mut per_sample);
+        let grads = y.square().sum().backward();
+        let fisher = per_sample_diagonal_fisher(&model, &per_sample, &grads);
+
+        let xs = x.as_vec();
+        let expected = diagonal_fisher(
+            &model,
+            xs.chunks(3).map(|x| {
+                let x: Tensor<Rank1<3>, f32, _> = dev.tensor([x[0], x[1], x[2]]);
+                model.forward(x.trace()).square().sum().backward()
+            }),
+        );
+        assert_close(&fisher.0.weight.array(), &expected.0.weight.array());
+        assert_close(&fisher.0.bias.array(), &expected.0.bias.array());
+        assert_close(&fisher.2.weight.array(), &expected.2.weight.array());
+        assert_close(&fisher.2.bias.array(), &expected.2.bias.array());
+    }
+
+    #[test]
+    fn test_ewc_penalty() {
+        let dev: TestDevice = Default::default();
+        let mut model: Linear<2, 1, _> = dev.build_module();
+        model.weight = dev.tensor([[1.0, 2.0]]);
+        model.bias = dev.tensor([0.5]);
+        let fisher = Linear {
+            weight: dev.tensor([[2.0, 0.0]]),
+            bias: dev.tensor([1.0]),
+        };
+        let ewc = Ewc::new(&model, fisher, 3.0);
+        let penalty = ewc.penalty(&model);
+        assert_eq!(penalty.array(), 0.0);
+        let g = penalty.backward();
+        assert_eq!(g.get(&model.weight).array(), [[0.0; 2]]);
+
+        model.weight = dev.tensor([[2.0, -1.0]]);
+        model.bias = dev.tensor([0.0]);
+        let penalty = ewc.penalty(&model);
+        // 3 / 2 * (2 * 1^2 + 0 * 3^2 + 1 * 0.5^2)
+        assert_close(&penalty.array(), &(1.5 * 2.25));
+        let g = penalty.backward();
+        // lambda * fisher * (params - anchor)
+        assert_close(&g.get(&model.weight).array(), &[[6.0, 0.0]]);
+        assert_close(&g.get(&model.bias).array(), &[-1.5]);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    gradients::Gradients,
+    shapes::{Axis, HasShape, Shape},
+    tensor::Tensor,
+    tensor_ops::{BroadcastTo, Device, MeanTo, ReshapeTo, TrySub},
+};
+
+use super::{GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors};
+
+/// Gradient centralization from
+/// [Gradient Centralization: A New Optimization Technique for Deep Neural Networks](https://arxiv.org/abs/2004.01461).
+///
+/// Wraps any other optimizer, and before each update subtracts the mean from the gradient of
+/// every parameter with 2 or more dimensions. The mean is taken over all but the first
+/// dimension, so each output unit of a [crate::nn::Linear] or [crate::nn::Conv2D] has a
+/// zero mean gradient. Biases and other 1d parameters are left alone.
+///
+/// Wrappers compose, so gradient centralization can be combined with [super::Lookahead]:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Linear<2, 1>;
+/// let mut opt: Lookahead<Model, GradientCentralization<Adam<Model>>> =
+///     Lookahead::new(GradientCentralization::new(Default::default()), Default::default());
+/// ```
+#[derive(Debug)]
+pub struct GradientCentralization<O> {
+    /// The inner optimizer
+    pub opt: O,
+}
+
+impl<O> GradientCentralization<O> {
+    /// Wraps `opt`
+    pub fn new(opt: O) -> Self {
+        Self { opt }
+    }
+}
+
+impl<M, O, D> Optimizer<M, D, f32> for GradientCentralization<O>
+where
+    M: GradientUpdate<D, f32>,
+    O: Optimizer<M, D, f32>,
+    D: Device<f32>,
+{
+    fn update(
+        &mut self,
+        module: &mut M,
+        mut gradients: Gradients<D>,
+    ) -> Result<(), OptimizerUpdateError<D>> {
+        let mut centralize = Centralize(&mut gradients);
+        module
+            .update(&mut centralize, &mut Default::default())
+            .map_err(OptimizerUpdateError::DeviceError)?;
+        self.opt.update(module, gradients)
+    }
+}
+
+/// Centralizes the gradient of each parameter it visits. Parameters without a gradient are
+/// left for the inner optimizer to report.
+struct Centralize<'a, D: Device<f32>>(&'a mut Gradients<D>);
+
+impl<'a, D: Device<f32>> ParamUpdater<D, f32> for Centralize<'a, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        if S::NUM_DIMS < 2 {
+            return Ok(());
+        }
+        if let Some(g) = self.0.remove(p) {
+            let shape = *p.shape();
+            let rows = shape.concrete()[0];
+            let cols = shape.num_elements() / rows.max(1);
+            let g = p.device.upgrade(g).try_reshape_like(&(rows, cols))?;
+            let mean = g.clone().try_mean::<_, Axis<1>>()?;
+            let g = g.try_sub(mean.try_broadcast_like::<_, Axis<1>>(&(rows, cols))?)?;
+            let g = g.try_reshape_like(&shape)?;
+            self.0.insert(p, g.storage);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        optim::{Sgd, SgdConfig},
+        shapes::*,
+        tensor::*,
+        tensor_ops::*,
+        tests::*,
+    };
+
+    #[test]
+    fn test_gradient_centralization() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Tensor<Rank2<2, 3>, f32, _>, Tensor<Rank1<3>, f32, _>) =
+            (dev.zeros(), dev.zeros());
+        let mut opt: GradientCentralization<Sgd<_, _>> =
+            GradientCentralization::new(Sgd::new(SgdConfig {
+                lr: 1.0,
+                momentum: None,
+                weight_decay: None,
+            }));
+
+        let w_coef = dev.tensor([[1.0, 2.0, 6.0], [0.0, -1.0, -2.0]]);
+        let b_coef = dev.tensor([1.0, 2.0, 3.0]);
+        let w = model.0.trace();
+        let (loss, tape) = (w * w_coef).sum().split_tape();
+        let b = model.1.clone().put_tape(tape);
+        let loss = (b * b_coef).sum() + loss;
+        let g = loss.backward();
+        opt.update(&mut model, g).expect("");
+        assert_close(&model.0.array(), &[[2.0, 1.0, -3.0], [-1.0, 0.0, 1.0]]);
+
+        // 1d parameters are not centralized
+        assert_eq!(model.1.array(), [-1.0, -2.0, -3.0]);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{gradients::Gradients, rl::try_polyak_update, tensor_ops::Device};
+
+use super::{GradientUpdate, Optimizer, OptimizerUpdateError};
+
+/// Configuration of hyperparameters for [Lookahead].
+#[derive(Debug, Clone, Copy)]
+pub struct LookaheadConfig {
+    /// The number of steps of the inner optimizer between synchronizations. Defaults to `5`.
+    pub k: usize,
+
+    /// How far the slow weights move towards the fast weights at each
+    /// synchronization. Defaults to `0.5`.
+    pub alpha: f32,
+}
+
+impl Default for LookaheadConfig {
+    fn default() -> Self {
+        Self { k: 5, alpha: 0.5 }
+    }
+}
+
+/// Lookahead from [Lookahead Optimizer: k steps forward, 1 step back](https://arxiv.org/abs/1907.08610).
+///
+/// Wraps any other optimizer, which updates the model (the "fast" weights) as usual. Every
+/// [LookaheadConfig::k] updates, a copy of the "slow" weights moves towards the fast weights by
+/// [LookaheadConfig::alpha], and the model is reset to the slow weights. The slow weights
+/// start as a copy of the model at the first update.
+///
+/// The model's tensors keep their ids when they are reset, so the state of the inner
+/// optimizer (like [super::Adam]'s moments) carries over.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # type Model = Linear<2, 1>;
+/// let mut opt: Lookahead<Model, Adam<Model>> = Lookahead::new(
+///     Default::default(),
+///     LookaheadConfig { k: 6, alpha: 0.5 },
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Lookahead<M, O> {
+    /// The inner optimizer
+    pub opt: O,
+
+    /// Hyperparameter configuration
+    pub cfg: LookaheadConfig,
+
+    slow: Option<M>,
+    /// Inner updates since the last synchronization
+    step: usize,
+}
+
+impl<M, O> Lookahead<M, O> {
+    /// Wraps `opt` with hyperparameters from `cfg`
+    pub fn new(opt: O, cfg: LookaheadConfig) -> Self {
+        Self {
+            opt,
+            cfg,
+            slow: None,
+            step: 0,
+        }
+    }
+}
+
+impl<M, O, D> Optimizer<M, D, f32> for Lookahead<M, O>
+where
+    M: GradientUpdate<D, f32> + Clone,
+    O: Optimizer<M, D, f32>,
+    D: Device<f32>,
+{
+    fn update(
+        &mut self,
+        module: &mut M,
+        gradients: Gradients<D>,
+    ) -> Result<(), OptimizerUpdateError<D>> {
+        let slow = self.slow.get_or_insert_with(|| module.clone());
+        self.opt.update(module, gradients)?;
+        self.step += 1;
+        if self.step >= self.cfg.k {
+            self.step = 0;
+            sync(slow, module, self.cfg.alpha).map_err(OptimizerUpdateError::DeviceError)?;
+        }
+        Ok(())
+    }
+}
+
+/// `slow += alpha * (fast - slow)`, followed by `fast = slow`
+fn sync<M, D: Device<f32>>(slow: &mut M, fast: &mut M, alpha: f32) -> Result<(), D::Err>
+where
+    M: GradientUpdate<D, f32> + Clone,
+{
+    try_polyak_update(slow, fast, alpha)?;
+    try_polyak_update(fast, slow, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        optim::{Sgd, SgdConfig},
+        shapes::*,
+        tensor::*,
+        tensor_ops::*,
+        tests::*,
+    };
+
+    #[test]
+    fn test_lookahead_syncs_every_k_steps() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<2>, f32, _> = dev.tensor([1.0, -2.0]);
+        let id = t.id;
+        let mut opt: Lookahead<_, Sgd<_, _>> = Lookahead::new(
+            Sgd::new(SgdConfig {
+                lr: 1.0,
+                momentum: None,
+                weight_decay: None,
+            }),
+            LookaheadConfig { k: 2, alpha: 0.5 },
+        );
+
+        // the gradient of sum() is 1.0, so each inner step subtracts 1.0
+        let g = t.trace().sum().backward();
+        opt.update(&mut t, g).expect("");
+        assert_eq!(t.array(), [0.0, -3.0]);
+
+        let g = t.trace().sum().backward();
+        opt.update(&mut t, g).expect("");
+        // fast is [-1, -4], slow moves half way from [1, -2]
+        assert_eq!(t.array(), [0.0, -3.0]);
+        assert_eq!(t.id, id);
+
+        let g = t.trace().sum().backward();
+        opt.update(&mut t, g).expect("");
+        assert_eq!(t.array(), [-1.0, -4.0]);
+        let g = t.trace().sum().backward();
+        opt.update(&mut t, g).expect("");
+        // fast is [-2, -5], slow is [0, -3]
+        assert_eq!(t.array(), [-1.0, -4.0]);
+    }
+
+    #[test]
+    fn test_lookahead_alpha_one_is_inner_optimizer() {
+        let dev: TestDevice = Default::default();
+        let mut a: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let mut b = a.clone();
+        let mut opt_a: Sgd<_, _> = Default::default();
+        let mut opt_b: Lookahead<_, Sgd<_, _>> =
+            Lookahead::new(Default::default(), LookaheadConfig { k: 2, alpha: 1.0 });
+        for _ in 0..5 {
+            let g = a.trace().square().sum().backward();
+            opt_a.update(&mut a, g).expect("");
+            let g = b.trace().square().sum().backward();
+            opt_b.update(&mut b, g).expect("");
+        }
+        assert_close(&a.array(), &b.array());
+    }
+}
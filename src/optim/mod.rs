@@ -1,4 +1,4 @@
-//! Optimizers such as [Sgd], [Adam], and [RMSprop] that can optimize neural networks.
+//! Optimizers such as [Sgd], [Adam], [RMSprop], [Adagrad], [Adadelta], and [Adafactor] that can optimize neural networks.
 //!
 //! # Initializing
 //!
@@ -7,6 +7,9 @@
 //! - [Sgd::new()] with [SgdConfig]
 //! - [Adam::new()] with [AdamConfig]
 //! - [RMSprop::new()] with [RMSpropConfig]
+//! - [Adagrad::new()] with [AdagradConfig]
+//! - [Adadelta::new()] with [AdadeltaConfig]
+//! - [Adafactor::new()] with [AdafactorConfig]
 //!
 //! # Updating network parameters
 //!
@@ -26,17 +29,56 @@
 //! let gradients: Gradients<Cpu> = loss.backward();
 //! opt.update(&mut model, gradients);
 //! ```
+//!
+//! # Weight averaging
+//!
+//! [Swa] keeps an average of the model's weights over the tail of training, and [update_bn()]
+//! recomputes batch norm statistics for the averaged weights.
+//!
+//! # Continual learning
+//!
+//! [Ewc] penalizes forgetting previous tasks, using the Fisher information from
+//! [diagonal_fisher()].
+//!
+//! # Differential privacy
+//!
+//! [DpSgd] wraps another optimizer to train with differential privacy, using the per-sample
+//! gradients from [crate::nn::PerSampleModule], and tracks the privacy budget that is spent.
+//!
+//! # Wrapping optimizers
+//!
+//! [Lookahead] and [GradientCentralization] wrap another optimizer, and can be nested:
+//! `Lookahead<M, GradientCentralization<Adam<M>>>`.
 
+mod adadelta;
+mod adafactor;
+mod adagrad;
 mod adam;
+mod dp_sgd;
+mod ewc;
+mod grad_centralization;
+mod lookahead;
 mod optimizer;
 mod rmsprop;
 mod sgd;
+mod swa;
 
+pub use adadelta::{Adadelta, AdadeltaConfig};
+pub use adafactor::{Adafactor, AdafactorConfig};
+pub use adagrad::{Adagrad, AdagradConfig};
 pub use adam::{Adam, AdamConfig};
+pub use dp_sgd::{DpSgd, DpSgdConfig};
+pub use ewc::{
+    diagonal_fisher, per_sample_diagonal_fisher, try_diagonal_fisher,
+    try_per_sample_diagonal_fisher, Ewc,
+};
+pub use grad_centralization::GradientCentralization;
+pub use lookahead::{Lookahead, LookaheadConfig};
 pub use optimizer::{GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors};
 pub use optimizer::{Momentum, WeightDecay};
 pub use rmsprop::{RMSprop, RMSpropConfig};
 pub use sgd::{Sgd, SgdConfig};
+pub use swa::{try_update_bn, update_bn, Swa};
 
 pub mod prelude {
     pub use super::{GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors};
@@ -103,6 +103,35 @@ pub trait ParamUpdater<D: DeviceStorage, E: Dtype> {
         p: &mut Tensor<S, E, D>,
         unused: &mut UnusedTensors,
     ) -> Result<(), D::Err>;
+
+    /// Visits the running statistics of a batch norm layer. These aren't parameters,
+    /// so the default implementation does nothing. See [crate::optim::update_bn()].
+    fn update_running_stats<S: Shape>(
+        &mut self,
+        _mean: &mut Tensor<S, E, D>,
+        _var: &mut Tensor<S, E, D>,
+        _momentum: &mut E,
+    ) -> Result<(), D::Err> {
+        Ok(())
+    }
+
+    /// Visits statistics that are accumulated over every seen sample, like the ones of
+    /// [crate::nn::RunningNorm1D]. These aren't parameters either, so the default
+    /// implementation does nothing.
+    fn update_accumulated_stats<S: Shape>(
+        &mut self,
+        _mean: &mut Tensor<S, E, D>,
+        _var: &mut Tensor<S, E, D>,
+        _count: &mut u64,
+    ) -> Result<(), D::Err> {
+        Ok(())
+    }
+
+    /// Visits other state of a module that isn't trained, like the masks of
+    /// [crate::nn::Pruned]. The default implementation does nothing.
+    fn update_buffer<S: Shape>(&mut self, _buffer: &mut Tensor<S, E, D>) -> Result<(), D::Err> {
+        Ok(())
+    }
 }
 
 /// Holds [UniqueId] of tensors that were missing gradients during
@@ -2,6 +2,8 @@ use crate::{
     optim::WeightDecay,
     tensor::cpu::{Cpu, StridedArray},
 };
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use super::{RMSpropConfig, RMSpropKernel};
 
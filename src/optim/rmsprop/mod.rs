@@ -196,7 +196,6 @@ mod tests {
         for e in expected.iter() {
             let gradients = (t.trace() * rate.clone()).square().sum().backward();
             opt.update(&mut t, gradients).expect("");
-            std::println!("{:?}", t.array());
             assert_close(&t.array(), e);
         }
     }
@@ -0,0 +1,206 @@
+use crate::{
+    nn::ModuleMut,
+    optim::{GradientUpdate, ParamUpdater, UnusedTensors},
+    rl::try_polyak_update,
+    shapes::Shape,
+    tensor::Tensor,
+    tensor_ops::Device,
+};
+use std::vec::Vec;
+
+/// Stochastic weight averaging from
+/// [Averaging Weights Leads to Wider Optima and Better Generalization](https://arxiv.org/abs/1803.05407).
+///
+/// Keeps an equally weighted running average of the parameters of a model over the tail of
+/// training. Call [Swa::update()] with the model being trained at the end of every epoch (or
+/// every few steps) once averaging should start, and then use [Swa::model] for evaluation.
+///
+/// Only parameters are averaged. Since the running statistics of batch norm layers don't match
+/// the averaged weights, recompute them with [update_bn()] before using the averaged model.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<2, 4>, ReLU, Linear<4, 1>);
+/// let mut model: Model = dev.build_module();
+/// let mut swa = Swa::new(&model);
+/// for epoch in 0..10 {
+///     // -- snip training --
+///     if epoch >= 7 {
+///         swa.update(&model);
+///     }
+/// }
+/// assert_eq!(swa.num_averaged, 3);
+/// let averaged: &Model = &swa.model;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Swa<M> {
+    /// The averaged model.
+    pub model: M,
+
+    /// The number of models that have been averaged into [Swa::model].
+    pub num_averaged: usize,
+}
+
+impl<M: Clone> Swa<M> {
+    /// Starts with a copy of `model`. The copy is overwritten by the first [Swa::update()].
+    pub fn new(model: &M) -> Self {
+        Self {
+            model: model.clone(),
+            num_averaged: 0,
+        }
+    }
+
+    /// Adds the parameters of `model` to the average:
+    /// `avg = avg + (model - avg) / (num_averaged + 1)`
+    pub fn update<D: Device<f32>>(&mut self, model: &M)
+    where
+        M: GradientUpdate<D, f32>,
+    {
+        self.try_update(model).unwrap()
+    }
+
+    /// Fallible version of [Swa::update()]
+    pub fn try_update<D: Device<f32>>(&mut self, model: &M) -> Result<(), D::Err>
+    where
+        M: GradientUpdate<D, f32>,
+    {
+        let tau = 1.0 / (self.num_averaged + 1) as f32;
+        try_polyak_update(&mut self.model, model, tau)?;
+        self.num_averaged += 1;
+        Ok(())
+    }
+}
+
+/// Recomputes the running statistics of every batch norm layer in `model` as the equally
+/// weighted average of the statistics of `batches`, which is what [Swa] needs after averaging.
+///
+/// The inputs must be traced, so the layers run their training forward. The momentum of each
+/// layer is restored afterwards.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// let mut bn: BatchNorm2D<3> = dev.build_module();
+/// let batches: [Tensor<Rank4<2, 3, 4, 4>>; 2] = [dev.sample_normal(), dev.sample_normal()];
+/// update_bn(&mut bn, batches.iter().map(|x| x.trace()));
+/// assert_eq!(bn.momentum, 0.1);
+/// ```
+pub fn update_bn<M, I, D: Device<f32>>(model: &mut M, batches: I)
+where
+    M: GradientUpdate<D, f32> + ModuleMut<I::Item>,
+    I: IntoIterator,
+{
+    try_update_bn(model, batches).unwrap()
+}
+
+/// Fallible version of [update_bn()]
+pub fn try_update_bn<M, I, D: Device<f32>>(model: &mut M, batches: I) -> Result<(), D::Err>
+where
+    M: GradientUpdate<D, f32> + ModuleMut<I::Item>,
+    I: IntoIterator,
+{
+    let mut unused = Default::default();
+    let mut reset = BnStats::Reset(Vec::new());
+    model.update(&mut reset, &mut unused)?;
+    for (i, batch) in batches.into_iter().enumerate() {
+        model.update(&mut BnStats::Momentum(1.0 / (i + 1) as f32), &mut unused)?;
+        model.forward_mut(batch);
+    }
+    if let BnStats::Reset(momenta) = reset {
+        model.update(&mut BnStats::Restore(momenta.into_iter()), &mut unused)?;
+    }
+    Ok(())
+}
+
+/// Visits the running statistics of batch norm layers for [try_update_bn()].
+enum BnStats {
+    /// Resets the statistics, and saves the momentum of each layer.
+    Reset(Vec<f32>),
+    /// Sets the momentum of each layer.
+    Momentum(f32),
+    /// Restores the saved momenta.
+    Restore(std::vec::IntoIter<f32>),
+}
+
+impl<D: Device<f32>> ParamUpdater<D, f32> for BnStats {
+    fn update_param<S: Shape>(
+        &mut self,
+        _: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        Ok(())
+    }
+
+    fn update_running_stats<S: Shape>(
+        &mut self,
+        mean: &mut Tensor<S, f32, D>,
+        var: &mut Tensor<S, f32, D>,
+        momentum: &mut f32,
+    ) -> Result<(), D::Err> {
+        match self {
+            Self::Reset(momenta) => {
+                momenta.push(*momentum);
+                mean.try_fill_with_zeros()?;
+                var.try_fill_with_ones()?;
+            }
+            Self::Momentum(m) => *momentum = *m,
+            Self::Restore(momenta) => *momentum = momenta.next().unwrap(),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, shapes::*, tensor::*, tests::*};
+
+    #[test]
+    fn test_swa_averages_models() {
+        let dev: TestDevice = Default::default();
+        let models: [Linear<2, 3, _>; 3] =
+            [dev.build_module(), dev.build_module(), dev.build_module()];
+        let mut swa = Swa::new(&models[1]);
+        for m in models.iter() {
+            swa.update(m);
+        }
+        assert_eq!(swa.num_averaged, 3);
+
+        let expected =
+            (models[0].weight.clone() + models[1].weight.clone() + models[2].weight.clone()) / 3.0;
+        assert_close(&swa.model.weight.array(), &expected.array());
+        let expected =
+            (models[0].bias.clone() + models[1].bias.clone() + models[2].bias.clone()) / 3.0;
+        assert_close(&swa.model.bias.array(), &expected.array());
+    }
+
+    #[test]
+    fn test_update_bn_recomputes_stats() {
+        let dev: TestDevice = Default::default();
+        let mut model: (BatchNorm2D<2, _>, ReLU) = dev.build_module();
+        model.0.momentum = 0.3;
+        let x1: Tensor<Rank4<2, 2, 3, 3>, f32, _> = dev.sample_normal();
+        let x2: Tensor<Rank4<2, 2, 3, 3>, f32, _> = dev.sample_normal() + 1.0;
+
+        // stats from a single batch with momentum 1.0
+        let mut bn1: BatchNorm2D<2, _> = dev.build_module();
+        bn1.momentum = 1.0;
+        let _ = bn1.forward_mut(x1.trace());
+        let mut bn2: BatchNorm2D<2, _> = dev.build_module();
+        bn2.momentum = 1.0;
+        let _ = bn2.forward_mut(x2.trace());
+
+        // the stale stats are thrown away
+        let _ = model.forward_mut(x2.trace());
+        update_bn(&mut model, [x1.trace(), x2.trace()]);
+
+        let expected = (bn1.running_mean.clone() + bn2.running_mean.clone()) / 2.0;
+        assert_close(&model.0.running_mean.array(), &expected.array());
+        let expected = (bn1.running_var.clone() + bn2.running_var.clone()) / 2.0;
+        assert_close(&model.0.running_var.array(), &expected.array());
+        assert_eq!(model.0.momentum, 0.3);
+    }
+}
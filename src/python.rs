@@ -0,0 +1,421 @@
+//! Python bindings with [PyO3](https://pyo3.rs), enabled by the "python" feature.
+//!
+//! [PyTensor] is exposed to python as `Tensor`. It stores `f32` data on the [Cpu] with
+//! a shape that is only known at runtime. Conversions with numpy:
+//! - `Tensor.numpy()` returns a read-only numpy view of the tensor's buffer, without copying
+//! - `Tensor.from_numpy(array)` copies the array, since its memory is owned by python
+//!
+//! Any dfdx model can be driven from python by wrapping it in a [PyModel], which converts
+//! the input to the shape the model expects. Register both classes in your own extension
+//! module with [register()]:
+//!
+//! ```ignore
+//! use dfdx::{prelude::*, python::PyModel};
+//! use pyo3::prelude::*;
+//!
+//! #[pyfunction]
+//! fn mlp() -> PyModel {
+//!     let dev: Cpu = Default::default();
+//!     let model = dev.build_module::<(Linear<4, 8>, ReLU, Linear<8, 2>), f32>();
+//!     PyModel::new::<Rank1<4>, _, _>(model)
+//! }
+//!
+//! #[pymodule]
+//! fn my_models(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     dfdx::python::register(m)?;
+//!     m.add_function(wrap_pyfunction!(mlp, m)?)
+//! }
+//! ```
+//!
+//! Then from python:
+//! ```python
+//! import numpy as np, my_models
+//! model = my_models.mlp()
+//! y = model(my_models.Tensor.from_numpy(np.ones(4, dtype=np.float32)))
+//! print(y.relu().numpy(), [p.shape for p in model.parameters()])
+//! ```
+
+use crate::{
+    nn::Module,
+    optim::{GradientUpdate, ParamUpdater, UnusedTensors},
+    shapes::{Axis, HasShape, Shape},
+    tensor::{
+        cpu::ndarray::shape_from_dims, AsArray, AsVec, Cpu, DeviceStorage, OnesTensor,
+        SampleTensor, StridedArray, Tensor, TensorFromVec, ZerosTensor,
+    },
+    tensor_ops::*,
+};
+
+use ::ndarray::{ArrayD, ArrayViewD, IxDyn};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use rust_numpy::{
+    npyffi::flags::NPY_ARRAY_WRITEABLE, PyArrayDyn, PyReadonlyArrayDyn, PyUntypedArrayMethods,
+};
+use std::{boxed::Box, format, marker::PhantomData, string::String, vec::Vec};
+
+/// A tensor with a runtime shape, exposed to python as `Tensor`.
+///
+/// The data is stored flat and contiguous, so reshaping and converting to
+/// and from typed tensors share the buffer instead of copying it.
+#[pyclass(name = "Tensor", module = "dfdx", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyTensor {
+    data: Tensor<(usize,), f32, Cpu>,
+    shape: Vec<usize>,
+}
+
+impl<S: Shape> From<Tensor<S, f32, Cpu>> for PyTensor {
+    fn from(t: Tensor<S, f32, Cpu>) -> Self {
+        let shape: Vec<usize> = t.shape().concrete().into();
+        let numel = t.shape().num_elements();
+        let dev = t.device.clone();
+        let array: ArrayD<f32> = t.into();
+        Self {
+            data: dev.tensor_from_vec(array.into_raw_vec(), (numel,)),
+            shape,
+        }
+    }
+}
+
+impl PyTensor {
+    /// Converts to a typed tensor without copying, or `None` if the shape doesn't fit `S`.
+    pub fn to_tensor<S: Shape>(&self) -> Option<Tensor<S, f32, Cpu>> {
+        shape_from_dims(&self.shape).map(|shape| self.with_shape(shape))
+    }
+
+    fn with_shape<S: Shape>(&self, shape: S) -> Tensor<S, f32, Cpu> {
+        let storage = StridedArray {
+            data: self.data.storage.data.clone(),
+            shape,
+            strides: shape.strides(),
+        };
+        self.data.device.upgrade(storage)
+    }
+
+    fn map(
+        &self,
+        f: impl FnOnce(Tensor<(usize,), f32, Cpu>) -> Tensor<(usize,), f32, Cpu>,
+    ) -> Self {
+        Self {
+            data: f(self.data.clone()),
+            shape: self.shape.clone(),
+        }
+    }
+
+    fn zip(
+        &self,
+        other: &Bound<'_, PyAny>,
+        f: impl FnOnce(
+            Tensor<(usize,), f32, Cpu>,
+            Tensor<(usize,), f32, Cpu>,
+        ) -> Tensor<(usize,), f32, Cpu>,
+    ) -> PyResult<Self> {
+        let rhs = match other.extract::<PyRef<'_, Self>>() {
+            Ok(rhs) if rhs.shape == self.shape => rhs.data.clone(),
+            Ok(rhs) => {
+                return Err(PyValueError::new_err(format!(
+                    "Shape mismatch: {:?} and {:?}",
+                    self.shape, rhs.shape
+                )))
+            }
+            Err(_) => {
+                let scalar: f32 = other.extract()?;
+                self.data.device.ones_like(&self.data) * scalar
+            }
+        };
+        Ok(self.map(|lhs| f(lhs, rhs)))
+    }
+}
+
+#[pymethods]
+impl PyTensor {
+    /// Copies a numpy array of float32 into a new tensor.
+    #[staticmethod]
+    fn from_numpy(array: PyReadonlyArrayDyn<'_, f32>) -> Self {
+        let array = array.as_array();
+        let shape = array.shape().to_vec();
+        let numel = array.len();
+        let data = Cpu::default().tensor_from_vec(array.iter().copied().collect(), (numel,));
+        Self { data, shape }
+    }
+
+    #[staticmethod]
+    fn zeros(shape: Vec<usize>) -> Self {
+        let numel = shape.iter().product();
+        let data = Cpu::default().zeros_like(&(numel,));
+        Self { data, shape }
+    }
+
+    #[staticmethod]
+    fn ones(shape: Vec<usize>) -> Self {
+        let numel = shape.iter().product();
+        let data = Cpu::default().ones_like(&(numel,));
+        Self { data, shape }
+    }
+
+    /// Samples from a standard normal distribution.
+    #[staticmethod]
+    #[pyo3(signature = (shape, seed = 0))]
+    fn randn(shape: Vec<usize>, seed: u64) -> Self {
+        let numel = shape.iter().product();
+        let data = Cpu::seed_from_u64(seed).sample_like(&(numel,), rand_distr::StandardNormal);
+        Self { data, shape }
+    }
+
+    /// A read-only numpy view of this tensor's buffer. The view keeps the tensor alive.
+    fn numpy<'py>(slf: &Bound<'py, Self>) -> Bound<'py, PyArrayDyn<f32>> {
+        let t = slf.get();
+        let view = ArrayViewD::from_shape(IxDyn(&t.shape), t.data.storage.data()).unwrap();
+        // SAFETY: the tensor is frozen, so its buffer is never written to and lives
+        // as long as `slf`, which the array holds on to.
+        unsafe {
+            let array = PyArrayDyn::borrow_from_array(&view, slf.clone().into_any());
+            (*array.as_array_ptr()).flags &= !NPY_ARRAY_WRITEABLE;
+            array
+        }
+    }
+
+    #[getter]
+    fn shape(&self) -> Vec<usize> {
+        self.shape.clone()
+    }
+
+    fn reshape(&self, shape: Vec<usize>) -> PyResult<Self> {
+        if shape.iter().product::<usize>() != self.data.shape().0 {
+            return Err(PyValueError::new_err(format!(
+                "Can't reshape {:?} to {shape:?}",
+                self.shape
+            )));
+        }
+        Ok(Self {
+            data: self.data.clone(),
+            shape,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Tensor(shape={:?}, data={:?})",
+            self.shape,
+            self.data.as_vec()
+        )
+    }
+
+    fn __add__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        self.zip(other, |a, b| a + b)
+    }
+
+    fn __sub__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        self.zip(other, |a, b| a - b)
+    }
+
+    fn __mul__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        self.zip(other, |a, b| a * b)
+    }
+
+    fn __truediv__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        self.zip(other, |a, b| a / b)
+    }
+
+    fn __neg__(&self) -> Self {
+        self.map(|t| -t)
+    }
+
+    fn relu(&self) -> Self {
+        self.map(|t| t.relu())
+    }
+
+    fn sigmoid(&self) -> Self {
+        self.map(|t| t.sigmoid())
+    }
+
+    fn tanh(&self) -> Self {
+        self.map(|t| t.tanh())
+    }
+
+    fn exp(&self) -> Self {
+        self.map(|t| t.exp())
+    }
+
+    fn ln(&self) -> Self {
+        self.map(|t| t.ln())
+    }
+
+    fn sqrt(&self) -> Self {
+        self.map(|t| t.sqrt())
+    }
+
+    fn abs(&self) -> Self {
+        self.map(|t| t.abs())
+    }
+
+    fn square(&self) -> Self {
+        self.map(|t| t.square())
+    }
+
+    /// Softmax over the last axis.
+    fn softmax(&self) -> Self {
+        let cols = self.shape.last().copied().unwrap_or(1);
+        let rows = self.data.shape().0.checked_div(cols).unwrap_or(0);
+        let t: Tensor<(usize, usize), f32, Cpu> = self.with_shape((rows, cols));
+        Self {
+            shape: self.shape.clone(),
+            ..t.softmax::<Axis<1>>().into()
+        }
+    }
+
+    fn sum(&self) -> f32 {
+        self.data.clone().sum::<(), _>().array()
+    }
+
+    fn mean(&self) -> f32 {
+        self.data.clone().mean::<(), _>().array()
+    }
+
+    fn max(&self) -> f32 {
+        self.data.clone().max::<(), _>().array()
+    }
+
+    fn min(&self) -> f32 {
+        self.data.clone().min::<(), _>().array()
+    }
+}
+
+/// Type erased forward & parameter access for [PyModel].
+trait PyForward: Send + Sync {
+    fn forward(&self, x: &PyTensor) -> PyResult<PyTensor>;
+    fn parameters(&mut self) -> Vec<PyTensor>;
+}
+
+struct Wrapped<S, M>(M, PhantomData<S>);
+
+/// Collects a copy of every parameter it visits, in order.
+struct CollectParams(Vec<PyTensor>);
+
+impl ParamUpdater<Cpu, f32> for CollectParams {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, Cpu>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), <Cpu as crate::tensor::HasErr>::Err> {
+        self.0.push(p.clone().into());
+        Ok(())
+    }
+}
+
+impl<S: Shape, O: Shape, M> PyForward for Wrapped<S, M>
+where
+    M: Module<Tensor<S, f32, Cpu>, Output = Tensor<O, f32, Cpu>> + GradientUpdate<Cpu, f32>,
+    M: Send + Sync,
+{
+    fn forward(&self, x: &PyTensor) -> PyResult<PyTensor> {
+        let x = x.to_tensor::<S>().ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Input of shape {:?} doesn't fit the model",
+                x.shape
+            ))
+        })?;
+        Ok(self.0.forward(x).into())
+    }
+
+    fn parameters(&mut self) -> Vec<PyTensor> {
+        let mut collector = CollectParams(Vec::new());
+        self.0
+            .update(&mut collector, &mut Default::default())
+            .unwrap();
+        collector.0
+    }
+}
+
+/// A dfdx model that can be called from python, exposed as `Model`.
+#[pyclass(name = "Model", module = "dfdx")]
+pub struct PyModel(Box<dyn PyForward>);
+
+impl PyModel {
+    /// Wraps `model`, which is called with tensors of shape `S` and returns tensors of shape `O`.
+    pub fn new<S: Shape, O: Shape, M>(model: M) -> Self
+    where
+        M: Module<Tensor<S, f32, Cpu>, Output = Tensor<O, f32, Cpu>>,
+        M: GradientUpdate<Cpu, f32> + Send + Sync + 'static,
+    {
+        Self(Box::new(Wrapped(model, PhantomData)))
+    }
+}
+
+#[pymethods]
+impl PyModel {
+    fn __call__(&self, x: PyRef<'_, PyTensor>) -> PyResult<PyTensor> {
+        self.0.forward(&x)
+    }
+
+    /// Copies of every parameter of the model, in the order they are visited by optimizers.
+    fn parameters(&mut self) -> Vec<PyTensor> {
+        self.0.parameters()
+    }
+}
+
+/// Adds the `Tensor` and `Model` classes to a python module.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyTensor>()?;
+    m.add_class::<PyModel>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, shapes::*, tensor::*, tests::assert_close};
+
+    #[test]
+    fn test_py_tensor_conversions() {
+        let dev: Cpu = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let py: PyTensor = t.clone().into();
+        assert_eq!(py.shape(), [2, 3]);
+        assert_eq!(py.to_tensor::<Rank2<2, 3>>().unwrap().array(), t.array());
+        assert!(py.to_tensor::<Rank2<3, 2>>().is_none());
+
+        let py = py.reshape(std::vec![3, 2]).unwrap();
+        let r: Tensor<(usize, Const<2>)> = py.to_tensor().unwrap();
+        assert_eq!(r.as_vec(), t.as_vec());
+        assert!(py.reshape(std::vec![4]).is_err());
+
+        let p: PyTensor = t.permute::<Rank2<3, 2>, _>().into();
+        assert_eq!(
+            p.to_tensor::<Rank2<3, 2>>().unwrap().array(),
+            [[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]
+        );
+    }
+
+    #[test]
+    fn test_py_tensor_ops() {
+        let dev: Cpu = Default::default();
+        let py: PyTensor = dev.tensor([[-1.0, 2.0], [3.0, -4.0]]).into();
+        assert_eq!(py.relu().data.as_vec(), [0.0, 2.0, 3.0, 0.0]);
+        assert_eq!(py.__neg__().data.as_vec(), [1.0, -2.0, -3.0, 4.0]);
+        assert_eq!(py.sum(), 0.0);
+        assert_eq!(py.max(), 3.0);
+        let s = py.softmax();
+        assert_eq!(s.shape(), [2, 2]);
+        assert_close(
+            &s.to_tensor::<Rank2<2, 2>>().unwrap().array(),
+            &[[0.047425874, 0.95257413], [0.99908894, 0.0009110512]],
+        );
+    }
+
+    #[test]
+    fn test_py_model() {
+        let dev: Cpu = Default::default();
+        let linear: Linear<2, 3> = dev.build_module();
+        let x = dev.tensor([1.0, 2.0]);
+        let y = linear.forward(x.clone());
+        let mut model = PyModel::new::<Rank1<2>, _, _>(linear);
+        let py_y = model.0.forward(&x.into()).unwrap();
+        assert_eq!(py_y.shape(), [3]);
+        assert_eq!(py_y.data.as_vec(), y.as_vec());
+        let params = model.parameters();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0].shape(), [3, 2]);
+        assert_eq!(params[1].shape(), [3]);
+    }
+}
@@ -0,0 +1,410 @@
+//! Reinforcement learning utilities such as [discounted_returns()], [gae()],
+//! [polyak_update()], and the action samplers [epsilon_greedy()] and [sample_categorical()].
+//!
+//! Rollouts are stored time major, so `rewards`, `values` and `dones` all have shape
+//! `(Steps, Envs)`. `dones[t][e]` is `1.0` if the episode of env `e` ended after step `t`,
+//! which stops returns & advantages from leaking across episode boundaries.
+//!
+//! Returns and advantages are regression targets, so they are computed without a tape.
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use rand::Rng;
+use std::{any::Any, boxed::Box, collections::VecDeque, vec::Vec};
+
+use crate::{
+    optim::{GradientUpdate, ParamUpdater, UnusedTensors},
+    shapes::{Dim, Dtype, HasShape, Shape},
+    tensor::{AsVec, CopySlice, DeviceStorage, Tensor, ZerosTensor},
+    tensor_ops::{Device, TryAdd, TryMul},
+};
+
+/// Computes the discounted return of every step of a rollout:
+/// `R[t] = rewards[t] + gamma * (1 - dones[t]) * R[t + 1]`, where the return after the
+/// last step is `0`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, rl::discounted_returns};
+/// # let dev: Cpu = Default::default();
+/// let rewards: Tensor<Rank2<3, 2>> = dev.ones();
+/// let dones: Tensor<Rank2<3, 2>> = dev.tensor([[0.0, 1.0], [0.0, 0.0], [0.0, 0.0]]);
+/// let returns = discounted_returns(&rewards, &dones, 0.5);
+/// assert_eq!(returns.array(), [[1.75, 1.0], [1.5, 1.5], [1.0, 1.0]]);
+/// ```
+pub fn discounted_returns<S: Dim, B: Dim, D: Device<f32>>(
+    rewards: &Tensor<(S, B), f32, D>,
+    dones: &Tensor<(S, B), f32, D>,
+    gamma: f32,
+) -> Tensor<(S, B), f32, D>
+where
+    D::Storage<(S, B), f32>: AsVec<Unit = f32>,
+{
+    let shape = *rewards.shape();
+    let (steps, envs) = (shape.0.size(), shape.1.size());
+    let r = rewards.as_vec();
+    let d = dones.as_vec();
+
+    let mut returns = alloc::vec![0.0; steps * envs];
+    let mut next = alloc::vec![0.0; envs];
+    for t in (0..steps).rev() {
+        for (e, next) in next.iter_mut().enumerate() {
+            let i = t * envs + e;
+            *next = r[i] + gamma * (1.0 - d[i]) * *next;
+            returns[i] = *next;
+        }
+    }
+
+    let mut out = rewards.device.zeros_like(&shape);
+    out.copy_from(&returns);
+    out
+}
+
+/// Generalized advantage estimation from [High-Dimensional Continuous Control Using
+/// Generalized Advantage Estimation](https://arxiv.org/abs/1506.02438).
+///
+/// With `delta[t] = rewards[t] + gamma * (1 - dones[t]) * values[t + 1] - values[t]`, the
+/// advantages are `A[t] = delta[t] + gamma * lambda * (1 - dones[t]) * A[t + 1]`.
+/// `last_values` are the values of the states after the last step, used to bootstrap
+/// episodes that are still running.
+///
+/// Returns `(advantages, returns)`, where `returns = advantages + values` are the
+/// targets for the value function.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, rl::gae};
+/// # let dev: Cpu = Default::default();
+/// let rewards: Tensor<Rank2<2, 1>> = dev.ones();
+/// let values: Tensor<Rank2<2, 1>> = dev.tensor([[1.0], [2.0]]);
+/// let dones: Tensor<Rank2<2, 1>> = dev.zeros();
+/// let last_values: Tensor<Rank1<1>> = dev.tensor([4.0]);
+/// let (advantages, returns) = gae(&rewards, &values, &dones, &last_values, 0.5, 0.5);
+/// assert_eq!(advantages.array(), [[1.25], [1.0]]);
+/// assert_eq!(returns.array(), [[2.25], [3.0]]);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn gae<S: Dim, B: Dim, D: Device<f32>>(
+    rewards: &Tensor<(S, B), f32, D>,
+    values: &Tensor<(S, B), f32, D>,
+    dones: &Tensor<(S, B), f32, D>,
+    last_values: &Tensor<(B,), f32, D>,
+    gamma: f32,
+    lambda: f32,
+) -> (Tensor<(S, B), f32, D>, Tensor<(S, B), f32, D>)
+where
+    D::Storage<(S, B), f32>: AsVec<Unit = f32>,
+    D::Storage<(B,), f32>: AsVec<Unit = f32>,
+{
+    let shape = *rewards.shape();
+    let (steps, envs) = (shape.0.size(), shape.1.size());
+    let r = rewards.as_vec();
+    let v = values.as_vec();
+    let d = dones.as_vec();
+
+    let mut advantages = alloc::vec![0.0; steps * envs];
+    let mut returns = alloc::vec![0.0; steps * envs];
+    let mut next_value = last_values.as_vec();
+    let mut next_adv = alloc::vec![0.0; envs];
+    for t in (0..steps).rev() {
+        for e in 0..envs {
+            let i = t * envs + e;
+            let not_done = 1.0 - d[i];
+            let delta = r[i] + gamma * not_done * next_value[e] - v[i];
+            next_adv[e] = delta + gamma * lambda * not_done * next_adv[e];
+            next_value[e] = v[i];
+            advantages[i] = next_adv[e];
+            returns[i] = next_adv[e] + v[i];
+        }
+    }
+
+    let mut adv_t = rewards.device.zeros_like(&shape);
+    adv_t.copy_from(&advantages);
+    let mut ret_t = rewards.device.zeros_like(&shape);
+    ret_t.copy_from(&returns);
+    (adv_t, ret_t)
+}
+
+/// Moves every parameter of `target` towards the matching parameter of `online`:
+/// `target = (1 - tau) * target + tau * online`. This is the soft target network
+/// update used by DDPG, TD3 and SAC. `tau = 1.0` copies `online` into `target`.
+///
+/// Parameters are matched by the order they are visited in, so `target` & `online`
+/// must have the same type.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, rl::polyak_update};
+/// # let dev: Cpu = Default::default();
+/// let online: Linear<2, 2> = dev.build_module();
+/// let mut target: Linear<2, 2> = dev.build_module();
+/// polyak_update(&mut target, &online, 1.0);
+/// assert_eq!(target.weight.array(), online.weight.array());
+/// ```
+pub fn polyak_update<M, D: Device<f32>>(target: &mut M, online: &M, tau: f32)
+where
+    M: GradientUpdate<D, f32> + Clone,
+{
+    try_polyak_update(target, online, tau).unwrap()
+}
+
+/// Fallible version of [polyak_update()]
+pub fn try_polyak_update<M, D: Device<f32>>(
+    target: &mut M,
+    online: &M,
+    tau: f32,
+) -> Result<(), D::Err>
+where
+    M: GradientUpdate<D, f32> + Clone,
+{
+    let mut unused = Default::default();
+    let mut collector = CollectParams(Default::default());
+    online.clone().update(&mut collector, &mut unused)?;
+    target.update(&mut Polyak(collector.0, tau), &mut unused)
+}
+
+/// Collects clones of every parameter it visits, in order.
+pub(crate) struct CollectParams(pub(crate) VecDeque<Box<dyn Any>>);
+
+impl<D: DeviceStorage, E: Dtype> ParamUpdater<D, E> for CollectParams {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, E, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        self.0.push_back(Box::new(p.clone()));
+        Ok(())
+    }
+}
+
+/// Averages every parameter it visits with the next parameter collected by [CollectParams].
+struct Polyak(VecDeque<Box<dyn Any>>, f32);
+
+impl<D: Device<f32>> ParamUpdater<D, f32> for Polyak {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let src = self
+            .0
+            .pop_front()
+            .and_then(|src| src.downcast::<Tensor<S, f32, D>>().ok())
+            .expect("Polyak update requires models with the same parameters");
+        let tau = self.1;
+        let avg = p.clone().try_mul(1.0 - tau)?.try_add(src.try_mul(tau)?)?;
+        // only the storage is replaced, so `p` keeps its id and any optimizer state for it
+        p.storage = avg.storage;
+        Ok(())
+    }
+}
+
+/// Picks a uniformly random action with probability `epsilon`, and the action
+/// with the highest q value otherwise, for each row of `q_values`.
+///
+/// **Panics** if `q_values` has no actions.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, rl::epsilon_greedy};
+/// # use rand::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let q_values: Tensor<Rank2<2, 3>> = dev.tensor([[0.0, 1.0, 0.5], [2.0, -1.0, 0.0]]);
+/// let actions = epsilon_greedy(&q_values, 0.0, &mut rng);
+/// assert_eq!(actions.array(), [1, 0]);
+/// ```
+pub fn epsilon_greedy<B: Dim, A: Dim, D, R: Rng>(
+    q_values: &Tensor<(B, A), f32, D>,
+    epsilon: f32,
+    rng: &mut R,
+) -> Tensor<(B,), usize, D>
+where
+    D: Device<f32> + ZerosTensor<usize> + CopySlice<usize>,
+    D::Storage<(B, A), f32>: AsVec<Unit = f32>,
+{
+    let (batch, num_actions) = *q_values.shape();
+    let num_actions = num_actions.size();
+    assert!(num_actions > 0, "epsilon_greedy needs at least one action");
+    let q = q_values.as_vec();
+    let actions: Vec<usize> = q
+        .chunks(num_actions.max(1))
+        .take(batch.size())
+        .map(|row| {
+            if rng.gen::<f32>() < epsilon {
+                rng.gen_range(0..num_actions)
+            } else {
+                let mut best = 0;
+                for (i, v) in row.iter().enumerate() {
+                    if *v > row[best] {
+                        best = i;
+                    }
+                }
+                best
+            }
+        })
+        .collect();
+    let mut out = q_values.device.zeros_like(&(batch,));
+    out.copy_from(&actions);
+    out
+}
+
+/// Samples an action from the softmax of each row of `logits`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, rl::sample_categorical};
+/// # use rand::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let logits: Tensor<Rank2<2, 3>> = dev.tensor([[0.0, 100.0, 0.0], [-100.0, -100.0, 0.0]]);
+/// let actions = sample_categorical(&logits, &mut rng);
+/// assert_eq!(actions.array(), [1, 2]);
+/// ```
+pub fn sample_categorical<B: Dim, A: Dim, D, R: Rng>(
+    logits: &Tensor<(B, A), f32, D>,
+    rng: &mut R,
+) -> Tensor<(B,), usize, D>
+where
+    D: Device<f32> + ZerosTensor<usize> + CopySlice<usize>,
+    D::Storage<(B, A), f32>: AsVec<Unit = f32>,
+{
+    let (batch, num_actions) = *logits.shape();
+    let actions: Vec<usize> = logits
+        .as_vec()
+        .chunks(num_actions.size().max(1))
+        .take(batch.size())
+        .map(|row| {
+            let max = row.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+            let total: f32 = row.iter().map(|l| (l - max).exp()).sum();
+            let mut u = rng.gen::<f32>() * total;
+            for (i, l) in row.iter().enumerate() {
+                u -= (l - max).exp();
+                if u < 0.0 {
+                    return i;
+                }
+            }
+            row.len() - 1
+        })
+        .collect();
+    let mut out = logits.device.zeros_like(&(batch,));
+    out.copy_from(&actions);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{Linear, ModuleBuilder},
+        shapes::*,
+        tensor::*,
+        tests::{assert_close, TestDevice},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_discounted_returns_resets_on_done() {
+        let dev: TestDevice = Default::default();
+        let rewards = dev.tensor([[1.0], [2.0], [3.0], [4.0]]);
+        let dones = dev.tensor([[0.0], [1.0], [0.0], [0.0]]);
+        let returns = discounted_returns(&rewards, &dones, 0.9);
+        assert_close(&returns.array(), &[[2.8], [2.0], [6.6], [4.0]]);
+    }
+
+    #[test]
+    fn test_gae_lambda_one_is_returns_minus_values() {
+        let dev: TestDevice = Default::default();
+        let rewards: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+        let values: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+        let dones = dev.tensor([
+            [0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+        ]);
+        let last_values: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        let (adv, ret) = gae(&rewards, &values, &dones, &last_values, 0.99, 1.0);
+        let expected = discounted_returns(&rewards, &dones, 0.99);
+        assert_close(&ret.array(), &expected.array());
+        assert_close(&adv.array(), &(expected - values).array());
+    }
+
+    #[test]
+    fn test_gae_lambda_zero_is_td_error() {
+        let dev: TestDevice = Default::default();
+        let rewards = dev.tensor([[1.0, 0.0], [0.5, 2.0]]);
+        let values = dev.tensor([[0.5, 1.0], [1.0, 3.0]]);
+        let dones = dev.tensor([[0.0, 1.0], [0.0, 0.0]]);
+        let last_values = dev.tensor([2.0, -1.0]);
+        let (adv, _) = gae(&rewards, &values, &dones, &last_values, 0.5, 0.0);
+        assert_close(
+            &adv.array(),
+            &[[1.0 + 0.5 - 0.5, -1.0], [0.5 + 1.0 - 1.0, 2.0 - 0.5 - 3.0]],
+        );
+    }
+
+    #[test]
+    fn test_polyak_update() {
+        let dev: TestDevice = Default::default();
+        type Model = (Linear<2, 3>, Linear<3, 1>);
+        let online: Model = dev.build_module();
+        let mut target: Model = dev.build_module();
+        let w0 = target.0.weight.array();
+        let b1 = target.1.bias.array();
+        let id = target.0.weight.id;
+        polyak_update(&mut target, &online, 0.25);
+        assert_eq!(target.0.weight.id, id);
+
+        let w = online.0.weight.array();
+        let mut expected = w0;
+        for i in 0..3 {
+            for j in 0..2 {
+                expected[i][j] = 0.75 * w0[i][j] + 0.25 * w[i][j];
+            }
+        }
+        assert_close(&target.0.weight.array(), &expected);
+        assert_close(
+            &target.1.bias.array(),
+            &[0.75 * b1[0] + 0.25 * online.1.bias.array()[0]],
+        );
+    }
+
+    #[test]
+    fn test_epsilon_greedy() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let q: Tensor<Rank2<64, 3>, f32, _> = dev.sample_normal();
+        let greedy = epsilon_greedy(&q, 0.0, &mut rng);
+        let q_arr = q.array();
+        for (row, a) in q_arr.iter().zip(greedy.array()) {
+            assert!(row.iter().all(|v| *v <= row[a]));
+        }
+
+        let random = epsilon_greedy(&q, 1.0, &mut rng).array();
+        assert!(random.iter().all(|a| *a < 3));
+        assert!(random.contains(&0));
+        assert!(random.contains(&1));
+        assert!(random.contains(&2));
+    }
+
+    #[test]
+    #[should_panic = "epsilon_greedy needs at least one action"]
+    fn test_epsilon_greedy_no_actions() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let q: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 0));
+        epsilon_greedy(&q, 1.0, &mut rng);
+    }
+
+    #[test]
+    fn test_sample_categorical_frequencies() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let logits: Tensor<Rank2<1000, 2>, f32, _> = dev.tensor([[0.0, 3f32.ln()]; 1000]);
+        let actions = sample_categorical(&logits, &mut rng).array();
+        let ones = actions.iter().filter(|a| **a == 1).count();
+        assert!((700..800).contains(&ones), "{ones}");
+    }
+}
@@ -74,6 +74,63 @@ broadcast_to!(3, (M, N, P), 4, (M, N, O, P), Axis<2>);
 broadcast_to!(3, (M, O, P), 4, (M, N, O, P), Axis<1>);
 broadcast_to!(3, (N, O, P), 4, (M, N, O, P), Axis<0>);
 
+/// Marker for shapes that can have their [Axes] `Ax` reduced while keeping the
+/// reduced dimensions with size 1. See Self::Kept for the resulting type.
+pub trait KeepDimShape<Ax: Axes>: ReduceShape<Ax> {
+    type Kept: Shape<Concrete = Self::Concrete> + ReduceShapeTo<Self::Reduced, Ax>;
+
+    #[inline]
+    fn kept(&self) -> Self::Kept {
+        let mut dims = self.concrete();
+        for ax in Ax::as_array().into_iter() {
+            dims[ax as usize] = 1;
+        }
+        Self::Kept::from_concrete(&dims).unwrap()
+    }
+}
+
+type C1 = Const<1>;
+
+macro_rules! keep_dims {
+    (($($Dims:tt),*), $Axes:ty, ($($Kept:ty),*)) => {
+        impl<$($Dims: Dim, )*> KeepDimShape<$Axes> for ($($Dims, )*) {
+            type Kept = ($($Kept, )*);
+        }
+    };
+}
+
+keep_dims!((M), Axis<0>, (C1));
+keep_dims!((M, N), Axes2<0, 1>, (C1, C1));
+keep_dims!((M, N, O), Axes3<0, 1, 2>, (C1, C1, C1));
+keep_dims!((M, N, O, P), Axes4<0, 1, 2, 3>, (C1, C1, C1, C1));
+keep_dims!((M, N, O, P, Q), Axes5<0, 1, 2, 3, 4>, (C1, C1, C1, C1, C1));
+keep_dims!((M, N, O, P, Q, R), Axes6<0, 1, 2, 3, 4, 5>, (C1, C1, C1, C1, C1, C1));
+
+keep_dims!((M, N), Axis<1>, (M, C1));
+keep_dims!((M, N), Axis<0>, (C1, N));
+keep_dims!((M, N, O), Axes2<1, 2>, (M, C1, C1));
+keep_dims!((M, N, O), Axes2<0, 2>, (C1, N, C1));
+keep_dims!((M, N, O), Axes2<0, 1>, (C1, C1, O));
+keep_dims!((M, N, O, P), Axes3<1, 2, 3>, (M, C1, C1, C1));
+keep_dims!((M, N, O, P), Axes3<0, 2, 3>, (C1, N, C1, C1));
+keep_dims!((M, N, O, P), Axes3<0, 1, 3>, (C1, C1, O, C1));
+keep_dims!((M, N, O, P), Axes3<0, 1, 2>, (C1, C1, C1, P));
+
+keep_dims!((M, N, O), Axis<2>, (M, N, C1));
+keep_dims!((M, N, O), Axis<1>, (M, C1, O));
+keep_dims!((M, N, O), Axis<0>, (C1, N, O));
+keep_dims!((M, N, O, P), Axes2<2, 3>, (M, N, C1, C1));
+keep_dims!((M, N, O, P), Axes2<1, 3>, (M, C1, O, C1));
+keep_dims!((M, N, O, P), Axes2<0, 3>, (C1, N, O, C1));
+keep_dims!((M, N, O, P), Axes2<1, 2>, (M, C1, C1, P));
+keep_dims!((M, N, O, P), Axes2<0, 2>, (C1, N, C1, P));
+keep_dims!((M, N, O, P), Axes2<0, 1>, (C1, C1, O, P));
+
+keep_dims!((M, N, O, P), Axis<3>, (M, N, O, C1));
+keep_dims!((M, N, O, P), Axis<2>, (M, N, C1, P));
+keep_dims!((M, N, O, P), Axis<1>, (M, C1, O, P));
+keep_dims!((M, N, O, P), Axis<0>, (C1, N, O, P));
+
 /// Internal implementation for broadcasting strides
 pub trait BroadcastStridesTo<S: Shape, Ax>: Shape + BroadcastShapeTo<S, Ax> {
     fn broadcast_strides(&self, strides: Self::Concrete) -> S::Concrete;
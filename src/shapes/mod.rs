@@ -5,13 +5,15 @@ mod permutes;
 mod replace_dim;
 mod same_numel;
 mod shape;
+mod slice;
 
 pub(crate) use axes::Axes;
 pub(crate) use broadcasts::{
-    BroadcastShapeTo, BroadcastStridesTo, ReduceShape, ReduceShapeTo, ReduceStridesTo,
+    BroadcastShapeTo, BroadcastStridesTo, KeepDimShape, ReduceShape, ReduceShapeTo, ReduceStridesTo,
 };
 pub(crate) use permutes::{PermuteShapeTo, PermuteStridesTo};
-pub(crate) use replace_dim::{RemoveDimTo, ReplaceDimTo};
+pub(crate) use replace_dim::{AppendDim, RemoveDimTo, ReplaceAxis, ReplaceDimTo};
+pub(crate) use slice::SliceShape;
 
 #[allow(unused_imports)]
 pub(crate) use same_numel::HasSameNumelAs;
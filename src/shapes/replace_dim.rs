@@ -111,3 +111,76 @@ impl<Batch: Dim, Seq: Dim, S1: Dim, S2: Dim> ReplaceDimTo<(Batch, Seq, S2), (Bat
 {
     type Ax = Axis<0>;
 }
+
+/// Marker for shapes that can have the dimension at axis `Ax` replaced with
+/// the dimension `New`. See Self::Replaced for the resulting type.
+pub trait ReplaceAxis<Ax: Axes<Array = [isize; 1]>, New: Dim>: Shape {
+    type Replaced: Shape<Concrete = Self::Concrete>;
+
+    #[inline]
+    fn replace_axis(&self, new: New) -> Self::Replaced {
+        let ax = Ax::as_array()[0] as usize;
+        let mut dims = self.concrete();
+        dims[ax] = new.size();
+        Self::Replaced::from_concrete(&dims).unwrap()
+    }
+}
+
+macro_rules! replace_axis {
+    (($($DimVars:tt),*), $Ax:literal, $Replaced:ty) => {
+impl<$($DimVars: Dim, )* New: Dim> ReplaceAxis<Axis<$Ax>, New> for ($($DimVars, )*) {
+    type Replaced = $Replaced;
+}
+    };
+}
+
+replace_axis!((D1), 0, (New,));
+
+replace_axis!((D1, D2), 0, (New, D2));
+replace_axis!((D1, D2), 1, (D1, New));
+
+replace_axis!((D1, D2, D3), 0, (New, D2, D3));
+replace_axis!((D1, D2, D3), 1, (D1, New, D3));
+replace_axis!((D1, D2, D3), 2, (D1, D2, New));
+
+replace_axis!((D1, D2, D3, D4), 0, (New, D2, D3, D4));
+replace_axis!((D1, D2, D3, D4), 1, (D1, New, D3, D4));
+replace_axis!((D1, D2, D3, D4), 2, (D1, D2, New, D4));
+replace_axis!((D1, D2, D3, D4), 3, (D1, D2, D3, New));
+
+replace_axis!((D1, D2, D3, D4, D5), 0, (New, D2, D3, D4, D5));
+replace_axis!((D1, D2, D3, D4, D5), 1, (D1, New, D3, D4, D5));
+replace_axis!((D1, D2, D3, D4, D5), 2, (D1, D2, New, D4, D5));
+replace_axis!((D1, D2, D3, D4, D5), 3, (D1, D2, D3, New, D5));
+replace_axis!((D1, D2, D3, D4, D5), 4, (D1, D2, D3, D4, New));
+
+/// Marker for shapes that can have the dimension `New` added after their last
+/// dimension. See Self::Appended for the resulting type.
+pub trait AppendDim<New: Dim>: Shape {
+    type Appended: Shape;
+
+    #[inline]
+    fn append_dim(&self, new: New) -> Self::Appended {
+        let src_dims = self.concrete();
+        let mut dims: <Self::Appended as Shape>::Concrete = Default::default();
+        for i in 0..Self::NUM_DIMS {
+            dims[i] = src_dims[i];
+        }
+        dims[Self::NUM_DIMS] = new.size();
+        Self::Appended::from_concrete(&dims).unwrap()
+    }
+}
+
+macro_rules! append_dim {
+    (($($DimVars:tt),*)) => {
+impl<$($DimVars: Dim, )* New: Dim> AppendDim<New> for ($($DimVars, )*) {
+    type Appended = ($($DimVars, )* New, );
+}
+    };
+}
+
+append_dim!(());
+append_dim!((D1));
+append_dim!((D1, D2));
+append_dim!((D1, D2, D3));
+append_dim!((D1, D2, D3, D4));
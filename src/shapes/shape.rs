@@ -8,7 +8,12 @@ pub trait Unit:
 impl Unit for f32 {}
 impl Unit for f64 {}
 impl Unit for usize {}
+impl Unit for i32 {}
+impl Unit for i64 {}
+impl Unit for u8 {}
 impl Unit for bool {}
+#[cfg(feature = "f16")]
+impl Unit for half::f16 {}
 
 /// Represents something that has a [Unit].
 pub trait HasUnitType {
@@ -31,6 +36,11 @@ pub trait Dtype:
 impl Dtype for f32 {}
 impl Dtype for f64 {}
 impl Dtype for usize {}
+impl Dtype for i32 {}
+impl Dtype for i64 {}
+impl Dtype for u8 {}
+#[cfg(feature = "f16")]
+impl Dtype for half::f16 {}
 
 /// Represents something that has a [Dtype].
 pub trait HasDtype {
@@ -0,0 +1,46 @@
+use super::{
+    axes::{Axes, Axis},
+    shape::{Dim, Shape},
+};
+
+/// Marker for shapes that can be sliced along axis `Ax`, which replaces
+/// that dimension with a runtime sized one. See Self::Sliced for the resulting type.
+pub trait SliceShape<Ax: Axes<Array = [isize; 1]>>: Shape {
+    type Sliced: Shape<Concrete = Self::Concrete>;
+
+    #[inline]
+    fn sliced(&self, len: usize) -> Self::Sliced {
+        let ax = Ax::as_array()[0] as usize;
+        let mut dims = self.concrete();
+        dims[ax] = len;
+        Self::Sliced::from_concrete(&dims).unwrap()
+    }
+}
+
+macro_rules! sliced {
+    (($($DimVars:tt),*), $Ax:literal, $Sliced:ty) => {
+impl<$($DimVars: Dim, )*> SliceShape<Axis<$Ax>> for ($($DimVars, )*) {
+    type Sliced = $Sliced;
+}
+    };
+}
+
+sliced!((D1), 0, (usize,));
+
+sliced!((D1, D2), 0, (usize, D2));
+sliced!((D1, D2), 1, (D1, usize));
+
+sliced!((D1, D2, D3), 0, (usize, D2, D3));
+sliced!((D1, D2, D3), 1, (D1, usize, D3));
+sliced!((D1, D2, D3), 2, (D1, D2, usize));
+
+sliced!((D1, D2, D3, D4), 0, (usize, D2, D3, D4));
+sliced!((D1, D2, D3, D4), 1, (D1, usize, D3, D4));
+sliced!((D1, D2, D3, D4), 2, (D1, D2, usize, D4));
+sliced!((D1, D2, D3, D4), 3, (D1, D2, D3, usize));
+
+sliced!((D1, D2, D3, D4, D5), 0, (usize, D2, D3, D4, D5));
+sliced!((D1, D2, D3, D4, D5), 1, (D1, usize, D3, D4, D5));
+sliced!((D1, D2, D3, D4, D5), 2, (D1, D2, usize, D4, D5));
+sliced!((D1, D2, D3, D4, D5), 3, (D1, D2, D3, usize, D5));
+sliced!((D1, D2, D3, D4, D5), 4, (D1, D2, D3, D4, usize));
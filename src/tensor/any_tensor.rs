@@ -0,0 +1,244 @@
+use crate::{
+    shapes::{Dtype, HasShape, Shape},
+    tensor_ops::{ReshapeKernel, ReshapeTo},
+};
+
+use super::{Cpu, DeviceStorage, Tensor};
+
+use std::{any::Any, boxed::Box, vec::Vec};
+
+type Data = Box<dyn Any + Send + Sync>;
+
+/// A tensor whose [Dtype] and [Shape] are only known at runtime, so tensors of different types
+/// can be passed through the same API, like [crate::nn::DynModule].
+///
+/// Convert a tensor into one with [AnyTensor::from_tensor()], and back with
+/// [AnyTensor::into_tensor()], which checks that the dtype and the shape match. Dimensions
+/// that are `usize` in the requested shape accept any size.
+///
+/// An [AnyTensor] doesn't have a tape, so it is meant for inference.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 3>> = dev.zeros();
+/// let any = AnyTensor::from_tensor(t);
+/// assert_eq!(any.dims(), &[2, 3]);
+/// assert!(any.is::<f32>());
+///
+/// let t: Tensor<(usize, Const<3>)> = any.clone().into_tensor().unwrap();
+/// assert_eq!(t.shape(), &(2, Const::<3>));
+/// assert!(any.clone().into_tensor::<Rank2<3, 2>, f32>().is_err());
+/// assert!(any.into_tensor::<Rank2<2, 3>, f64>().is_err());
+/// ```
+pub struct AnyTensor<D: DeviceStorage = Cpu> {
+    dims: Vec<usize>,
+    dtype: &'static str,
+    /// Always a `Tensor<(usize,), E, D>` with the data in row major order.
+    data: Data,
+    clone_data: fn(&Data) -> Data,
+    marker: std::marker::PhantomData<D>,
+}
+
+/// The error returned by [AnyTensor::try_into_tensor()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyTensorError<Err> {
+    /// The tensor has a different dtype than requested.
+    DtypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The dimensions of the tensor don't fit the requested shape.
+    ShapeMismatch {
+        expected: &'static str,
+        found: Vec<usize>,
+    },
+    /// An error from the device.
+    Device(Err),
+}
+
+impl<Err: std::fmt::Display> std::fmt::Display for AnyTensorError<Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DtypeMismatch { expected, found } => {
+                write!(f, "Expected dtype {expected}, found {found}")
+            }
+            Self::ShapeMismatch { expected, found } => {
+                write!(f, "Expected shape {expected}, found dimensions {found:?}")
+            }
+            Self::Device(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Err: std::fmt::Debug + std::fmt::Display> std::error::Error for AnyTensorError<Err> {}
+
+fn clone_data<E: Dtype, D: DeviceStorage>(data: &Data) -> Data {
+    let t: &Tensor<(usize,), E, D> = data.downcast_ref().unwrap();
+    Box::new(t.clone())
+}
+
+impl<D: DeviceStorage> AnyTensor<D> {
+    /// Erases the shape and dtype of `t`.
+    pub fn from_tensor<S: Shape, E: Dtype>(t: Tensor<S, E, D>) -> Self
+    where
+        D: ReshapeKernel<E>,
+    {
+        Self::try_from_tensor(t).unwrap()
+    }
+
+    /// Fallible version of [AnyTensor::from_tensor()]
+    pub fn try_from_tensor<S: Shape, E: Dtype>(t: Tensor<S, E, D>) -> Result<Self, D::Err>
+    where
+        D: ReshapeKernel<E>,
+    {
+        let shape = *t.shape();
+        let data = t.try_reshape_like(&(shape.num_elements(),))?;
+        Ok(Self {
+            dims: shape.concrete().into(),
+            dtype: std::any::type_name::<E>(),
+            data: Box::new(data),
+            clone_data: clone_data::<E, D>,
+            marker: Default::default(),
+        })
+    }
+
+    /// Converts back into a tensor, see [AnyTensor::try_into_tensor()].
+    pub fn into_tensor<S: Shape, E: Dtype>(self) -> Result<Tensor<S, E, D>, AnyTensorError<D::Err>>
+    where
+        D: ReshapeKernel<E>,
+    {
+        self.try_into_tensor()
+    }
+
+    /// Converts back into a tensor of shape `S` and dtype `E`. Returns an error if the dtype is
+    /// different, or if the dimensions don't fit `S`: the number of dimensions must be the same,
+    /// and compile time dimensions must have the same size.
+    pub fn try_into_tensor<S: Shape, E: Dtype>(
+        self,
+    ) -> Result<Tensor<S, E, D>, AnyTensorError<D::Err>>
+    where
+        D: ReshapeKernel<E>,
+    {
+        let data: Box<Tensor<(usize,), E, D>> =
+            self.data
+                .downcast()
+                .map_err(|_| AnyTensorError::DtypeMismatch {
+                    expected: std::any::type_name::<E>(),
+                    found: self.dtype,
+                })?;
+        let shape = Self::shape_from_dims(&self.dims).ok_or(AnyTensorError::ShapeMismatch {
+            expected: std::any::type_name::<S>(),
+            found: self.dims,
+        })?;
+        data.try_reshape_like(&shape)
+            .map_err(AnyTensorError::Device)
+    }
+
+    fn shape_from_dims<S: Shape>(dims: &[usize]) -> Option<S> {
+        if dims.len() != S::NUM_DIMS {
+            return None;
+        }
+        let mut concrete: S::Concrete = Default::default();
+        for (i, &dim) in dims.iter().enumerate() {
+            concrete[i] = dim;
+        }
+        S::from_concrete(&concrete)
+    }
+
+    /// The size of each dimension.
+    pub fn dims(&self) -> &[usize] {
+        &self.dims
+    }
+
+    /// The number of elements; the product of all dimensions.
+    pub fn num_elements(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    /// The name of the dtype, e.g. `"f32"`.
+    pub fn dtype_name(&self) -> &'static str {
+        self.dtype
+    }
+
+    /// Returns `true` if the dtype is `E`.
+    pub fn is<E: Dtype>(&self) -> bool {
+        self.data.is::<Tensor<(usize,), E, D>>()
+    }
+}
+
+impl<D: DeviceStorage> Clone for AnyTensor<D> {
+    /// Clones the tensor, which shares its data like [Tensor::clone()].
+    fn clone(&self) -> Self {
+        Self {
+            dims: self.dims.clone(),
+            dtype: self.dtype,
+            data: (self.clone_data)(&self.data),
+            clone_data: self.clone_data,
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<D: DeviceStorage> std::fmt::Debug for AnyTensor<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnyTensor")
+            .field("dims", &self.dims)
+            .field("dtype", &self.dtype)
+            .finish()
+    }
+}
+
+impl<S: Shape, E: Dtype, D: ReshapeKernel<E>> From<Tensor<S, E, D>> for AnyTensor<D> {
+    fn from(t: Tensor<S, E, D>) -> Self {
+        Self::from_tensor(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+
+    #[test]
+    fn test_any_tensor_round_trip() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let any: AnyTensor<_> = t.clone().into();
+        assert_eq!(any.dims(), &[2, 3]);
+        assert_eq!(any.num_elements(), 6);
+        assert_eq!(any.dtype_name(), "f32");
+        assert!(any.is::<f32>() && !any.is::<f64>());
+
+        let same: Tensor<Rank2<2, 3>, f32, _> = any.clone().into_tensor().unwrap();
+        assert_eq!(same.array(), t.array());
+        let dynamic: Tensor<(usize, usize), f32, _> = any.into_tensor().unwrap();
+        assert_eq!(dynamic.shape(), &(2, 3));
+        assert_eq!(dynamic.as_vec(), t.as_vec());
+    }
+
+    #[test]
+    fn test_any_tensor_mismatches() {
+        let dev: TestDevice = Default::default();
+        let any = AnyTensor::from_tensor(dev.tensor([1usize, 2, 3]));
+        assert_eq!(any.dtype_name(), "usize");
+        assert!(matches!(
+            any.clone().into_tensor::<Rank1<3>, f32>(),
+            Err(AnyTensorError::DtypeMismatch {
+                expected: "f32",
+                found: "usize"
+            })
+        ));
+        assert!(matches!(
+            any.clone().into_tensor::<Rank1<4>, usize>(),
+            Err(AnyTensorError::ShapeMismatch { .. })
+        ));
+        assert!(matches!(
+            any.clone().into_tensor::<(usize, usize), usize>(),
+            Err(AnyTensorError::ShapeMismatch { .. })
+        ));
+        let t: Tensor<Rank1<3>, usize, _> = any.into_tensor().unwrap();
+        assert_eq!(t.array(), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,674 @@
+//! A compact, versioned binary format for tensors and models.
+//!
+//! A file starts with a fixed header:
+//! - the magic number `b"DFDX"`
+//! - the format version as a little endian `u16`, see [VERSION]
+//! - flags as a little endian `u16`, where bit 0 means the rest of the file is one zstd stream
+//!
+//! It is followed by one record per tensor:
+//! - the tag `1u8`
+//! - the dtype as a `u8`, see [BinaryDtype::TAG]
+//! - the layout as a `u8`, where `0` is contiguous row major (the only layout so far)
+//! - the number of dimensions as a `u8`, and each dimension as a little endian `u64`
+//! - the elements in little endian
+//! - the CRC-32 of the bytes of the elements as a little endian `u32`
+//!
+//! and ends with the tag `0u8` and the number of tensors as a little endian `u64`.
+//!
+//! Tensors are written and read one at a time, so saving or loading a model only needs one
+//! extra copy of its largest tensor in memory.
+
+use crate::shapes::{HasShape, Shape, Unit};
+
+use super::{CopySlice, DeviceStorage, Tensor};
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    vec::Vec,
+};
+
+const MAGIC_NUMBER: &[u8; 4] = b"DFDX";
+
+/// The version of the format that is written. Files with a newer version can't be read.
+pub const VERSION: u16 = 1;
+
+#[cfg(feature = "zstd")]
+const FLAG_ZSTD: u16 = 1;
+const TAG_END: u8 = 0;
+const TAG_TENSOR: u8 = 1;
+const LAYOUT_ROW_MAJOR: u8 = 0;
+
+/// Elements are converted in chunks of this many bytes.
+const CHUNK_SIZE: usize = 1 << 16;
+
+/// Compression of the tensor records of a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+
+    /// Zstandard compression at the given level, where `0` is zstd's default level.
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+}
+
+/// Element types that can be stored in the binary format.
+pub trait BinaryDtype: Unit {
+    /// Identifies the dtype in the header of each tensor.
+    const TAG: u8;
+
+    /// The number of bytes of each element.
+    const SIZE: usize;
+
+    fn write_le(&self, dst: &mut [u8]);
+    fn read_le(src: &[u8]) -> Self;
+}
+
+macro_rules! binary_dtype {
+    ($Ty:ty, $Tag:expr) => {
+        impl BinaryDtype for $Ty {
+            const TAG: u8 = $Tag;
+            const SIZE: usize = std::mem::size_of::<$Ty>();
+            fn write_le(&self, dst: &mut [u8]) {
+                dst.copy_from_slice(&self.to_le_bytes());
+            }
+            fn read_le(src: &[u8]) -> Self {
+                Self::from_le_bytes(src.try_into().unwrap())
+            }
+        }
+    };
+}
+
+binary_dtype!(f32, 0);
+binary_dtype!(f64, 1);
+binary_dtype!(i32, 2);
+binary_dtype!(i64, 3);
+binary_dtype!(u8, 4);
+
+impl BinaryDtype for bool {
+    const TAG: u8 = 5;
+    const SIZE: usize = 1;
+    fn write_le(&self, dst: &mut [u8]) {
+        dst[0] = *self as u8;
+    }
+    fn read_le(src: &[u8]) -> Self {
+        src[0] != 0
+    }
+}
+
+/// Stored as a `u64`, so files are the same on every platform.
+impl BinaryDtype for usize {
+    const TAG: u8 = 6;
+    const SIZE: usize = 8;
+    fn write_le(&self, dst: &mut [u8]) {
+        dst.copy_from_slice(&(*self as u64).to_le_bytes());
+    }
+    fn read_le(src: &[u8]) -> Self {
+        u64::from_le_bytes(src.try_into().unwrap()) as usize
+    }
+}
+
+enum Sink<W: Write> {
+    Plain(W),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Sink<W> {
+    /// Ends the compressed stream, if any
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Sink::Plain(w) => Ok(w),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(w) => w.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Sink<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Plain(w) => w.write(buf),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Plain(w) => w.flush(),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Writes tensors to a [Write] in the binary format, see [crate::tensor::binary].
+///
+/// Call [BinaryWriter::finish()] after the last tensor, otherwise the file is incomplete.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::{prelude::*, tensor::binary::*};
+/// # let dev: Cpu = Default::default();
+/// let a: Tensor<Rank2<2, 3>> = dev.sample_normal();
+/// let b: Tensor<Rank1<3>> = dev.sample_normal();
+/// let mut w = BinaryWriter::new(Vec::new(), Compression::None).unwrap();
+/// w.write_tensor(&a).unwrap();
+/// w.write_tensor(&b).unwrap();
+/// let bytes = w.finish().unwrap();
+///
+/// let mut r = BinaryReader::new(bytes.as_slice()).unwrap();
+/// let mut c: Tensor<Rank2<2, 3>> = dev.zeros();
+/// r.read_tensor(&mut c).unwrap();
+/// assert_eq!(a.array(), c.array());
+/// ```
+pub struct BinaryWriter<W: Write> {
+    sink: Sink<W>,
+    num_tensors: u64,
+}
+
+impl<W: Write> BinaryWriter<W> {
+    /// Writes the header of the file.
+    pub fn new(mut w: W, compression: Compression) -> io::Result<Self> {
+        let flags: u16 = match compression {
+            Compression::None => 0,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(_) => FLAG_ZSTD,
+        };
+        w.write_all(MAGIC_NUMBER)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+        w.write_all(&flags.to_le_bytes())?;
+        let sink = match compression {
+            Compression::None => Sink::Plain(w),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(level) => Sink::Zstd(zstd::Encoder::new(w, level)?),
+        };
+        Ok(Self {
+            sink,
+            num_tensors: 0,
+        })
+    }
+
+    /// Appends `t` to the file.
+    pub fn write_tensor<S: Shape, E: BinaryDtype, D: CopySlice<E>, T>(
+        &mut self,
+        t: &Tensor<S, E, D, T>,
+    ) -> io::Result<()> {
+        let dims = t.shape().concrete();
+        self.sink
+            .write_all(&[TAG_TENSOR, E::TAG, LAYOUT_ROW_MAJOR])?;
+        self.sink.write_all(&[S::NUM_DIMS as u8])?;
+        for dim in dims {
+            self.sink.write_all(&(dim as u64).to_le_bytes())?;
+        }
+
+        let mut data = std::vec![Default::default(); t.shape().num_elements()];
+        D::copy_into(t, &mut data);
+        let mut crc = Crc32::default();
+        let mut bytes = std::vec![0; CHUNK_SIZE - CHUNK_SIZE % E::SIZE];
+        for chunk in data.chunks(bytes.len() / E::SIZE) {
+            let bytes = &mut bytes[..chunk.len() * E::SIZE];
+            for (x, dst) in chunk.iter().zip(bytes.chunks_exact_mut(E::SIZE)) {
+                x.write_le(dst);
+            }
+            crc.update(bytes);
+            self.sink.write_all(bytes)?;
+        }
+        self.sink.write_all(&crc.finish().to_le_bytes())?;
+        self.num_tensors += 1;
+        Ok(())
+    }
+
+    /// Writes the end of the file, and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.sink.write_all(&[TAG_END])?;
+        self.sink.write_all(&self.num_tensors.to_le_bytes())?;
+        let mut w = self.sink.finish()?;
+        w.flush()?;
+        Ok(w)
+    }
+}
+
+enum Source<R: Read> {
+    Plain(R),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Decoder<'static, BufReader<R>>),
+}
+
+impl<R: Read> Read for Source<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Source::Plain(r) => r.read(buf),
+            #[cfg(feature = "zstd")]
+            Source::Zstd(r) => r.read(buf),
+        }
+    }
+}
+
+/// Reads tensors from a [Read] in the binary format, in the order they were written. See
+/// [BinaryWriter] for an example.
+pub struct BinaryReader<R: Read> {
+    source: Source<R>,
+    num_tensors: u64,
+}
+
+impl<R: Read> BinaryReader<R> {
+    /// Reads and checks the header of the file.
+    pub fn new(mut r: R) -> Result<Self, BinaryError> {
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC_NUMBER {
+            return Err(BinaryError::InvalidMagicNumber(magic));
+        }
+        let version = read_u16(&mut r)?;
+        if version > VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+        let flags = read_u16(&mut r)?;
+        let source = match flags {
+            0 => Source::Plain(r),
+            #[cfg(feature = "zstd")]
+            FLAG_ZSTD => Source::Zstd(zstd::Decoder::new(r)?),
+            _ => return Err(BinaryError::UnsupportedFlags(flags)),
+        };
+        Ok(Self {
+            source,
+            num_tensors: 0,
+        })
+    }
+
+    /// Reads the next tensor of the file into `t`. Its dtype and shape must match `t`.
+    pub fn read_tensor<S: Shape, E: BinaryDtype, D: CopySlice<E>, T>(
+        &mut self,
+        t: &mut Tensor<S, E, D, T>,
+    ) -> Result<(), BinaryError> {
+        let r = &mut self.source;
+        match read_u8(r)? {
+            TAG_TENSOR => (),
+            TAG_END => return Err(BinaryError::MissingTensor(self.num_tensors)),
+            tag => return Err(BinaryError::InvalidTag(tag)),
+        }
+        let dtype = read_u8(r)?;
+        if dtype != E::TAG {
+            return Err(BinaryError::DtypeMismatch {
+                expected: E::TAG,
+                found: dtype,
+            });
+        }
+        let layout = read_u8(r)?;
+        if layout != LAYOUT_ROW_MAJOR {
+            return Err(BinaryError::UnsupportedLayout(layout));
+        }
+        let num_dims = read_u8(r)?;
+        let mut dims = Vec::with_capacity(num_dims as usize);
+        for _ in 0..num_dims {
+            let mut bytes = [0; 8];
+            r.read_exact(&mut bytes)?;
+            dims.push(u64::from_le_bytes(bytes) as usize);
+        }
+        let expected: Vec<usize> = t.shape().concrete().into();
+        if dims != expected {
+            return Err(BinaryError::ShapeMismatch {
+                expected,
+                found: dims,
+            });
+        }
+
+        let mut data = Vec::with_capacity(t.shape().num_elements());
+        let mut crc = Crc32::default();
+        let mut bytes = std::vec![0; CHUNK_SIZE - CHUNK_SIZE % E::SIZE];
+        while data.len() < t.shape().num_elements() {
+            let remaining = (t.shape().num_elements() - data.len()) * E::SIZE;
+            let bytes = &mut bytes[..remaining.min(CHUNK_SIZE - CHUNK_SIZE % E::SIZE)];
+            r.read_exact(bytes)?;
+            crc.update(bytes);
+            data.extend(bytes.chunks_exact(E::SIZE).map(E::read_le));
+        }
+        let expected = read_u32(r)?;
+        let found = crc.finish();
+        if found != expected {
+            return Err(BinaryError::ChecksumMismatch { expected, found });
+        }
+        D::copy_from(t, &data);
+        self.num_tensors += 1;
+        Ok(())
+    }
+
+    /// Checks that all tensors of the file have been read.
+    pub fn finish(mut self) -> Result<(), BinaryError> {
+        let r = &mut self.source;
+        match read_u8(r)? {
+            TAG_END => (),
+            TAG_TENSOR => return Err(BinaryError::UnreadTensors(self.num_tensors)),
+            tag => return Err(BinaryError::InvalidTag(tag)),
+        }
+        let mut bytes = [0; 8];
+        r.read_exact(&mut bytes)?;
+        let expected = u64::from_le_bytes(bytes);
+        if expected != self.num_tensors {
+            return Err(BinaryError::TensorCountMismatch {
+                expected,
+                found: self.num_tensors,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u16<R: Read>(r: &mut R) -> io::Result<u16> {
+    let mut bytes = [0; 2];
+    r.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+impl<S: Shape, E: BinaryDtype, D: DeviceStorage + CopySlice<E>, T> Tensor<S, E, D, T> {
+    /// Saves the tensor to a file in the binary format at `path`, see [crate::tensor::binary].
+    pub fn save_binary<P: AsRef<Path>>(&self, path: P, compression: Compression) -> io::Result<()> {
+        let f = BufWriter::new(File::create(path)?);
+        let mut w = BinaryWriter::new(f, compression)?;
+        w.write_tensor(self)?;
+        w.finish()?;
+        Ok(())
+    }
+
+    /// Loads the tensor from a file in the binary format at `path`, which must contain
+    /// exactly this tensor.
+    pub fn load_binary<P: AsRef<Path>>(&mut self, path: P) -> Result<(), BinaryError> {
+        let f = BufReader::new(File::open(path)?);
+        let mut r = BinaryReader::new(f)?;
+        r.read_tensor(self)?;
+        r.finish()
+    }
+}
+
+/// CRC-32 (the one used by zip and png) of the bytes of each tensor.
+struct Crc32(u32);
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self(!0)
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xEDB88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+impl Crc32 {
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = CRC32_TABLE[((self.0 ^ b as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+/// Error that can happen while reading the binary format.
+#[derive(Debug)]
+pub enum BinaryError {
+    /// Error from opening a file, reading values, etc.
+    IoError(std::io::Error),
+
+    /// The file doesn't start with `b"DFDX"`.
+    InvalidMagicNumber([u8; 4]),
+
+    /// The file was written by a newer version of the format.
+    UnsupportedVersion(u16),
+
+    /// The file uses features that aren't known, or that aren't enabled, like compression
+    /// without the `zstd` feature.
+    UnsupportedFlags(u16),
+
+    /// A record doesn't start with a known tag.
+    InvalidTag(u8),
+
+    /// The tensor is stored in a layout that isn't known.
+    UnsupportedLayout(u8),
+
+    DtypeMismatch {
+        expected: u8,
+        found: u8,
+    },
+
+    ShapeMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+
+    /// The data of a tensor is corrupted.
+    ChecksumMismatch {
+        expected: u32,
+        found: u32,
+    },
+
+    /// The file ended after this many tensors, but more were read.
+    MissingTensor(u64),
+
+    /// The file has more tensors than the this many that were read.
+    UnreadTensors(u64),
+
+    /// The end of the file has a different number of tensors than were read.
+    TensorCountMismatch {
+        expected: u64,
+        found: u64,
+    },
+}
+
+impl std::fmt::Display for BinaryError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BinaryError::IoError(err) => write!(fmt, "{err}"),
+            BinaryError::InvalidMagicNumber(num) => write!(fmt, "invalid magic number: {num:?}"),
+            BinaryError::UnsupportedVersion(ver) => write!(fmt, "unsupported version: {ver}"),
+            BinaryError::UnsupportedFlags(flags) => write!(fmt, "unsupported flags: {flags:#x}"),
+            BinaryError::InvalidTag(tag) => write!(fmt, "invalid record tag: {tag}"),
+            BinaryError::UnsupportedLayout(layout) => write!(fmt, "unsupported layout: {layout}"),
+            BinaryError::DtypeMismatch { expected, found } => {
+                write!(fmt, "dtype mismatch: expected {expected} found {found}")
+            }
+            BinaryError::ShapeMismatch { expected, found } => {
+                write!(fmt, "shape mismatch: expected {expected:?} found {found:?}")
+            }
+            BinaryError::ChecksumMismatch { expected, found } => write!(
+                fmt,
+                "checksum mismatch: expected {expected:#010x} found {found:#010x}"
+            ),
+            BinaryError::MissingTensor(n) => {
+                write!(fmt, "expected more than {n} tensors in the file")
+            }
+            BinaryError::UnreadTensors(n) => {
+                write!(fmt, "expected only {n} tensors in the file")
+            }
+            BinaryError::TensorCountMismatch { expected, found } => write!(
+                fmt,
+                "tensor count mismatch: expected {expected} found {found}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BinaryError::IoError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for BinaryError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_crc32() {
+        let mut crc = Crc32::default();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xCBF43926);
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let b = dev.tensor([1.0f64, -2.0]);
+        let c = dev.tensor([[true, false, true]]);
+        let d: Tensor<Rank0, f32, _> = dev.tensor(3.0);
+
+        let mut w = BinaryWriter::new(Vec::new(), Compression::None).unwrap();
+        w.write_tensor(&a).unwrap();
+        w.write_tensor(&b).unwrap();
+        w.write_tensor(&c).unwrap();
+        w.write_tensor(&d).unwrap();
+        let bytes = w.finish().unwrap();
+        assert_eq!(&bytes[..8], b"DFDX\x01\x00\x00\x00");
+
+        let mut r = BinaryReader::new(bytes.as_slice()).unwrap();
+        let mut a2: Tensor<Rank3<2, 3, 4>, f32, _> = dev.zeros();
+        let mut b2: Tensor<Rank1<2>, f64, _> = dev.zeros();
+        let mut c2: Tensor<Rank2<1, 3>, bool, _> = dev.tensor([[false; 3]]);
+        let mut d2: Tensor<Rank0, f32, _> = dev.zeros();
+        r.read_tensor(&mut a2).unwrap();
+        r.read_tensor(&mut b2).unwrap();
+        r.read_tensor(&mut c2).unwrap();
+        r.read_tensor(&mut d2).unwrap();
+        r.finish().unwrap();
+        assert_eq!(a.array(), a2.array());
+        assert_eq!(b2.array(), [1.0, -2.0]);
+        assert_eq!(c2.array(), [[true, false, true]]);
+        assert_eq!(d2.array(), 3.0);
+    }
+
+    #[test]
+    fn test_binary_large_tensor_file() {
+        // larger than one chunk
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<100, 300>, f32, _> = dev.sample_normal();
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        a.save_binary(file.path(), Compression::None).unwrap();
+        let mut b: Tensor<Rank2<100, 300>, f32, _> = dev.zeros();
+        b.load_binary(file.path()).unwrap();
+        assert_eq!(a.array(), b.array());
+    }
+
+    #[test]
+    fn test_binary_errors() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0f32, 2.0, 3.0]);
+        let mut w = BinaryWriter::new(Vec::new(), Compression::None).unwrap();
+        w.write_tensor(&a).unwrap();
+        let bytes = w.finish().unwrap();
+
+        let mut r = BinaryReader::new(bytes.as_slice()).unwrap();
+        let mut b: Tensor<Rank1<4>, f32, _> = dev.zeros();
+        assert!(matches!(
+            r.read_tensor(&mut b),
+            Err(BinaryError::ShapeMismatch { .. })
+        ));
+
+        let mut r = BinaryReader::new(bytes.as_slice()).unwrap();
+        let mut b: Tensor<Rank1<3>, f64, _> = dev.zeros();
+        assert!(matches!(
+            r.read_tensor(&mut b),
+            Err(BinaryError::DtypeMismatch {
+                expected: 1,
+                found: 0
+            })
+        ));
+
+        // flip a bit of the data
+        let mut corrupted = bytes.clone();
+        corrupted[8 + 4 + 8 + 5] ^= 1;
+        let mut r = BinaryReader::new(corrupted.as_slice()).unwrap();
+        let mut b: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        assert!(matches!(
+            r.read_tensor(&mut b),
+            Err(BinaryError::ChecksumMismatch { .. })
+        ));
+
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        assert!(matches!(
+            BinaryReader::new(newer.as_slice()),
+            Err(BinaryError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            BinaryReader::new(&b"NUMPY\x01\x00\x00"[..]),
+            Err(BinaryError::InvalidMagicNumber(_))
+        ));
+
+        let mut r = BinaryReader::new(bytes.as_slice()).unwrap();
+        let mut b: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        r.read_tensor(&mut b).unwrap();
+        assert!(matches!(
+            r.read_tensor(&mut b),
+            Err(BinaryError::MissingTensor(1))
+        ));
+        let r = BinaryReader::new(bytes.as_slice()).unwrap();
+        assert!(matches!(r.finish(), Err(BinaryError::UnreadTensors(0))));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_binary_zstd() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<64, 64>, f32, _> = dev.zeros();
+        let b: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let mut w = BinaryWriter::new(Vec::new(), Compression::Zstd(0)).unwrap();
+        w.write_tensor(&a).unwrap();
+        w.write_tensor(&b).unwrap();
+        let bytes = w.finish().unwrap();
+        assert!(bytes.len() < 64 * 64 * 4 / 10);
+
+        let mut r = BinaryReader::new(bytes.as_slice()).unwrap();
+        let mut a2: Tensor<Rank2<64, 64>, f32, _> = dev.ones();
+        let mut b2: Tensor<Rank1<5>, f32, _> = dev.zeros();
+        r.read_tensor(&mut a2).unwrap();
+        r.read_tensor(&mut b2).unwrap();
+        r.finish().unwrap();
+        assert_eq!(a2.array(), [[0.0; 64]; 64]);
+        assert_eq!(b.array(), b2.array());
+    }
+}
@@ -15,14 +15,15 @@ impl<S: Shape, E: Default + Clone> StridedArray<S, E> {
         Self::try_new_with(shape, Default::default())
     }
 
+    /// Allocates a contiguous array with every element set to `elem`.
     #[inline]
-    pub(crate) fn try_new_with(shape: S, elem: E) -> Result<Self, CpuError> {
+    pub fn try_new_with(shape: S, elem: E) -> Result<Self, CpuError> {
         let numel = shape.num_elements();
         let strides: S::Concrete = shape.strides();
         let mut data: Vec<E> = Vec::new();
         data.try_reserve(numel).map_err(|_| CpuError::OutOfMemory)?;
         data.resize(numel, elem);
-        let data = Arc::new(data);
+        let data = Arc::new(data.into());
         Ok(StridedArray {
             data,
             shape,
@@ -38,7 +39,7 @@ impl<S: Shape, E: Default + Clone> StridedArray<S, E> {
         let mut data: Vec<E> = Vec::new();
         data.try_reserve(numel).map_err(|_| CpuError::OutOfMemory)?;
         data.resize(numel, elem);
-        let data = Arc::new(data);
+        let data = Arc::new(data.into());
         Ok(StridedArray {
             data,
             shape,
@@ -92,7 +93,7 @@ impl<E: Unit> SampleTensor<E> for Cpu {
     ) -> Result<Tensor<S::Shape, E, Self>, Self::Err> {
         let mut storage = StridedArray::try_new_with(*src.shape(), Default::default())?;
         {
-            let mut rng = self.rng.lock().unwrap();
+            let mut rng = self.rng();
             for v in storage.buf_iter_mut() {
                 *v = rng.sample(&distr);
             }
@@ -105,7 +106,7 @@ impl<E: Unit> SampleTensor<E> for Cpu {
         distr: D,
     ) -> Result<(), Self::Err> {
         {
-            let mut rng = self.rng.lock().unwrap();
+            let mut rng = self.rng();
             for v in storage.buf_iter_mut() {
                 *v = rng.sample(&distr);
             }
@@ -123,6 +124,28 @@ impl<E: Unit> CopySlice<E> for Cpu {
     }
 }
 
+impl<E: Unit> TensorFromVec<E> for Cpu {
+    fn try_tensor_from_vec<S: Shape>(
+        &self,
+        src: Vec<E>,
+        shape: S,
+    ) -> Result<Tensor<S, E, Self>, Self::Err> {
+        let numel = shape.num_elements();
+        assert_eq!(
+            src.len(),
+            numel,
+            "Vec has {} elements, but shape {shape:?} needs {numel}",
+            src.len()
+        );
+        let storage = StridedArray {
+            data: Arc::new(src.into()),
+            shape,
+            strides: shape.strides(),
+        };
+        Ok(self.upgrade(storage))
+    }
+}
+
 impl<E: Unit> TensorFromArray<E, Rank0, E> for Cpu {
     fn try_tensor(&self, src: E) -> Result<Tensor<Rank0, E, Self>, Self::Err> {
         let mut storage: StridedArray<_, E> = StridedArray::new(Default::default())?;
@@ -0,0 +1,152 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+    vec::Vec,
+};
+
+#[cfg(all(feature = "mmap", unix))]
+use super::mmap::MappedSlice;
+
+/// The buffer behind a [super::StridedArray]. Either owned, a part of another buffer (for
+/// views like [crate::tensor::Tensor::slice()]), or (with the `mmap` feature) a read-only
+/// slice of a memory mapped file.
+///
+/// Shared and mapped buffers are copied into an owned buffer the first time they are
+/// mutated, so the buffer they point into (or the file) is never written to.
+pub(crate) enum CpuBuffer<E> {
+    Owned(Vec<E>),
+    /// `len` elements of `parent`, starting at element `offset`.
+    Sub {
+        parent: Arc<CpuBuffer<E>>,
+        offset: usize,
+        len: usize,
+    },
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(MappedSlice<E>),
+}
+
+impl<E> CpuBuffer<E> {
+    /// A buffer of the `len` elements of `buf` starting at `offset`, without copying them.
+    pub(crate) fn sub(buf: &Arc<Self>, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= buf.len());
+        match buf.as_ref() {
+            Self::Sub {
+                parent,
+                offset: parent_offset,
+                ..
+            } => Self::Sub {
+                parent: parent.clone(),
+                offset: parent_offset + offset,
+                len,
+            },
+            _ => Self::Sub {
+                parent: buf.clone(),
+                offset,
+                len,
+            },
+        }
+    }
+
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[E] {
+        match self {
+            Self::Owned(data) => data.as_slice(),
+            Self::Sub {
+                parent,
+                offset,
+                len,
+            } => &parent.as_slice()[*offset..*offset + *len],
+            #[cfg(all(feature = "mmap", unix))]
+            Self::Mapped(data) => data.as_slice(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [E]
+    where
+        E: Clone,
+    {
+        match self {
+            Self::Owned(data) => data.as_mut_slice(),
+            _ => {
+                *self = Self::Owned(self.as_slice().to_vec());
+                self.as_mut_slice()
+            }
+        }
+    }
+
+    /// Takes the owned data, copying it out of mapped buffers.
+    #[allow(unused)]
+    pub(crate) fn into_vec(self) -> Vec<E>
+    where
+        E: Clone,
+    {
+        match self {
+            Self::Owned(data) => data,
+            _ => self.as_slice().to_vec(),
+        }
+    }
+
+    /// Whether this is a memory mapped buffer that hasn't been copied yet.
+    #[allow(unused)]
+    pub(crate) fn is_mapped(&self) -> bool {
+        match self {
+            Self::Owned(_) => false,
+            Self::Sub { parent, .. } => parent.is_mapped(),
+            #[cfg(all(feature = "mmap", unix))]
+            Self::Mapped(_) => true,
+        }
+    }
+
+    /// Whether this buffer points into another buffer that hasn't been copied yet.
+    #[allow(unused)]
+    pub(crate) fn is_shared_view(&self) -> bool {
+        matches!(self, Self::Sub { .. })
+    }
+}
+
+impl<E> From<Vec<E>> for CpuBuffer<E> {
+    fn from(data: Vec<E>) -> Self {
+        Self::Owned(data)
+    }
+}
+
+impl<E: Clone> Clone for CpuBuffer<E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Owned(data) => Self::Owned(data.clone()),
+            Self::Sub {
+                parent,
+                offset,
+                len,
+            } => Self::Sub {
+                parent: parent.clone(),
+                offset: *offset,
+                len: *len,
+            },
+            #[cfg(all(feature = "mmap", unix))]
+            Self::Mapped(data) => Self::Mapped(data.clone()),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug> std::fmt::Debug for CpuBuffer<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<E> Deref for CpuBuffer<E> {
+    type Target = [E];
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<E: Clone> DerefMut for CpuBuffer<E> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
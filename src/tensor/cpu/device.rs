@@ -1,26 +1,26 @@
 use crate::shapes::{Dtype, HasDtype, HasShape, HasUnitType, Shape, Unit};
-use crate::tensor::storage_traits::*;
+use crate::tensor::{replay::Recorder, storage_traits::*};
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::{
-    sync::{Arc, Mutex},
-    vec::Vec,
-};
+use std::sync::{Arc, Mutex};
+
+use super::buffer::CpuBuffer;
 
 /// A device that stores data on the heap.
 ///
 /// The [Default] impl seeds the underlying rng with seed of 0.
 ///
-/// Use [Cpu::seed_from_u64] to control what seed is used.
+/// Use [Cpu::seed_from_u64] to control what seed is used, and [Cpu::with_matmul_backend]
+/// to control how matrix multiplications are computed.
 #[derive(Clone, Debug)]
 pub struct Cpu {
     pub(crate) rng: Arc<Mutex<StdRng>>,
+    pub(crate) matmul_backend: CpuMatMulBackend,
+    pub(crate) recorder: Arc<Recorder<Cpu>>,
 }
 
 impl Default for Cpu {
     fn default() -> Self {
-        Self {
-            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(0))),
-        }
+        Self::seed_from_u64(0)
     }
 }
 
@@ -29,18 +29,95 @@ impl Cpu {
     pub fn seed_from_u64(seed: u64) -> Self {
         Self {
             rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+            matmul_backend: Default::default(),
+            recorder: Default::default(),
+        }
+    }
+
+    /// Uses `backend` for all matrix multiplications (including the ones in convolutions)
+    /// done with this device and tensors created from it.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// let dev = Cpu::default().with_matmul_backend(CpuMatMulBackend::Blocked);
+    /// let a: Tensor<Rank2<2, 3>> = dev.ones();
+    /// let b: Tensor<Rank2<3, 4>> = dev.ones();
+    /// assert_eq!(a.matmul(b).array(), [[3.0; 4]; 2]);
+    /// ```
+    pub fn with_matmul_backend(mut self, backend: CpuMatMulBackend) -> Self {
+        self.matmul_backend = backend;
+        self
+    }
+
+    /// The backend used for matrix multiplications. See [Cpu::with_matmul_backend].
+    pub fn matmul_backend(&self) -> CpuMatMulBackend {
+        self.matmul_backend
+    }
+
+    /// Locks the rng. Without the "std" feature this is a spin lock.
+    pub(crate) fn rng(&self) -> impl core::ops::DerefMut<Target = StdRng> + '_ {
+        #[cfg(feature = "std")]
+        {
+            self.rng.lock().unwrap()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.rng.lock()
         }
     }
 }
 
+/// The implementation [Cpu] uses for matrix multiplication.
+///
+/// The default is `CpuMatMulBackend::Blas` if one of the BLAS features (`cblas`, `intel-mkl`,
+/// `openblas`, `accelerate`) is enabled, and [CpuMatMulBackend::MatrixMultiply] otherwise.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CpuMatMulBackend {
+    /// The pure rust [matrixmultiply](https://crates.io/crates/matrixmultiply) crate.
+    #[cfg_attr(not(feature = "cblas"), default)]
+    MatrixMultiply,
+
+    /// A pure rust blocked & packed kernel, which splits large multiplications
+    /// across multiple threads when the `std` feature is enabled.
+    Blocked,
+
+    /// The linked BLAS library.
+    #[cfg(feature = "cblas")]
+    #[default]
+    Blas,
+}
+
 /// The storage for the cpu device
 #[derive(Debug, Clone)]
 pub struct StridedArray<S: Shape, E> {
-    pub(crate) data: Arc<Vec<E>>,
+    pub(crate) data: Arc<CpuBuffer<E>>,
     pub(crate) shape: S,
     pub(crate) strides: S::Concrete,
 }
 
+impl<S: Shape, E> StridedArray<S, E> {
+    /// The strides of each dimension into [StridedArray::data()].
+    pub fn strides(&self) -> &S::Concrete {
+        &self.strides
+    }
+
+    /// The underlying buffer. The element at index `[i, j, ...]` is at
+    /// `i * strides[0] + j * strides[1] + ...`. Broadcasted dimensions have a
+    /// stride of 0, so the buffer may hold fewer than `shape.num_elements()` items.
+    pub fn data(&self) -> &[E] {
+        self.data.as_slice()
+    }
+
+    /// Mutable version of [StridedArray::data()]. Copies the buffer
+    /// if it is shared with another array.
+    pub fn data_mut(&mut self) -> &mut [E]
+    where
+        E: Clone,
+    {
+        Arc::make_mut(&mut self.data).as_mut_slice()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum CpuError {
     /// Device is out of memory
@@ -89,6 +166,10 @@ impl DeviceStorage for Cpu {
     }
 
     fn random_u64(&self) -> u64 {
-        self.rng.lock().unwrap().gen()
+        self.rng().gen()
+    }
+
+    fn recorder(&self) -> &Recorder<Self> {
+        &self.recorder
     }
 }
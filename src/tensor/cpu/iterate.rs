@@ -1,7 +1,6 @@
 use super::device::StridedArray;
 use crate::shapes::{BroadcastStridesTo, Shape};
 use std::sync::Arc;
-use std::vec::Vec;
 
 struct NdIndex<S: Shape> {
     indices: S::Concrete,
@@ -22,7 +21,7 @@ impl<S: Shape> NdIndex<S> {
             indices,
             shape: shape.concrete(),
             strides,
-            next: Some(i),
+            next: (shape.num_elements() > 0).then_some(i),
         }
     }
 }
@@ -65,22 +64,22 @@ impl<S: Shape> NdIndex<S> {
 }
 
 pub(crate) struct StridedRefIter<'a, S: Shape, E> {
-    data: &'a Vec<E>,
+    data: &'a [E],
     index: NdIndex<S>,
 }
 
 pub(crate) struct StridedMutIter<'a, S: Shape, E> {
-    data: &'a mut Vec<E>,
+    data: &'a mut [E],
     index: NdIndex<S>,
 }
 
 pub(crate) struct StridedRefIndexIter<'a, S: Shape, E> {
-    data: &'a Vec<E>,
+    data: &'a [E],
     index: NdIndex<S>,
 }
 
 pub(crate) struct StridedMutIndexIter<'a, S: Shape, E> {
-    data: &'a mut Vec<E>,
+    data: &'a mut [E],
     index: NdIndex<S>,
 }
 
@@ -102,7 +101,7 @@ impl<S: Shape, E: Clone> StridedArray<S, E> {
 
     pub(crate) fn iter_mut(&mut self) -> StridedMutIter<S, E> {
         StridedMutIter {
-            data: std::sync::Arc::make_mut(&mut self.data),
+            data: std::sync::Arc::make_mut(&mut self.data).as_mut_slice(),
             index: NdIndex::new(self.shape, self.strides),
         }
     }
@@ -116,7 +115,7 @@ impl<S: Shape, E: Clone> StridedArray<S, E> {
 
     pub(crate) fn iter_mut_with_index(&mut self) -> StridedMutIndexIter<S, E> {
         StridedMutIndexIter {
-            data: std::sync::Arc::make_mut(&mut self.data),
+            data: std::sync::Arc::make_mut(&mut self.data).as_mut_slice(),
             index: NdIndex::new(self.shape, self.strides),
         }
     }
@@ -138,7 +137,7 @@ impl<S: Shape, E: Clone> StridedArray<S, E> {
         S: BroadcastStridesTo<Dst, Axes>,
     {
         StridedMutIter {
-            data: Arc::make_mut(&mut self.data),
+            data: Arc::make_mut(&mut self.data).as_mut_slice(),
             index: NdIndex::new(*dst, self.shape.broadcast_strides(self.strides)),
         }
     }
@@ -196,7 +195,7 @@ mod tests {
     #[test]
     fn test_0d_contiguous_iter() {
         let s: StridedArray<Rank0, f32> = StridedArray {
-            data: Arc::new([0.0].to_vec()),
+            data: Arc::new([0.0].to_vec().into()),
             shape: (),
             strides: ().strides(),
         };
@@ -209,7 +208,7 @@ mod tests {
     fn test_1d_contiguous_iter() {
         let shape = Default::default();
         let s: StridedArray<Rank1<3>, f32> = StridedArray {
-            data: Arc::new([0.0, 1.0, 2.0].to_vec()),
+            data: Arc::new([0.0, 1.0, 2.0].to_vec().into()),
             shape,
             strides: shape.strides(),
         };
@@ -224,7 +223,7 @@ mod tests {
     fn test_2d_contiguous_iter() {
         let shape = Default::default();
         let s: StridedArray<Rank2<2, 3>, f32> = StridedArray {
-            data: Arc::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0].to_vec()),
+            data: Arc::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0].to_vec().into()),
             shape,
             strides: shape.strides(),
         };
@@ -241,7 +240,7 @@ mod tests {
     #[test]
     fn test_2d_broadcasted_0_iter() {
         let s: StridedArray<Rank2<2, 3>, f32> = StridedArray {
-            data: Arc::new([1.0, 0.0, -1.0].to_vec()),
+            data: Arc::new([1.0, 0.0, -1.0].to_vec().into()),
             shape: Default::default(),
             strides: [0, 1],
         };
@@ -258,7 +257,7 @@ mod tests {
     #[test]
     fn test_2d_broadcasted_1_iter() {
         let s: StridedArray<Rank2<2, 3>, f32> = StridedArray {
-            data: Arc::new([1.0, -1.0].to_vec()),
+            data: Arc::new([1.0, -1.0].to_vec().into()),
             shape: Default::default(),
             strides: [1, 0],
         };
@@ -275,7 +274,7 @@ mod tests {
     #[test]
     fn test_2d_permuted_iter() {
         let s: StridedArray<Rank2<3, 2>, f32> = StridedArray {
-            data: Arc::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0].to_vec()),
+            data: Arc::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0].to_vec().into()),
             shape: Default::default(),
             strides: [1, 3],
         };
@@ -292,7 +291,7 @@ mod tests {
     #[test]
     fn test_3d_broadcasted_iter() {
         let s: StridedArray<Rank3<3, 1, 2>, f32> = StridedArray {
-            data: Arc::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0].to_vec()),
+            data: Arc::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0].to_vec().into()),
             shape: Default::default(),
             strides: [2, 0, 1],
         };
@@ -0,0 +1,399 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{
+        numpy::{read_header, Endian, NpyError, NpzError, NumpyDtype},
+        storage_traits::DeviceStorage,
+        Tensor,
+    },
+};
+
+use super::{buffer::CpuBuffer, Cpu, StridedArray};
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Cursor},
+    marker::PhantomData,
+    os::unix::io::AsRawFd,
+    path::Path,
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+
+use zip::{result::ZipError, CompressionMethod, ZipArchive};
+
+/// A read-only memory mapping of a whole file, unmapped on drop.
+#[derive(Debug)]
+pub(crate) struct Mapping {
+    ptr: *const u8,
+    len: usize,
+}
+
+// SAFETY: the mapping is read-only and never remapped, so it can be read from any thread.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        if len == 0 {
+            return Ok(Self {
+                ptr: std::ptr::NonNull::dangling().as_ptr(),
+                len,
+            });
+        }
+        // SAFETY: the file is open for reading, and the arguments are valid for a read-only mapping.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            ptr: ptr as *const u8,
+            len,
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `len` readable bytes for the lifetime of `self`.
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: `ptr` and `len` are exactly what `mmap` returned.
+            unsafe { libc::munmap(self.ptr as *mut libc::c_void, self.len) };
+        }
+    }
+}
+
+/// `len` elements of type `E` starting at byte `offset` of a [Mapping].
+pub(crate) struct MappedSlice<E> {
+    map: Arc<Mapping>,
+    offset: usize,
+    len: usize,
+    marker: PhantomData<E>,
+}
+
+impl<E> MappedSlice<E> {
+    /// Returns `None` if the bytes aren't aligned for `E`.
+    fn new(map: Arc<Mapping>, offset: usize, len: usize) -> Option<Self> {
+        assert!(offset + len * std::mem::size_of::<E>() <= map.len);
+        let addr = map.ptr as usize + offset;
+        let aligned = addr & (std::mem::align_of::<E>() - 1) == 0;
+        aligned.then_some(Self {
+            map,
+            offset,
+            len,
+            marker: PhantomData,
+        })
+    }
+
+    pub(crate) fn as_slice(&self) -> &[E] {
+        // SAFETY: bounds & alignment are checked in `new`, and `E` is only ever a [NumpyDtype],
+        // for which every bit pattern is valid.
+        unsafe { std::slice::from_raw_parts(self.map.ptr.add(self.offset) as *const E, self.len) }
+    }
+}
+
+impl<E> Clone for MappedSlice<E> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            offset: self.offset,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Creates a tensor backed by the `.npy` data found at `offset` of `map`. `offset` and `len`
+/// are read from the file, so they are checked against the size of the mapping.
+fn mapped_tensor<S: Shape, E: Dtype + NumpyDtype>(
+    device: &Cpu,
+    map: &Arc<Mapping>,
+    offset: usize,
+    len: usize,
+    shape: S,
+) -> Result<Tensor<S, E, Cpu>, NpyError> {
+    let out_of_bounds =
+        || io::Error::new(io::ErrorKind::UnexpectedEof, "npy data is out of bounds");
+    let bytes = offset
+        .checked_add(len)
+        .and_then(|end| map.bytes().get(offset..end))
+        .ok_or_else(out_of_bounds)?;
+    let mut cursor = Cursor::new(bytes);
+    let endian = read_header::<_, E>(&mut cursor, shape.concrete().into_iter().collect())?;
+    let start = cursor.position() as usize;
+    let numel = shape.num_elements();
+    let end = numel
+        .checked_mul(std::mem::size_of::<E>())
+        .and_then(|n| n.checked_add(start))
+        .ok_or_else(out_of_bounds)?;
+    if end > bytes.len() {
+        return Err(out_of_bounds().into());
+    }
+
+    let native = match endian {
+        Endian::Native => true,
+        Endian::Little => cfg!(target_endian = "little"),
+        Endian::Big => cfg!(target_endian = "big"),
+    };
+    let mapped = MappedSlice::new(map.clone(), offset + start, numel).filter(|_| native);
+    let data = match mapped {
+        Some(mapped) => CpuBuffer::Mapped(mapped),
+        None => {
+            // not mappable, fall back to reading a copy
+            let mut r = &bytes[start..];
+            let mut data = Vec::with_capacity(numel);
+            for _ in 0..numel {
+                data.push(E::read_endian(&mut r, endian)?);
+            }
+            data.into()
+        }
+    };
+    Ok(device.upgrade(StridedArray {
+        data: Arc::new(data),
+        shape,
+        strides: shape.strides(),
+    }))
+}
+
+impl Cpu {
+    /// Creates a tensor whose data is memory mapped from the `.npy` file at `path`, so
+    /// it is loaded lazily by the operating system, and shared between all processes
+    /// mapping the same file.
+    ///
+    /// The file is never written to: the data is copied into memory the first time the tensor
+    /// (or a clone of it) is mutated, e.g. by an optimizer.
+    ///
+    /// If the data is not aligned for `E`, or not stored in native byte order, it is copied instead.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the tensor, or any clone of it that
+    /// still shares the mapped data, is alive. Modifying it changes the values of the tensor
+    /// behind the compiler's back, and truncating it makes reading the tensor crash with
+    /// `SIGBUS`.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # let path = dir.path().join("weight.npy");
+    /// dev.tensor([[1.0f32, 2.0], [3.0, 4.0]]).save_to_npy(&path).unwrap();
+    /// // SAFETY: nothing else writes to the file
+    /// let t: Tensor<Rank2<2, 2>> = unsafe { dev.mmap_npy(&path, &Default::default()) }.unwrap();
+    /// assert_eq!(t.array(), [[1.0, 2.0], [3.0, 4.0]]);
+    /// ```
+    pub unsafe fn mmap_npy<S: Shape, E: Dtype + NumpyDtype, P: AsRef<Path>>(
+        &self,
+        path: P,
+        shape: &S,
+    ) -> Result<Tensor<S, E, Self>, NpyError> {
+        let map = Arc::new(Mapping::open(path)?);
+        let len = map.len;
+        mapped_tensor(self, &map, 0, len, *shape)
+    }
+}
+
+/// A memory mapped `.npz` file, whose entries can be turned into tensors without copying
+/// the data. See [Cpu::mmap_npy()].
+///
+/// Only uncompressed entries can be mapped, which includes all files written with
+/// [crate::nn::SaveToNpz] and `numpy.savez`.
+///
+/// Opening the file and creating tensors is `unsafe`, see the safety contract of
+/// [Cpu::mmap_npy()].
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # let dir = tempfile::tempdir().unwrap();
+/// # let path = dir.path().join("model.npz");
+/// let model: Linear<5, 10> = dev.build_module();
+/// model.save(&path).unwrap();
+///
+/// // SAFETY: nothing else writes to the file
+/// let npz = unsafe { MmapNpz::open(&path) }.unwrap();
+/// let mut mapped: Linear<5, 10> = dev.build_module();
+/// unsafe {
+///     mapped.weight = npz.tensor(&dev, "weight.npy", &Default::default()).unwrap();
+///     mapped.bias = npz.tensor(&dev, "bias.npy", &Default::default()).unwrap();
+/// }
+/// assert_eq!(mapped.weight.array(), model.weight.array());
+/// ```
+#[derive(Debug)]
+pub struct MmapNpz {
+    map: Arc<Mapping>,
+    entries: HashMap<String, (usize, usize)>,
+}
+
+impl MmapNpz {
+    /// Maps the `.npz` file at `path`, and reads the names of its entries.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the returned [MmapNpz] is alive.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<Self, NpzError> {
+        let map = Arc::new(Mapping::open(path)?);
+        let mut zip = ZipArchive::new(Cursor::new(map.bytes()))?;
+        let mut entries = HashMap::new();
+        for i in 0..zip.len() {
+            let f = zip.by_index(i)?;
+            if f.compression() == CompressionMethod::Stored {
+                let start = f.data_start() as usize;
+                entries.insert(f.name().into(), (start, f.size() as usize));
+            }
+        }
+        drop(zip);
+        Ok(Self { map, entries })
+    }
+
+    /// The names of all entries that can be mapped.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(|k| k.as_str())
+    }
+
+    /// Creates a tensor backed by the entry named `name`, which must have shape `shape`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the tensor, or any clone of it that
+    /// still shares the mapped data, is alive. See [Cpu::mmap_npy()].
+    pub unsafe fn tensor<S: Shape, E: Dtype + NumpyDtype>(
+        &self,
+        device: &Cpu,
+        name: &str,
+        shape: &S,
+    ) -> Result<Tensor<S, E, Cpu>, NpzError> {
+        let &(offset, len) = self.entries.get(name).ok_or(ZipError::FileNotFound)?;
+        Ok(mapped_tensor(device, &self.map, offset, len, *shape)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::assert_close};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_mmap_npy_copy_on_write() {
+        let dev: Cpu = Default::default();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("t.npy");
+        let x = dev.sample_normal::<Rank2<3, 4>>();
+        x.save_to_npy(&path).unwrap();
+
+        let mut t: Tensor<Rank2<3, 4>, f32, _> =
+            unsafe { dev.mmap_npy(&path, &Default::default()) }.unwrap();
+        assert!(t.storage.data.is_mapped());
+        assert_eq!(t.array(), x.array());
+
+        let y = t.clone() * 2.0;
+        assert_close(&y.array(), &(x.clone() * 2.0).array());
+
+        let u = t.clone();
+        t.fill_with_zeros();
+        assert!(!t.storage.data.is_mapped());
+        assert!(u.storage.data.is_mapped());
+        assert_eq!(t.array(), [[0.0; 4]; 3]);
+        assert_eq!(u.array(), x.array());
+
+        // the file is unchanged
+        let again: Tensor<Rank2<3, 4>, f32, _> =
+            unsafe { dev.mmap_npy(&path, &Default::default()) }.unwrap();
+        assert_eq!(again.array(), x.array());
+    }
+
+    #[test]
+    fn test_mmap_npy_runtime_shape() {
+        let dev: Cpu = Default::default();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("t.npy");
+        dev.tensor([1.0f64, 2.0, 3.0]).save_to_npy(&path).unwrap();
+
+        let t: Tensor<(usize,), f64, _> = unsafe { dev.mmap_npy(&path, &(3,)) }.unwrap();
+        assert!(t.storage.data.is_mapped());
+        assert_eq!(t.as_vec(), [1.0, 2.0, 3.0]);
+
+        let r: Result<Tensor<(usize,), f64, _>, _> = unsafe { dev.mmap_npy(&path, &(4,)) };
+        assert!(r.is_err());
+        let r: Result<Tensor<(usize,), f32, _>, _> = unsafe { dev.mmap_npy(&path, &(3,)) };
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_mmap_npz() {
+        let dev: Cpu = Default::default();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("t.npz");
+        let a: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+            a.write_to_npz(&mut zip, "a.npy".into()).unwrap();
+            b.write_to_npz(&mut zip, "b.npy".into()).unwrap();
+            zip.finish().unwrap();
+        }
+
+        let npz = unsafe { MmapNpz::open(&path) }.unwrap();
+        let mut names: Vec<&str> = npz.names().collect();
+        names.sort();
+        assert_eq!(names, ["a.npy", "b.npy"]);
+
+        let a2: Tensor<Rank1<5>, f32, _> =
+            unsafe { npz.tensor(&dev, "a.npy", &Default::default()) }.unwrap();
+        let b2: Tensor<Rank2<2, 3>, f32, _> =
+            unsafe { npz.tensor(&dev, "b.npy", &Default::default()) }.unwrap();
+        assert!(a2.storage.data.is_mapped());
+        assert!(b2.storage.data.is_mapped());
+        assert_eq!(a2.array(), a.array());
+        assert_eq!(b2.array(), b.array());
+
+        // tensors keep the file mapped
+        drop(npz);
+        let g = (a2.trace() * 2.0).sum().backward();
+        assert_eq!(g.get(&a2).array(), [2.0; 5]);
+
+        let npz = unsafe { MmapNpz::open(&path) }.unwrap();
+        let r: Result<Tensor<Rank1<5>, f32, _>, _> =
+            unsafe { npz.tensor(&dev, "c.npy", &Default::default()) };
+        assert!(matches!(r, Err(NpzError::Zip(ZipError::FileNotFound))));
+    }
+
+    #[test]
+    fn test_mmap_out_of_bounds() {
+        let dev: Cpu = Default::default();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("t.npy");
+        dev.tensor([1.0f32, 2.0, 3.0]).save_to_npy(&path).unwrap();
+        let map = Arc::new(Mapping::open(&path).unwrap());
+
+        let r = mapped_tensor::<Rank1<3>, f32>(&dev, &map, map.len - 4, 8, Default::default());
+        assert!(matches!(r, Err(NpyError::IoError(_))));
+        let r = mapped_tensor::<Rank1<3>, f32>(&dev, &map, usize::MAX, 2, Default::default());
+        assert!(matches!(r, Err(NpyError::IoError(_))));
+
+        // the header promises more data than the file has
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(map.len as u64 - 4).unwrap();
+        drop(map);
+        let r: Result<Tensor<Rank1<3>, f32, _>, _> =
+            unsafe { dev.mmap_npy(&path, &Default::default()) };
+        assert!(matches!(r, Err(NpyError::IoError(_))));
+    }
+}
@@ -1,11 +1,20 @@
 mod allocate;
+mod buffer;
 mod device;
 mod index;
 mod iterate;
+#[cfg(all(feature = "mmap", unix))]
+mod mmap;
+#[cfg(feature = "ndarray")]
+pub(crate) mod ndarray;
 mod views;
 
-pub(crate) use device::StridedArray;
+pub(crate) use buffer::CpuBuffer;
 pub(crate) use iterate::LendingIterator;
 pub(crate) use views::{View, ViewMut};
 
-pub use device::{Cpu, CpuError};
+#[cfg(feature = "ndarray")]
+pub use self::ndarray::NdarrayShapeError;
+pub use device::{Cpu, CpuError, CpuMatMulBackend, StridedArray};
+#[cfg(all(feature = "mmap", unix))]
+pub use mmap::MmapNpz;
@@ -0,0 +1,204 @@
+use crate::{
+    shapes::{Shape, Unit},
+    tensor::{storage_traits::TensorFromVec, Tensor},
+};
+
+use super::Cpu;
+
+use ::ndarray::{ArrayBase, ArrayD, ArrayViewD, Dimension, IxDyn, OwnedRepr, ShapeBuilder};
+use std::{sync::Arc, vec::Vec};
+
+/// Returned when an ndarray's shape doesn't match the [Shape] of the tensor
+/// it is converted to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdarrayShapeError {
+    /// The shape of the ndarray
+    pub found: Vec<usize>,
+}
+
+impl std::fmt::Display for NdarrayShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ndarray of shape {:?} doesn't match the tensor",
+            self.found
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NdarrayShapeError {}
+
+/// Converts runtime dimensions into `S`, or `None` if they don't fit.
+pub(crate) fn shape_from_dims<S: Shape>(dims: &[usize]) -> Option<S> {
+    if dims.len() != S::NUM_DIMS {
+        return None;
+    }
+    let mut concrete: S::Concrete = Default::default();
+    for (i, &d) in dims.iter().enumerate() {
+        concrete[i] = d;
+    }
+    S::from_concrete(&concrete)
+}
+
+impl<S: Shape, E: Unit, T> Tensor<S, E, Cpu, T> {
+    /// Views the tensor as an ndarray without copying. The view uses the same strides
+    /// as the tensor, so permuted and broadcasted tensors work too.
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 3>> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    /// let a = t.as_ndarray();
+    /// assert_eq!(a.shape(), &[2, 3]);
+    /// assert_eq!(a[[1, 0]], 4.0);
+    /// ```
+    pub fn as_ndarray(&self) -> ArrayViewD<'_, E> {
+        let dims: Vec<usize> = self.storage.shape.concrete().into();
+        let strides: Vec<usize> = self.storage.strides.into();
+        // SAFETY: every index within the tensor's shape points into its buffer,
+        // and the view borrows the tensor so the buffer outlives it.
+        unsafe {
+            ArrayViewD::from_shape_ptr(
+                IxDyn(&dims).strides(IxDyn(&strides)),
+                self.storage.data.as_ptr(),
+            )
+        }
+    }
+
+    /// Copies the tensor into an owned ndarray in standard layout.
+    pub fn to_ndarray(&self) -> ArrayD<E> {
+        self.as_ndarray().as_standard_layout().into_owned()
+    }
+}
+
+impl Cpu {
+    /// Creates a tensor from an ndarray. Arrays in standard layout are moved into the
+    /// tensor without copying, any other layout is copied.
+    ///
+    /// Returns an error if the array's shape doesn't fit `S`.
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a = ndarray::Array2::<f32>::zeros((2, 3));
+    /// let t: Tensor<(usize, Const<3>)> = dev.from_ndarray(a).unwrap();
+    /// assert_eq!(t.shape(), &(2, Const));
+    /// ```
+    pub fn from_ndarray<S: Shape, E: Unit, D: Dimension>(
+        &self,
+        array: ArrayBase<OwnedRepr<E>, D>,
+    ) -> Result<Tensor<S, E, Self>, NdarrayShapeError> {
+        let shape: S = shape_from_dims(array.shape()).ok_or_else(|| NdarrayShapeError {
+            found: array.shape().to_vec(),
+        })?;
+        let numel = shape.num_elements();
+        let data = if numel == 0 {
+            Vec::new()
+        } else if array.is_standard_layout() {
+            // the raw vec can start before the first element if the array was sliced
+            let first = array.as_ptr();
+            let mut data = array.into_raw_vec();
+            let offset = (first as usize - data.as_ptr() as usize) / std::mem::size_of::<E>();
+            data.drain(..offset);
+            data.truncate(numel);
+            data
+        } else {
+            array.iter().cloned().collect()
+        };
+        Ok(self.tensor_from_vec(data, shape))
+    }
+}
+
+impl<S: Shape, E: Unit, T> From<Tensor<S, E, Cpu, T>> for ArrayD<E> {
+    /// Moves the tensor's buffer into the array if it is contiguous and not shared,
+    /// otherwise copies it.
+    fn from(t: Tensor<S, E, Cpu, T>) -> Self {
+        let shape = t.storage.shape;
+        if t.storage.strides != shape.strides() || t.storage.data.len() != shape.num_elements() {
+            return t.to_ndarray();
+        }
+        let data = match Arc::try_unwrap(t.storage.data) {
+            Ok(data) => data.into_vec(),
+            Err(data) => data.to_vec(),
+        };
+        let dims: Vec<usize> = shape.concrete().into();
+        ArrayD::from_shape_vec(IxDyn(&dims), data).unwrap()
+    }
+}
+
+impl<S: Shape, E: Unit, D: Dimension> TryFrom<ArrayBase<OwnedRepr<E>, D>> for Tensor<S, E, Cpu> {
+    type Error = NdarrayShapeError;
+    /// Creates the tensor on a default [Cpu]. See [Cpu::from_ndarray].
+    fn try_from(array: ArrayBase<OwnedRepr<E>, D>) -> Result<Self, Self::Error> {
+        Cpu::default().from_ndarray(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+    use ::ndarray::{array, s, Array2};
+    use std::vec;
+
+    #[test]
+    fn test_ndarray_round_trip() {
+        let dev: Cpu = Default::default();
+        let t: Tensor<Rank2<2, 3>> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let ptr = t.storage.data.as_ptr();
+        let a: ArrayD<f32> = t.into();
+        assert_eq!(a.as_ptr(), ptr);
+        assert_eq!(a, array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn());
+
+        let ptr = a.as_ptr();
+        let t: Tensor<Rank2<2, 3>> = dev.from_ndarray(a).unwrap();
+        assert_eq!(t.storage.data.as_ptr(), ptr);
+        assert_eq!(t.array(), [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    }
+
+    #[test]
+    fn test_as_ndarray_strided() {
+        let dev: Cpu = Default::default();
+        let t: Tensor<Rank2<2, 3>> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let p = t.clone().permute::<Rank2<3, 2>, _>();
+        assert_eq!(
+            p.as_ndarray(),
+            array![[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]].into_dyn()
+        );
+        let a: ArrayD<f32> = p.into();
+        assert!(a.is_standard_layout());
+        assert_eq!(a, array![[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]].into_dyn());
+
+        let b: Tensor<Rank2<2, 3>> = dev.tensor([1.0, 2.0, 3.0]).broadcast();
+        assert_eq!(
+            b.as_ndarray(),
+            array![[1.0, 2.0, 3.0], [1.0, 2.0, 3.0]].into_dyn()
+        );
+        assert_eq!(
+            b.to_ndarray().as_slice().unwrap(),
+            &[1.0, 2.0, 3.0, 1.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn test_from_ndarray_layouts() {
+        let dev: Cpu = Default::default();
+        let a: Array2<f32> = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+
+        let t: Tensor<(usize, usize)> = dev.from_ndarray(a.clone().reversed_axes()).unwrap();
+        assert_eq!(t.shape(), &(3, 2));
+        assert_eq!(t.as_vec(), [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+
+        let mut sliced = a.clone();
+        sliced.slice_collapse(s![1.., ..]);
+        let t: Tensor<(Const<1>, usize)> = dev.from_ndarray(sliced).unwrap();
+        assert_eq!(t.as_vec(), [4.0, 5.0, 6.0]);
+
+        let t: Tensor<Rank2<2, 3>> = a.clone().try_into().unwrap();
+        assert_eq!(t.array(), [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+        let r: Result<Tensor<Rank2<3, 2>>, _> = dev.from_ndarray(a.clone());
+        assert_eq!(r.unwrap_err(), NdarrayShapeError { found: vec![2, 3] });
+        let r: Result<Tensor<Rank1<6>>, _> = dev.from_ndarray(a);
+        assert!(r.is_err());
+    }
+}
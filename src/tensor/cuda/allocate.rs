@@ -99,7 +99,7 @@ where
     ) -> Result<(), Self::Err> {
         let mut host_vec = std::vec![Default::default(); storage.data.len()];
         {
-            let mut rng = self.cpu.rng.lock().unwrap();
+            let mut rng = self.cpu.rng();
             host_vec.fill_with(|| rng.sample(&distr));
         }
         self.dev
@@ -138,6 +138,16 @@ where
     }
 }
 
+impl<E: Unit> TensorFromVec<E> for Cuda {
+    fn try_tensor_from_vec<S: Shape>(
+        &self,
+        src: Vec<E>,
+        shape: S,
+    ) -> Result<Tensor<S, E, Self>, Self::Err> {
+        self.take_cpu_tensor(self.cpu.try_tensor_from_vec(src, shape)?)
+    }
+}
+
 impl<S: Shape, E: Unit> AsArray for CudaArray<S, E>
 where
     StridedArray<S, E>: AsArray,
@@ -1,5 +1,6 @@
 use crate::shapes::{Dtype, HasDtype, HasShape, HasUnitType, Shape, Unit};
 use crate::tensor::cpu::{Cpu, CpuError};
+use crate::tensor::replay::Recorder;
 use crate::tensor::storage_traits::{DeviceStorage, HasErr};
 
 use cudarc::{
@@ -45,6 +46,7 @@ pub struct Cuda {
     pub(crate) cpu: Cpu,
     pub(crate) dev: Arc<CudaDevice>,
     pub(crate) blas: Arc<CudaBlas>,
+    pub(crate) recorder: Arc<Recorder<Cuda>>,
 }
 
 impl Default for Cuda {
@@ -68,7 +70,12 @@ impl Cuda {
         let cpu = Cpu::seed_from_u64(seed);
         let dev = CudaDeviceBuilder::new(ordinal).build()?;
         let blas = Arc::new(CudaBlas::new(dev.clone())?);
-        Ok(Self { cpu, dev, blas })
+        Ok(Self {
+            cpu,
+            dev,
+            blas,
+            recorder: Default::default(),
+        })
     }
 }
 
@@ -124,4 +131,8 @@ impl DeviceStorage for Cuda {
     fn random_u64(&self) -> u64 {
         self.cpu.random_u64()
     }
+
+    fn recorder(&self) -> &Recorder<Self> {
+        &self.recorder
+    }
 }
@@ -31,6 +31,16 @@
 //! let t = dev.tensor([1.0, 2.0, 3.0]);
 //! ```
 //!
+//! ### From a Vec
+//!
+//! See [TensorFromVec]. This also works for shapes with runtime dimensions.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let t: Tensor<(usize, Const<2>)> = dev.tensor_from_vec(vec![1.0; 6], (3, Const));
+//! ```
+//!
 //! ### Filled with 0s or 1s
 //!
 //! See [ZerosTensor] and [OnesTensor].
@@ -68,6 +78,9 @@
 //! a.copy_from(&buf);
 //! ```
 //!
+//! With the "ndarray" feature, tensors on the [Cpu] can be converted to and from `ndarray`
+//! arrays with `Tensor::as_ndarray()` and `Cpu::from_ndarray()`, or the `From`/`TryFrom` impls.
+//!
 //! # Modifying an already constructed tensor
 //!
 //! There are only a few ways to do this, as normally you should just create a new tensor with tensor_ops.
@@ -87,6 +100,19 @@
 //! let t: [[f32; 3]; 2] = t.array();
 //! ```
 //!
+//! # Views
+//!
+//! Tensors are stored with strides, so a number of operations return views that share
+//! data with their input instead of copying it:
+//! - [crate::tensor_ops::PermuteTo] only permutes the strides
+//! - [crate::tensor_ops::BroadcastTo] uses a stride of 0 for the new axes
+//! - [crate::tensor_ops::ReshapeTo] of a contiguous tensor only changes the shape
+//! - [Tensor::slice()] and [Tensor::narrow()] point into the buffer of their input (on the
+//!   [Cpu]; other devices copy the slice)
+//!
+//! All kernels understand strides, so views can be used like any other tensor. Shared
+//! data is copied on write, so modifying a view never changes the tensor it came from.
+//!
 //! # Tracking gradients
 //!
 //! Use the [Tensor::trace] or [Tensor::traced] methods to add [crate::gradients::OwnedTape] to the [Tensor].
@@ -109,7 +135,23 @@
 //!
 //! You can also use [Tensor::write_to_npz] and [Tensor::read_from_npz] when working with
 //! zip archives.
+//!
+//! With the "mmap" feature, `Cpu::mmap_npy()` and `MmapNpz` create tensors whose data is
+//! memory mapped from a file instead of read into memory. These are `unsafe`, because the file
+//! must not change while it is mapped.
+//!
+//! # Serialization to the binary format
+//!
+//! [binary] is a versioned format with checksums and optional zstd compression, see
+//! [Tensor::save_binary] and [Tensor::load_binary]. Models can be saved with
+//! [crate::nn::SaveToBinary].
+//!
+//! # Type erased tensors
+//!
+//! [AnyTensor] stores a tensor together with its dtype and shape at runtime, which is
+//! what [crate::nn::DynModule] passes around.
 
+mod any_tensor;
 pub(crate) mod cpu;
 mod tensor_impls;
 
@@ -119,16 +161,29 @@ pub(crate) mod cuda;
 #[cfg(feature = "numpy")]
 pub(crate) mod numpy;
 
+#[cfg(feature = "std")]
+pub mod binary;
+
+pub(crate) mod replay;
+mod sparse;
 pub(crate) mod storage_traits;
 
 pub(crate) use storage_traits::{OneFillStorage, ZeroFillStorage};
 
-pub use cpu::{Cpu, CpuError};
+pub use any_tensor::{AnyTensor, AnyTensorError};
+pub use cpu::{Cpu, CpuError, CpuMatMulBackend, StridedArray};
+
+#[cfg(all(feature = "mmap", unix))]
+pub use cpu::MmapNpz;
+
+#[cfg(feature = "ndarray")]
+pub use cpu::NdarrayShapeError;
 
 #[cfg(feature = "cuda")]
 pub use cuda::{Cuda, CudaError};
 
-pub use storage_traits::{AsArray, AsVec, CopySlice, TensorFromArray};
+pub use sparse::{CooTensor, CsrTensor};
+pub use storage_traits::{AsArray, AsVec, CopySlice, TensorFromArray, TensorFromVec};
 pub use storage_traits::{DeviceStorage, HasErr};
 pub use storage_traits::{OnesTensor, SampleTensor, ZerosTensor};
 
@@ -221,6 +276,24 @@ mod tests {
         assert_eq!(t.array(), [[1.0, 2.0], [3.0, 4.0]]);
     }
 
+    #[test]
+    fn test_convert_vec() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize, Const<2>), f32, _> =
+            dev.tensor_from_vec(alloc::vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], (3, Const));
+        assert_eq!(t.shape(), &(3, Const));
+        assert_eq!(t.as_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let t = Tensor::from_vec_with_shape(&dev, alloc::vec![1.0, 2.0], (Const::<2>,));
+        assert_eq!(t.array(), [1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_convert_vec_wrong_len() {
+        let dev: TestDevice = Default::default();
+        let _: Tensor<(usize,), f32, _> = dev.tensor_from_vec(alloc::vec![1.0, 2.0], (3,));
+    }
+
     #[test]
     fn fuzz_test_rand() {
         let dev: TestDevice = Default::default();
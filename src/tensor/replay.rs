@@ -0,0 +1,128 @@
+//! Records the kernels that a forward pass runs, so that [crate::nn::compile()] can replay
+//! them without running the module code again.
+
+use crate::{
+    shapes::{Shape, Unit},
+    unique_id::UniqueId,
+};
+
+use super::{DeviceStorage, HasErr, Tensor};
+
+use core::any::Any;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::{boxed::Box, collections::BTreeMap, sync::Mutex, vec::Vec};
+
+/// Runs one recorded kernel, reading its inputs from and writing its output to the buffers.
+pub type ReplayFn<D> =
+    Box<dyn Fn(&mut ReplayBuffers) -> Result<(), <D as HasErr>::Err> + Send + Sync>;
+
+/// A kernel recorded by [Recorder::record()].
+pub struct ReplayStep<D: HasErr> {
+    /// Used in the panic messages of [crate::nn::compile()].
+    pub name: &'static str,
+    pub inputs: Vec<UniqueId>,
+    pub output: UniqueId,
+    pub run: ReplayFn<D>,
+}
+
+/// The storages of the tensors computed while replaying, by the id of the tensor they were
+/// computed for during the trace.
+#[derive(Default)]
+pub struct ReplayBuffers(BTreeMap<UniqueId, Box<dyn Any>>);
+
+impl ReplayBuffers {
+    /// The storage computed for `t`, or the storage of `t` itself if it wasn't computed while
+    /// replaying, like a parameter of the module.
+    pub fn get<'a, S: Shape, E: Unit, D: DeviceStorage>(
+        &'a self,
+        t: &'a Tensor<S, E, D>,
+    ) -> &'a D::Storage<S, E> {
+        self.0
+            .get(&t.id)
+            .and_then(|s| s.downcast_ref())
+            .unwrap_or(&t.storage)
+    }
+
+    pub fn insert<S: Shape, E: Unit, D: DeviceStorage>(
+        &mut self,
+        id: UniqueId,
+        storage: D::Storage<S, E>,
+    ) {
+        self.0.insert(id, Box::new(storage));
+    }
+
+    pub fn remove<S: Shape, E: Unit, D: DeviceStorage>(
+        &mut self,
+        id: &UniqueId,
+    ) -> Option<D::Storage<S, E>> {
+        self.0
+            .remove(id)
+            .and_then(|s| s.downcast().ok())
+            .map(|s| *s)
+    }
+}
+
+/// Owned by each device, and shared between its clones. Only records while
+/// [Recorder::start()] is active, otherwise [Recorder::record()] is a single atomic load.
+pub struct Recorder<D: HasErr> {
+    active: AtomicBool,
+    steps: Mutex<Vec<ReplayStep<D>>>,
+}
+
+impl<D: HasErr> Default for Recorder<D> {
+    fn default() -> Self {
+        Self {
+            active: AtomicBool::new(false),
+            steps: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<D: HasErr> std::fmt::Debug for Recorder<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Recorder")
+            .field("active", &self.active.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl<D: HasErr> Recorder<D> {
+    /// Locks the steps. Without the "std" feature this is a spin lock.
+    fn steps(&self) -> impl core::ops::DerefMut<Target = Vec<ReplayStep<D>>> + '_ {
+        #[cfg(feature = "std")]
+        {
+            self.steps.lock().unwrap()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.steps.lock()
+        }
+    }
+
+    pub fn start(&self) {
+        self.steps().clear();
+        self.active.store(true, Ordering::Relaxed);
+    }
+
+    pub fn stop(&self) -> Vec<ReplayStep<D>> {
+        self.active.store(false, Ordering::Relaxed);
+        core::mem::take(&mut *self.steps())
+    }
+
+    /// Records the kernel that computed the tensor with id `output` from `inputs`. `run` is
+    /// only called while recording, and creates the function that recomputes it.
+    pub fn record<F, R>(&self, name: &'static str, inputs: &[UniqueId], output: UniqueId, run: R)
+    where
+        F: 'static + Fn(&mut ReplayBuffers) -> Result<(), D::Err> + Send + Sync,
+        R: FnOnce() -> F,
+    {
+        if self.active.load(Ordering::Relaxed) {
+            self.steps().push(ReplayStep {
+                name,
+                inputs: inputs.into(),
+                output,
+                run: Box::new(run()),
+            });
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{
+    optim::{GradientUpdate, ParamUpdater, UnusedTensors},
+    shapes::{Dim, Dtype, HasShape, Unit},
+};
+
+use super::{AsVec, CopySlice, Cpu, DeviceStorage, Tensor, ZerosTensor};
+
+use std::vec::Vec;
+
+/// A sparse matrix of shape `(M, N)` in coordinate (COO) format, i.e. a list of
+/// `(row, col, value)` triplets. Entries that aren't listed are 0, and duplicate
+/// entries are summed.
+///
+/// This is the easiest format to build, convert it with [CooTensor::to_csr()] to do math with it.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = CooTensor::new(&dev, (Const::<2>, Const::<3>), &[0, 1, 1], &[2, 0, 2], &[1.0, 2.0, 3.0]);
+/// assert_eq!(a.nnz(), 3);
+/// assert_eq!(a.to_dense().array(), [[0.0, 0.0, 1.0], [2.0, 0.0, 3.0]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CooTensor<M: Dim, N: Dim, E: Unit = f32, D: DeviceStorage = Cpu> {
+    pub(crate) shape: (M, N),
+    pub(crate) rows: Tensor<(usize,), usize, D>,
+    pub(crate) cols: Tensor<(usize,), usize, D>,
+    pub(crate) values: Tensor<(usize,), E, D>,
+}
+
+/// A sparse matrix of shape `(M, N)` in compressed sparse row (CSR) format. The non zero
+/// entries of row `i` are at positions `row_ptr[i]..row_ptr[i + 1]` of the column indices
+/// and values, sorted by column.
+///
+/// Create one with [CooTensor::to_csr()], and multiply it with dense matrices with [CsrTensor::matmul()].
+#[derive(Debug, Clone)]
+pub struct CsrTensor<M: Dim, N: Dim, E: Unit = f32, D: DeviceStorage = Cpu> {
+    pub(crate) shape: (M, N),
+    pub(crate) row_ptr: Tensor<(usize,), usize, D>,
+    pub(crate) col_idx: Tensor<(usize,), usize, D>,
+    pub(crate) values: Tensor<(usize,), E, D>,
+}
+
+fn vec_tensor<E: Unit, D: ZerosTensor<E> + CopySlice<E>>(
+    device: &D,
+    data: &[E],
+) -> Result<Tensor<(usize,), E, D>, D::Err> {
+    let mut t = device.try_zeros_like(&(data.len(),))?;
+    t.copy_from(data);
+    Ok(t)
+}
+
+impl<M: Dim, N: Dim, E: Unit, D> CooTensor<M, N, E, D>
+where
+    D: ZerosTensor<usize> + ZerosTensor<E> + CopySlice<usize> + CopySlice<E>,
+{
+    /// Creates a sparse matrix with `values[i]` at `(rows[i], cols[i])`.
+    ///
+    /// **Panics** if the slices have different lengths, or an index is out of bounds.
+    pub fn new(device: &D, shape: (M, N), rows: &[usize], cols: &[usize], values: &[E]) -> Self {
+        Self::try_new(device, shape, rows, cols, values).unwrap()
+    }
+
+    /// Fallible version of [CooTensor::new()]
+    pub fn try_new(
+        device: &D,
+        shape: (M, N),
+        rows: &[usize],
+        cols: &[usize],
+        values: &[E],
+    ) -> Result<Self, D::Err> {
+        assert_eq!(rows.len(), values.len(), "Expected one row index per value");
+        assert_eq!(
+            cols.len(),
+            values.len(),
+            "Expected one column index per value"
+        );
+        let (m, n) = (shape.0.size(), shape.1.size());
+        for (&r, &c) in rows.iter().zip(cols.iter()) {
+            assert!(
+                r < m && c < n,
+                "Index ({r}, {c}) is out of bounds for sparse matrix of shape ({m}, {n})"
+            );
+        }
+        Ok(Self {
+            shape,
+            rows: vec_tensor(device, rows)?,
+            cols: vec_tensor(device, cols)?,
+            values: vec_tensor(device, values)?,
+        })
+    }
+
+    /// Creates a sparse matrix from the non zero entries of `dense`.
+    pub fn from_dense<T>(dense: &Tensor<(M, N), E, D, T>) -> Self {
+        Self::try_from_dense(dense).unwrap()
+    }
+
+    /// Fallible version of [CooTensor::from_dense()]
+    pub fn try_from_dense<T>(dense: &Tensor<(M, N), E, D, T>) -> Result<Self, D::Err> {
+        let shape = *dense.shape();
+        let n = shape.1.size();
+        let mut data = alloc::vec![Default::default(); shape.0.size() * n];
+        dense.copy_into(&mut data);
+        let (mut rows, mut cols, mut values) = (Vec::new(), Vec::new(), Vec::new());
+        for (i, &v) in data.iter().enumerate() {
+            if v != E::default() {
+                rows.push(i / n);
+                cols.push(i % n);
+                values.push(v);
+            }
+        }
+        Self::try_new(&dense.device, shape, &rows, &cols, &values)
+    }
+}
+
+impl<M: Dim, N: Dim, E: Unit, D: DeviceStorage> CooTensor<M, N, E, D> {
+    /// The shape of the matrix.
+    pub fn shape(&self) -> &(M, N) {
+        &self.shape
+    }
+
+    /// The number of stored entries (including duplicates and explicit zeros).
+    pub fn nnz(&self) -> usize {
+        self.values.shape().0
+    }
+
+    /// The row index of each entry.
+    pub fn rows(&self) -> &Tensor<(usize,), usize, D> {
+        &self.rows
+    }
+
+    /// The column index of each entry.
+    pub fn cols(&self) -> &Tensor<(usize,), usize, D> {
+        &self.cols
+    }
+
+    /// The value of each entry.
+    pub fn values(&self) -> &Tensor<(usize,), E, D> {
+        &self.values
+    }
+
+    /// Transposes the matrix, which only swaps the row & column indices.
+    pub fn transpose(self) -> CooTensor<N, M, E, D> {
+        CooTensor {
+            shape: (self.shape.1, self.shape.0),
+            rows: self.cols,
+            cols: self.rows,
+            values: self.values,
+        }
+    }
+}
+
+impl<M: Dim, N: Dim, E: Dtype, D> CooTensor<M, N, E, D>
+where
+    D: ZerosTensor<usize> + ZerosTensor<E> + CopySlice<usize> + CopySlice<E>,
+    D::Storage<(usize,), usize>: AsVec<Unit = usize>,
+    D::Storage<(usize,), E>: AsVec<Unit = E>,
+{
+    /// Converts to [CsrTensor], summing duplicate entries. This sorts the entries on the host.
+    pub fn to_csr(&self) -> CsrTensor<M, N, E, D> {
+        self.try_to_csr().unwrap()
+    }
+
+    /// Fallible version of [CooTensor::to_csr()]
+    pub fn try_to_csr(&self) -> Result<CsrTensor<M, N, E, D>, D::Err> {
+        let (rows, cols, values) = (self.rows.as_vec(), self.cols.as_vec(), self.values.as_vec());
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by_key(|&i| (rows[i], cols[i]));
+
+        let mut row_ptr = alloc::vec![0; self.shape.0.size() + 1];
+        let mut col_idx: Vec<usize> = Vec::with_capacity(order.len());
+        let mut csr_values: Vec<E> = Vec::with_capacity(order.len());
+        let mut last = None;
+        for i in order {
+            if last == Some((rows[i], cols[i])) {
+                *csr_values.last_mut().unwrap() += values[i];
+            } else {
+                row_ptr[rows[i] + 1] += 1;
+                col_idx.push(cols[i]);
+                csr_values.push(values[i]);
+                last = Some((rows[i], cols[i]));
+            }
+        }
+        for i in 0..self.shape.0.size() {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+
+        let device = &self.values.device;
+        Ok(CsrTensor {
+            shape: self.shape,
+            row_ptr: vec_tensor(device, &row_ptr)?,
+            col_idx: vec_tensor(device, &col_idx)?,
+            values: vec_tensor(device, &csr_values)?,
+        })
+    }
+
+    /// Converts to a dense tensor, summing duplicate entries.
+    pub fn to_dense(&self) -> Tensor<(M, N), E, D> {
+        self.try_to_dense().unwrap()
+    }
+
+    /// Fallible version of [CooTensor::to_dense()]
+    pub fn try_to_dense(&self) -> Result<Tensor<(M, N), E, D>, D::Err> {
+        let n = self.shape.1.size();
+        let mut data = alloc::vec![E::default(); self.shape.0.size() * n];
+        let (rows, cols, values) = (self.rows.as_vec(), self.cols.as_vec(), self.values.as_vec());
+        for ((r, c), v) in rows.into_iter().zip(cols).zip(values) {
+            data[r * n + c] += v;
+        }
+        let mut dense = self.values.device.try_zeros_like(&self.shape)?;
+        dense.copy_from(&data);
+        Ok(dense)
+    }
+}
+
+impl<M: Dim, N: Dim, E: Unit, D: DeviceStorage> CsrTensor<M, N, E, D> {
+    /// The shape of the matrix.
+    pub fn shape(&self) -> &(M, N) {
+        &self.shape
+    }
+
+    /// The number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.shape().0
+    }
+
+    /// The offsets of each row into [CsrTensor::col_idx()] and [CsrTensor::values()], of length `M + 1`.
+    pub fn row_ptr(&self) -> &Tensor<(usize,), usize, D> {
+        &self.row_ptr
+    }
+
+    /// The column index of each entry.
+    pub fn col_idx(&self) -> &Tensor<(usize,), usize, D> {
+        &self.col_idx
+    }
+
+    /// The value of each entry.
+    pub fn values(&self) -> &Tensor<(usize,), E, D> {
+        &self.values
+    }
+}
+
+/// Visits [CsrTensor::values()], so the non zero entries of a sparse matrix can be trained
+/// with optimizers. See [CsrTensor::matmul()].
+impl<M: Dim, N: Dim, E: Dtype, D: DeviceStorage> GradientUpdate<D, E> for CsrTensor<M, N, E, D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        self.values.update(updater, unused)
+    }
+}
+
+impl<M: Dim, N: Dim, E: Dtype, D> CsrTensor<M, N, E, D>
+where
+    D: ZerosTensor<usize> + ZerosTensor<E> + CopySlice<usize> + CopySlice<E>,
+    D::Storage<(usize,), usize>: AsVec<Unit = usize>,
+{
+    /// Converts to [CooTensor].
+    pub fn to_coo(&self) -> CooTensor<M, N, E, D> {
+        self.try_to_coo().unwrap()
+    }
+
+    /// Fallible version of [CsrTensor::to_coo()]
+    pub fn try_to_coo(&self) -> Result<CooTensor<M, N, E, D>, D::Err> {
+        let row_ptr = self.row_ptr.as_vec();
+        let mut rows = Vec::with_capacity(self.nnz());
+        for i in 0..self.shape.0.size() {
+            rows.resize(row_ptr[i + 1], i);
+        }
+        Ok(CooTensor {
+            shape: self.shape,
+            rows: vec_tensor(&self.values.device, &rows)?,
+            cols: self.col_idx.clone(),
+            values: self.values.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+
+    #[test]
+    fn test_coo_to_csr_sums_duplicates() {
+        let dev: TestDevice = Default::default();
+        let a = CooTensor::new(
+            &dev,
+            (Const::<3>, Const::<4>),
+            &[2, 0, 2, 0, 2],
+            &[1, 3, 0, 3, 1],
+            &[1.0, 2.0, 3.0, 4.0, 5.0],
+        );
+        assert_eq!(a.nnz(), 5);
+        let dense = [[0.0, 0.0, 0.0, 6.0], [0.0; 4], [3.0, 6.0, 0.0, 0.0]];
+        assert_eq!(a.to_dense().array(), dense);
+
+        let csr = a.to_csr();
+        assert_eq!(csr.nnz(), 3);
+        assert_eq!(csr.row_ptr().as_vec(), [0, 1, 1, 3]);
+        assert_eq!(csr.col_idx().as_vec(), [3, 0, 1]);
+        assert_eq!(csr.values().as_vec(), [6.0, 3.0, 6.0]);
+
+        let coo = csr.to_coo();
+        assert_eq!(coo.rows().as_vec(), [0, 2, 2]);
+        assert_eq!(coo.to_dense().array(), dense);
+    }
+
+    #[test]
+    fn test_from_dense_and_transpose() {
+        let dev: TestDevice = Default::default();
+        let dense = dev.tensor([[0.0, 1.0, 0.0], [2.0, 0.0, 3.0]]);
+        let a = CooTensor::from_dense(&dense);
+        assert_eq!(a.nnz(), 3);
+        assert_eq!(a.to_dense().array(), dense.array());
+        let t = a.transpose();
+        assert_eq!(t.shape(), &(Const::<3>, Const::<2>));
+        assert_eq!(t.to_dense().array(), [[0.0, 2.0], [1.0, 0.0], [0.0, 3.0]]);
+    }
+
+    #[test]
+    fn test_runtime_shape() {
+        let dev: TestDevice = Default::default();
+        let a: CooTensor<usize, usize, f32, _> =
+            CooTensor::new(&dev, (2, 1000), &[1, 0], &[999, 5], &[1.0, 2.0]);
+        let csr = a.to_csr();
+        assert_eq!(csr.row_ptr().as_vec(), [0, 1, 2]);
+        assert_eq!(csr.col_idx().as_vec(), [5, 999]);
+    }
+
+    #[test]
+    #[should_panic = "Index (2, 0) is out of bounds for sparse matrix of shape (2, 3)"]
+    fn test_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let _ = CooTensor::new(&dev, (Const::<2>, Const::<3>), &[2], &[0], &[1.0]);
+    }
+}
@@ -6,26 +6,32 @@ use crate::{
     unique_id::unique_id,
 };
 
-use super::Tensor;
+use super::{replay::Recorder, Tensor};
 
 /// Represents something that has an error associated type
 pub trait HasErr: Sized {
     type Err: std::fmt::Debug + std::fmt::Display;
 }
 
-/// Something that can store nd arrays for a given [Shape] and [Dtype]
-pub trait DeviceStorage: 'static + Default + Clone + HasErr {
+/// Something that can store nd arrays for a given [Shape] and [Dtype]. Devices are [Send] and [Sync],
+/// so tensors without a tape can be shared between threads.
+pub trait DeviceStorage: 'static + Default + Clone + Send + Sync + HasErr {
     /// Generic storage type
     type Storage<S: Shape, E: Unit>: 'static
         + std::fmt::Debug
         + Clone
         + Send
         + Sync
-        + HasShape<Shape = S>;
+        + HasShape<Shape = S>
+        + HasUnitType<Unit = E>
+        + AsVec;
 
     /// Generates a random u64 number
     fn random_u64(&self) -> u64;
 
+    /// Records the kernels run on this device while [crate::nn::compile()] traces a module.
+    fn recorder(&self) -> &Recorder<Self>;
+
     /// Allocates a gradient for the given nd array
     fn try_alloc_grad<S: Shape, E: Dtype>(
         &self,
@@ -247,6 +253,37 @@ pub trait TensorFromArray<Src, S: Shape, E: Unit>: DeviceStorage {
     fn try_tensor(&self, src: Src) -> Result<Tensor<S, E, Self>, Self::Err>;
 }
 
+/// Construct tensors from a [std::vec::Vec] and a runtime shape. This is how to make
+/// tensors with dynamic dimensions, which can't be created from rust arrays.
+pub trait TensorFromVec<E: Unit>: DeviceStorage {
+    /// Create a tensor from a contiguous, row major buffer. On [crate::tensor::Cpu] the buffer
+    /// is moved into the tensor without copying.
+    ///
+    /// **Panics** if `src.len()` isn't the number of elements in `shape`.
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<(usize, Const<2>)> = dev.tensor_from_vec(vec![1.0, 2.0, 3.0, 4.0], (2, Const));
+    /// assert_eq!(t.shape(), &(2, Const));
+    /// ```
+    fn tensor_from_vec<S: Shape>(&self, src: std::vec::Vec<E>, shape: S) -> Tensor<S, E, Self> {
+        self.try_tensor_from_vec(src, shape).unwrap()
+    }
+    /// Fallible version of [TensorFromVec::tensor_from_vec]
+    fn try_tensor_from_vec<S: Shape>(
+        &self,
+        src: std::vec::Vec<E>,
+        shape: S,
+    ) -> Result<Tensor<S, E, Self>, Self::Err>;
+}
+
+impl<S: Shape, E: Unit, D: TensorFromVec<E>> Tensor<S, E, D> {
+    /// Creates a tensor on `device` from a row major buffer. See [TensorFromVec::tensor_from_vec].
+    pub fn from_vec_with_shape(device: &D, src: std::vec::Vec<E>, shape: S) -> Self {
+        device.tensor_from_vec(src, shape)
+    }
+}
+
 /// Convert tensors to rust arrays
 pub trait AsArray {
     type Array: std::fmt::Debug;
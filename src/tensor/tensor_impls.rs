@@ -39,7 +39,7 @@ pub struct Tensor<S: Shape, E: Unit = f32, D: DeviceStorage = Cpu, T = NoneTape>
     pub(crate) tape: T,
 }
 
-impl<S: Shape, E: Dtype, D: DeviceStorage, T> HasShape for Tensor<S, E, D, T> {
+impl<S: Shape, E: Unit, D: DeviceStorage, T> HasShape for Tensor<S, E, D, T> {
     type WithShape<New: Shape> = Tensor<New, E, D, T>;
     type Shape = S;
     fn shape(&self) -> &Self::Shape {
@@ -55,13 +55,13 @@ impl<S: Shape, E: Dtype, D: DeviceStorage, T> HasDtype for Tensor<S, E, D, T> {
     type Dtype = E;
 }
 
-impl<S: Shape, E: Dtype, D: DeviceStorage, T> HasUniqueId for Tensor<S, E, D, T> {
+impl<S: Shape, E: Unit, D: DeviceStorage, T> HasUniqueId for Tensor<S, E, D, T> {
     fn id(&self) -> &UniqueId {
         &self.id
     }
 }
 
-impl<S: Shape, E: Dtype, D: DeviceStorage, T> HasErr for Tensor<S, E, D, T> {
+impl<S: Shape, E: Unit, D: DeviceStorage, T> HasErr for Tensor<S, E, D, T> {
     type Err = D::Err;
 }
 
@@ -74,6 +74,16 @@ impl<S: Shape, E: Dtype, D: DeviceStorage> Tensor<S, E, D, NoneTape> {
     pub fn traced(self) -> Tensor<S, E, D, OwnedTape<D>> {
         self.put_tape(Default::default())
     }
+    /// Like [Tensor::trace()], but the tape also records every operation, so that
+    /// [OwnedTape::summary()] can describe them. See [OwnedTape::with_summary()].
+    pub fn trace_with_summary(&self) -> Tensor<S, E, D, OwnedTape<D>> {
+        self.clone().put_tape(OwnedTape::with_summary())
+    }
+    /// Like [Tensor::trace()], but the backward pass panics at the first operation that
+    /// produces a NaN or infinite gradient. See [OwnedTape::with_anomaly_detection()].
+    pub fn trace_with_anomaly_detection(&self) -> Tensor<S, E, D, OwnedTape<D>> {
+        self.clone().put_tape(OwnedTape::with_anomaly_detection())
+    }
 }
 
 impl<S: Shape, E: Dtype, D: DeviceStorage, T: Tape<D>> Tensor<S, E, D, T> {
@@ -1,4 +1,8 @@
-use crate::tensor_ops::cpu_kernels::{BinaryDerivative, UnaryDerivative};
+use super::TryAdd;
+use crate::{
+    tensor::{Cpu, DeviceStorage},
+    tensor_ops::cpu_kernels::{int_arithmetic, BinaryDerivative, UnaryDerivative},
+};
 
 impl BinaryDerivative<f32> for super::BinaryAddKernelOp {
     #[inline(always)]
@@ -23,3 +27,5 @@ impl UnaryDerivative<f32> for super::ScalarAddKernelOp<f32> {
         1.0
     }
 }
+
+int_arithmetic!(TryAdd, try_add, wrapping_add, [i32, i64, u8]);
@@ -3,11 +3,11 @@ mod cpu_kernel;
 #[cfg(feature = "cuda")]
 mod cuda_kernel;
 
-use super::{ops::*, Device};
+use super::ops::*;
 use crate::{
     gradients::*,
     shapes::*,
-    tensor::{HasErr, Tensor},
+    tensor::{DeviceStorage, HasErr, Tensor},
 };
 
 #[repr(C)]
@@ -39,10 +39,13 @@ pub struct ScalarAddKernelOp<E> {
 /// let r = a + 1.0;
 /// assert_eq!(r.array(), [[2.0, 3.0, 4.0], [0.0, -1.0, -2.0]]);
 /// ```
-pub fn add<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
+pub fn add<S: Shape, E: Dtype, D, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
-) -> Tensor<S, E, D, T> {
+) -> Tensor<S, E, D, T>
+where
+    D: BinaryKernel<BinaryAddKernelOp, E>,
+{
     lhs + rhs
 }
 
@@ -51,9 +54,10 @@ pub trait TryAdd<Rhs = Self>: HasErr {
     fn try_add(self, rhs: Rhs) -> Result<Self, Self::Err>;
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LhsTape: Tape<D>, RhsTape: Tape<D>>
-    TryAdd<Tensor<S, E, D, RhsTape>> for Tensor<S, E, D, LhsTape>
+impl<S: Shape, E: Dtype, D, LhsTape: Tape<D>, RhsTape: Tape<D>> TryAdd<Tensor<S, E, D, RhsTape>>
+    for Tensor<S, E, D, LhsTape>
 where
+    D: BinaryKernel<BinaryAddKernelOp, E>,
     LhsTape: Merge<RhsTape>,
 {
     /// See [add]
@@ -62,14 +66,17 @@ where
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> TryAdd<E> for Tensor<S, E, D, T> {
+impl<S: Shape, E: Dtype, D, T: Tape<D>> TryAdd<E> for Tensor<S, E, D, T>
+where
+    D: UnaryKernel<ScalarAddKernelOp<E>, E>,
+{
     /// See [add]
     fn try_add(self, rhs: E) -> Result<Self, Self::Err> {
         try_unary_op(ScalarAddKernelOp { scalar: rhs }, self)
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LhsTape: Tape<D>, Rhs> std::ops::Add<Rhs>
+impl<S: Shape, E: Dtype, D: DeviceStorage, LhsTape: Tape<D>, Rhs> std::ops::Add<Rhs>
     for Tensor<S, E, D, LhsTape>
 where
     Self: TryAdd<Rhs>,
@@ -197,4 +204,18 @@ mod tests {
         let g = r.exp().sum().backward();
         assert_eq!(g.get(&x).array(), [[1.6487212; 2]; 3]);
     }
+
+    #[test]
+    fn test_add_int() {
+        let dev: Cpu = Default::default();
+        let a = dev.tensor([1i32, -2, 3]);
+        let b = dev.tensor([4i32, 5, -6]);
+        assert_eq!((a.clone() + b).array(), [5, 3, -3]);
+        assert_eq!((a + 1).array(), [2, -1, 4]);
+
+        // integer arithmetic wraps
+        let c = dev.tensor([250u8, 10]);
+        assert_eq!((c + 10).array(), [4, 20]);
+        assert_eq!((dev.tensor([i64::MAX]) + 1).array(), [i64::MIN]);
+    }
 }
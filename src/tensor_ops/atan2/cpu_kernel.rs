@@ -0,0 +1,18 @@
+use crate::tensor_ops::cpu_kernels::BinaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl BinaryDerivative<f32> for super::Atan2KernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32, y: &f32) -> f32 {
+        x.atan2(*y)
+    }
+    #[inline(always)]
+    fn dfdx(&self, x: &f32, y: &f32) -> f32 {
+        y / (x * x + y * y)
+    }
+    #[inline(always)]
+    fn dfdy(&self, x: &f32, y: &f32) -> f32 {
+        -x / (x * x + y * y)
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::BinaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::Atan2KernelOp {}
+
+impl BinaryOpCudaKernel for super::Atan2KernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/atan2.ptx"));
+    const MODULE_NAME: &'static str = "atan2";
+    const FWD_FN_NAME: &'static str = "atan2_forward";
+    const BWD_FN_NAME: &'static str = "atan2_backward";
+}
@@ -0,0 +1,85 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::{ops::try_binary_op, Device};
+use crate::{gradients::*, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Atan2KernelOp;
+
+/// Element wise four quadrant arctangent of `lhs / rhs`, in radians. `lhs` is the
+/// y coordinate, and `rhs` is the x coordinate, the same as [f32::atan2].
+///
+/// The derivatives are `rhs / (lhs^2 + rhs^2)` and `-lhs / (lhs^2 + rhs^2)`.
+///
+/// **Pytorch equivalent**: `torch.atan2(a, b)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let y = dev.tensor([1.0, 1.0, -1.0]);
+/// let x = dev.tensor([1.0, -1.0, 0.0]);
+/// let r = y.atan2(x);
+/// assert_eq!(
+///     r.array(),
+///     [
+///         core::f32::consts::FRAC_PI_4,
+///         3.0 * core::f32::consts::FRAC_PI_4,
+///         -core::f32::consts::FRAC_PI_2,
+///     ]
+/// );
+/// ```
+pub fn atan2<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D> + Merge<RTape>, RTape: Tape<D>>(
+    lhs: Tensor<S, E, D, LTape>,
+    rhs: Tensor<S, E, D, RTape>,
+) -> Tensor<S, E, D, LTape> {
+    lhs.atan2(rhs)
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>> Tensor<S, E, D, LTape> {
+    /// See [atan2]
+    pub fn atan2<RTape: Tape<D>>(self, rhs: Tensor<S, E, D, RTape>) -> Self
+    where
+        LTape: Merge<RTape>,
+    {
+        self.try_atan2(rhs).unwrap()
+    }
+
+    /// See [atan2]
+    pub fn try_atan2<R: Tape<D>>(self, rhs: Tensor<S, E, D, R>) -> Result<Self, D::Err>
+    where
+        LTape: Merge<R>,
+    {
+        try_binary_op(Atan2KernelOp, self, rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_atan2() {
+        use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, -2.0, 0.0], [3.0, 0.5, -1.0]]);
+        let b = dev.tensor([[1.0, 1.0, -1.0], [-4.0, 0.0, -1.0]]);
+
+        let r = a.trace().atan2(b.clone());
+        assert_close(
+            &r.array(),
+            &[
+                [FRAC_PI_4, -1.1071488, PI],
+                [2.4980915, FRAC_PI_2, -3.0 * FRAC_PI_4],
+            ],
+        );
+
+        let g = r.sum().backward();
+        assert_close(&g.get(&a).array(), &[[0.5, 0.2, -1.0], [-0.16, 0.0, -0.5]]);
+        assert_close(&g.get(&b).array(), &[[-0.5, 0.4, 0.0], [-0.12, -2.0, 0.5]]);
+    }
+}
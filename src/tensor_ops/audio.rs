@@ -0,0 +1,393 @@
+#![allow(clippy::type_complexity)]
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use std::vec::Vec;
+
+use super::{Device, ReshapeTo, SumTo, TryAdd, TryMatMul};
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::*,
+    tensor::*,
+};
+
+/// A window function that is applied to each frame by [Tensor::stft()].
+///
+/// The windows are periodic, like the defaults of pytorch's `hann_window` and
+/// `hamming_window`, which is what spectral analysis with overlapping frames wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+}
+
+impl Window {
+    /// The `len` coefficients of the window.
+    pub fn coefficients(&self, len: usize) -> Vec<f32> {
+        let phase = |n: usize| (2.0 * core::f32::consts::PI * n as f32 / len as f32).cos();
+        (0..len)
+            .map(|n| match self {
+                Self::Rectangular => 1.0,
+                Self::Hann => 0.5 - 0.5 * phase(n),
+                Self::Hamming => 0.54 - 0.46 * phase(n),
+            })
+            .collect()
+    }
+}
+
+impl<B: Dim, L: Dim, D: Device<f32> + TensorFromVec<usize>, T: Tape<D>> Tensor<(B, L), f32, D, T> {
+    /// Splits a batch of signals into overlapping frames of length `N`, starting every
+    /// `hop` samples. There are `1 + (L - N) / hop` frames, and samples after the last full
+    /// frame are dropped. Samples that are in multiple frames sum their gradients.
+    ///
+    /// **Pytorch equivalent**: `t.unfold(1, N, hop)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<1, 6>> = dev.tensor([[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]]);
+    /// let r: Tensor<(Const<1>, usize, Const<3>)> = t.frames::<3>(2);
+    /// assert_eq!(r.as_vec(), [1.0, 2.0, 3.0, 3.0, 4.0, 5.0]);
+    /// ```
+    pub fn frames<const N: usize>(self, hop: usize) -> Tensor<(B, usize, Const<N>), f32, D, T> {
+        self.try_frames::<N>(hop).unwrap()
+    }
+
+    /// Fallible version of [Tensor::frames()]
+    pub fn try_frames<const N: usize>(
+        self,
+        hop: usize,
+    ) -> Result<Tensor<(B, usize, Const<N>), f32, D, T>, D::Err> {
+        assert!(hop > 0);
+        let (b, l) = *self.shape();
+        assert!(
+            l.size() >= N,
+            "Signal of length {} is shorter than a frame of length {N}",
+            l.size()
+        );
+        let num_frames = 1 + (l.size() - N) / hop;
+        let mut idx = Vec::with_capacity(num_frames * N);
+        for f in 0..num_frames {
+            idx.extend(f * hop..f * hop + N);
+        }
+        let idx = self.device.try_tensor_from_vec(idx, (num_frames * N,))?;
+        self.try_index_select::<Axis<1>, _>(idx)?
+            .try_reshape_like(&(b, num_frames, Const::<N>))
+    }
+
+    /// The short-time Fourier transform of a batch of signals: the discrete Fourier
+    /// transform of each of the [Tensor::frames()] after multiplying it with `window`.
+    /// Returns the real and imaginary parts of the `K = N / 2 + 1` non negative frequencies,
+    /// with shape `(B, frames, K, 2)`.
+    ///
+    /// The transform is a matmul with the (windowed) Fourier basis, so it is differentiable
+    /// and runs on any device. This costs `O(N^2)` per frame instead of the `O(N log N)` of
+    /// an FFT, which is fine for the frame lengths of typical audio front ends.
+    ///
+    /// **Pytorch equivalent**:
+    /// `torch.view_as_real(torch.stft(t, N, hop, window=w, center=False, return_complex=True)).transpose(1, 2)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<1, 8>> = dev.tensor([[1.0, 0.0, -1.0, 0.0, 1.0, 0.0, -1.0, 0.0]]);
+    /// let r = t.stft::<4, 3>(4, Window::Rectangular);
+    /// // both frames are cos(pi n / 2), which is all in frequency 1
+    /// let expected = [0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0];
+    /// for (a, b) in r.as_vec().iter().zip(expected) {
+    ///     assert!((a - b).abs() < 1e-6);
+    /// }
+    /// ```
+    pub fn stft<const N: usize, const K: usize>(
+        self,
+        hop: usize,
+        window: Window,
+    ) -> Tensor<(B, usize, Const<K>, Const<2>), f32, D, T> {
+        self.try_stft::<N, K>(hop, window).unwrap()
+    }
+
+    /// Fallible version of [Tensor::stft()]
+    pub fn try_stft<const N: usize, const K: usize>(
+        self,
+        hop: usize,
+        window: Window,
+    ) -> Result<Tensor<(B, usize, Const<K>, Const<2>), f32, D, T>, D::Err> {
+        assert_eq!(K, N / 2 + 1, "K must be N / 2 + 1");
+        // basis[n, (k, 0)] = w[n] cos(2 pi k n / N), basis[n, (k, 1)] = -w[n] sin(2 pi k n / N)
+        let w = window.coefficients(N);
+        let mut basis = Vec::with_capacity(N * K * 2);
+        for (n, w_n) in w.iter().enumerate() {
+            for k in 0..K {
+                let theta = 2.0 * core::f32::consts::PI * ((k * n) % N) as f32 / N as f32;
+                basis.push(w_n * theta.cos());
+                basis.push(-w_n * theta.sin());
+            }
+        }
+        let mut basis_t = self.device.try_zeros_like(&(Const::<N>, K * 2))?;
+        basis_t.copy_from(&basis);
+
+        let frames = self.try_frames::<N>(hop)?;
+        let (b, f, _) = *frames.shape();
+        frames
+            .try_matmul(basis_t)?
+            .try_reshape_like(&(b, f, Const::<K>, Const::<2>))
+    }
+
+    /// The power spectrogram `re^2 + im^2` of the [Tensor::stft()], with shape
+    /// `(B, frames, K)`.
+    ///
+    /// **Pytorch equivalent**:
+    /// `torchaudio.transforms.Spectrogram(N, hop_length=hop, window_fn=w, center=False)(t).transpose(1, 2)`
+    pub fn spectrogram<const N: usize, const K: usize>(
+        self,
+        hop: usize,
+        window: Window,
+    ) -> Tensor<(B, usize, Const<K>), f32, D, T> {
+        self.try_spectrogram::<N, K>(hop, window).unwrap()
+    }
+
+    /// Fallible version of [Tensor::spectrogram()]
+    pub fn try_spectrogram<const N: usize, const K: usize>(
+        self,
+        hop: usize,
+        window: Window,
+    ) -> Result<Tensor<(B, usize, Const<K>), f32, D, T>, D::Err> {
+        self.try_stft::<N, K>(hop, window)?
+            .try_square()?
+            .try_sum::<_, Axis<3>>()
+    }
+}
+
+/// The mel scale of [HTK](https://htk.eng.cam.ac.uk/)
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10.0f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// A `(K, M)` matrix of `M` triangular filters that are evenly spaced on the mel scale
+/// between `f_min` and `f_max` Hz, for the `K` frequencies of a [Tensor::spectrogram()] of
+/// a signal sampled at `sample_rate` Hz. Multiplying a spectrogram with it gives the mel
+/// spectrogram, see [Tensor::log_mel()].
+///
+/// **Pytorch equivalent**: `torchaudio.functional.melscale_fbanks(K, f_min, f_max, M, sample_rate)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let fb: Tensor<Rank2<201, 40>> = mel_filterbank(&dev, 16000.0, 0.0, 8000.0);
+/// ```
+pub fn mel_filterbank<const K: usize, const M: usize, D: Device<f32>>(
+    dev: &D,
+    sample_rate: f32,
+    f_min: f32,
+    f_max: f32,
+) -> Tensor<(Const<K>, Const<M>), f32, D> {
+    try_mel_filterbank(dev, sample_rate, f_min, f_max).unwrap()
+}
+
+/// Fallible version of [mel_filterbank()]
+pub fn try_mel_filterbank<const K: usize, const M: usize, D: Device<f32>>(
+    dev: &D,
+    sample_rate: f32,
+    f_min: f32,
+    f_max: f32,
+) -> Result<Tensor<(Const<K>, Const<M>), f32, D>, D::Err> {
+    assert!(K > 1);
+    let (m_min, m_max) = (hz_to_mel(f_min), hz_to_mel(f_max));
+    let f_pts: Vec<f32> = (0..M + 2)
+        .map(|i| mel_to_hz(m_min + (m_max - m_min) * i as f32 / (M + 1) as f32))
+        .collect();
+    let mut data = Vec::with_capacity(K * M);
+    for k in 0..K {
+        let f = k as f32 * sample_rate / 2.0 / (K - 1) as f32;
+        for m in 0..M {
+            let up = (f - f_pts[m]) / (f_pts[m + 1] - f_pts[m]);
+            let down = (f_pts[m + 2] - f) / (f_pts[m + 2] - f_pts[m + 1]);
+            data.push(up.min(down).max(0.0));
+        }
+    }
+    let mut fb = dev.try_zeros()?;
+    fb.copy_from(&data);
+    Ok(fb)
+}
+
+impl<B: Dim, F: Dim, const K: usize, D: Device<f32>, T: Tape<D>>
+    Tensor<(B, F, Const<K>), f32, D, T>
+{
+    /// The log mel spectrogram `ln(spectrogram * filterbank + eps)` of a power
+    /// [Tensor::spectrogram()], where `filterbank` is usually from [mel_filterbank()].
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let audio: Tensor<Rank2<2, 16000>> = dev.sample_normal();
+    /// let fb = mel_filterbank::<201, 40, _>(&dev, 16000.0, 0.0, 8000.0);
+    /// let r: Tensor<(Const<2>, usize, Const<40>)> = audio
+    ///     .spectrogram::<400, 201>(160, Window::Hann)
+    ///     .log_mel(fb, 1e-6);
+    /// assert_eq!(r.shape().1, 98);
+    /// ```
+    pub fn log_mel<M: Dim, R: Tape<D>>(
+        self,
+        filterbank: Tensor<(Const<K>, M), f32, D, R>,
+        eps: f32,
+    ) -> Tensor<(B, F, M), f32, D, T>
+    where
+        T: Merge<R>,
+    {
+        self.try_log_mel(filterbank, eps).unwrap()
+    }
+
+    /// Fallible version of [Tensor::log_mel()]
+    pub fn try_log_mel<M: Dim, R: Tape<D>>(
+        self,
+        filterbank: Tensor<(Const<K>, M), f32, D, R>,
+        eps: f32,
+    ) -> Result<Tensor<(B, F, M), f32, D, T>, D::Err>
+    where
+        T: Merge<R>,
+    {
+        self.try_matmul(filterbank)?.try_add(eps)?.try_ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_frames() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 7>, f32, _> = dev.tensor([
+            [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0],
+            [-1.0, -2.0, -3.0, -4.0, -5.0, -6.0, -7.0],
+        ]);
+        let r = t.trace().frames::<3>(2);
+        assert_eq!(r.shape().1, 3);
+        assert_eq!(
+            r.as_vec(),
+            [
+                1.0, 2.0, 3.0, 3.0, 4.0, 5.0, 5.0, 6.0, 7.0, -1.0, -2.0, -3.0, -3.0, -4.0, -5.0,
+                -5.0, -6.0, -7.0
+            ]
+        );
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[1.0, 1.0, 2.0, 1.0, 2.0, 1.0, 1.0]; 2]);
+    }
+
+    #[test]
+    fn test_windows() {
+        assert_eq!(Window::Rectangular.coefficients(3), [1.0; 3]);
+        assert_close(
+            &Window::Hann.coefficients(4),
+            &alloc::vec![0.0, 0.5, 1.0, 0.5],
+        );
+        assert_close(
+            &Window::Hamming.coefficients(4),
+            &alloc::vec![0.08, 0.54, 1.0, 0.54],
+        );
+    }
+
+    #[test]
+    fn test_stft_matches_dft() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<1, 10>, f32, _> = dev.sample_normal();
+        let x = t.array()[0];
+        let r = t.trace().stft::<8, 5>(2, Window::Hann);
+        assert_eq!(r.shape().1, 2);
+
+        let w = Window::Hann.coefficients(8);
+        let r_vec = r.as_vec();
+        for f in 0..2 {
+            for k in 0..5 {
+                let (mut re, mut im) = (0.0f32, 0.0f32);
+                for n in 0..8 {
+                    let theta = 2.0 * core::f32::consts::PI * (k * n) as f32 / 8.0;
+                    re += w[n] * x[f * 2 + n] * theta.cos();
+                    im -= w[n] * x[f * 2 + n] * theta.sin();
+                }
+                assert!((r_vec[(f * 5 + k) * 2] - re).abs() < 1e-5);
+                assert!((r_vec[(f * 5 + k) * 2 + 1] - im).abs() < 1e-5);
+            }
+        }
+
+        // the DC component of the real part sums the windowed frames
+        let dc = r.narrow::<Axis<2>>(0, 1).narrow::<Axis<3>>(0, 1).sum();
+        let g = dc.backward();
+        let mut expected = [0.0; 10];
+        for f in 0..2 {
+            for n in 0..8 {
+                expected[f * 2 + n] += w[n];
+            }
+        }
+        assert_close(&g.get(&t).array(), &[expected]);
+    }
+
+    #[test]
+    fn test_spectrogram_parseval() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 8>, f32, _> = dev.sample_normal();
+        let spec = t.clone().spectrogram::<8, 5>(8, Window::Rectangular);
+        // with N = 8, bins 1..=3 stand for 2 frequencies each
+        let scale = dev.tensor([1.0, 2.0, 2.0, 2.0, 1.0]);
+        let energy = (spec * scale.broadcast_like::<_, Axes2<0, 1>>(&(Const::<3>, 1, Const::<5>)))
+            .sum::<_, Axes2<1, 2>>()
+            / 8.0;
+        let expected = t.square().sum::<_, Axis<1>>();
+        assert_close_with_tolerance(&energy.array(), &expected.array(), 1e-4);
+    }
+
+    #[test]
+    fn test_mel_filterbank() {
+        let dev: TestDevice = Default::default();
+        let fb: Tensor<Rank2<9, 3>, f32, _> = mel_filterbank(&dev, 16000.0, 0.0, 8000.0);
+        let fb = fb.array();
+        let f_pts: Vec<f32> = (0..5)
+            .map(|i| mel_to_hz(hz_to_mel(8000.0) * i as f32 / 4.0))
+            .collect();
+        for (k, row) in fb.iter().enumerate() {
+            let f = k as f32 * 1000.0;
+            for m in 0..3 {
+                let expected = if f <= f_pts[m] || f >= f_pts[m + 2] {
+                    0.0
+                } else if f <= f_pts[m + 1] {
+                    (f - f_pts[m]) / (f_pts[m + 1] - f_pts[m])
+                } else {
+                    (f_pts[m + 2] - f) / (f_pts[m + 2] - f_pts[m + 1])
+                };
+                assert!((row[m] - expected).abs() < 1e-6);
+            }
+        }
+        assert!((mel_to_hz(hz_to_mel(440.0)) - 440.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_log_mel_backward() {
+        let dev: TestDevice = Default::default();
+        let spec: Tensor<(Const<1>, usize, Const<3>), f32, _> =
+            dev.tensor_from_vec(alloc::vec![1.0, 2.0, 3.0, 0.0, 1.0, 0.0], (Const, 2, Const));
+        let fb: Tensor<Rank2<3, 2>, f32, _> = dev.tensor([[1.0, 0.0], [0.5, 0.5], [0.0, 1.0]]);
+        let r = spec.trace().log_mel(fb, 1e-6);
+        assert_close_with_tolerance(
+            &r.as_vec(),
+            &alloc::vec![2.0f32.ln(), 4.0f32.ln(), 0.5f32.ln(), 0.5f32.ln()],
+            1e-5,
+        );
+        let g = r.sum().backward();
+        // d/dspec[k] = sum_m fb[k, m] / mel[m]
+        assert_close_with_tolerance(
+            &g.get(&spec).as_vec(),
+            &alloc::vec![0.5, 0.375, 0.25, 2.0, 2.0, 2.0],
+            1e-4,
+        );
+    }
+}
@@ -0,0 +1,77 @@
+// Computes the strided index into `inp` for the contiguous output index `i` of
+// a (batch, channels, height, width) crop. Each sample `n` has `params[3 * n..]`
+// of `[top, left, flip]`. Returns false if the index falls in the zero padding.
+__device__ bool get_crop_flip_index(
+    unsigned int i,
+    const int *params,
+    const size_t *inp_dims,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    unsigned int *inp_i
+) {
+    unsigned int j = i % out_dims[3];
+    i /= out_dims[3];
+    unsigned int row = i % out_dims[2];
+    i /= out_dims[2];
+    unsigned int c = i % out_dims[1];
+    unsigned int n = i / out_dims[1];
+
+    const int *p = params + 3 * n;
+    if (p[2]) {
+        j = out_dims[3] - 1 - j;
+    }
+    int y = (int)row + p[0];
+    int x = (int)j + p[1];
+    if (y < 0 || x < 0 || y >= (int)inp_dims[2] || x >= (int)inp_dims[3]) {
+        return false;
+    }
+    *inp_i = n * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+    return true;
+}
+
+extern "C" __global__ void crop_flip_forward(
+    const size_t numel,
+    const int *params,
+    const size_t *inp_dims,
+    const float *inp,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i;
+    if (get_crop_flip_index(i, params, inp_dims, inp_strides, out_dims, &inp_i)) {
+        out[i] = inp[inp_i];
+    }
+}
+
+extern "C" __global__ void crop_flip_backward(
+    const size_t numel,
+    const int *params,
+    const size_t *inp_dims,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i;
+    if (get_crop_flip_index(i, params, inp_dims, inp_strides, out_dims, &inp_i)) {
+        unsigned int out_i = i % out_dims[3] * out_strides[3];
+        unsigned int k = i / out_dims[3];
+        out_i += k % out_dims[2] * out_strides[2];
+        k /= out_dims[2];
+        out_i += k % out_dims[1] * out_strides[1];
+        out_i += k / out_dims[1] * out_strides[0];
+        atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::shapes::{Dtype, Shape};
+use crate::tensor::cpu::{Cpu, LendingIterator, StridedArray};
+
+use super::CropFlip;
+
+/// The index into the input for output index `i`, if it isn't in the padding.
+fn src_index(
+    i: [usize; 4],
+    inp_dims: [usize; 4],
+    out_w: usize,
+    p: &CropFlip,
+) -> Option<[usize; 4]> {
+    let j = if p.flip { out_w - 1 - i[3] } else { i[3] };
+    let y = i[2] as isize + p.top;
+    let x = j as isize + p.left;
+    if y < 0 || x < 0 || y as usize >= inp_dims[2] || x as usize >= inp_dims[3] {
+        None
+    } else {
+        Some([i[0], i[1], y as usize, x as usize])
+    }
+}
+
+impl<E: Dtype> super::CropFlipKernel<E> for Cpu {
+    fn forward<Src: Shape<Concrete = [usize; 4]>, Dst: Shape<Concrete = [usize; 4]>>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        dst: Dst,
+        params: &[CropFlip],
+    ) -> Result<Self::Storage<Dst, E>, Self::Err> {
+        let mut out = StridedArray::new(dst)?;
+        let inp_dims = inp.shape.concrete();
+        let out_w = dst.concrete()[3];
+        let mut out_iter = out.iter_mut_with_index();
+        while let Some((o, i)) = out_iter.next() {
+            if let Some(i) = src_index(i, inp_dims, out_w, &params[i[0]]) {
+                *o = inp[i];
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<Src: Shape<Concrete = [usize; 4]>, Dst: Shape<Concrete = [usize; 4]>>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+        params: &[CropFlip],
+    ) -> Result<(), Self::Err> {
+        let inp_dims = grad_inp.shape.concrete();
+        let out_w = grad_out.shape.concrete()[3];
+        let mut out_iter = grad_out.iter_with_index();
+        while let Some((o, i)) = out_iter.next() {
+            if let Some(i) = src_index(i, inp_dims, out_w, &params[i[0]]) {
+                grad_inp[i] += *o;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+use super::CropFlip;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/augment.ptx"));
+const MODULE_NAME: &str = "augment";
+const FWD_FN_NAME: &str = "crop_flip_forward";
+const BWD_FN_NAME: &str = "crop_flip_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl Cuda {
+    /// Uploads `params` as `[top, left, flip]` triples.
+    fn upload_crop_flip(
+        &self,
+        params: &[CropFlip],
+    ) -> Result<CudaSlice<i32>, <Self as crate::tensor::DeviceStorage>::Err> {
+        let mut flat: Vec<i32> = Vec::with_capacity(3 * params.len());
+        for p in params {
+            flat.extend([p.top as i32, p.left as i32, p.flip as i32]);
+        }
+        Ok(self.dev.take_async(flat)?)
+    }
+}
+
+impl super::CropFlipKernel<f32> for Cuda {
+    fn forward<Src: Shape<Concrete = [usize; 4]>, Dst: Shape<Concrete = [usize; 4]>>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        dst: Dst,
+        params: &[CropFlip],
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = dst.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        if numel > 0 {
+            let params = self.upload_crop_flip(params)?;
+            let inp_dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+            let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+            let out_dims: CudaSlice<usize> = self.dev.take_async(dst.concrete().into())?;
+
+            let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                numel,             // const size_t numel,
+                &params,           // const int *params,
+                &inp_dims,         // const size_t *inp_dims,
+                inp.data.as_ref(), // const float *inp,
+                &inp_strides,      // const size_t *inp_strides,
+                &out_dims,         // const size_t *out_dims,
+                &mut storage,      // float *out
+            );
+            unsafe { fwd_fn.launch_async(cfg, params) }?;
+        }
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+
+    fn backward<Src: Shape<Concrete = [usize; 4]>, Dst: Shape<Concrete = [usize; 4]>>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+        params: &[CropFlip],
+    ) -> Result<(), Self::Err> {
+        let numel = grad_out.shape.num_elements();
+        if numel == 0 {
+            return Ok(());
+        }
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let params = self.upload_crop_flip(params)?;
+        let inp_dims: CudaSlice<usize> = self.dev.take_async(grad_inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let out_dims: CudaSlice<usize> = self.dev.take_async(grad_out.shape.concrete().into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            &params,                           // const int *params,
+            &inp_dims,                         // const size_t *inp_dims,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            &out_dims,                         // const size_t *out_dims,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &out_strides,                      // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
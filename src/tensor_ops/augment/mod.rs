@@ -0,0 +1,375 @@
+#![allow(clippy::type_complexity)]
+
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::vec::Vec;
+
+use super::{BroadcastTo, Device, ReshapeTo, TryMul, TrySub};
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+/// Where a single sample of a batch is cropped from, relative to the top left corner of
+/// the unpadded image, and whether the crop is flipped horizontally.
+#[derive(Debug, Clone, Copy)]
+pub struct CropFlip {
+    pub top: isize,
+    pub left: isize,
+    pub flip: bool,
+}
+
+pub trait CropFlipKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape<Concrete = [usize; 4]>, Dst: Shape<Concrete = [usize; 4]>>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        dst: Dst,
+        params: &[CropFlip],
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+    fn backward<Src: Shape<Concrete = [usize; 4]>, Dst: Shape<Concrete = [usize; 4]>>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+        params: &[CropFlip],
+    ) -> Result<(), Self::Err>;
+}
+
+impl<S: Shape<Concrete = [usize; 4]>, D: Device<f32>, T: Tape<D>> Tensor<S, f32, D, T> {
+    /// Crops & flips each image of the batch with its own `params`. Pixels outside
+    /// of the image are zero.
+    fn try_crop_flip<Dst: Shape<Concrete = [usize; 4]>>(
+        self,
+        dst: Dst,
+        params: Vec<CropFlip>,
+    ) -> Result<Tensor<Dst, f32, D, T>, D::Err> {
+        let (inp, mut tape) = self.split_tape();
+        let storage = CropFlipKernel::forward(&inp.device, &inp.storage, dst, &params)?;
+        let out = inp.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            CropFlipKernel::backward(&inp.device, grad_inp, grad_out, &params)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+/// Image augmentations for batches of images with shape `(batch, channels, height, width)`.
+///
+/// The random parameters of each image are drawn from a seed from the device's rng, so
+/// only the images themselves are on the device. All of the augmentations are
+/// differentiable with respect to the input.
+///
+/// A CIFAR-10 style pipeline:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let images: Tensor<Rank4<8, 3, 32, 32>> = dev.sample_uniform();
+/// let images: Tensor<Rank4<8, 3, 32, 32>> = images
+///     .random_crop::<32, 32>(4)
+///     .random_horizontal_flip(0.5)
+///     .normalize_channels([0.49, 0.48, 0.45], [0.25, 0.24, 0.26])
+///     .random_cutout(8);
+/// ```
+impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Tensor<(B, Const<C>, H, W), f32, D, T>
+{
+    /// Crops a random `OH`x`OW` window out of each image, after padding the image with
+    /// `padding` zeros on every side.
+    ///
+    /// **Panics** if the padded image is smaller than the window.
+    pub fn random_crop<const OH: usize, const OW: usize>(
+        self,
+        padding: usize,
+    ) -> Tensor<(B, Const<C>, Const<OH>, Const<OW>), f32, D, T> {
+        self.try_random_crop(padding).unwrap()
+    }
+
+    /// Fallible version of [Tensor::random_crop()]
+    pub fn try_random_crop<const OH: usize, const OW: usize>(
+        self,
+        padding: usize,
+    ) -> Result<Tensor<(B, Const<C>, Const<OH>, Const<OW>), f32, D, T>, D::Err> {
+        let (b, _, h, w) = *self.shape();
+        let (h, w) = (h.size(), w.size());
+        assert!(
+            OH <= h + 2 * padding && OW <= w + 2 * padding,
+            "Can't crop {OH}x{OW} out of {h}x{w} with padding {padding}"
+        );
+        let mut rng = StdRng::seed_from_u64(self.device.random_u64());
+        let p = padding as isize;
+        let params = (0..b.size())
+            .map(|_| CropFlip {
+                top: rng.gen_range(0..=h + 2 * padding - OH) as isize - p,
+                left: rng.gen_range(0..=w + 2 * padding - OW) as isize - p,
+                flip: false,
+            })
+            .collect();
+        self.try_crop_flip((b, Const, Const, Const), params)
+    }
+
+    /// Flips each image horizontally with probability `prob`.
+    pub fn random_horizontal_flip(self, prob: f32) -> Self {
+        self.try_random_horizontal_flip(prob).unwrap()
+    }
+
+    /// Fallible version of [Tensor::random_horizontal_flip()]
+    pub fn try_random_horizontal_flip(self, prob: f32) -> Result<Self, D::Err> {
+        let shape = *self.shape();
+        let mut rng = StdRng::seed_from_u64(self.device.random_u64());
+        let params = (0..shape.0.size())
+            .map(|_| CropFlip {
+                top: 0,
+                left: 0,
+                flip: rng.gen::<f32>() < prob,
+            })
+            .collect();
+        self.try_crop_flip(shape, params)
+    }
+
+    /// Normalizes each channel with `(x - mean[c]) / std[c]`.
+    ///
+    /// **Pytorch equivalent**: `torchvision.transforms.Normalize(mean, std)`
+    pub fn normalize_channels(self, mean: [f32; C], std: [f32; C]) -> Self {
+        self.try_normalize_channels(mean, std).unwrap()
+    }
+
+    /// Fallible version of [Tensor::normalize_channels()]
+    pub fn try_normalize_channels(self, mean: [f32; C], std: [f32; C]) -> Result<Self, D::Err> {
+        let shape = *self.shape();
+        let mut m: Tensor<Rank1<C>, f32, D> = self.device.try_zeros()?;
+        m.copy_from(&mean);
+        let mut inv_std: Tensor<Rank1<C>, f32, D> = self.device.try_zeros()?;
+        inv_std.copy_from(&std.map(|s| 1.0 / s));
+        self.try_sub(m.try_broadcast_like::<_, Axes3<0, 2, 3>>(&shape)?)?
+            .try_mul(inv_std.try_broadcast_like::<_, Axes3<0, 2, 3>>(&shape)?)
+    }
+
+    /// Zeros out a `size`x`size` square of each image, centered on a random pixel, in all
+    /// channels. The square is clipped at the border of the image. Described in
+    /// [Improved Regularization of Convolutional Neural Networks with Cutout](https://arxiv.org/abs/1708.04552).
+    pub fn random_cutout(self, size: usize) -> Self {
+        self.try_random_cutout(size).unwrap()
+    }
+
+    /// Fallible version of [Tensor::random_cutout()]
+    pub fn try_random_cutout(self, size: usize) -> Result<Self, D::Err> {
+        let shape = *self.shape();
+        let (b, _, h, w) = shape;
+        let (nh, nw) = (h.size(), w.size());
+        let mut rng = StdRng::seed_from_u64(self.device.random_u64());
+        let mut mask = alloc::vec![1.0; b.size() * nh * nw];
+        if nh > 0 && nw > 0 {
+            for img in mask.chunks_exact_mut(nh * nw) {
+                let cy = rng.gen_range(0..nh);
+                let cx = rng.gen_range(0..nw);
+                for y in cy.saturating_sub(size / 2)..(cy + size - size / 2).min(nh) {
+                    for x in cx.saturating_sub(size / 2)..(cx + size - size / 2).min(nw) {
+                        img[y * nw + x] = 0.0;
+                    }
+                }
+            }
+        }
+        let mut m = self.device.try_zeros_like(&(b, h, w))?;
+        m.copy_from(&mask);
+        self.try_mul(m.try_broadcast_like::<_, Axis<1>>(&shape)?)
+    }
+}
+
+/// The same augmentations as for batches, applied to a single image with shape
+/// `(channels, height, width)`.
+impl<const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Tensor<(Const<C>, H, W), f32, D, T>
+{
+    /// Adds a batch dimension of 1, applies `f`, and removes it again.
+    fn try_as_batch<OH: Dim, OW: Dim>(
+        self,
+        f: impl FnOnce(
+            Tensor<(Const<1>, Const<C>, H, W), f32, D, T>,
+        ) -> Result<Tensor<(Const<1>, Const<C>, OH, OW), f32, D, T>, D::Err>,
+    ) -> Result<Tensor<(Const<C>, OH, OW), f32, D, T>, D::Err> {
+        let (c, h, w) = *self.shape();
+        let out = f(self.try_reshape_like(&(Const::<1>, c, h, w))?)?;
+        let (_, _, oh, ow) = *out.shape();
+        out.try_reshape_like(&(c, oh, ow))
+    }
+
+    /// See [Tensor::random_crop()] for batches.
+    pub fn random_crop<const OH: usize, const OW: usize>(
+        self,
+        padding: usize,
+    ) -> Tensor<(Const<C>, Const<OH>, Const<OW>), f32, D, T> {
+        self.try_random_crop(padding).unwrap()
+    }
+
+    /// Fallible version of [Tensor::random_crop()]
+    pub fn try_random_crop<const OH: usize, const OW: usize>(
+        self,
+        padding: usize,
+    ) -> Result<Tensor<(Const<C>, Const<OH>, Const<OW>), f32, D, T>, D::Err> {
+        self.try_as_batch(|t| t.try_random_crop(padding))
+    }
+
+    /// See [Tensor::random_horizontal_flip()] for batches.
+    pub fn random_horizontal_flip(self, prob: f32) -> Self {
+        self.try_random_horizontal_flip(prob).unwrap()
+    }
+
+    /// Fallible version of [Tensor::random_horizontal_flip()]
+    pub fn try_random_horizontal_flip(self, prob: f32) -> Result<Self, D::Err> {
+        self.try_as_batch(|t| t.try_random_horizontal_flip(prob))
+    }
+
+    /// See [Tensor::normalize_channels()] for batches.
+    pub fn normalize_channels(self, mean: [f32; C], std: [f32; C]) -> Self {
+        self.try_normalize_channels(mean, std).unwrap()
+    }
+
+    /// Fallible version of [Tensor::normalize_channels()]
+    pub fn try_normalize_channels(self, mean: [f32; C], std: [f32; C]) -> Result<Self, D::Err> {
+        self.try_as_batch(|t| t.try_normalize_channels(mean, std))
+    }
+
+    /// See [Tensor::random_cutout()] for batches.
+    pub fn random_cutout(self, size: usize) -> Self {
+        self.try_random_cutout(size).unwrap()
+    }
+
+    /// Fallible version of [Tensor::random_cutout()]
+    pub fn try_random_cutout(self, size: usize) -> Result<Self, D::Err> {
+        self.try_as_batch(|t| t.try_random_cutout(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_crop_flip_values() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<2, 1, 2, 3>, f32, _> = dev.tensor([
+            [[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]],
+            [[[7.0, 8.0, 9.0], [10.0, 11.0, 12.0]]],
+        ]);
+        let params = alloc::vec![
+            CropFlip {
+                top: -1,
+                left: 1,
+                flip: false,
+            },
+            CropFlip {
+                top: 0,
+                left: 1,
+                flip: true,
+            },
+        ];
+        let r = t
+            .trace()
+            .try_crop_flip((Const::<2>, Const::<1>, Const::<2>, Const::<2>), params)
+            .unwrap();
+        assert_eq!(
+            r.array(),
+            [[[[0.0, 0.0], [2.0, 3.0]]], [[[9.0, 8.0], [12.0, 11.0]]]]
+        );
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [
+                [[[0.0, 1.0, 1.0], [0.0, 0.0, 0.0]]],
+                [[[0.0, 1.0, 1.0], [0.0, 1.0, 1.0]]],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_random_crop_is_a_window() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<4, 2, 5, 5>, f32, _> = dev.sample_uniform::<Rank4<4, 2, 5, 5>>() + 1.0;
+        let r: Tensor<Rank4<4, 2, 3, 3>, f32, _> = t.clone().random_crop(0);
+        let t = t.array();
+        for (n, img) in r.array().iter().enumerate() {
+            let found = (0..3).any(|y| {
+                (0..3).any(|x| {
+                    (0..2).all(|c| {
+                        (0..3).all(|i| (0..3).all(|j| img[c][i][j] == t[n][c][y + i][x + j]))
+                    })
+                })
+            });
+            assert!(found);
+        }
+    }
+
+    #[test]
+    fn test_random_crop_with_padding() {
+        let dev = TestDevice::seed_from_u64(0);
+        let t: Tensor<Rank4<8, 1, 2, 2>, f32, _> = dev.tensor([[[[1.0, 2.0], [3.0, 4.0]]]; 8]);
+        let r: Tensor<Rank4<8, 1, 2, 2>, f32, _> = t.random_crop(1);
+
+        // draw the same offsets the crop did
+        let mut rng = StdRng::seed_from_u64(TestDevice::seed_from_u64(0).random_u64());
+        let padded = [
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 2.0, 0.0],
+            [0.0, 3.0, 4.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+        ];
+        let mut offsets = Vec::new();
+        for img in r.array().iter() {
+            let top = rng.gen_range(0..=2);
+            let left = rng.gen_range(0..=2);
+            offsets.push((top, left));
+            let expected = [[
+                [padded[top][left], padded[top][left + 1]],
+                [padded[top + 1][left], padded[top + 1][left + 1]],
+            ]];
+            assert_eq!(img, &expected);
+        }
+        // the crops should include padding on every side
+        assert!(offsets.iter().any(|o| o.0 == 0) && offsets.iter().any(|o| o.0 == 2));
+        assert!(offsets.iter().any(|o| o.1 == 0) && offsets.iter().any(|o| o.1 == 2));
+    }
+
+    #[test]
+    fn test_random_horizontal_flip() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 2, 3>, f32, _> = dev.sample_normal();
+        assert_eq!(t.clone().random_horizontal_flip(0.0).array(), t.array());
+
+        let r = t.trace().random_horizontal_flip(1.0);
+        let expected = t.clone().index_select::<Axis<2>, _>(dev.tensor([2, 1, 0]));
+        assert_eq!(r.array(), expected.array());
+        let g = (r * 2.0).sum().backward();
+        assert_eq!(g.get(&t).array(), [[[2.0; 3]; 2]; 2]);
+    }
+
+    #[test]
+    fn test_normalize_channels() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<1, 2, 1, 2>, f32, _> = dev.tensor([[[[1.0, 2.0]], [[3.0, 5.0]]]]);
+        let r = t.trace().normalize_channels([1.0, 1.0], [2.0, 0.5]);
+        assert_eq!(r.array(), [[[[0.0, 0.5]], [[4.0, 8.0]]]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[[[0.5, 0.5]], [[2.0, 2.0]]]]);
+    }
+
+    #[test]
+    fn test_random_cutout() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<3, 2, 6, 6>, f32, _> = dev.ones();
+        let r = t.trace().random_cutout(2);
+        let a = r.array();
+        for img in a.iter() {
+            // the same pixels are zeroed in every channel
+            assert_eq!(img[0], img[1]);
+            let zeros = img[0].iter().flatten().filter(|&&x| x == 0.0).count();
+            assert!((1..=4).contains(&zeros));
+        }
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), a);
+    }
+}
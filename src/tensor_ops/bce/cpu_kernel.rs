@@ -1,4 +1,6 @@
 use crate::tensor_ops::cpu_kernels::BinaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 impl BinaryDerivative<f32> for super::BCEKernelOp {
     #[inline(always)]
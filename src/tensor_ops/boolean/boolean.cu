@@ -0,0 +1,79 @@
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+extern "C" __global__ void not_forward(const size_t numel, const bool *inp, bool *out) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    out[i] = !inp[i];
+}
+
+#define BOOL_OP(FWD, SYMBOL) \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t *dims, \
+    const bool *lhs, \
+    const size_t *lhs_strides, \
+    const bool *rhs, \
+    const size_t *rhs_strides, \
+    bool *out \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+    unsigned int lhs_i = get_strided_index(i, num_dims, dims, lhs_strides); \
+    unsigned int rhs_i = get_strided_index(i, num_dims, dims, rhs_strides); \
+    out[i] = lhs[lhs_i] SYMBOL rhs[rhs_i]; \
+}
+
+BOOL_OP(and_forward, &&);
+BOOL_OP(or_forward, ||);
+BOOL_OP(xor_forward, !=);
+
+extern "C" __global__ void fill_with(bool *buf, bool value, const size_t numel) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    buf[i] = value;
+}
+
+// Every thread that finds a value different from `init` writes `!init` into
+// out[i / chunk_len]. All writers store the same value, so no atomics are needed.
+#define BOOL_REDUCE(FWD, INIT) \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t chunk_len, \
+    const bool *inp, \
+    const size_t *dims, \
+    const size_t *strides, \
+    bool *out \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+    unsigned int inp_i = get_strided_index(i, num_dims, dims, strides); \
+    if (inp[inp_i] != INIT) { \
+        out[i / chunk_len] = !INIT; \
+    } \
+}
+
+BOOL_REDUCE(any_forward, false);
+BOOL_REDUCE(all_forward, true);
@@ -0,0 +1,95 @@
+use crate::{
+    shapes::{Axes, ReduceShapeTo, Shape},
+    tensor::cpu::{Cpu, CpuError, LendingIterator, StridedArray},
+};
+
+impl Cpu {
+    fn bool_binary<S: Shape>(
+        &self,
+        lhs: &StridedArray<S, bool>,
+        rhs: &StridedArray<S, bool>,
+        f: impl Fn(bool, bool) -> bool,
+    ) -> Result<StridedArray<S, bool>, CpuError> {
+        let mut out: StridedArray<S, bool> = StridedArray::new(lhs.shape)?;
+        let mut lhs_iter = lhs.iter();
+        let mut rhs_iter = rhs.iter();
+        let mut out_iter = out.iter_mut();
+        while let Some((o, (l, r))) = out_iter.next().zip(lhs_iter.next().zip(rhs_iter.next())) {
+            *o = f(*l, *r);
+        }
+        Ok(out)
+    }
+}
+
+impl super::BooleanKernel for Cpu {
+    fn not<S: Shape>(
+        &self,
+        inp: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        let mut out = inp.clone();
+        for x in out.buf_iter_mut() {
+            *x = !*x;
+        }
+        Ok(out)
+    }
+
+    fn and<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        self.bool_binary(lhs, rhs, |l, r| l && r)
+    }
+
+    fn or<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        self.bool_binary(lhs, rhs, |l, r| l || r)
+    }
+
+    fn xor<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        self.bool_binary(lhs, rhs, |l, r| l ^ r)
+    }
+}
+
+impl super::BoolReduceKernel for Cpu {
+    fn any<Src, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: Shape + ReduceShapeTo<Dst, Ax>,
+    {
+        let mut out: StridedArray<Dst, bool> = StridedArray::try_new_with(dst, false)?;
+        let mut out_iter = out.iter_mut_as(&inp.shape);
+        let mut inp_iter = inp.iter();
+        while let Some((out_i, inp_i)) = out_iter.next().zip(inp_iter.next()) {
+            *out_i |= *inp_i;
+        }
+        Ok(out)
+    }
+
+    fn all<Src, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: Shape + ReduceShapeTo<Dst, Ax>,
+    {
+        let mut out: StridedArray<Dst, bool> = StridedArray::try_new_with(dst, true)?;
+        let mut out_iter = out.iter_mut_as(&inp.shape);
+        let mut inp_iter = inp.iter();
+        while let Some((out_i, inp_i)) = out_iter.next().zip(inp_iter.next()) {
+            *out_i &= *inp_i;
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,186 @@
+use crate::tensor_ops::internal_reshapes::permute_for_reductions;
+use crate::{
+    shapes::{Axes, ReduceShapeTo, Shape},
+    tensor::cuda::{Cuda, CudaArray, CudaError},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/boolean.ptx"));
+const MODULE_NAME: &str = "boolean";
+const ALL_FN_NAMES: [&str; 7] = [
+    "not_forward",
+    "and_forward",
+    "or_forward",
+    "xor_forward",
+    "fill_with",
+    "any_forward",
+    "all_forward",
+];
+
+impl Cuda {
+    fn bool_binary<S: Shape>(
+        &self,
+        fn_name: &str,
+        lhs: &CudaArray<S, bool>,
+        rhs: &CudaArray<S, bool>,
+    ) -> Result<CudaArray<S, bool>, CudaError> {
+        if !self.dev.has_func(MODULE_NAME, fn_name) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = lhs.shape;
+        let strides = lhs.shape.strides();
+        let numel = shape.num_elements();
+
+        let mut storage = self.dev.alloc_zeros_async::<bool>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let lhs_strides: CudaSlice<usize> = self.dev.take_async(lhs.strides.into())?;
+        let rhs_strides: CudaSlice<usize> = self.dev.take_async(rhs.strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, fn_name).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            S::NUM_DIMS,       // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            lhs.data.as_ref(), // const bool *lhs,
+            &lhs_strides,      // const size_t *lhs_strides,
+            rhs.data.as_ref(), // const bool *rhs,
+            &rhs_strides,      // const size_t *rhs_strides,
+            &mut storage,      // bool *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+
+    fn bool_reduce<Src, Dst: Shape, Ax: Axes>(
+        &self,
+        fn_name: &str,
+        init: bool,
+        dst: Dst,
+        inp: &CudaArray<Src, bool>,
+    ) -> Result<CudaArray<Dst, bool>, CudaError>
+    where
+        Src: Shape + ReduceShapeTo<Dst, Ax>,
+    {
+        if !self.dev.has_func(MODULE_NAME, fn_name) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let mut storage = self.dev.alloc_zeros_async::<bool>(dst.num_elements())?;
+        let fill_fn = self.dev.get_func(MODULE_NAME, "fill_with").unwrap();
+        unsafe {
+            fill_fn.launch_async(
+                LaunchConfig::for_num_elems(dst.num_elements() as u32),
+                (&mut storage, init, dst.num_elements()),
+            )
+        }?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, fn_name).unwrap();
+
+        let (dims, strides) = permute_for_reductions::<_, Ax>(inp.shape.concrete(), inp.strides);
+        let dims: CudaSlice<usize> = self.dev.take_async(dims)?;
+        let strides: CudaSlice<usize> = self.dev.take_async(strides)?;
+
+        let physical_numel = inp.data.len();
+        let chunk_len = physical_numel / dst.num_elements();
+
+        let cfg = LaunchConfig::for_num_elems(physical_numel as u32);
+        let params = (
+            physical_numel,    // const size_t numel,
+            dims.len(),        // const size_t num_dims,
+            chunk_len,         // const size_t chunk_len,
+            inp.data.as_ref(), // const bool *inp,
+            &dims,             // const size_t *dims,
+            &strides,          // const size_t *strides,
+            &mut storage,      // bool *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+}
+
+impl super::BooleanKernel for Cuda {
+    fn not<S: Shape>(
+        &self,
+        inp: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, "not_forward") {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = inp.data.len();
+        let mut storage = self.dev.alloc_zeros_async::<bool>(numel)?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, "not_forward").unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (numel, inp.data.as_ref(), &mut storage);
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: inp.shape,
+            strides: inp.strides,
+        })
+    }
+
+    fn and<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        self.bool_binary("and_forward", lhs, rhs)
+    }
+
+    fn or<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        self.bool_binary("or_forward", lhs, rhs)
+    }
+
+    fn xor<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        self.bool_binary("xor_forward", lhs, rhs)
+    }
+}
+
+impl super::BoolReduceKernel for Cuda {
+    fn any<Src, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: Shape + ReduceShapeTo<Dst, Ax>,
+    {
+        self.bool_reduce::<Src, Dst, Ax>("any_forward", false, dst, inp)
+    }
+
+    fn all<Src, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: Shape + ReduceShapeTo<Dst, Ax>,
+    {
+        self.bool_reduce::<Src, Dst, Ax>("all_forward", true, dst, inp)
+    }
+}
@@ -0,0 +1,264 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    shapes::{Axes, HasShape, ReduceShapeTo, ReduceStridesTo, Shape},
+    tensor::{DeviceStorage, Tensor},
+};
+
+pub trait BooleanKernel: DeviceStorage {
+    fn not<S: Shape>(
+        &self,
+        inp: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err>;
+
+    fn and<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err>;
+
+    fn or<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err>;
+
+    fn xor<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, bool>,
+        rhs: &Self::Storage<S, bool>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err>;
+}
+
+pub trait BoolReduceKernel: DeviceStorage {
+    fn any<Src, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: Shape + ReduceShapeTo<Dst, Ax>;
+
+    fn all<Src, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: Shape + ReduceShapeTo<Dst, Ax>;
+}
+
+/// Logical ops on `bool` tensors, which are usually created by comparisons like
+/// [Tensor::lt()]. `bool` tensors never have a tape.
+///
+/// The ops are available as methods and through `!`, `&`, `|` and `^`.
+///
+/// Use [Tensor::to_dtype()] to turn a `bool` tensor into a float mask of `0.0` and `1.0`,
+/// and `.to_dtype::<bool>()` to go the other way (any non-zero value becomes `true`).
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([true, true, false, false]);
+/// let b = dev.tensor([true, false, true, false]);
+/// assert_eq!(a.and(&b).array(), [true, false, false, false]);
+/// assert_eq!((a.clone() | b.clone()).array(), [true, true, true, false]);
+/// assert_eq!((&a ^ &b).array(), [false, true, true, false]);
+/// assert_eq!((!a.clone()).array(), [false, false, true, true]);
+/// assert_eq!(a.to_dtype::<f32>().array(), [1.0, 1.0, 0.0, 0.0]);
+/// ```
+impl<S: Shape, D: BooleanKernel> Tensor<S, bool, D> {
+    /// Element wise logical not.
+    pub fn not(&self) -> Self {
+        self.try_not().unwrap()
+    }
+
+    /// Fallible version of [Tensor::not]
+    pub fn try_not(&self) -> Result<Self, D::Err> {
+        let storage = self.device.not(&self.storage)?;
+        Ok(self.device.upgrade(storage))
+    }
+
+    /// Element wise logical and.
+    pub fn and(&self, rhs: &Self) -> Self {
+        self.try_and(rhs).unwrap()
+    }
+
+    /// Fallible version of [Tensor::and]
+    pub fn try_and(&self, rhs: &Self) -> Result<Self, D::Err> {
+        let storage = self.device.and(&self.storage, &rhs.storage)?;
+        Ok(self.device.upgrade(storage))
+    }
+
+    /// Element wise logical or.
+    pub fn or(&self, rhs: &Self) -> Self {
+        self.try_or(rhs).unwrap()
+    }
+
+    /// Fallible version of [Tensor::or]
+    pub fn try_or(&self, rhs: &Self) -> Result<Self, D::Err> {
+        let storage = self.device.or(&self.storage, &rhs.storage)?;
+        Ok(self.device.upgrade(storage))
+    }
+
+    /// Element wise logical xor.
+    pub fn xor(&self, rhs: &Self) -> Self {
+        self.try_xor(rhs).unwrap()
+    }
+
+    /// Fallible version of [Tensor::xor]
+    pub fn try_xor(&self, rhs: &Self) -> Result<Self, D::Err> {
+        let storage = self.device.xor(&self.storage, &rhs.storage)?;
+        Ok(self.device.upgrade(storage))
+    }
+}
+
+impl<S: Shape, D: BoolReduceKernel> Tensor<S, bool, D> {
+    /// Whether any value is `true` along `Ax`. Works the same as other reductions like
+    /// [crate::tensor_ops::SumTo::sum()].
+    ///
+    /// **Pytorch equivalent**: `t.any(Ax)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[true, false, false], [false, false, false]]);
+    /// assert_eq!(t.any::<Rank1<2>, _>().array(), [true, false]);
+    /// assert_eq!(t.any::<Rank1<3>, _>().array(), [true, false, false]);
+    /// assert_eq!(t.any::<Rank0, _>().array(), true);
+    /// ```
+    pub fn any<Dst: Shape, Ax: Axes>(&self) -> Tensor<Dst, bool, D>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_any().unwrap()
+    }
+
+    /// Fallible version of [Tensor::any]
+    pub fn try_any<Dst: Shape, Ax: Axes>(&self) -> Result<Tensor<Dst, bool, D>, D::Err>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        let storage = self.device.any(dst, &self.storage)?;
+        Ok(self.device.upgrade(storage))
+    }
+
+    /// Whether all values are `true` along `Ax`. Works the same as other reductions like
+    /// [crate::tensor_ops::SumTo::sum()].
+    ///
+    /// **Pytorch equivalent**: `t.all(Ax)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[true, false, true], [true, true, true]]);
+    /// assert_eq!(t.all::<Rank1<2>, _>().array(), [false, true]);
+    /// assert_eq!(t.all::<Rank1<3>, _>().array(), [true, false, true]);
+    /// assert_eq!(t.all::<Rank0, _>().array(), false);
+    /// ```
+    pub fn all<Dst: Shape, Ax: Axes>(&self) -> Tensor<Dst, bool, D>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_all().unwrap()
+    }
+
+    /// Fallible version of [Tensor::all]
+    pub fn try_all<Dst: Shape, Ax: Axes>(&self) -> Result<Tensor<Dst, bool, D>, D::Err>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        let storage = self.device.all(dst, &self.storage)?;
+        Ok(self.device.upgrade(storage))
+    }
+}
+
+impl<S: Shape, D: BooleanKernel> std::ops::Not for Tensor<S, bool, D> {
+    type Output = Self;
+    /// See [Tensor::not]
+    fn not(self) -> Self {
+        Tensor::not(&self)
+    }
+}
+
+impl<S: Shape, D: BooleanKernel> std::ops::Not for &Tensor<S, bool, D> {
+    type Output = Tensor<S, bool, D>;
+    /// See [Tensor::not]
+    fn not(self) -> Self::Output {
+        Tensor::not(self)
+    }
+}
+
+macro_rules! bool_binary_op {
+    ($Trait:ident, $trait_fn:ident, $fn:ident) => {
+        impl<S: Shape, D: BooleanKernel> std::ops::$Trait for Tensor<S, bool, D> {
+            type Output = Self;
+            #[doc = concat!("See [Tensor::", stringify!($fn), "]")]
+            fn $trait_fn(self, rhs: Self) -> Self {
+                self.$fn(&rhs)
+            }
+        }
+
+        impl<'a, S: Shape, D: BooleanKernel> std::ops::$Trait for &'a Tensor<S, bool, D> {
+            type Output = Tensor<S, bool, D>;
+            #[doc = concat!("See [Tensor::", stringify!($fn), "]")]
+            fn $trait_fn(self, rhs: Self) -> Self::Output {
+                self.$fn(rhs)
+            }
+        }
+    };
+}
+
+bool_binary_op!(BitAnd, bitand, and);
+bool_binary_op!(BitOr, bitor, or);
+bool_binary_op!(BitXor, bitxor, xor);
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+
+    #[test]
+    fn test_logical_ops() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[true, true], [false, false]]);
+        let b = dev.tensor([[true, false], [true, false]]);
+        assert_eq!(a.not().array(), [[false, false], [true, true]]);
+        assert_eq!(a.and(&b).array(), [[true, false], [false, false]]);
+        assert_eq!(a.or(&b).array(), [[true, true], [true, false]]);
+        assert_eq!(a.xor(&b).array(), [[false, true], [true, false]]);
+        assert_eq!((!&b).array(), [[false, true], [false, true]]);
+        assert_eq!((a & b).array(), [[true, false], [false, false]]);
+    }
+
+    #[test]
+    fn test_any_all() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[true, false, true], [false, false, false]]);
+        assert_eq!(t.any::<Rank1<3>, _>().array(), [true, false, true]);
+        assert_eq!(t.any::<Rank1<2>, _>().array(), [true, false]);
+        assert_eq!(t.all::<Rank1<3>, _>().array(), [false, false, false]);
+        assert_eq!(t.not().all::<Rank1<2>, _>().array(), [false, true]);
+        assert!(t.any::<Rank0, _>().array());
+        assert!(!t.all::<Rank0, _>().array());
+    }
+
+    #[test]
+    fn test_masks() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.0, 0.5, 2.0, 0.0]);
+        let mask = x.scalar_gt(0.0).and(&x.scalar_lt(1.0));
+        assert_eq!(mask.array(), [false, true, false, false]);
+        assert_eq!(mask.to_dtype::<f32>().array(), [0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(x.to_dtype::<bool>().array(), [true, true, true, false]);
+        assert_eq!(
+            dev.tensor([0u8, 3]).to_dtype::<bool>().array(),
+            [false, true]
+        );
+    }
+}
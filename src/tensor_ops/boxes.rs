@@ -0,0 +1,294 @@
+#![allow(clippy::type_complexity)]
+
+use std::vec::Vec;
+
+use super::{BroadcastTo, Device, ReshapeTo, TryAdd, TryDiv, TryMul, TrySub};
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::*,
+    tensor::*,
+};
+
+/// The intersection over union of every pair of boxes in `a` and `b`, with shape `(N, M)`.
+/// Boxes are `[x1, y1, x2, y2]` with `x1 <= x2` and `y1 <= y2`.
+///
+/// The result is differentiable with respect to the coordinates of both sets of boxes,
+/// so it can be used in losses like `1 - iou`.
+///
+/// **Pytorch equivalent**: `torchvision.ops.box_iou(a, b)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a: Tensor<Rank2<1, 4>> = dev.tensor([[0.0, 0.0, 2.0, 2.0]]);
+/// let b: Tensor<Rank2<2, 4>> = dev.tensor([[1.0, 1.0, 3.0, 3.0], [0.0, 0.0, 2.0, 1.0]]);
+/// let r = box_iou(a, b);
+/// assert_eq!(r.array(), [[1.0 / 7.0, 0.5]]);
+/// ```
+pub fn box_iou<N: Dim, M: Dim, D: Device<f32>, T, R>(
+    a: Tensor<(N, Const<4>), f32, D, T>,
+    b: Tensor<(M, Const<4>), f32, D, R>,
+) -> Tensor<(N, M), f32, D, T>
+where
+    T: Tape<D> + Merge<R>,
+    R: Tape<D>,
+{
+    try_box_iou(a, b).unwrap()
+}
+
+/// Fallible version of [box_iou()]
+pub fn try_box_iou<N: Dim, M: Dim, D: Device<f32>, T, R>(
+    a: Tensor<(N, Const<4>), f32, D, T>,
+    b: Tensor<(M, Const<4>), f32, D, R>,
+) -> Result<Tensor<(N, M), f32, D, T>, D::Err>
+where
+    T: Tape<D> + Merge<R>,
+    R: Tape<D>,
+{
+    let shape = (a.shape().0, b.shape().0);
+    let [ax1, ay1, ax2, ay2] = columns(a)?;
+    let [bx1, by1, bx2, by2] = columns(b)?;
+
+    let area_a = ax2
+        .retaped::<T>()
+        .try_sub(ax1.retaped::<T>())?
+        .try_mul(ay2.retaped::<T>().try_sub(ay1.retaped::<T>())?)?
+        .try_broadcast_like::<_, Axis<1>>(&shape)?;
+    let area_b = bx2
+        .retaped::<R>()
+        .try_sub(bx1.retaped::<R>())?
+        .try_mul(by2.retaped::<R>().try_sub(by1.retaped::<R>())?)?
+        .try_broadcast_like::<_, Axis<0>>(&shape)?;
+
+    let ix1 = ax1
+        .try_broadcast_like::<_, Axis<1>>(&shape)?
+        .try_maximum(bx1.try_broadcast_like::<_, Axis<0>>(&shape)?)?;
+    let iy1 = ay1
+        .try_broadcast_like::<_, Axis<1>>(&shape)?
+        .try_maximum(by1.try_broadcast_like::<_, Axis<0>>(&shape)?)?;
+    let ix2 = ax2
+        .try_broadcast_like::<_, Axis<1>>(&shape)?
+        .try_minimum(bx2.try_broadcast_like::<_, Axis<0>>(&shape)?)?;
+    let iy2 = ay2
+        .try_broadcast_like::<_, Axis<1>>(&shape)?
+        .try_minimum(by2.try_broadcast_like::<_, Axis<0>>(&shape)?)?;
+    let inter = ix2
+        .try_sub(ix1)?
+        .try_relu()?
+        .try_mul(iy2.try_sub(iy1)?.try_relu()?)?;
+
+    let union = area_a.try_add(area_b)?.try_sub(inter.retaped::<T>())?;
+    inter.try_div(union)
+}
+
+/// Splits `(N, 4)` boxes into their 4 coordinates, each with shape `(N,)`
+fn columns<N: Dim, D: Device<f32>, T: Tape<D>>(
+    boxes: Tensor<(N, Const<4>), f32, D, T>,
+) -> Result<[Tensor<(N,), f32, D, T>; 4], D::Err> {
+    let n = boxes.shape().0;
+    let (boxes, tape) = boxes.split_tape();
+    let column = |k: usize, t: Tensor<(N, Const<4>), f32, D, T>| {
+        t.try_narrow::<Axis<1>>(k, 1)?.try_reshape_like(&(n,))
+    };
+    Ok([
+        column(0, boxes.clone().put_tape(tape))?,
+        column(1, boxes.retaped())?,
+        column(2, boxes.retaped())?,
+        column(3, boxes.retaped())?,
+    ])
+}
+
+/// Non-maximum suppression: keeps the boxes that don't overlap a box with a higher score
+/// by more than `iou_threshold` (see [box_iou()]). Returns the indices of the kept boxes
+/// in order of decreasing score. Boxes with a `NaN` score are never kept.
+///
+/// This is not differentiable. The overlaps are computed on the device, and the greedy
+/// selection happens on the host.
+///
+/// **Pytorch equivalent**: `torchvision.ops.nms(boxes, scores, iou_threshold)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let boxes: Tensor<Rank2<3, 4>> = dev.tensor([
+///     [0.0, 0.0, 2.0, 2.0],
+///     [0.1, 0.0, 2.0, 2.0],
+///     [3.0, 3.0, 4.0, 4.0],
+/// ]);
+/// let scores: Tensor<Rank1<3>> = dev.tensor([0.5, 0.9, 0.1]);
+/// let keep = nms(&boxes, &scores, 0.5);
+/// assert_eq!(keep.as_vec(), [1, 2]);
+/// ```
+pub fn nms<N: Dim, D: Device<f32> + TensorFromVec<usize>>(
+    boxes: &Tensor<(N, Const<4>), f32, D>,
+    scores: &Tensor<(N,), f32, D>,
+    iou_threshold: f32,
+) -> Tensor<(usize,), usize, D> {
+    try_nms(boxes, scores, iou_threshold).unwrap()
+}
+
+/// Fallible version of [nms()]
+pub fn try_nms<N: Dim, D: Device<f32> + TensorFromVec<usize>>(
+    boxes: &Tensor<(N, Const<4>), f32, D>,
+    scores: &Tensor<(N,), f32, D>,
+    iou_threshold: f32,
+) -> Result<Tensor<(usize,), usize, D>, D::Err> {
+    let iou = try_box_iou(boxes.clone(), boxes.clone())?.as_vec();
+    let keep = greedy_nms(&iou, &scores.as_vec(), None, iou_threshold);
+    let len = keep.len();
+    boxes.device.try_tensor_from_vec(keep, (len,))
+}
+
+/// [nms()] for boxes of different classes: a box is only suppressed by a box of the same
+/// class, so each class is filtered independently. Returns the indices of the kept boxes
+/// in order of decreasing score.
+///
+/// **Pytorch equivalent**: `torchvision.ops.batched_nms(boxes, scores, classes, iou_threshold)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let boxes: Tensor<Rank2<3, 4>> = dev.tensor([
+///     [0.0, 0.0, 2.0, 2.0],
+///     [0.1, 0.0, 2.0, 2.0],
+///     [0.0, 0.1, 2.0, 2.0],
+/// ]);
+/// let scores: Tensor<Rank1<3>> = dev.tensor([0.5, 0.9, 0.1]);
+/// let classes: Tensor<Rank1<3>, usize> = dev.tensor([0, 0, 1]);
+/// let keep = batched_nms(&boxes, &scores, &classes, 0.5);
+/// assert_eq!(keep.as_vec(), [1, 2]);
+/// ```
+pub fn batched_nms<N: Dim, D: Device<f32> + TensorFromVec<usize>>(
+    boxes: &Tensor<(N, Const<4>), f32, D>,
+    scores: &Tensor<(N,), f32, D>,
+    classes: &Tensor<(N,), usize, D>,
+    iou_threshold: f32,
+) -> Tensor<(usize,), usize, D> {
+    try_batched_nms(boxes, scores, classes, iou_threshold).unwrap()
+}
+
+/// Fallible version of [batched_nms()]
+pub fn try_batched_nms<N: Dim, D: Device<f32> + TensorFromVec<usize>>(
+    boxes: &Tensor<(N, Const<4>), f32, D>,
+    scores: &Tensor<(N,), f32, D>,
+    classes: &Tensor<(N,), usize, D>,
+    iou_threshold: f32,
+) -> Result<Tensor<(usize,), usize, D>, D::Err> {
+    let iou = try_box_iou(boxes.clone(), boxes.clone())?.as_vec();
+    let classes = classes.as_vec();
+    let keep = greedy_nms(&iou, &scores.as_vec(), Some(&classes), iou_threshold);
+    let len = keep.len();
+    boxes.device.try_tensor_from_vec(keep, (len,))
+}
+
+/// Visits boxes in order of decreasing score, keeping each box that hasn't been
+/// suppressed and suppressing the boxes (of the same class) that overlap it.
+fn greedy_nms(
+    iou: &[f32],
+    scores: &[f32],
+    classes: Option<&[usize]>,
+    iou_threshold: f32,
+) -> Vec<usize> {
+    let n = scores.len();
+    let mut order: Vec<usize> = (0..n).filter(|&i| !scores[i].is_nan()).collect();
+    order.sort_by(|&i, &j| scores[j].total_cmp(&scores[i]));
+
+    let mut suppressed = alloc::vec![false; n];
+    let mut keep = Vec::new();
+    for (k, &i) in order.iter().enumerate() {
+        if suppressed[i] {
+            continue;
+        }
+        keep.push(i);
+        for &j in order[k + 1..].iter() {
+            let same_class = match classes {
+                Some(c) => c[i] == c[j],
+                None => true,
+            };
+            if same_class && iou[i * n + j] > iou_threshold {
+                suppressed[j] = true;
+            }
+        }
+    }
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_box_iou() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 4>, f32, _> =
+            dev.tensor([[0.0, 0.0, 2.0, 2.0], [1.0, 0.0, 2.0, 4.0]]);
+        let b: Tensor<Rank2<3, 4>, f32, _> = dev.tensor([
+            [1.0, 1.0, 3.0, 3.0],
+            [0.0, 0.0, 2.0, 2.0],
+            [5.0, 5.0, 6.0, 6.0],
+        ]);
+        let r = box_iou(a, b);
+        assert_close(
+            &r.array(),
+            &[[1.0 / 7.0, 1.0, 0.0], [2.0 / 6.0, 2.0 / 6.0, 0.0]],
+        );
+    }
+
+    #[test]
+    fn test_box_iou_backward() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<1, 4>, f32, _> = dev.tensor([[0.0, 0.0, 2.0, 2.0]]);
+        let b: Tensor<Rank2<1, 4>, f32, _> = dev.tensor([[1.0, 1.0, 3.0, 3.0]]);
+        let r = box_iou(a.trace(), b.trace());
+        let g = r.sum().backward();
+        // iou = w * h / (8 - w * h) with w = min(ax2, bx2) - max(ax1, bx1), so
+        // d/dw = h * 8 / (8 - wh)^2 = 8 / 49. The areas contribute -1 / 49 per unit.
+        assert_close(
+            &g.get(&a).array(),
+            &[[2.0 / 49.0, 2.0 / 49.0, 6.0 / 49.0, 6.0 / 49.0]],
+        );
+        assert_close(
+            &g.get(&b).array(),
+            &[[-6.0 / 49.0, -6.0 / 49.0, -2.0 / 49.0, -2.0 / 49.0]],
+        );
+    }
+
+    #[test]
+    fn test_nms_keeps_highest_scores() {
+        let dev: TestDevice = Default::default();
+        let boxes: Tensor<Rank2<5, 4>, f32, _> = dev.tensor([
+            [0.0, 0.0, 10.0, 10.0],
+            [1.0, 1.0, 11.0, 11.0],
+            [20.0, 20.0, 30.0, 30.0],
+            [0.0, 0.0, 10.0, 9.0],
+            [21.0, 20.0, 30.0, 30.0],
+        ]);
+        let scores: Tensor<Rank1<5>, f32, _> = dev.tensor([0.8, 0.9, 0.3, 0.7, 0.6]);
+        assert_eq!(nms(&boxes, &scores, 0.5).as_vec(), [1, 4]);
+        // box 0 overlaps box 1 with iou 81 / 119, but box 3 with iou 0.9
+        assert_eq!(nms(&boxes, &scores, 0.7).as_vec(), [1, 0, 4]);
+        assert_eq!(nms(&boxes, &scores, 0.95).as_vec(), [1, 0, 3, 4, 2]);
+
+        let classes: Tensor<Rank1<5>, usize, _> = dev.tensor([0, 1, 0, 0, 1]);
+        assert_eq!(
+            batched_nms(&boxes, &scores, &classes, 0.5).as_vec(),
+            [1, 0, 4, 2]
+        );
+    }
+
+    #[test]
+    fn test_nms_nan_scores() {
+        let dev: TestDevice = Default::default();
+        let boxes: Tensor<Rank2<3, 4>, f32, _> = dev.tensor([
+            [0.0, 0.0, 10.0, 10.0],
+            [1.0, 1.0, 11.0, 11.0],
+            [20.0, 20.0, 30.0, 30.0],
+        ]);
+        let scores: Tensor<Rank1<3>, f32, _> = dev.tensor([0.8, f32::NAN, 0.3]);
+        assert_eq!(nms(&boxes, &scores, 0.5).as_vec(), [0, 2]);
+    }
+}
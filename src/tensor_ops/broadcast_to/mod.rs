@@ -77,9 +77,19 @@ impl<S: Shape, E: Dtype, D: BroadcastKernel<E>, T: Tape<D>> BroadcastTo for Tens
     {
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.upgrade(inp.device.forward(*dst, &inp.storage)?);
+        inp.device
+            .recorder()
+            .record("broadcast", &[inp.id], out.id, || {
+                let (inp, dst, out_id) = (inp.clone(), *dst, out.id);
+                move |bufs| {
+                    let storage = inp.device.forward(dst, bufs.get(&inp))?;
+                    bufs.insert::<Dst, E, D>(out_id, storage);
+                    Ok(())
+                }
+            });
         let phantom_out = out.clone();
         tape.try_alloc_grad(&inp)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
             inp.device.backward(grad_inp, grad_out)
@@ -88,6 +98,41 @@ impl<S: Shape, E: Dtype, D: BroadcastKernel<E>, T: Tape<D>> BroadcastTo for Tens
     }
 }
 
+impl<S: Shape, E: Dtype, D: BroadcastKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// Applies the reduction `f` along `Ax`, keeping the reduced dimensions in the
+    /// result with size 1. The reduced tensor is broadcasted to the [Const<1>]
+    /// dimensions, so no data is copied.
+    ///
+    /// **Pytorch equivalent**: passing `keepdim=True` to a reduction.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank3<2, 3, 4>> = dev.ones();
+    /// let r: Tensor<(Const<2>, Const<1>, Const<4>)> = t.clone().keepdim::<Axis<1>, _>(|t| t.sum());
+    /// assert_eq!(r.array(), [[[3.0; 4]], [[3.0; 4]]]);
+    /// let r: Tensor<Rank3<1, 3, 1>> = t.keepdim::<Axes2<0, 2>, _>(|t| t.var_with_ddof(1));
+    /// assert_eq!(r.array(), [[[0.0], [0.0], [0.0]]]);
+    /// ```
+    pub fn keepdim<Ax: Axes, F>(self, f: F) -> Tensor<S::Kept, E, D, T>
+    where
+        S: KeepDimShape<Ax>,
+        F: FnOnce(Self) -> Tensor<S::Reduced, E, D, T>,
+    {
+        self.try_keepdim::<Ax, _>(|t| Ok(f(t))).unwrap()
+    }
+
+    /// Fallible version of [Tensor::keepdim]
+    pub fn try_keepdim<Ax: Axes, F>(self, f: F) -> Result<Tensor<S::Kept, E, D, T>, D::Err>
+    where
+        S: KeepDimShape<Ax>,
+        F: FnOnce(Self) -> Result<Tensor<S::Reduced, E, D, T>, D::Err>,
+    {
+        let kept = self.shape().kept();
+        f(self)?.try_broadcast_like::<S::Kept, Ax>(&kept)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,4 +198,22 @@ mod tests {
         g.get(&a).array().assert_close(&a_grad.array(), 1e-4);
         g.get(&b).array().assert_close(&b_grad.array(), 1e-4);
     }
+
+    #[test]
+    fn test_keepdim() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 4.0, 0.5]]);
+        let r = a.trace().keepdim::<Axis<1>, _>(|t| t.max());
+        assert_eq!(r.shape(), &(Const::<2>, Const::<1>));
+        assert_eq!(r.array(), [[3.0], [4.0]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&a).array(), [[0.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+
+        let r: Tensor<Rank2<1, 3>, _, _> = a.keepdim::<Axis<0>, _>(|t| t.prod());
+        assert_eq!(r.array(), [[-1.0, 8.0, 1.5]]);
+
+        let b: Tensor<(usize, Const<3>), f32, _> = dev.zeros_like(&(5, Const));
+        let r = b.keepdim::<Axis<0>, _>(|t| t.mean());
+        assert_eq!(r.shape(), &(Const::<1>, Const::<3>));
+    }
 }
@@ -0,0 +1,29 @@
+struct CeilKernelOp {};
+
+extern "C" __global__ void ceil_forward(
+    const CeilKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    out[i] = ceilf(inp[i]);
+}
+
+extern "C" __global__ void ceil_backward(
+    const CeilKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float dx = 0.0;
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::CeilKernelOp {}
+
+impl UnaryOpCudaKernel for super::CeilKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/ceil.ptx"));
+    const MODULE_NAME: &'static str = "ceil";
+    const FWD_FN_NAME: &'static str = "ceil_forward";
+    const BWD_FN_NAME: &'static str = "ceil_backward";
+}
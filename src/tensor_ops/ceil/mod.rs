@@ -0,0 +1,55 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct CeilKernelOp;
+
+/// Rounds up to the nearest integer.
+///
+/// The derivative is `0`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.5, 0.0, 1.2]);
+/// let r = t.ceil();
+/// assert_eq!(r.array(), [-1.0, 0.0, 2.0]);
+/// ```
+pub fn ceil<S: Shape, E: Dtype, D: UnaryKernel<CeilKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.ceil()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<CeilKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [ceil]
+    pub fn ceil(self) -> Self {
+        self.try_ceil().unwrap()
+    }
+    /// See [ceil]
+    pub fn try_ceil(self) -> Result<Self, D::Err> {
+        try_unary_op(CeilKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_ceil() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.5, -1.0, 0.2, 1.7]);
+        let r = x.trace().ceil();
+        assert_eq!(r.array(), [-1.0, -1.0, 1.0, 2.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 4]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+use crate::tensor_ops::cpu_kernels::{BinaryDerivative, UnaryDerivative};
 
 impl UnaryDerivative<f32> for super::ClampKernelOp<f32> {
     #[inline(always)]
@@ -14,3 +14,18 @@ impl UnaryDerivative<f32> for super::ClampKernelOp<f32> {
         }
     }
 }
+
+impl BinaryDerivative<f32> for super::StraightThroughKernelOp {
+    #[inline(always)]
+    fn f(&self, _: &f32, y: &f32) -> f32 {
+        *y
+    }
+    #[inline(always)]
+    fn dfdx(&self, _: &f32, _: &f32) -> f32 {
+        1.0
+    }
+    #[inline(always)]
+    fn dfdy(&self, _: &f32, _: &f32) -> f32 {
+        0.0
+    }
+}
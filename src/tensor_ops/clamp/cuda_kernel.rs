@@ -1,4 +1,4 @@
-use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+use crate::tensor_ops::cuda_kernels::{BinaryOpCudaKernel, UnaryOpCudaKernel};
 
 unsafe impl cudarc::driver::AsKernelParam for super::ClampKernelOp<f32> {}
 
@@ -8,3 +8,12 @@ impl UnaryOpCudaKernel for super::ClampKernelOp<f32> {
     const FWD_FN_NAME: &'static str = "clamp_forward";
     const BWD_FN_NAME: &'static str = "clamp_backward";
 }
+
+unsafe impl cudarc::driver::AsKernelParam for super::StraightThroughKernelOp {}
+
+impl BinaryOpCudaKernel for super::StraightThroughKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/straight_through.ptx"));
+    const MODULE_NAME: &'static str = "straight_through";
+    const FWD_FN_NAME: &'static str = "straight_through_forward";
+    const BWD_FN_NAME: &'static str = "straight_through_backward";
+}
@@ -3,8 +3,15 @@ mod cpu_kernel;
 #[cfg(feature = "cuda")]
 mod cuda_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
-use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+use super::{
+    ops::{try_binary_op, try_unary_op, UnaryKernel},
+    Device,
+};
+use crate::{
+    gradients::{Merge, NoneTape, Tape},
+    shapes::*,
+    tensor::Tensor,
+};
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -13,8 +20,17 @@ pub struct ClampKernelOp<E> {
     pub max: E,
 }
 
+/// Returns the value of `rhs`, with the gradient of `lhs`: the gradient of the
+/// output is passed to `lhs` unchanged. See [Tensor::straight_through()].
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StraightThroughKernelOp;
+
 /// Clamp all elements between the provided min and max values.
 ///
+/// See [Tensor::clamp_between()] for tensor valued bounds, and [Tensor::clamp_ste()] for
+/// a straight-through gradient.
+///
 /// Example:
 /// ```rust
 /// # use dfdx::prelude::*;
@@ -42,6 +58,110 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<ClampKernelOp<E>, E>, T: Tape<D>> Tensor
     }
 }
 
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// Clamps each element between the matching elements of `min` and `max`. The gradient
+    /// flows to whichever of `self`, `min` or `max` is selected, and is split evenly on ties
+    /// like [Tensor::maximum()].
+    ///
+    /// **Pytorch equivalent**: `t.clamp(min, max)` with tensor bounds
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([-1.0, 0.5, 2.0]);
+    /// let min = dev.tensor([0.0, 0.0, 0.0]);
+    /// let max = dev.tensor([1.0, 0.25, 1.5]);
+    /// let r = t.clamp_between(min, max);
+    /// assert_eq!(r.array(), [0.0, 0.25, 1.5]);
+    /// ```
+    pub fn clamp_between<R: Tape<D>>(self, min: Tensor<S, E, D, R>, max: Tensor<S, E, D, R>) -> Self
+    where
+        T: Merge<R>,
+    {
+        self.try_clamp_between(min, max).unwrap()
+    }
+
+    /// See [Tensor::clamp_between()]
+    pub fn try_clamp_between<R: Tape<D>>(
+        self,
+        min: Tensor<S, E, D, R>,
+        max: Tensor<S, E, D, R>,
+    ) -> Result<Self, D::Err>
+    where
+        T: Merge<R>,
+    {
+        self.try_maximum(min)?.try_minimum(max)
+    }
+
+    /// Clamps all elements between `min` and `max` like [Tensor::clamp()], but with a
+    /// straight-through gradient: the gradient passes through unchanged, both inside and
+    /// outside of the bounds. This is used for quantization aware training, where the
+    /// forward pass is clipped but the weights should keep learning.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([-2.0, 0.5, 2.0]);
+    /// let r = t.trace().clamp_ste(-1.0, 1.0);
+    /// assert_eq!(r.array(), [-1.0, 0.5, 1.0]);
+    /// let g = r.sum().backward();
+    /// assert_eq!(g.get(&t).array(), [1.0; 3]);
+    /// ```
+    pub fn clamp_ste(self, min: E, max: E) -> Self {
+        self.try_clamp_ste(min, max).unwrap()
+    }
+
+    /// See [Tensor::clamp_ste()]
+    pub fn try_clamp_ste(self, min: E, max: E) -> Result<Self, D::Err> {
+        let value = self.retaped::<NoneTape>().try_clamp(min, max)?;
+        self.try_straight_through(value)
+    }
+
+    /// [Tensor::clamp_between()] with a straight-through gradient like [Tensor::clamp_ste()].
+    /// The bounds don't receive a gradient.
+    pub fn clamp_between_ste(self, min: &Tensor<S, E, D>, max: &Tensor<S, E, D>) -> Self {
+        self.try_clamp_between_ste(min, max).unwrap()
+    }
+
+    /// See [Tensor::clamp_between_ste()]
+    pub fn try_clamp_between_ste(
+        self,
+        min: &Tensor<S, E, D>,
+        max: &Tensor<S, E, D>,
+    ) -> Result<Self, D::Err> {
+        let value = self
+            .retaped::<NoneTape>()
+            .try_clamp_between(min.clone(), max.clone())?;
+        self.try_straight_through(value)
+    }
+
+    /// Returns `value`, but records the identity function of `self` on the tape, so the
+    /// gradient of the output is passed to `self` unchanged. This is the
+    /// [straight-through estimator](https://arxiv.org/abs/1308.3432) for ops without a
+    /// useful gradient, like rounding:
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([0.2, 1.7]);
+    /// let rounded = t.clone().round();
+    /// let r = t.trace().straight_through(rounded);
+    /// assert_eq!(r.array(), [0.0, 2.0]);
+    /// let g = r.sum().backward();
+    /// assert_eq!(g.get(&t).array(), [1.0; 2]);
+    /// ```
+    pub fn straight_through(self, value: Tensor<S, E, D>) -> Self {
+        self.try_straight_through(value).unwrap()
+    }
+
+    /// See [Tensor::straight_through()]
+    pub fn try_straight_through(self, value: Tensor<S, E, D>) -> Result<Self, D::Err> {
+        try_binary_op(StraightThroughKernelOp, self, value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -58,4 +178,49 @@ mod tests {
             &[[0.06131324, 0.16666667, 0.45304698], [0.0; 3]],
         );
     }
+
+    #[test]
+    fn test_clamp_between() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[-1.0, 0.0, 1.0], [-2.0, 2.0, 1.1]]);
+        let min = dev.tensor([[0.0, -1.0, -1.0], [-3.0, -3.0, 1.5]]);
+        let max = dev.tensor([[0.5, 1.0, 0.5], [-1.0, 3.0, 2.0]]);
+        let r = t.trace().clamp_between(min.trace(), max.trace());
+        assert_close(&r.array(), &[[0.0, 0.0, 0.5], [-2.0, 2.0, 1.5]]);
+
+        // the gradient flows to whichever of the three was selected
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[0.0, 1.0, 0.0], [1.0, 1.0, 0.0]]);
+        assert_eq!(g.get(&min).array(), [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert_eq!(g.get(&max).array(), [[0.0, 0.0, 1.0], [0.0, 0.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_clamp_ste() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[-1.0, 0.0, 1.0], [-2.0, 2.0, 1.1]]);
+        let r = t.trace().clamp_ste(-1.0, 1.0);
+        assert_close(&r.array(), &[[-1.0, 0.0, 1.0], [-1.0, 1.0, 1.0]]);
+        let g = r.exp().mean().backward();
+        // the gradient of exp() at the clamped values, passed through unchanged
+        assert_close(
+            &g.get(&t).array(),
+            &[
+                [0.06131324, 0.16666667, 0.45304698],
+                [0.06131324, 0.45304698, 0.45304698],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_clamp_between_ste() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([-1.0, 0.25, 3.0]);
+        let min = dev.tensor([0.0, 0.0, 0.0]);
+        let max = dev.tensor([1.0, 1.0, 2.0]);
+        let r = t.trace().clamp_between_ste(&min, &max);
+        assert_eq!(r.array(), [0.0, 0.25, 2.0]);
+        let g = (r * 2.0).sum().backward();
+        assert_eq!(g.get(&t).array(), [2.0; 3]);
+    }
 }
@@ -0,0 +1,57 @@
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+#define CMP_OP(FWD, SCALAR_FWD, SYMBOL) \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t *dims, \
+    const float *lhs, \
+    const size_t *lhs_strides, \
+    const float *rhs, \
+    const size_t *rhs_strides, \
+    bool *out \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+    unsigned int lhs_i = get_strided_index(i, num_dims, dims, lhs_strides); \
+    unsigned int rhs_i = get_strided_index(i, num_dims, dims, rhs_strides); \
+    out[i] = lhs[lhs_i] SYMBOL rhs[rhs_i]; \
+} \
+extern "C" __global__ void SCALAR_FWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t *dims, \
+    const float *lhs, \
+    const size_t *lhs_strides, \
+    const float rhs, \
+    bool *out \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+    unsigned int lhs_i = get_strided_index(i, num_dims, dims, lhs_strides); \
+    out[i] = lhs[lhs_i] SYMBOL rhs; \
+}
+
+CMP_OP(eq_forward, scalar_eq_forward, ==)
+CMP_OP(ne_forward, scalar_ne_forward, !=)
+CMP_OP(gt_forward, scalar_gt_forward, >)
+CMP_OP(ge_forward, scalar_ge_forward, >=)
+CMP_OP(lt_forward, scalar_lt_forward, <)
+CMP_OP(le_forward, scalar_le_forward, <=)
@@ -0,0 +1,84 @@
+use super::{CmpKernel, ScalarCmpKernel};
+use crate::{
+    shapes::{Shape, Unit},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+trait CmpOpCpuKernel<E: Unit> {
+    fn func(lhs: &E, rhs: &E) -> bool;
+}
+
+impl<E: Unit> CmpOpCpuKernel<E> for super::EqKernelOp {
+    #[inline(always)]
+    fn func(lhs: &E, rhs: &E) -> bool {
+        lhs == rhs
+    }
+}
+
+impl<E: Unit> CmpOpCpuKernel<E> for super::NeKernelOp {
+    #[inline(always)]
+    fn func(lhs: &E, rhs: &E) -> bool {
+        lhs != rhs
+    }
+}
+
+impl<E: Unit> CmpOpCpuKernel<E> for super::GtKernelOp {
+    #[inline(always)]
+    fn func(lhs: &E, rhs: &E) -> bool {
+        lhs > rhs
+    }
+}
+
+impl<E: Unit> CmpOpCpuKernel<E> for super::GeKernelOp {
+    #[inline(always)]
+    fn func(lhs: &E, rhs: &E) -> bool {
+        lhs >= rhs
+    }
+}
+
+impl<E: Unit> CmpOpCpuKernel<E> for super::LtKernelOp {
+    #[inline(always)]
+    fn func(lhs: &E, rhs: &E) -> bool {
+        lhs < rhs
+    }
+}
+
+impl<E: Unit> CmpOpCpuKernel<E> for super::LeKernelOp {
+    #[inline(always)]
+    fn func(lhs: &E, rhs: &E) -> bool {
+        lhs <= rhs
+    }
+}
+
+impl<Op: CmpOpCpuKernel<E>, E: Unit> CmpKernel<Op, E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, E>,
+        rhs: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        let mut out: Self::Storage<S, bool> = StridedArray::new(lhs.shape)?;
+        let mut lhs_iter = lhs.iter();
+        let mut rhs_iter = rhs.iter();
+        let mut out_iter = out.iter_mut();
+        while let Some((o, (l, r))) = out_iter.next().zip(lhs_iter.next().zip(rhs_iter.next())) {
+            *o = Op::func(l, r);
+        }
+        Ok(out)
+    }
+}
+
+impl<Op: CmpOpCpuKernel<E>, E: Unit> ScalarCmpKernel<Op, E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, E>,
+        rhs: E,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        let mut out: Self::Storage<S, bool> = StridedArray::new(lhs.shape)?;
+        let mut lhs_iter = lhs.iter();
+        let mut out_iter = out.iter_mut();
+        while let Some((o, l)) = out_iter.next().zip(lhs_iter.next()) {
+            *o = Op::func(l, &rhs);
+        }
+        Ok(out)
+    }
+}
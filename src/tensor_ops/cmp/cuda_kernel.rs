@@ -0,0 +1,133 @@
+use super::{CmpKernel, ScalarCmpKernel};
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/cmp.ptx"));
+const MODULE_NAME: &str = "cmp";
+const ALL_FN_NAMES: [&str; 12] = [
+    "eq_forward",
+    "ne_forward",
+    "gt_forward",
+    "ge_forward",
+    "lt_forward",
+    "le_forward",
+    "scalar_eq_forward",
+    "scalar_ne_forward",
+    "scalar_gt_forward",
+    "scalar_ge_forward",
+    "scalar_lt_forward",
+    "scalar_le_forward",
+];
+
+trait CmpOpCudaKernel {
+    /// Name of function in the .cu file
+    const FWD_FN_NAME: &'static str;
+
+    /// Name of function in the .cu file
+    const SCALAR_FWD_FN_NAME: &'static str;
+}
+
+macro_rules! cuda_cmp {
+    ($Op:ty, $Fwd:literal, $ScalarFwd:literal) => {
+        impl CmpOpCudaKernel for $Op {
+            const FWD_FN_NAME: &'static str = $Fwd;
+            const SCALAR_FWD_FN_NAME: &'static str = $ScalarFwd;
+        }
+    };
+}
+
+cuda_cmp!(super::EqKernelOp, "eq_forward", "scalar_eq_forward");
+cuda_cmp!(super::NeKernelOp, "ne_forward", "scalar_ne_forward");
+cuda_cmp!(super::GtKernelOp, "gt_forward", "scalar_gt_forward");
+cuda_cmp!(super::GeKernelOp, "ge_forward", "scalar_ge_forward");
+cuda_cmp!(super::LtKernelOp, "lt_forward", "scalar_lt_forward");
+cuda_cmp!(super::LeKernelOp, "le_forward", "scalar_le_forward");
+
+impl<Op: CmpOpCudaKernel> CmpKernel<Op, f32> for Cuda {
+    fn forward<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, f32>,
+        rhs: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, Op::FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = lhs.shape;
+        let strides = lhs.shape.strides();
+        let numel = shape.num_elements();
+
+        let mut storage = self.dev.alloc_zeros_async::<bool>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let lhs_strides: CudaSlice<usize> = self.dev.take_async(lhs.strides.into())?;
+        let rhs_strides: CudaSlice<usize> = self.dev.take_async(rhs.strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, Op::FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            S::NUM_DIMS,       // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            lhs.data.as_ref(), // const float *lhs,
+            &lhs_strides,      // const size_t *lhs_strides,
+            rhs.data.as_ref(), // const float *rhs,
+            &rhs_strides,      // const size_t *rhs_strides,
+            &mut storage,      // bool *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+}
+
+impl<Op: CmpOpCudaKernel> ScalarCmpKernel<Op, f32> for Cuda {
+    fn forward<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, f32>,
+        rhs: f32,
+    ) -> Result<Self::Storage<S, bool>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, Op::SCALAR_FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = lhs.shape;
+        let strides = lhs.shape.strides();
+        let numel = shape.num_elements();
+
+        let mut storage = self.dev.alloc_zeros_async::<bool>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let lhs_strides: CudaSlice<usize> = self.dev.take_async(lhs.strides.into())?;
+
+        let fwd_fn = self
+            .dev
+            .get_func(MODULE_NAME, Op::SCALAR_FWD_FN_NAME)
+            .unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            S::NUM_DIMS,       // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            lhs.data.as_ref(), // const float *lhs,
+            &lhs_strides,      // const size_t *lhs_strides,
+            rhs,               // float rhs,
+            &mut storage,      // bool *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+}
@@ -0,0 +1,153 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    shapes::{Shape, Unit},
+    tensor::{DeviceStorage, Tensor},
+};
+
+pub trait CmpKernel<Op, E: Unit>: DeviceStorage {
+    fn forward<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, E>,
+        rhs: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, bool>, Self::Err>;
+}
+
+pub trait ScalarCmpKernel<Op, E: Unit>: DeviceStorage {
+    fn forward<S: Shape>(
+        &self,
+        lhs: &Self::Storage<S, E>,
+        rhs: E,
+    ) -> Result<Self::Storage<S, bool>, Self::Err>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EqKernelOp;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NeKernelOp;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GtKernelOp;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GeKernelOp;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LtKernelOp;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LeKernelOp;
+
+macro_rules! cmp_ops {
+    ($Op:ty, $fn:ident, $try_fn:ident, $scalar_fn:ident, $try_scalar_fn:ident, $sym:literal) => {
+        impl<S: Shape, E: Unit, D: DeviceStorage, T> Tensor<S, E, D, T> {
+            #[doc = concat!("Element wise `lhs ", $sym, " rhs`. The result is a `bool` tensor and is never tracked by a tape.")]
+            pub fn $fn<R>(&self, rhs: &Tensor<S, E, D, R>) -> Tensor<S, bool, D>
+            where
+                D: CmpKernel<$Op, E>,
+            {
+                self.$try_fn(rhs).unwrap()
+            }
+
+            #[doc = concat!("Fallible version of [Tensor::", stringify!($fn), "]")]
+            pub fn $try_fn<R>(&self, rhs: &Tensor<S, E, D, R>) -> Result<Tensor<S, bool, D>, D::Err>
+            where
+                D: CmpKernel<$Op, E>,
+            {
+                let storage = CmpKernel::<$Op, E>::forward(&self.device, &self.storage, &rhs.storage)?;
+                Ok(self.device.upgrade(storage))
+            }
+
+            #[doc = concat!("Element wise `lhs ", $sym, " scalar`. The result is a `bool` tensor and is never tracked by a tape.")]
+            pub fn $scalar_fn(&self, rhs: E) -> Tensor<S, bool, D>
+            where
+                D: ScalarCmpKernel<$Op, E>,
+            {
+                self.$try_scalar_fn(rhs).unwrap()
+            }
+
+            #[doc = concat!("Fallible version of [Tensor::", stringify!($scalar_fn), "]")]
+            pub fn $try_scalar_fn(&self, rhs: E) -> Result<Tensor<S, bool, D>, D::Err>
+            where
+                D: ScalarCmpKernel<$Op, E>,
+            {
+                let storage = ScalarCmpKernel::<$Op, E>::forward(&self.device, &self.storage, rhs)?;
+                Ok(self.device.upgrade(storage))
+            }
+        }
+    };
+}
+
+cmp_ops!(EqKernelOp, eq, try_eq, scalar_eq, try_scalar_eq, "==");
+cmp_ops!(NeKernelOp, ne, try_ne, scalar_ne, try_scalar_ne, "!=");
+cmp_ops!(GtKernelOp, gt, try_gt, scalar_gt, try_scalar_gt, ">");
+cmp_ops!(GeKernelOp, ge, try_ge, scalar_ge, try_scalar_ge, ">=");
+cmp_ops!(LtKernelOp, lt, try_lt, scalar_lt, try_scalar_lt, "<");
+cmp_ops!(LeKernelOp, le, try_le, scalar_le, try_scalar_le, "<=");
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_cmp_float() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0, 3.0], [-1.0, f32::NAN, 0.5]]);
+        let b = dev.tensor([[1.0, 0.0, 4.0], [-1.0, f32::NAN, -0.5]]);
+        assert_eq!(
+            a.eq(&b).array(),
+            [[true, false, false], [true, false, false]]
+        );
+        assert_eq!(a.ne(&b).array(), [[false, true, true], [false, true, true]]);
+        assert_eq!(
+            a.gt(&b).array(),
+            [[false, true, false], [false, false, true]]
+        );
+        assert_eq!(a.ge(&b).array(), [[true, true, false], [true, false, true]]);
+        assert_eq!(
+            a.lt(&b).array(),
+            [[false, false, true], [false, false, false]]
+        );
+        assert_eq!(
+            a.le(&b).array(),
+            [[true, false, true], [true, false, false]]
+        );
+    }
+
+    #[test]
+    fn test_cmp_scalar() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([-1.0, 0.0, 1.0]);
+        assert_eq!(a.scalar_eq(0.0).array(), [false, true, false]);
+        assert_eq!(a.scalar_ne(0.0).array(), [true, false, true]);
+        assert_eq!(a.scalar_gt(0.0).array(), [false, false, true]);
+        assert_eq!(a.scalar_ge(0.0).array(), [false, true, true]);
+        assert_eq!(a.scalar_lt(0.0).array(), [true, false, false]);
+        assert_eq!(a.scalar_le(0.0).array(), [true, true, false]);
+    }
+
+    #[test]
+    fn test_cmp_int() {
+        let dev: Cpu = Default::default();
+        let a = dev.tensor([1i32, 5, -3]);
+        let b = dev.tensor([1i32, 2, 4]);
+        assert_eq!(a.eq(&b).array(), [true, false, false]);
+        assert_eq!(a.gt(&b).array(), [false, true, false]);
+        assert_eq!(a.scalar_le(1).array(), [true, false, true]);
+
+        let labels = dev.tensor([0u8, 3, 3, 1]);
+        assert_eq!(labels.scalar_eq(3).array(), [false, true, true, false]);
+    }
+
+    #[test]
+    fn test_cmp_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let b = dev.tensor([2.0, 3.0]).broadcast::<Rank2<2, 2>, Axis<0>>();
+        assert_eq!(a.ge(&b).array(), [[false, false], [true, true]]);
+    }
+}
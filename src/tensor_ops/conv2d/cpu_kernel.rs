@@ -1,6 +1,5 @@
 use crate::shapes::Shape;
 use crate::tensor::cpu::*;
-use crate::tensor_ops::matmul::cpu_kernel::matmul;
 
 use super::{Conv2DKernel, Conv2DOp};
 
@@ -29,7 +28,7 @@ impl Cpu {
         let m = op.chan_out;
         let k = op.chan_in * op.kernel * op.kernel;
         let n = op.w_out * op.h_out;
-        matmul(
+        self.matmul(
             View::new(filters, (m, k)),
             View::new(inp_patches_buf.view().data, (k, n)),
             &mut ViewMut::new(out, (m, n)),
@@ -79,7 +78,7 @@ impl Cpu {
             let m = op.chan_in;
             let k = op.chan_out * op.kernel * op.kernel;
             let n = op.h_in * op.w_in;
-            matmul(
+            self.matmul(
                 View::new(filters_tr, (m, k)),
                 View::new(out_patches_buf.view().data, (k, n)),
                 &mut ViewMut::new(grad_img, (m, n)),
@@ -92,7 +91,7 @@ impl Cpu {
             let m = op.chan_in;
             let k = op.h_in * op.w_in;
             let n = op.chan_out * op.kernel * op.kernel;
-            matmul(
+            self.matmul(
                 View::new(img, (m, k)),
                 View::new(out_patches_buf.view().data, (n, k)).tr(),
                 &mut ViewMut::new(grad_filters_tr, (m, n)),
@@ -197,4 +196,41 @@ impl Conv2DKernel<f32> for Cpu {
 
         Ok(())
     }
+
+    fn unfold<L: Shape, O: Shape>(
+        &self,
+        op: Conv2DOp,
+        img: &Self::Storage<L, f32>,
+        patches: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istride = if L::NUM_DIMS == 3 {
+            0
+        } else {
+            debug_assert_eq!(L::NUM_DIMS, 4);
+            img.strides[0]
+        };
+        let img = img.data.as_ref();
+        let mut i = 0;
+        let buf = Arc::make_mut(&mut patches.data);
+        for i_batch in 0..op.batch {
+            let img = &img[i_batch * istride..];
+            for c in 0..op.chan_in {
+                for k1 in 0..op.kernel {
+                    for k2 in 0..op.kernel {
+                        for oh in 0..op.h_out {
+                            for ow in 0..op.w_out {
+                                let y = (oh * op.stride + k1).wrapping_sub(op.padding);
+                                let x = (ow * op.stride + k2).wrapping_sub(op.padding);
+                                if y < op.h_in && x < op.w_in {
+                                    buf[i] = img[c * (op.w_in * op.h_in) + y * op.w_in + x];
+                                }
+                                i += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -155,4 +155,28 @@ impl super::Conv2DKernel<f32> for Cuda {
 
         Ok(())
     }
+
+    fn unfold<L: Shape, O: Shape>(
+        &self,
+        op: super::Conv2DOp,
+        img: &Self::Storage<L, f32>,
+        patches: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        assert_eq!(
+            img.shape().strides(),
+            img.strides,
+            "Only works with contiguous image strides"
+        );
+
+        if !self.dev.has_func(MODULE_NAME, ALL_FN_NAMES[0]) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let unfold_fn = self.dev.get_func(MODULE_NAME, UNFOLD_INPUT_FN).unwrap();
+        let cfg = LaunchConfig::for_num_elems(patches.shape.num_elements() as u32);
+        let params = (op, img.data.as_ref(), Arc::make_mut(&mut patches.data));
+        unsafe { unfold_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
 }
@@ -73,12 +73,27 @@ pub(super) trait Conv2DKernel<E: Dtype>: DeviceStorage {
         grad_rhs: &mut Self::Storage<R, E>,
         grad_out: &Self::Storage<O, E>,
     ) -> Result<(), Self::Err>;
+
+    /// Unfolds the patches of `img` into `patches`, with shape
+    /// `(batch, chan_in * kernel * kernel, h_out * w_out)`.
+    fn unfold<L: Shape, O: Shape>(
+        &self,
+        op: Conv2DOp,
+        img: &Self::Storage<L, E>,
+        patches: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
 }
 
-pub trait ConvAlgebra<const K: usize, const S: usize, const P: usize>: ConstDim {
-    type Convolved: ConstDim;
+/// Computes the output dimension of a convolution with kernel size `K`, stride `S`
+/// and padding `P`.
+///
+/// With the `nightly` feature, [Const] dimensions convolve to [Const] dimensions.
+/// Otherwise every dimension convolves to a `usize` that is checked at runtime.
+pub trait ConvAlgebra<const K: usize, const S: usize, const P: usize>: Dim {
+    type Convolved: Dim;
 }
 
+#[cfg(feature = "nightly")]
 impl<const D: usize, const K: usize, const S: usize, const P: usize> ConvAlgebra<K, S, P>
     for Const<D>
 where
@@ -87,6 +102,17 @@ where
     type Convolved = Const<{ (D + 2 * P - K) / S + 1 }>;
 }
 
+#[cfg(not(feature = "nightly"))]
+impl<const D: usize, const K: usize, const S: usize, const P: usize> ConvAlgebra<K, S, P>
+    for Const<D>
+{
+    type Convolved = usize;
+}
+
+impl<const K: usize, const S: usize, const P: usize> ConvAlgebra<K, S, P> for usize {
+    type Convolved = usize;
+}
+
 pub trait TryConv2DTo<F, const S: usize, const P: usize>: HasErr {
     type Output;
     fn conv2d_to(self, filters: F) -> Self::Output {
@@ -115,47 +141,72 @@ pub trait TryConv2D<F> {
 
 impl<T, F> TryConv2D<F> for T {}
 
+/// Unfolds the patches that a convolution with kernel size `K`, stride `S` and padding `P`
+/// multiplies with its filters (im2col), as a `(batch, C * K * K, H_out * W_out)` tensor
+/// without a tape.
+pub trait TryUnfold2D<const K: usize, const S: usize, const P: usize>: HasErr {
+    type Output;
+    fn try_unfold2d(self) -> Result<Self::Output, Self::Err>;
+}
+
+impl<
+        B: Dim,
+        const C: usize,
+        H: Dim,
+        W: Dim,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        D: Conv2DKernel<f32> + ZerosTensor<f32>,
+        T,
+    > TryUnfold2D<K, S, P> for Tensor<(B, Const<C>, H, W), f32, D, T>
+{
+    type Output = Tensor<(usize, usize, usize), f32, D>;
+    fn try_unfold2d(self) -> Result<Self::Output, Self::Err> {
+        let &(batch, _, h, w) = self.shape();
+        let op = Conv2DOp::new(S, P, K, [batch.size(), C, h.size(), w.size()], 0);
+        let shape = (op.batch, C * K * K, op.h_out * op.w_out);
+        let mut patches = self.device.try_zeros_like(&shape)?;
+        self.device
+            .unfold(op, &self.storage, &mut patches.storage)?;
+        Ok(patches)
+    }
+}
+
 impl<
         const C: usize,
-        const H: usize,
-        const W: usize,
+        H: ConvAlgebra<K, S, P>,
+        W: ConvAlgebra<K, S, P>,
         const O: usize,
         const K: usize,
         const S: usize,
         const P: usize,
         D: Conv2DKernel<f32> + ZerosTensor<f32>,
         T: 'static + Tape<D>,
-    > TryConv2DTo<Tensor<Rank4<O, C, K, K>, f32, D>, S, P> for Tensor<Rank3<C, H, W>, f32, D, T>
-where
-    Const<H>: ConvAlgebra<K, S, P>,
-    Const<W>: ConvAlgebra<K, S, P>,
+    > TryConv2DTo<Tensor<Rank4<O, C, K, K>, f32, D>, S, P> for Tensor<(Const<C>, H, W), f32, D, T>
 {
-    type Output = Tensor<
-        (
-            Const<O>,
-            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
-            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
-        ),
-        f32,
-        D,
-        T,
-    >;
+    type Output = Tensor<(Const<O>, H::Convolved, W::Convolved), f32, D, T>;
 
     fn try_conv2d_to(
         self,
         filters: Tensor<Rank4<O, C, K, K>, f32, D>,
     ) -> Result<Self::Output, Self::Err> {
-        let op = Conv2DOp::new(S, P, K, [1, C, H, W], O);
+        let &(_, h, w) = self.shape();
+        let op = Conv2DOp::new(S, P, K, [1, C, h.size(), w.size()], O);
         let (lhs, ltape) = self.split_tape();
         let (rhs, rtape) = filters.split_tape();
         let mut tape = ltape.merge(rtape);
-        let mut out = lhs.device.try_zeros()?;
+        let mut out = lhs.device.try_zeros_like(&(
+            Const,
+            H::Convolved::from_size(op.h_out).unwrap(),
+            W::Convolved::from_size(op.w_out).unwrap(),
+        ))?;
         lhs.device
             .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
         let phantom_out = out.clone();
         tape.try_alloc_grad(&lhs)?;
         tape.try_alloc_grad(&rhs)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
             lhs.device
@@ -168,8 +219,8 @@ where
 impl<
         B: Dim,
         const C: usize,
-        const H: usize,
-        const W: usize,
+        H: ConvAlgebra<K, S, P>,
+        W: ConvAlgebra<K, S, P>,
         const O: usize,
         const K: usize,
         const S: usize,
@@ -177,40 +228,30 @@ impl<
         D: Conv2DKernel<f32> + ZerosTensor<f32>,
         T: 'static + Tape<D>,
     > TryConv2DTo<Tensor<Rank4<O, C, K, K>, f32, D>, S, P>
-    for Tensor<(B, Const<C>, Const<H>, Const<W>), f32, D, T>
-where
-    Const<H>: ConvAlgebra<K, S, P>,
-    Const<W>: ConvAlgebra<K, S, P>,
+    for Tensor<(B, Const<C>, H, W), f32, D, T>
 {
-    type Output = Tensor<
-        (
-            B,
-            Const<O>,
-            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
-            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
-        ),
-        f32,
-        D,
-        T,
-    >;
+    type Output = Tensor<(B, Const<O>, H::Convolved, W::Convolved), f32, D, T>;
     fn try_conv2d_to(
         self,
         filters: Tensor<Rank4<O, C, K, K>, f32, D>,
     ) -> Result<Self::Output, Self::Err> {
-        let batch = self.shape().0;
-        let op = Conv2DOp::new(S, P, K, [batch.size(), C, H, W], O);
+        let &(batch, _, h, w) = self.shape();
+        let op = Conv2DOp::new(S, P, K, [batch.size(), C, h.size(), w.size()], O);
         let (lhs, ltape) = self.split_tape();
         let (rhs, rtape) = filters.split_tape();
-        let mut out =
-            lhs.device
-                .try_zeros_like(&(batch, Const, Default::default(), Default::default()))?;
+        let mut out = lhs.device.try_zeros_like(&(
+            batch,
+            Const,
+            H::Convolved::from_size(op.h_out).unwrap(),
+            W::Convolved::from_size(op.w_out).unwrap(),
+        ))?;
         let mut tape = ltape.merge(rtape);
         lhs.device
             .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
         let phantom_out = out.clone();
         tape.try_alloc_grad(&lhs)?;
         tape.try_alloc_grad(&rhs)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
             lhs.device
@@ -221,6 +262,7 @@ where
     }
 }
 
+#[cfg(feature = "nightly")]
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -473,3 +515,26 @@ mod tests {
         ]);
     }
 }
+
+#[cfg(test)]
+mod runtime_tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_conv2d_runtime_dims_match_const_dims() {
+        let dev: TestDevice = Default::default();
+        let weight: Tensor<Rank4<2, 1, 2, 2>, f32, _> = dev.sample_normal();
+        let x: Tensor<Rank3<1, 3, 4>, f32, _> = dev.sample_normal();
+        let x_dyn = x.clone().reshape_like(&(Const::<1>, 3, 4));
+
+        let y = x.trace().conv2d::<1, 0>(weight.clone());
+        let y_dyn = x_dyn.trace().conv2d::<1, 0>(weight.clone());
+        assert_eq!(y_dyn.shape(), &(Const, 2, 3));
+        assert_close(&y_dyn.as_vec(), &y.as_vec());
+
+        let g = y.exp().mean().backward();
+        let g_dyn = y_dyn.exp().mean().backward();
+        assert_close(&g_dyn.get(&x_dyn).as_vec(), &g.get(&x).as_vec());
+        assert_close(&g_dyn.get(&weight).array(), &g.get(&weight).array());
+    }
+}
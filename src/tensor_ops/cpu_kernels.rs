@@ -1,7 +1,7 @@
 use super::ops::{BinaryKernel, UnaryKernel};
 use crate::{
-    shapes::{Dtype, Shape},
-    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+    shapes::{Dtype, Shape, Unit},
+    tensor::cpu::{Cpu, CpuError, LendingIterator, StridedArray},
 };
 
 pub trait UnaryDerivative<E> {
@@ -15,6 +15,39 @@ pub trait BinaryDerivative<E> {
     fn dfdy(&self, x: &E, y: &E) -> E;
 }
 
+/// Implements the scalar & binary versions of an arithmetic op for integer tensors on the
+/// [Cpu], using the wrapping version of the operation (e.g. `wrapping_add`).
+///
+/// Integer tensors don't support gradients, so these are only implemented for tensors
+/// without a tape; there is no derivative to record on one.
+macro_rules! int_arithmetic {
+    ($Try:ident, $try_fn:ident, $method:ident, [$($Ty:ty),*]) => {
+        $(
+            impl<S: $crate::shapes::Shape> $Try<$Ty> for $crate::tensor::Tensor<S, $Ty, Cpu> {
+                fn $try_fn(self, rhs: $Ty) -> Result<Self, Self::Err> {
+                    let mut out = self.storage.clone();
+                    for x in out.buf_iter_mut() {
+                        *x = x.$method(rhs);
+                    }
+                    Ok(self.device.upgrade(out))
+                }
+            }
+
+            impl<S: $crate::shapes::Shape> $Try for $crate::tensor::Tensor<S, $Ty, Cpu> {
+                fn $try_fn(self, rhs: Self) -> Result<Self, Self::Err> {
+                    let out = $crate::tensor_ops::cpu_kernels::binary_map(
+                        &self.storage,
+                        &rhs.storage,
+                        |x: &$Ty, y: &$Ty| x.$method(*y),
+                    )?;
+                    Ok(self.device.upgrade(out))
+                }
+            }
+        )*
+    };
+}
+pub(crate) use int_arithmetic;
+
 impl<E: Dtype, Op: UnaryDerivative<E>> UnaryKernel<Op, E> for Cpu {
     fn forward<S: Shape>(
         &self,
@@ -51,14 +84,7 @@ impl<E: Dtype, Op: BinaryDerivative<E>> BinaryKernel<Op, E> for Cpu {
         lhs: &Self::Storage<S, E>,
         rhs: &Self::Storage<S, E>,
     ) -> Result<Self::Storage<S, E>, Self::Err> {
-        let mut out: Self::Storage<S, E> = StridedArray::new(lhs.shape)?;
-        let mut lhs_iter = lhs.iter();
-        let mut rhs_iter = rhs.iter();
-        let mut out_iter = out.iter_mut();
-        while let Some((o, (l, r))) = out_iter.next().zip(lhs_iter.next().zip(rhs_iter.next())) {
-            *o = op.f(l, r);
-        }
-        Ok(out)
+        binary_map(lhs, rhs, |l, r| op.f(l, r))
     }
     fn backward<S: Shape>(
         &self,
@@ -86,3 +112,19 @@ impl<E: Dtype, Op: BinaryDerivative<E>> BinaryKernel<Op, E> for Cpu {
         Ok(())
     }
 }
+
+/// Applies `f` to each pair of elements of `lhs` and `rhs`, which may have different strides.
+pub(crate) fn binary_map<S: Shape, E: Unit>(
+    lhs: &StridedArray<S, E>,
+    rhs: &StridedArray<S, E>,
+    mut f: impl FnMut(&E, &E) -> E,
+) -> Result<StridedArray<S, E>, CpuError> {
+    let mut out: StridedArray<S, E> = StridedArray::new(lhs.shape)?;
+    let mut lhs_iter = lhs.iter();
+    let mut rhs_iter = rhs.iter();
+    let mut out_iter = out.iter_mut();
+    while let Some((o, (l, r))) = out_iter.next().zip(lhs_iter.next().zip(rhs_iter.next())) {
+        *o = f(l, r);
+    }
+    Ok(out)
+}
@@ -0,0 +1,248 @@
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::{Dtype, Shape},
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+/// Applies a user defined differentiable operation to `t`. This lets code outside of
+/// this crate add new ops that are recorded on the tape like any builtin op.
+///
+/// - `forward` computes the output storage from the input storage.
+/// - `backward` receives the input storage, the input's gradient (which it should
+///   **add** to), and the gradient of the output.
+///
+/// Both closures work directly on the device's storage ([crate::tensor::StridedArray] for [crate::tensor::Cpu]),
+/// so supporting multiple devices is done by implementing the closures for each device.
+///
+/// Example implementing `x^3` on the cpu:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank1<3>> = dev.tensor([1.0, 2.0, 3.0]);
+/// let r = custom_op::<_, Rank1<3>, _, _, _, _, _>(
+///     t.trace(),
+///     |inp| {
+///         let mut out = inp.clone();
+///         out.data_mut().iter_mut().for_each(|x| *x = x.powi(3));
+///         Ok(out)
+///     },
+///     |inp, grad_inp, grad_out| {
+///         let x = inp.data().iter();
+///         let g = grad_inp.data_mut().iter_mut().zip(grad_out.data());
+///         x.zip(g).for_each(|(x, (gi, go))| *gi += 3.0 * x * x * go);
+///         Ok(())
+///     },
+/// );
+/// assert_eq!(r.array(), [1.0, 8.0, 27.0]);
+/// let g = r.sum().backward();
+/// assert_eq!(g.get(&t).array(), [3.0, 12.0, 27.0]);
+/// ```
+pub fn custom_op<Src: Shape, Dst: Shape, E: Dtype, D: DeviceStorage, T: Tape<D>, Fwd, Bwd>(
+    t: Tensor<Src, E, D, T>,
+    forward: Fwd,
+    backward: Bwd,
+) -> Tensor<Dst, E, D, T>
+where
+    Fwd: FnOnce(&D::Storage<Src, E>) -> Result<D::Storage<Dst, E>, D::Err>,
+    Bwd: 'static
+        + FnOnce(
+            &D::Storage<Src, E>,
+            &mut D::Storage<Src, E>,
+            &D::Storage<Dst, E>,
+        ) -> Result<(), D::Err>,
+{
+    t.custom_op(forward, backward)
+}
+
+/// Two input version of [custom_op()]. `backward` receives the storage & gradient
+/// of both inputs, and the gradient of the output.
+///
+/// **Panics** if `lhs` and `rhs` are the same tensor.
+pub fn custom_binary_op<L, R, Dst, E, D, LTape, RTape, Fwd, Bwd>(
+    lhs: Tensor<L, E, D, LTape>,
+    rhs: Tensor<R, E, D, RTape>,
+    forward: Fwd,
+    backward: Bwd,
+) -> Tensor<Dst, E, D, LTape>
+where
+    L: Shape,
+    R: Shape,
+    Dst: Shape,
+    E: Dtype,
+    D: DeviceStorage,
+    RTape: Tape<D>,
+    LTape: Tape<D> + Merge<RTape>,
+    Fwd: FnOnce(&D::Storage<L, E>, &D::Storage<R, E>) -> Result<D::Storage<Dst, E>, D::Err>,
+    Bwd: 'static
+        + FnOnce(
+            &D::Storage<L, E>,
+            &mut D::Storage<L, E>,
+            &D::Storage<R, E>,
+            &mut D::Storage<R, E>,
+            &D::Storage<Dst, E>,
+        ) -> Result<(), D::Err>,
+{
+    try_custom_binary_op(lhs, rhs, forward, backward).unwrap()
+}
+
+/// Fallible version of [custom_binary_op()]
+pub fn try_custom_binary_op<L, R, Dst, E, D, LTape, RTape, Fwd, Bwd>(
+    lhs: Tensor<L, E, D, LTape>,
+    rhs: Tensor<R, E, D, RTape>,
+    forward: Fwd,
+    backward: Bwd,
+) -> Result<Tensor<Dst, E, D, LTape>, D::Err>
+where
+    L: Shape,
+    R: Shape,
+    Dst: Shape,
+    E: Dtype,
+    D: DeviceStorage,
+    RTape: Tape<D>,
+    LTape: Tape<D> + Merge<RTape>,
+    Fwd: FnOnce(&D::Storage<L, E>, &D::Storage<R, E>) -> Result<D::Storage<Dst, E>, D::Err>,
+    Bwd: 'static
+        + FnOnce(
+            &D::Storage<L, E>,
+            &mut D::Storage<L, E>,
+            &D::Storage<R, E>,
+            &mut D::Storage<R, E>,
+            &D::Storage<Dst, E>,
+        ) -> Result<(), D::Err>,
+{
+    let (lhs, ltape) = lhs.split_tape();
+    let (rhs, rtape) = rhs.split_tape();
+    let mut tape = ltape.merge(rtape);
+    let storage = forward(&lhs.storage, &rhs.storage)?;
+    let out = lhs.device.upgrade(storage);
+    let phantom_out = out.clone();
+    tape.try_alloc_grad(&lhs)?;
+    tape.try_alloc_grad(&rhs)?;
+    tape.try_alloc_output_grad(&out)?;
+    tape.add_named_backward_op(core::any::type_name::<Fwd>(), move |grads| {
+        let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+        backward(&lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+    });
+    Ok(out.put_tape(tape))
+}
+
+impl<Src: Shape, E: Dtype, D: DeviceStorage, T: Tape<D>> Tensor<Src, E, D, T> {
+    /// See [custom_op()]
+    pub fn custom_op<Dst: Shape, Fwd, Bwd>(
+        self,
+        forward: Fwd,
+        backward: Bwd,
+    ) -> Tensor<Dst, E, D, T>
+    where
+        Fwd: FnOnce(&D::Storage<Src, E>) -> Result<D::Storage<Dst, E>, D::Err>,
+        Bwd: 'static
+            + FnOnce(
+                &D::Storage<Src, E>,
+                &mut D::Storage<Src, E>,
+                &D::Storage<Dst, E>,
+            ) -> Result<(), D::Err>,
+    {
+        self.try_custom_op(forward, backward).unwrap()
+    }
+
+    /// See [custom_op()]
+    pub fn try_custom_op<Dst: Shape, Fwd, Bwd>(
+        self,
+        forward: Fwd,
+        backward: Bwd,
+    ) -> Result<Tensor<Dst, E, D, T>, D::Err>
+    where
+        Fwd: FnOnce(&D::Storage<Src, E>) -> Result<D::Storage<Dst, E>, D::Err>,
+        Bwd: 'static
+            + FnOnce(
+                &D::Storage<Src, E>,
+                &mut D::Storage<Src, E>,
+                &D::Storage<Dst, E>,
+            ) -> Result<(), D::Err>,
+    {
+        let (inp, mut tape) = self.split_tape();
+        let storage = forward(&inp.storage)?;
+        let out = inp.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_named_backward_op(core::any::type_name::<Fwd>(), move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            backward(&inp.storage, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::assert_close;
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    type Arr<S> = StridedArray<S, f32>;
+
+    #[test]
+    fn test_custom_reduce_op() {
+        let dev: Cpu = Default::default();
+        let t = dev.tensor([[1.0, -2.0, 3.0], [-4.0, 5.0, -6.0]]);
+        // sum of absolute values along the last axis
+        let r: Tensor<Rank1<2>, _, _, _> = t.trace().custom_op(
+            |inp: &Arr<Rank2<2, 3>>| {
+                let mut out = StridedArray::try_new_with(Default::default(), 0.0)?;
+                for (i, o) in out.data_mut().iter_mut().enumerate() {
+                    *o = inp.data()[i * 3..(i + 1) * 3].iter().map(|x| x.abs()).sum();
+                }
+                Ok(out)
+            },
+            |inp: &Arr<Rank2<2, 3>>, grad_inp: &mut Arr<Rank2<2, 3>>, grad_out: &Arr<Rank1<2>>| {
+                let grad_inp = grad_inp.data_mut();
+                for (i, x) in inp.data().iter().enumerate() {
+                    grad_inp[i] += x.signum() * grad_out.data()[i / 3];
+                }
+                Ok(())
+            },
+        );
+        assert_eq!(r.array(), [6.0, 15.0]);
+        let g = (r * dev.tensor([1.0, 2.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [[1.0, -1.0, 1.0], [-2.0, 2.0, -2.0]]);
+    }
+
+    #[test]
+    fn test_custom_binary_op() {
+        let dev: Cpu = Default::default();
+        let a = dev.tensor([1.0, 2.0, 3.0]);
+        let b = dev.tensor([0.5, -1.0, 2.0]);
+        // a * exp(b)
+        let r = custom_binary_op::<_, _, Rank1<3>, _, _, _, _, _, _>(
+            a.trace(),
+            b.clone(),
+            |a: &Arr<Rank1<3>>, b: &Arr<Rank1<3>>| {
+                let mut out = a.clone();
+                let out_data = out.data_mut();
+                for (o, b) in out_data.iter_mut().zip(b.data()) {
+                    *o *= b.exp();
+                }
+                Ok(out)
+            },
+            |a: &Arr<Rank1<3>>,
+             grad_a: &mut Arr<Rank1<3>>,
+             b: &Arr<Rank1<3>>,
+             grad_b: &mut Arr<Rank1<3>>,
+             grad_out: &Arr<Rank1<3>>| {
+                let grad_a = grad_a.data_mut();
+                let grad_b = grad_b.data_mut();
+                for i in 0..3 {
+                    let (x, y, go) = (a.data()[i], b.data()[i], grad_out.data()[i]);
+                    grad_a[i] += y.exp() * go;
+                    grad_b[i] += x * y.exp() * go;
+                }
+                Ok(())
+            },
+        );
+        let expected = a.clone() * b.clone().exp();
+        assert_close(&r.array(), &expected.array());
+        let g = r.sum().backward();
+        assert_close(&g.get(&a).array(), &b.clone().exp().array());
+        assert_close(&g.get(&b).array(), &expected.array());
+    }
+}
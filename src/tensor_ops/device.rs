@@ -21,12 +21,23 @@ pub trait Device<E: Dtype>:
     + super::sum_to::SumKernel<E>
     + super::max_to::MaxReduceKernel<E>
     + super::min_to::MinReduceKernel<E>
+    + super::prod_to::ProdReduceKernel<E>
+    + super::logsumexp_to::LogSumExpKernel<E>
+    + super::softmax::SoftmaxKernel<E>
     + super::permute_to::PermuteKernel<E>
     + super::reshape_to::ReshapeKernel<E>
 
     // indexing
     + super::select_and_gather::ReplaceDimKernel<E>
     + super::select_and_gather::RemoveDimKernel<E>
+    + super::index_select::IndexSelectKernel<E>
+    + super::sparse_matmul::SparseMatMulKernel<E>
+    + super::segment_reduce::SegmentReduceKernel<E>
+    + super::slice::SliceKernel<E>
+    + super::one_hot::OneHotKernel<E>
+    + super::augment::CropFlipKernel<E>
+    + super::grid_sample::GridSampleKernel<E>
+    + super::roi_align::RoiAlignKernel<E>
 
     // matmuls
     + super::matmul::VecMatKernel<E>
@@ -36,6 +47,12 @@ pub trait Device<E: Dtype>:
     + super::matmul::MatMatBatch3Kernel<E>
     + super::matmul::MatMatBatch4Kernel<E>
 
+    // fused elementwise
+    + super::fused::FusedElementwiseKernel<E>
+    + super::layer_norm::LayerNormKernel<E>
+    + super::linear_act::LinearActKernel<super::relu::ReLUKernelOp, E>
+    + super::linear_act::LinearActKernel<super::gelu::GeLUKernelOp, E>
+
     // scalar arithmetic
     + UnaryKernel<super::add::ScalarAddKernelOp<E>, E>
     + UnaryKernel<super::sub::ScalarSubKernelOp<E>, E>
@@ -50,16 +67,32 @@ pub trait Device<E: Dtype>:
 
     // unary
     + UnaryKernel<super::abs::AbsKernelOp, E>
+    + UnaryKernel<super::ceil::CeilKernelOp, E>
     + UnaryKernel<super::clamp::ClampKernelOp<E>, E>
     + UnaryKernel<super::cos::CosKernelOp, E>
     + UnaryKernel<super::dropout::DropoutKernelOp, E>
+    + UnaryKernel<super::elu::ELUKernelOp<E>, E>
+    + UnaryKernel<super::erf::ErfKernelOp, E>
     + UnaryKernel<super::exp::ExpKernelOp, E>
+    + UnaryKernel<super::expm1::Expm1KernelOp, E>
+    + UnaryKernel<super::fast_gelu::FastGeLUKernelOp, E>
+    + UnaryKernel<super::floor::FloorKernelOp, E>
+    + UnaryKernel<super::gelu::GeLUKernelOp, E>
+    + UnaryKernel<super::leaky_relu::LeakyReLUKernelOp<E>, E>
     + UnaryKernel<super::ln::LnKernelOp, E>
+    + UnaryKernel<super::log1p::Log1pKernelOp, E>
+    + UnaryKernel<super::mish::MishKernelOp, E>
+    + UnaryKernel<super::nan_to_num::NanToNumKernelOp<E>, E>
     + UnaryKernel<super::nans_to::NansToKernelOp<E>, E>
     + UnaryKernel<super::negate::NegateKernelOp, E>
     + UnaryKernel<super::relu::ReLUKernelOp, E>
+    + UnaryKernel<super::round::RoundKernelOp, E>
+    + UnaryKernel<super::rsqrt::RsqrtKernelOp, E>
     + UnaryKernel<super::sigmoid::SigmoidKernelOp, E>
+    + UnaryKernel<super::sign::SignKernelOp, E>
+    + UnaryKernel<super::silu::SiLUKernelOp, E>
     + UnaryKernel<super::sin::SinKernelOp, E>
+    + UnaryKernel<super::softplus::SoftplusKernelOp<E>, E>
     + UnaryKernel<super::sqrt::SqrtKernelOp, E>
     + UnaryKernel<super::square::SquareKernelOp, E>
     + UnaryKernel<super::tanh::TanhKernelOp, E>
@@ -67,10 +100,12 @@ pub trait Device<E: Dtype>:
     + UnaryKernel<super::pow::PowKernelOp<i32>, E>
 
     // binary
+    + BinaryKernel<super::atan2::Atan2KernelOp, E>
     + BinaryKernel<super::bce::BCEKernelOp, E>
     + BinaryKernel<super::huber_error::HuberErrorKernelOp<E>, E>
     + BinaryKernel<super::maximum::MaximumKernelOp, E>
     + BinaryKernel<super::minimum::MinimumKernelOp, E>
+    + BinaryKernel<super::clamp::StraightThroughKernelOp, E>
 {
 }
 
@@ -1,4 +1,10 @@
-use crate::tensor_ops::cpu_kernels::{BinaryDerivative, UnaryDerivative};
+use super::TryDiv;
+use crate::{
+    tensor::{Cpu, DeviceStorage},
+    tensor_ops::cpu_kernels::{int_arithmetic, BinaryDerivative, UnaryDerivative},
+};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 impl UnaryDerivative<f32> for super::ScalarDivKernelOp<f32> {
     fn f(&self, x: &f32) -> f32 {
@@ -23,3 +29,5 @@ impl BinaryDerivative<f32> for super::BinaryDivKernelOp {
         -x / y.powi(2)
     }
 }
+
+int_arithmetic!(TryDiv, try_div, wrapping_div, [i32, i64, u8]);
@@ -3,7 +3,7 @@ mod cpu_kernel;
 #[cfg(feature = "cuda")]
 mod cuda_kernel;
 
-use super::{ops::*, Device};
+use super::ops::*;
 use crate::{gradients::*, shapes::*, tensor::*};
 
 #[repr(C)]
@@ -18,6 +18,8 @@ pub struct BinaryDivKernelOp;
 
 /// Element wise and scalar division.
 ///
+/// **Panics** if an integer tensor is divided by zero.
+///
 /// Example:
 /// ```rust
 /// # use dfdx::prelude::*;
@@ -36,10 +38,13 @@ pub struct BinaryDivKernelOp;
 /// let r = a / 2.0;
 /// assert_eq!(r.array(), [[0.5, 1.0, 1.5], [-0.5, -1.0, -1.5]]);
 /// ```
-pub fn div<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
+pub fn div<S: Shape, E: Dtype, D, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
-) -> Tensor<S, E, D, T> {
+) -> Tensor<S, E, D, T>
+where
+    D: BinaryKernel<BinaryDivKernelOp, E>,
+{
     lhs / rhs
 }
 
@@ -48,9 +53,10 @@ pub trait TryDiv<Rhs = Self>: HasErr {
     fn try_div(self, rhs: Rhs) -> Result<Self, Self::Err>;
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LhsTape: Tape<D>, RhsTape: Tape<D>>
-    TryDiv<Tensor<S, E, D, RhsTape>> for Tensor<S, E, D, LhsTape>
+impl<S: Shape, E: Dtype, D, LhsTape: Tape<D>, RhsTape: Tape<D>> TryDiv<Tensor<S, E, D, RhsTape>>
+    for Tensor<S, E, D, LhsTape>
 where
+    D: BinaryKernel<BinaryDivKernelOp, E>,
     LhsTape: Merge<RhsTape>,
 {
     /// See [div]
@@ -59,14 +65,17 @@ where
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> TryDiv<E> for Tensor<S, E, D, T> {
+impl<S: Shape, E: Dtype, D, T: Tape<D>> TryDiv<E> for Tensor<S, E, D, T>
+where
+    D: UnaryKernel<ScalarDivKernelOp<E>, E>,
+{
     /// See [div]
     fn try_div(self, rhs: E) -> Result<Self, Self::Err> {
         try_unary_op(ScalarDivKernelOp { scalar: rhs }, self)
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LhsTape: Tape<D>, Rhs> std::ops::Div<Rhs>
+impl<S: Shape, E: Dtype, D: DeviceStorage, LhsTape: Tape<D>, Rhs> std::ops::Div<Rhs>
     for Tensor<S, E, D, LhsTape>
 where
     Self: TryDiv<Rhs>,
@@ -171,4 +180,21 @@ mod tests {
         let g = r.exp().sum().backward();
         assert_eq!(g.get(&x).array(), [[0.8243606; 2]; 3]);
     }
+
+    #[test]
+    fn test_div_int() {
+        let dev: Cpu = Default::default();
+        let a = dev.tensor([7i32, -7, 9]);
+        let b = dev.tensor([2i32, 2, -3]);
+        assert_eq!((a.clone() / b).array(), [3, -3, -3]);
+        assert_eq!((a / 4).array(), [1, -1, 2]);
+        assert_eq!((dev.tensor([255u8, 7]) / 2).array(), [127, 3]);
+    }
+
+    #[test]
+    #[should_panic = "attempt to divide by zero"]
+    fn test_div_int_by_zero() {
+        let dev: Cpu = Default::default();
+        let _ = dev.tensor([1i32, 2]) / dev.tensor([1i32, 0]);
+    }
 }
@@ -0,0 +1,22 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl UnaryDerivative<f32> for super::ELUKernelOp<f32> {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        if *x > 0.0 {
+            *x
+        } else {
+            self.alpha * x.exp_m1()
+        }
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        if *x > 0.0 {
+            1.0
+        } else {
+            self.alpha * x.exp()
+        }
+    }
+}
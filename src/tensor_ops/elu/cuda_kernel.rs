@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::ELUKernelOp<f32> {}
+
+impl UnaryOpCudaKernel for super::ELUKernelOp<f32> {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/elu.ptx"));
+    const MODULE_NAME: &'static str = "elu";
+    const FWD_FN_NAME: &'static str = "elu_forward";
+    const BWD_FN_NAME: &'static str = "elu_backward";
+}
@@ -0,0 +1,33 @@
+struct ELUKernelOp {
+    float alpha;
+};
+
+extern "C" __global__ void elu_forward(
+    const ELUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    out[i] = x > 0.0 ? x : op.alpha * expm1f(x);
+}
+
+extern "C" __global__ void elu_backward(
+    const ELUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float dx = x > 0.0 ? 1.0 : op.alpha * expf(x);
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,57 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ELUKernelOp<E> {
+    pub alpha: E,
+}
+
+/// [Exponential Linear Unit (ELU)](https://paperswithcode.com/method/elu). `t` for positive values, and `alpha * (e^t - 1)` otherwise.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.elu(t, alpha)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.elu(1.0);
+/// ```
+pub fn elu<S: Shape, E: Dtype, D: UnaryKernel<ELUKernelOp<E>, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    alpha: E,
+) -> Tensor<S, E, D, T> {
+    t.elu(alpha)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<ELUKernelOp<E>, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [elu]
+    pub fn elu(self, alpha: E) -> Self {
+        self.try_elu(alpha).unwrap()
+    }
+    /// See [elu]
+    pub fn try_elu(self, alpha: E) -> Result<Self, D::Err> {
+        try_unary_op(ELUKernelOp { alpha }, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_elu() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.5, 0.0, 1.0, 3.0]);
+        let r = x.trace().elu(1.5);
+        assert_close(&r.array(), &[-1.2969971, -0.590204, 0.0, 1.0, 3.0]);
+        let g = r.mean().backward();
+        assert_close(&g.get(&x).array(), &[0.040600587, 0.1819592, 0.3, 0.2, 0.2]);
+    }
+}
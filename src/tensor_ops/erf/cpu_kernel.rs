@@ -0,0 +1,40 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// There is no `erf` in core/std, so this uses a taylor series close to 0, and
+/// the `erfc` approximation from Numerical Recipes (fractional error < 1.2e-7)
+/// everywhere else. Both are computed in f64 to avoid losing precision.
+pub(crate) fn erf(x: f32) -> f32 {
+    let x = x as f64;
+    let z = x.abs();
+    if z < 0.5 {
+        let x2 = x * x;
+        let series = 1.0
+            - x2 * (1.0 / 3.0
+                - x2 * (1.0 / 10.0 - x2 * (1.0 / 42.0 - x2 * (1.0 / 216.0 - x2 / 1320.0))));
+        return (core::f64::consts::FRAC_2_SQRT_PI * x * series) as f32;
+    }
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let erfc = t * (-z * z + poly).exp();
+    (1.0 - erfc).copysign(x) as f32
+}
+
+impl UnaryDerivative<f32> for super::ErfKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        erf(*x)
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        core::f32::consts::FRAC_2_SQRT_PI * (-x * x).exp()
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::ErfKernelOp {}
+
+impl UnaryOpCudaKernel for super::ErfKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/erf.ptx"));
+    const MODULE_NAME: &'static str = "erf";
+    const FWD_FN_NAME: &'static str = "erf_forward";
+    const BWD_FN_NAME: &'static str = "erf_backward";
+}
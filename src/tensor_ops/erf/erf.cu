@@ -0,0 +1,29 @@
+struct ErfKernelOp {};
+
+extern "C" __global__ void erf_forward(
+    const ErfKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    out[i] = erff(inp[i]);
+}
+
+extern "C" __global__ void erf_backward(
+    const ErfKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float dx = M_2_SQRTPI * expf(-inp[i] * inp[i]);
+    grad_inp[i] += dx * grad_out[i];
+}
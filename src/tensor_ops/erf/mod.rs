@@ -0,0 +1,66 @@
+pub(super) mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ErfKernelOp;
+
+/// [Error function (erf)](https://en.wikipedia.org/wiki/Error_function).
+///
+/// The derivative is `2 / √π * e^(-t^2)`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.erf();
+/// ```
+pub fn erf<S: Shape, E: Dtype, D: UnaryKernel<ErfKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.erf()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<ErfKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [erf]
+    pub fn erf(self) -> Self {
+        self.try_erf().unwrap()
+    }
+    /// See [erf]
+    pub fn try_erf(self) -> Result<Self, D::Err> {
+        try_unary_op(ErfKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_erf() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.5, 0.0, 1.0, 3.0]);
+        let r = x.trace().erf();
+        assert_close(
+            &r.array(),
+            &[-0.9953223, -0.5204999, 0.0, 0.8427008, 0.9999779],
+        );
+        let g = r.mean().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &[
+                0.004133397,
+                0.17575652,
+                0.22567584,
+                0.0830215,
+                0.00002785061,
+            ],
+        );
+    }
+}
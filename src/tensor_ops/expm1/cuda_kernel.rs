@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::Expm1KernelOp {}
+
+impl UnaryOpCudaKernel for super::Expm1KernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/expm1.ptx"));
+    const MODULE_NAME: &'static str = "expm1";
+    const FWD_FN_NAME: &'static str = "expm1_forward";
+    const BWD_FN_NAME: &'static str = "expm1_backward";
+}
@@ -0,0 +1,29 @@
+struct Expm1KernelOp {};
+
+extern "C" __global__ void expm1_forward(
+    const Expm1KernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    out[i] = expm1f(inp[i]);
+}
+
+extern "C" __global__ void expm1_backward(
+    const Expm1KernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float dx = expf(inp[i]);
+    grad_inp[i] += dx * grad_out[i];
+}
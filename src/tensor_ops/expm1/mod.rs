@@ -0,0 +1,55 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Expm1KernelOp;
+
+/// `e^t - 1`, which is more accurate than `t.exp() - 1.0` for `t` close to 0.
+///
+/// The derivative is `e^t`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1e-8, 1.0]);
+/// let r = t.expm1();
+/// assert_eq!(r.array()[2], 1e-8);
+/// ```
+pub fn expm1<S: Shape, E: Dtype, D: UnaryKernel<Expm1KernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.expm1()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<Expm1KernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [expm1]
+    pub fn expm1(self) -> Self {
+        self.try_expm1().unwrap()
+    }
+    /// See [expm1]
+    pub fn try_expm1(self) -> Result<Self, D::Err> {
+        try_unary_op(Expm1KernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_expm1() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.0, 0.0, 1e-8, 1.0]);
+        let r = x.trace().expm1();
+        assert_close(&r.array(), &[-0.63212055, 0.0, 1e-8, 1.7182817]);
+        let g = r.mean().backward();
+        assert_close(&g.get(&x).array(), &[0.09196986, 0.25, 0.25, 0.67957044]);
+    }
+}
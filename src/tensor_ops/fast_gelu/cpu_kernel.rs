@@ -0,0 +1,20 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// `√(2/π)`
+const SQRT_2_OVER_PI: f32 = core::f32::consts::FRAC_2_SQRT_PI * core::f32::consts::FRAC_1_SQRT_2;
+
+impl UnaryDerivative<f32> for super::FastGeLUKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        let t = (SQRT_2_OVER_PI * (x + 0.044715 * x * x * x)).tanh();
+        0.5 * x * (1.0 + t)
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        let t = (SQRT_2_OVER_PI * (x + 0.044715 * x * x * x)).tanh();
+        let du = SQRT_2_OVER_PI * (1.0 + 3.0 * 0.044715 * x * x);
+        0.5 * (1.0 + t) + 0.5 * x * (1.0 - t * t) * du
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::FastGeLUKernelOp {}
+
+impl UnaryOpCudaKernel for super::FastGeLUKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/fast_gelu.ptx"));
+    const MODULE_NAME: &'static str = "fast_gelu";
+    const FWD_FN_NAME: &'static str = "fast_gelu_forward";
+    const BWD_FN_NAME: &'static str = "fast_gelu_backward";
+}
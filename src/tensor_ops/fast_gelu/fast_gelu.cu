@@ -0,0 +1,36 @@
+#define SQRT_2_OVER_PI 0.7978845608028654
+
+struct FastGeLUKernelOp {};
+
+extern "C" __global__ void fast_gelu_forward(
+    const FastGeLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float t = tanhf(SQRT_2_OVER_PI * (x + 0.044715 * x * x * x));
+    out[i] = 0.5 * x * (1.0 + t);
+}
+
+extern "C" __global__ void fast_gelu_backward(
+    const FastGeLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float t = tanhf(SQRT_2_OVER_PI * (x + 0.044715 * x * x * x));
+    float du = SQRT_2_OVER_PI * (1.0 + 3.0 * 0.044715 * x * x);
+    float dx = 0.5 * (1.0 + t) + 0.5 * x * (1.0 - t * t) * du;
+    grad_inp[i] += dx * grad_out[i];
+}
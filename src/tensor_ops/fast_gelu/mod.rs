@@ -0,0 +1,60 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct FastGeLUKernelOp;
+
+/// The `tanh` approximation of [gelu()]. `0.5 * t * (1 + tanh(√(2/π) * (t + 0.044715 * t^3)))`
+///
+/// **Pytorch equivalent**: `torch.nn.functional.gelu(t, approximate="tanh")`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.fast_gelu();
+/// ```
+pub fn fast_gelu<S: Shape, E: Dtype, D: UnaryKernel<FastGeLUKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.fast_gelu()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<FastGeLUKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [fast_gelu]
+    pub fn fast_gelu(self) -> Self {
+        self.try_fast_gelu().unwrap()
+    }
+    /// See [fast_gelu]
+    pub fn try_fast_gelu(self) -> Result<Self, D::Err> {
+        try_unary_op(FastGeLUKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_fast_gelu() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.5, 0.0, 1.0, 3.0]);
+        let r = x.trace().fast_gelu();
+        assert_close(
+            &r.array(),
+            &[-0.045402307, -0.154286, 0.0, 0.841192, 2.9963627],
+        );
+        let g = r.mean().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &[-0.01721985, 0.026526019, 0.1, 0.21659282, 0.20231684],
+        );
+    }
+}
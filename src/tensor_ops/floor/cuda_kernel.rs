@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::FloorKernelOp {}
+
+impl UnaryOpCudaKernel for super::FloorKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/floor.ptx"));
+    const MODULE_NAME: &'static str = "floor";
+    const FWD_FN_NAME: &'static str = "floor_forward";
+    const BWD_FN_NAME: &'static str = "floor_backward";
+}
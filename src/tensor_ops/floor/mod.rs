@@ -0,0 +1,55 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct FloorKernelOp;
+
+/// Rounds down to the nearest integer.
+///
+/// The derivative is `0`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.5, 0.0, 1.7]);
+/// let r = t.floor();
+/// assert_eq!(r.array(), [-2.0, 0.0, 1.0]);
+/// ```
+pub fn floor<S: Shape, E: Dtype, D: UnaryKernel<FloorKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.floor()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<FloorKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [floor]
+    pub fn floor(self) -> Self {
+        self.try_floor().unwrap()
+    }
+    /// See [floor]
+    pub fn try_floor(self) -> Result<Self, D::Err> {
+        try_unary_op(FloorKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_floor() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.5, -1.0, 0.2, 1.7]);
+        let r = x.trace().floor();
+        assert_eq!(r.array(), [-2.0, -1.0, 0.0, 1.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 4]);
+    }
+}
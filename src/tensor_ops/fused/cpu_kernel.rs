@@ -0,0 +1,87 @@
+use super::ElementwiseOp;
+use crate::shapes::Shape;
+use crate::tensor::cpu::Cpu;
+use crate::tensor_ops::{
+    abs::AbsKernelOp, clamp::ClampKernelOp, cpu_kernels::UnaryDerivative, exp::ExpKernelOp,
+    ln::LnKernelOp, relu::ReLUKernelOp, sigmoid::SigmoidKernelOp, sqrt::SqrtKernelOp,
+    square::SquareKernelOp, tanh::TanhKernelOp,
+};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl ElementwiseOp {
+    #[inline(always)]
+    fn f(&self, x: f32) -> f32 {
+        match *self {
+            Self::AddScalar(s) => x + s,
+            Self::MulScalar(s) => x * s,
+            Self::Powf(p) => x.powf(p),
+            Self::Clamp { min, max } => ClampKernelOp { min, max }.f(&x),
+            Self::Abs => AbsKernelOp.f(&x),
+            Self::Exp => ExpKernelOp.f(&x),
+            Self::Ln => LnKernelOp.f(&x),
+            Self::Negate => -x,
+            Self::ReLU => ReLUKernelOp.f(&x),
+            Self::Sigmoid => SigmoidKernelOp.f(&x),
+            Self::Sqrt => SqrtKernelOp.f(&x),
+            Self::Square => SquareKernelOp.f(&x),
+            Self::Tanh => TanhKernelOp.f(&x),
+        }
+    }
+
+    #[inline(always)]
+    fn df(&self, x: f32) -> f32 {
+        match *self {
+            Self::AddScalar(_) => 1.0,
+            Self::MulScalar(s) => s,
+            Self::Powf(p) => p * x.powf(p - 1.0),
+            Self::Clamp { min, max } => ClampKernelOp { min, max }.df(&x),
+            Self::Abs => AbsKernelOp.df(&x),
+            Self::Exp => ExpKernelOp.df(&x),
+            Self::Ln => LnKernelOp.df(&x),
+            Self::Negate => -1.0,
+            Self::ReLU => ReLUKernelOp.df(&x),
+            Self::Sigmoid => SigmoidKernelOp.df(&x),
+            Self::Sqrt => SqrtKernelOp.df(&x),
+            Self::Square => SquareKernelOp.df(&x),
+            Self::Tanh => TanhKernelOp.df(&x),
+        }
+    }
+}
+
+impl super::FusedElementwiseKernel<f32> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        ops: &[ElementwiseOp],
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        let mut out: Self::Storage<S, f32> = inp.clone();
+        for x in out.buf_iter_mut() {
+            *x = ops.iter().fold(*x, |x, op| op.f(x));
+        }
+        Ok(out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        ops: &[ElementwiseOp],
+        inp: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        debug_assert_eq!(grad_inp.data.len(), grad_out.data.len());
+        debug_assert_eq!(inp.data.len(), grad_out.data.len());
+        for (i, g) in grad_inp.buf_iter_mut().enumerate() {
+            // recompute the chain, multiplying the derivative of each op along the way
+            let mut x = inp.data[i];
+            let mut dx = 1.0;
+            for op in ops.iter() {
+                dx *= op.df(x);
+                x = op.f(x);
+            }
+            *g += dx * grad_out.data[i];
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use super::ElementwiseOp;
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/fused.ptx"));
+const MODULE_NAME: &str = "fused";
+const FWD_FN_NAME: &str = "fused_forward";
+const BWD_FN_NAME: &str = "fused_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl ElementwiseOp {
+    /// The op code used in fused.cu, and the two parameters of the op.
+    fn encode(&self) -> (usize, f32, f32) {
+        match *self {
+            Self::AddScalar(s) => (0, s, 0.0),
+            Self::MulScalar(s) => (1, s, 0.0),
+            Self::Powf(p) => (2, p, 0.0),
+            Self::Clamp { min, max } => (3, min, max),
+            Self::Abs => (4, 0.0, 0.0),
+            Self::Exp => (5, 0.0, 0.0),
+            Self::Ln => (6, 0.0, 0.0),
+            Self::Negate => (7, 0.0, 0.0),
+            Self::ReLU => (8, 0.0, 0.0),
+            Self::Sigmoid => (9, 0.0, 0.0),
+            Self::Sqrt => (10, 0.0, 0.0),
+            Self::Square => (11, 0.0, 0.0),
+            Self::Tanh => (12, 0.0, 0.0),
+        }
+    }
+}
+
+impl Cuda {
+    /// Copies the op codes and op parameters to the device.
+    fn encode_ops(
+        &self,
+        ops: &[ElementwiseOp],
+    ) -> Result<(CudaSlice<usize>, CudaSlice<f32>), <Self as crate::tensor::DeviceStorage>::Err>
+    {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+        let mut codes = Vec::with_capacity(ops.len());
+        let mut params = Vec::with_capacity(2 * ops.len());
+        for op in ops.iter() {
+            let (code, a, b) = op.encode();
+            codes.push(code);
+            params.push(a);
+            params.push(b);
+        }
+        Ok((self.dev.take_async(codes)?, self.dev.take_async(params)?))
+    }
+}
+
+impl super::FusedElementwiseKernel<f32> for Cuda {
+    fn forward<S: Shape>(
+        &self,
+        ops: &[ElementwiseOp],
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        let (codes, op_params) = self.encode_ops(ops)?;
+        let numel = inp.data.len();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            ops.len(),         // const size_t num_ops,
+            &codes,            // const size_t *codes,
+            &op_params,        // const float *params,
+            numel,             // const size_t numel,
+            inp.data.as_ref(), // const float *inp,
+            &mut storage,      // float *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: inp.shape,
+            strides: inp.strides,
+        })
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        ops: &[ElementwiseOp],
+        inp: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let (codes, op_params) = self.encode_ops(ops)?;
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let numel = inp.data.len();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            ops.len(),                         // const size_t num_ops,
+            &codes,                            // const size_t *codes,
+            &op_params,                        // const float *params,
+            numel,                             // const size_t numel,
+            inp.data.as_ref(),                 // const float *inp,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,432 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::{Tape, TapeSummary},
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+use core::ops::Range;
+use std::vec::Vec;
+
+/// A single elementwise operation that can be part of a [FusedElementwise] chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ElementwiseOp {
+    AddScalar(f32),
+    MulScalar(f32),
+    Powf(f32),
+    Clamp { min: f32, max: f32 },
+    Abs,
+    Exp,
+    Ln,
+    Negate,
+    ReLU,
+    Sigmoid,
+    Sqrt,
+    Square,
+    Tanh,
+}
+
+pub trait FusedElementwiseKernel<E: Dtype>: DeviceStorage {
+    fn forward<S: Shape>(
+        &self,
+        ops: &[ElementwiseOp],
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    fn backward<S: Shape>(
+        &self,
+        ops: &[ElementwiseOp],
+        inp: &Self::Storage<S, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// A chain of elementwise ops captured from [Tensor::fuse()], which is run as a single
+/// kernel with [FusedElementwise::run()]. [Tensor::fused()] opens a capture scope that does
+/// both.
+///
+/// Each element goes through the whole chain at once, so none of the intermediate
+/// tensors are allocated, and only one operation is recorded on the tape. The backward
+/// pass recomputes the intermediate values from the input instead of storing them.
+///
+/// This is useful for long chains like normalization and activation stacks, where the
+/// separate ops would each read and write the whole tensor.
+///
+/// Fusion is explicit. Ops run eagerly as soon as they are called, so by the time they are
+/// recorded on the tape they have already been executed, and the tape can't rewrite them
+/// into a single kernel. Use [TapeSummary::fusible_chains()] to find the chains on a tape
+/// traced with [Tensor::trace_with_summary()] that could be written with [Tensor::fuse()]:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let x_fused = x.trace_with_summary().fuse();
+/// let fused = x_fused.mul_scalar(2.0).add_scalar(-1.0).relu().square().run();
+/// let unfused = ((x.trace() * 2.0) + -1.0).relu().square();
+/// assert_eq!(fused.array(), unfused.array());
+///
+/// let (fused, tape) = fused.split_tape();
+/// assert_eq!(tape.summary().ops.len(), 1);
+/// let g = fused.put_tape(tape).sum().backward();
+/// assert_eq!(g.get(&x).array(), [0.0, 0.0, 4.0, 12.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FusedElementwise<S: Shape, D: DeviceStorage, T> {
+    t: Tensor<S, f32, D, T>,
+    ops: Vec<ElementwiseOp>,
+}
+
+impl<S: Shape, D: DeviceStorage, T> Tensor<S, f32, D, T> {
+    /// Starts capturing elementwise ops to run as one fused kernel. See [FusedElementwise].
+    pub fn fuse(self) -> FusedElementwise<S, D, T> {
+        FusedElementwise {
+            t: self,
+            ops: Vec::new(),
+        }
+    }
+}
+
+impl<S: Shape, D: FusedElementwiseKernel<f32>, T: Tape<D>> Tensor<S, f32, D, T> {
+    /// Runs `f` in a capture scope. The ops that `f` applies to its argument are only
+    /// recorded, and then run as a single fused kernel when the scope ends.
+    ///
+    /// Scalar `+`, `-` and `*`, unary `-` and the methods of [FusedElementwise] can be used
+    /// in the scope:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+    /// let y = x.trace().fused(|x| (-(x * 2.0) + 1.0).tanh().square());
+    /// assert_eq!(y.array(), ((-(x * 2.0) + 1.0).tanh().square()).array());
+    /// ```
+    pub fn fused<F>(self, f: F) -> Self
+    where
+        F: FnOnce(FusedElementwise<S, D, T>) -> FusedElementwise<S, D, T>,
+    {
+        self.try_fused(f).unwrap()
+    }
+
+    /// Fallible version of [Tensor::fused()]
+    pub fn try_fused<F>(self, f: F) -> Result<Self, D::Err>
+    where
+        F: FnOnce(FusedElementwise<S, D, T>) -> FusedElementwise<S, D, T>,
+    {
+        f(self.fuse()).try_run()
+    }
+}
+
+impl<S: Shape, D: DeviceStorage, T> core::ops::Add<f32> for FusedElementwise<S, D, T> {
+    type Output = Self;
+    fn add(self, rhs: f32) -> Self {
+        self.add_scalar(rhs)
+    }
+}
+
+impl<S: Shape, D: DeviceStorage, T> core::ops::Sub<f32> for FusedElementwise<S, D, T> {
+    type Output = Self;
+    fn sub(self, rhs: f32) -> Self {
+        self.add_scalar(-rhs)
+    }
+}
+
+impl<S: Shape, D: DeviceStorage, T> core::ops::Mul<f32> for FusedElementwise<S, D, T> {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self {
+        self.mul_scalar(rhs)
+    }
+}
+
+impl<S: Shape, D: DeviceStorage, T> core::ops::Neg for FusedElementwise<S, D, T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.negate()
+    }
+}
+
+impl<S: Shape, D: DeviceStorage, T> FusedElementwise<S, D, T> {
+    /// The ops captured so far, in the order they are applied.
+    pub fn ops(&self) -> &[ElementwiseOp] {
+        &self.ops
+    }
+
+    /// Appends `op` to the chain.
+    pub fn push(mut self, op: ElementwiseOp) -> Self {
+        self.ops.push(op);
+        self
+    }
+
+    /// `x + scalar`
+    pub fn add_scalar(self, scalar: f32) -> Self {
+        self.push(ElementwiseOp::AddScalar(scalar))
+    }
+
+    /// `x * scalar`
+    pub fn mul_scalar(self, scalar: f32) -> Self {
+        self.push(ElementwiseOp::MulScalar(scalar))
+    }
+
+    /// See [crate::tensor_ops::powf()]
+    pub fn powf(self, exponent: f32) -> Self {
+        self.push(ElementwiseOp::Powf(exponent))
+    }
+
+    /// See [crate::tensor_ops::clamp()]
+    pub fn clamp(self, min: f32, max: f32) -> Self {
+        self.push(ElementwiseOp::Clamp { min, max })
+    }
+
+    /// See [crate::tensor_ops::abs()]
+    pub fn abs(self) -> Self {
+        self.push(ElementwiseOp::Abs)
+    }
+
+    /// See [crate::tensor_ops::exp()]
+    pub fn exp(self) -> Self {
+        self.push(ElementwiseOp::Exp)
+    }
+
+    /// See [crate::tensor_ops::ln()]
+    pub fn ln(self) -> Self {
+        self.push(ElementwiseOp::Ln)
+    }
+
+    /// See [crate::tensor_ops::negate()]
+    pub fn negate(self) -> Self {
+        self.push(ElementwiseOp::Negate)
+    }
+
+    /// See [crate::tensor_ops::relu()]
+    pub fn relu(self) -> Self {
+        self.push(ElementwiseOp::ReLU)
+    }
+
+    /// See [crate::tensor_ops::sigmoid()]
+    pub fn sigmoid(self) -> Self {
+        self.push(ElementwiseOp::Sigmoid)
+    }
+
+    /// See [crate::tensor_ops::sqrt()]
+    pub fn sqrt(self) -> Self {
+        self.push(ElementwiseOp::Sqrt)
+    }
+
+    /// See [crate::tensor_ops::square()]
+    pub fn square(self) -> Self {
+        self.push(ElementwiseOp::Square)
+    }
+
+    /// See [crate::tensor_ops::tanh()]
+    pub fn tanh(self) -> Self {
+        self.push(ElementwiseOp::Tanh)
+    }
+}
+
+impl<S: Shape, D: FusedElementwiseKernel<f32>, T: Tape<D>> FusedElementwise<S, D, T> {
+    /// Runs all the captured ops as a single kernel.
+    pub fn run(self) -> Tensor<S, f32, D, T> {
+        self.try_run().unwrap()
+    }
+
+    /// Fallible version of [FusedElementwise::run()]
+    pub fn try_run(self) -> Result<Tensor<S, f32, D, T>, D::Err> {
+        let FusedElementwise { t, ops } = self;
+        if ops.is_empty() {
+            return Ok(t);
+        }
+        let (inp, mut tape) = t.split_tape();
+        let storage = FusedElementwiseKernel::forward(&inp.device, &ops, &inp.storage)?;
+        let out = inp.device.upgrade(storage);
+        inp.device
+            .recorder()
+            .record("FusedElementwise", &[inp.id], out.id, || {
+                let (inp, ops, out_id) = (inp.clone(), ops.clone(), out.id);
+                move |bufs| {
+                    let storage =
+                        FusedElementwiseKernel::forward(&inp.device, &ops, bufs.get(&inp))?;
+                    bufs.insert::<S, f32, D>(out_id, storage);
+                    Ok(())
+                }
+            });
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_named_backward_op("FusedElementwise", move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            FusedElementwiseKernel::backward(&inp.device, &ops, &inp.storage, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl ElementwiseOp {
+    /// Whether an op recorded on the tape with `name` (see [crate::gradients::OpRecord::name()])
+    /// has an [ElementwiseOp] equivalent.
+    fn is_fusible(name: &str) -> bool {
+        matches!(
+            name,
+            "ScalarAddKernelOp"
+                | "ScalarMulKernelOp"
+                | "PowKernelOp"
+                | "ClampKernelOp"
+                | "AbsKernelOp"
+                | "ExpKernelOp"
+                | "LnKernelOp"
+                | "NegateKernelOp"
+                | "ReLUKernelOp"
+                | "SigmoidKernelOp"
+                | "SqrtKernelOp"
+                | "SquareKernelOp"
+                | "TanhKernelOp"
+        )
+    }
+}
+
+impl TapeSummary {
+    /// Finds the chains of at least two consecutive ops that could be run as one
+    /// [FusedElementwise], as ranges of indices into [TapeSummary::ops].
+    ///
+    /// Each op in a chain has an [ElementwiseOp] equivalent, takes the output of the
+    /// previous op as its only input, and the outputs inside the chain aren't used by
+    /// any other op.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<3>> = dev.zeros();
+    /// let y = ((x.trace_with_summary() * 2.0) + 1.0).relu().sum();
+    /// let (_, tape) = y.split_tape();
+    /// assert_eq!(tape.summary().fusible_chains(), [0..3]);
+    /// ```
+    pub fn fusible_chains(&self) -> Vec<Range<usize>> {
+        let mut num_uses = std::collections::BTreeMap::new();
+        for op in self.ops.iter() {
+            for t in op.inputs() {
+                *num_uses.entry(t.id).or_insert(0) += 1;
+            }
+        }
+        let fusible: Vec<bool> = self
+            .ops
+            .iter()
+            .map(|op| {
+                op.inputs().count() == 1
+                    && op.output().is_some()
+                    && ElementwiseOp::is_fusible(&op.name())
+            })
+            .collect();
+
+        let mut chains = Vec::new();
+        let mut start = 0;
+        for i in 0..=self.ops.len() {
+            let continues = i > start && i < self.ops.len() && fusible[i] && fusible[i - 1] && {
+                let prev = self.ops[i - 1].output().unwrap().id;
+                self.ops[i].inputs().next().unwrap().id == prev && num_uses[&prev] == 1
+            };
+            if !continues {
+                if i - start >= 2 {
+                    chains.push(start..i);
+                }
+                start = i;
+            }
+        }
+        chains
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_fused_matches_unfused() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+
+        let fused = x
+            .trace()
+            .fuse()
+            .mul_scalar(0.5)
+            .add_scalar(1.0)
+            .clamp(0.1, 1.2)
+            .powf(1.5)
+            .ln()
+            .exp()
+            .sqrt()
+            .sigmoid()
+            .negate()
+            .tanh()
+            .abs()
+            .square()
+            .run();
+        let unfused = ((x.trace() * 0.5) + 1.0)
+            .clamp(0.1, 1.2)
+            .powf(1.5)
+            .ln()
+            .exp()
+            .sqrt()
+            .sigmoid()
+            .negate()
+            .tanh()
+            .abs()
+            .square();
+        assert_close(&fused.array(), &unfused.array());
+
+        let g_fused = fused.exp().mean().backward();
+        let g_unfused = unfused.exp().mean().backward();
+        assert_close(&g_fused.get(&x).array(), &g_unfused.get(&x).array());
+    }
+
+    #[test]
+    fn test_fused_records_one_op() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+        let y = x.trace_with_summary().fuse().relu().exp().tanh().run();
+        let (_, tape) = y.split_tape();
+        let summary = tape.summary();
+        assert_eq!(summary.ops.len(), 1);
+        assert_eq!(summary.ops[0].name(), "FusedElementwise");
+    }
+
+    #[test]
+    fn test_fused_scope() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+
+        let fused = x
+            .trace_with_summary()
+            .fused(|x| (-(x * 0.5 - 1.0)).sigmoid() * 3.0 + 0.25);
+        let unfused = (-((x.trace() * 0.5) - 1.0)).sigmoid() * 3.0 + 0.25;
+        assert_close(&fused.array(), &unfused.array());
+
+        let (fused, tape) = fused.split_tape();
+        assert_eq!(tape.summary().ops.len(), 1);
+        let g_fused = fused.put_tape(tape).square().mean().backward();
+        let g_unfused = unfused.square().mean().backward();
+        assert_close(&g_fused.get(&x).array(), &g_unfused.get(&x).array());
+    }
+
+    #[test]
+    fn test_fusible_chains() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+        let (y, tape) = x.trace_with_summary().relu().split_tape();
+        // the output of relu is used twice, so it can't be fused with exp
+        let z = y.clone().put_tape(tape).exp().tanh() + y;
+        let (_, tape) = z.sigmoid().square().sum().split_tape();
+        assert_eq!(tape.summary().fusible_chains(), [1..3, 4..6]);
+    }
+
+    #[test]
+    fn test_fused_empty_chain() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let fused = x.clone().fuse();
+        assert!(fused.ops().is_empty());
+        let y = fused.run();
+        assert_eq!(y.array(), x.array());
+    }
+}
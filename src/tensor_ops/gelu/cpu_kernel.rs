@@ -0,0 +1,17 @@
+use super::super::erf::cpu_kernel::erf;
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+use core::f32::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl UnaryDerivative<f32> for super::GeLUKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        0.5 * x * (1.0 + erf(x * FRAC_1_SQRT_2))
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        0.5 * (1.0 + erf(x * FRAC_1_SQRT_2))
+            + x * (-0.5 * x * x).exp() * FRAC_2_SQRT_PI * FRAC_1_SQRT_2 * 0.5
+    }
+}
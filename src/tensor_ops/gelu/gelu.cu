@@ -0,0 +1,31 @@
+struct GeLUKernelOp {};
+
+extern "C" __global__ void gelu_forward(
+    const GeLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    out[i] = 0.5 * x * (1.0 + erff(x * M_SQRT1_2));
+}
+
+extern "C" __global__ void gelu_backward(
+    const GeLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float dx = 0.5 * (1.0 + erff(x * M_SQRT1_2)) + x * expf(-0.5 * x * x) * M_2_SQRTPI * M_SQRT1_2 * 0.5;
+    grad_inp[i] += dx * grad_out[i];
+}
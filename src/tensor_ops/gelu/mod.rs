@@ -0,0 +1,62 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct GeLUKernelOp;
+
+/// [Gaussian Error Linear Unit (GeLU)](https://paperswithcode.com/method/gelu). `0.5 * t * (1 + erf(t / √2))`
+///
+/// This is the exact version, see [fast_gelu()] for the `tanh` approximation.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.gelu(t)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.gelu();
+/// ```
+pub fn gelu<S: Shape, E: Dtype, D: UnaryKernel<GeLUKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.gelu()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<GeLUKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [gelu]
+    pub fn gelu(self) -> Self {
+        self.try_gelu().unwrap()
+    }
+    /// See [gelu]
+    pub fn try_gelu(self) -> Result<Self, D::Err> {
+        try_unary_op(GeLUKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_gelu() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.5, 0.0, 1.0, 3.0]);
+        let r = x.trace().gelu();
+        assert_close(
+            &r.array(),
+            &[-0.045500264, -0.15426877, 0.0, 0.8413448, 2.9959502],
+        );
+        let g = r.mean().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &[-0.01704636, 0.026500976, 0.1, 0.21666309, 0.20238914],
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::shapes::{Const, Dim};
+use crate::tensor::cpu::{Cpu, StridedArray};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// A neighbor of a sampling location that is inside the image.
+struct Corner {
+    y: usize,
+    x: usize,
+    /// The bilinear weight of this neighbor
+    weight: f32,
+    /// The derivative of `weight` with respect to the x & y grid coordinates
+    dx: f32,
+    dy: f32,
+}
+
+/// The neighbors of the sampling location `(gx, gy)`, with the coordinates
+/// normalized to `[-1, 1]` like pytorch's `align_corners=False`.
+fn corners(gx: f32, gy: f32, h: usize, w: usize) -> impl Iterator<Item = Corner> {
+    let ix = ((gx + 1.0) * w as f32 - 1.0) / 2.0;
+    let iy = ((gy + 1.0) * h as f32 - 1.0) / 2.0;
+    let (x0, y0) = (ix.floor(), iy.floor());
+    let (wx1, wy1) = (ix - x0, iy - y0);
+    let (sx, sy) = (w as f32 / 2.0, h as f32 / 2.0);
+    [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)]
+        .into_iter()
+        .filter_map(move |(oy, ox)| {
+            let (y, x) = (y0 + oy, x0 + ox);
+            if y < 0.0 || x < 0.0 || y as usize >= h || x as usize >= w {
+                return None;
+            }
+            let wx = if ox == 0.0 { 1.0 - wx1 } else { wx1 };
+            let wy = if oy == 0.0 { 1.0 - wy1 } else { wy1 };
+            let sign_x = if ox == 0.0 { -1.0 } else { 1.0 };
+            let sign_y = if oy == 0.0 { -1.0 } else { 1.0 };
+            Some(Corner {
+                y: y as usize,
+                x: x as usize,
+                weight: wy * wx,
+                dx: wy * sign_x * sx,
+                dy: wx * sign_y * sy,
+            })
+        })
+}
+
+impl super::GridSampleKernel<f32> for Cpu {
+    fn forward<B: Dim, C: Dim, H: Dim, W: Dim, OH: Dim, OW: Dim>(
+        &self,
+        inp: &Self::Storage<(B, C, H, W), f32>,
+        grid: &Self::Storage<(B, OH, OW, Const<2>), f32>,
+    ) -> Result<Self::Storage<(B, C, OH, OW), f32>, Self::Err> {
+        let (b, c, h, w) = inp.shape;
+        let (_, oh, ow, _) = grid.shape;
+        let mut out = StridedArray::new((b, c, oh, ow))?;
+        for n in 0..b.size() {
+            for i in 0..oh.size() {
+                for j in 0..ow.size() {
+                    let (gx, gy) = (grid[[n, i, j, 0]], grid[[n, i, j, 1]]);
+                    for p in corners(gx, gy, h.size(), w.size()) {
+                        for k in 0..c.size() {
+                            out[[n, k, i, j]] += p.weight * inp[[n, k, p.y, p.x]];
+                        }
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<B: Dim, C: Dim, H: Dim, W: Dim, OH: Dim, OW: Dim>(
+        &self,
+        inp: &Self::Storage<(B, C, H, W), f32>,
+        grad_inp: &mut Self::Storage<(B, C, H, W), f32>,
+        grid: &Self::Storage<(B, OH, OW, Const<2>), f32>,
+        grad_grid: &mut Self::Storage<(B, OH, OW, Const<2>), f32>,
+        grad_out: &Self::Storage<(B, C, OH, OW), f32>,
+    ) -> Result<(), Self::Err> {
+        let (b, c, h, w) = inp.shape;
+        let (_, oh, ow, _) = grid.shape;
+        for n in 0..b.size() {
+            for i in 0..oh.size() {
+                for j in 0..ow.size() {
+                    let (gx, gy) = (grid[[n, i, j, 0]], grid[[n, i, j, 1]]);
+                    let (mut dx, mut dy) = (0.0, 0.0);
+                    for p in corners(gx, gy, h.size(), w.size()) {
+                        for k in 0..c.size() {
+                            let g = grad_out[[n, k, i, j]];
+                            grad_inp[[n, k, p.y, p.x]] += p.weight * g;
+                            let v = inp[[n, k, p.y, p.x]] * g;
+                            dx += p.dx * v;
+                            dy += p.dy * v;
+                        }
+                    }
+                    grad_grid[[n, i, j, 0]] += dx;
+                    grad_grid[[n, i, j, 1]] += dy;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    shapes::{Const, Dim, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/grid_sample.ptx"));
+const MODULE_NAME: &str = "grid_sample";
+const FWD_FN_NAME: &str = "grid_sample_forward";
+const BWD_FN_NAME: &str = "grid_sample_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl super::GridSampleKernel<f32> for Cuda {
+    fn forward<B: Dim, C: Dim, H: Dim, W: Dim, OH: Dim, OW: Dim>(
+        &self,
+        inp: &Self::Storage<(B, C, H, W), f32>,
+        grid: &Self::Storage<(B, OH, OW, Const<2>), f32>,
+    ) -> Result<Self::Storage<(B, C, OH, OW), f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let (b, c, _, _) = inp.shape;
+        let (_, oh, ow, _) = grid.shape;
+        let shape = (b, c, oh, ow);
+        let mut storage = self.dev.alloc_zeros_async::<f32>(shape.num_elements())?;
+
+        let numel = b.size() * oh.size() * ow.size();
+        if numel > 0 {
+            let inp_dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+            let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+            let out_dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+            let grid_strides: CudaSlice<usize> = self.dev.take_async(grid.strides.into())?;
+            let out_strides: CudaSlice<usize> = self.dev.take_async(shape.strides().into())?;
+
+            let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                numel,              // const size_t numel,
+                &inp_dims,          // const size_t *inp_dims,
+                inp.data.as_ref(),  // const float *inp,
+                &inp_strides,       // const size_t *inp_strides,
+                &out_dims,          // const size_t *out_dims,
+                grid.data.as_ref(), // const float *grid,
+                &grid_strides,      // const size_t *grid_strides,
+                &mut storage,       // float *out,
+                &out_strides,       // const size_t *out_strides
+            );
+            unsafe { fwd_fn.launch_async(cfg, params) }?;
+        }
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+
+    fn backward<B: Dim, C: Dim, H: Dim, W: Dim, OH: Dim, OW: Dim>(
+        &self,
+        inp: &Self::Storage<(B, C, H, W), f32>,
+        grad_inp: &mut Self::Storage<(B, C, H, W), f32>,
+        grid: &Self::Storage<(B, OH, OW, Const<2>), f32>,
+        grad_grid: &mut Self::Storage<(B, OH, OW, Const<2>), f32>,
+        grad_out: &Self::Storage<(B, C, OH, OW), f32>,
+    ) -> Result<(), Self::Err> {
+        let (b, _, oh, ow) = grad_out.shape;
+        let numel = b.size() * oh.size() * ow.size();
+        if numel == 0 {
+            return Ok(());
+        }
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let inp_dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_dims: CudaSlice<usize> = self.dev.take_async(grad_out.shape.concrete().into())?;
+        let grid_strides: CudaSlice<usize> = self.dev.take_async(grid.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                              // const size_t numel,
+            &inp_dims,                          // const size_t *inp_dims,
+            inp.data.as_ref(),                  // const float *inp,
+            Arc::make_mut(&mut grad_inp.data),  // float *grad_inp,
+            &inp_strides,                       // const size_t *inp_strides,
+            &out_dims,                          // const size_t *out_dims,
+            grid.data.as_ref(),                 // const float *grid,
+            Arc::make_mut(&mut grad_grid.data), // float *grad_grid,
+            &grid_strides,                      // const size_t *grid_strides,
+            grad_out.data.as_ref(),             // const float *grad_out,
+            &out_strides,                       // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
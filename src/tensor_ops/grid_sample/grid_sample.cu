@@ -0,0 +1,101 @@
+// Bilinear sampling of (batch, channels, height, width) images at the locations
+// of a (batch, out_height, out_width, 2) grid of (x, y) coordinates in [-1, 1],
+// like pytorch's `grid_sample(..., align_corners=False)`. Locations outside of
+// the image read zeros.
+
+// One thread per output location (n, i, j), looping over the channels.
+extern "C" __global__ void grid_sample_forward(
+    const size_t numel,
+    const size_t *inp_dims,
+    const float *inp,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    const float *grid,
+    const size_t *grid_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int idx = blockIdx.x * blockDim.x + threadIdx.x;
+    if (idx >= numel) {
+        return;
+    }
+
+    unsigned int j = idx % out_dims[3];
+    unsigned int i = (idx / out_dims[3]) % out_dims[2];
+    unsigned int n = idx / (out_dims[3] * out_dims[2]);
+    const size_t C = inp_dims[1], H = inp_dims[2], W = inp_dims[3];
+
+    unsigned int g_i = n * grid_strides[0] + i * grid_strides[1] + j * grid_strides[2];
+    float ix = ((grid[g_i] + 1.0) * W - 1.0) / 2.0;
+    float iy = ((grid[g_i + grid_strides[3]] + 1.0) * H - 1.0) / 2.0;
+    float x0 = floorf(ix), y0 = floorf(iy);
+    float wx1 = ix - x0, wy1 = iy - y0;
+
+    for (unsigned int k = 0; k < C; k++) {
+        float v = 0.0;
+        for (int oy = 0; oy < 2; oy++) {
+            for (int ox = 0; ox < 2; ox++) {
+                float y = y0 + oy, x = x0 + ox;
+                if (y < 0 || x < 0 || y >= H || x >= W) {
+                    continue;
+                }
+                float w = (oy ? wy1 : 1.0 - wy1) * (ox ? wx1 : 1.0 - wx1);
+                v += w * inp[n * inp_strides[0] + k * inp_strides[1] + (unsigned int)y * inp_strides[2] + (unsigned int)x * inp_strides[3]];
+            }
+        }
+        out[n * out_strides[0] + k * out_strides[1] + i * out_strides[2] + j * out_strides[3]] = v;
+    }
+}
+
+extern "C" __global__ void grid_sample_backward(
+    const size_t numel,
+    const size_t *inp_dims,
+    const float *inp,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    const float *grid,
+    float *grad_grid,
+    const size_t *grid_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int idx = blockIdx.x * blockDim.x + threadIdx.x;
+    if (idx >= numel) {
+        return;
+    }
+
+    unsigned int j = idx % out_dims[3];
+    unsigned int i = (idx / out_dims[3]) % out_dims[2];
+    unsigned int n = idx / (out_dims[3] * out_dims[2]);
+    const size_t C = inp_dims[1], H = inp_dims[2], W = inp_dims[3];
+
+    unsigned int g_i = n * grid_strides[0] + i * grid_strides[1] + j * grid_strides[2];
+    float ix = ((grid[g_i] + 1.0) * W - 1.0) / 2.0;
+    float iy = ((grid[g_i + grid_strides[3]] + 1.0) * H - 1.0) / 2.0;
+    float x0 = floorf(ix), y0 = floorf(iy);
+    float wx1 = ix - x0, wy1 = iy - y0;
+
+    float dx = 0.0, dy = 0.0;
+    for (unsigned int k = 0; k < C; k++) {
+        float g = grad_out[n * out_strides[0] + k * out_strides[1] + i * out_strides[2] + j * out_strides[3]];
+        for (int oy = 0; oy < 2; oy++) {
+            for (int ox = 0; ox < 2; ox++) {
+                float y = y0 + oy, x = x0 + ox;
+                if (y < 0 || x < 0 || y >= H || x >= W) {
+                    continue;
+                }
+                float wx = ox ? wx1 : 1.0 - wx1;
+                float wy = oy ? wy1 : 1.0 - wy1;
+                unsigned int inp_i = n * inp_strides[0] + k * inp_strides[1] + (unsigned int)y * inp_strides[2] + (unsigned int)x * inp_strides[3];
+                atomicAdd(grad_inp + inp_i, wy * wx * g);
+                float v = inp[inp_i] * g;
+                dx += (ox ? 1.0 : -1.0) * wy * v;
+                dy += (oy ? 1.0 : -1.0) * wx * v;
+            }
+        }
+    }
+    // the grid may be broadcast, so other threads can write to the same gradient
+    atomicAdd(grad_grid + g_i, dx * W / 2.0);
+    atomicAdd(grad_grid + g_i + grid_strides[3], dy * H / 2.0);
+}
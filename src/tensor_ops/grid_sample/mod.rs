@@ -0,0 +1,252 @@
+#![allow(clippy::type_complexity)]
+
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use std::vec::Vec;
+
+use super::{Device, PermuteTo, ReshapeTo, TryMatMul};
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::*,
+    tensor::*,
+};
+
+pub trait GridSampleKernel<E: Dtype>: DeviceStorage {
+    fn forward<B: Dim, C: Dim, H: Dim, W: Dim, OH: Dim, OW: Dim>(
+        &self,
+        inp: &Self::Storage<(B, C, H, W), E>,
+        grid: &Self::Storage<(B, OH, OW, Const<2>), E>,
+    ) -> Result<Self::Storage<(B, C, OH, OW), E>, Self::Err>;
+
+    fn backward<B: Dim, C: Dim, H: Dim, W: Dim, OH: Dim, OW: Dim>(
+        &self,
+        inp: &Self::Storage<(B, C, H, W), E>,
+        grad_inp: &mut Self::Storage<(B, C, H, W), E>,
+        grid: &Self::Storage<(B, OH, OW, Const<2>), E>,
+        grad_grid: &mut Self::Storage<(B, OH, OW, Const<2>), E>,
+        grad_out: &Self::Storage<(B, C, OH, OW), E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Samples `inp` with bilinear interpolation at the locations in `grid`. The output
+/// pixel `(i, j)` of each image is read from the location `grid[n, i, j] = (x, y)`, where
+/// `(-1, -1)` is the top left corner of the image and `(1, 1)` the bottom right corner.
+/// Locations outside of the image read zeros.
+///
+/// The gradient flows to both `inp` and `grid`, so the sampling locations can be learned,
+/// as in spatial transformer networks or flow based warping. Use [affine_grid()] to make
+/// a grid from an affine transformation.
+///
+/// **Pytorch equivalent**:
+/// `grid_sample(inp, grid, mode="bilinear", padding_mode="zeros", align_corners=False)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let inp: Tensor<Rank4<1, 1, 2, 2>> = dev.tensor([[[[1.0, 2.0], [3.0, 4.0]]]]);
+/// // the centers of the top right pixel & the whole image
+/// let grid: Tensor<Rank4<1, 1, 2, 2>> = dev.tensor([[[[0.5, -0.5], [0.0, 0.0]]]]);
+/// let r = grid_sample(inp, grid);
+/// assert_eq!(r.array(), [[[[2.0, 2.5]]]]);
+/// ```
+pub fn grid_sample<B: Dim, C: Dim, H: Dim, W: Dim, OH: Dim, OW: Dim, D, T, R>(
+    inp: Tensor<(B, C, H, W), f32, D, T>,
+    grid: Tensor<(B, OH, OW, Const<2>), f32, D, R>,
+) -> Tensor<(B, C, OH, OW), f32, D, T>
+where
+    D: GridSampleKernel<f32>,
+    T: Tape<D> + Merge<R>,
+    R: Tape<D>,
+{
+    inp.grid_sample(grid)
+}
+
+impl<B: Dim, C: Dim, H: Dim, W: Dim, D: GridSampleKernel<f32>, T: Tape<D>>
+    Tensor<(B, C, H, W), f32, D, T>
+{
+    /// See [grid_sample()]
+    pub fn grid_sample<OH: Dim, OW: Dim, R: Tape<D>>(
+        self,
+        grid: Tensor<(B, OH, OW, Const<2>), f32, D, R>,
+    ) -> Tensor<(B, C, OH, OW), f32, D, T>
+    where
+        T: Merge<R>,
+    {
+        self.try_grid_sample(grid).unwrap()
+    }
+
+    /// See [grid_sample()]
+    pub fn try_grid_sample<OH: Dim, OW: Dim, R: Tape<D>>(
+        self,
+        grid: Tensor<(B, OH, OW, Const<2>), f32, D, R>,
+    ) -> Result<Tensor<(B, C, OH, OW), f32, D, T>, D::Err>
+    where
+        T: Merge<R>,
+    {
+        assert_eq!(self.shape().0, grid.shape().0);
+        let (inp, ltape) = self.split_tape();
+        let (grid, rtape) = grid.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let storage = inp.device.forward(&inp.storage, &grid.storage)?;
+        let out = inp.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&grid)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_grid, grad_out) = grads.muts_and_ref(&inp, &grid, &phantom_out);
+            inp.device
+                .backward(&inp.storage, grad_inp, &grid.storage, grad_grid, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+/// Makes a `OH`x`OW` sampling grid for [grid_sample()] from a batch of affine
+/// transformations `theta` with shape `(B, 2, 3)`. The location of output pixel `(i, j)`
+/// is `theta[n] * (x_j, y_i, 1)`, where `(x_j, y_i)` is the center of the pixel in the
+/// same `[-1, 1]` coordinates that [grid_sample()] uses. The identity transformation
+/// `[[1, 0, 0], [0, 1, 0]]` samples the image as is.
+///
+/// The grid is differentiable with respect to `theta`.
+///
+/// **Pytorch equivalent**: `affine_grid(theta, (B, C, OH, OW), align_corners=False)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let img: Tensor<Rank4<1, 1, 2, 2>> = dev.tensor([[[[1.0, 2.0], [3.0, 4.0]]]]);
+/// // flip horizontally
+/// let theta: Tensor<Rank3<1, 2, 3>> = dev.tensor([[[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]]);
+/// let grid = affine_grid::<2, 2, _, _, _>(theta);
+/// assert_eq!(grid_sample(img, grid).array(), [[[[2.0, 1.0], [4.0, 3.0]]]]);
+/// ```
+pub fn affine_grid<const OH: usize, const OW: usize, B: Dim, D: Device<f32>, T: Tape<D>>(
+    theta: Tensor<(B, Const<2>, Const<3>), f32, D, T>,
+) -> Tensor<(B, Const<OH>, Const<OW>, Const<2>), f32, D, T> {
+    theta.affine_grid::<OH, OW>()
+}
+
+impl<B: Dim, D: Device<f32>, T: Tape<D>> Tensor<(B, Const<2>, Const<3>), f32, D, T> {
+    /// See [affine_grid()]
+    pub fn affine_grid<const OH: usize, const OW: usize>(
+        self,
+    ) -> Tensor<(B, Const<OH>, Const<OW>, Const<2>), f32, D, T> {
+        self.try_affine_grid::<OH, OW>().unwrap()
+    }
+
+    /// See [affine_grid()]
+    pub fn try_affine_grid<const OH: usize, const OW: usize>(
+        self,
+    ) -> Result<Tensor<(B, Const<OH>, Const<OW>, Const<2>), f32, D, T>, D::Err> {
+        // the homogeneous coordinates of the pixel centers, with shape (3, OH * OW)
+        let mut base: Vec<f32> = Vec::with_capacity(3 * OH * OW);
+        for k in 0..3 {
+            for i in 0..OH {
+                for j in 0..OW {
+                    base.push(match k {
+                        0 => (2 * j + 1) as f32 / OW as f32 - 1.0,
+                        1 => (2 * i + 1) as f32 / OH as f32 - 1.0,
+                        _ => 1.0,
+                    });
+                }
+            }
+        }
+        let mut base_t = self.device.try_zeros_like(&(Const::<3>, OH * OW))?;
+        base_t.copy_from(&base);
+
+        let b = self.shape().0;
+        let grid = self
+            .try_reshape_like(&(b.size() * 2, Const::<3>))?
+            .try_matmul(base_t)?;
+        grid.try_reshape_like(&(b, Const::<2>, Const::<OH>, Const::<OW>))?
+            .try_permute::<_, Axes4<0, 2, 3, 1>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_grid_sample_identity() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank4<2, 3, 4, 5>, f32, _> = dev.sample_normal();
+        let theta: Tensor<Rank3<2, 2, 3>, f32, _> =
+            dev.tensor([[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]; 2]);
+        let grid = theta.affine_grid::<4, 5>();
+        let r = inp.trace().grid_sample(grid);
+        assert_close(&r.array(), &inp.array());
+        let g = r.sum().backward();
+        assert_close(&g.get(&inp).array(), &[[[[1.0; 5]; 4]; 3]; 2]);
+    }
+
+    #[test]
+    fn test_affine_grid() {
+        let dev: TestDevice = Default::default();
+        let theta: Tensor<Rank3<1, 2, 3>, f32, _> =
+            dev.tensor([[[1.0, 2.0, 0.5], [-1.0, 0.0, 1.0]]]);
+        let grid = theta.trace().affine_grid::<2, 3>();
+        let x = [-2.0 / 3.0, 0.0, 2.0 / 3.0];
+        let y = [-0.5, 0.5];
+        let mut expected = [[[[0.0; 2]; 3]; 2]; 1];
+        for i in 0..2 {
+            for j in 0..3 {
+                expected[0][i][j] = [x[j] + 2.0 * y[i] + 0.5, -x[j] + 1.0];
+            }
+        }
+        assert_close(&grid.array(), &expected);
+
+        let g = grid.sum().backward();
+        // the sum of the homogeneous coordinates of all pixels
+        assert_close(
+            &g.get(&theta).array(),
+            &[[[0.0, 0.0, 6.0], [0.0, 0.0, 6.0]]],
+        );
+    }
+
+    #[test]
+    fn test_grid_sample_bilinear() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank4<1, 2, 2, 2>, f32, _> =
+            dev.tensor([[[[1.0, 2.0], [3.0, 4.0]], [[0.0, 1.0], [0.0, -1.0]]]]);
+        let grid: Tensor<Rank4<1, 1, 3, 2>, f32, _> =
+            dev.tensor([[[[0.0, 0.0], [-0.25, 0.4], [1.0, 1.0]]]]);
+        let r = inp.trace().grid_sample(grid.trace());
+        assert_close(&r.array(), &[[[[2.5, 3.05, 1.0]], [[0.0, -0.2, -0.25]]]]);
+
+        let g = r.exp().sum().backward();
+        assert_close_with_tolerance(
+            &g.get(&inp).array(),
+            &[[
+                [[4.629274, 3.573507], [17.298481, 8.476146]],
+                [[0.3114048, 0.27046827], [0.80264326, 0.6289146]],
+            ]],
+            1e-4,
+        );
+        assert_close_with_tolerance(
+            &g.get(&grid).array(),
+            &[[[
+                [12.182494, 23.364988],
+                [20.46036, 41.821323],
+                [-5.047163, -5.047163],
+            ]]],
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn test_grid_sample_out_of_bounds_is_zero() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank4<1, 1, 2, 2>, f32, _> = dev.ones();
+        let grid: Tensor<Rank4<1, 1, 2, 2>, f32, _> = dev.tensor([[[[3.0, 0.0], [-1.0, 0.0]]]]);
+        let r = inp.grid_sample(grid);
+        assert_eq!(r.array(), [[[[0.0, 0.5]]]]);
+    }
+}
@@ -0,0 +1,281 @@
+#![allow(clippy::type_complexity)]
+
+use core::ops::RangeInclusive;
+use num_traits::AsPrimitive;
+use std::vec::Vec;
+
+use crate::{shapes::*, tensor::*};
+
+/// Counts the number of occurrences of each value in `t`. The result has
+/// `max(t) + 1` elements, or `min_len` if that is more.
+///
+/// This is not differentiable, the counting happens on the host.
+///
+/// **Pytorch equivalent**: `torch.bincount(t, minlength=min_len)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 3>, usize> = dev.tensor([[0, 1, 1], [3, 1, 0]]);
+/// assert_eq!(bincount(&t, 0).as_vec(), [2, 3, 0, 1]);
+/// assert_eq!(bincount(&t, 6).as_vec(), [2, 3, 0, 1, 0, 0]);
+/// ```
+pub fn bincount<S: Shape, D: TensorFromVec<usize>>(
+    t: &Tensor<S, usize, D>,
+    min_len: usize,
+) -> Tensor<(usize,), usize, D> {
+    try_bincount(t, min_len).unwrap()
+}
+
+/// Fallible version of [bincount()]
+pub fn try_bincount<S: Shape, D: TensorFromVec<usize>>(
+    t: &Tensor<S, usize, D>,
+    min_len: usize,
+) -> Result<Tensor<(usize,), usize, D>, D::Err> {
+    let values = t.as_vec();
+    let len = values
+        .iter()
+        .map(|&v| v + 1)
+        .max()
+        .unwrap_or(0)
+        .max(min_len);
+    let mut counts = alloc::vec![0; len];
+    for v in values {
+        counts[v] += 1;
+    }
+    t.device.try_tensor_from_vec(counts, (len,))
+}
+
+/// [bincount()] of each row of `t` separately, with `num_bins` bins each. The result has
+/// shape `(B, num_bins)`.
+///
+/// **Panics** if a value is `>= num_bins`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 3>, usize> = dev.tensor([[0, 1, 1], [2, 1, 0]]);
+/// let counts = batched_bincount(&t, 3);
+/// assert_eq!(counts.shape(), &(Const::<2>, 3));
+/// assert_eq!(counts.as_vec(), [1, 2, 0, 1, 1, 1]);
+/// ```
+pub fn batched_bincount<B: Dim, N: Dim, D: TensorFromVec<usize>>(
+    t: &Tensor<(B, N), usize, D>,
+    num_bins: usize,
+) -> Tensor<(B, usize), usize, D> {
+    try_batched_bincount(t, num_bins).unwrap()
+}
+
+/// Fallible version of [batched_bincount()]
+pub fn try_batched_bincount<B: Dim, N: Dim, D: TensorFromVec<usize>>(
+    t: &Tensor<(B, N), usize, D>,
+    num_bins: usize,
+) -> Result<Tensor<(B, usize), usize, D>, D::Err> {
+    let (b, n) = *t.shape();
+    let values = t.as_vec();
+    let mut counts = alloc::vec![0; b.size() * num_bins];
+    for (row, values) in counts
+        .chunks_mut(num_bins)
+        .zip(values.chunks(n.size().max(1)))
+    {
+        for &v in values {
+            assert!(
+                v < num_bins,
+                "Value {v} is out of bounds for bincount with {num_bins} bins"
+            );
+            row[v] += 1;
+        }
+    }
+    t.device.try_tensor_from_vec(counts, (b, num_bins))
+}
+
+/// Counts the values of `t` in `bins` equal width bins between the ends of `range`. The last
+/// bin includes the end of the range, and values outside of the range (and NaNs) are ignored.
+///
+/// **Panics** if `bins` is 0, or the range is empty.
+///
+/// **Pytorch equivalent**: `torch.histc(t, bins, min, max)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank1<6>> = dev.tensor([0.0, 0.2, 0.5, 0.7, 1.0, 2.0]);
+/// assert_eq!(histogram(&t, 4, 0.0..=1.0).as_vec(), [2, 0, 2, 1]);
+/// ```
+pub fn histogram<S: Shape, E, D: TensorFromVec<usize>>(
+    t: &Tensor<S, E, D>,
+    bins: usize,
+    range: RangeInclusive<E>,
+) -> Tensor<(usize,), usize, D>
+where
+    E: Dtype + AsPrimitive<f64>,
+{
+    try_histogram(t, bins, range).unwrap()
+}
+
+/// Fallible version of [histogram()]
+pub fn try_histogram<S: Shape, E, D: TensorFromVec<usize>>(
+    t: &Tensor<S, E, D>,
+    bins: usize,
+    range: RangeInclusive<E>,
+) -> Result<Tensor<(usize,), usize, D>, D::Err>
+where
+    E: Dtype + AsPrimitive<f64>,
+{
+    assert!(bins > 0, "Histogram needs at least one bin");
+    let (min, max): (f64, f64) = (range.start().as_(), range.end().as_());
+    assert!(min < max, "Histogram range {min}..={max} is empty");
+    let mut counts = alloc::vec![0; bins];
+    for v in t.as_vec() {
+        let v: f64 = v.as_();
+        if (min..=max).contains(&v) {
+            let bin = ((v - min) / (max - min) * bins as f64) as usize;
+            counts[bin.min(bins - 1)] += 1;
+        }
+    }
+    t.device.try_tensor_from_vec(counts, (bins,))
+}
+
+/// The distinct values of `t` in ascending order. NaNs are never equal to each other, so
+/// each NaN is kept, at the end.
+///
+/// **Pytorch equivalent**: `torch.unique(t, sorted=True)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 3>, usize> = dev.tensor([[5, 1, 5], [3, 1, 1]]);
+/// assert_eq!(unique(&t).as_vec(), [1, 3, 5]);
+/// ```
+pub fn unique<S: Shape, E: Unit, D: TensorFromVec<E>>(
+    t: &Tensor<S, E, D>,
+) -> Tensor<(usize,), E, D> {
+    try_unique(t).unwrap()
+}
+
+/// Fallible version of [unique()]
+pub fn try_unique<S: Shape, E: Unit, D: TensorFromVec<E>>(
+    t: &Tensor<S, E, D>,
+) -> Result<Tensor<(usize,), E, D>, D::Err> {
+    let (values, _) = sorted_unique(t.as_vec());
+    let len = values.len();
+    t.device.try_tensor_from_vec(values, (len,))
+}
+
+/// [unique()] that also returns how often each value occurs.
+///
+/// **Pytorch equivalent**: `torch.unique(t, sorted=True, return_counts=True)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank1<5>> = dev.tensor([0.5, -1.0, 0.5, 2.0, 0.5]);
+/// let (values, counts) = unique_counts(&t);
+/// assert_eq!(values.as_vec(), [-1.0, 0.5, 2.0]);
+/// assert_eq!(counts.as_vec(), [1, 3, 1]);
+/// ```
+pub fn unique_counts<S: Shape, E: Unit, D: TensorFromVec<E> + TensorFromVec<usize>>(
+    t: &Tensor<S, E, D>,
+) -> (Tensor<(usize,), E, D>, Tensor<(usize,), usize, D>) {
+    try_unique_counts(t).unwrap()
+}
+
+/// Fallible version of [unique_counts()]
+pub fn try_unique_counts<S: Shape, E: Unit, D: TensorFromVec<E> + TensorFromVec<usize>>(
+    t: &Tensor<S, E, D>,
+) -> Result<(Tensor<(usize,), E, D>, Tensor<(usize,), usize, D>), D::Err> {
+    let (values, counts) = sorted_unique(t.as_vec());
+    let len = values.len();
+    Ok((
+        t.device.try_tensor_from_vec(values, (len,))?,
+        t.device.try_tensor_from_vec(counts, (len,))?,
+    ))
+}
+
+/// Sorts `values` with NaNs last, and removes consecutive equal values, counting them.
+fn sorted_unique<E: Unit>(mut values: Vec<E>) -> (Vec<E>, Vec<usize>) {
+    #[allow(clippy::eq_op)]
+    let is_nan = |v: &E| v != v;
+    values.sort_by(|a, b| {
+        a.partial_cmp(b)
+            .unwrap_or_else(|| is_nan(a).cmp(&is_nan(b)))
+    });
+    let mut unique: Vec<E> = Vec::new();
+    let mut counts = Vec::new();
+    for v in values {
+        match unique.last() {
+            Some(last) if *last == v => *counts.last_mut().unwrap() += 1,
+            _ => {
+                unique.push(v);
+                counts.push(1);
+            }
+        }
+    }
+    (unique, counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_bincount() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize,), usize, _> = dev.tensor_from_vec(alloc::vec![], (0,));
+        assert!(bincount(&t, 0).as_vec().is_empty());
+        assert_eq!(bincount(&t, 2).as_vec(), [0, 0]);
+
+        let t: Tensor<Rank1<5>, usize, _> = dev.tensor([4, 0, 4, 4, 2]);
+        assert_eq!(bincount(&t, 3).as_vec(), [1, 0, 1, 0, 3]);
+    }
+
+    #[test]
+    fn test_batched_bincount() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize, Const<4>), usize, _> =
+            dev.tensor_from_vec(alloc::vec![0, 0, 1, 3, 2, 2, 2, 2], (2, Const));
+        let counts = batched_bincount(&t, 5);
+        assert_eq!(counts.shape(), &(2, 5));
+        assert_eq!(counts.as_vec(), [2, 1, 0, 1, 0, 0, 0, 4, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic = "Value 3 is out of bounds for bincount with 3 bins"]
+    fn test_batched_bincount_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<1, 2>, usize, _> = dev.tensor([[1, 3]]);
+        let _ = batched_bincount(&t, 3);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<8>, f32, _> =
+            dev.tensor([-1.0, -0.5, 0.0, 0.49, 0.5, 1.0, 1.5, f32::NAN]);
+        assert_eq!(histogram(&t, 2, -0.5..=1.0).as_vec(), [2, 3]);
+        assert_eq!(histogram(&t, 1, -1.0..=1.5).as_vec(), [7]);
+
+        let t: Tensor<Rank1<4>, i32, _> = dev.tensor([0, 3, 5, 9]);
+        assert_eq!(histogram(&t, 3, 0..=9).as_vec(), [1, 2, 1]);
+    }
+
+    #[test]
+    fn test_unique() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<6>, f32, _> = dev.tensor([2.0, f32::NAN, -1.0, 2.0, f32::NAN, -1.0]);
+        let (values, counts) = unique_counts(&t);
+        let values = values.as_vec();
+        assert_eq!(values[..2], [-1.0, 2.0]);
+        assert!(values[2].is_nan() && values[3].is_nan());
+        assert_eq!(counts.as_vec(), [2, 2, 1, 1]);
+
+        let t: Tensor<Rank1<4>, bool, _> = dev.tensor([true, false, true, true]);
+        assert_eq!(unique(&t).as_vec(), [false, true]);
+    }
+}
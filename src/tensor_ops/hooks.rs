@@ -0,0 +1,116 @@
+use crate::{
+    gradients::Tape,
+    shapes::{Dtype, Shape},
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+impl<S: Shape, E: Dtype, D: DeviceStorage, T: Tape<D>> Tensor<S, E, D, T> {
+    /// Calls `f` with this tensor during the forward pass. `f` can observe
+    /// or modify the values of the tensor.
+    ///
+    /// Modifications are treated like the identity function during the backward pass,
+    /// so gradients flow through unchanged.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([1.0, -2.0, 3.0]);
+    /// let r = t.trace().forward_hook(|x| {
+    ///     std::println!("activations: {:?}", x.array());
+    ///     *x = x.clone().abs();
+    /// });
+    /// assert_eq!(r.array(), [1.0, 2.0, 3.0]);
+    /// ```
+    pub fn forward_hook<F: FnOnce(&mut Tensor<S, E, D>)>(self, f: F) -> Self {
+        let (mut t, tape) = self.split_tape();
+        let id = t.id;
+        f(&mut t);
+        // keep the same id so gradients are still associated with this tensor
+        t.id = id;
+        t.put_tape(tape)
+    }
+
+    /// Calls `f` with the gradient of this tensor during the backward pass, after
+    /// all of the operations using this tensor have contributed to it. The gradient
+    /// is replaced with the result of `f`, which is then backpropagated to the
+    /// operation that created this tensor.
+    ///
+    /// This only has an effect if the tensor has an [crate::gradients::OwnedTape].
+    ///
+    /// A gradient reversal layer:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([1.0, 2.0, 3.0]);
+    /// let r = t.trace().backward_hook(|g| g.negate());
+    /// let g = r.sum().backward();
+    /// assert_eq!(g.get(&t).array(), [-1.0; 3]);
+    /// ```
+    pub fn backward_hook<F>(self, f: F) -> Self
+    where
+        F: 'static + FnOnce(Tensor<S, E, D>) -> Tensor<S, E, D>,
+    {
+        self.try_backward_hook(f).unwrap()
+    }
+
+    /// See [Tensor::backward_hook]
+    pub fn try_backward_hook<F>(self, f: F) -> Result<Self, D::Err>
+    where
+        F: 'static + FnOnce(Tensor<S, E, D>) -> Tensor<S, E, D>,
+    {
+        let (t, mut tape) = self.split_tape();
+        let phantom = t.clone();
+        tape.try_alloc_grad(&t)?;
+        tape.add_named_backward_op("backward_hook", move |grads| {
+            let grad = grads.get_mut(&phantom);
+            let hooked = f(phantom.device.upgrade(grad.clone()));
+            *grad = hooked.storage;
+            Ok(())
+        });
+        Ok(t.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{assert_close, TestDevice};
+    use crate::{gradients::OwnedTape, shapes::*, tensor::*, tensor_ops::*};
+    use std::{cell::RefCell, rc::Rc, vec::Vec};
+
+    #[test]
+    fn test_forward_hook_modifies_values() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, -2.0, 3.0]);
+        let r = t.trace().forward_hook(|x| *x = x.clone() * 2.0);
+        assert_eq!(r.array(), [2.0, -4.0, 6.0]);
+        let g = r.square().sum().backward();
+        // backward treats the hook as the identity, so d/dt = 2 * r
+        assert_eq!(g.get(&t).array(), [4.0, -8.0, 12.0]);
+    }
+
+    #[test]
+    fn test_backward_hook_observes_accumulated_grad() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_ = seen.clone();
+        let r = t.trace().exp().backward_hook(move |g| {
+            seen_.borrow_mut().push(g.array());
+            g
+        });
+        let g = (r.retaped::<OwnedTape<_>>() + r.square()).sum().backward();
+        let r = t.clone().exp().array();
+        let expected = r.map(|x| 1.0 + 2.0 * x);
+        assert_eq!(seen.borrow().as_slice(), [expected]);
+        assert_close(&g.get(&t).array(), &[0, 1, 2].map(|i| expected[i] * r[i]));
+    }
+
+    #[test]
+    fn test_backward_hook_reverses_grad() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let r = t.trace().square().backward_hook(|g| g * -0.5);
+        let g = r.mean::<Rank0, _>().backward();
+        assert_eq!(g.get(&t).array(), [[-0.25, -0.5], [-0.75, -1.0]]);
+    }
+}
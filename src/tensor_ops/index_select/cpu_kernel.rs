@@ -0,0 +1,61 @@
+use crate::shapes::{Dtype, Shape};
+use crate::tensor::cpu::{Cpu, LendingIterator, StridedArray};
+use std::ops::Index;
+
+/// The position in `idx` of the index used at `i`, where the dims of `idx` line up with the
+/// dims of `i` ending at `ax`.
+fn idx_position<Idx: Shape, I: Index<usize, Output = usize>>(
+    idx: &StridedArray<Idx, usize>,
+    i: &I,
+    ax: usize,
+) -> usize {
+    let first = ax + 1 - Idx::NUM_DIMS;
+    (0..Idx::NUM_DIMS)
+        .map(|k| i[first + k] * idx.strides[k])
+        .sum()
+}
+
+impl<E: Dtype> super::IndexSelectKernel<E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Idx: Shape>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        idx: &Self::Storage<Idx, usize>,
+        dst: Dst,
+        ax: usize,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err> {
+        let mut out = StridedArray::new(dst)?;
+        if dst.num_elements() == 0 {
+            return Ok(out);
+        }
+        let size = inp.shape.concrete()[ax];
+        let mut out_iter = out.iter_mut_with_index();
+        while let Some((o, mut i)) = out_iter.next() {
+            i[ax] = idx.data[idx_position(idx, &i, ax)];
+            assert!(
+                i[ax] < size,
+                "Index {} is out of bounds for axis {ax} with size {size}",
+                i[ax]
+            );
+            *o = inp[i];
+        }
+        Ok(out)
+    }
+
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Idx: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        idx: &Self::Storage<Idx, usize>,
+        grad_out: &Self::Storage<Dst, E>,
+        ax: usize,
+    ) -> Result<(), Self::Err> {
+        if grad_out.shape.num_elements() == 0 {
+            return Ok(());
+        }
+        let mut out_iter = grad_out.iter_with_index();
+        while let Some((o, mut i)) = out_iter.next() {
+            i[ax] = idx.data[idx_position(idx, &i, ax)];
+            grad_inp[i] += *o;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/index_select.ptx"));
+const MODULE_NAME: &str = "index_select";
+const FWD_FN_NAME: &str = "index_select_forward";
+const BWD_FN_NAME: &str = "index_select_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl super::IndexSelectKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Idx: Shape>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        idx: &Self::Storage<Idx, usize>,
+        dst: Dst,
+        ax: usize,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = dst.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        if numel == 0 {
+            return Ok(CudaArray {
+                data: Arc::new(storage),
+                shape: dst,
+                strides: dst.strides(),
+            });
+        }
+
+        let out_dims: CudaSlice<usize> = self.dev.take_async(dst.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(dst.strides().into())?;
+        let idx_strides: CudaSlice<usize> = self.dev.take_async(idx.strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            Dst::NUM_DIMS,     // const size_t num_dims,
+            &out_dims,         // const size_t *out_dims,
+            ax,                // const size_t ax,
+            idx.data.as_ref(), // const size_t *idx,
+            Idx::NUM_DIMS,     // const size_t idx_num_dims,
+            &idx_strides,      // const size_t *idx_strides,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut storage,      // float *out,
+            &out_strides,      // const size_t *out_strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Idx: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        idx: &Self::Storage<Idx, usize>,
+        grad_out: &Self::Storage<Dst, f32>,
+        ax: usize,
+    ) -> Result<(), Self::Err> {
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let numel = grad_out.shape.num_elements();
+        if numel == 0 {
+            return Ok(());
+        }
+
+        let out_dims: CudaSlice<usize> = self.dev.take_async(grad_out.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+        let idx_strides: CudaSlice<usize> = self.dev.take_async(idx.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            Dst::NUM_DIMS,                     // const size_t num_dims,
+            &out_dims,                         // const size_t *out_dims,
+            ax,                                // const size_t ax,
+            idx.data.as_ref(),                 // const size_t *idx,
+            Idx::NUM_DIMS,                     // const size_t idx_num_dims,
+            &idx_strides,                      // const size_t *idx_strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &out_strides,                      // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
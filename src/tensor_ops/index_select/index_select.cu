@@ -0,0 +1,82 @@
+// Computes the strided index into `inp` & `out` at the same time, replacing
+// the index along `ax` with the value looked up in `idx`. The dims of `idx`
+// line up with the dims of `out` ending at `ax`.
+__device__ void get_strided_indices(
+    unsigned int i,
+    const size_t num_dims,
+    const size_t *out_dims,
+    const size_t ax,
+    const size_t *idx,
+    const size_t idx_num_dims,
+    const size_t *idx_strides,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    unsigned int *inp_i,
+    unsigned int *out_i
+) {
+    *inp_i = 0;
+    *out_i = 0;
+    unsigned int idx_i = 0;
+    const size_t first = ax + 1 - idx_num_dims;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        unsigned int j = i % out_dims[dim_idx];
+        i /= out_dims[dim_idx];
+        *out_i += j * out_strides[dim_idx];
+        if (dim_idx >= first && dim_idx <= ax) {
+            idx_i += j * idx_strides[dim_idx - first];
+        }
+        if (dim_idx != ax) {
+            *inp_i += j * inp_strides[dim_idx];
+        }
+    }
+    *inp_i += idx[idx_i] * inp_strides[ax];
+}
+
+extern "C" __global__ void index_select_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *out_dims,
+    const size_t ax,
+    const size_t *idx,
+    const size_t idx_num_dims,
+    const size_t *idx_strides,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i, out_i;
+    get_strided_indices(i, num_dims, out_dims, ax, idx, idx_num_dims, idx_strides, inp_strides, out_strides, &inp_i, &out_i);
+
+    out[out_i] = inp[inp_i];
+}
+
+extern "C" __global__ void index_select_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *out_dims,
+    const size_t ax,
+    const size_t *idx,
+    const size_t idx_num_dims,
+    const size_t *idx_strides,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i, out_i;
+    get_strided_indices(i, num_dims, out_dims, ax, idx, idx_num_dims, idx_strides, inp_strides, out_strides, &inp_i, &out_i);
+
+    atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+}
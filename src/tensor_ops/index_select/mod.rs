@@ -0,0 +1,356 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+pub trait IndexSelectKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Idx: Shape>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        idx: &Self::Storage<Idx, usize>,
+        dst: Dst,
+        ax: usize,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Idx: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        idx: &Self::Storage<Idx, usize>,
+        grad_out: &Self::Storage<Dst, E>,
+        ax: usize,
+    ) -> Result<(), Self::Err>;
+}
+
+/// The shape of an index tensor for [Tensor::index_select]. The last dimension is the
+/// number of indices, and the dimensions before it are batch dimensions.
+pub trait IndexShape: Shape {
+    /// The number of indices.
+    type Last: Dim;
+    fn last_dim(&self) -> Self::Last;
+}
+
+macro_rules! index_shape {
+    (($($Batch:ident),*), $Idx:tt) => {
+        impl<$($Batch: Dim, )* Z: Dim> IndexShape for ($($Batch, )* Z,) {
+            type Last = Z;
+            fn last_dim(&self) -> Z {
+                self.$Idx
+            }
+        }
+    };
+}
+
+index_shape!((), 0);
+index_shape!((B1), 1);
+index_shape!((B1, B2), 2);
+index_shape!((B1, B2, B3), 3);
+index_shape!((B1, B2, B3, B4), 4);
+
+/// Index tensors that can select from a tensor of shape `S` along `Ax`. This is
+/// a single index tensor for a single axis, or a tuple of them for a tuple of axes.
+/// See [Tensor::index_select].
+pub trait SelectIndices<S: Shape, Ax: Axes, D: DeviceStorage> {
+    /// The shape of the selected tensor.
+    type Selected: Shape;
+
+    fn try_select_from<E: Dtype, T: Tape<D>>(
+        self,
+        t: Tensor<S, E, D, T>,
+    ) -> Result<Tensor<Self::Selected, E, D, T>, D::Err>
+    where
+        D: IndexSelectKernel<E>;
+}
+
+impl<S, Ax, Idx, D> SelectIndices<S, Ax, D> for Tensor<Idx, usize, D>
+where
+    S: ReplaceAxis<Ax, Idx::Last>,
+    Ax: Axes<Array = [isize; 1]>,
+    Idx: IndexShape,
+    D: DeviceStorage,
+{
+    type Selected = S::Replaced;
+
+    fn try_select_from<E: Dtype, T: Tape<D>>(
+        self,
+        t: Tensor<S, E, D, T>,
+    ) -> Result<Tensor<Self::Selected, E, D, T>, D::Err>
+    where
+        D: IndexSelectKernel<E>,
+    {
+        let idx = self;
+        let ax = Ax::as_array()[0] as usize;
+        let dst = t.shape().replace_axis(idx.shape().last_dim());
+        assert!(
+            Idx::NUM_DIMS <= ax + 1,
+            "Index tensor has {} dims, but can have at most {} to select along axis {ax}",
+            Idx::NUM_DIMS,
+            ax + 1
+        );
+        let (dst_dims, idx_dims) = (dst.concrete(), idx.shape().concrete());
+        let first = ax + 1 - Idx::NUM_DIMS;
+        for k in 0..Idx::NUM_DIMS - 1 {
+            assert_eq!(
+                idx_dims[k],
+                dst_dims[first + k],
+                "Batch dim {k} of the index tensor does not match dim {} of the tensor",
+                first + k
+            );
+        }
+
+        let (inp, mut tape) = t.split_tape();
+        let out = inp
+            .device
+            .upgrade(inp.device.forward(&inp.storage, &idx.storage, dst, ax)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(grad_inp, &idx.storage, grad_out, ax)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<S, const A0: isize, const A1: isize, I0, I1, D> SelectIndices<S, Axes2<A0, A1>, D> for (I0, I1)
+where
+    S: Shape,
+    I0: SelectIndices<S, Axis<A0>, D>,
+    I1: SelectIndices<I0::Selected, Axis<A1>, D>,
+    D: DeviceStorage,
+{
+    type Selected = I1::Selected;
+
+    fn try_select_from<E: Dtype, T: Tape<D>>(
+        self,
+        t: Tensor<S, E, D, T>,
+    ) -> Result<Tensor<Self::Selected, E, D, T>, D::Err>
+    where
+        D: IndexSelectKernel<E>,
+    {
+        let t = self.0.try_select_from(t)?;
+        self.1.try_select_from(t)
+    }
+}
+
+impl<S, const A0: isize, const A1: isize, const A2: isize, I0, I1, I2, D>
+    SelectIndices<S, Axes3<A0, A1, A2>, D> for (I0, I1, I2)
+where
+    S: Shape,
+    I0: SelectIndices<S, Axis<A0>, D>,
+    I1: SelectIndices<I0::Selected, Axis<A1>, D>,
+    I2: SelectIndices<I1::Selected, Axis<A2>, D>,
+    D: DeviceStorage,
+{
+    type Selected = I2::Selected;
+
+    fn try_select_from<E: Dtype, T: Tape<D>>(
+        self,
+        t: Tensor<S, E, D, T>,
+    ) -> Result<Tensor<Self::Selected, E, D, T>, D::Err>
+    where
+        D: IndexSelectKernel<E>,
+    {
+        let t = self.0.try_select_from(t)?;
+        let t = self.1.try_select_from(t)?;
+        self.2.try_select_from(t)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: IndexSelectKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// Selects the entries given by `idx` along axis `Ax`, in the order they appear
+    /// in `idx`. Indices may repeat, in which case their gradients are summed during
+    /// backward. The dimension at `Ax` is replaced by the last dimension of `idx`.
+    ///
+    /// Any dimensions of `idx` before its last one are batch dimensions, which line up with
+    /// the dimensions right before `Ax`, so each batch entry selects with its own indices.
+    /// Missing leading batch dimensions are broadcast, so a 1d `idx` uses the same indices
+    /// for every batch entry.
+    ///
+    /// To select along several axes, pass a tuple of index tensors and a tuple of axes.
+    /// They are applied in order, so the batch dimensions of each index tensor line up
+    /// with the result of the previous selection.
+    ///
+    /// The indices are read on the device, so they can be the output of other ops
+    /// (e.g. an argmax or top-k) without copying them back to the host.
+    ///
+    /// **Pytorch equivalent**: `t.index_select(Ax, idx)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 3>> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    /// let r: Tensor<Rank2<2, 4>> = t
+    ///     .clone()
+    ///     .index_select::<Axis<1>, _>(dev.tensor([2, 0, 0, 1]));
+    /// assert_eq!(r.array(), [[3.0, 1.0, 1.0, 2.0], [6.0, 4.0, 4.0, 5.0]]);
+    ///
+    /// // runtime number of indices
+    /// let idx: Tensor<(usize,), usize> = dev.zeros_like(&(2,));
+    /// let r: Tensor<(usize, Const<3>)> = t.clone().index_select::<Axis<0>, _>(idx);
+    /// assert_eq!(r.as_vec(), [1.0, 2.0, 3.0, 1.0, 2.0, 3.0]);
+    ///
+    /// // different indices for each row
+    /// let r: Tensor<Rank2<2, 2>> = t
+    ///     .clone()
+    ///     .index_select::<Axis<1>, _>(dev.tensor([[2, 0], [1, 1]]));
+    /// assert_eq!(r.array(), [[3.0, 1.0], [5.0, 5.0]]);
+    ///
+    /// // multiple axes
+    /// let r: Tensor<Rank2<1, 2>> = t
+    ///     .index_select::<Axes2<0, 1>, _>((dev.tensor([1]), dev.tensor([0, 2])));
+    /// assert_eq!(r.array(), [[4.0, 6.0]]);
+    /// ```
+    ///
+    /// **Panics** if the batch dimensions of `idx` don't match the dimensions before `Ax`,
+    /// and on Cpu if any index is out of bounds of the dimension.
+    pub fn index_select<Ax: Axes, I: SelectIndices<S, Ax, D>>(
+        self,
+        idx: I,
+    ) -> Tensor<I::Selected, E, D, T> {
+        self.try_index_select::<Ax, I>(idx).unwrap()
+    }
+
+    /// Fallible version of [Tensor::index_select]
+    pub fn try_index_select<Ax: Axes, I: SelectIndices<S, Ax, D>>(
+        self,
+        idx: I,
+    ) -> Result<Tensor<I::Selected, E, D, T>, D::Err> {
+        idx.try_select_from(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{assert_close, TestDevice};
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    #[test]
+    fn test_index_select_1d_repeated() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let r = t
+            .trace()
+            .index_select::<Axis<0>, _>(dev.tensor([2, 0, 2, 2]));
+        assert_eq!(r.array(), [3.0, 1.0, 3.0, 3.0]);
+        let g = r.exp().sum().backward();
+        assert_close(&g.get(&t).array(), &[1f32.exp(), 0.0, 3.0 * 3f32.exp()]);
+    }
+
+    #[test]
+    fn test_index_select_3d_each_axis() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let ta = t.array();
+
+        let r = t.clone().index_select::<Axis<0>, _>(dev.tensor([1, 0]));
+        assert_eq!(r.array(), [ta[1], ta[0]]);
+
+        let r = t.clone().index_select::<Axis<1>, _>(dev.tensor([2]));
+        assert_eq!(r.array(), [[ta[0][2]], [ta[1][2]]]);
+
+        let g = t
+            .trace()
+            .index_select::<Axis<2>, _>(dev.tensor([3, 3, 1]))
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [[[0.0, 1.0, 0.0, 2.0]; 3]; 2]);
+    }
+
+    #[test]
+    fn test_index_select_chained_axes() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        let r = t
+            .trace()
+            .index_select::<Axis<0>, _>(dev.tensor([2, 0]))
+            .index_select::<Axis<1>, _>(dev.tensor([1, 1]));
+        assert_eq!(r.array(), [[8.0, 8.0], [2.0, 2.0]]);
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.0, 2.0, 0.0], [0.0, 0.0, 0.0], [0.0, 2.0, 0.0]]
+        );
+    }
+
+    #[test]
+    fn test_index_select_broadcasted_input() {
+        let dev: TestDevice = Default::default();
+        let v = dev.tensor([1.0, 2.0, 3.0]);
+        let r = v
+            .trace()
+            .broadcast::<Rank2<4, 3>, _>()
+            .index_select::<Axis<1>, _>(dev.tensor([0, 0]));
+        assert_eq!(r.array(), [[1.0, 1.0]; 4]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&v).array(), [8.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_index_select_batched() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 2>, f32, _> = dev.sample_normal();
+        let ta = t.array();
+        let r = t
+            .trace()
+            .index_select::<Axis<1>, _>(dev.tensor([[2, 2], [0, 1]]));
+        assert_eq!(r.array(), [[ta[0][2], ta[0][2]], [ta[1][0], ta[1][1]]]);
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [
+                [[0.0; 2], [0.0; 2], [2.0; 2]],
+                [[1.0; 2], [1.0; 2], [0.0; 2]]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_index_select_broadcasted_indices() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 2, 3>, f32, _> = dev.sample_normal();
+        let idx: Tensor<Rank1<2>, usize, _> = dev.tensor([2, 0]);
+
+        // missing batch dims & broadcasted batch dims select the same entries
+        let r1 = t.clone().index_select::<Axis<2>, _>(idx.clone());
+        let r2 = t
+            .clone()
+            .index_select::<Axis<2>, _>(idx.clone().broadcast::<Rank2<2, 2>, Axis<0>>());
+        let r3 = t.index_select::<Axis<2>, _>(idx.broadcast::<Rank3<2, 2, 2>, Axes2<0, 1>>());
+        assert_eq!(r1.array(), r2.array());
+        assert_eq!(r1.array(), r3.array());
+    }
+
+    #[test]
+    fn test_index_select_axes_tuple() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        let r = t
+            .trace()
+            .index_select::<Axes2<0, 1>, _>((dev.tensor([2, 0]), dev.tensor([[0, 1], [2, 2]])));
+        assert_eq!(r.array(), [[7.0, 8.0], [3.0, 3.0]]);
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.0, 0.0, 2.0], [0.0, 0.0, 0.0], [1.0, 1.0, 0.0]]
+        );
+    }
+
+    #[test]
+    #[should_panic = "Batch dim 0 of the index tensor does not match dim 0 of the tensor"]
+    fn test_index_select_batch_mismatch() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let idx: Tensor<(usize, Const<1>), usize, _> = dev.zeros_like(&(3, Const));
+        let _ = t.index_select::<Axis<1>, _>(idx);
+    }
+
+    #[test]
+    #[should_panic = "Index 3 is out of bounds for axis 0 with size 3"]
+    fn test_index_select_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let _ = t.index_select::<Axis<0>, _>(dev.tensor([3]));
+    }
+}
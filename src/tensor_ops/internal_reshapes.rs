@@ -1,19 +1,15 @@
-#[cfg(feature = "cuda")]
 use crate::prelude::Axes;
-#[cfg(feature = "cuda")]
 use std::vec::Vec;
 
-/// Moves all axes in Ax to the end of dims and strides and removes broadcasted dimensions
-/// so that a cuda kernel called for each physical element of the input tensor will place elements
-/// to be reduced with each other next to each other in memory.
-#[cfg(feature = "cuda")]
-pub(super) fn permute_for_reductions<I, Ax: Axes>(dims: I, strides: I) -> (Vec<usize>, Vec<usize>)
+/// Moves all axes in Ax to the end of dims and strides, keeping the order of the other axes,
+/// so that the elements reduced with each other are next to each other in row major order.
+pub(super) fn permute_axes_last<I, Ax: Axes>(dims: I, strides: I) -> (Vec<usize>, Vec<usize>)
 where
     I: IntoIterator<Item = usize>,
 {
     let mut tmp = dims
         .into_iter()
-        .zip(strides.into_iter())
+        .zip(strides)
         .map(|x| (false, x))
         .collect::<Vec<_>>();
 
@@ -24,8 +20,39 @@ where
     // requires stable sorting to keep non-summed axes in the correct order
     tmp.sort_by_key(|x| x.0);
 
-    tmp.into_iter()
-        .map(|(_, x)| x)
+    tmp.into_iter().map(|(_, x)| x).unzip()
+}
+
+/// The number of elements reduced with each other, given the dims permuted by [permute_axes_last].
+pub(super) fn reduced_len<Ax: Axes>(permuted_dims: &[usize]) -> usize {
+    let num_reduced = Ax::as_array().into_iter().count();
+    permuted_dims[permuted_dims.len() - num_reduced..]
+        .iter()
+        .product()
+}
+
+/// Moves all axes in Ax to the end of dims and strides and removes broadcasted dimensions
+/// so that a cuda kernel called for each physical element of the input tensor will place elements
+/// to be reduced with each other next to each other in memory.
+#[cfg(feature = "cuda")]
+pub(super) fn permute_for_reductions<I, Ax: Axes>(dims: I, strides: I) -> (Vec<usize>, Vec<usize>)
+where
+    I: IntoIterator<Item = usize>,
+{
+    let (dims, strides) = permute_axes_last::<I, Ax>(dims, strides);
+    dims.into_iter()
+        .zip(strides)
         .filter(|(_, stride)| *stride != 0)
         .unzip()
 }
+
+/// The index into the physical buffer of the `i`th element (in row major order)
+/// of an array with `dims` and `strides`.
+pub(super) fn strided_index(mut i: usize, dims: &[usize], strides: &[usize]) -> usize {
+    let mut idx = 0;
+    for (dim, stride) in dims.iter().zip(strides.iter()).rev() {
+        idx += (i % dim) * stride;
+        i /= dim;
+    }
+    idx
+}
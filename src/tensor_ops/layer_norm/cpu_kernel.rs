@@ -0,0 +1,118 @@
+use crate::{
+    shapes::*,
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::internal_reshapes::strided_index,
+};
+use std::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl super::LayerNormKernel<f32> for Cpu {
+    fn forward<S: Shape, G: Shape>(
+        &self,
+        inp: &Self::Storage<S, f32>,
+        gamma: &Self::Storage<G, f32>,
+        beta: &Self::Storage<G, f32>,
+        epsilon: f32,
+    ) -> Result<
+        (
+            Self::Storage<S, f32>,
+            Self::Storage<(usize,), f32>,
+            Self::Storage<(usize,), f32>,
+        ),
+        Self::Err,
+    > {
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let strides: Vec<usize> = inp.strides.into();
+        let g_dims: Vec<usize> = gamma.shape.concrete().into();
+        let g_strides: Vec<usize> = gamma.strides.into();
+        let b_strides: Vec<usize> = beta.strides.into();
+        let row_len = dims.last().copied().unwrap_or(1);
+        let num_rows = inp.shape.num_elements() / row_len.max(1);
+        debug_assert_eq!(gamma.shape.num_elements(), row_len);
+
+        let mut out = StridedArray::new(inp.shape)?;
+        let mut mean = StridedArray::new((num_rows,))?;
+        let mut rstd = StridedArray::new((num_rows,))?;
+        {
+            let out_data = out.data_mut();
+            let mean_data = mean.data_mut();
+            let rstd_data = rstd.data_mut();
+            for row in 0..num_rows {
+                let x = |j: usize| inp.data[strided_index(row * row_len + j, &dims, &strides)];
+
+                // welford's algorithm for the mean & variance in a single pass
+                let mut m = 0.0;
+                let mut m2 = 0.0;
+                for j in 0..row_len {
+                    let delta = x(j) - m;
+                    m += delta / (j + 1) as f32;
+                    m2 += delta * (x(j) - m);
+                }
+                let r = (m2 / row_len as f32 + epsilon).powf(-0.5);
+                mean_data[row] = m;
+                rstd_data[row] = r;
+
+                for j in 0..row_len {
+                    let g = gamma.data[strided_index(j, &g_dims, &g_strides)];
+                    let b = beta.data[strided_index(j, &g_dims, &b_strides)];
+                    out_data[row * row_len + j] = (x(j) - m) * r * g + b;
+                }
+            }
+        }
+        Ok((out, mean, rstd))
+    }
+
+    fn backward<S: Shape, G: Shape>(
+        &self,
+        inp: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        gamma: &Self::Storage<G, f32>,
+        grad_gamma: &mut Self::Storage<G, f32>,
+        grad_beta: &mut Self::Storage<G, f32>,
+        mean: &Self::Storage<(usize,), f32>,
+        rstd: &Self::Storage<(usize,), f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let strides: Vec<usize> = inp.strides.into();
+        let gi_strides: Vec<usize> = grad_inp.strides.into();
+        let go_strides: Vec<usize> = grad_out.strides.into();
+        let g_dims: Vec<usize> = gamma.shape.concrete().into();
+        let g_strides: Vec<usize> = gamma.strides.into();
+        let gg_strides: Vec<usize> = grad_gamma.strides.into();
+        let gb_strides: Vec<usize> = grad_beta.strides.into();
+        let row_len = dims.last().copied().unwrap_or(1);
+        let num_rows = mean.shape.0;
+
+        let grad_inp = grad_inp.data_mut();
+        let grad_gamma = grad_gamma.data_mut();
+        let grad_beta = grad_beta.data_mut();
+        for row in 0..num_rows {
+            let (m, r) = (mean.data[row], rstd.data[row]);
+            let x_hat =
+                |j: usize| (inp.data[strided_index(row * row_len + j, &dims, &strides)] - m) * r;
+            let go = |j: usize| grad_out.data[strided_index(row * row_len + j, &dims, &go_strides)];
+            let g = |j: usize| go(j) * gamma.data[strided_index(j, &g_dims, &g_strides)];
+
+            let mut g_mean = 0.0;
+            let mut gx_mean = 0.0;
+            for j in 0..row_len {
+                g_mean += g(j);
+                gx_mean += g(j) * x_hat(j);
+                grad_beta[strided_index(j, &g_dims, &gb_strides)] += go(j);
+                grad_gamma[strided_index(j, &g_dims, &gg_strides)] += go(j) * x_hat(j);
+            }
+            g_mean /= row_len as f32;
+            gx_mean /= row_len as f32;
+
+            // grad_inp += rstd * (g - mean(g) - x_hat * mean(g * x_hat)), g = grad_out * gamma
+            for j in 0..row_len {
+                let i = strided_index(row * row_len + j, &dims, &gi_strides);
+                grad_inp[i] += r * (g(j) - g_mean - x_hat(j) * gx_mean);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    shapes::*,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/layer_norm.ptx"));
+const MODULE_NAME: &str = "layer_norm";
+const FWD_FN_NAME: &str = "layer_norm_forward";
+const BWD_FN_NAME: &str = "layer_norm_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl super::LayerNormKernel<f32> for Cuda {
+    fn forward<S: Shape, G: Shape>(
+        &self,
+        inp: &Self::Storage<S, f32>,
+        gamma: &Self::Storage<G, f32>,
+        beta: &Self::Storage<G, f32>,
+        epsilon: f32,
+    ) -> Result<
+        (
+            Self::Storage<S, f32>,
+            Self::Storage<(usize,), f32>,
+            Self::Storage<(usize,), f32>,
+        ),
+        Self::Err,
+    > {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let mut info: Vec<usize> = Vec::with_capacity(2 * S::NUM_DIMS);
+        info.extend(inp.shape.concrete());
+        info.extend(inp.strides);
+        let mut g_info: Vec<usize> = Vec::with_capacity(3 * G::NUM_DIMS);
+        g_info.extend(gamma.shape.concrete());
+        g_info.extend(gamma.strides);
+        g_info.extend(beta.strides);
+        let info = self.dev.take_async(info)?;
+        let g_info = self.dev.take_async(g_info)?;
+
+        let numel = inp.shape.num_elements();
+        let row_len = inp.shape.concrete().into_iter().last().unwrap_or(1);
+        let num_rows = numel / row_len.max(1);
+        let mut out = self.dev.alloc_zeros_async::<f32>(numel)?;
+        let mut mean = self.dev.alloc_zeros_async::<f32>(num_rows)?;
+        let mut rstd = self.dev.alloc_zeros_async::<f32>(num_rows)?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            S::NUM_DIMS,         // const size_t num_dims,
+            &info,               // const size_t *info,
+            G::NUM_DIMS,         // const size_t g_num_dims,
+            &g_info,             // const size_t *g_info,
+            epsilon,             // const float epsilon,
+            inp.data.as_ref(),   // const float *inp,
+            gamma.data.as_ref(), // const float *gamma,
+            beta.data.as_ref(),  // const float *beta,
+            &mut out,            // float *out,
+            &mut mean,           // float *mean,
+            &mut rstd,           // float *rstd
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+
+        let out = CudaArray {
+            data: Arc::new(out),
+            shape: inp.shape,
+            strides: inp.shape.strides(),
+        };
+        let mean = CudaArray {
+            data: Arc::new(mean),
+            shape: (num_rows,),
+            strides: [1],
+        };
+        let rstd = CudaArray {
+            data: Arc::new(rstd),
+            shape: (num_rows,),
+            strides: [1],
+        };
+        Ok((out, mean, rstd))
+    }
+
+    fn backward<S: Shape, G: Shape>(
+        &self,
+        inp: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        gamma: &Self::Storage<G, f32>,
+        grad_gamma: &mut Self::Storage<G, f32>,
+        grad_beta: &mut Self::Storage<G, f32>,
+        mean: &Self::Storage<(usize,), f32>,
+        rstd: &Self::Storage<(usize,), f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut info: Vec<usize> = Vec::with_capacity(4 * S::NUM_DIMS);
+        info.extend(inp.shape.concrete());
+        info.extend(inp.strides);
+        info.extend(grad_inp.strides);
+        info.extend(grad_out.strides);
+        let mut g_info: Vec<usize> = Vec::with_capacity(4 * G::NUM_DIMS);
+        g_info.extend(gamma.shape.concrete());
+        g_info.extend(gamma.strides);
+        g_info.extend(grad_gamma.strides);
+        g_info.extend(grad_beta.strides);
+        let info = self.dev.take_async(info)?;
+        let g_info = self.dev.take_async(g_info)?;
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(mean.shape.0 as u32);
+        let params = (
+            S::NUM_DIMS,                         // const size_t num_dims,
+            &info,                               // const size_t *info,
+            G::NUM_DIMS,                         // const size_t g_num_dims,
+            &g_info,                             // const size_t *g_info,
+            inp.data.as_ref(),                   // const float *inp,
+            Arc::make_mut(&mut grad_inp.data),   // float *grad_inp,
+            gamma.data.as_ref(),                 // const float *gamma,
+            Arc::make_mut(&mut grad_gamma.data), // float *grad_gamma,
+            Arc::make_mut(&mut grad_beta.data),  // float *grad_beta,
+            mean.data.as_ref(),                  // const float *mean,
+            rstd.data.as_ref(),                  // const float *rstd,
+            grad_out.data.as_ref(),              // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
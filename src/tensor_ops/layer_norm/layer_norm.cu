@@ -0,0 +1,129 @@
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+// The number of rows along the last axis of an array with `dims`.
+__device__ size_t num_rows(const size_t num_dims, const size_t *dims) {
+    size_t n = 1;
+    for (unsigned int d = 0; d + 1 < num_dims; d++) {
+        n *= dims[d];
+    }
+    return n;
+}
+
+// `info` holds the dims and then the strides of `inp`,
+// `g_info` holds the dims and then the strides of `gamma` and `beta`.
+// One thread handles one row.
+extern "C" __global__ void layer_norm_forward(
+    const size_t num_dims,
+    const size_t *info,
+    const size_t g_num_dims,
+    const size_t *g_info,
+    const float epsilon,
+    const float *inp,
+    const float *gamma,
+    const float *beta,
+    float *out,
+    float *mean,
+    float *rstd
+) {
+    const size_t row_len = info[num_dims - 1];
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows(num_dims, info)) {
+        return;
+    }
+    const size_t *dims = info;
+    const size_t *strides = info + num_dims;
+    const size_t *g_dims = g_info;
+    const size_t *g_strides = g_info + g_num_dims;
+    const size_t *b_strides = g_info + 2 * g_num_dims;
+
+    // welford's algorithm for the mean & variance in a single pass
+    float m = 0.0;
+    float m2 = 0.0;
+    for (unsigned int j = 0; j < row_len; j++) {
+        float x = inp[get_strided_index(row * row_len + j, num_dims, dims, strides)];
+        float delta = x - m;
+        m += delta / (j + 1);
+        m2 += delta * (x - m);
+    }
+    float r = rsqrtf(m2 / row_len + epsilon);
+    mean[row] = m;
+    rstd[row] = r;
+
+    for (unsigned int j = 0; j < row_len; j++) {
+        float x = inp[get_strided_index(row * row_len + j, num_dims, dims, strides)];
+        float g = gamma[get_strided_index(j, g_num_dims, g_dims, g_strides)];
+        float b = beta[get_strided_index(j, g_num_dims, g_dims, b_strides)];
+        out[row * row_len + j] = (x - m) * r * g + b;
+    }
+}
+
+// `info` holds the dims and then the strides of `inp`, `grad_inp` and `grad_out`,
+// `g_info` holds the dims and then the strides of `gamma`, `grad_gamma` and `grad_beta`.
+// One thread handles one row.
+extern "C" __global__ void layer_norm_backward(
+    const size_t num_dims,
+    const size_t *info,
+    const size_t g_num_dims,
+    const size_t *g_info,
+    const float *inp,
+    float *grad_inp,
+    const float *gamma,
+    float *grad_gamma,
+    float *grad_beta,
+    const float *mean,
+    const float *rstd,
+    const float *grad_out
+) {
+    const size_t row_len = info[num_dims - 1];
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows(num_dims, info)) {
+        return;
+    }
+    const size_t *dims = info;
+    const size_t *strides = info + num_dims;
+    const size_t *gi_strides = info + 2 * num_dims;
+    const size_t *go_strides = info + 3 * num_dims;
+    const size_t *g_dims = g_info;
+    const size_t *g_strides = g_info + g_num_dims;
+    const size_t *gg_strides = g_info + 2 * g_num_dims;
+    const size_t *gb_strides = g_info + 3 * g_num_dims;
+    const float m = mean[row];
+    const float r = rstd[row];
+
+    float g_mean = 0.0;
+    float gx_mean = 0.0;
+    for (unsigned int j = 0; j < row_len; j++) {
+        unsigned int i = row * row_len + j;
+        float x_hat = (inp[get_strided_index(i, num_dims, dims, strides)] - m) * r;
+        float go = grad_out[get_strided_index(i, num_dims, dims, go_strides)];
+        float g = go * gamma[get_strided_index(j, g_num_dims, g_dims, g_strides)];
+        g_mean += g;
+        gx_mean += g * x_hat;
+        atomicAdd(grad_beta + get_strided_index(j, g_num_dims, g_dims, gb_strides), go);
+        atomicAdd(grad_gamma + get_strided_index(j, g_num_dims, g_dims, gg_strides), go * x_hat);
+    }
+    g_mean /= row_len;
+    gx_mean /= row_len;
+
+    // grad_inp += rstd * (g - mean(g) - x_hat * mean(g * x_hat)), g = grad_out * gamma
+    for (unsigned int j = 0; j < row_len; j++) {
+        unsigned int i = row * row_len + j;
+        float x_hat = (inp[get_strided_index(i, num_dims, dims, strides)] - m) * r;
+        float go = grad_out[get_strided_index(i, num_dims, dims, go_strides)];
+        float g = go * gamma[get_strided_index(j, g_num_dims, g_dims, g_strides)];
+        atomicAdd(grad_inp + get_strided_index(i, num_dims, dims, gi_strides), r * (g - g_mean - x_hat * gx_mean));
+    }
+}
@@ -0,0 +1,221 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::Device;
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
+};
+
+pub trait LayerNormKernel<E: Dtype>: DeviceStorage {
+    /// Normalizes each row of `inp` along its last axis and applies `gamma` and `beta`.
+    /// Returns the output, and the mean and reciprocal standard deviation of each row.
+    #[allow(clippy::type_complexity)]
+    fn forward<S: Shape, G: Shape>(
+        &self,
+        inp: &Self::Storage<S, E>,
+        gamma: &Self::Storage<G, E>,
+        beta: &Self::Storage<G, E>,
+        epsilon: E,
+    ) -> Result<
+        (
+            Self::Storage<S, E>,
+            Self::Storage<(usize,), E>,
+            Self::Storage<(usize,), E>,
+        ),
+        Self::Err,
+    >;
+
+    /// Accumulates the gradients of `inp`, `gamma` and `beta`, given the `mean` and `rstd`
+    /// of each row from the forward pass.
+    #[allow(clippy::too_many_arguments)]
+    fn backward<S: Shape, G: Shape>(
+        &self,
+        inp: &Self::Storage<S, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        gamma: &Self::Storage<G, E>,
+        grad_gamma: &mut Self::Storage<G, E>,
+        grad_beta: &mut Self::Storage<G, E>,
+        mean: &Self::Storage<(usize,), E>,
+        rstd: &Self::Storage<(usize,), E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Layer normalization with an affine transform, computed as a single operation on the tape.
+/// Normalizes `t` along its last axis and then computes `t_hat * gamma + beta`, where
+/// `gamma` and `beta` are broadcasted along `Ax` (all the axes except the last one).
+///
+/// This is equivalent to `t.normalize(epsilon) * gamma.broadcast() + beta.broadcast()`,
+/// but the forward is a single kernel that only keeps the mean and reciprocal standard
+/// deviation of each row around, and the backward computes the gradients of `t`, `gamma`
+/// and `beta` with a single kernel.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.layer_norm(t, (M,), gamma, beta, epsilon)`
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 3>> = dev.zeros();
+/// let gamma: Tensor<Rank1<3>> = dev.ones();
+/// let beta: Tensor<Rank1<3>> = dev.zeros();
+/// let _ = t.layer_norm::<_, Axis<0>>(&gamma, &beta, 1e-5);
+/// ```
+pub fn layer_norm<S, G, Ax, D, T>(
+    t: Tensor<S, f32, D, T>,
+    gamma: &Tensor<G, f32, D>,
+    beta: &Tensor<G, f32, D>,
+    epsilon: f32,
+) -> Tensor<S, f32, D, T>
+where
+    S: ReduceShape<<S as Shape>::LastAxis> + ReduceShape<Ax, Reduced = G>,
+    G: Shape,
+    Ax: Axes,
+    D: Device<f32>,
+    T: Tape<D>,
+{
+    t.layer_norm::<G, Ax>(gamma, beta, epsilon)
+}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> Tensor<S, f32, D, T> {
+    /// See [layer_norm]
+    pub fn layer_norm<G: Shape, Ax: Axes>(
+        self,
+        gamma: &Tensor<G, f32, D>,
+        beta: &Tensor<G, f32, D>,
+        epsilon: f32,
+    ) -> Self
+    where
+        S: ReduceShape<<S as Shape>::LastAxis> + ReduceShape<Ax, Reduced = G>,
+    {
+        self.try_layer_norm::<G, Ax>(gamma, beta, epsilon).unwrap()
+    }
+
+    /// See [layer_norm]
+    pub fn try_layer_norm<G: Shape, Ax: Axes>(
+        self,
+        gamma: &Tensor<G, f32, D>,
+        beta: &Tensor<G, f32, D>,
+        epsilon: f32,
+    ) -> Result<Self, <Self as HasErr>::Err>
+    where
+        S: ReduceShape<<S as Shape>::LastAxis> + ReduceShape<Ax, Reduced = G>,
+    {
+        let (inp, mut tape) = self.split_tape();
+        let gamma = gamma.clone();
+        let beta = beta.clone();
+        let (out, mean, rstd) = LayerNormKernel::forward(
+            &inp.device,
+            &inp.storage,
+            &gamma.storage,
+            &beta.storage,
+            epsilon,
+        )?;
+        let out = inp.device.upgrade(out);
+
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&gamma)?;
+        tape.try_alloc_grad(&beta)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let mut grad_gamma = grads.remove(&gamma).unwrap();
+            let mut grad_beta = grads.remove(&beta).unwrap();
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            LayerNormKernel::backward(
+                &inp.device,
+                &inp.storage,
+                grad_inp,
+                &gamma.storage,
+                &mut grad_gamma,
+                &mut grad_beta,
+                &mean,
+                &rstd,
+                grad_out,
+            )?;
+            grads.insert(&gamma, grad_gamma);
+            grads.insert(&beta, grad_beta);
+            Ok(())
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{assert_close, assert_close_with_tolerance, TestDevice};
+    use crate::{gradients::OwnedTape, shapes::*, tensor::*, tensor_ops::*};
+
+    #[test]
+    fn test_layer_norm_matches_unfused() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank3<2, 3, 5>, f32, _> = dev.sample_normal();
+        let gamma: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let beta: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+
+        let fused = x.trace().layer_norm::<_, Axes2<0, 1>>(&gamma, &beta, 1e-5);
+        let unfused = x.trace().normalize::<Axis<2>>(1e-5)
+            * gamma.retaped::<OwnedTape<_>>().broadcast()
+            + beta.retaped::<OwnedTape<_>>().broadcast();
+        assert_close(&fused.array(), &unfused.array());
+
+        let g1 = fused.exp().mean().backward();
+        let g2 = unfused.exp().mean().backward();
+        assert_close_with_tolerance(&g1.get(&x).array(), &g2.get(&x).array(), 1e-4);
+        assert_close_with_tolerance(&g1.get(&gamma).array(), &g2.get(&gamma).array(), 1e-4);
+        assert_close_with_tolerance(&g1.get(&beta).array(), &g2.get(&beta).array(), 1e-4);
+    }
+
+    #[test]
+    fn test_layer_norm_broadcasted_input() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+        let gamma: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+        let beta: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+
+        let fused = x
+            .trace()
+            .broadcast::<Rank2<3, 4>, _>()
+            .layer_norm::<_, Axis<0>>(&gamma, &beta, 1e-5);
+        let unfused = x
+            .trace()
+            .broadcast::<Rank2<3, 4>, _>()
+            .normalize::<Axis<1>>(1e-5)
+            * gamma.retaped::<OwnedTape<_>>().broadcast()
+            + beta.retaped::<OwnedTape<_>>().broadcast();
+        assert_close(&fused.array(), &unfused.array());
+
+        let g1 = fused.exp().mean().backward();
+        let g2 = unfused.exp().mean().backward();
+        assert_close_with_tolerance(&g1.get(&x).array(), &g2.get(&x).array(), 1e-4);
+        assert_close_with_tolerance(&g1.get(&gamma).array(), &g2.get(&gamma).array(), 1e-4);
+    }
+
+    #[test]
+    fn test_layer_norm_2d() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[-2.0, 0.0, 5.0], [1.0, 2.0, 3.0]]);
+        let gamma = dev.tensor([1.0, 2.0, -1.0]);
+        let beta = dev.tensor([0.0, 1.0, 0.5]);
+        let r = x
+            .trace_with_summary()
+            .layer_norm::<_, Axis<0>>(&gamma, &beta, 1e-5);
+        // the whole op is a single node on the tape
+        let (r, tape) = r.split_tape();
+        assert_eq!(tape.summary().ops.len(), 1);
+        let r = r.put_tape(tape);
+        assert_close(
+            &r.array(),
+            &[
+                [-1.0190487, 0.3206342, -0.8587316],
+                [-1.2247356, 1.0, -0.7247356],
+            ],
+        );
+        let g = r.sum().backward();
+        assert_close(&g.get(&beta).array(), &[2.0; 3]);
+        assert_close(&g.get(&gamma).array(), &[-2.2437843, -0.3396829, 2.5834672]);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+
+impl UnaryDerivative<f32> for super::LeakyReLUKernelOp<f32> {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        if *x > 0.0 {
+            *x
+        } else {
+            self.slope * x
+        }
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        if *x > 0.0 {
+            1.0
+        } else {
+            self.slope
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::LeakyReLUKernelOp<f32> {}
+
+impl UnaryOpCudaKernel for super::LeakyReLUKernelOp<f32> {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/leaky_relu.ptx"));
+    const MODULE_NAME: &'static str = "leaky_relu";
+    const FWD_FN_NAME: &'static str = "leaky_relu_forward";
+    const BWD_FN_NAME: &'static str = "leaky_relu_backward";
+}
@@ -0,0 +1,33 @@
+struct LeakyReLUKernelOp {
+    float slope;
+};
+
+extern "C" __global__ void leaky_relu_forward(
+    const LeakyReLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    out[i] = x > 0.0 ? x : op.slope * x;
+}
+
+extern "C" __global__ void leaky_relu_backward(
+    const LeakyReLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float dx = x > 0.0 ? 1.0 : op.slope;
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,58 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LeakyReLUKernelOp<E> {
+    pub slope: E,
+}
+
+/// [Leaky ReLU](https://paperswithcode.com/method/leaky-relu). `t` for positive values, and `slope * t` otherwise.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.leaky_relu(t, slope)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.leaky_relu(0.1);
+/// assert_eq!(r.array(), [-0.1, 0.0, 1.0, 2.0]);
+/// ```
+pub fn leaky_relu<S: Shape, E: Dtype, D: UnaryKernel<LeakyReLUKernelOp<E>, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    slope: E,
+) -> Tensor<S, E, D, T> {
+    t.leaky_relu(slope)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<LeakyReLUKernelOp<E>, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [leaky_relu]
+    pub fn leaky_relu(self, slope: E) -> Self {
+        self.try_leaky_relu(slope).unwrap()
+    }
+    /// See [leaky_relu]
+    pub fn try_leaky_relu(self, slope: E) -> Result<Self, D::Err> {
+        try_unary_op(LeakyReLUKernelOp { slope }, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_leaky_relu() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.5, 0.0, 1.0, 3.0]);
+        let r = x.trace().leaky_relu(0.1);
+        assert_close(&r.array(), &[-0.2, -0.05, 0.0, 1.0, 3.0]);
+        let g = r.mean().backward();
+        assert_close(&g.get(&x).array(), &[0.02, 0.02, 0.02, 0.2, 0.2]);
+    }
+}
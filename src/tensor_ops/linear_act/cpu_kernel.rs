@@ -0,0 +1,61 @@
+use crate::{
+    shapes::*,
+    tensor::{
+        cpu::{Cpu, StridedArray},
+        AsVec,
+    },
+    tensor_ops::cpu_kernels::UnaryDerivative,
+};
+
+impl<Op: UnaryDerivative<f32>> super::LinearActKernel<Op, f32> for Cpu {
+    fn forward<B: Dim, const I: usize, const O: usize>(
+        &self,
+        op: Op,
+        x: &Self::Storage<(B, Const<I>), f32>,
+        weight: &Self::Storage<Rank2<O, I>, f32>,
+        bias: &Self::Storage<Rank1<O>, f32>,
+    ) -> Result<
+        (
+            Self::Storage<(B, Const<O>), f32>,
+            Self::Storage<(B, Const<O>), f32>,
+        ),
+        Self::Err,
+    > {
+        // start from the bias, the matmul adds `x * weight^T` to it
+        let bias = bias.as_vec();
+        let mut z = StridedArray::new((x.shape.0, Const::<O>))?;
+        for row in z.data_mut().chunks_mut(O) {
+            row.copy_from_slice(&bias);
+        }
+        self.matmul(x.view(), weight.view().tr(), &mut z.view_mut());
+
+        let mut out = z.clone();
+        for v in out.buf_iter_mut() {
+            *v = op.f(v);
+        }
+        Ok((z, out))
+    }
+
+    fn backward<B: Dim, const I: usize, const O: usize>(
+        &self,
+        op: Op,
+        x: &Self::Storage<(B, Const<I>), f32>,
+        grad_x: &mut Self::Storage<(B, Const<I>), f32>,
+        weight: &Self::Storage<Rank2<O, I>, f32>,
+        grad_weight: &mut Self::Storage<Rank2<O, I>, f32>,
+        grad_bias: &mut Self::Storage<Rank1<O>, f32>,
+        z: &Self::Storage<(B, Const<O>), f32>,
+        grad_out: &Self::Storage<(B, Const<O>), f32>,
+    ) -> Result<(), Self::Err> {
+        let mut grad_z = z.clone();
+        for (i, g) in grad_z.buf_iter_mut().enumerate() {
+            *g = op.df(&z.data[i]) * grad_out.data[i];
+        }
+        self.matmul(grad_z.view(), weight.view(), &mut grad_x.view_mut());
+        self.matmul(grad_z.view().tr(), x.view(), &mut grad_weight.view_mut());
+        for (o, g) in grad_bias.buf_iter_mut().enumerate() {
+            *g += grad_z.data.iter().skip(o).step_by(O).sum::<f32>();
+        }
+        Ok(())
+    }
+}
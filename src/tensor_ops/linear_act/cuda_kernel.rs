@@ -0,0 +1,68 @@
+use crate::{
+    shapes::*,
+    tensor::{Cuda, DeviceStorage},
+    tensor_ops::{
+        add::BinaryAddKernelOp,
+        broadcast_to::BroadcastKernel,
+        matmul::MatMatKernel,
+        ops::{BinaryKernel, UnaryKernel},
+        permute_to::PermuteKernel,
+    },
+};
+
+impl<Op> super::LinearActKernel<Op, f32> for Cuda
+where
+    Self: UnaryKernel<Op, f32>,
+{
+    fn forward<B: Dim, const I: usize, const O: usize>(
+        &self,
+        op: Op,
+        x: &Self::Storage<(B, Const<I>), f32>,
+        weight: &Self::Storage<Rank2<O, I>, f32>,
+        bias: &Self::Storage<Rank1<O>, f32>,
+    ) -> Result<
+        (
+            Self::Storage<(B, Const<O>), f32>,
+            Self::Storage<(B, Const<O>), f32>,
+        ),
+        Self::Err,
+    > {
+        let weight_t =
+            PermuteKernel::forward::<Rank2<O, I>, Rank2<I, O>, Axes2<1, 0>>(self, weight)?;
+        let xw = MatMatKernel::forward(self, x, &weight_t)?;
+        let bias = BroadcastKernel::forward::<Rank1<O>, (B, Const<O>), Axis<0>>(
+            self,
+            (x.shape.0, Const),
+            bias,
+        )?;
+        let z = BinaryKernel::forward(self, BinaryAddKernelOp, &bias, &xw)?;
+        let out = UnaryKernel::forward(self, op, &z)?;
+        Ok((z, out))
+    }
+
+    fn backward<B: Dim, const I: usize, const O: usize>(
+        &self,
+        op: Op,
+        x: &Self::Storage<(B, Const<I>), f32>,
+        grad_x: &mut Self::Storage<(B, Const<I>), f32>,
+        weight: &Self::Storage<Rank2<O, I>, f32>,
+        grad_weight: &mut Self::Storage<Rank2<O, I>, f32>,
+        grad_bias: &mut Self::Storage<Rank1<O>, f32>,
+        z: &Self::Storage<(B, Const<O>), f32>,
+        grad_out: &Self::Storage<(B, Const<O>), f32>,
+    ) -> Result<(), Self::Err> {
+        let mut grad_z = self.try_alloc_grad(z)?;
+        UnaryKernel::backward(self, op, z, &mut grad_z, grad_out)?;
+
+        let weight_t =
+            PermuteKernel::forward::<Rank2<O, I>, Rank2<I, O>, Axes2<1, 0>>(self, weight)?;
+        let mut grad_weight_t = self.try_alloc_grad(&weight_t)?;
+        MatMatKernel::backward(self, x, grad_x, &weight_t, &mut grad_weight_t, &grad_z)?;
+        PermuteKernel::backward::<Rank2<O, I>, Rank2<I, O>, Axes2<1, 0>>(
+            self,
+            grad_weight,
+            &grad_weight_t,
+        )?;
+        BroadcastKernel::backward::<Rank1<O>, (B, Const<O>), Axis<0>>(self, grad_bias, &grad_z)
+    }
+}
@@ -0,0 +1,246 @@
+#![allow(clippy::type_complexity)]
+
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::{gelu::GeLUKernelOp, relu::ReLUKernelOp, BroadcastTo, Device, SumTo};
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
+};
+
+pub trait LinearActKernel<Op, E: Dtype>: DeviceStorage {
+    /// Returns the pre-activations `x * weight^T + bias`, and the activations.
+    fn forward<B: Dim, const I: usize, const O: usize>(
+        &self,
+        op: Op,
+        x: &Self::Storage<(B, Const<I>), E>,
+        weight: &Self::Storage<Rank2<O, I>, E>,
+        bias: &Self::Storage<Rank1<O>, E>,
+    ) -> Result<
+        (
+            Self::Storage<(B, Const<O>), E>,
+            Self::Storage<(B, Const<O>), E>,
+        ),
+        Self::Err,
+    >;
+
+    /// Accumulates the gradients of `x`, `weight` and `bias`, given the pre-activations `z`.
+    #[allow(clippy::too_many_arguments)]
+    fn backward<B: Dim, const I: usize, const O: usize>(
+        &self,
+        op: Op,
+        x: &Self::Storage<(B, Const<I>), E>,
+        grad_x: &mut Self::Storage<(B, Const<I>), E>,
+        weight: &Self::Storage<Rank2<O, I>, E>,
+        grad_weight: &mut Self::Storage<Rank2<O, I>, E>,
+        grad_bias: &mut Self::Storage<Rank1<O>, E>,
+        z: &Self::Storage<(B, Const<O>), E>,
+        grad_out: &Self::Storage<(B, Const<O>), E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Computes `op(x * weight^T + bias)` with a single kernel. Only the pre-activation values are
+/// kept around for the backward pass, and the gradients of `x`, `weight`, and `bias` are all
+/// computed in one backward op.
+pub(crate) fn try_linear_act<Op, B: Dim, const I: usize, const O: usize, D, T>(
+    op: Op,
+    x: Tensor<(B, Const<I>), f32, D, T>,
+    weight: &Tensor<Rank2<O, I>, f32, D>,
+    bias: &Tensor<Rank1<O>, f32, D>,
+) -> Result<Tensor<(B, Const<O>), f32, D, T>, D::Err>
+where
+    Op: 'static + Clone,
+    D: LinearActKernel<Op, f32>,
+    T: Tape<D>,
+{
+    let (x, mut tape) = x.split_tape();
+    let weight = weight.clone();
+    let bias = bias.clone();
+    let (z, out) = LinearActKernel::forward(
+        &x.device,
+        op.clone(),
+        &x.storage,
+        &weight.storage,
+        &bias.storage,
+    )?;
+    let out = x.device.upgrade(out);
+
+    let phantom_out = out.clone();
+    tape.try_alloc_grad(&x)?;
+    tape.try_alloc_grad(&weight)?;
+    tape.try_alloc_grad(&bias)?;
+    tape.try_alloc_output_grad(&out)?;
+    tape.add_named_backward_op(core::any::type_name::<Op>(), move |grads| {
+        let mut grad_bias = grads.remove(&bias).unwrap();
+        let (grad_x, grad_weight, grad_out) = grads.muts_and_ref(&x, &weight, &phantom_out);
+        LinearActKernel::backward(
+            &x.device,
+            op,
+            &x.storage,
+            grad_x,
+            &weight.storage,
+            grad_weight,
+            &mut grad_bias,
+            &z,
+            grad_out,
+        )?;
+        grads.insert(&bias, grad_bias);
+        Ok(())
+    });
+    Ok(out.put_tape(tape))
+}
+
+impl<B: Dim, const I: usize, D: Device<f32>, T: Tape<D>> Tensor<(B, Const<I>), f32, D, T> {
+    /// Fused version of `relu(x.matmul(weight.permute()) + bias.broadcast())`. The bias and
+    /// activation are applied to the output of the matmul in place, and the intermediate
+    /// results are not recorded on the tape, which saves memory and time.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank2<10, 3>> = dev.zeros();
+    /// let weight: Tensor<Rank2<5, 3>> = dev.zeros();
+    /// let bias: Tensor<Rank1<5>> = dev.zeros();
+    /// let _: Tensor<Rank2<10, 5>> = x.linear_relu(&weight, &bias);
+    /// ```
+    pub fn linear_relu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Tensor<(B, Const<O>), f32, D, T> {
+        self.try_linear_relu(weight, bias).unwrap()
+    }
+
+    /// See [Tensor::linear_relu]
+    pub fn try_linear_relu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Tensor<(B, Const<O>), f32, D, T>, <Self as HasErr>::Err> {
+        try_linear_act(ReLUKernelOp, self, weight, bias)
+    }
+
+    /// Fused version of `gelu(x.matmul(weight.permute()) + bias.broadcast())`.
+    /// See [Tensor::linear_relu].
+    pub fn linear_gelu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Tensor<(B, Const<O>), f32, D, T> {
+        self.try_linear_gelu(weight, bias).unwrap()
+    }
+
+    /// See [Tensor::linear_gelu]
+    pub fn try_linear_gelu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Tensor<(B, Const<O>), f32, D, T>, <Self as HasErr>::Err> {
+        try_linear_act(GeLUKernelOp, self, weight, bias)
+    }
+}
+
+impl<const I: usize, D: Device<f32>, T: Tape<D>> Tensor<Rank1<I>, f32, D, T> {
+    /// Single item version of [Tensor::linear_relu]
+    pub fn linear_relu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Tensor<Rank1<O>, f32, D, T> {
+        self.try_linear_relu(weight, bias).unwrap()
+    }
+
+    /// See [Tensor::linear_relu]
+    pub fn try_linear_relu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Tensor<Rank1<O>, f32, D, T>, <Self as HasErr>::Err> {
+        self.try_broadcast::<Rank2<1, I>, _>()?
+            .try_linear_relu(weight, bias)?
+            .try_sum()
+    }
+
+    /// Single item version of [Tensor::linear_gelu]
+    pub fn linear_gelu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Tensor<Rank1<O>, f32, D, T> {
+        self.try_linear_gelu(weight, bias).unwrap()
+    }
+
+    /// See [Tensor::linear_gelu]
+    pub fn try_linear_gelu<const O: usize>(
+        self,
+        weight: &Tensor<Rank2<O, I>, f32, D>,
+        bias: &Tensor<Rank1<O>, f32, D>,
+    ) -> Result<Tensor<Rank1<O>, f32, D, T>, <Self as HasErr>::Err> {
+        self.try_broadcast::<Rank2<1, I>, _>()?
+            .try_linear_gelu(weight, bias)?
+            .try_sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gradients::OwnedTape, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_linear_relu_matches_unfused() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let w: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+
+        let fused = x.trace().linear_relu(&w, &b);
+        let unfused = (x.trace().matmul(w.retaped::<OwnedTape<_>>().permute())
+            + b.retaped::<OwnedTape<_>>().broadcast())
+        .relu();
+        assert_close(&fused.array(), &unfused.array());
+
+        let g1 = fused.square().mean().backward();
+        let g2 = unfused.square().mean().backward();
+        assert_close(&g1.get(&x).array(), &g2.get(&x).array());
+        assert_close(&g1.get(&w).array(), &g2.get(&w).array());
+        assert_close(&g1.get(&b).array(), &g2.get(&b).array());
+    }
+
+    #[test]
+    fn test_linear_gelu_matches_unfused() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let w: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+
+        let fused = x.trace().linear_gelu(&w, &b);
+        let unfused = (x.trace().matmul(w.retaped::<OwnedTape<_>>().permute())
+            + b.retaped::<OwnedTape<_>>().broadcast())
+        .gelu();
+        assert_close(&fused.array(), &unfused.array());
+
+        let g1 = fused.square().mean().backward();
+        let g2 = unfused.square().mean().backward();
+        assert_close(&g1.get(&x).array(), &g2.get(&x).array());
+        assert_close(&g1.get(&w).array(), &g2.get(&w).array());
+        assert_close(&g1.get(&b).array(), &g2.get(&b).array());
+    }
+
+    #[test]
+    fn test_linear_relu_1d() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([1.0, -2.0]);
+        let w = dev.tensor([[1.0, 1.0], [1.0, -1.0], [0.5, 0.0]]);
+        let b = dev.tensor([0.0, 0.0, -1.0]);
+        let r = x.trace().linear_relu(&w, &b);
+        assert_eq!(r.array(), [0.0, 3.0, 0.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [1.0, -1.0]);
+        assert_eq!(g.get(&w).array(), [[0.0, 0.0], [1.0, -2.0], [0.0, 0.0]]);
+        assert_eq!(g.get(&b).array(), [0.0, 1.0, 0.0]);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl UnaryDerivative<f32> for super::Log1pKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        x.ln_1p()
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        1.0 / (1.0 + x)
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::Log1pKernelOp {}
+
+impl UnaryOpCudaKernel for super::Log1pKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/log1p.ptx"));
+    const MODULE_NAME: &'static str = "log1p";
+    const FWD_FN_NAME: &'static str = "log1p_forward";
+    const BWD_FN_NAME: &'static str = "log1p_backward";
+}
@@ -0,0 +1,29 @@
+struct Log1pKernelOp {};
+
+extern "C" __global__ void log1p_forward(
+    const Log1pKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    out[i] = log1pf(inp[i]);
+}
+
+extern "C" __global__ void log1p_backward(
+    const Log1pKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float dx = 1.0 / (1.0 + inp[i]);
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,56 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Log1pKernelOp;
+
+/// `ln(1 + t)`, which is more accurate than `(t + 1).ln()` for `t` close to 0.
+///
+/// The derivative is `1 / (1 + t)`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-0.5, 0.0, 1e-8, 1.0]);
+/// let r = t.log1p();
+/// assert_eq!(r.array()[2], 1e-8);
+/// ```
+pub fn log1p<S: Shape, E: Dtype, D: UnaryKernel<Log1pKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.log1p()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<Log1pKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [log1p]
+    pub fn log1p(self) -> Self {
+        self.try_log1p().unwrap()
+    }
+    /// See [log1p]
+    pub fn try_log1p(self) -> Result<Self, D::Err> {
+        try_unary_op(Log1pKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_log1p() {
+        use std::f32::consts::LN_2;
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-0.5, 0.0, 1e-8, 1.0]);
+        let r = x.trace().log1p();
+        assert_close(&r.array(), &[-LN_2, 0.0, 1e-8, LN_2]);
+        let g = r.mean().backward();
+        assert_close(&g.get(&x).array(), &[0.5, 0.25, 0.25, 0.125]);
+    }
+}
@@ -1,7 +1,12 @@
-use super::{BroadcastTo, Device, LogSumExpTo, TrySub};
-use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+use super::{softmax::SoftmaxKernel, Device};
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{PutTape, SplitTape, Tensor},
+};
 
-/// `log(softmax(t))` in numerically stable way across `Ax`. Does `t - logsumexp(t)` under the hood.
+/// `log(softmax(t))` in numerically stable way across `Ax`. Computes `t - logsumexp(t)`
+/// with a single kernel, and the backward pass is a single kernel as well.
 ///
 /// **Pytorch equivalent**: `t.log_softmax(Ax)`
 ///
@@ -42,9 +47,24 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
     where
         S: ReduceShape<Ax>,
     {
-        let logsumexp = self.retaped::<T>().try_logsumexp::<S::Reduced, Ax>()?;
-        let logsumexp = logsumexp.try_broadcast_like(self.shape())?;
-        self.try_sub(logsumexp)
+        let (inp, mut tape) = self.split_tape();
+        let out = SoftmaxKernel::forward::<S, Ax>(&inp.device, true, &inp.storage)?;
+        let out = inp.device.upgrade(out);
+
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            SoftmaxKernel::backward::<S, Ax>(
+                &inp.device,
+                true,
+                &phantom_out.storage,
+                grad_inp,
+                grad_out,
+            )
+        });
+        Ok(out.put_tape(tape))
     }
 }
 
@@ -95,4 +115,26 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_log_softmax_large_logits_0th_axis() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1000.0, -1000.0], [1001.0, -999.0]]);
+        let r = a.trace_with_summary().log_softmax::<Axis<0>>();
+        // the whole op is a single node on the tape
+        let (r, tape) = r.split_tape();
+        assert_eq!(tape.summary().ops.len(), 1);
+        let r = r.put_tape(tape);
+        assert_close_with_tolerance(
+            &r.array(),
+            &[[-1.3132616, -1.3132616], [-0.31326166, -0.31326166]],
+            1e-4,
+        );
+        let g = r.mean().backward();
+        assert_close_with_tolerance(
+            &g.get(&a).array(),
+            &[[0.11552929, 0.11552929], [-0.11552929, -0.11552929]],
+            1e-4,
+        );
+    }
 }
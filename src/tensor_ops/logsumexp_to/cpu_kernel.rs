@@ -0,0 +1,65 @@
+use crate::{
+    shapes::{Axes, ReduceShapeTo, Shape},
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::internal_reshapes::{permute_axes_last, reduced_len, strided_index},
+};
+use std::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl super::LogSumExpKernel<f32> for Cpu {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let mut out = StridedArray::new(dst)?;
+        let (dims, strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), inp.strides);
+        let row_len = reduced_len::<Ax>(&dims);
+
+        for (row, o) in out.data_mut().iter_mut().enumerate() {
+            let x = |j: usize| inp.data[strided_index(row * row_len + j, &dims, &strides)];
+
+            // subtracting the max keeps exp() from overflowing for large values
+            let max = (0..row_len).map(x).fold(f32::NEG_INFINITY, f32::max);
+            let sum: f32 = (0..row_len).map(|j| (x(j) - max).exp()).sum();
+            *o = max + sum.ln();
+        }
+        Ok(out)
+    }
+
+    fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        out: &Self::Storage<Dst, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let (dims, strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), inp.strides);
+        let (_, gi_strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), grad_inp.strides);
+        let dst_dims: Vec<usize> = out.shape.concrete().into();
+        let out_strides: Vec<usize> = out.strides.into();
+        let go_strides: Vec<usize> = grad_out.strides.into();
+        let row_len = reduced_len::<Ax>(&dims);
+
+        let grad_inp = grad_inp.data_mut();
+        for row in 0..out.shape.num_elements() {
+            let lse = out.data[strided_index(row, &dst_dims, &out_strides)];
+            let g = grad_out.data[strided_index(row, &dst_dims, &go_strides)];
+            // d/dx logsumexp(x) = softmax(x) = exp(x - logsumexp(x))
+            for j in 0..row_len {
+                let v = row * row_len + j;
+                let x = inp.data[strided_index(v, &dims, &strides)];
+                grad_inp[strided_index(v, &dims, &gi_strides)] += (x - lse).exp() * g;
+            }
+        }
+        Ok(())
+    }
+}
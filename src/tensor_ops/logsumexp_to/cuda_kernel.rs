@@ -0,0 +1,91 @@
+use crate::{
+    shapes::{Axes, ReduceShapeTo, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+    tensor_ops::internal_reshapes::{permute_axes_last, reduced_len},
+};
+use cudarc::driver::{LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/logsumexp_to.ptx"));
+const MODULE_NAME: &str = "logsumexp_to";
+const FWD_FN_NAME: &str = "logsumexp_to_forward";
+const BWD_FN_NAME: &str = "logsumexp_to_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl super::LogSumExpKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let (dims, strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), inp.strides);
+        let row_len = reduced_len::<Ax>(&dims);
+        let num_rows = dst.num_elements();
+        let mut info: Vec<usize> = Vec::with_capacity(2 * Src::NUM_DIMS);
+        info.extend(dims);
+        info.extend(strides);
+        let info = self.dev.take_async(info)?;
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(num_rows)?;
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,          // const size_t num_rows,
+            row_len,           // const size_t row_len,
+            Src::NUM_DIMS,     // const size_t num_dims,
+            &info,             // const size_t *info,
+            inp.data.as_ref(), // const float *inp,
+            &mut storage,      // float *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+
+    fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        out: &Self::Storage<Dst, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let (dims, strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), inp.strides);
+        let (_, gi_strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), grad_inp.strides);
+        let row_len = reduced_len::<Ax>(&dims);
+        let num_rows = out.shape.num_elements();
+        let mut info: Vec<usize> = Vec::with_capacity(3 * Src::NUM_DIMS);
+        info.extend(dims);
+        info.extend(strides);
+        info.extend(gi_strides);
+        let info = self.dev.take_async(info)?;
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,                          // const size_t num_rows,
+            row_len,                           // const size_t row_len,
+            Src::NUM_DIMS,                     // const size_t num_dims,
+            &info,                             // const size_t *info,
+            inp.data.as_ref(),                 // const float *inp,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            out.data.as_ref(),                 // const float *out,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
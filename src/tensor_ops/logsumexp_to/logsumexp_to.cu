@@ -0,0 +1,73 @@
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+// `info` holds the dims and then the strides of `inp`, with the reduced axes moved to the end.
+// One thread handles one row, and writes one element of the contiguous `out`.
+extern "C" __global__ void logsumexp_to_forward(
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *inp,
+    float *out
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+    const size_t *dims = info;
+    const size_t *strides = info + num_dims;
+
+    // subtracting the max keeps exp() from overflowing for large values
+    float max = -INFINITY;
+    for (unsigned int j = 0; j < row_len; j++) {
+        max = fmaxf(max, inp[get_strided_index(row * row_len + j, num_dims, dims, strides)]);
+    }
+    float sum = 0.0;
+    for (unsigned int j = 0; j < row_len; j++) {
+        sum += expf(inp[get_strided_index(row * row_len + j, num_dims, dims, strides)] - max);
+    }
+    out[row] = logf(sum) + max;
+}
+
+// `info` holds the dims, the strides of `inp`, and then the strides of `grad_inp`, with the
+// reduced axes moved to the end. `out` and `grad_out` are contiguous. One thread handles one row.
+extern "C" __global__ void logsumexp_to_backward(
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *inp,
+    float *grad_inp,
+    const float *out,
+    const float *grad_out
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+    const size_t *dims = info;
+    const size_t *strides = info + num_dims;
+    const size_t *gi_strides = info + 2 * num_dims;
+
+    // d/dx logsumexp(x) = softmax(x) = exp(x - logsumexp(x))
+    float lse = out[row];
+    float g = grad_out[row];
+    for (unsigned int j = 0; j < row_len; j++) {
+        unsigned int i = row * row_len + j;
+        float x = inp[get_strided_index(i, num_dims, dims, strides)];
+        atomicAdd(grad_inp + get_strided_index(i, num_dims, dims, gi_strides), expf(x - lse) * g);
+    }
+}
@@ -1,6 +1,33 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
 use super::*;
 use crate::{gradients::Tape, shapes::*, tensor::*};
 
+pub trait LogSumExpKernel<E: Dtype>: DeviceStorage {
+    /// Computes `ln(sum(exp(inp)))` across `Ax`.
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+
+    /// Accumulates the gradient of the input, given the output of [LogSumExpKernel::forward].
+    fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        grad_inp: &mut Self::Storage<Src, E>,
+        out: &Self::Storage<Dst, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+}
+
 /// Reduction along multiple axes using [LogSumExp](https://en.wikipedia.org/wiki/LogSumExp).
 pub trait LogSumExpTo: HasErr + HasShape {
     /// [LogSumExp](https://en.wikipedia.org/wiki/LogSumExp) reduction.
@@ -41,10 +68,25 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> LogSumExpTo for Tensor<S, E,
     where
         Self::Shape: ReduceShapeTo<Dst, Ax>,
     {
-        let shape = *self.shape();
-        let max: Tensor<Dst, E, D> = self.retaped().try_max()?;
-        let t = self.try_sub(max.clone().try_broadcast_like::<_, Ax>(&shape)?)?;
-        t.try_exp()?.try_sum::<Dst, Ax>()?.try_ln()?.try_add(max)
+        let (inp, mut tape) = self.split_tape();
+        let dst: Dst = inp.shape().reduced();
+        let out = LogSumExpKernel::forward(&inp.device, dst, &inp.storage)?;
+        let out = inp.device.upgrade(out);
+
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            LogSumExpKernel::backward(
+                &inp.device,
+                &inp.storage,
+                grad_inp,
+                &phantom_out.storage,
+                grad_out,
+            )
+        });
+        Ok(out.put_tape(tape))
     }
 }
 
@@ -0,0 +1,147 @@
+//! A blocked & packed matrix multiplication written in pure rust. Blocks of both
+//! inputs are copied into contiguous buffers so the inner loop only touches
+//! contiguous memory (and can be auto-vectorized), regardless of the strides
+//! of the inputs. Large multiplications are split across threads.
+
+use std::vec::Vec;
+
+/// Number of rows of `a` packed at a time
+const MC: usize = 64;
+/// Number of columns of `a` (and rows of `b`) packed at a time
+const KC: usize = 256;
+/// Number of columns of `b` packed at a time
+const NC: usize = 512;
+
+/// Multiplications with fewer multiply-adds than this per thread are not split up.
+#[cfg(feature = "std")]
+const MIN_FLOPS_PER_THREAD: usize = 1 << 18;
+
+#[derive(Clone, Copy)]
+pub(super) struct MatRef {
+    pub(super) ptr: *const f32,
+    pub(super) strides: [isize; 2],
+}
+
+#[derive(Clone, Copy)]
+pub(super) struct MatMut {
+    pub(super) ptr: *mut f32,
+    pub(super) strides: [isize; 2],
+}
+
+// SAFETY: the pointers are only used while the buffers they point to are borrowed by
+// [sgemm()], and threads only ever write to non overlapping parts of the output.
+unsafe impl Send for MatRef {}
+unsafe impl Send for MatMut {}
+
+impl MatRef {
+    #[inline(always)]
+    unsafe fn get(&self, i: usize, j: usize) -> f32 {
+        *self
+            .ptr
+            .offset(i as isize * self.strides[0] + j as isize * self.strides[1])
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    unsafe fn offset(self, i: usize, j: usize) -> Self {
+        let ptr = self
+            .ptr
+            .offset(i as isize * self.strides[0] + j as isize * self.strides[1]);
+        Self { ptr, ..self }
+    }
+}
+
+impl MatMut {
+    #[inline(always)]
+    unsafe fn get_mut(&mut self, i: usize, j: usize) -> &mut f32 {
+        &mut *self
+            .ptr
+            .offset(i as isize * self.strides[0] + j as isize * self.strides[1])
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    unsafe fn offset(self, i: usize, j: usize) -> Self {
+        let ptr = self
+            .ptr
+            .offset(i as isize * self.strides[0] + j as isize * self.strides[1]);
+        Self { ptr, ..self }
+    }
+}
+
+/// Computes `c += a * b`, where `a` is `(m, k)`, `b` is `(k, n)`, and `c` is `(m, n)`.
+///
+/// # Safety
+/// All elements addressed by the shapes & strides must be in bounds, and `c` must not
+/// have a stride of 0 along a dimension with size greater than 1.
+pub(super) unsafe fn sgemm([m, k, n]: [usize; 3], a: MatRef, b: MatRef, c: MatMut) {
+    #[cfg(feature = "std")]
+    {
+        let num_threads = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(m * n * k / MIN_FLOPS_PER_THREAD);
+        if num_threads > 1 {
+            // split the output along its larger dimension, so each thread writes
+            // to a different block of `c`.
+            std::thread::scope(|s| {
+                if m >= n {
+                    let chunk = m.div_ceil(num_threads);
+                    for i in (0..m).step_by(chunk) {
+                        let (a, c) = (a.offset(i, 0), c.offset(i, 0));
+                        let rows = chunk.min(m - i);
+                        s.spawn(move || sgemm_single([rows, k, n], a, b, c));
+                    }
+                } else {
+                    let chunk = n.div_ceil(num_threads);
+                    for j in (0..n).step_by(chunk) {
+                        let (b, c) = (b.offset(0, j), c.offset(0, j));
+                        let cols = chunk.min(n - j);
+                        s.spawn(move || sgemm_single([m, k, cols], a, b, c));
+                    }
+                }
+            });
+            return;
+        }
+    }
+    sgemm_single([m, k, n], a, b, c)
+}
+
+unsafe fn sgemm_single([m, k, n]: [usize; 3], a: MatRef, b: MatRef, mut c: MatMut) {
+    let mut a_pack: Vec<f32> = Vec::with_capacity(MC.min(m) * KC.min(k));
+    let mut b_pack: Vec<f32> = Vec::with_capacity(KC.min(k) * NC.min(n));
+    let mut acc: Vec<f32> = Vec::with_capacity(NC.min(n));
+
+    for jc in (0..n).step_by(NC) {
+        let nc = NC.min(n - jc);
+        for pc in (0..k).step_by(KC) {
+            let kc = KC.min(k - pc);
+
+            b_pack.clear();
+            for p in 0..kc {
+                b_pack.extend((0..nc).map(|j| b.get(pc + p, jc + j)));
+            }
+
+            for ic in (0..m).step_by(MC) {
+                let mc = MC.min(m - ic);
+
+                a_pack.clear();
+                for i in 0..mc {
+                    a_pack.extend((0..kc).map(|p| a.get(ic + i, pc + p)));
+                }
+
+                for (i, a_row) in a_pack.chunks_exact(kc).enumerate() {
+                    acc.clear();
+                    acc.resize(nc, 0.0);
+                    for (&a_ip, b_row) in a_row.iter().zip(b_pack.chunks_exact(nc)) {
+                        for (o, &b_pj) in acc.iter_mut().zip(b_row) {
+                            *o += a_ip * b_pj;
+                        }
+                    }
+                    for (j, o) in acc.iter().enumerate() {
+                        *c.get_mut(ic + i, jc + j) += o;
+                    }
+                }
+            }
+        }
+    }
+}
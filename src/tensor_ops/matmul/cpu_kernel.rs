@@ -1,48 +1,70 @@
+use super::cpu_blocked::{sgemm, MatMut, MatRef};
 use crate::shapes::*;
-use crate::tensor::cpu::{Cpu, StridedArray, View, ViewMut};
+use crate::tensor::cpu::{Cpu, CpuMatMulBackend, StridedArray, View, ViewMut};
 
 #[cfg(feature = "cblas")]
 use cblas_sys::{
-    cblas_sgemm as sgemm, CblasColMajor as ColMajor, CblasNoTrans as NoTr,
+    cblas_sgemm as sgemm_blas, CblasColMajor as ColMajor, CblasNoTrans as NoTr,
     CblasRowMajor as RowMajor, CblasTrans as Tr,
 };
 
-#[inline]
-pub(crate) fn matmul<M: Dim, K: Dim, N: Dim>(
-    a: View<(M, K), f32>,
-    b: View<(K, N), f32>,
-    c: &mut ViewMut<(M, N), f32>,
-) {
-    let [m, k] = a.shape.concrete();
-    let n = b.shape.1.size();
+impl Cpu {
+    /// Computes `c += a * b` with [Cpu::matmul_backend()].
+    #[inline]
+    pub(crate) fn matmul<M: Dim, K: Dim, N: Dim>(
+        &self,
+        a: View<(M, K), f32>,
+        b: View<(K, N), f32>,
+        c: &mut ViewMut<(M, N), f32>,
+    ) {
+        let [m, k] = a.shape.concrete();
+        let n = b.shape.1.size();
 
-    let ap = a.ptr();
-    let bp = b.ptr();
-    let cp = c.ptr_mut();
+        let ap = a.ptr();
+        let bp = b.ptr();
+        let cp = c.ptr_mut();
 
-    #[cfg(not(feature = "cblas"))]
-    unsafe {
         let [ar, ac] = a.strides.map(|x| x as isize);
         let [br, bc] = b.strides.map(|x| x as isize);
         let [cr, cc] = c.strides.map(|x| x as isize);
-        matrixmultiply::sgemm(m, k, n, 1.0, ap, ar, ac, bp, br, bc, 1.0, cp, cr, cc);
-    }
 
-    #[cfg(feature = "cblas")]
-    unsafe {
-        let (lda, a_tr) = super::matrix_strides((m, k), a.strides);
-        let (ldb, b_tr) = super::matrix_strides((k, n), b.strides);
-        let (ldc, c_tr) = super::matrix_strides((m, n), c.strides);
-        let (m, n, k) = (m as libc::c_int, n as libc::c_int, k as libc::c_int);
-        let layout = if c_tr { ColMajor } else { RowMajor };
-        let (a_tr, b_tr) = if c_tr {
-            (if a_tr { NoTr } else { Tr }, if b_tr { NoTr } else { Tr })
-        } else {
-            (if a_tr { Tr } else { NoTr }, if b_tr { Tr } else { NoTr })
-        };
-        sgemm(
-            layout, a_tr, b_tr, m, n, k, 1.0, ap, lda as i32, bp, ldb as i32, 1.0, cp, ldc as i32,
-        )
+        match self.matmul_backend {
+            CpuMatMulBackend::MatrixMultiply => unsafe {
+                matrixmultiply::sgemm(m, k, n, 1.0, ap, ar, ac, bp, br, bc, 1.0, cp, cr, cc);
+            },
+            CpuMatMulBackend::Blocked => unsafe {
+                let a = MatRef {
+                    ptr: ap,
+                    strides: [ar, ac],
+                };
+                let b = MatRef {
+                    ptr: bp,
+                    strides: [br, bc],
+                };
+                let c = MatMut {
+                    ptr: cp,
+                    strides: [cr, cc],
+                };
+                sgemm([m, k, n], a, b, c);
+            },
+            #[cfg(feature = "cblas")]
+            CpuMatMulBackend::Blas => unsafe {
+                let (lda, a_tr) = super::matrix_strides((m, k), a.strides);
+                let (ldb, b_tr) = super::matrix_strides((k, n), b.strides);
+                let (ldc, c_tr) = super::matrix_strides((m, n), c.strides);
+                let (m, n, k) = (m as libc::c_int, n as libc::c_int, k as libc::c_int);
+                let layout = if c_tr { ColMajor } else { RowMajor };
+                let (a_tr, b_tr) = if c_tr {
+                    (if a_tr { NoTr } else { Tr }, if b_tr { NoTr } else { Tr })
+                } else {
+                    (if a_tr { Tr } else { NoTr }, if b_tr { Tr } else { NoTr })
+                };
+                sgemm_blas(
+                    layout, a_tr, b_tr, m, n, k, 1.0, ap, lda as i32, bp, ldb as i32, 1.0, cp,
+                    ldc as i32,
+                )
+            },
+        }
     }
 }
 
@@ -53,7 +75,7 @@ impl super::VecVecKernel<f32> for Cpu {
         rhs: &Self::Storage<(N,), f32>,
     ) -> Result<Self::Storage<(M, N), f32>, Self::Err> {
         let mut out = StridedArray::new((lhs.shape().0, rhs.shape().0))?;
-        matmul(lhs.view().br1(), rhs.view().br0(), &mut out.view_mut());
+        self.matmul(lhs.view().br1(), rhs.view().br0(), &mut out.view_mut());
         Ok(out)
     }
     fn backward<M: Dim, N: Dim>(
@@ -67,8 +89,8 @@ impl super::VecVecKernel<f32> for Cpu {
         let grad_out = grad_out.view();
         let lhs = lhs.view().br1().tr();
         let rhs = rhs.view().br0().tr();
-        matmul(grad_out, rhs, &mut grad_lhs.view_mut().br1());
-        matmul(lhs, grad_out, &mut grad_rhs.view_mut().br0());
+        self.matmul(grad_out, rhs, &mut grad_lhs.view_mut().br1());
+        self.matmul(lhs, grad_out, &mut grad_rhs.view_mut().br0());
         Ok(())
     }
 }
@@ -80,7 +102,7 @@ impl super::VecMatKernel<f32> for Cpu {
         rhs: &Self::Storage<(Const<K>, N), f32>,
     ) -> Result<Self::Storage<(N,), f32>, Self::Err> {
         let mut out = StridedArray::new((rhs.shape.1,))?;
-        matmul(lhs.view().br0(), rhs.view(), &mut out.view_mut().br0());
+        self.matmul(lhs.view().br0(), rhs.view(), &mut out.view_mut().br0());
         Ok(out)
     }
     fn backward<const K: usize, N: Dim>(
@@ -92,8 +114,8 @@ impl super::VecMatKernel<f32> for Cpu {
         grad_out: &Self::Storage<(N,), f32>,
     ) -> Result<(), Self::Err> {
         let grad_out = grad_out.view().br0();
-        matmul(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut().br0());
-        matmul(lhs.view().br0().tr(), grad_out, &mut grad_rhs.view_mut());
+        self.matmul(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut().br0());
+        self.matmul(lhs.view().br0().tr(), grad_out, &mut grad_rhs.view_mut());
         Ok(())
     }
 }
@@ -105,7 +127,7 @@ impl super::MatMatKernel<f32> for Cpu {
         rhs: &Self::Storage<(Const<K>, N), f32>,
     ) -> Result<Self::Storage<(M, N), f32>, Self::Err> {
         let mut out = StridedArray::new((lhs.shape.0, rhs.shape.1))?;
-        matmul(lhs.view(), rhs.view(), &mut out.view_mut());
+        self.matmul(lhs.view(), rhs.view(), &mut out.view_mut());
         Ok(out)
     }
     fn backward<M: Dim, const K: usize, N: Dim>(
@@ -117,8 +139,8 @@ impl super::MatMatKernel<f32> for Cpu {
         grad_out: &Self::Storage<(M, N), f32>,
     ) -> Result<(), Self::Err> {
         let grad_out = grad_out.view();
-        matmul(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut());
-        matmul(lhs.view().tr(), grad_out, &mut grad_rhs.view_mut());
+        self.matmul(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut());
+        self.matmul(lhs.view().tr(), grad_out, &mut grad_rhs.view_mut());
         Ok(())
     }
 }
@@ -136,7 +158,7 @@ impl super::MatMatBrKernel<f32> for Cpu {
         let b = rhs.view();
         let mut c = out.view_mut();
         for batch in 0..batch.size() {
-            matmul(a.idx(batch), b, &mut c.idx_mut(batch));
+            self.matmul(a.idx(batch), b, &mut c.idx_mut(batch));
         }
         Ok(out)
     }
@@ -156,8 +178,8 @@ impl super::MatMatBrKernel<f32> for Cpu {
         let grad_out = grad_out.view();
         for b in 0..batch_size {
             let go = grad_out.idx(b);
-            matmul(go, rhs, &mut grad_lhs.idx_mut(b));
-            matmul(lhs.idx(b).tr(), go, &mut grad_rhs);
+            self.matmul(go, rhs, &mut grad_lhs.idx_mut(b));
+            self.matmul(lhs.idx(b).tr(), go, &mut grad_rhs);
         }
         Ok(())
     }
@@ -176,7 +198,7 @@ impl super::MatMatBatch3Kernel<f32> for Cpu {
         let b = rhs.view();
         let mut c = out.view_mut();
         for batch in 0..B {
-            matmul(a.idx(batch), b.idx(batch), &mut c.idx_mut(batch));
+            self.matmul(a.idx(batch), b.idx(batch), &mut c.idx_mut(batch));
         }
         Ok(out)
     }
@@ -195,8 +217,8 @@ impl super::MatMatBatch3Kernel<f32> for Cpu {
         let grad_out = grad_out.view();
         for b in 0..B {
             let go = grad_out.idx(b);
-            matmul(go, rhs.idx(b).tr(), &mut grad_lhs.idx_mut(b));
-            matmul(lhs.idx(b).tr(), go, &mut grad_rhs.idx_mut(b));
+            self.matmul(go, rhs.idx(b).tr(), &mut grad_lhs.idx_mut(b));
+            self.matmul(lhs.idx(b).tr(), go, &mut grad_rhs.idx_mut(b));
         }
         Ok(())
     }
@@ -219,7 +241,7 @@ impl super::MatMatBatch4Kernel<f32> for Cpu {
             let r_b = rhs.idx(b);
             let mut o_b = out_view.idx_mut(b);
             for s in 0..S {
-                matmul(l_b.idx(s), r_b.idx(s), &mut o_b.idx_mut(s));
+                self.matmul(l_b.idx(s), r_b.idx(s), &mut o_b.idx_mut(s));
             }
         }
         Ok(out)
@@ -244,8 +266,8 @@ impl super::MatMatBatch4Kernel<f32> for Cpu {
             let mut gr_b = grad_rhs.idx_mut(b);
             let go_b = grad_out.idx(b);
             for s in 0..S {
-                matmul(go_b.idx(s), r_b.idx(s).tr(), &mut gl_b.idx_mut(s));
-                matmul(l_b.idx(s).tr(), go_b.idx(s), &mut gr_b.idx_mut(s));
+                self.matmul(go_b.idx(s), r_b.idx(s).tr(), &mut gl_b.idx_mut(s));
+                self.matmul(l_b.idx(s).tr(), go_b.idx(s), &mut gr_b.idx_mut(s));
             }
         }
         Ok(())
@@ -1,5 +1,6 @@
 #![allow(clippy::type_complexity)]
 
+mod cpu_blocked;
 pub(super) mod cpu_kernel;
 
 #[cfg(feature = "cuda")]
@@ -83,7 +84,7 @@ fn try_binary_op<
     D: DeviceStorage,
     RhsTape: Tape<D>,
     LhsTape: Tape<D> + Merge<RhsTape>,
-    Fwd: 'static + FnMut(&D, &D::Storage<Lhs, E>, &D::Storage<Rhs, E>) -> Result<D::Storage<Out, E>, D::Err>,
+    Fwd: 'static + Clone + Send + Sync + FnMut(&D, &D::Storage<Lhs, E>, &D::Storage<Rhs, E>) -> Result<D::Storage<Out, E>, D::Err>,
     Bwd: 'static + FnMut(&D, &D::Storage<Lhs, E>, &mut D::Storage<Lhs, E>, &D::Storage<Rhs, E>, &mut D::Storage<Rhs, E>, &D::Storage<Out, E>) -> Result<(), D::Err>,
 >(
     lhs: Tensor<Lhs, E, D, LhsTape>,
@@ -95,10 +96,18 @@ fn try_binary_op<
     let (rhs, rtape) = rhs.split_tape();
     let mut tape = ltape.merge(rtape);
     let out = lhs.device.upgrade(fwd(&lhs.device, &lhs.storage, &rhs.storage)?);
+    lhs.device.recorder().record("matmul", &[lhs.id, rhs.id], out.id, || {
+        let (lhs, rhs, out_id) = (lhs.clone(), rhs.clone(), out.id);
+        move |bufs| {
+            let storage = (fwd.clone())(&lhs.device, bufs.get(&lhs), bufs.get(&rhs))?;
+            bufs.insert::<Out, E, D>(out_id, storage);
+            Ok(())
+        }
+    });
     let phantom_out = out.clone();
     tape.try_alloc_grad(&lhs)?;
     tape.try_alloc_grad(&rhs)?;
-    tape.try_alloc_grad(&out)?;
+    tape.try_alloc_output_grad(&out)?;
     tape.add_backward_op(move |grads| {
         let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
         bwd(&lhs.device, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
@@ -676,4 +685,31 @@ mod tests {
                 .assert_close(&[[2.0276, 0.40552002]], 1e-5);
         }
     }
+
+    #[test]
+    fn test_cpu_matmul_backends_agree() {
+        let dev = Cpu::default();
+        let blocked = Cpu::default().with_matmul_backend(CpuMatMulBackend::Blocked);
+
+        let a: Tensor<Rank3<2, 64, 128>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<96, 128>, f32, _> = dev.sample_normal();
+        let a2 = blocked.tensor(a.array());
+        let b2 = blocked.tensor(b.array());
+
+        // uses transposed & broadcasted inputs, and is big enough to be split across threads
+        let c1 = a.trace().matmul(b.trace().permute());
+        let c2 = a2.trace().matmul(b2.trace().permute());
+        assert_close_with_tolerance(&c1.array(), &c2.array(), 1e-4);
+
+        let g1 = c1.exp().mean().backward();
+        let g2 = c2.exp().mean().backward();
+        assert_close_with_tolerance(&g1.get(&a).array(), &g2.get(&a2).array(), 1e-4);
+        assert_close_with_tolerance(&g1.get(&b).array(), &g2.get(&b2).array(), 1e-4);
+
+        let v: Tensor<Rank1<128>, f32, _> = dev.sample_normal();
+        let v2 = blocked.tensor(v.array());
+        let r1 = v.matmul(b.permute());
+        let r2 = v2.matmul(b2.permute());
+        assert_close_with_tolerance(&r1.array(), &r2.array(), 1e-4);
+    }
 }
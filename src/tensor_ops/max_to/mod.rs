@@ -70,7 +70,7 @@ impl<S: Shape, E: Dtype, D: MaxReduceKernel<E>, T: Tape<D>> MaxTo for Tensor<S,
         let out = inp.device.upgrade(inp.device.forward(dst, &inp.storage)?);
         let phantom_out = out.clone();
         tape.try_alloc_grad(&inp)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
             inp.device
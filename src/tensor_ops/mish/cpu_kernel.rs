@@ -0,0 +1,16 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl UnaryDerivative<f32> for super::MishKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        x * x.exp().ln_1p().tanh()
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        let t = x.exp().ln_1p().tanh();
+        let s = 1.0 / (1.0 + (-x).exp());
+        t + x * (1.0 - t * t) * s
+    }
+}
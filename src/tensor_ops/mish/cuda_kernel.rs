@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::MishKernelOp {}
+
+impl UnaryOpCudaKernel for super::MishKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/mish.ptx"));
+    const MODULE_NAME: &'static str = "mish";
+    const FWD_FN_NAME: &'static str = "mish_forward";
+    const BWD_FN_NAME: &'static str = "mish_backward";
+}
@@ -0,0 +1,33 @@
+struct MishKernelOp {};
+
+extern "C" __global__ void mish_forward(
+    const MishKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    out[i] = x * tanhf(log1pf(expf(x)));
+}
+
+extern "C" __global__ void mish_backward(
+    const MishKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float t = tanhf(log1pf(expf(x)));
+    float s = 1.0 / (1.0 + expf(-x));
+    float dx = t + x * (1.0 - t * t) * s;
+    grad_inp[i] += dx * grad_out[i];
+}
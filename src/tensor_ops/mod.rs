@@ -50,11 +50,24 @@
 //! - [MaxTo]
 //! - [MeanTo]
 //! - [MinTo]
+//! - [NanMaxTo]
+//! - [NanMinTo]
+//! - [NormTo]
+//! - [ProdTo]
 //! - [SumTo]
 //! - [VarTo]
 //! - [StddevTo]
 //! - [LogSumExpTo]
 //!
+//! To keep the reduced dimensions with size 1 (pytorch's `keepdim=True`), wrap the reduction
+//! in [crate::tensor::Tensor::keepdim()]:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let t: Tensor<Rank3<2, 4, 6>> = dev.zeros();
+//! let _: Tensor<Rank3<2, 1, 6>> = t.keepdim::<Axis<1>, _>(|t| t.min());
+//! ```
+//!
 //! # Broadcasts
 //!
 //! Broadcasting tensors is provided through the [BroadcastTo] trait. Similar to reductions
@@ -134,6 +147,87 @@
 //! let r = t.select::<Rank1<2>, _>(dev.tensor(1).broadcast());
 //! assert_eq!(r.array(), [2.0, 5.0]);
 //! ```
+//!
+//! # Graph message passing
+//!
+//! Message passing over an edge list is an [crate::tensor::Tensor::index_select()] of the source
+//! nodes, followed by [crate::tensor::Tensor::segment_sum()] or [crate::tensor::Tensor::segment_mean()]
+//! into the destination nodes. Both take the indices as a tensor, so the number of edges can be
+//! known only at runtime:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let nodes: Tensor<Rank2<3, 4>> = dev.sample_normal();
+//! let weight: Tensor<Rank2<4, 4>> = dev.sample_normal();
+//! // edges 0->1, 1->2, 2->0, 2->1
+//! let src = dev.tensor([0, 1, 2, 2]);
+//! let dst = dev.tensor([1, 2, 0, 1]);
+//! let messages = nodes.trace().index_select::<Axis<0>, _>(src);
+//! let aggregated: Tensor<Rank2<3, 4>, f32, Cpu, _> = messages.segment_sum::<Axis<0>, _, _>(dst, Const);
+//! let _ = aggregated.matmul(weight).relu();
+//! ```
+//!
+//! # Integer dtypes, comparisons and casting
+//!
+//! Tensors of `i32`, `i64` and `u8` support element wise and scalar arithmetic on the [crate::tensor::Cpu]
+//! device. Integer arithmetic wraps on overflow, and division by zero panics, the same as
+//! `wrapping_div` in rust. Integer tensors do not support gradients, so arithmetic is only
+//! implemented for them without a tape:
+//!
+//! ```compile_fail
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let tokens = dev.tensor([3i64, 7, 1]);
+//! let _ = tokens.trace() + 1;
+//! ```
+//!
+//! Comparisons like [crate::tensor::Tensor::lt()] and [crate::tensor::Tensor::scalar_eq()] work on
+//! any dtype, and produce `bool` tensors. NaN follows IEEE 754, so every comparison except `ne` is `false`.
+//!
+//! Use [to_dtype()] to convert between dtypes.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let tokens = dev.tensor([3i64, 7, 1]);
+//! let shifted = tokens.clone() + 1;
+//! assert_eq!(shifted.array(), [4, 8, 2]);
+//! assert_eq!(tokens.scalar_gt(2).array(), [true, true, false]);
+//! let a = dev.tensor([1.0, 2.0, 3.0]);
+//! let b = dev.tensor([3.0, 2.0, 1.0]);
+//! assert_eq!(a.lt(&b).array(), [true, false, false]);
+//! assert_eq!(tokens.to_dtype::<f32>().array(), [3.0, 7.0, 1.0]);
+//! ```
+//!
+//! `bool` tensors support logical ops through [crate::tensor::Tensor::and()], `&`, `|`, `^` and `!`,
+//! and can be reduced with [crate::tensor::Tensor::any()] and [crate::tensor::Tensor::all()].
+//! Casting a `bool` tensor with [to_dtype()] gives a mask of `0` and `1`.
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let x = dev.tensor([-1.0, 0.5, 2.0]);
+//! let in_range = x.scalar_ge(0.0) & x.scalar_le(1.0);
+//! assert_eq!(in_range.array(), [false, true, false]);
+//! assert!(in_range.any::<Rank0, _>().array());
+//! assert_eq!((x * in_range.to_dtype::<f32>()).array(), [0.0, 0.5, 0.0]);
+//! ```
+//!
+//! [bincount()], [histogram()] and [unique()] count values on the host, e.g. to weight a loss by
+//! class frequency.
+//!
+//! # Fusing elementwise ops
+//!
+//! Every op reads and writes a whole tensor, and records its own operation on the tape.
+//! For chains of elementwise ops, [crate::tensor::Tensor::fuse()] captures the chain and
+//! runs it as a single kernel, without allocating the intermediate tensors:
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let x: Tensor<Rank2<2, 3>> = dev.sample_normal();
+//! let y = x.trace().fuse().mul_scalar(0.5).add_scalar(0.5).clamp(0.0, 1.0).run();
+//! ```
+//! See [FusedElementwise] for the supported ops.
 
 mod device;
 pub use device::Device;
@@ -141,16 +235,39 @@ pub use device::Device;
 // mod impl_mask;
 mod abs;
 mod add;
+mod atan2;
+mod audio;
+mod augment;
 mod backward;
 mod bce;
+mod boolean;
+mod boxes;
 mod broadcast_to;
+mod ceil;
 mod clamp;
+mod cmp;
 mod cos;
+mod custom_op;
 mod div;
 mod dropout;
+mod elu;
+mod erf;
 mod exp;
+mod expm1;
+mod fast_gelu;
+mod floor;
+mod fused;
+mod gelu;
+mod grid_sample;
+mod histogram;
+mod hooks;
 mod huber_error;
+mod index_select;
+mod layer_norm;
+mod leaky_relu;
+mod linear_act;
 mod ln;
+mod log1p;
 mod log_softmax;
 mod logsumexp_to;
 mod matmul;
@@ -159,23 +276,40 @@ mod maximum;
 mod mean_to;
 mod min_to;
 mod minimum;
+mod mish;
 mod mul;
+mod nan_to_num;
+mod nanmax_to;
+mod nanmin_to;
 mod nans_to;
 mod negate;
+mod norm_to;
 mod normalize;
+mod one_hot;
 mod permute_to;
 mod pow;
+mod prod_to;
 mod relu;
+mod roi_align;
+mod round;
+mod rsqrt;
+mod segment_reduce;
 mod select_and_gather;
 mod sigmoid;
+mod sign;
+mod silu;
 mod sin;
+mod slice;
 mod softmax;
+mod softplus;
+mod sparse_matmul;
 mod sqrt;
 mod square;
 mod stddev_to;
 mod sub;
 mod sum_to;
 mod tanh;
+mod to_dtype;
 mod var_to;
 
 pub(crate) mod cpu_kernels;
@@ -186,16 +320,37 @@ pub(crate) mod ops;
 
 pub use abs::abs;
 pub use add::{add, TryAdd};
+pub use atan2::atan2;
+pub use audio::{mel_filterbank, try_mel_filterbank, Window};
 pub use backward::Backward;
 pub use bce::bce_with_logits;
+pub use boxes::{batched_nms, box_iou, nms, try_batched_nms, try_box_iou, try_nms};
 pub use broadcast_to::BroadcastTo;
+pub use ceil::ceil;
 pub use clamp::clamp;
+
 pub use cos::cos;
+pub use custom_op::{custom_binary_op, custom_op, try_custom_binary_op};
 pub use div::{div, TryDiv};
 pub use dropout::dropout;
+pub use elu::elu;
+pub use erf::erf;
 pub use exp::exp;
+pub use expm1::expm1;
+pub use fast_gelu::fast_gelu;
+pub use floor::floor;
+pub use fused::{ElementwiseOp, FusedElementwise};
+pub use gelu::gelu;
+pub use grid_sample::{affine_grid, grid_sample};
+pub use histogram::{
+    batched_bincount, bincount, histogram, try_batched_bincount, try_bincount, try_histogram,
+    try_unique, try_unique_counts, unique, unique_counts,
+};
 pub use huber_error::huber_error;
+pub use layer_norm::layer_norm;
+pub use leaky_relu::leaky_relu;
 pub use ln::ln;
+pub use log1p::log1p;
 pub use log_softmax::log_softmax;
 pub use logsumexp_to::LogSumExpTo;
 pub use matmul::{matmul, TryMatMul};
@@ -204,39 +359,54 @@ pub use maximum::maximum;
 pub use mean_to::MeanTo;
 pub use min_to::MinTo;
 pub use minimum::minimum;
+pub use mish::mish;
 pub use mul::{mul, TryMul};
+pub use nan_to_num::nan_to_num;
+pub use nanmax_to::NanMaxTo;
+pub use nanmin_to::NanMinTo;
 pub use nans_to::nans_to;
 pub use negate::negate;
+pub use norm_to::NormTo;
 pub use normalize::normalize;
 pub use permute_to::PermuteTo;
 pub use pow::{powf, powi};
+pub use prod_to::ProdTo;
 pub use relu::relu;
+pub use roi_align::RoiAlignConfig;
+pub use round::round;
+pub use rsqrt::rsqrt;
 pub use select_and_gather::{GatherTo, SelectTo};
 pub use sigmoid::sigmoid;
+pub use sign::sign;
+pub use silu::silu;
 pub use sin::sin;
 pub use softmax::softmax;
+pub use softplus::softplus;
 pub use sqrt::sqrt;
 pub use square::square;
 pub use stddev_to::StddevTo;
 pub use sub::{sub, TrySub};
 pub use sum_to::SumTo;
 pub use tanh::tanh;
+pub use to_dtype::to_dtype;
 pub use var_to::VarTo;
 // pub use impl_mask::*;
 
 mod reshape_to;
-pub use reshape_to::ReshapeTo;
+pub(crate) use index_select::IndexSelectKernel;
+pub use index_select::{IndexShape, SelectIndices};
+pub use reshape_to::{ReshapeKernel, ReshapeTo};
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 mod conv2d;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 pub use conv2d::TryConv2D;
-#[cfg(feature = "nightly")]
-pub(crate) use conv2d::TryConv2DTo;
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
+pub(crate) use conv2d::{TryConv2DTo, TryUnfold2D};
 
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 mod pool2d;
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 pub(crate) use pool2d::{ConstAvgPool2D, ConstMaxPool2D, ConstMinPool2D};
-#[cfg(feature = "nightly")]
+#[cfg(any(feature = "nightly", feature = "stable-fallback"))]
 pub use pool2d::{TryAvgPool2D, TryMaxPool2D, TryMinPool2D};
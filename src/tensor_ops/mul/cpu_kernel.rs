@@ -1,4 +1,8 @@
-use crate::tensor_ops::cpu_kernels::{BinaryDerivative, UnaryDerivative};
+use super::TryMul;
+use crate::{
+    tensor::{Cpu, DeviceStorage},
+    tensor_ops::cpu_kernels::{int_arithmetic, BinaryDerivative, UnaryDerivative},
+};
 
 impl UnaryDerivative<f32> for super::ScalarMulKernelOp<f32> {
     fn f(&self, x: &f32) -> f32 {
@@ -23,3 +27,5 @@ impl BinaryDerivative<f32> for super::BinaryMulKernelOp {
         *x
     }
 }
+
+int_arithmetic!(TryMul, try_mul, wrapping_mul, [i32, i64, u8]);
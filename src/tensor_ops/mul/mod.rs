@@ -3,7 +3,7 @@ mod cpu_kernel;
 #[cfg(feature = "cuda")]
 mod cuda_kernel;
 
-use super::{ops::*, Device};
+use super::ops::*;
 use crate::{gradients::*, shapes::*, tensor::*};
 
 #[repr(C)]
@@ -35,19 +35,23 @@ pub struct ScalarMulKernelOp<E> {
 /// let r = a * 2.0;
 /// assert_eq!(r.array(), [[2.0, 4.0, 6.0], [-2.0, -4.0, -6.0]]);
 /// ```
-pub fn mul<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
+pub fn mul<S: Shape, E: Dtype, D, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
-) -> Tensor<S, E, D, T> {
+) -> Tensor<S, E, D, T>
+where
+    D: BinaryKernel<BinaryMulKernelOp, E>,
+{
     lhs * rhs
 }
 pub trait TryMul<Rhs = Self>: HasErr {
     fn try_mul(self, rhs: Rhs) -> Result<Self, Self::Err>;
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LhsTape: Tape<D>, RhsTape: Tape<D>>
-    TryMul<Tensor<S, E, D, RhsTape>> for Tensor<S, E, D, LhsTape>
+impl<S: Shape, E: Dtype, D, LhsTape: Tape<D>, RhsTape: Tape<D>> TryMul<Tensor<S, E, D, RhsTape>>
+    for Tensor<S, E, D, LhsTape>
 where
+    D: BinaryKernel<BinaryMulKernelOp, E>,
     LhsTape: Merge<RhsTape>,
 {
     fn try_mul(self, rhs: Tensor<S, E, D, RhsTape>) -> Result<Self, Self::Err> {
@@ -55,13 +59,16 @@ where
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> TryMul<E> for Tensor<S, E, D, T> {
+impl<S: Shape, E: Dtype, D, T: Tape<D>> TryMul<E> for Tensor<S, E, D, T>
+where
+    D: UnaryKernel<ScalarMulKernelOp<E>, E>,
+{
     fn try_mul(self, rhs: E) -> Result<Self, Self::Err> {
         try_unary_op(ScalarMulKernelOp { scalar: rhs }, self)
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LhsTape: Tape<D>, Rhs> std::ops::Mul<Rhs>
+impl<S: Shape, E: Dtype, D: DeviceStorage, LhsTape: Tape<D>, Rhs> std::ops::Mul<Rhs>
     for Tensor<S, E, D, LhsTape>
 where
     Self: TryMul<Rhs>,
@@ -161,4 +168,14 @@ mod tests {
         let g = r.exp().sum().backward();
         assert_eq!(g.get(&x).array(), [[0.8243606; 2]; 3]);
     }
+
+    #[test]
+    fn test_mul_int() {
+        let dev: Cpu = Default::default();
+        let a = dev.tensor([1i32, -2, 3]);
+        let b = dev.tensor([4i32, 5, -6]);
+        assert_eq!((a.clone() * b).array(), [4, -10, -18]);
+        assert_eq!((a * 3).array(), [3, -6, 9]);
+        assert_eq!((dev.tensor([16u8, 2]) * 16).array(), [0, 32]);
+    }
 }
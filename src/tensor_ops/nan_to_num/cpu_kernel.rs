@@ -0,0 +1,24 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+
+impl UnaryDerivative<f32> for super::NanToNumKernelOp<f32> {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        if x.is_nan() {
+            self.nan
+        } else if *x == f32::INFINITY {
+            self.posinf
+        } else if *x == f32::NEG_INFINITY {
+            self.neginf
+        } else {
+            *x
+        }
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        if x.is_finite() {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::NanToNumKernelOp<f32> {}
+
+impl UnaryOpCudaKernel for super::NanToNumKernelOp<f32> {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/nan_to_num.ptx"));
+    const MODULE_NAME: &'static str = "nan_to_num";
+    const FWD_FN_NAME: &'static str = "nan_to_num_forward";
+    const BWD_FN_NAME: &'static str = "nan_to_num_backward";
+}
@@ -0,0 +1,73 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct NanToNumKernelOp<E> {
+    pub nan: E,
+    pub posinf: E,
+    pub neginf: E,
+}
+
+/// Replaces [f32::NAN] with `nan`, [f32::INFINITY] with `posinf`, and [f32::NEG_INFINITY]
+/// with `neginf`. The gradient is `0` for the replaced values.
+///
+/// See [nans_to](crate::tensor_ops::nans_to) to only replace nans.
+///
+/// **Pytorch equivalent**: `t.nan_to_num(nan, posinf, neginf)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([1.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY]);
+/// let r = t.nan_to_num(0.0, 100.0, -100.0);
+/// assert_eq!(r.array(), [1.0, 0.0, 100.0, -100.0]);
+/// ```
+pub fn nan_to_num<S: Shape, E: Dtype, D: UnaryKernel<NanToNumKernelOp<E>, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    nan: E,
+    posinf: E,
+    neginf: E,
+) -> Tensor<S, E, D, T> {
+    t.nan_to_num(nan, posinf, neginf)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<NanToNumKernelOp<E>, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [nan_to_num]
+    pub fn nan_to_num(self, nan: E, posinf: E, neginf: E) -> Self {
+        self.try_nan_to_num(nan, posinf, neginf).unwrap()
+    }
+    /// See [nan_to_num]
+    pub fn try_nan_to_num(self, nan: E, posinf: E, neginf: E) -> Result<Self, D::Err> {
+        try_unary_op(
+            NanToNumKernelOp {
+                nan,
+                posinf,
+                neginf,
+            },
+            self,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_nan_to_num() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -2.0]);
+        let r = t.trace().nan_to_num(0.5, 3.0, -3.0);
+        assert_eq!(r.array(), [1.0, 0.5, 3.0, -3.0, -2.0]);
+        // NOTE: .exp() so we cover case where nan_to_num() needs to use result grad
+        let g = r.exp().mean().backward();
+        assert_eq!(g.get(&t).array(), [0.54365635, 0.0, 0.0, 0.0, 0.027067056]);
+    }
+}
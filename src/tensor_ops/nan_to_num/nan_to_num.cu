@@ -0,0 +1,40 @@
+struct NanToNumKernelOp {
+    float nan;
+    float posinf;
+    float neginf;
+};
+
+extern "C" __global__ void nan_to_num_forward(
+    const NanToNumKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    if (isnan(x)) {
+        out[i] = op.nan;
+    } else if (isinf(x)) {
+        out[i] = x > 0.0 ? op.posinf : op.neginf;
+    } else {
+        out[i] = x;
+    }
+}
+
+extern "C" __global__ void nan_to_num_backward(
+    const NanToNumKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float dx = isfinite(inp[i]) ? 1.0 : 0.0;
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,70 @@
+use super::*;
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+/// Reduction along multiple axes using `max`, ignoring nans.
+pub trait NanMaxTo: HasErr + HasShape {
+    /// Max reduction that skips [f32::NAN] values, which is useful when nans mark missing
+    /// values. Slices that only contain nans reduce to [f32::NEG_INFINITY]. Nans get a
+    /// gradient of `0`.
+    ///
+    /// **Numpy equivalent**: `np.nanmax(t, Ax)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[1.0, f32::NAN, 3.0], [f32::NAN, f32::NAN, f32::NAN]]);
+    /// let r = t.nanmax::<Rank1<2>, _>(); // or `nanmax::<_, Axis<1>>()`
+    /// assert_eq!(r.array(), [3.0, f32::NEG_INFINITY]);
+    /// ```
+    fn nanmax<Dst: Shape, Ax: Axes>(self) -> Self::WithShape<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_nanmax().unwrap()
+    }
+    /// Fallible version of [NanMaxTo::nanmax]
+    fn try_nanmax<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
+}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> NanMaxTo for Tensor<S, f32, D, T> {
+    fn try_nanmax<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_nans_to(f32::NEG_INFINITY)?.try_max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestDevice;
+
+    #[test]
+    fn test_nanmax_axis_1() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, f32::NAN, 3.0, 3.0], [f32::NAN, -1.0, -2.0, f32::NAN]]);
+        let r = t.trace().nanmax::<Rank1<2>, _>();
+        assert_eq!(r.array(), [3.0, -1.0]);
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.0, 0.0, 1.0, 1.0], [0.0, 1.0, 0.0, 0.0]]
+        );
+    }
+
+    #[test]
+    fn test_nanmax_all_nans() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[f32::NAN; 3], [0.5, f32::NAN, -0.5]]);
+        let r = t.trace().nanmax::<Rank1<3>, _>();
+        assert_eq!(r.array(), [0.5, f32::NEG_INFINITY, -0.5]);
+        let g = r.exp().sum().backward();
+        let g = g.get(&t).array();
+        assert_eq!(g[0], [0.0; 3]);
+        assert_eq!(g[1], [0.5f32.exp(), 0.0, (-0.5f32).exp()]);
+    }
+}
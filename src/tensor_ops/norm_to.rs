@@ -0,0 +1,134 @@
+use super::*;
+use crate::{
+    gradients::{NoneTape, Tape},
+    shapes::*,
+    tensor::*,
+};
+
+/// Reduction along multiple axes using the p-norm.
+pub trait NormTo: HasErr + HasShape {
+    /// p-norm reduction, `sum(|x|^p)^(1/p)`. Use `f32::INFINITY` for the max norm.
+    ///
+    /// **Pytorch equivalent**: `t.norm(p, Axes)`
+    ///
+    /// Examples:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[3.0, -4.0], [1.0, 1.0]]);
+    /// let r = t.clone().norm::<_, Axis<1>>(2.0);
+    /// assert_eq!(r.array(), [5.0, 2.0f32.sqrt()]);
+    /// let r = t.clone().norm::<_, Axis<1>>(1.0);
+    /// assert_eq!(r.array(), [7.0, 2.0]);
+    /// let r = t.norm::<_, Axis<1>>(f32::INFINITY);
+    /// assert_eq!(r.array(), [4.0, 1.0]);
+    /// ```
+    ///
+    /// **Panics** if `p` is not positive.
+    fn norm<Dst: Shape, Ax: Axes>(self, p: f32) -> Self::WithShape<Dst>
+    where
+        Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_norm(p).unwrap()
+    }
+    /// Fallible version of [NormTo::norm]
+    fn try_norm<Dst: Shape, Ax: Axes>(self, p: f32) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>;
+}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> NormTo for Tensor<S, f32, D, T> {
+    fn try_norm<Dst: Shape, Ax: Axes>(self, p: f32) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
+    {
+        assert!(p > 0.0, "p must be positive, found {p}");
+        if p == f32::INFINITY {
+            self.try_abs()?.try_max()
+        } else if p == 1.0 {
+            self.try_abs()?.try_sum()
+        } else {
+            let sum: Tensor<Dst, f32, D, T> = if p == 2.0 {
+                self.try_square()?.try_sum()?
+            } else {
+                self.try_abs()?.try_powf(p)?.try_sum()?
+            };
+            // the root has an infinite derivative at 0, which turns the gradient of an all zero
+            // input into NaN. adding 1 to zero sums before the root, and subtracting it after,
+            // keeps the result the same and makes the gradient 0 instead.
+            let zeros = sum
+                .retaped::<NoneTape>()
+                .try_sign()?
+                .try_negate()?
+                .try_add(1.0)?;
+            let root = sum.try_add(zeros.clone())?;
+            let root = if p == 2.0 {
+                root.try_sqrt()?
+            } else {
+                root.try_powf(p.recip())?
+            };
+            root.try_sub(zeros)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_norm_2() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[3.0, -4.0, 0.0], [1.0, 2.0, -2.0]]);
+        let r = t.trace().norm::<Rank1<2>, _>(2.0);
+        assert_eq!(r.array(), [5.0, 3.0]);
+        let g = r.sum().backward();
+        assert_close(
+            &g.get(&t).array(),
+            &[[0.6, -0.8, 0.0], [1.0 / 3.0, 2.0 / 3.0, -2.0 / 3.0]],
+        );
+    }
+
+    #[test]
+    fn test_norm_1_and_inf() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[3.0, -4.0, 0.5], [1.0, 2.0, -2.0]]);
+        let r = t.trace().norm::<Rank1<3>, _>(1.0);
+        assert_eq!(r.array(), [4.0, 6.0, 2.5]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[1.0, -1.0, 1.0], [1.0, 1.0, -1.0]]);
+
+        let r = t.trace().norm::<Rank0, _>(f32::INFINITY);
+        assert_eq!(r.array(), 4.0);
+        let g = r.backward();
+        assert_eq!(g.get(&t).array(), [[0.0, -1.0, 0.0], [0.0; 3]]);
+    }
+
+    #[test]
+    fn test_norm_3() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, -2.0, 2.0]);
+        let r = t.trace().norm::<Rank0, _>(3.0);
+        assert_close(&r.array(), &17f32.powf(1.0 / 3.0));
+        // d/dx = sign(x) * |x|^2 / norm^2
+        let n2 = 17f32.powf(2.0 / 3.0);
+        let g = r.backward();
+        assert_close(&g.get(&t).array(), &[1.0 / n2, -4.0 / n2, 4.0 / n2]);
+    }
+
+    #[test]
+    fn test_norm_of_zeros_has_zero_grad() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[0.0, 0.0], [3.0, -4.0]]);
+        for p in [2.0, 3.0] {
+            let r = t.trace().norm::<_, Axis<1>>(p);
+            assert_close(
+                &r.array(),
+                &[0.0, (3f32.powf(p) + 4f32.powf(p)).powf(p.recip())],
+            );
+            let g = r.sum().backward();
+            assert_eq!(g.get(&t).array()[0], [0.0; 2]);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::shapes::{Dtype, Shape};
+use crate::tensor::cpu::{Cpu, LendingIterator, StridedArray};
+
+impl<E: Dtype> super::OneHotKernel<E> for Cpu {
+    fn forward<S: Shape, Dst: Shape>(
+        &self,
+        idx: &Self::Storage<S, usize>,
+        dst: Dst,
+        on: E,
+        off: E,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err> {
+        let mut out = StridedArray::try_new_with(dst, off)?;
+        let n = dst.concrete()[Dst::NUM_DIMS - 1];
+        let buf = std::sync::Arc::make_mut(&mut out.data);
+        let mut idx_iter = idx.iter();
+        let mut row = 0;
+        while let Some(&i) = idx_iter.next() {
+            assert!(i < n, "Index {i} is out of bounds for {n} classes");
+            buf[row * n + i] = on;
+            row += 1;
+        }
+        Ok(out)
+    }
+}
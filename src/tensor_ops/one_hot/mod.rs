@@ -0,0 +1,145 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+pub trait OneHotKernel<E: Dtype>: DeviceStorage {
+    fn forward<S: Shape, Dst: Shape>(
+        &self,
+        idx: &Self::Storage<S, usize>,
+        dst: Dst,
+        on: E,
+        off: E,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+}
+
+impl<S: Shape, D: OneHotKernel<f32>> Tensor<S, usize, D> {
+    /// One hot encodes a tensor of class indices on the device, adding a new dimension
+    /// of size `N` after the last one. See [Tensor::smooth_one_hot()] for label smoothing.
+    ///
+    /// Unlike [crate::data::OneHotEncode], the indices don't need to be on the host, so
+    /// this works for any index shape and for indices computed by other ops.
+    ///
+    /// **Pytorch equivalent**: `torch.nn.functional.one_hot(t, N).float()`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let labels: Tensor<Rank1<3>, usize> = dev.tensor([2, 0, 1]);
+    /// let r: Tensor<Rank2<3, 4>> = labels.one_hot::<4>();
+    /// assert_eq!(
+    ///     r.array(),
+    ///     [[0.0, 0.0, 1.0, 0.0], [1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]]
+    /// );
+    /// ```
+    ///
+    /// **Panics** on Cpu if any index is `>= N`.
+    pub fn one_hot<const N: usize>(&self) -> Tensor<S::Appended, f32, D>
+    where
+        S: AppendDim<Const<N>>,
+    {
+        self.try_one_hot::<N>().unwrap()
+    }
+
+    /// Fallible version of [Tensor::one_hot()]
+    pub fn try_one_hot<const N: usize>(&self) -> Result<Tensor<S::Appended, f32, D>, D::Err>
+    where
+        S: AppendDim<Const<N>>,
+    {
+        self.try_smooth_one_hot::<N>(0.0)
+    }
+
+    /// [Tensor::one_hot()] with label smoothing: the target class gets
+    /// `1 - smoothing + smoothing / N`, and every other class gets `smoothing / N`, so
+    /// each row still sums to 1.
+    ///
+    /// **Pytorch equivalent**: the targets of `CrossEntropyLoss(label_smoothing=smoothing)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let labels: Tensor<Rank1<2>, usize> = dev.tensor([1, 0]);
+    /// let r: Tensor<Rank2<2, 4>> = labels.smooth_one_hot::<4>(0.2);
+    /// assert_eq!(r.array(), [[0.05, 0.85, 0.05, 0.05], [0.85, 0.05, 0.05, 0.05]]);
+    /// ```
+    pub fn smooth_one_hot<const N: usize>(&self, smoothing: f32) -> Tensor<S::Appended, f32, D>
+    where
+        S: AppendDim<Const<N>>,
+    {
+        self.try_smooth_one_hot::<N>(smoothing).unwrap()
+    }
+
+    /// Fallible version of [Tensor::smooth_one_hot()]
+    pub fn try_smooth_one_hot<const N: usize>(
+        &self,
+        smoothing: f32,
+    ) -> Result<Tensor<S::Appended, f32, D>, D::Err>
+    where
+        S: AppendDim<Const<N>>,
+    {
+        let dst = self.shape().append_dim(Const::<N>);
+        let off = smoothing / N as f32;
+        let on = 1.0 - smoothing + off;
+        let storage = self.device.forward(&self.storage, dst, on, off)?;
+        Ok(self.device.upgrade(storage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{losses::cross_entropy_with_logits_loss, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_one_hot_2d() {
+        let dev: TestDevice = Default::default();
+        let idx: Tensor<Rank2<2, 2>, usize, _> = dev.tensor([[0, 2], [1, 1]]);
+        let r: Tensor<Rank3<2, 2, 3>, f32, _> = idx.one_hot::<3>();
+        assert_eq!(
+            r.array(),
+            [
+                [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+                [[0.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_one_hot_matches_one_hot_encode() {
+        use crate::data::OneHotEncode;
+        let dev: TestDevice = Default::default();
+        let labels = [0, 4, 2, 2, 1];
+        let idx: Tensor<(usize,), usize, _> = dev.tensor_from_vec(labels.to_vec(), (5,));
+        assert_eq!(
+            idx.one_hot::<5>().as_vec(),
+            dev.one_hot_encode::<5>(&labels).as_vec()
+        );
+    }
+
+    #[test]
+    fn test_one_hot_of_broadcast_indices() {
+        let dev: TestDevice = Default::default();
+        let idx: Tensor<Rank1<3>, usize, _> = dev.tensor(1).broadcast();
+        let r: Tensor<Rank2<3, 2>, f32, _> = idx.one_hot::<2>();
+        assert_eq!(r.array(), [[0.0, 1.0]; 3]);
+    }
+
+    #[test]
+    fn test_smooth_one_hot_loss() {
+        let dev: TestDevice = Default::default();
+        let idx: Tensor<Rank1<2>, usize, _> = dev.tensor([2, 0]);
+        let targ = idx.smooth_one_hot::<3>(0.3);
+        assert_close(&targ.array(), &[[0.1, 0.1, 0.8], [0.8, 0.1, 0.1]]);
+        assert_close(&targ.clone().sum::<Rank1<2>, _>().array(), &[1.0; 2]);
+
+        let logits: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let loss = cross_entropy_with_logits_loss(logits.trace(), targ.clone());
+        let expected = (logits.log_softmax::<Axis<1>>() * targ)
+            .sum::<Rank0, _>()
+            .negate()
+            / 2.0;
+        assert_close(&loss.array(), &expected.array());
+    }
+}
@@ -0,0 +1,38 @@
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+// The output is contiguous, with the classes as the last dimension. Indices that
+// are out of bounds produce a row of `off`.
+extern "C" __global__ void one_hot_forward(
+    const size_t numel,
+    const size_t num_classes,
+    const size_t num_dims,
+    const size_t *idx_dims,
+    const size_t *idx,
+    const size_t *idx_strides,
+    const float on,
+    const float off,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int row = i / num_classes;
+    unsigned int c = i % num_classes;
+    unsigned int idx_i = get_strided_index(row, num_dims, idx_dims, idx_strides);
+    out[i] = idx[idx_i] == c ? on : off;
+}
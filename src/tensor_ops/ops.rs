@@ -39,7 +39,7 @@ pub trait BinaryKernel<Op, E: Dtype>: DeviceStorage {
 }
 
 pub(crate) fn try_unary_op<
-    Op: 'static + Clone,
+    Op: 'static + Clone + Send + Sync,
     S: Shape,
     E: Dtype,
     D: UnaryKernel<Op, E>,
@@ -51,10 +51,19 @@ pub(crate) fn try_unary_op<
     let (inp, mut tape) = inp.split_tape();
     let storage = inp.device.forward(op.clone(), &inp.storage)?;
     let out = inp.device.upgrade(storage);
+    let name = core::any::type_name::<Op>();
+    inp.device.recorder().record(name, &[inp.id], out.id, || {
+        let (inp, op, out_id) = (inp.clone(), op.clone(), out.id);
+        move |bufs| {
+            let storage = inp.device.forward(op.clone(), bufs.get(&inp))?;
+            bufs.insert::<S, E, D>(out_id, storage);
+            Ok(())
+        }
+    });
     let phantom_out = out.clone();
     tape.try_alloc_grad(&inp)?;
-    tape.try_alloc_grad(&out)?;
-    tape.add_backward_op(move |grads| {
+    tape.try_alloc_output_grad(&out)?;
+    tape.add_named_backward_op(name, move |grads| {
         let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
         inp.device.backward(op, &inp.storage, grad_inp, grad_out)?;
         Ok(())
@@ -63,7 +72,7 @@ pub(crate) fn try_unary_op<
 }
 
 pub(crate) fn try_binary_op<
-    Op: 'static + Copy,
+    Op: 'static + Copy + Send + Sync,
     S: Shape,
     E: Dtype,
     D: BinaryKernel<Op, E>,
@@ -79,11 +88,22 @@ pub(crate) fn try_binary_op<
     let mut tape = ltape.merge(rtape);
     let storage = lhs.device.forward(op, &lhs.storage, &rhs.storage)?;
     let out = lhs.device.upgrade(storage);
+    let name = core::any::type_name::<Op>();
+    lhs.device
+        .recorder()
+        .record(name, &[lhs.id, rhs.id], out.id, || {
+            let (lhs, rhs, out_id) = (lhs.clone(), rhs.clone(), out.id);
+            move |bufs| {
+                let storage = lhs.device.forward(op, bufs.get(&lhs), bufs.get(&rhs))?;
+                bufs.insert::<S, E, D>(out_id, storage);
+                Ok(())
+            }
+        });
     let phantom_out = out.clone();
     tape.try_alloc_grad(&lhs)?;
     tape.try_alloc_grad(&rhs)?;
-    tape.try_alloc_grad(&out)?;
-    tape.add_backward_op(move |grads| {
+    tape.try_alloc_output_grad(&out)?;
+    tape.add_named_backward_op(name, move |grads| {
         let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
         lhs.device
             .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)?;
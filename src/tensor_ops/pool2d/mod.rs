@@ -86,40 +86,30 @@ macro_rules! pool2d {
 
         impl<
                 C: Dim,
-                const H: usize,
-                const W: usize,
+                H: ConvAlgebra<K, S, P>,
+                W: ConvAlgebra<K, S, P>,
                 D: $Kernel<f32> + ZerosTensor<f32>,
                 T: 'static + Tape<D>,
                 const K: usize,
                 const S: usize,
                 const P: usize,
-            > $ConstTrait<K, S, P> for Tensor<(C, Const<H>, Const<W>), f32, D, T>
-        where
-            Const<H>: ConvAlgebra<K, S, P>,
-            Const<W>: ConvAlgebra<K, S, P>,
+            > $ConstTrait<K, S, P> for Tensor<(C, H, W), f32, D, T>
         {
-            type Output = Tensor<
-                (
-                    C,
-                    <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
-                    <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
-                ),
-                f32,
-                D,
-                T,
-            >;
+            type Output = Tensor<(C, H::Convolved, W::Convolved), f32, D, T>;
 
             fn try_pool2d(self) -> Result<Self::Output, Self::Err> {
-                let &(chan, _, _) = self.shape();
-                let op = Pool2DOp::new(K, S, P, [1, chan.size(), H, W]);
+                let &(chan, h, w) = self.shape();
+                let op = Pool2DOp::new(K, S, P, [1, chan.size(), h.size(), w.size()]);
                 let (inp, mut tape) = self.split_tape();
-                let mut out =
-                    inp.device
-                        .try_zeros_like(&(chan, Default::default(), Default::default()))?;
+                let mut out = inp.device.try_zeros_like(&(
+                    chan,
+                    H::Convolved::from_size(op.h_out).unwrap(),
+                    W::Convolved::from_size(op.w_out).unwrap(),
+                ))?;
                 inp.device.forward(op, &inp.storage, &mut out.storage)?;
                 let phantom_out = out.clone();
                 tape.try_alloc_grad(&inp)?;
-                tape.try_alloc_grad(&out)?;
+                tape.try_alloc_output_grad(&out)?;
                 tape.add_backward_op(move |grads| {
                     let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
                     inp.device
@@ -132,44 +122,31 @@ macro_rules! pool2d {
         impl<
                 B: Dim,
                 C: Dim,
-                const H: usize,
-                const W: usize,
+                H: ConvAlgebra<K, S, P>,
+                W: ConvAlgebra<K, S, P>,
                 D: $Kernel<f32> + ZerosTensor<f32>,
                 T: 'static + Tape<D>,
                 const K: usize,
                 const S: usize,
                 const P: usize,
-            > $ConstTrait<K, S, P> for Tensor<(B, C, Const<H>, Const<W>), f32, D, T>
-        where
-            Const<H>: ConvAlgebra<K, S, P>,
-            Const<W>: ConvAlgebra<K, S, P>,
+            > $ConstTrait<K, S, P> for Tensor<(B, C, H, W), f32, D, T>
         {
-            type Output = Tensor<
-                (
-                    B,
-                    C,
-                    <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
-                    <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
-                ),
-                f32,
-                D,
-                T,
-            >;
+            type Output = Tensor<(B, C, H::Convolved, W::Convolved), f32, D, T>;
 
             fn try_pool2d(self) -> Result<Self::Output, Self::Err> {
-                let &(batch, chan, _, _) = self.shape();
-                let op = Pool2DOp::new(K, S, P, [batch.size(), chan.size(), H, W]);
+                let &(batch, chan, h, w) = self.shape();
+                let op = Pool2DOp::new(K, S, P, [batch.size(), chan.size(), h.size(), w.size()]);
                 let (inp, mut tape) = self.split_tape();
                 let mut out = inp.device.try_zeros_like(&(
                     batch,
                     chan,
-                    Default::default(),
-                    Default::default(),
+                    H::Convolved::from_size(op.h_out).unwrap(),
+                    W::Convolved::from_size(op.w_out).unwrap(),
                 ))?;
                 inp.device.forward(op, &inp.storage, &mut out.storage)?;
                 let phantom_out = out.clone();
                 tape.try_alloc_grad(&inp)?;
-                tape.try_alloc_grad(&out)?;
+                tape.try_alloc_output_grad(&out)?;
                 tape.add_backward_op(move |grads| {
                     let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
                     inp.device
@@ -205,6 +182,7 @@ pool2d!(
     TryMeth = try_min_pool2d
 );
 
+#[cfg(feature = "nightly")]
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,3 +291,23 @@ mod tests {
         );
     }
 }
+
+#[cfg(test)]
+mod runtime_tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_pool2d_runtime_dims() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[1.0f32, 1., 0.5, 0.2], [0.2, 0.2, 0.5, 1.2]]]);
+        let x = x.reshape_like(&(Const::<1>, 2, 4));
+        let r = x.trace().max_pool2d::<2, 1, 0>();
+        assert_eq!(r.shape(), &(Const, 1, 3));
+        assert_close(&r.as_vec(), &std::vec![1., 1., 1.2]);
+        let g = r.sum().backward();
+        assert_close(
+            &g.get(&x).as_vec(),
+            &std::vec![1., 2., 0., 0., 0., 0., 0., 1.],
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    shapes::{Axes, ReduceShapeTo, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+impl super::ProdReduceKernel<f32> for Cpu {
+    fn forward<Src: Shape + ReduceShapeTo<Dst, Ax>, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err> {
+        let mut out: StridedArray<Dst, f32> = StridedArray::try_new_with(dst, 1.0)?;
+        let mut out_iter = out.iter_mut_as(&inp.shape);
+        let mut inp_iter = inp.iter();
+        while let Some((out_i, inp_i)) = out_iter.next().zip(inp_iter.next()) {
+            *out_i *= *inp_i;
+        }
+        Ok(out)
+    }
+
+    fn backward<Src: Shape + ReduceShapeTo<Dst, Ax>, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        // the product of the non zero elements, and the number of zeros, of each
+        // reduction. these are enough to compute the product of all other
+        // elements without dividing by zero.
+        let mut nz_prod: StridedArray<Dst, f32> = StridedArray::try_new_with(grad_out.shape, 1.0)?;
+        let mut num_zeros: StridedArray<Dst, usize> = StridedArray::new(grad_out.shape)?;
+        {
+            let mut nz_prod_iter = nz_prod.iter_mut_as(&inp.shape);
+            let mut num_zeros_iter = num_zeros.iter_mut_as(&inp.shape);
+            let mut inp_iter = inp.iter();
+            for _ in 0..inp.shape.num_elements() {
+                let x = *inp_iter.next().unwrap();
+                let p = nz_prod_iter.next().unwrap();
+                let z = num_zeros_iter.next().unwrap();
+                if x == 0.0 {
+                    *z += 1;
+                } else {
+                    *p *= x;
+                }
+            }
+        }
+
+        let mut inp_iter = inp.iter();
+        let mut grad_inp_iter = grad_inp.iter_mut();
+        let mut nz_prod_iter = nz_prod.iter_as(&inp.shape);
+        let mut num_zeros_iter = num_zeros.iter_as(&inp.shape);
+        let mut grad_out_iter = grad_out.iter_as(&inp.shape);
+        for _ in 0..inp.shape.num_elements() {
+            let x = *inp_iter.next().unwrap();
+            let p = *nz_prod_iter.next().unwrap();
+            let d = match (x == 0.0, *num_zeros_iter.next().unwrap()) {
+                (false, 0) => p / x,
+                (true, 1) => p,
+                _ => 0.0,
+            };
+            *grad_inp_iter.next().unwrap() += *grad_out_iter.next().unwrap() * d;
+        }
+        Ok(())
+    }
+}
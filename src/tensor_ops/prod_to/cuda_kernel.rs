@@ -0,0 +1,116 @@
+use crate::{
+    shapes::{Axes, BroadcastStridesTo, ReduceShapeTo, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+};
+
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+
+use std::sync::Arc;
+
+const MODULE_NAME: &str = "prod_to";
+const FWD_FN_NAME: &str = "prod_to_forward";
+const NONZERO_FN_NAME: &str = "prod_to_nonzero";
+const BWD_FN_NAME: &str = "prod_to_backward";
+const ALL_FN_NAMES: [&str; 3] = [FWD_FN_NAME, NONZERO_FN_NAME, BWD_FN_NAME];
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/prod_to.ptx"));
+
+impl super::ProdReduceKernel<f32> for Cuda {
+    fn forward<Src: Shape + ReduceShapeTo<Dst, Ax>, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let mut storage = self.dev.take_async(std::vec![1.0f32; dst.num_elements()])?;
+        let numel = inp.shape.num_elements();
+        if numel == 0 {
+            return Ok(CudaArray {
+                data: Arc::new(storage),
+                shape: dst,
+                strides: dst.strides(),
+            });
+        }
+
+        let dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_strides: Src::Concrete =
+            BroadcastStridesTo::<Src, Ax>::broadcast_strides(&dst, dst.strides());
+        let out_strides: CudaSlice<usize> = self.dev.take_async(out_strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            Src::NUM_DIMS,     // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut storage,      // float *out,
+            &out_strides,      // const size_t *out_strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+
+    fn backward<Src: Shape + ReduceShapeTo<Dst, Ax>, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        let numel = inp.shape.num_elements();
+        if numel == 0 {
+            return Ok(());
+        }
+
+        let dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_strides: Src::Concrete =
+            BroadcastStridesTo::<Src, Ax>::broadcast_strides(&grad_out.shape, grad_out.strides);
+        let out_strides: CudaSlice<usize> = self.dev.take_async(out_strides.into())?;
+
+        // these have the same layout as grad_out
+        let mut nz_prod = self
+            .dev
+            .take_async(std::vec![1.0f32; grad_out.data.len()])?;
+        let mut num_zeros = self.dev.alloc_zeros_async::<f32>(grad_out.data.len())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let nonzero_fn = self.dev.get_func(MODULE_NAME, NONZERO_FN_NAME).unwrap();
+        let params = (
+            numel,             // const size_t numel,
+            Src::NUM_DIMS,     // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut nz_prod,      // float *nz_prod,
+            &mut num_zeros,    // float *num_zeros,
+            &out_strides,      // const size_t *out_strides
+        );
+        unsafe { nonzero_fn.launch_async(cfg, params) }?;
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let params = (
+            numel,                             // const size_t numel,
+            Src::NUM_DIMS,                     // const size_t num_dims,
+            &dims,                             // const size_t *dims,
+            inp.data.as_ref(),                 // const float *inp,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            &nz_prod,                          // const float *nz_prod,
+            &num_zeros,                        // const float *num_zeros,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &out_strides,                      // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
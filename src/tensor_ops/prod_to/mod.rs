@@ -0,0 +1,112 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+pub trait ProdReduceKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape + ReduceShapeTo<Dst, Ax>, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+    fn backward<Src: Shape + ReduceShapeTo<Dst, Ax>, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Reduction along multiple axes using `prod`.
+pub trait ProdTo: HasErr + HasShape {
+    /// Product reduction. **Pytorch equivalent**: `t.prod(Ax)`
+    ///
+    /// **NOTE** The gradient of each element is the product of the other elements of its
+    /// reduction. It is computed from the product of the non zero elements and the number of
+    /// zeros: non zero elements divide the product by themselves, and if there are zeros
+    /// only they get a gradient. So reductions with zeros have finite gradients, but the
+    /// product of the non zero elements should not overflow or underflow.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 3>, _> = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]]);
+    /// let r = t.prod::<Rank1<2>, _>(); // or `prod::<_, Axis<1>>()`
+    /// assert_eq!(r.array(), [6.0, -2.0]);
+    /// ```
+    fn prod<Dst: Shape, Ax: Axes>(self) -> Self::WithShape<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_prod().unwrap()
+    }
+    /// Fallible version of [ProdTo::prod]
+    fn try_prod<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
+}
+
+impl<S: Shape, E: Dtype, D: ProdReduceKernel<E>, T: Tape<D>> ProdTo for Tensor<S, E, D, T> {
+    fn try_prod<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        let (inp, mut tape) = self.split_tape();
+        let out = inp.device.upgrade(inp.device.forward(dst, &inp.storage)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(&inp.storage, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor_ops::*;
+    use crate::tests::TestDevice;
+
+    #[test]
+    fn test_prod_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0, -0.5]);
+        let r = t.trace().prod::<Rank0, _>();
+        assert_eq!(r.array(), -3.0);
+        let g = r.backward();
+        assert_eq!(g.get(&t).array(), [-3.0, -1.5, -1.0, 6.0]);
+    }
+
+    #[test]
+    fn test_prod_axis_with_zeros() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[2.0, 0.0, 3.0], [0.0, 4.0, 0.0], [1.0, 2.0, 3.0]]);
+        let r = t.trace().prod::<_, Axis<1>>();
+        assert_eq!(r.array(), [0.0, 0.0, 6.0]);
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.0, 6.0, 0.0], [0.0, 0.0, 0.0], [6.0, 3.0, 2.0]]
+        );
+    }
+
+    #[test]
+    fn test_prod_axis_0_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, -3.0]);
+        let r = t
+            .trace()
+            .broadcast::<Rank2<2, 3>, _>()
+            .prod::<Rank1<3>, _>();
+        assert_eq!(r.array(), [1.0, 4.0, 9.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [2.0, 4.0, -6.0]);
+    }
+}
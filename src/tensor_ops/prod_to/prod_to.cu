@@ -0,0 +1,104 @@
+// there is no atomicMul, so this is implemented with atomicCAS.
+__device__ __forceinline__ float atomicMulf(float *addr, float value) {
+    unsigned int *addr_u = (unsigned int *)addr;
+    unsigned int old = *addr_u;
+    unsigned int assumed;
+    do {
+        assumed = old;
+        old = atomicCAS(addr_u, assumed, __float_as_uint(value * __uint_as_float(assumed)));
+    } while (assumed != old);
+    return __uint_as_float(old);
+}
+
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+// Accepts pre-broadcasted strides for out, so one thread runs for each
+// element of inp.
+extern "C" __global__ void prod_to_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(i, num_dims, dims, inp_strides);
+    unsigned int out_i = get_strided_index(i, num_dims, dims, out_strides);
+    atomicMulf(out + out_i, inp[inp_i]);
+}
+
+// Computes the product of the non zero elements & the number of zeros
+// of each reduction.
+extern "C" __global__ void prod_to_nonzero(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    float *nz_prod,
+    float *num_zeros,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(i, num_dims, dims, inp_strides);
+    unsigned int out_i = get_strided_index(i, num_dims, dims, out_strides);
+    float x = inp[inp_i];
+    if (x == 0.0) {
+        atomicAdd(num_zeros + out_i, 1.0);
+    } else {
+        atomicMulf(nz_prod + out_i, x);
+    }
+}
+
+extern "C" __global__ void prod_to_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *inp,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *nz_prod,
+    const float *num_zeros,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(i, num_dims, dims, inp_strides);
+    unsigned int out_i = get_strided_index(i, num_dims, dims, out_strides);
+    float x = inp[inp_i];
+    float zeros = num_zeros[out_i];
+    float d = 0.0;
+    if (x != 0.0 && zeros == 0.0) {
+        d = nz_prod[out_i] / x;
+    } else if (x == 0.0 && zeros == 1.0) {
+        d = nz_prod[out_i];
+    }
+    atomicAdd(grad_inp + inp_i, grad_out[out_i] * d);
+}
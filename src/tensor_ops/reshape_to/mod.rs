@@ -5,24 +5,26 @@ mod cuda_kernel;
 
 use crate::{gradients::Tape, shapes::*, tensor::*};
 
+/// The device implementation of [ReshapeTo]. Needed as a bound to reshape
+/// tensors of a generic device.
 pub trait ReshapeKernel<E: Dtype>: DeviceStorage {
     fn forward<Src: Shape, Dst: Shape>(
         &self,
         dst: Dst,
         inp: &Self::Storage<Src, E>,
-    ) -> Result<Self::Storage<Dst, E>, Self::Err>
-    where
-        Src: HasSameNumelAs<Dst>;
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
     fn backward<Src: Shape, Dst: Shape>(
         &self,
         grad_inp: &mut Self::Storage<Src, E>,
         grad_out: &Self::Storage<Dst, E>,
-    ) -> Result<(), Self::Err>
-    where
-        Src: HasSameNumelAs<Dst>;
+    ) -> Result<(), Self::Err>;
 }
 
-/// **Requires Nightly** Change the shape of a tensor moving data around.
+/// Change the shape of a tensor moving data around.
+///
+/// [ReshapeTo::reshape] checks the number of elements at compile time and **requires nightly**.
+/// [ReshapeTo::reshape_like] checks it at runtime, so it also works on stable and with
+/// runtime dimensions.
 pub trait ReshapeTo: HasErr + HasShape {
     fn reshape<Dst: Shape + Default>(self) -> Self::WithShape<Dst>
     where
@@ -32,20 +34,49 @@ pub trait ReshapeTo: HasErr + HasShape {
     }
     fn try_reshape<Dst: Shape + Default>(self) -> Result<Self::WithShape<Dst>, Self::Err>
     where
-        Self::Shape: HasSameNumelAs<Dst>;
+        Self::Shape: HasSameNumelAs<Dst>,
+    {
+        self.try_reshape_like(&Default::default())
+    }
+
+    /// Reshapes to `dst`, **panics** if it has a different number of elements than `self`.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+    /// let r: Tensor<(usize,), f32, _> = t.reshape_like(&(6,));
+    /// ```
+    fn reshape_like<Dst: Shape>(self, dst: &Dst) -> Self::WithShape<Dst> {
+        self.try_reshape_like(dst).unwrap()
+    }
+    fn try_reshape_like<Dst: Shape>(self, dst: &Dst) -> Result<Self::WithShape<Dst>, Self::Err>;
 }
 
 impl<S: Shape, E: Dtype, D: ReshapeKernel<E>, T: Tape<D>> ReshapeTo for Tensor<S, E, D, T> {
-    fn try_reshape<Dst: Shape + Default>(self) -> Result<Self::WithShape<Dst>, Self::Err>
-    where
-        Self::Shape: HasSameNumelAs<Dst>,
-    {
-        let dst: Dst = Default::default();
+    fn try_reshape_like<Dst: Shape>(self, dst: &Dst) -> Result<Self::WithShape<Dst>, Self::Err> {
+        assert_eq!(
+            self.shape().num_elements(),
+            dst.num_elements(),
+            "Can't reshape {:?} to {:?}",
+            self.shape().concrete(),
+            dst.concrete()
+        );
         let (inp, mut tape) = self.split_tape();
-        let out = inp.device.upgrade(inp.device.forward(dst, &inp.storage)?);
+        let out = inp.device.upgrade(inp.device.forward(*dst, &inp.storage)?);
+        inp.device
+            .recorder()
+            .record("reshape", &[inp.id], out.id, || {
+                let (inp, dst, out_id) = (inp.clone(), *dst, out.id);
+                move |bufs| {
+                    let storage = inp.device.forward(dst, bufs.get(&inp))?;
+                    bufs.insert::<Dst, E, D>(out_id, storage);
+                    Ok(())
+                }
+            });
         let phantom_out = out.clone();
         tape.try_alloc_grad(&inp)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
             inp.device.backward(grad_inp, grad_out)
@@ -110,4 +141,52 @@ mod tests {
             [0.18419516, 0.20356713, 0.22497648, 0.24863747, 0.2747869, 0.3036865]
         )
     }
+
+    #[test]
+    fn test_reshape_contiguous_is_view() {
+        let dev: Cpu = Default::default();
+        let a = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let b = a.clone().reshape::<Rank1<6>>();
+        assert!(std::sync::Arc::ptr_eq(&a.storage.data, &b.storage.data));
+        assert_eq!(b.array(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        // permuted data is not contiguous, so reshape has to copy it
+        let c = a.clone().permute::<Rank2<3, 2>, _>().reshape::<Rank1<6>>();
+        assert!(!std::sync::Arc::ptr_eq(&a.storage.data, &c.storage.data));
+        assert_eq!(c.array(), [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+    }
+}
+
+#[cfg(test)]
+mod runtime_tests {
+    use crate::tensor::*;
+    use crate::tensor_ops::*;
+    use crate::tests::TestDevice;
+
+    use super::*;
+
+    #[test]
+    fn test_reshape_like() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]]);
+        let b = a.trace().reshape_like(&(3, Const::<2>));
+        assert_eq!(b.shape(), &(3, Const));
+        assert_eq!(b.as_vec(), [0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+        let g = b.exp().mean().backward();
+        assert_eq!(
+            g.get(&a).array(),
+            [
+                [0.18419516, 0.20356713, 0.22497648],
+                [0.24863747, 0.2747869, 0.3036865]
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reshape_like_wrong_numel() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let _ = a.reshape_like(&(5,));
+    }
 }
@@ -0,0 +1,128 @@
+use crate::shapes::{Const, Dim};
+use crate::tensor::cpu::{Cpu, StridedArray};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use super::RoiAlignConfig;
+
+/// Calls `f(y, x, weight)` for every input pixel that contributes to output bin `(ph, pw)`
+/// of the region `[x1, y1, x2, y2]`, where `weight` includes the average over the
+/// sampling points of the bin.
+fn for_each_sample<F: FnMut(usize, usize, f32)>(
+    op: &RoiAlignConfig,
+    roi: [f32; 4],
+    (h, w): (usize, usize),
+    (ph, pw): (usize, usize),
+    (out_h, out_w): (usize, usize),
+    mut f: F,
+) {
+    let offset = if op.aligned { 0.5 } else { 0.0 };
+    let start_x = roi[0] * op.spatial_scale - offset;
+    let start_y = roi[1] * op.spatial_scale - offset;
+    let mut roi_w = roi[2] * op.spatial_scale - offset - start_x;
+    let mut roi_h = roi[3] * op.spatial_scale - offset - start_y;
+    if !op.aligned {
+        roi_w = roi_w.max(1.0);
+        roi_h = roi_h.max(1.0);
+    }
+    let bin_h = roi_h / out_h as f32;
+    let bin_w = roi_w / out_w as f32;
+    let (grid_h, grid_w) = if op.sampling_ratio > 0 {
+        (op.sampling_ratio, op.sampling_ratio)
+    } else {
+        (
+            (roi_h / out_h as f32).ceil() as usize,
+            (roi_w / out_w as f32).ceil() as usize,
+        )
+    };
+    let count = (grid_h * grid_w).max(1) as f32;
+
+    for iy in 0..grid_h {
+        let y = start_y + ph as f32 * bin_h + (iy as f32 + 0.5) * bin_h / grid_h as f32;
+        for ix in 0..grid_w {
+            let x = start_x + pw as f32 * bin_w + (ix as f32 + 0.5) * bin_w / grid_w as f32;
+            if y < -1.0 || y > h as f32 || x < -1.0 || x > w as f32 {
+                continue;
+            }
+            let (y, x) = (y.max(0.0), x.max(0.0));
+            let (y0, x0) = (y as usize, x as usize);
+            let (y0, y1, y) = if y0 >= h - 1 {
+                (h - 1, h - 1, (h - 1) as f32)
+            } else {
+                (y0, y0 + 1, y)
+            };
+            let (x0, x1, x) = if x0 >= w - 1 {
+                (w - 1, w - 1, (w - 1) as f32)
+            } else {
+                (x0, x0 + 1, x)
+            };
+            let (ly, lx) = (y - y0 as f32, x - x0 as f32);
+            let (hy, hx) = (1.0 - ly, 1.0 - lx);
+            f(y0, x0, hy * hx / count);
+            f(y0, x1, hy * lx / count);
+            f(y1, x0, ly * hx / count);
+            f(y1, x1, ly * lx / count);
+        }
+    }
+}
+
+impl super::RoiAlignKernel<f32> for Cpu {
+    fn forward<N: Dim, C: Dim, H: Dim, W: Dim, K: Dim, const PH: usize, const PW: usize>(
+        &self,
+        op: RoiAlignConfig,
+        inp: &Self::Storage<(N, C, H, W), f32>,
+        boxes: &Self::Storage<(K, Const<5>), f32>,
+    ) -> Result<Self::Storage<(K, C, Const<PH>, Const<PW>), f32>, Self::Err> {
+        let (n, c, h, w) = inp.shape;
+        let k = boxes.shape.0;
+        let mut out = StridedArray::new((k, c, Const, Const))?;
+        if h.size() == 0 || w.size() == 0 {
+            return Ok(out);
+        }
+        for r in 0..k.size() {
+            let b = boxes[[r, 0]] as usize;
+            assert!(b < n.size(), "Box {r} has batch index {b} >= {}", n.size());
+            let roi = [boxes[[r, 1]], boxes[[r, 2]], boxes[[r, 3]], boxes[[r, 4]]];
+            for ph in 0..PH {
+                for pw in 0..PW {
+                    let hw = (h.size(), w.size());
+                    for_each_sample(&op, roi, hw, (ph, pw), (PH, PW), |y, x, weight| {
+                        for ch in 0..c.size() {
+                            out[[r, ch, ph, pw]] += weight * inp[[b, ch, y, x]];
+                        }
+                    });
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<N: Dim, C: Dim, H: Dim, W: Dim, K: Dim, const PH: usize, const PW: usize>(
+        &self,
+        op: RoiAlignConfig,
+        grad_inp: &mut Self::Storage<(N, C, H, W), f32>,
+        boxes: &Self::Storage<(K, Const<5>), f32>,
+        grad_out: &Self::Storage<(K, C, Const<PH>, Const<PW>), f32>,
+    ) -> Result<(), Self::Err> {
+        let (_, c, h, w) = grad_inp.shape;
+        if h.size() == 0 || w.size() == 0 {
+            return Ok(());
+        }
+        for r in 0..boxes.shape.0.size() {
+            let b = boxes[[r, 0]] as usize;
+            let roi = [boxes[[r, 1]], boxes[[r, 2]], boxes[[r, 3]], boxes[[r, 4]]];
+            for ph in 0..PH {
+                for pw in 0..PW {
+                    let hw = (h.size(), w.size());
+                    for_each_sample(&op, roi, hw, (ph, pw), (PH, PW), |y, x, weight| {
+                        for ch in 0..c.size() {
+                            grad_inp[[b, ch, y, x]] += weight * grad_out[[r, ch, ph, pw]];
+                        }
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
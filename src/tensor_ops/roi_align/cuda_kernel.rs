@@ -0,0 +1,96 @@
+use crate::{
+    shapes::{Const, Dim, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/roi_align.ptx"));
+const MODULE_NAME: &str = "roi_align";
+const FWD_FN_NAME: &str = "roi_align_forward";
+const BWD_FN_NAME: &str = "roi_align_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+unsafe impl cudarc::driver::AsKernelParam for super::RoiAlignConfig {}
+
+impl super::RoiAlignKernel<f32> for Cuda {
+    fn forward<N: Dim, C: Dim, H: Dim, W: Dim, K: Dim, const PH: usize, const PW: usize>(
+        &self,
+        op: super::RoiAlignConfig,
+        inp: &Self::Storage<(N, C, H, W), f32>,
+        boxes: &Self::Storage<(K, Const<5>), f32>,
+    ) -> Result<Self::Storage<(K, C, Const<PH>, Const<PW>), f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = (boxes.shape.0, inp.shape.1, Const::<PH>, Const::<PW>);
+        let numel = shape.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        if numel > 0 {
+            let inp_dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+            let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+            let box_strides: CudaSlice<usize> = self.dev.take_async(boxes.strides.into())?;
+            let out_dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+            let out_strides: CudaSlice<usize> = self.dev.take_async(shape.strides().into())?;
+
+            let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                op,                  // const RoiAlignConfig op,
+                numel,               // const size_t numel,
+                &inp_dims,           // const size_t *inp_dims,
+                &inp_strides,        // const size_t *inp_strides,
+                boxes.data.as_ref(), // const float *boxes,
+                &box_strides,        // const size_t *box_strides,
+                &out_dims,           // const size_t *out_dims,
+                &out_strides,        // const size_t *out_strides,
+                inp.data.as_ref(),   // const float *inp,
+                &mut storage,        // float *out
+            );
+            unsafe { fwd_fn.launch_async(cfg, params) }?;
+        }
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+
+    fn backward<N: Dim, C: Dim, H: Dim, W: Dim, K: Dim, const PH: usize, const PW: usize>(
+        &self,
+        op: super::RoiAlignConfig,
+        grad_inp: &mut Self::Storage<(N, C, H, W), f32>,
+        boxes: &Self::Storage<(K, Const<5>), f32>,
+        grad_out: &Self::Storage<(K, C, Const<PH>, Const<PW>), f32>,
+    ) -> Result<(), Self::Err> {
+        let numel = grad_out.shape.num_elements();
+        if numel == 0 {
+            return Ok(());
+        }
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let inp_dims: CudaSlice<usize> = self.dev.take_async(grad_inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let box_strides: CudaSlice<usize> = self.dev.take_async(boxes.strides.into())?;
+        let out_dims: CudaSlice<usize> = self.dev.take_async(grad_out.shape.concrete().into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            op,                                // const RoiAlignConfig op,
+            numel,                             // const size_t numel,
+            &inp_dims,                         // const size_t *inp_dims,
+            &inp_strides,                      // const size_t *inp_strides,
+            boxes.data.as_ref(),               // const float *boxes,
+            &box_strides,                      // const size_t *box_strides,
+            &out_dims,                         // const size_t *out_dims,
+            &out_strides,                      // const size_t *out_strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
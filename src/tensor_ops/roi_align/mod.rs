@@ -0,0 +1,195 @@
+#![allow(clippy::type_complexity)]
+
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+/// Configuration of [Tensor::roi_align()]. The defaults match torchvision.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RoiAlignConfig {
+    /// Scales the box coordinates into feature map coordinates, e.g. `1.0 / 16.0` for a
+    /// backbone with a total stride of 16. Defaults to `1.0`.
+    pub spatial_scale: f32,
+
+    /// The number of sampling points along each side of an output bin. `0` uses
+    /// `ceil(roi_size / output_size)` points, which adapts to the size of each box.
+    /// Defaults to `0`.
+    pub sampling_ratio: usize,
+
+    /// Shifts the boxes by half a pixel, so that pixel centers line up with the box
+    /// corners. Defaults to `false`, but `true` is more accurate.
+    pub aligned: bool,
+}
+
+impl Default for RoiAlignConfig {
+    fn default() -> Self {
+        Self {
+            spatial_scale: 1.0,
+            sampling_ratio: 0,
+            aligned: false,
+        }
+    }
+}
+
+pub trait RoiAlignKernel<E: Dtype>: DeviceStorage {
+    fn forward<N: Dim, C: Dim, H: Dim, W: Dim, K: Dim, const PH: usize, const PW: usize>(
+        &self,
+        op: RoiAlignConfig,
+        inp: &Self::Storage<(N, C, H, W), E>,
+        boxes: &Self::Storage<(K, Const<5>), E>,
+    ) -> Result<Self::Storage<(K, C, Const<PH>, Const<PW>), E>, Self::Err>;
+
+    fn backward<N: Dim, C: Dim, H: Dim, W: Dim, K: Dim, const PH: usize, const PW: usize>(
+        &self,
+        op: RoiAlignConfig,
+        grad_inp: &mut Self::Storage<(N, C, H, W), E>,
+        boxes: &Self::Storage<(K, Const<5>), E>,
+        grad_out: &Self::Storage<(K, C, Const<PH>, Const<PW>), E>,
+    ) -> Result<(), Self::Err>;
+}
+
+impl<N: Dim, C: Dim, H: Dim, W: Dim, D: RoiAlignKernel<f32>, T: Tape<D>>
+    Tensor<(N, C, H, W), f32, D, T>
+{
+    /// Pools each box of a batch of feature maps into a fixed `PH`x`PW` grid, from
+    /// [Mask R-CNN](https://arxiv.org/abs/1703.06870). Each bin is the average of bilinearly
+    /// interpolated samples, so the boxes don't have to line up with the pixels.
+    ///
+    /// Each row of `boxes` is `[batch_index, x1, y1, x2, y2]`, in the coordinates of the
+    /// input image. The boxes don't receive a gradient.
+    ///
+    /// **Pytorch equivalent**:
+    /// `torchvision.ops.roi_align(t, boxes, (PH, PW), spatial_scale, sampling_ratio, aligned)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::{prelude::*, tensor_ops::RoiAlignConfig};
+    /// # let dev: Cpu = Default::default();
+    /// let features: Tensor<Rank4<2, 8, 14, 14>> = dev.sample_normal();
+    /// // boxes in a 224x224 image, with a backbone stride of 16
+    /// let boxes: Tensor<Rank2<3, 5>> = dev.tensor([
+    ///     [0.0, 0.0, 0.0, 100.0, 80.0],
+    ///     [0.0, 50.0, 60.0, 200.0, 220.0],
+    ///     [1.0, 10.0, 10.0, 40.0, 40.0],
+    /// ]);
+    /// let cfg = RoiAlignConfig {
+    ///     spatial_scale: 1.0 / 16.0,
+    ///     aligned: true,
+    ///     ..Default::default()
+    /// };
+    /// let pooled: Tensor<Rank4<3, 8, 7, 7>> = features.roi_align::<7, 7, _>(boxes, cfg);
+    /// ```
+    ///
+    /// **Panics** on Cpu if a batch index is out of bounds.
+    pub fn roi_align<const PH: usize, const PW: usize, K: Dim>(
+        self,
+        boxes: Tensor<(K, Const<5>), f32, D>,
+        cfg: RoiAlignConfig,
+    ) -> Tensor<(K, C, Const<PH>, Const<PW>), f32, D, T> {
+        self.try_roi_align(boxes, cfg).unwrap()
+    }
+
+    /// Fallible version of [Tensor::roi_align()]
+    pub fn try_roi_align<const PH: usize, const PW: usize, K: Dim>(
+        self,
+        boxes: Tensor<(K, Const<5>), f32, D>,
+        cfg: RoiAlignConfig,
+    ) -> Result<Tensor<(K, C, Const<PH>, Const<PW>), f32, D, T>, D::Err> {
+        let (inp, mut tape) = self.split_tape();
+        let storage = inp.device.forward(cfg, &inp.storage, &boxes.storage)?;
+        let out = inp.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(cfg, grad_inp, &boxes.storage, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_roi_align_whole_image() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<1, 1, 4, 4>, f32, _> = dev.tensor([[[
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ]]]);
+        let boxes = dev.tensor([[0.0, 0.0, 0.0, 4.0, 4.0]]);
+        let cfg = RoiAlignConfig {
+            aligned: true,
+            sampling_ratio: 1,
+            ..Default::default()
+        };
+        let r = t.trace().roi_align::<2, 2, _>(boxes, cfg);
+        // each bin samples the center of a 2x2 block
+        assert_close(&r.array(), &[[[[3.5, 5.5], [11.5, 13.5]]]]);
+        let g = r.sum().backward();
+        assert_close(&g.get(&t).array(), &[[[[0.25; 4]; 4]]]);
+    }
+
+    #[test]
+    fn test_roi_align_batch_index_and_scale() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<2, 2, 3, 3>, f32, _> = dev.sample_normal();
+        let boxes = dev.tensor([[1.0, 2.0, 2.0, 4.0, 4.0], [0.0, 0.0, 0.0, 2.0, 2.0]]);
+        let cfg = RoiAlignConfig {
+            spatial_scale: 0.5,
+            sampling_ratio: 2,
+            aligned: false,
+        };
+        let r = t.trace().roi_align::<1, 1, _>(boxes, cfg);
+        let a = t.array();
+        // the boxes are [1, 1, 2, 2] & [0, 0, 1, 1] after scaling. The samples are at 1/4 &
+        // 3/4 of the way through, so their average weighs a 2x2 block of pixels equally
+        let mix = |n: usize, c: usize, y: usize, x: usize| {
+            (a[n][c][y][x] + a[n][c][y][x + 1] + a[n][c][y + 1][x] + a[n][c][y + 1][x + 1]) / 4.0
+        };
+        assert_close(
+            &r.array(),
+            &[
+                [[[mix(1, 0, 1, 1)]], [[mix(1, 1, 1, 1)]]],
+                [[[mix(0, 0, 0, 0)]], [[mix(0, 1, 0, 0)]]],
+            ],
+        );
+
+        let g = r.sum().backward();
+        let g = g.get(&t).array();
+        // only the pixels covered by the boxes get a gradient
+        assert_eq!(g[0][0][2], [0.0; 3]);
+        assert_eq!(g[1][0][0], [0.0; 3]);
+        let total: f32 = g.iter().flatten().flatten().flatten().sum();
+        assert_close(&total, &4.0);
+    }
+
+    #[test]
+    fn test_roi_align_adaptive_sampling() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<1, 1, 4, 4>, f32, _> = dev.sample_normal();
+        let boxes = dev.tensor([[0.0, 0.0, 0.0, 4.0, 4.0]]);
+        let cfg = RoiAlignConfig {
+            aligned: true,
+            ..Default::default()
+        };
+        // the samples land on the pixel centers, so a 1x1 output is the mean
+        let r = t.clone().roi_align::<1, 1, _>(boxes.clone(), cfg);
+        assert_close(
+            &r.array()[0][0][0][0],
+            &t.clone().mean::<Rank0, _>().array(),
+        );
+        let r = t.clone().roi_align::<4, 4, _>(boxes, cfg);
+        assert_close(&r.array(), &t.array());
+    }
+}
@@ -0,0 +1,152 @@
+struct RoiAlignConfig {
+    float spatial_scale;
+    size_t sampling_ratio;
+    bool aligned;
+};
+
+// Accumulates the bilinear weights of the 4 neighbors of (y, x) into `weights`
+// and their strided offsets into `offsets`, like torchvision's roi_align.
+// Returns false if the point is too far outside of the image.
+__device__ bool bilinear(
+    float y,
+    float x,
+    const size_t H,
+    const size_t W,
+    const size_t *strides,
+    float *weights,
+    unsigned int *offsets
+) {
+    if (y < -1.0 || y > H || x < -1.0 || x > W) {
+        return false;
+    }
+    y = fmaxf(y, 0.0);
+    x = fmaxf(x, 0.0);
+    unsigned int y0 = (unsigned int)y, x0 = (unsigned int)x, y1, x1;
+    if (y0 >= H - 1) {
+        y0 = y1 = H - 1;
+        y = (float)y0;
+    } else {
+        y1 = y0 + 1;
+    }
+    if (x0 >= W - 1) {
+        x0 = x1 = W - 1;
+        x = (float)x0;
+    } else {
+        x1 = x0 + 1;
+    }
+    float ly = y - y0, lx = x - x0;
+    float hy = 1.0 - ly, hx = 1.0 - lx;
+    weights[0] = hy * hx;
+    weights[1] = hy * lx;
+    weights[2] = ly * hx;
+    weights[3] = ly * lx;
+    offsets[0] = y0 * strides[2] + x0 * strides[3];
+    offsets[1] = y0 * strides[2] + x1 * strides[3];
+    offsets[2] = y1 * strides[2] + x0 * strides[3];
+    offsets[3] = y1 * strides[2] + x1 * strides[3];
+    return true;
+}
+
+// One thread per output element (k, c, ph, pw). Either reads `inp` into `out`,
+// or scatters `grad_out` into `grad_inp` when `backward` is set.
+__device__ void roi_align(
+    const RoiAlignConfig op,
+    const bool backward,
+    const size_t numel,
+    const size_t *inp_dims,
+    const size_t *inp_strides,
+    const float *boxes,
+    const size_t *box_strides,
+    const size_t *out_dims,
+    const size_t *out_strides,
+    const float *inp,
+    float *grad_inp,
+    float *out,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    const size_t PH = out_dims[2], PW = out_dims[3];
+    const size_t H = inp_dims[2], W = inp_dims[3];
+    if (H == 0 || W == 0) {
+        return;
+    }
+    unsigned int pw = i % PW;
+    unsigned int ph = (i / PW) % PH;
+    unsigned int c = (i / (PW * PH)) % out_dims[1];
+    unsigned int k = i / (PW * PH * out_dims[1]);
+
+    const float *roi = boxes + k * box_strides[0];
+    unsigned int b = (unsigned int)roi[0];
+    float offset = op.aligned ? 0.5 : 0.0;
+    float start_x = roi[1 * box_strides[1]] * op.spatial_scale - offset;
+    float start_y = roi[2 * box_strides[1]] * op.spatial_scale - offset;
+    float roi_w = roi[3 * box_strides[1]] * op.spatial_scale - offset - start_x;
+    float roi_h = roi[4 * box_strides[1]] * op.spatial_scale - offset - start_y;
+    if (!op.aligned) {
+        roi_w = fmaxf(roi_w, 1.0);
+        roi_h = fmaxf(roi_h, 1.0);
+    }
+    float bin_h = roi_h / PH, bin_w = roi_w / PW;
+    unsigned int grid_h = op.sampling_ratio > 0 ? op.sampling_ratio : (unsigned int)ceilf(roi_h / PH);
+    unsigned int grid_w = op.sampling_ratio > 0 ? op.sampling_ratio : (unsigned int)ceilf(roi_w / PW);
+    float count = fmaxf((float)(grid_h * grid_w), 1.0);
+
+    unsigned int base = b * inp_strides[0] + c * inp_strides[1];
+    unsigned int out_i = k * out_strides[0] + c * out_strides[1] + ph * out_strides[2] + pw * out_strides[3];
+    float g = backward ? grad_out[out_i] / count : 0.0;
+    float v = 0.0;
+    float weights[4];
+    unsigned int offsets[4];
+    for (unsigned int iy = 0; iy < grid_h; iy++) {
+        float y = start_y + ph * bin_h + (iy + 0.5) * bin_h / grid_h;
+        for (unsigned int ix = 0; ix < grid_w; ix++) {
+            float x = start_x + pw * bin_w + (ix + 0.5) * bin_w / grid_w;
+            if (!bilinear(y, x, H, W, inp_strides, weights, offsets)) {
+                continue;
+            }
+            for (int j = 0; j < 4; j++) {
+                if (backward) {
+                    atomicAdd(grad_inp + base + offsets[j], weights[j] * g);
+                } else {
+                    v += weights[j] * inp[base + offsets[j]];
+                }
+            }
+        }
+    }
+    if (!backward) {
+        out[i] = v / count;
+    }
+}
+
+extern "C" __global__ void roi_align_forward(
+    const RoiAlignConfig op,
+    const size_t numel,
+    const size_t *inp_dims,
+    const size_t *inp_strides,
+    const float *boxes,
+    const size_t *box_strides,
+    const size_t *out_dims,
+    const size_t *out_strides,
+    const float *inp,
+    float *out
+) {
+    roi_align(op, false, numel, inp_dims, inp_strides, boxes, box_strides, out_dims, out_strides, inp, NULL, out, NULL);
+}
+
+extern "C" __global__ void roi_align_backward(
+    const RoiAlignConfig op,
+    const size_t numel,
+    const size_t *inp_dims,
+    const size_t *inp_strides,
+    const float *boxes,
+    const size_t *box_strides,
+    const size_t *out_dims,
+    const size_t *out_strides,
+    float *grad_inp,
+    const float *grad_out
+) {
+    roi_align(op, true, numel, inp_dims, inp_strides, boxes, box_strides, out_dims, out_strides, NULL, grad_inp, NULL, grad_out);
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::RoundKernelOp {}
+
+impl UnaryOpCudaKernel for super::RoundKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/round.ptx"));
+    const MODULE_NAME: &'static str = "round";
+    const FWD_FN_NAME: &'static str = "round_forward";
+    const BWD_FN_NAME: &'static str = "round_backward";
+}
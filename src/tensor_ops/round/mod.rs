@@ -0,0 +1,57 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RoundKernelOp;
+
+/// Rounds to the nearest integer, rounding half-way cases away from `0.0`.
+///
+/// **NOTE** pytorch rounds half-way cases to the nearest even integer.
+///
+/// The derivative is `0`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.5, 0.4, 2.5]);
+/// let r = t.round();
+/// assert_eq!(r.array(), [-2.0, 0.0, 3.0]);
+/// ```
+pub fn round<S: Shape, E: Dtype, D: UnaryKernel<RoundKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.round()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<RoundKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [round]
+    pub fn round(self) -> Self {
+        self.try_round().unwrap()
+    }
+    /// See [round]
+    pub fn try_round(self) -> Result<Self, D::Err> {
+        try_unary_op(RoundKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_round() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.5, -1.2, 0.5, 1.7]);
+        let r = x.trace().round();
+        assert_eq!(r.array(), [-2.0, -1.0, 1.0, 2.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 4]);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl UnaryDerivative<f32> for super::RsqrtKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        x.sqrt().recip()
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        -0.5 / (x * x.sqrt())
+    }
+}
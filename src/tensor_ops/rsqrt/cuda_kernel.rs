@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::RsqrtKernelOp {}
+
+impl UnaryOpCudaKernel for super::RsqrtKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/rsqrt.ptx"));
+    const MODULE_NAME: &'static str = "rsqrt";
+    const FWD_FN_NAME: &'static str = "rsqrt_forward";
+    const BWD_FN_NAME: &'static str = "rsqrt_backward";
+}
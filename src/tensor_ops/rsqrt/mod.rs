@@ -0,0 +1,58 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RsqrtKernelOp;
+
+/// `1 / √t`
+///
+/// The derivative is `-0.5 / (t * √t)`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([0.25, 1.0, 4.0]);
+/// let r = t.rsqrt();
+/// assert_eq!(r.array(), [2.0, 1.0, 0.5]);
+/// ```
+pub fn rsqrt<S: Shape, E: Dtype, D: UnaryKernel<RsqrtKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.rsqrt()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<RsqrtKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [rsqrt]
+    pub fn rsqrt(self) -> Self {
+        self.try_rsqrt().unwrap()
+    }
+    /// See [rsqrt]
+    pub fn try_rsqrt(self) -> Result<Self, D::Err> {
+        try_unary_op(RsqrtKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_rsqrt() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.0, 0.0, 0.25, 4.0]);
+        let r = x.trace().rsqrt();
+        assert!(r.array()[0].is_nan());
+        assert_eq!(r.array()[1..], [f32::INFINITY, 2.0, 0.5]);
+        let g = r.mean().backward();
+        let g = g.get(&x).array();
+        assert!(g[0].is_nan());
+        assert_eq!(g[1..], [f32::NEG_INFINITY, -1.0, -0.015625]);
+    }
+}
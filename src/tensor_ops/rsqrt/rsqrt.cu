@@ -0,0 +1,29 @@
+struct RsqrtKernelOp {};
+
+extern "C" __global__ void rsqrt_forward(
+    const RsqrtKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    out[i] = rsqrtf(inp[i]);
+}
+
+extern "C" __global__ void rsqrt_backward(
+    const RsqrtKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float dx = -0.5 * rsqrtf(inp[i]) / inp[i];
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,80 @@
+use crate::shapes::{Dim, Shape};
+use crate::tensor::cpu::{Cpu, LendingIterator, StridedArray};
+use alloc::vec;
+use std::vec::Vec;
+
+/// The number of entries in each segment, or `None` for a plain sum.
+fn segment_counts<Z: Dim>(
+    idx: &StridedArray<(Z,), usize>,
+    num_segments: usize,
+    mean: bool,
+) -> Option<Vec<f32>> {
+    mean.then(|| {
+        let mut counts = vec![0.0; num_segments];
+        for j in 0..idx.shape.0.size() {
+            let s = idx[[j]];
+            assert!(
+                s < num_segments,
+                "Index {s} is out of bounds for {num_segments} segments"
+            );
+            counts[s] += 1.0;
+        }
+        counts
+    })
+}
+
+impl super::SegmentReduceKernel<f32> for Cpu {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Z: Dim>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        idx: &Self::Storage<(Z,), usize>,
+        dst: Dst,
+        ax: usize,
+        mean: bool,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err> {
+        let mut out = StridedArray::new(dst)?;
+        if inp.shape.num_elements() == 0 {
+            return Ok(out);
+        }
+        let num_segments = dst.concrete()[ax];
+        let counts = segment_counts(idx, num_segments, mean);
+        let mut inp_iter = inp.iter_with_index();
+        while let Some((x, mut i)) = inp_iter.next() {
+            let s = idx[[i[ax]]];
+            assert!(
+                s < num_segments,
+                "Index {s} is out of bounds for {num_segments} segments"
+            );
+            i[ax] = s;
+            out[i] += match &counts {
+                Some(counts) => *x / counts[s],
+                None => *x,
+            };
+        }
+        Ok(out)
+    }
+
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Z: Dim>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        idx: &Self::Storage<(Z,), usize>,
+        grad_out: &Self::Storage<Dst, f32>,
+        ax: usize,
+        mean: bool,
+    ) -> Result<(), Self::Err> {
+        if grad_inp.shape.num_elements() == 0 {
+            return Ok(());
+        }
+        let counts = segment_counts(idx, grad_out.shape.concrete()[ax], mean);
+        let mut inp_iter = grad_inp.iter_mut_with_index();
+        while let Some((g, mut i)) = inp_iter.next() {
+            let s = idx[[i[ax]]];
+            i[ax] = s;
+            *g += match &counts {
+                Some(counts) => grad_out[i] / counts[s],
+                None => grad_out[i],
+            };
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    shapes::{Dim, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/segment_reduce.ptx"));
+const MODULE_NAME: &str = "segment_reduce";
+const COUNTS_FN_NAME: &str = "segment_counts";
+const FWD_FN_NAME: &str = "segment_reduce_forward";
+const BWD_FN_NAME: &str = "segment_reduce_backward";
+const ALL_FN_NAMES: [&str; 3] = [COUNTS_FN_NAME, FWD_FN_NAME, BWD_FN_NAME];
+
+impl Cuda {
+    /// The number of entries in each segment for means, or all zeros for sums.
+    fn segment_counts<Z: Dim>(
+        &self,
+        idx: &CudaArray<(Z,), usize>,
+        num_segments: usize,
+        mean: bool,
+    ) -> Result<CudaSlice<f32>, <Self as crate::tensor::DeviceStorage>::Err> {
+        let mut counts = self.dev.alloc_zeros_async::<f32>(num_segments)?;
+        let numel = idx.shape.0.size();
+        if mean && numel > 0 {
+            let counts_fn = self.dev.get_func(MODULE_NAME, COUNTS_FN_NAME).unwrap();
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                numel,             // const size_t numel,
+                idx.data.as_ref(), // const size_t *idx,
+                idx.strides[0],    // const size_t idx_stride,
+                &mut counts,       // float *counts
+            );
+            unsafe { counts_fn.launch_async(cfg, params) }?;
+        }
+        Ok(counts)
+    }
+}
+
+impl super::SegmentReduceKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Z: Dim>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        idx: &Self::Storage<(Z,), usize>,
+        dst: Dst,
+        ax: usize,
+        mean: bool,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(dst.num_elements())?;
+        let numel = inp.shape.num_elements();
+        if numel > 0 {
+            let counts = self.segment_counts(idx, dst.concrete()[ax], mean)?;
+            let inp_dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+            let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+            let out_strides: CudaSlice<usize> = self.dev.take_async(dst.strides().into())?;
+
+            let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                numel,             // const size_t numel,
+                Src::NUM_DIMS,     // const size_t num_dims,
+                &inp_dims,         // const size_t *inp_dims,
+                ax,                // const size_t ax,
+                idx.data.as_ref(), // const size_t *idx,
+                idx.strides[0],    // const size_t idx_stride,
+                &counts,           // const float *counts,
+                inp.data.as_ref(), // const float *inp,
+                &inp_strides,      // const size_t *inp_strides,
+                &mut storage,      // float *out,
+                &out_strides,      // const size_t *out_strides
+            );
+            unsafe { fwd_fn.launch_async(cfg, params) }?;
+        }
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Z: Dim>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        idx: &Self::Storage<(Z,), usize>,
+        grad_out: &Self::Storage<Dst, f32>,
+        ax: usize,
+        mean: bool,
+    ) -> Result<(), Self::Err> {
+        let numel = grad_inp.shape.num_elements();
+        if numel == 0 {
+            return Ok(());
+        }
+
+        let counts = self.segment_counts(idx, grad_out.shape.concrete()[ax], mean)?;
+        let inp_dims: CudaSlice<usize> = self.dev.take_async(grad_inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            Src::NUM_DIMS,                     // const size_t num_dims,
+            &inp_dims,                         // const size_t *inp_dims,
+            ax,                                // const size_t ax,
+            idx.data.as_ref(),                 // const size_t *idx,
+            idx.strides[0],                    // const size_t idx_stride,
+            &counts,                           // const float *counts,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &out_strides,                      // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+pub trait SegmentReduceKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Z: Dim>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        idx: &Self::Storage<(Z,), usize>,
+        dst: Dst,
+        ax: usize,
+        mean: bool,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>, Z: Dim>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        idx: &Self::Storage<(Z,), usize>,
+        grad_out: &Self::Storage<Dst, E>,
+        ax: usize,
+        mean: bool,
+    ) -> Result<(), Self::Err>;
+}
+
+impl<S: Shape, E: Dtype, D: SegmentReduceKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// Sums the entries along axis `Ax` into `num_segments` segments, where entry `i`
+    /// is added to segment `idx[i]`. Segments that no entry maps to are `0`. This is the
+    /// reverse of [Tensor::index_select()], and is how messages are aggregated in graph
+    /// neural networks, with `idx` holding the destination node of each edge.
+    ///
+    /// `idx` does not need to be sorted, and must have the same size as the dimension at `Ax`.
+    ///
+    /// **Pytorch equivalent**: `torch.zeros(...).index_add_(Ax, idx, t)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<4, 2>> = dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0], [7.0, 8.0]]);
+    /// let r: Tensor<Rank2<3, 2>> = t.segment_sum::<Axis<0>, _, _>(dev.tensor([2, 0, 2, 2]), Const);
+    /// assert_eq!(r.array(), [[3.0, 4.0], [0.0, 0.0], [13.0, 16.0]]);
+    /// ```
+    ///
+    /// **Panics** if the size of `idx` doesn't match the dimension at `Ax`,
+    /// and on Cpu if any index is out of bounds of `num_segments`.
+    pub fn segment_sum<Ax: Axes<Array = [isize; 1]>, Z: Dim, N: Dim>(
+        self,
+        idx: Tensor<(Z,), usize, D>,
+        num_segments: N,
+    ) -> Tensor<S::Replaced, E, D, T>
+    where
+        S: ReplaceAxis<Ax, N>,
+    {
+        self.try_segment_sum::<Ax, Z, N>(idx, num_segments).unwrap()
+    }
+
+    /// Fallible version of [Tensor::segment_sum]
+    pub fn try_segment_sum<Ax: Axes<Array = [isize; 1]>, Z: Dim, N: Dim>(
+        self,
+        idx: Tensor<(Z,), usize, D>,
+        num_segments: N,
+    ) -> Result<Tensor<S::Replaced, E, D, T>, D::Err>
+    where
+        S: ReplaceAxis<Ax, N>,
+    {
+        self.try_segment_reduce::<Ax, Z, N>(idx, num_segments, false)
+    }
+
+    /// Averages the entries along axis `Ax` into `num_segments` segments, where entry `i`
+    /// belongs to segment `idx[i]`. Segments that no entry maps to are `0`.
+    ///
+    /// See [Tensor::segment_sum()] for more details.
+    ///
+    /// Here is a mean aggregation over the incoming edges of each node, like in
+    /// GraphSAGE:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let nodes: Tensor<Rank2<3, 2>> = dev.tensor([[1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+    /// // edges 0->1, 2->1, 1->0
+    /// let src = dev.tensor([0, 2, 1]);
+    /// let dst = dev.tensor([1, 1, 0]);
+    /// let messages = nodes.index_select::<Axis<0>, _>(src);
+    /// let r: Tensor<Rank2<3, 2>> = messages.segment_mean::<Axis<0>, _, _>(dst, Const);
+    /// assert_eq!(r.array(), [[0.0, 1.0], [1.0, 0.5], [0.0, 0.0]]);
+    /// ```
+    pub fn segment_mean<Ax: Axes<Array = [isize; 1]>, Z: Dim, N: Dim>(
+        self,
+        idx: Tensor<(Z,), usize, D>,
+        num_segments: N,
+    ) -> Tensor<S::Replaced, E, D, T>
+    where
+        S: ReplaceAxis<Ax, N>,
+    {
+        self.try_segment_mean::<Ax, Z, N>(idx, num_segments)
+            .unwrap()
+    }
+
+    /// Fallible version of [Tensor::segment_mean]
+    pub fn try_segment_mean<Ax: Axes<Array = [isize; 1]>, Z: Dim, N: Dim>(
+        self,
+        idx: Tensor<(Z,), usize, D>,
+        num_segments: N,
+    ) -> Result<Tensor<S::Replaced, E, D, T>, D::Err>
+    where
+        S: ReplaceAxis<Ax, N>,
+    {
+        self.try_segment_reduce::<Ax, Z, N>(idx, num_segments, true)
+    }
+
+    fn try_segment_reduce<Ax: Axes<Array = [isize; 1]>, Z: Dim, N: Dim>(
+        self,
+        idx: Tensor<(Z,), usize, D>,
+        num_segments: N,
+        mean: bool,
+    ) -> Result<Tensor<S::Replaced, E, D, T>, D::Err>
+    where
+        S: ReplaceAxis<Ax, N>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        assert_eq!(
+            self.shape().concrete()[ax],
+            idx.shape().0.size(),
+            "Segment indices must have the same size as axis {ax}"
+        );
+        let dst = self.shape().replace_axis(num_segments);
+        let (inp, mut tape) = self.split_tape();
+        let out =
+            inp.device.upgrade(
+                inp.device
+                    .forward(&inp.storage, &idx.storage, dst, ax, mean)?,
+            );
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device
+                .backward(grad_inp, &idx.storage, grad_out, ax, mean)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{assert_close, TestDevice};
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    #[test]
+    fn test_segment_sum_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0, 4.0]);
+        let r = t
+            .trace()
+            .segment_sum::<Axis<0>, _, _>(dev.tensor([1, 1, 0, 1]), Const::<3>);
+        assert_eq!(r.array(), [3.0, 7.0, 0.0]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [2.0, 2.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_segment_sum_matches_index_select_transpose() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        let ta = t.array();
+        let r = t
+            .trace()
+            .segment_sum::<Axis<1>, _, _>(dev.tensor([3, 0, 3, 1, 3]), Const::<4>);
+        let ra = r.array();
+        for b in 0..2 {
+            for c in 0..3 {
+                assert_close(&ra[b][0][c], &ta[b][1][c]);
+                assert_close(&ra[b][1][c], &ta[b][3][c]);
+                assert_eq!(ra[b][2][c], 0.0);
+                assert_close(&ra[b][3][c], &(ta[b][0][c] + ta[b][2][c] + ta[b][4][c]));
+            }
+        }
+
+        // gradient of a segment sum is an index select of the output gradient
+        let w: Tensor<Rank3<2, 4, 3>, f32, _> = dev.sample_normal();
+        let g = (r * w.clone()).sum().backward();
+        let expected = w.index_select::<Axis<1>, _>(dev.tensor([3, 0, 3, 1, 3]));
+        assert_close(&g.get(&t).array(), &expected.array());
+    }
+
+    #[test]
+    fn test_segment_mean() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let r = t
+            .trace()
+            .segment_mean::<Axis<0>, _, _>(dev.tensor([0, 2, 0]), Const::<4>);
+        assert_eq!(r.array(), [[3.0, 4.0], [0.0, 0.0], [3.0, 4.0], [0.0, 0.0]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[0.5; 2], [1.0; 2], [0.5; 2]]);
+    }
+
+    #[test]
+    fn test_segment_mean_runtime_segments() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize, Const<2>), f32, _> = dev.zeros_like(&(4, Const));
+        let t = t + 1.0;
+        let idx: Tensor<(usize,), usize, _> = dev.zeros_like(&(4,));
+        let r = t.trace().segment_mean::<Axis<0>, _, _>(idx, 2);
+        assert_eq!(r.shape(), &(2, Const::<2>));
+        assert_eq!(r.as_vec(), [1.0, 1.0, 0.0, 0.0]);
+        let g = r.exp().sum().backward();
+        assert_close(&g.get(&t).as_vec(), &alloc::vec![1f32.exp() / 4.0; 8]);
+    }
+
+    #[test]
+    fn test_segment_sum_broadcasted_input() {
+        let dev: TestDevice = Default::default();
+        let v = dev.tensor([1.0, 2.0]);
+        let r = v
+            .trace()
+            .broadcast::<Rank2<3, 2>, _>()
+            .segment_sum::<Axis<0>, _, _>(dev.tensor([1, 1, 0]), Const::<2>);
+        assert_eq!(r.array(), [[1.0, 2.0], [2.0, 4.0]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&v).array(), [3.0, 3.0]);
+    }
+
+    #[test]
+    #[should_panic = "Segment indices must have the same size as axis 0"]
+    fn test_segment_sum_wrong_idx_len() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        let _ = t.segment_sum::<Axis<0>, _, _>(dev.tensor([0, 0]), Const::<2>);
+    }
+}
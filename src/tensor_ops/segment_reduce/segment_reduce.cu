@@ -0,0 +1,92 @@
+// Computes the strided index into `inp` & `out` at the same time, replacing
+// the index along `ax` with the segment looked up in `idx`.
+__device__ void get_strided_indices(
+    unsigned int i,
+    const size_t num_dims,
+    const size_t *inp_dims,
+    const size_t ax,
+    const size_t *idx,
+    const size_t idx_stride,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    unsigned int *inp_i,
+    unsigned int *out_i,
+    unsigned int *seg
+) {
+    *inp_i = 0;
+    *out_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        unsigned int j = i % inp_dims[dim_idx];
+        i /= inp_dims[dim_idx];
+        *inp_i += j * inp_strides[dim_idx];
+        if (dim_idx == ax) {
+            j = idx[j * idx_stride];
+            *seg = j;
+        }
+        *out_i += j * out_strides[dim_idx];
+    }
+}
+
+// Counts the entries of each segment. Only used for means, a sum leaves all the
+// counts at 0, which are treated as 1 below.
+extern "C" __global__ void segment_counts(
+    const size_t numel,
+    const size_t *idx,
+    const size_t idx_stride,
+    float *counts
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    atomicAdd(counts + idx[i * idx_stride], 1.0);
+}
+
+extern "C" __global__ void segment_reduce_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *inp_dims,
+    const size_t ax,
+    const size_t *idx,
+    const size_t idx_stride,
+    const float *counts,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i, out_i, seg;
+    get_strided_indices(i, num_dims, inp_dims, ax, idx, idx_stride, inp_strides, out_strides, &inp_i, &out_i, &seg);
+
+    atomicAdd(out + out_i, inp[inp_i] / fmaxf(counts[seg], 1.0));
+}
+
+extern "C" __global__ void segment_reduce_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *inp_dims,
+    const size_t ax,
+    const size_t *idx,
+    const size_t idx_stride,
+    const float *counts,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i, out_i, seg;
+    get_strided_indices(i, num_dims, inp_dims, ax, idx, idx_stride, inp_strides, out_strides, &inp_i, &out_i, &seg);
+
+    atomicAdd(grad_inp + inp_i, grad_out[out_i] / fmaxf(counts[seg], 1.0));
+}
@@ -100,7 +100,7 @@ impl<Src: Shape, E: Dtype, D: RemoveDimKernel<E>, T: Tape<D>> SelectTo<D> for Te
         let out = inp.device.upgrade(storage);
         let phantom_out = out.clone();
         tape.try_alloc_grad(&inp)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
             inp.device.backward(grad_inp, &idx.storage, grad_out)
@@ -169,7 +169,7 @@ impl<Src: Shape, E: Dtype, D: ReplaceDimKernel<E>, T: Tape<D>> GatherTo<D>
         let out = inp.device.upgrade(storage);
         let phantom_out = out.clone();
         tape.try_alloc_grad(&inp)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
             inp.device.backward(grad_inp, &idx.storage, grad_out)
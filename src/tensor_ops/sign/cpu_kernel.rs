@@ -0,0 +1,18 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+
+impl UnaryDerivative<f32> for super::SignKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        if *x > 0.0 {
+            1.0
+        } else if *x < 0.0 {
+            -1.0
+        } else {
+            *x
+        }
+    }
+    #[inline(always)]
+    fn df(&self, _x: &f32) -> f32 {
+        0.0
+    }
+}
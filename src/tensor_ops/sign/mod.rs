@@ -0,0 +1,57 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SignKernelOp;
+
+/// `1` for positive values, `-1` for negative values, and `0` for zeros.
+/// NaNs are kept as NaN.
+///
+/// The derivative is `0`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-2.0, 0.0, 3.0]);
+/// let r = t.sign();
+/// assert_eq!(r.array(), [-1.0, 0.0, 1.0]);
+/// ```
+pub fn sign<S: Shape, E: Dtype, D: UnaryKernel<SignKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.sign()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<SignKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [sign]
+    pub fn sign(self) -> Self {
+        self.try_sign().unwrap()
+    }
+    /// See [sign]
+    pub fn try_sign(self) -> Result<Self, D::Err> {
+        try_unary_op(SignKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::TestDevice};
+
+    #[test]
+    fn test_sign() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.0, 0.0, 0.5, f32::NAN]);
+        let r = x.trace().sign();
+        assert_eq!(r.array()[..4], [-1.0, 0.0, 0.0, 1.0]);
+        assert!(r.array()[4].is_nan());
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 5]);
+    }
+}
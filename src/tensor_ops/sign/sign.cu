@@ -0,0 +1,29 @@
+struct SignKernelOp {};
+
+extern "C" __global__ void sign_forward(
+    const SignKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    out[i] = inp[i] > 0.0 ? 1.0 : (inp[i] < 0.0 ? -1.0 : inp[i]);
+}
+
+extern "C" __global__ void sign_backward(
+    const SignKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float dx = 0.0;
+    grad_inp[i] += dx * grad_out[i];
+}
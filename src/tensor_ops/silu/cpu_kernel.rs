@@ -0,0 +1,15 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl UnaryDerivative<f32> for super::SiLUKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        x / (1.0 + (-x).exp())
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        let s = 1.0 / (1.0 + (-x).exp());
+        s + x * s * (1.0 - s)
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::SiLUKernelOp {}
+
+impl UnaryOpCudaKernel for super::SiLUKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/silu.ptx"));
+    const MODULE_NAME: &'static str = "silu";
+    const FWD_FN_NAME: &'static str = "silu_forward";
+    const BWD_FN_NAME: &'static str = "silu_backward";
+}
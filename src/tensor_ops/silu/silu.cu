@@ -0,0 +1,32 @@
+struct SiLUKernelOp {};
+
+extern "C" __global__ void silu_forward(
+    const SiLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    out[i] = x / (1.0 + expf(-x));
+}
+
+extern "C" __global__ void silu_backward(
+    const SiLUKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float s = 1.0 / (1.0 + expf(-x));
+    float dx = s + x * s * (1.0 - s);
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,72 @@
+use crate::shapes::{Dtype, Shape};
+use crate::tensor::cpu::{Cpu, CpuBuffer, StridedArray};
+use std::sync::Arc;
+
+impl<E: Dtype> super::SliceKernel<E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        dst: Dst,
+        ax: usize,
+        start: usize,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err> {
+        // a view into the same buffer, starting at the first element of the slice
+        let strides = inp.strides;
+        let len = if dst.num_elements() == 0 {
+            0
+        } else {
+            let dims = dst.concrete();
+            1 + (0..Dst::NUM_DIMS)
+                .map(|i| (dims[i] - 1) * strides[i])
+                .sum::<usize>()
+        };
+        let offset = if len == 0 { 0 } else { start * strides[ax] };
+        Ok(StridedArray {
+            data: Arc::new(CpuBuffer::sub(&inp.data, offset, len)),
+            shape: dst,
+            strides,
+        })
+    }
+
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+        ax: usize,
+        start: usize,
+    ) -> Result<(), Self::Err> {
+        if grad_out.shape.num_elements() == 0 {
+            return Ok(());
+        }
+        // `grad_out` has the layout of the view, so each of its elements is at the same place
+        // in the buffer of `grad_inp`, after the offset of the view. Broadcasted axes hold the
+        // sum of their gradients in a single element, so they are only visited once.
+        let strides = grad_out.strides;
+        let mut dims = grad_out.shape.concrete();
+        for d in 0..Dst::NUM_DIMS {
+            if strides[d] == 0 {
+                dims[d] = 1;
+            }
+        }
+        let offset = start * grad_inp.strides[ax];
+        let grad_inp = grad_inp.data_mut();
+        let mut idx: Dst::Concrete = Default::default();
+        loop {
+            let i: usize = (0..Dst::NUM_DIMS).map(|d| idx[d] * strides[d]).sum();
+            grad_inp[offset + i] += grad_out.data[i];
+
+            let mut d = Dst::NUM_DIMS;
+            loop {
+                if d == 0 {
+                    return Ok(());
+                }
+                d -= 1;
+                idx[d] += 1;
+                if idx[d] < dims[d] {
+                    break;
+                }
+                idx[d] = 0;
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+use core::ops::{Bound, RangeBounds};
+
+pub trait SliceKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        dst: Dst,
+        ax: usize,
+        start: usize,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+        ax: usize,
+        start: usize,
+    ) -> Result<(), Self::Err>;
+}
+
+impl<S: Shape, E: Dtype, D: SliceKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// Selects the sub-range `range` along axis `Ax`. The sliced dimension
+    /// becomes a runtime sized [usize] dimension. During backward the gradient
+    /// is padded with zeros outside of `range`.
+    ///
+    /// On the [Cpu] the result is a view that shares the data of `self`, see
+    /// [crate::tensor#views].
+    ///
+    /// **Pytorch equivalent**: `t[:, range]` (for `Axis<1>`)
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 4>> = dev.tensor([[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]]);
+    /// let r: Tensor<(Const<2>, usize)> = t.clone().slice::<Axis<1>>(1..3);
+    /// assert_eq!(r.as_vec(), [2.0, 3.0, 6.0, 7.0]);
+    /// let r: Tensor<(usize, Const<4>)> = t.slice::<Axis<0>>(1..);
+    /// assert_eq!(r.as_vec(), [5.0, 6.0, 7.0, 8.0]);
+    /// ```
+    ///
+    /// **Panics** if `range` is out of bounds of the dimension.
+    pub fn slice<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        range: impl RangeBounds<usize>,
+    ) -> Tensor<S::Sliced, E, D, T>
+    where
+        S: SliceShape<Ax>,
+    {
+        self.try_slice::<Ax>(range).unwrap()
+    }
+
+    /// Fallible version of [Tensor::slice]
+    pub fn try_slice<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<Tensor<S::Sliced, E, D, T>, D::Err>
+    where
+        S: SliceShape<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let size = self.shape().concrete()[ax];
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => size,
+        };
+        assert!(
+            start <= end && end <= size,
+            "Slice {start}..{end} is out of bounds for axis {ax} with size {size}"
+        );
+        self.try_narrow::<Ax>(start, end - start)
+    }
+
+    /// Selects `len` elements along axis `Ax`, beginning at `start`.
+    /// Same as `t.slice::<Ax>(start..start + len)`, see [Tensor::slice].
+    ///
+    /// **Pytorch equivalent**: `t.narrow(Ax, start, len)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank1<5>> = dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// let r = t.narrow::<Axis<0>>(1, 3);
+    /// assert_eq!(r.as_vec(), [2.0, 3.0, 4.0]);
+    /// ```
+    ///
+    /// **Panics** if `start + len` is out of bounds of the dimension.
+    pub fn narrow<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        start: usize,
+        len: usize,
+    ) -> Tensor<S::Sliced, E, D, T>
+    where
+        S: SliceShape<Ax>,
+    {
+        self.try_narrow::<Ax>(start, len).unwrap()
+    }
+
+    /// Fallible version of [Tensor::narrow]
+    pub fn try_narrow<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        start: usize,
+        len: usize,
+    ) -> Result<Tensor<S::Sliced, E, D, T>, D::Err>
+    where
+        S: SliceShape<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let size = self.shape().concrete()[ax];
+        assert!(
+            start + len <= size,
+            "Slice {start}..{} is out of bounds for axis {ax} with size {size}",
+            start + len
+        );
+        let dst = self.shape().sliced(len);
+        let (inp, mut tape) = self.split_tape();
+        let out = inp
+            .device
+            .upgrade(inp.device.forward(&inp.storage, dst, ax, start)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(grad_inp, grad_out, ax, start)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{assert_close, TestDevice};
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    #[test]
+    fn test_slice_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0]);
+        let r = t.trace().slice::<Axis<0>>(1..4);
+        assert_eq!(r.shape(), &(3,));
+        assert_eq!(r.as_vec(), [2.0, 3.0, 4.0]);
+        let g = r.exp().sum().backward();
+        assert_close(
+            &g.get(&t).array(),
+            &[0.0, 2f32.exp(), 3f32.exp(), 4f32.exp(), 0.0],
+        );
+    }
+
+    #[test]
+    fn test_slice_is_view() {
+        let dev: crate::tensor::Cpu = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        let r = t.clone().slice::<Axis<1>>(1..);
+        assert!(r.storage.data.is_shared_view());
+        assert_eq!(r.storage.data.len(), 8);
+        let r2 = r.clone().narrow::<Axis<0>>(1, 2);
+        assert!(r2.storage.data.is_shared_view());
+        assert_eq!(r2.as_vec(), [5.0, 6.0, 8.0, 9.0]);
+
+        // ops on views work as usual, and writing to a view copies it
+        assert_eq!((r2.clone() * 2.0).as_vec(), [10.0, 12.0, 16.0, 18.0]);
+        let mut r3 = r2.clone();
+        r3.fill_with_zeros();
+        assert!(!r3.storage.data.is_shared_view());
+        assert_eq!(r3.as_vec(), [0.0; 4]);
+        assert_eq!(r2.as_vec(), [5.0, 6.0, 8.0, 9.0]);
+        assert_eq!(t.array()[2], [7.0, 8.0, 9.0]);
+
+        // the elements of the buffer between the rows of a view don't get gradients
+        let u = dev.tensor([[1.0, -1.0], [4.0, -1.0]]);
+        let g = u.trace().slice::<Axis<1>>(..1).sqrt().sum().backward();
+        assert_eq!(g.get(&u).array(), [[0.5, 0.0], [0.25, 0.0]]);
+
+        // contiguous slices can be reshaped without copying
+        let rows = t.narrow::<Axis<0>>(1, 2).reshape_like(&(6,));
+        assert!(rows.storage.data.is_shared_view());
+        assert_eq!(rows.as_vec(), [4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+    }
+
+    #[test]
+    fn test_slice_3d_middle_axis() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 4, 3>, f32, _> = dev.sample_normal();
+        let t_array = t.array();
+        let r = t.trace().slice::<Axis<1>>(2..=3);
+        assert_eq!(r.shape(), &(Const::<2>, 2, Const::<3>));
+        let mut expected = std::vec::Vec::new();
+        for b in t_array.iter() {
+            for row in b[2..4].iter() {
+                expected.extend_from_slice(row);
+            }
+        }
+        assert_eq!(r.as_vec(), expected);
+
+        let g = r.sum().backward();
+        let mut expected = [[[0.0; 3]; 4]; 2];
+        for b in expected.iter_mut() {
+            b[2] = [1.0; 3];
+            b[3] = [1.0; 3];
+        }
+        assert_eq!(g.get(&t).array(), expected);
+    }
+
+    #[test]
+    fn test_slice_permuted_and_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let r = t
+            .trace()
+            .permute::<Rank2<3, 2>, _>()
+            .narrow::<Axis<0>>(1, 2);
+        assert_eq!(r.as_vec(), [2.0, 5.0, 3.0, 6.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[0.0, 1.0, 1.0], [0.0, 1.0, 1.0]]);
+
+        let v = dev.tensor([1.0, 2.0, 3.0]);
+        let r = v
+            .trace()
+            .broadcast::<Rank2<4, 3>, _>()
+            .slice::<Axis<0>>(..2);
+        assert_eq!(r.as_vec(), [1.0, 2.0, 3.0, 1.0, 2.0, 3.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&v).array(), [2.0; 3]);
+    }
+
+    #[test]
+    fn test_slice_empty() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let r = t.slice::<Axis<0>>(3..);
+        assert_eq!(r.shape(), &(0,));
+    }
+
+    #[test]
+    #[should_panic = "Slice 2..4 is out of bounds for axis 0 with size 3"]
+    fn test_slice_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let _ = t.slice::<Axis<0>>(2..4);
+    }
+}
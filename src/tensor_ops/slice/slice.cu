@@ -0,0 +1,56 @@
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+extern "C" __global__ void slice_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *out_dims,
+    const size_t offset,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = offset + get_strided_index(i, num_dims, out_dims, inp_strides);
+    unsigned int out_i = get_strided_index(i, num_dims, out_dims, out_strides);
+
+    out[out_i] = inp[inp_i];
+}
+
+extern "C" __global__ void slice_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *out_dims,
+    const size_t offset,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = offset + get_strided_index(i, num_dims, out_dims, inp_strides);
+    unsigned int out_i = get_strided_index(i, num_dims, out_dims, out_strides);
+
+    atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+}
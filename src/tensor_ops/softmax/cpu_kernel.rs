@@ -0,0 +1,77 @@
+use crate::{
+    shapes::{Axes, Shape},
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::internal_reshapes::{permute_axes_last, reduced_len, strided_index},
+};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl super::SoftmaxKernel<f32> for Cpu {
+    fn forward<S: Shape, Ax: Axes>(
+        &self,
+        log: bool,
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        let mut out = StridedArray::new(inp.shape)?;
+        let (dims, strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), inp.strides);
+        let (_, out_strides) = permute_axes_last::<_, Ax>(out.shape.concrete(), out.strides);
+        let row_len = reduced_len::<Ax>(&dims);
+        let num_rows = inp.shape.num_elements() / row_len.max(1);
+
+        let out_data = out.data_mut();
+        for row in 0..num_rows {
+            let x = |j: usize| inp.data[strided_index(row * row_len + j, &dims, &strides)];
+
+            // subtracting the max keeps exp() from overflowing for large values
+            let max = (0..row_len).map(x).fold(f32::NEG_INFINITY, f32::max);
+            let sum: f32 = (0..row_len).map(|j| (x(j) - max).exp()).sum();
+            let lse = sum.ln() + max;
+            for j in 0..row_len {
+                let i = strided_index(row * row_len + j, &dims, &out_strides);
+                out_data[i] = if log {
+                    x(j) - lse
+                } else {
+                    (x(j) - max).exp() / sum
+                };
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<S: Shape, Ax: Axes>(
+        &self,
+        log: bool,
+        out: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let (dims, out_strides) = permute_axes_last::<_, Ax>(out.shape.concrete(), out.strides);
+        let (_, gi_strides) = permute_axes_last::<_, Ax>(out.shape.concrete(), grad_inp.strides);
+        let (_, go_strides) = permute_axes_last::<_, Ax>(out.shape.concrete(), grad_out.strides);
+        let row_len = reduced_len::<Ax>(&dims);
+        let num_rows = out.shape.num_elements() / row_len.max(1);
+
+        let grad_inp = grad_inp.data_mut();
+        for row in 0..num_rows {
+            let y = |j: usize| out.data[strided_index(row * row_len + j, &dims, &out_strides)];
+            let g = |j: usize| grad_out.data[strided_index(row * row_len + j, &dims, &go_strides)];
+            if log {
+                // grad_inp += grad_out - exp(out) * sum(grad_out)
+                let sum: f32 = (0..row_len).map(g).sum();
+                for j in 0..row_len {
+                    let i = strided_index(row * row_len + j, &dims, &gi_strides);
+                    grad_inp[i] += g(j) - y(j).exp() * sum;
+                }
+            } else {
+                // grad_inp += out * (grad_out - sum(grad_out * out))
+                let dot: f32 = (0..row_len).map(|j| g(j) * y(j)).sum();
+                for j in 0..row_len {
+                    let i = strided_index(row * row_len + j, &dims, &gi_strides);
+                    grad_inp[i] += y(j) * (g(j) - dot);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    shapes::{Axes, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+    tensor_ops::internal_reshapes::{permute_axes_last, reduced_len},
+};
+use cudarc::driver::{LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/softmax.ptx"));
+const MODULE_NAME: &str = "softmax";
+const ALL_FN_NAMES: [&str; 4] = [
+    "softmax_forward",
+    "log_softmax_forward",
+    "softmax_backward",
+    "log_softmax_backward",
+];
+
+impl super::SoftmaxKernel<f32> for Cuda {
+    fn forward<S: Shape, Ax: Axes>(
+        &self,
+        log: bool,
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, ALL_FN_NAMES[0]) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let (dims, strides) = permute_axes_last::<_, Ax>(inp.shape.concrete(), inp.strides);
+        let (_, out_strides) =
+            permute_axes_last::<_, Ax>(inp.shape.concrete(), inp.shape.strides());
+        let row_len = reduced_len::<Ax>(&dims);
+        let numel = inp.shape.num_elements();
+        let num_rows = numel / row_len.max(1);
+        let mut info: Vec<usize> = Vec::with_capacity(3 * S::NUM_DIMS);
+        info.extend(dims);
+        info.extend(strides);
+        info.extend(out_strides);
+        let info = self.dev.take_async(info)?;
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        let fn_name = if log {
+            "log_softmax_forward"
+        } else {
+            "softmax_forward"
+        };
+        let fwd_fn = self.dev.get_func(MODULE_NAME, fn_name).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,          // const size_t num_rows,
+            row_len,           // const size_t row_len,
+            S::NUM_DIMS,       // const size_t num_dims,
+            &info,             // const size_t *info,
+            inp.data.as_ref(), // const float *inp,
+            &mut storage,      // float *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: inp.shape,
+            strides: inp.shape.strides(),
+        })
+    }
+
+    fn backward<S: Shape, Ax: Axes>(
+        &self,
+        log: bool,
+        out: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let (dims, out_strides) = permute_axes_last::<_, Ax>(out.shape.concrete(), out.strides);
+        let (_, gi_strides) = permute_axes_last::<_, Ax>(out.shape.concrete(), grad_inp.strides);
+        let (_, go_strides) = permute_axes_last::<_, Ax>(out.shape.concrete(), grad_out.strides);
+        let row_len = reduced_len::<Ax>(&dims);
+        let num_rows = out.shape.num_elements() / row_len.max(1);
+        let mut info: Vec<usize> = Vec::with_capacity(4 * S::NUM_DIMS);
+        info.extend(dims);
+        info.extend(out_strides);
+        info.extend(gi_strides);
+        info.extend(go_strides);
+        let info = self.dev.take_async(info)?;
+
+        let fn_name = if log {
+            "log_softmax_backward"
+        } else {
+            "softmax_backward"
+        };
+        let bwd_fn = self.dev.get_func(MODULE_NAME, fn_name).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,                          // const size_t num_rows,
+            row_len,                           // const size_t row_len,
+            S::NUM_DIMS,                       // const size_t num_dims,
+            &info,                             // const size_t *info,
+            out.data.as_ref(),                 // const float *out,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
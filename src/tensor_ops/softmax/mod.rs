@@ -1,10 +1,38 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
 use super::Device;
-use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait SoftmaxKernel<E: Dtype>: DeviceStorage {
+    /// Computes the softmax of `inp` across `Ax`, or the log softmax if `log` is true.
+    fn forward<S: Shape, Ax: Axes>(
+        &self,
+        log: bool,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    /// Accumulates the gradient of the input, given the output of [SoftmaxKernel::forward].
+    fn backward<S: Shape, Ax: Axes>(
+        &self,
+        log: bool,
+        out: &Self::Storage<S, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
 
 /// Computes the [softmax function](https://en.wikipedia.org/wiki/Softmax_function) across
 /// `Ax`.
 ///
-/// Equivalent to `exp(log_softmax(t))`.
+/// Equivalent to `exp(log_softmax(t))`, but the forward and backward passes are each
+/// a single kernel.
 ///
 /// **Pytorch equivalent**: `t.softmax(Axes)`
 ///
@@ -37,7 +65,24 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
     where
         S: ReduceShape<Ax>,
     {
-        self.try_log_softmax::<Ax>()?.try_exp()
+        let (inp, mut tape) = self.split_tape();
+        let out = SoftmaxKernel::forward::<S, Ax>(&inp.device, false, &inp.storage)?;
+        let out = inp.device.upgrade(out);
+
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            SoftmaxKernel::backward::<S, Ax>(
+                &inp.device,
+                false,
+                &phantom_out.storage,
+                grad_inp,
+                grad_out,
+            )
+        });
+        Ok(out.put_tape(tape))
     }
 }
 
@@ -137,4 +182,36 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_softmax_broadcasted_input() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([-2.0, 0.5, 1.0]);
+        let w = dev.tensor([[1.0, 0.0, 2.0], [0.5, -1.0, 0.0]]);
+        let r = a.trace().broadcast::<Rank2<2, 3>, _>().softmax::<Axis<1>>();
+        let g = (r * w).sum().backward();
+
+        // both rows are the same softmax, so their gradients are summed into `a`
+        let r2 = a.trace().softmax();
+        let g2 = (r2 * dev.tensor([1.5, -1.0, 2.0])).sum().backward();
+        assert_close(&g.get(&a).array(), &g2.get(&a).array());
+    }
+
+    #[test]
+    fn test_softmax_large_logits() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1000.0, 1001.0], [-1000.0, -999.0]]);
+        let r = a.trace().softmax::<Axis<1>>();
+        assert_close_with_tolerance(
+            &r.array(),
+            &[[0.26894143, 0.7310586], [0.26894143, 0.7310586]],
+            1e-4,
+        );
+        let g = (r * dev.tensor([[1.0, 0.0], [0.0, 1.0]])).sum().backward();
+        assert_close_with_tolerance(
+            &g.get(&a).array(),
+            &[[0.19661193, -0.19661193], [-0.19661193, 0.19661193]],
+            1e-4,
+        );
+    }
 }
@@ -0,0 +1,134 @@
+__device__ unsigned int get_strided_index(
+    unsigned int idx,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides
+) {
+    unsigned int strided_i = 0;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        strided_i += (idx % dims[dim_idx]) * strides[dim_idx];
+        idx /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
+// `info` holds the dims, the strides of `inp`, and then the strides of `out`, with the
+// reduced axes moved to the end. One thread handles one row.
+__device__ void softmax_fwd(
+    const bool log,
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *inp,
+    float *out
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+    const size_t *dims = info;
+    const size_t *strides = info + num_dims;
+    const size_t *out_strides = info + 2 * num_dims;
+
+    // subtracting the max keeps exp() from overflowing for large values
+    float max = -INFINITY;
+    for (unsigned int j = 0; j < row_len; j++) {
+        max = fmaxf(max, inp[get_strided_index(row * row_len + j, num_dims, dims, strides)]);
+    }
+    float sum = 0.0;
+    for (unsigned int j = 0; j < row_len; j++) {
+        sum += expf(inp[get_strided_index(row * row_len + j, num_dims, dims, strides)] - max);
+    }
+    float lse = logf(sum) + max;
+    for (unsigned int j = 0; j < row_len; j++) {
+        unsigned int i = row * row_len + j;
+        float x = inp[get_strided_index(i, num_dims, dims, strides)];
+        out[get_strided_index(i, num_dims, dims, out_strides)] = log ? x - lse : expf(x - max) / sum;
+    }
+}
+
+// `info` holds the dims, the strides of `out`, `grad_inp`, and then the strides of `grad_out`,
+// with the reduced axes moved to the end. One thread handles one row.
+__device__ void softmax_bwd(
+    const bool log,
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *out,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+    const size_t *dims = info;
+    const size_t *out_strides = info + num_dims;
+    const size_t *gi_strides = info + 2 * num_dims;
+    const size_t *go_strides = info + 3 * num_dims;
+
+    // log_softmax: grad_inp += grad_out - exp(out) * sum(grad_out)
+    // softmax: grad_inp += out * (grad_out - sum(grad_out * out))
+    float sum = 0.0;
+    for (unsigned int j = 0; j < row_len; j++) {
+        unsigned int i = row * row_len + j;
+        float g = grad_out[get_strided_index(i, num_dims, dims, go_strides)];
+        sum += log ? g : g * out[get_strided_index(i, num_dims, dims, out_strides)];
+    }
+    for (unsigned int j = 0; j < row_len; j++) {
+        unsigned int i = row * row_len + j;
+        float y = out[get_strided_index(i, num_dims, dims, out_strides)];
+        float g = grad_out[get_strided_index(i, num_dims, dims, go_strides)];
+        float d = log ? g - expf(y) * sum : y * (g - sum);
+        atomicAdd(grad_inp + get_strided_index(i, num_dims, dims, gi_strides), d);
+    }
+}
+
+extern "C" __global__ void softmax_forward(
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *inp,
+    float *out
+) {
+    softmax_fwd(false, num_rows, row_len, num_dims, info, inp, out);
+}
+
+extern "C" __global__ void log_softmax_forward(
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *inp,
+    float *out
+) {
+    softmax_fwd(true, num_rows, row_len, num_dims, info, inp, out);
+}
+
+extern "C" __global__ void softmax_backward(
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *out,
+    float *grad_inp,
+    const float *grad_out
+) {
+    softmax_bwd(false, num_rows, row_len, num_dims, info, out, grad_inp, grad_out);
+}
+
+extern "C" __global__ void log_softmax_backward(
+    const size_t num_rows,
+    const size_t row_len,
+    const size_t num_dims,
+    const size_t *info,
+    const float *out,
+    float *grad_inp,
+    const float *grad_out
+) {
+    softmax_bwd(true, num_rows, row_len, num_dims, info, out, grad_inp, grad_out);
+}
@@ -0,0 +1,24 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+impl UnaryDerivative<f32> for super::SoftplusKernelOp<f32> {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        let bx = self.beta * x;
+        if bx > self.threshold {
+            *x
+        } else {
+            bx.exp().ln_1p() / self.beta
+        }
+    }
+    #[inline(always)]
+    fn df(&self, x: &f32) -> f32 {
+        let bx = self.beta * x;
+        if bx > self.threshold {
+            1.0
+        } else {
+            1.0 / (1.0 + (-bx).exp())
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::SoftplusKernelOp<f32> {}
+
+impl UnaryOpCudaKernel for super::SoftplusKernelOp<f32> {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/softplus.ptx"));
+    const MODULE_NAME: &'static str = "softplus";
+    const FWD_FN_NAME: &'static str = "softplus_forward";
+    const BWD_FN_NAME: &'static str = "softplus_backward";
+}
@@ -0,0 +1,69 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SoftplusKernelOp<E> {
+    pub beta: E,
+    pub threshold: E,
+}
+
+/// [Softplus](https://paperswithcode.com/method/softplus). `ln(1 + e^(beta * t)) / beta`, which is a smooth approximation
+/// of [relu()]. For numerical stability this is `t` when `beta * t > threshold`.
+///
+/// The derivative is `sigmoid(beta * t)`.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.softplus(t, beta, threshold)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 30.0]);
+/// let r = t.softplus(1.0, 20.0);
+/// assert_eq!(r.array()[3], 30.0);
+/// ```
+pub fn softplus<S: Shape, E: Dtype, D: UnaryKernel<SoftplusKernelOp<E>, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    beta: E,
+    threshold: E,
+) -> Tensor<S, E, D, T> {
+    t.softplus(beta, threshold)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<SoftplusKernelOp<E>, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [softplus]
+    pub fn softplus(self, beta: E, threshold: E) -> Self {
+        self.try_softplus(beta, threshold).unwrap()
+    }
+    /// See [softplus]
+    pub fn try_softplus(self, beta: E, threshold: E) -> Result<Self, D::Err> {
+        try_unary_op(SoftplusKernelOp { beta, threshold }, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_softplus() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.5, 0.0, 1.0, 3.0]);
+        let r = x.trace().softplus(2.0, 4.0);
+        assert_close(
+            &r.array(),
+            &[0.009074964, 0.15663084, 0.3465736, 1.063464, 3.0],
+        );
+        let g = r.mean().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &[0.003597242, 0.053788286, 0.1, 0.17615943, 0.2],
+        );
+    }
+}
@@ -0,0 +1,36 @@
+struct SoftplusKernelOp {
+    float beta;
+    float threshold;
+};
+
+extern "C" __global__ void softplus_forward(
+    const SoftplusKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float bx = op.beta * x;
+    out[i] = bx > op.threshold ? x : log1pf(expf(bx)) / op.beta;
+}
+
+extern "C" __global__ void softplus_backward(
+    const SoftplusKernelOp op,
+    const size_t numel,
+    const float *inp,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+    float x = inp[i];
+    float bx = op.beta * x;
+    float dx = bx > op.threshold ? 1.0 : 1.0 / (1.0 + expf(-bx));
+    grad_inp[i] += dx * grad_out[i];
+}
@@ -0,0 +1,63 @@
+use crate::{
+    shapes::{Dim, Dtype},
+    tensor::{cpu::Cpu, CsrTensor, StridedArray},
+};
+
+impl<E: Dtype> super::SparseMatMulKernel<E> for Cpu {
+    fn forward<M: Dim, N: Dim, K: Dim>(
+        &self,
+        lhs: &CsrTensor<M, N, E, Self>,
+        rhs: &Self::Storage<(N, K), E>,
+    ) -> Result<Self::Storage<(M, K), E>, Self::Err> {
+        let (m, k) = (lhs.shape.0, rhs.shape.1);
+        let mut out = StridedArray::new((m, k))?;
+        let row_ptr = lhs.row_ptr.storage.data();
+        let col_idx = lhs.col_idx.storage.data();
+        let values = lhs.values.storage.data();
+        let [rs0, rs1] = rhs.strides;
+        let (k, rhs_buf) = (k.size(), rhs.data());
+        let out_buf = out.data_mut();
+        for i in 0..m.size() {
+            let out_row = &mut out_buf[i * k..(i + 1) * k];
+            for p in row_ptr[i]..row_ptr[i + 1] {
+                let (j, v) = (col_idx[p], values[p]);
+                for (c, o) in out_row.iter_mut().enumerate() {
+                    *o += v * rhs_buf[j * rs0 + c * rs1];
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<M: Dim, N: Dim, K: Dim>(
+        &self,
+        lhs: &CsrTensor<M, N, E, Self>,
+        grad_values: &mut Self::Storage<(usize,), E>,
+        rhs: &Self::Storage<(N, K), E>,
+        grad_rhs: &mut Self::Storage<(N, K), E>,
+        grad_out: &Self::Storage<(M, K), E>,
+    ) -> Result<(), Self::Err> {
+        let row_ptr = lhs.row_ptr.storage.data();
+        let col_idx = lhs.col_idx.storage.data();
+        let values = lhs.values.storage.data();
+        let [rs0, rs1] = rhs.strides;
+        let [gs0, gs1] = grad_rhs.strides;
+        let [os0, os1] = grad_out.strides;
+        let k = grad_out.shape.1.size();
+        let (rhs, grad_out) = (rhs.data(), grad_out.data());
+        let (grad_values, grad_rhs) = (grad_values.data_mut(), grad_rhs.data_mut());
+        for i in 0..lhs.shape.0.size() {
+            for p in row_ptr[i]..row_ptr[i + 1] {
+                let (j, v) = (col_idx[p], values[p]);
+                let mut g_v = E::default();
+                for c in 0..k {
+                    let g = grad_out[i * os0 + c * os1];
+                    grad_rhs[j * gs0 + c * gs1] += v * g;
+                    g_v += rhs[j * rs0 + c * rs1] * g;
+                }
+                grad_values[p] += g_v;
+            }
+        }
+        Ok(())
+    }
+}
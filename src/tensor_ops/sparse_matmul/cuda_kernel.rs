@@ -0,0 +1,108 @@
+use crate::{
+    shapes::{Dim, Shape},
+    tensor::{
+        cuda::{Cuda, CudaArray},
+        CsrTensor,
+    },
+};
+use cudarc::driver::{LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/sparse_matmul.ptx"));
+const MODULE_NAME: &str = "sparse_matmul";
+const FWD_FN_NAME: &str = "sparse_matmul_forward";
+const BWD_FN_NAME: &str = "sparse_matmul_backward";
+const BWD_VALUES_FN_NAME: &str = "sparse_matmul_backward_values";
+const ALL_FN_NAMES: [&str; 3] = [FWD_FN_NAME, BWD_FN_NAME, BWD_VALUES_FN_NAME];
+
+impl super::SparseMatMulKernel<f32> for Cuda {
+    fn forward<M: Dim, N: Dim, K: Dim>(
+        &self,
+        lhs: &CsrTensor<M, N, f32, Self>,
+        rhs: &Self::Storage<(N, K), f32>,
+    ) -> Result<Self::Storage<(M, K), f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = (lhs.shape.0, rhs.shape.1);
+        let numel = shape.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        if numel > 0 {
+            let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                shape.0.size(),                    // const size_t m,
+                shape.1.size(),                    // const size_t k,
+                lhs.row_ptr.storage.data.as_ref(), // const size_t *row_ptr,
+                lhs.col_idx.storage.data.as_ref(), // const size_t *col_idx,
+                lhs.values.storage.data.as_ref(),  // const float *values,
+                rhs.data.as_ref(),                 // const float *rhs,
+                rhs.strides[0],                    // const size_t rhs_stride0,
+                rhs.strides[1],                    // const size_t rhs_stride1,
+                &mut storage,                      // float *out
+            );
+            unsafe { fwd_fn.launch_async(cfg, params) }?;
+        }
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+
+    fn backward<M: Dim, N: Dim, K: Dim>(
+        &self,
+        lhs: &CsrTensor<M, N, f32, Self>,
+        grad_values: &mut Self::Storage<(usize,), f32>,
+        rhs: &Self::Storage<(N, K), f32>,
+        grad_rhs: &mut Self::Storage<(N, K), f32>,
+        grad_out: &Self::Storage<(M, K), f32>,
+    ) -> Result<(), Self::Err> {
+        let numel = grad_out.shape.num_elements();
+        if numel == 0 {
+            return Ok(());
+        }
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            grad_out.shape.0.size(),           // const size_t m,
+            grad_out.shape.1.size(),           // const size_t k,
+            lhs.row_ptr.storage.data.as_ref(), // const size_t *row_ptr,
+            lhs.col_idx.storage.data.as_ref(), // const size_t *col_idx,
+            lhs.values.storage.data.as_ref(),  // const float *values,
+            Arc::make_mut(&mut grad_rhs.data), // float *grad_rhs,
+            grad_rhs.strides[0],               // const size_t grad_rhs_stride0,
+            grad_rhs.strides[1],               // const size_t grad_rhs_stride1,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            grad_out.strides[0],               // const size_t grad_out_stride0,
+            grad_out.strides[1],               // const size_t grad_out_stride1
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+
+        let nnz = lhs.nnz();
+        if nnz > 0 {
+            let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_VALUES_FN_NAME).unwrap();
+            let cfg = LaunchConfig::for_num_elems(nnz as u32);
+            let params = (
+                nnz,                                  // const size_t nnz,
+                grad_out.shape.0.size(),              // const size_t m,
+                grad_out.shape.1.size(),              // const size_t k,
+                lhs.row_ptr.storage.data.as_ref(),    // const size_t *row_ptr,
+                lhs.col_idx.storage.data.as_ref(),    // const size_t *col_idx,
+                Arc::make_mut(&mut grad_values.data), // float *grad_values,
+                rhs.data.as_ref(),                    // const float *rhs,
+                rhs.strides[0],                       // const size_t rhs_stride0,
+                rhs.strides[1],                       // const size_t rhs_stride1,
+                grad_out.data.as_ref(),               // const float *grad_out,
+                grad_out.strides[0],                  // const size_t grad_out_stride0,
+                grad_out.strides[1],                  // const size_t grad_out_stride1
+            );
+            unsafe { bwd_fn.launch_async(cfg, params) }?;
+        }
+        Ok(())
+    }
+}
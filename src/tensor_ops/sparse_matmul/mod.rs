@@ -0,0 +1,209 @@
+#![allow(clippy::type_complexity)]
+
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{CsrTensor, DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait SparseMatMulKernel<E: Dtype>: DeviceStorage {
+    fn forward<M: Dim, N: Dim, K: Dim>(
+        &self,
+        lhs: &CsrTensor<M, N, E, Self>,
+        rhs: &Self::Storage<(N, K), E>,
+    ) -> Result<Self::Storage<(M, K), E>, Self::Err>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn backward<M: Dim, N: Dim, K: Dim>(
+        &self,
+        lhs: &CsrTensor<M, N, E, Self>,
+        grad_values: &mut Self::Storage<(usize,), E>,
+        rhs: &Self::Storage<(N, K), E>,
+        grad_rhs: &mut Self::Storage<(N, K), E>,
+        grad_out: &Self::Storage<(M, K), E>,
+    ) -> Result<(), Self::Err>;
+}
+
+impl<M: Dim, N: Dim, E: Dtype, D: SparseMatMulKernel<E>> CsrTensor<M, N, E, D> {
+    /// Sparse-dense matrix multiplication `self @ rhs`, which only does work for the non zero
+    /// entries of `self`.
+    ///
+    /// Gradients are tracked for `rhs` and for the values of `self`. The gradient of the
+    /// values has one element per non zero entry, in the order of [CsrTensor::values()], so
+    /// only the non zero entries can be trained (see the [crate::optim::GradientUpdate]
+    /// impl of [CsrTensor]). The gradient of `rhs` is a dense tensor like all gradients, but
+    /// the backward pass only does work for the rows of `rhs` that are referenced by a non zero entry.
+    ///
+    /// For example, an embedding bag that sums the embeddings of a variable number of tokens
+    /// per sample:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let table: Tensor<Rank2<1000, 4>> = dev.sample_normal();
+    /// // sample 0 has tokens [3, 10], sample 1 has token [999]
+    /// let bags = CooTensor::new(&dev, (2, Const::<1000>), &[0, 0, 1], &[3, 10, 999], &[1.0; 3]);
+    /// let y = bags.to_csr().matmul(table.trace());
+    /// assert_eq!(y.shape(), &(2, Const::<4>));
+    /// let g = y.sum().backward();
+    /// assert_eq!(g.get(&table).array()[10], [1.0; 4]);
+    /// assert_eq!(g.get(&table).array()[11], [0.0; 4]);
+    /// ```
+    ///
+    /// The weights of the embeddings in each bag can be trained too:
+    /// ```rust
+    /// # use dfdx::{prelude::*, optim::*};
+    /// # let dev: Cpu = Default::default();
+    /// # let table: Tensor<Rank2<1000, 4>> = dev.sample_normal();
+    /// let mut bags = CooTensor::new(&dev, (2, Const::<1000>), &[0, 0, 1], &[3, 10, 999], &[1.0; 3]).to_csr();
+    /// let g = bags.matmul(table.trace()).sum().backward();
+    /// assert_eq!(g.get(bags.values()).as_vec().len(), 3);
+    /// let mut opt = Sgd::new(Default::default());
+    /// opt.update(&mut bags, g).unwrap();
+    /// ```
+    pub fn matmul<K: Dim, T: Tape<D>>(
+        &self,
+        rhs: Tensor<(N, K), E, D, T>,
+    ) -> Tensor<(M, K), E, D, T> {
+        self.try_matmul(rhs).unwrap()
+    }
+
+    /// Fallible version of [CsrTensor::matmul()]
+    pub fn try_matmul<K: Dim, T: Tape<D>>(
+        &self,
+        rhs: Tensor<(N, K), E, D, T>,
+    ) -> Result<Tensor<(M, K), E, D, T>, D::Err> {
+        assert_eq!(
+            self.shape.1.size(),
+            rhs.shape().0.size(),
+            "Sparse matmul inner dimensions don't match"
+        );
+        let (rhs, mut tape) = rhs.split_tape();
+        let storage = rhs.device.forward(self, &rhs.storage)?;
+        let out = rhs.device.upgrade(storage);
+        let phantom_out = out.clone();
+        let lhs = self.clone();
+        tape.try_alloc_grad(&lhs.values)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_values, grad_rhs, grad_out) =
+                grads.muts_and_ref(&lhs.values, &rhs, &phantom_out);
+            rhs.device
+                .backward(&lhs, grad_values, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        shapes::*,
+        tensor::*,
+        tensor_ops::*,
+        tests::{assert_close, TestDevice},
+    };
+
+    #[test]
+    fn test_sparse_matmul_matches_dense() {
+        let dev: TestDevice = Default::default();
+        let a = CooTensor::new(
+            &dev,
+            (Const::<3>, Const::<4>),
+            &[0, 0, 2, 2, 2],
+            &[1, 3, 0, 1, 1],
+            &[1.0, -2.0, 0.5, 3.0, 1.0],
+        );
+        let dense_a = a.to_dense();
+        let b: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+
+        let r = a.to_csr().matmul(b.trace());
+        let r_dense = dense_a.trace().matmul(b.trace());
+        assert_close(&r.array(), &r_dense.array());
+        assert_eq!(r.array()[1], [0.0; 5]);
+
+        let g = r.exp().sum().backward();
+        let g_dense = r_dense.exp().sum().backward();
+        assert_close(&g.get(&b).array(), &g_dense.get(&b).array());
+        assert_eq!(g.get(&b).array()[2], [0.0; 5]);
+    }
+
+    #[test]
+    fn test_sparse_matmul_values_grad() {
+        let dev: TestDevice = Default::default();
+        // (2, 0) appears twice, and is summed by to_csr
+        let a = CooTensor::new(
+            &dev,
+            (Const::<3>, Const::<4>),
+            &[2, 0, 2, 0],
+            &[0, 3, 0, 1],
+            &[0.5, -2.0, 1.5, 1.0],
+        );
+        let csr = a.to_csr();
+        let b: Tensor<Rank2<4, 2>, f32, _> = dev.sample_normal();
+
+        let r = csr.matmul(b.trace());
+        let dense_a = a.to_dense();
+        let r_dense = dense_a.trace().matmul(b.clone());
+        let g = r.exp().sum().backward();
+        let g_dense = r_dense.exp().sum().backward().get(&dense_a).array();
+
+        // the values of csr are (0, 1), (0, 3), (2, 0)
+        let g_values = g.get(csr.values()).as_vec();
+        assert_close(
+            &g_values,
+            &alloc::vec![g_dense[0][1], g_dense[0][3], g_dense[2][0]],
+        );
+    }
+
+    #[test]
+    fn test_sparse_matmul_strided_rhs() {
+        let dev: TestDevice = Default::default();
+        let a = CooTensor::new(
+            &dev,
+            (2, Const::<3>),
+            &[0, 1, 1],
+            &[2, 0, 1],
+            &[2.0, 1.0, -1.0],
+        );
+        let b: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let v: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+
+        let csr = a.to_csr();
+
+        // permuted rhs
+        let r = csr.matmul(b.trace().permute::<Rank2<3, 4>, _>());
+        let r_dense = a
+            .to_dense()
+            .trace()
+            .matmul(b.trace().permute::<Rank2<3, 4>, _>());
+        assert_close(&r.as_vec(), &r_dense.as_vec());
+        let g = r.square().mean().backward();
+        let g_dense = r_dense.square().mean().backward();
+        assert_close(&g.get(&b).array(), &g_dense.get(&b).array());
+
+        // broadcasted rhs, the rows of `a` sum to [2.0, 0.0]
+        let r = csr.matmul(v.trace().broadcast::<Rank2<3, 4>, _>());
+        let v_arr = v.array();
+        assert_close(&r.as_vec(), &[v_arr.map(|x| 2.0 * x), [0.0; 4]].concat());
+        let g = r.sum().backward();
+        assert_close(&g.get(&v).array(), &[2.0; 4]);
+    }
+
+    #[test]
+    fn test_sparse_matmul_empty_rows() {
+        let dev: TestDevice = Default::default();
+        let a: CooTensor<Const<2>, Const<2>, f32, _> =
+            CooTensor::new(&dev, Default::default(), &[], &[], &[]);
+        let b: Tensor<Rank2<2, 3>, f32, _> = dev.ones();
+        let r = a.to_csr().matmul(b.trace());
+        assert_eq!(r.array(), [[0.0; 3]; 2]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&b).array(), [[0.0; 3]; 2]);
+    }
+}
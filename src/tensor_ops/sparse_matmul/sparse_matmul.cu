@@ -0,0 +1,94 @@
+// One thread per output element `(i, c)`, summing over the non zero entries of row `i`.
+extern "C" __global__ void sparse_matmul_forward(
+    const size_t m,
+    const size_t k,
+    const size_t *row_ptr,
+    const size_t *col_idx,
+    const float *values,
+    const float *rhs,
+    const size_t rhs_stride0,
+    const size_t rhs_stride1,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= m * k) {
+        return;
+    }
+
+    unsigned int row = i / k;
+    unsigned int c = i % k;
+    float sum = 0.0;
+    for (size_t p = row_ptr[row]; p < row_ptr[row + 1]; p++) {
+        sum += values[p] * rhs[col_idx[p] * rhs_stride0 + c * rhs_stride1];
+    }
+    out[i] = sum;
+}
+
+// One thread per element `(i, c)` of grad_out, scattering it into the rows of grad_rhs
+// referenced by row `i`.
+extern "C" __global__ void sparse_matmul_backward(
+    const size_t m,
+    const size_t k,
+    const size_t *row_ptr,
+    const size_t *col_idx,
+    const float *values,
+    float *grad_rhs,
+    const size_t grad_rhs_stride0,
+    const size_t grad_rhs_stride1,
+    const float *grad_out,
+    const size_t grad_out_stride0,
+    const size_t grad_out_stride1
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= m * k) {
+        return;
+    }
+
+    unsigned int row = i / k;
+    unsigned int c = i % k;
+    float g = grad_out[row * grad_out_stride0 + c * grad_out_stride1];
+    for (size_t p = row_ptr[row]; p < row_ptr[row + 1]; p++) {
+        atomicAdd(grad_rhs + col_idx[p] * grad_rhs_stride0 + c * grad_rhs_stride1, values[p] * g);
+    }
+}
+
+// One thread per non zero entry `p`, computing the dot product of grad_out's row with the row
+// of rhs that `p` references. The row of `p` is found with a binary search in row_ptr.
+extern "C" __global__ void sparse_matmul_backward_values(
+    const size_t nnz,
+    const size_t m,
+    const size_t k,
+    const size_t *row_ptr,
+    const size_t *col_idx,
+    float *grad_values,
+    const float *rhs,
+    const size_t rhs_stride0,
+    const size_t rhs_stride1,
+    const float *grad_out,
+    const size_t grad_out_stride0,
+    const size_t grad_out_stride1
+) {
+    unsigned int p = blockIdx.x * blockDim.x + threadIdx.x;
+    if (p >= nnz) {
+        return;
+    }
+
+    // the last row with row_ptr[row] <= p
+    size_t lo = 0;
+    size_t hi = m;
+    while (hi - lo > 1) {
+        size_t mid = (lo + hi) / 2;
+        if (row_ptr[mid] <= p) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    size_t j = col_idx[p];
+    float sum = 0.0;
+    for (size_t c = 0; c < k; c++) {
+        sum += grad_out[lo * grad_out_stride0 + c * grad_out_stride1] * rhs[j * rhs_stride0 + c * rhs_stride1];
+    }
+    grad_values[p] += sum;
+}
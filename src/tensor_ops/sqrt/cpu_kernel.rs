@@ -1,4 +1,6 @@
 use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 impl UnaryDerivative<f32> for super::SqrtKernelOp {
     #[inline(always)]
@@ -26,26 +26,65 @@ pub trait StddevTo: HasErr + HasShape {
         self,
         epsilon: f32,
     ) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_stddev_with_ddof(0, epsilon)
+    }
+
+    /// Standard deviation reduction, using [VarTo::var_with_ddof] for the variance.
+    ///
+    /// **Panics** if `ddof` is not 0, and not less than the number of elements reduced.
+    ///
+    /// **Pytorch equivalent**: `t.std(Axes, correction=ddof)`
+    ///
+    /// Examples:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[2.0, 3.0, 4.0], [3.0, 6.0, 9.0]]);
+    /// let r = t.stddev_with_ddof::<Rank1<2>, _>(1, 0.0);
+    /// assert_eq!(r.array(), [1.0, 3.0]);
+    /// ```
+    fn stddev_with_ddof<Dst: Shape, Ax: Axes>(
+        self,
+        ddof: usize,
+        epsilon: f32,
+    ) -> Self::WithShape<Dst>
+    where
+        Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_stddev_with_ddof(ddof, epsilon).unwrap()
+    }
+    /// Fallible version of [StddevTo::stddev_with_ddof]. Only device errors are returned,
+    /// so this still **panics** if `ddof` is not 0, and not less than the number of
+    /// elements reduced.
+    fn try_stddev_with_ddof<Dst: Shape, Ax: Axes>(
+        self,
+        ddof: usize,
+        epsilon: f32,
+    ) -> Result<Self::WithShape<Dst>, Self::Err>
     where
         Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>;
 }
 
 impl<S: Shape, D: Device<f32>, T: Tape<D>> StddevTo for Tensor<S, f32, D, T> {
-    fn try_stddev<Dst: Shape, Ax: Axes>(
+    fn try_stddev_with_ddof<Dst: Shape, Ax: Axes>(
         self,
+        ddof: usize,
         epsilon: f32,
     ) -> Result<Self::WithShape<Dst>, Self::Err>
     where
         Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
     {
-        self.try_var()?.try_add(epsilon)?.try_sqrt()
+        self.try_var_with_ddof(ddof)?.try_add(epsilon)?.try_sqrt()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::TestDevice;
+    use crate::tests::{assert_close, TestDevice};
 
     #[test]
     fn test_std_axis_0_2d() {
@@ -75,4 +114,21 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_std_with_ddof() {
+        use std::f32::consts::{FRAC_1_SQRT_2, SQRT_2};
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0, 4.0], [0.0, 2.0, 5.0, 10.0]]);
+        let r = t.trace().stddev_with_ddof::<Rank1<4>, _>(1, 1e-8);
+        assert_close(&r.array(), &[FRAC_1_SQRT_2, 0.0001, SQRT_2, 4.2426405]);
+        let g = r.sum().backward();
+        assert_close(
+            &g.get(&t).array(),
+            &[
+                [FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+                [-FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+            ],
+        );
+    }
 }
@@ -1,4 +1,8 @@
-use crate::tensor_ops::cpu_kernels::{BinaryDerivative, UnaryDerivative};
+use super::TrySub;
+use crate::{
+    tensor::{Cpu, DeviceStorage},
+    tensor_ops::cpu_kernels::{int_arithmetic, BinaryDerivative, UnaryDerivative},
+};
 
 impl UnaryDerivative<f32> for super::ScalarSubKernelOp<f32> {
     fn f(&self, x: &f32) -> f32 {
@@ -23,3 +27,5 @@ impl BinaryDerivative<f32> for super::BinarySubKernelOp {
         -1.0
     }
 }
+
+int_arithmetic!(TrySub, try_sub, wrapping_sub, [i32, i64, u8]);
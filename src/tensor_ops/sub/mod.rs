@@ -3,7 +3,7 @@ mod cpu_kernel;
 #[cfg(feature = "cuda")]
 mod cuda_kernel;
 
-use super::{ops::*, Device};
+use super::ops::*;
 use crate::{gradients::*, shapes::*, tensor::*};
 
 #[repr(C)]
@@ -36,10 +36,13 @@ pub struct ScalarSubKernelOp<E> {
 /// let r = a - 1.0;
 /// assert_eq!(r.array(), [[0.0, 1.0, 2.0], [-2.0, -3.0, -4.0]]);
 /// ```
-pub fn sub<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
+pub fn sub<S: Shape, E: Dtype, D, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
-) -> Tensor<S, E, D, T> {
+) -> Tensor<S, E, D, T>
+where
+    D: BinaryKernel<BinarySubKernelOp, E>,
+{
     lhs - rhs
 }
 
@@ -48,9 +51,10 @@ pub trait TrySub<Rhs = Self>: HasErr {
     fn try_sub(self, rhs: Rhs) -> Result<Self, Self::Err>;
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>, RTape: Tape<D>>
-    TrySub<Tensor<S, E, D, RTape>> for Tensor<S, E, D, LTape>
+impl<S: Shape, E: Dtype, D, LTape: Tape<D>, RTape: Tape<D>> TrySub<Tensor<S, E, D, RTape>>
+    for Tensor<S, E, D, LTape>
 where
+    D: BinaryKernel<BinarySubKernelOp, E>,
     LTape: Merge<RTape>,
 {
     fn try_sub(self, rhs: Tensor<S, E, D, RTape>) -> Result<Self, Self::Err> {
@@ -58,13 +62,16 @@ where
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> TrySub<E> for Tensor<S, E, D, T> {
+impl<S: Shape, E: Dtype, D, T: Tape<D>> TrySub<E> for Tensor<S, E, D, T>
+where
+    D: UnaryKernel<ScalarSubKernelOp<E>, E>,
+{
     fn try_sub(self, rhs: E) -> Result<Self, Self::Err> {
         try_unary_op(ScalarSubKernelOp { scalar: rhs }, self)
     }
 }
 
-impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>, Rhs> std::ops::Sub<Rhs>
+impl<S: Shape, E: Dtype, D: DeviceStorage, LTape: Tape<D>, Rhs> std::ops::Sub<Rhs>
     for Tensor<S, E, D, LTape>
 where
     Self: TrySub<Rhs>,
@@ -156,4 +163,14 @@ mod tests {
         let g = r.exp().sum().backward();
         assert_close(&g.get(&x).array(), &[[0.36787945; 2]; 3]);
     }
+
+    #[test]
+    fn test_sub_int() {
+        let dev: Cpu = Default::default();
+        let a = dev.tensor([1i64, -2, 3]);
+        let b = dev.tensor([4i64, 5, -6]);
+        assert_eq!((a.clone() - b).array(), [-3, -7, 9]);
+        assert_eq!((a - 1).array(), [0, -3, 2]);
+        assert_eq!((dev.tensor([0u8, 5]) - 1).array(), [255, 4]);
+    }
 }
@@ -63,9 +63,17 @@ impl<S: Shape, E: Dtype, D: SumKernel<E>, T: Tape<D>> SumTo for Tensor<S, E, D,
         let dst: Dst = self.shape().reduced();
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.upgrade(inp.device.forward(dst, &inp.storage)?);
+        inp.device.recorder().record("sum", &[inp.id], out.id, || {
+            let (inp, out_id) = (inp.clone(), out.id);
+            move |bufs| {
+                let storage = inp.device.forward(dst, bufs.get(&inp))?;
+                bufs.insert::<Dst, E, D>(out_id, storage);
+                Ok(())
+            }
+        });
         let phantom_out = out.clone();
         tape.try_alloc_grad(&inp)?;
-        tape.try_alloc_grad(&out)?;
+        tape.try_alloc_output_grad(&out)?;
         tape.add_backward_op(move |grads| {
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
             inp.device.backward(grad_inp, grad_out)
@@ -0,0 +1,195 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, StridedArray},
+};
+use std::{sync::Arc, vec::Vec};
+
+macro_rules! cpu_to_dtype {
+    ($Src:ty => [$($Dst:ty),*]) => {
+        $(
+            impl super::ToDtypeKernel<$Src, $Dst> for Cpu {
+                fn forward<S: Shape>(
+                    &self,
+                    inp: Self::Storage<S, $Src>,
+                ) -> Result<Self::Storage<S, $Dst>, Self::Err> {
+                    let data: Vec<$Dst> = inp.data.iter().map(|x| *x as $Dst).collect();
+                    Ok(StridedArray {
+                        data: Arc::new(data.into()),
+                        shape: inp.shape,
+                        strides: inp.strides,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! cpu_to_dtype_grad {
+    ($Src:ty => [$($Dst:ty),*]) => {
+        $(
+            impl super::ToDtypeGradKernel<$Src, $Dst> for Cpu {
+                fn backward<S: Shape>(
+                    &self,
+                    grad_inp: &mut Self::Storage<S, $Src>,
+                    grad_out: &Self::Storage<S, $Dst>,
+                ) -> Result<(), Self::Err> {
+                    debug_assert_eq!(grad_inp.data.len(), grad_out.data.len());
+                    for (i, o) in grad_inp.buf_iter_mut().zip(grad_out.buf_iter()) {
+                        *i += *o as $Src;
+                    }
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+cpu_to_dtype!(f32 => [f32, f64, i32, i64, u8, usize]);
+cpu_to_dtype!(f64 => [f32, f64, i32, i64, u8, usize]);
+cpu_to_dtype!(i32 => [f32, f64, i32, i64, u8, usize]);
+cpu_to_dtype!(i64 => [f32, f64, i32, i64, u8, usize]);
+cpu_to_dtype!(u8 => [f32, f64, i32, i64, u8, usize]);
+cpu_to_dtype!(usize => [f32, f64, i32, i64, u8, usize]);
+
+cpu_to_dtype_grad!(f32 => [f32, f64]);
+cpu_to_dtype_grad!(f64 => [f32, f64]);
+
+macro_rules! cpu_bool_to_dtype {
+    ($($Ty:ty),*) => {
+        $(
+            impl super::ToDtypeKernel<bool, $Ty> for Cpu {
+                fn forward<S: Shape>(
+                    &self,
+                    inp: Self::Storage<S, bool>,
+                ) -> Result<Self::Storage<S, $Ty>, Self::Err> {
+                    let data: Vec<$Ty> = inp.data.iter().map(|x| <$Ty>::from(u8::from(*x))).collect();
+                    Ok(StridedArray {
+                        data: Arc::new(data.into()),
+                        shape: inp.shape,
+                        strides: inp.strides,
+                    })
+                }
+            }
+
+            impl super::ToDtypeKernel<$Ty, bool> for Cpu {
+                fn forward<S: Shape>(
+                    &self,
+                    inp: Self::Storage<S, $Ty>,
+                ) -> Result<Self::Storage<S, bool>, Self::Err> {
+                    let zero: $Ty = Default::default();
+                    let data: Vec<bool> = inp.data.iter().map(|x| *x != zero).collect();
+                    Ok(StridedArray {
+                        data: Arc::new(data.into()),
+                        shape: inp.shape,
+                        strides: inp.strides,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+cpu_bool_to_dtype!(f32, f64, i32, i64, u8, usize);
+#[cfg(feature = "f16")]
+cpu_bool_to_dtype!(half::f16);
+
+#[cfg(feature = "f16")]
+macro_rules! cpu_f16_to_dtype {
+    ($($Ty:ty),*) => {
+        $(
+            impl super::ToDtypeKernel<half::f16, $Ty> for Cpu {
+                fn forward<S: Shape>(
+                    &self,
+                    inp: Self::Storage<S, half::f16>,
+                ) -> Result<Self::Storage<S, $Ty>, Self::Err> {
+                    let data: Vec<$Ty> = inp.data.iter().map(|x| x.to_f64() as $Ty).collect();
+                    Ok(StridedArray {
+                        data: Arc::new(data.into()),
+                        shape: inp.shape,
+                        strides: inp.strides,
+                    })
+                }
+            }
+
+            impl super::ToDtypeKernel<$Ty, half::f16> for Cpu {
+                fn forward<S: Shape>(
+                    &self,
+                    inp: Self::Storage<S, $Ty>,
+                ) -> Result<Self::Storage<S, half::f16>, Self::Err> {
+                    let data: Vec<half::f16> =
+                        inp.data.iter().map(|x| half::f16::from_f64(*x as f64)).collect();
+                    Ok(StridedArray {
+                        data: Arc::new(data.into()),
+                        shape: inp.shape,
+                        strides: inp.strides,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "f16")]
+macro_rules! cpu_f16_to_dtype_grad {
+    ($($Ty:ty),*) => {
+        $(
+            impl super::ToDtypeGradKernel<half::f16, $Ty> for Cpu {
+                fn backward<S: Shape>(
+                    &self,
+                    grad_inp: &mut Self::Storage<S, half::f16>,
+                    grad_out: &Self::Storage<S, $Ty>,
+                ) -> Result<(), Self::Err> {
+                    debug_assert_eq!(grad_inp.data.len(), grad_out.data.len());
+                    for (i, o) in grad_inp.buf_iter_mut().zip(grad_out.buf_iter()) {
+                        *i += half::f16::from_f64(*o as f64);
+                    }
+                    Ok(())
+                }
+            }
+
+            impl super::ToDtypeGradKernel<$Ty, half::f16> for Cpu {
+                fn backward<S: Shape>(
+                    &self,
+                    grad_inp: &mut Self::Storage<S, $Ty>,
+                    grad_out: &Self::Storage<S, half::f16>,
+                ) -> Result<(), Self::Err> {
+                    debug_assert_eq!(grad_inp.data.len(), grad_out.data.len());
+                    for (i, o) in grad_inp.buf_iter_mut().zip(grad_out.buf_iter()) {
+                        *i += o.to_f64() as $Ty;
+                    }
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "f16")]
+cpu_f16_to_dtype!(f32, f64, i32, i64, u8, usize);
+#[cfg(feature = "f16")]
+cpu_f16_to_dtype_grad!(f32, f64);
+
+#[cfg(feature = "f16")]
+impl super::ToDtypeKernel<half::f16, half::f16> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        inp: Self::Storage<S, half::f16>,
+    ) -> Result<Self::Storage<S, half::f16>, Self::Err> {
+        Ok(inp)
+    }
+}
+
+#[cfg(feature = "f16")]
+impl super::ToDtypeGradKernel<half::f16, half::f16> for Cpu {
+    fn backward<S: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<S, half::f16>,
+        grad_out: &Self::Storage<S, half::f16>,
+    ) -> Result<(), Self::Err> {
+        debug_assert_eq!(grad_inp.data.len(), grad_out.data.len());
+        for (i, o) in grad_inp.buf_iter_mut().zip(grad_out.buf_iter()) {
+            *i += *o;
+        }
+        Ok(())
+    }
+}
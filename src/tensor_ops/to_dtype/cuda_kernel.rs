@@ -0,0 +1,90 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/to_dtype.ptx"));
+const MODULE_NAME: &str = "to_dtype";
+const ALL_FN_NAMES: [&str; 12] = [
+    "to_dtype_f32_f64_forward",
+    "to_dtype_f32_f64_backward",
+    "to_dtype_f64_f32_forward",
+    "to_dtype_f64_f32_backward",
+    "to_dtype_f32_f16_forward",
+    "to_dtype_f32_f16_backward",
+    "to_dtype_f16_f32_forward",
+    "to_dtype_f16_f32_backward",
+    "to_dtype_f64_f16_forward",
+    "to_dtype_f64_f16_backward",
+    "to_dtype_f16_f64_forward",
+    "to_dtype_f16_f64_backward",
+];
+
+macro_rules! cuda_to_dtype {
+    ($Src:ty => $Dst:ty, $Fwd:literal, $Bwd:literal) => {
+        impl super::ToDtypeKernel<$Src, $Dst> for Cuda {
+            fn forward<S: Shape>(
+                &self,
+                inp: Self::Storage<S, $Src>,
+            ) -> Result<Self::Storage<S, $Dst>, Self::Err> {
+                if !self.dev.has_func(MODULE_NAME, $Fwd) {
+                    self.dev
+                        .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+                }
+
+                // the output keeps the strides of `inp`, so only the buffer needs converting
+                let numel = inp.data.len();
+                let mut storage = self
+                    .dev
+                    .take_async(std::vec![<$Dst>::default(); numel])?;
+
+                let fwd_fn = self.dev.get_func(MODULE_NAME, $Fwd).unwrap();
+                let cfg = LaunchConfig::for_num_elems(numel as u32);
+                let params = (
+                    numel,             // const size_t numel,
+                    inp.data.as_ref(), // const SRC *inp,
+                    &mut storage,      // DST *out
+                );
+                unsafe { fwd_fn.launch_async(cfg, params) }?;
+                Ok(CudaArray {
+                    data: Arc::new(storage),
+                    shape: inp.shape,
+                    strides: inp.strides,
+                })
+            }
+        }
+
+        impl super::ToDtypeGradKernel<$Src, $Dst> for Cuda {
+            fn backward<S: Shape>(
+                &self,
+                grad_inp: &mut Self::Storage<S, $Src>,
+                grad_out: &Self::Storage<S, $Dst>,
+            ) -> Result<(), Self::Err> {
+                debug_assert_eq!(grad_inp.data.len(), grad_out.data.len());
+                let bwd_fn = self.dev.get_func(MODULE_NAME, $Bwd).unwrap();
+                let numel = grad_inp.data.len();
+                let cfg = LaunchConfig::for_num_elems(numel as u32);
+                let params = (
+                    numel,                             // const size_t numel,
+                    Arc::make_mut(&mut grad_inp.data), // SRC *grad_inp,
+                    grad_out.data.as_ref(),            // const DST *grad_out
+                );
+                unsafe { bwd_fn.launch_async(cfg, params) }?;
+                Ok(())
+            }
+        }
+    };
+}
+
+cuda_to_dtype!(f32 => f64, "to_dtype_f32_f64_forward", "to_dtype_f32_f64_backward");
+cuda_to_dtype!(f64 => f32, "to_dtype_f64_f32_forward", "to_dtype_f64_f32_backward");
+#[cfg(feature = "f16")]
+cuda_to_dtype!(f32 => half::f16, "to_dtype_f32_f16_forward", "to_dtype_f32_f16_backward");
+#[cfg(feature = "f16")]
+cuda_to_dtype!(half::f16 => f32, "to_dtype_f16_f32_forward", "to_dtype_f16_f32_backward");
+#[cfg(feature = "f16")]
+cuda_to_dtype!(f64 => half::f16, "to_dtype_f64_f16_forward", "to_dtype_f64_f16_backward");
+#[cfg(feature = "f16")]
+cuda_to_dtype!(half::f16 => f64, "to_dtype_f16_f64_forward", "to_dtype_f16_f64_backward");
@@ -0,0 +1,192 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::{OwnedTape, Tape},
+    shapes::{Dtype, Shape, Unit},
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait ToDtypeKernel<E1: Unit, E2: Unit>: DeviceStorage {
+    fn forward<S: Shape>(
+        &self,
+        inp: Self::Storage<S, E1>,
+    ) -> Result<Self::Storage<S, E2>, Self::Err>;
+}
+
+/// Casts between float dtypes that support gradients. The gradient is cast back to `E1`.
+pub trait ToDtypeGradKernel<E1: Dtype, E2: Dtype>: ToDtypeKernel<E1, E2> {
+    fn backward<S: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<S, E1>,
+        grad_out: &Self::Storage<S, E2>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Converts the elements of a tensor to another dtype, the same as an `as` cast in rust.
+///
+/// Casting floats to integers rounds towards zero and saturates at the bounds of the integer
+/// type, with `NaN` becoming `0`.
+///
+/// **Pytorch equivalent**: `t.to(dtype)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([1.7f32, -2.5, 300.0]);
+/// assert_eq!(a.clone().to_dtype::<i32>().array(), [1, -2, 300]);
+/// assert_eq!(a.to_dtype::<u8>().array(), [1, 0, 255]);
+///
+/// let labels = dev.tensor([0u8, 2, 1]);
+/// assert_eq!(labels.to_dtype::<f32>().array(), [0.0, 2.0, 1.0]);
+/// ```
+///
+/// Tensors with a tape can be cast between float dtypes. The gradient flows straight through,
+/// and is cast back to the original dtype:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([1.0f32, 2.0, 3.0]);
+/// let b: Tensor<Rank1<3>, f64, _, _> = a.trace().to_dtype();
+/// let c: Tensor<Rank1<3>, f32, _, _> = b.to_dtype();
+/// let g = (c * 2.0).sum().backward();
+/// assert_eq!(g.get(&a).array(), [2.0; 3]);
+/// ```
+///
+/// With the `f16` feature enabled, `half::f16` is supported as well, both with and without a
+/// tape.
+pub fn to_dtype<E2: Unit, S: Shape, E1: Unit, D: ToDtypeKernel<E1, E2>>(
+    t: Tensor<S, E1, D>,
+) -> Tensor<S, E2, D> {
+    t.to_dtype()
+}
+
+impl<S: Shape, E: Unit, D: DeviceStorage> Tensor<S, E, D> {
+    /// See [to_dtype]
+    pub fn to_dtype<E2: Unit>(self) -> Tensor<S, E2, D>
+    where
+        D: ToDtypeKernel<E, E2>,
+    {
+        self.try_to_dtype().unwrap()
+    }
+
+    /// See [to_dtype]
+    pub fn try_to_dtype<E2: Unit>(self) -> Result<Tensor<S, E2, D>, D::Err>
+    where
+        D: ToDtypeKernel<E, E2>,
+    {
+        let storage = self.device.forward(self.storage)?;
+        Ok(self.device.upgrade(storage))
+    }
+}
+
+impl<S: Shape, E: Dtype, D: DeviceStorage> Tensor<S, E, D, OwnedTape<D>> {
+    /// See [to_dtype]
+    pub fn to_dtype<E2: Dtype>(self) -> Tensor<S, E2, D, OwnedTape<D>>
+    where
+        D: ToDtypeGradKernel<E, E2>,
+    {
+        self.try_to_dtype().unwrap()
+    }
+
+    /// See [to_dtype]
+    pub fn try_to_dtype<E2: Dtype>(self) -> Result<Tensor<S, E2, D, OwnedTape<D>>, D::Err>
+    where
+        D: ToDtypeGradKernel<E, E2>,
+    {
+        let (inp, mut tape) = self.split_tape();
+        let storage = inp.device.forward(inp.storage.clone())?;
+        let out = inp.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_output_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        shapes::*,
+        tensor::*,
+        tensor_ops::*,
+        tests::{assert_close, TestDevice},
+    };
+
+    #[test]
+    fn test_float_to_int() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[-1.5, 0.4, 2.9], [f32::NAN, 1e20, -1e20]]);
+        assert_eq!(
+            a.clone().to_dtype::<i32>().array(),
+            [[-1, 0, 2], [0, i32::MAX, i32::MIN]]
+        );
+        assert_eq!(a.to_dtype::<u8>().array(), [[0, 0, 2], [0, u8::MAX, 0]]);
+    }
+
+    #[test]
+    fn test_int_to_float() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([3i64, -2, 0]);
+        let b: Tensor<Rank1<3>, f32, _> = a.to_dtype();
+        assert_eq!(b.array(), [3.0, -2.0, 0.0]);
+        assert_eq!((b * 0.5).to_dtype::<f64>().array(), [1.5, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_to_dtype_grads() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[0.5, -1.0], [2.0, 0.0]]);
+        let b: Tensor<Rank2<2, 2>, f64, _, _> = a.trace().to_dtype();
+        assert_eq!(b.array(), [[0.5, -1.0], [2.0, 0.0]]);
+        let c = b.to_dtype::<f32>().exp();
+        assert_close(&c.array(), &a.clone().exp().array());
+        let g = c.sum().backward();
+        assert_close(&g.get(&a).array(), &a.exp().array());
+    }
+
+    #[test]
+    fn test_to_dtype_grads_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0]);
+        let b = a.trace().broadcast::<Rank2<3, 2>, _>().to_dtype::<f64>();
+        let g = (b.to_dtype::<f32>() * 0.5).sum().backward();
+        assert_eq!(g.get(&a).array(), [1.5, 1.5]);
+    }
+
+    #[cfg(feature = "f16")]
+    #[test]
+    fn test_to_dtype_f16_grads() {
+        use half::f16;
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([0.5, -1.0, 65504.0, 1e-8]);
+        let b = a.trace().to_dtype::<f16>();
+        assert_eq!(
+            b.array(),
+            [
+                f16::from_f32(0.5),
+                f16::from_f32(-1.0),
+                f16::MAX,
+                f16::from_f32(0.0)
+            ]
+        );
+        let c = b.to_dtype::<f32>() * 3.0;
+        assert_eq!(c.array(), [1.5, -3.0, 196512.0, 0.0]);
+        let g = c.sum().backward();
+        assert_eq!(g.get(&a).array(), [3.0; 4]);
+    }
+
+    #[test]
+    fn test_to_dtype_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0]).broadcast::<Rank2<3, 2>, _>();
+        assert_eq!(a.to_dtype::<usize>().array(), [[1, 2]; 3]);
+    }
+}
@@ -0,0 +1,51 @@
+#include "cuda_fp16.h"
+
+__device__ __forceinline__ float cast_f32(float x) { return x; }
+__device__ __forceinline__ float cast_f32(double x) { return x; }
+__device__ __forceinline__ float cast_f32(__half x) { return __half2float(x); }
+
+__device__ __forceinline__ double cast_f64(float x) { return x; }
+__device__ __forceinline__ double cast_f64(double x) { return x; }
+__device__ __forceinline__ double cast_f64(__half x) { return __half2float(x); }
+
+__device__ __forceinline__ __half cast_f16(float x) { return __float2half(x); }
+__device__ __forceinline__ __half cast_f16(double x) { return __float2half((float)x); }
+__device__ __forceinline__ __half cast_f16(__half x) { return x; }
+
+__device__ __forceinline__ void accumulate(float *acc, float x) { *acc += x; }
+__device__ __forceinline__ void accumulate(double *acc, double x) { *acc += x; }
+__device__ __forceinline__ void accumulate(__half *acc, __half x) {
+    *acc = __float2half(__half2float(*acc) + __half2float(x));
+}
+
+#define TO_DTYPE(SRC, DST, CAST_FWD, CAST_BWD, FWD, BWD) \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const SRC *inp, \
+    DST *out \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+    out[i] = CAST_FWD(inp[i]); \
+} \
+\
+extern "C" __global__ void BWD( \
+    const size_t numel, \
+    SRC *grad_inp, \
+    const DST *grad_out \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+    accumulate(grad_inp + i, CAST_BWD(grad_out[i])); \
+}
+
+TO_DTYPE(float, double, cast_f64, cast_f32, to_dtype_f32_f64_forward, to_dtype_f32_f64_backward);
+TO_DTYPE(double, float, cast_f32, cast_f64, to_dtype_f64_f32_forward, to_dtype_f64_f32_backward);
+TO_DTYPE(float, __half, cast_f16, cast_f32, to_dtype_f32_f16_forward, to_dtype_f32_f16_backward);
+TO_DTYPE(__half, float, cast_f32, cast_f16, to_dtype_f16_f32_forward, to_dtype_f16_f32_backward);
+TO_DTYPE(double, __half, cast_f16, cast_f64, to_dtype_f64_f16_forward, to_dtype_f64_f16_backward);
+TO_DTYPE(__half, double, cast_f64, cast_f16, to_dtype_f16_f64_forward, to_dtype_f16_f64_backward);
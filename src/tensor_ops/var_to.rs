@@ -23,20 +23,65 @@ pub trait VarTo: HasErr + HasShape {
     }
     /// Fallible version of [VarTo::var]
     fn try_var<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_var_with_ddof(0)
+    }
+
+    /// Variance where the sum of squared differences is divided by `N - ddof`
+    /// instead of `N`, with `N` being the number of elements reduced. `ddof = 1`
+    /// gives the unbiased estimate.
+    ///
+    /// **Panics** if `ddof` is not 0, and not less than `N`.
+    ///
+    /// **Pytorch equivalent**: `t.var(Axes, correction=ddof)`
+    ///
+    /// Examples:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[2.0, 3.0, 4.0], [3.0, 6.0, 9.0]]);
+    /// let r = t.var_with_ddof::<Rank1<2>, _>(1);
+    /// assert_eq!(r.array(), [1.0, 9.0]);
+    /// ```
+    fn var_with_ddof<Dst: Shape, Ax: Axes>(self, ddof: usize) -> Self::WithShape<Dst>
+    where
+        Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_var_with_ddof(ddof).unwrap()
+    }
+    /// Fallible version of [VarTo::var_with_ddof]. Only device errors are returned,
+    /// so this still **panics** if `ddof` is not 0, and not less than `N`.
+    fn try_var_with_ddof<Dst: Shape, Ax: Axes>(
+        self,
+        ddof: usize,
+    ) -> Result<Self::WithShape<Dst>, Self::Err>
     where
         Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>;
 }
 
 impl<S: Shape, D: Device<f32>, T: Tape<D>> VarTo for Tensor<S, f32, D, T> {
-    fn try_var<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    fn try_var_with_ddof<Dst: Shape, Ax: Axes>(
+        self,
+        ddof: usize,
+    ) -> Result<Self::WithShape<Dst>, Self::Err>
     where
         Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
     {
+        let num_elements_reduced = <S as HasAxes<Ax>>::size(self.shape());
+        assert!(
+            ddof == 0 || ddof < num_elements_reduced,
+            "ddof ({ddof}) must be less than the number of elements reduced ({num_elements_reduced})"
+        );
         let mean = self
             .retaped::<T>()
             .try_mean::<Dst, Ax>()?
             .try_broadcast_like(self.shape())?;
-        mean.try_sub(self)?.try_square()?.try_mean()
+        mean.try_sub(self)?
+            .try_square()?
+            .try_sum()?
+            .try_div((num_elements_reduced - ddof) as f32)
     }
 }
 
@@ -73,4 +118,28 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_var_with_ddof() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0, 4.0], [0.0, 2.0, 5.0, 10.0]]);
+        let r = t.trace().var_with_ddof::<Rank1<4>, _>(1);
+        assert_eq!(r.array(), [0.5, 0.0, 2.0, 18.0]);
+        let g = r.mean().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.25, 0.0, -0.5, -1.5], [-0.25, 0.0, 0.5, 1.5]]
+        );
+
+        let r = t.var_with_ddof::<Rank1<2>, _>(1);
+        assert_eq!(r.array(), [1.6666666, 18.916666]);
+    }
+
+    #[test]
+    #[should_panic = "ddof (2) must be less than the number of elements reduced (2)"]
+    fn test_var_with_ddof_too_large() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let _ = t.var_with_ddof::<Rank1<2>, Axis<1>>(2);
+    }
 }